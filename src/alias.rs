@@ -0,0 +1,211 @@
+//! 命令别名（rtfm alias）
+//!
+//! 按名称查找命令前先解析别名：rtfm <name>、TUI 搜索和 /api/command/{name} 中的 gco 显示 git checkout
+//! （即 git-checkout 页面）。别名只解析一层，可以手动添加，也可以从 shell 配置文件（bash、zsh 的
+//! `alias name='value'`，fish 的 `alias` 和 `abbr`）和 `git config --get-regexp alias` 导入。
+//! 导入时只取别名值中选项之前的命令词，含管道、变量等的别名和只是加了选项的同名别名（如 ls='ls --color'）被跳过。
+
+use std::path::PathBuf;
+
+use crate::storage::{Alias, Database};
+
+/// 别名值开头可以跳过的包装命令
+const WRAPPERS: &[&str] = &["sudo", "command", "builtin", "noglob", "nocorrect"];
+
+/// 去掉首尾空白并合并连续空白，用作别名名称和指向的命令
+pub fn normalize(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 别名指向的命令可能的页面名，多个词用 `-` 连接（tldr 的命名规范），从长到短：
+/// docker compose up → docker-compose-up、docker-compose、docker
+pub fn command_names(target: &str) -> Vec<String> {
+  let words: Vec<&str> = target.split_whitespace().collect();
+  (1..=words.len())
+    .rev()
+    .map(|len| words[..len].join("-"))
+    .collect()
+}
+
+/// 别名值中的命令词（选项和参数之前的部分）；含 shell 语法时返回 None
+fn command_words(value: &str) -> Option<String> {
+  if value.contains(['|', ';', '&', '$', '`', '<', '>', '(', ')']) {
+    return None;
+  }
+  let words: Vec<&str> = value
+    .split_whitespace()
+    .skip_while(|word| WRAPPERS.contains(word))
+    .take_while(|word| {
+      !word.starts_with('-')
+        && word
+          .chars()
+          .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    })
+    .collect();
+  (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// 去掉值两侧的引号；引号后的内容（如注释）被忽略
+fn unquote(value: &str) -> &str {
+  match value.chars().next() {
+    Some(quote @ ('\'' | '"')) => value[1..].split(quote).next().unwrap_or(""),
+    _ => value,
+  }
+}
+
+/// 解析 shell 配置文件中的一行别名定义
+fn parse_shell_line(line: &str) -> Option<(String, String)> {
+  let (keyword, rest) = line.trim().split_once(char::is_whitespace)?;
+  if keyword != "alias" && keyword != "abbr" {
+    return None;
+  }
+  // 跳过选项，如 zsh 的 alias -g、fish 的 abbr -a
+  let mut rest = rest.trim_start();
+  while rest.starts_with('-') {
+    rest = rest.split_once(char::is_whitespace)?.1.trim_start();
+  }
+  // bash/zsh 为 name=value，fish 为 name value
+  let (name, value) = match rest.split_once('=') {
+    Some((name, value)) if !name.contains(char::is_whitespace) => (name, value),
+    _ => rest.split_once(char::is_whitespace)?,
+  };
+  let name = unquote(name.trim());
+  let target = command_words(unquote(value.trim()))?;
+  (!name.is_empty() && name != target).then(|| (name.to_string(), target))
+}
+
+/// 解析 shell 配置文件中的别名
+pub fn parse_shell(content: &str) -> Vec<(String, String)> {
+  content.lines().filter_map(parse_shell_line).collect()
+}
+
+/// 解析 `git config --get-regexp alias` 的输出，如 `alias.co checkout` 为 git co → git checkout
+pub fn parse_git(output: &str) -> Vec<(String, String)> {
+  output
+    .lines()
+    .filter_map(|line| {
+      let (key, value) = line.split_once(char::is_whitespace)?;
+      let name = key.strip_prefix("alias.")?;
+      // ! 开头的别名运行 shell 命令
+      let target = command_words(value)?;
+      Some((format!("git {}", name), format!("git {}", target)))
+    })
+    .collect()
+}
+
+/// 可能定义别名的 shell 配置文件
+fn rc_files() -> Vec<PathBuf> {
+  let Some(home) = dirs::home_dir() else {
+    return Vec::new();
+  };
+  let zdotdir = std::env::var_os("ZDOTDIR")
+    .map(PathBuf::from)
+    .unwrap_or_else(|| home.clone());
+  let xdg_config = std::env::var_os("XDG_CONFIG_HOME")
+    .map(PathBuf::from)
+    .unwrap_or_else(|| home.join(".config"));
+  vec![
+    home.join(".bashrc"),
+    home.join(".bash_aliases"),
+    zdotdir.join(".zshrc"),
+    xdg_config.join("fish/config.fish"),
+  ]
+}
+
+/// 本机 shell 配置文件与 git 中定义的别名；同名时先找到的优先
+pub fn discover() -> Vec<(String, String)> {
+  let mut found: Vec<(String, String)> = Vec::new();
+  for path in rc_files() {
+    if let Ok(content) = std::fs::read_to_string(&path) {
+      found.extend(parse_shell(&content));
+    }
+  }
+  let git = std::process::Command::new("git")
+    .args(["config", "--get-regexp", r"^alias\."])
+    .output();
+  if let Ok(output) = git {
+    if output.status.success() {
+      found.extend(parse_git(&String::from_utf8_lossy(&output.stdout)));
+    }
+  }
+
+  let mut aliases: Vec<(String, String)> = Vec::new();
+  for (name, target) in found {
+    if !aliases.iter().any(|(existing, _)| *existing == name) {
+      aliases.push((name, target));
+    }
+  }
+  aliases
+}
+
+/// 保存找到的别名，已有的别名（包括手动添加的）保持不变，返回新增的别名。
+/// command 不为空时只导入指向该命令的别名（rtfm learn 时使用）
+pub fn import(db: &Database, command: Option<&str>) -> anyhow::Result<Vec<Alias>> {
+  let mut added = Vec::new();
+  for (name, target) in discover() {
+    if command.is_some_and(|command| target.split_whitespace().next() != Some(command)) {
+      continue;
+    }
+    if db.get_alias(&name)?.is_none() {
+      db.set_alias(&name, &target)?;
+      added.push(Alias { name, target });
+    }
+  }
+  Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pair(name: &str, target: &str) -> (String, String) {
+    (name.to_string(), target.to_string())
+  }
+
+  #[test]
+  fn test_parse_shell() {
+    let content = r#"
+# aliases
+alias gco='git checkout'
+alias ll="ls -la"  # long listing
+alias ls='ls --color=auto'
+alias -g G='| grep'
+alias k=kubectl
+alias dc='sudo docker compose up -d'
+alias gitroot='cd $(git rev-parse --show-toplevel)'
+alias gst 'git status'
+abbr -a gp git push
+export PATH="$HOME/bin:$PATH"
+"#;
+    assert_eq!(
+      parse_shell(content),
+      [
+        pair("gco", "git checkout"),
+        pair("ll", "ls"),
+        pair("k", "kubectl"),
+        pair("dc", "docker compose up"),
+        pair("gst", "git status"),
+        pair("gp", "git push"),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse_git() {
+    let output = "alias.co checkout\nalias.lg log --oneline --graph\nalias.up !git pull --rebase\n";
+    assert_eq!(
+      parse_git(output),
+      [pair("git co", "git checkout"), pair("git lg", "git log")]
+    );
+  }
+
+  #[test]
+  fn test_names() {
+    assert_eq!(normalize("  git   checkout "), "git checkout");
+    assert_eq!(
+      command_names("docker compose up"),
+      ["docker-compose-up", "docker-compose", "docker"]
+    );
+    assert_eq!(command_names("kubectl"), ["kubectl"]);
+  }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::audit::Action;
+use crate::storage::AuditEntry;
+use crate::AppState;
+
+/// 单次请求最多返回的记录数
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+  /// Error message
+  pub error: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditQuery {
+  /// Maximum entries to return (default: 50, at most 1000)
+  pub limit: Option<usize>,
+  /// Only list this operation: import, learn, update, delete, reset, restore or sync
+  pub action: Option<String>,
+}
+
+/// List recent data modifications, newest first
+#[utoipa::path(
+    get,
+    path = "/api/audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit log entries", body = Vec<AuditEntry>),
+        (status = 500, description = "Unknown action or storage error", body = ErrorResponse)
+    ),
+    tag = "Audit"
+)]
+pub async fn list(
+  State(state): State<Arc<AppState>>,
+  Query(params): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+  if let Some(action) = params.action.as_deref() {
+    if Action::parse(action).is_none() {
+      return Err(to_error(format!("Unknown action '{}'", action)));
+    }
+  }
+  let limit = params.limit.unwrap_or(50).min(MAX_LIMIT);
+  state
+    .db
+    .list_audit(limit, params.action.as_deref())
+    .map(Json)
+    .map_err(|e| to_error(e.to_string()))
+}
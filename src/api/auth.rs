@@ -0,0 +1,161 @@
+//! API 的身份验证
+//!
+//! server.multi_user 开启时，除 /api/health 和 /api/health/ready 外的 API 请求和 /ws/ 连接都需要 `Authorization: Bearer <token>`。
+//! 验证通过后把 CurrentUser 放入请求扩展，供各接口选择用户自己的数据；
+//! 修改共享数据的接口和审计日志只允许管理员调用。
+//!
+//! 单用户模式下配置了 server.api_keys 或 server.api_key_file 时，修改数据的接口需要其中一个密钥，
+//! 放在 `Authorization: Bearer <key>` 或 `X-API-Key` 请求头中；只读接口不受影响。
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use crate::audit::Origin;
+use crate::config::ServerConfig;
+use crate::users::{self, UserData};
+use crate::AppState;
+
+/// 修改共享数据的接口
+pub const SHARED_MUTATIONS: &[&str] = &[
+  "/api/import",
+  "/api/import/file",
+  "/api/restore",
+  "/api/reset",
+  "/api/update/download",
+  "/api/sync/push",
+];
+
+/// 除 Authorization 外可以放 API 密钥的请求头
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// 只允许管理员调用的接口（含客户端地址等信息）
+pub const ADMIN_ONLY: &[&str] = &["/api/audit"];
+
+/// 验证通过的用户
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+  pub name: String,
+}
+
+fn reject(status: StatusCode, error: &str) -> Response {
+  let mut response = (
+    status,
+    Json(super::search::ErrorResponse {
+      error: error.to_string(),
+    }),
+  )
+    .into_response();
+  if status == StatusCode::UNAUTHORIZED {
+    response
+      .headers_mut()
+      .insert(header::WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+  }
+  response
+}
+
+pub async fn authenticate(
+  State(state): State<Arc<AppState>>,
+  mut request: Request,
+  next: Next,
+) -> Response {
+  let config = state.config();
+  let path = request.uri().path();
+  if !config.server.multi_user {
+    if config.server.requires_api_key() && super::is_mutating(request.method(), path) {
+      if let Some(response) = check_api_key(&config.server, &request) {
+        return response;
+      }
+    }
+    return next.run(request).await;
+  }
+  if !(path.starts_with("/api/") || path.starts_with("/ws/"))
+    || matches!(path, "/api/health" | "/api/health/ready")
+  {
+    return next.run(request).await;
+  }
+
+  let token = bearer_token(&request);
+  let Some((name, admin)) = token.and_then(|t| users::authenticate(&config, t)) else {
+    return reject(StatusCode::UNAUTHORIZED, "Missing or invalid API token");
+  };
+
+  if !admin && request.method() != Method::GET && SHARED_MUTATIONS.contains(&path) {
+    return reject(
+      StatusCode::FORBIDDEN,
+      "Only admin users can modify the shared dataset",
+    );
+  }
+  if !admin && ADMIN_ONLY.contains(&path) {
+    return reject(
+      StatusCode::FORBIDDEN,
+      "Only admin users can view the audit log",
+    );
+  }
+
+  request.extensions_mut().insert(CurrentUser { name });
+  next.run(request).await
+}
+
+fn bearer_token(request: &Request) -> Option<&str> {
+  request
+    .headers()
+    .get(header::AUTHORIZATION)
+    .and_then(|v| v.to_str().ok())
+    .and_then(|v| v.strip_prefix("Bearer "))
+    .map(str::trim)
+}
+
+/// 检查请求中的 API 密钥，不通过时返回拒绝的响应
+fn check_api_key(server: &ServerConfig, request: &Request) -> Option<Response> {
+  let keys = match server.load_api_keys() {
+    Ok(keys) => keys,
+    Err(e) => {
+      tracing::error!("Failed to read server.api_key_file: {}", e);
+      return Some(reject(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to read the API key file",
+      ));
+    }
+  };
+  let key = request
+    .headers()
+    .get(API_KEY_HEADER)
+    .and_then(|v| v.to_str().ok())
+    .map(str::trim)
+    .or_else(|| bearer_token(request));
+  // 比较摘要，耗时与密钥内容无关
+  let valid = key.is_some_and(|key| {
+    let hash = users::hash_token(key);
+    keys.iter().any(|k| users::hash_token(k) == hash)
+  });
+  (!valid).then(|| reject(StatusCode::UNAUTHORIZED, "Missing or invalid API key"))
+}
+
+/// 审计日志中的请求来源；没有连接信息时（如测试中直接调用路由）不记录地址
+pub fn origin(client: Option<ConnectInfo<SocketAddr>>, user: Option<&CurrentUser>) -> Origin {
+  Origin::api(
+    client.map(|ConnectInfo(addr)| addr),
+    user.map(|user| user.name.as_str()),
+  )
+}
+
+/// 当前用户自己的数据；单用户模式下为 None
+pub fn user_data(
+  state: &AppState,
+  user: Option<&CurrentUser>,
+) -> Result<Option<Arc<UserData>>, String> {
+  match user {
+    Some(user) => state
+      .users
+      .get(&user.name, &state.config())
+      .map(Some)
+      .map_err(|e| format!("Failed to open data for user '{}': {}", user.name, e)),
+    None => Ok(None),
+  }
+}
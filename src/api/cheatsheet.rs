@@ -0,0 +1,439 @@
+//! 速查表网页
+//!
+//! `/cheatsheet` 列出所有命令，`/cheatsheet/{name}` 把命令渲染成独立的 HTML 页面：
+//! 先按 tldr 页面的格式写成 Markdown，再转换为 HTML，示例代码按 shell 语法着色。
+//! 页面不依赖外部资源，`rtfm serve` 即可作为团队内部的文档站点。
+
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::Html;
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Parser, Tag, TagEnd};
+use serde::Deserialize;
+
+use crate::storage::Command;
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CheatsheetQuery {
+  /// 语言，默认 search.default_lang
+  pub lang: Option<String>,
+}
+
+/// 页面共用的样式，跟随系统的深浅色
+const STYLE: &str = r#"
+:root { color-scheme: light dark; --fg: #1f2328; --bg: #ffffff; --muted: #656d76; --code-bg: #f6f8fa;
+  --cmd: #0550ae; --flag: #8250df; --ph: #953800; --str: #0a3069; --comment: #6e7781; --op: #cf222e; }
+@media (prefers-color-scheme: dark) {
+  :root { --fg: #e6edf3; --bg: #0d1117; --muted: #8d96a0; --code-bg: #161b22;
+    --cmd: #79c0ff; --flag: #d2a8ff; --ph: #ffa657; --str: #a5d6ff; --comment: #8b949e; --op: #ff7b72; }
+}
+body { font: 16px/1.6 system-ui, sans-serif; color: var(--fg); background: var(--bg);
+  max-width: 56rem; margin: 0 auto; padding: 1.5rem; }
+a { color: var(--cmd); text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav, .meta, .desc, blockquote { color: var(--muted); }
+blockquote { margin: 0; }
+h2 { font-size: 1.1rem; margin: 1.5rem 0 0.5rem; }
+pre { background: var(--code-bg); padding: 0.75rem 1rem; border-radius: 6px; overflow-x: auto; }
+code { font: 14px/1.5 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+ul.index { list-style: none; padding: 0; columns: 2 20rem; }
+ul.index li { break-inside: avoid; margin-bottom: 0.25rem; }
+.cmd { color: var(--cmd); font-weight: 600; } .flag { color: var(--flag); } .str { color: var(--str); }
+.ph { color: var(--ph); font-style: italic; } .comment { color: var(--comment); } .op { color: var(--op); }
+"#;
+
+/// 断开两条命令、其后是新命令名的记号
+const COMMAND_SEPARATORS: &[&str] = &["|", "||", "&&", ";", "&", "$(", "`"];
+
+/// 重定向等只着色、不影响命令名的记号
+const OPERATORS: &[&str] = &[">", ">>", "<", "2>", "2>&1", "&>"];
+
+/// 列出命令的首页
+pub async fn index(
+  State(state): State<Arc<AppState>>,
+  Query(params): Query<CheatsheetQuery>,
+) -> Result<Html<String>, (StatusCode, Html<String>)> {
+  let config = state.config();
+  let lang = params
+    .lang
+    .as_deref()
+    .unwrap_or(&config.search.default_lang);
+  let to_error = |e: crate::storage::StorageError| {
+    (
+      StatusCode::INTERNAL_SERVER_ERROR,
+      Html(error_page(&e.to_string())),
+    )
+  };
+
+  // 本地学习的命令与该语言的页面一起列出，同名时只列一次
+  let mut commands = state.db.get_all_commands(lang).map_err(to_error)?;
+  if lang != "local" {
+    let local = state.db.get_all_commands("local").map_err(to_error)?;
+    let local: Vec<Command> = local
+      .into_iter()
+      .filter(|l| !commands.iter().any(|c| c.name == l.name))
+      .collect();
+    commands.extend(local);
+  }
+  commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+  Ok(Html(index_page(&commands, lang)))
+}
+
+/// 单条命令的页面，name 也可以是别名
+pub async fn page(
+  State(state): State<Arc<AppState>>,
+  Path(name): Path<String>,
+  Query(params): Query<CheatsheetQuery>,
+) -> Result<Html<String>, (StatusCode, Html<String>)> {
+  let config = state.config();
+  let lang = params
+    .lang
+    .as_deref()
+    .unwrap_or(&config.search.default_lang);
+
+  // 别名（如 gco → git checkout）按指向的命令查找，页面名从长到短
+  let names = match state.db.get_alias(&crate::alias::normalize(&name)) {
+    Ok(Some(target)) => crate::alias::command_names(&target),
+    _ => vec![name.clone()],
+  };
+  for candidate in &names {
+    match state.db.find_command(candidate, lang) {
+      Ok(Some(cmd)) => {
+        crate::usage::record_lookup(&state.db, &config, &cmd.name, &cmd.lang, "api");
+        return Ok(Html(command_page(&cmd)));
+      }
+      Ok(None) => {}
+      Err(e) => {
+        return Err((
+          StatusCode::INTERNAL_SERVER_ERROR,
+          Html(error_page(&e.to_string())),
+        ))
+      }
+    }
+  }
+  Err((
+    StatusCode::NOT_FOUND,
+    Html(error_page(&format!("Command '{}' not found", name))),
+  ))
+}
+
+/// 完整的 HTML 文档
+fn document(title: &str, body: &str) -> String {
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+     <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+     <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+    escape(title),
+    STYLE,
+    body
+  )
+}
+
+fn error_page(message: &str) -> String {
+  document(
+    "RTFM",
+    &format!(
+      "<nav><a href=\"/cheatsheet\">RTFM</a></nav>\n<p>{}</p>\n",
+      escape(message)
+    ),
+  )
+}
+
+fn index_page(commands: &[Command], lang: &str) -> String {
+  let mut body = format!(
+    "<h1>RTFM</h1>\n<p class=\"meta\">{} commands ({})</p>\n<ul class=\"index\">\n",
+    commands.len(),
+    escape(lang)
+  );
+  for cmd in commands {
+    body.push_str(&format!(
+      "<li><a href=\"/cheatsheet/{}?lang={}\">{}</a> <span class=\"desc\">{}</span></li>\n",
+      url_encode(&cmd.name),
+      url_encode(&cmd.lang),
+      escape(&cmd.name),
+      escape(first_line(&cmd.description))
+    ));
+  }
+  body.push_str("</ul>\n");
+  document("RTFM", &body)
+}
+
+fn command_page(cmd: &Command) -> String {
+  let mut body = String::from("<nav><a href=\"/cheatsheet\">RTFM</a></nav>\n");
+  body.push_str(&markdown_html(&crate::output::command_markdown(cmd)));
+  body.push_str(&format!(
+    "<p class=\"meta\">{} · {} · {}</p>\n",
+    escape(&cmd.lang),
+    escape(&cmd.platform),
+    escape(&cmd.category)
+  ));
+  document(&cmd.name, &body)
+}
+
+/// Markdown 转为 HTML，代码块按 shell 语法着色
+fn markdown_html(md: &str) -> String {
+  let mut events = Vec::new();
+  // 代码块中的文字先收集起来，结束时整体着色；bool 表示是否为 shell 示例
+  let mut code: Option<(bool, String)> = None;
+  for event in Parser::new(md) {
+    match event {
+      Event::Start(Tag::CodeBlock(kind)) => {
+        // 只有标注了 sh 的示例着色，用法摘要保持原样
+        let shell = matches!(kind, CodeBlockKind::Fenced(ref lang) if &**lang == "sh");
+        code = Some((shell, String::new()));
+      }
+      Event::End(TagEnd::CodeBlock) => {
+        let (shell, text) = code.take().unwrap_or_default();
+        let text = text.trim_end_matches('\n');
+        let html = if shell {
+          format!(
+            "<pre class=\"shell\"><code>{}</code></pre>\n",
+            highlight_shell(text)
+          )
+        } else {
+          format!("<pre><code>{}</code></pre>\n", escape(text))
+        };
+        events.push(Event::Html(CowStr::from(html)));
+      }
+      Event::Text(text) if code.is_some() => {
+        if let Some((_, buffer)) = code.as_mut() {
+          buffer.push_str(&text);
+        }
+      }
+      // 命令内容来自 man 页面、文档集、插件或同步，其中的 HTML 一律按文字转义
+      Event::Html(html) | Event::InlineHtml(html) => events.push(Event::Text(html)),
+      Event::Start(Tag::Link {
+        link_type,
+        dest_url,
+        title,
+        id,
+      }) => events.push(Event::Start(Tag::Link {
+        link_type,
+        dest_url: safe_url(dest_url),
+        title,
+        id,
+      })),
+      Event::Start(Tag::Image {
+        link_type,
+        dest_url,
+        title,
+        id,
+      }) => events.push(Event::Start(Tag::Image {
+        link_type,
+        dest_url: safe_url(dest_url),
+        title,
+        id,
+      })),
+      event => events.push(event),
+    }
+  }
+  let mut html = String::with_capacity(md.len() * 2);
+  pulldown_cmark::html::push_html(&mut html, events.into_iter());
+  html
+}
+
+/// 只保留 http(s)、mailto 和相对地址，其他协议（如 javascript:）的链接去掉地址
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+  let scheme_end = url.find([':', '/', '?', '#']);
+  match scheme_end {
+    Some(i) if url[i..].starts_with(':') => {
+      let scheme = url[..i].to_ascii_lowercase();
+      if ["http", "https", "mailto"].contains(&scheme.as_str()) {
+        url
+      } else {
+        CowStr::from("")
+      }
+    }
+    _ => url,
+  }
+}
+
+/// shell 示例着色：命令名、选项、字符串、注释、运算符和 tldr 占位符
+fn highlight_shell(code: &str) -> String {
+  let mut out = String::with_capacity(code.len() * 2);
+  for (i, line) in code.lines().enumerate() {
+    if i > 0 {
+      out.push('\n');
+    }
+    highlight_line(line, &mut out);
+  }
+  out
+}
+
+fn highlight_line(line: &str, out: &mut String) {
+  // 行首或分隔符之后的词是命令名
+  let mut command = true;
+  let mut rest = line;
+  loop {
+    let trimmed = rest.trim_start();
+    out.push_str(&rest[..rest.len() - trimmed.len()]);
+    rest = trimmed;
+    if rest.is_empty() {
+      break;
+    }
+    if rest.starts_with('#') {
+      push_span(out, "comment", rest);
+      break;
+    }
+    let end = word_end(rest);
+    let word = &rest[..end];
+    rest = &rest[end..];
+
+    let class = if COMMAND_SEPARATORS.contains(&word) {
+      command = true;
+      "op"
+    } else if OPERATORS.contains(&word) {
+      "op"
+    } else if command && !word.contains('=') {
+      command = false;
+      "cmd"
+    } else if word.starts_with('-') || word.starts_with("{{[") {
+      "flag"
+    } else if word.starts_with(['\'', '"']) {
+      "str"
+    } else {
+      ""
+    };
+    push_span(out, class, word);
+  }
+}
+
+/// 词的结尾：空白处结束，引号和占位符中的空白不算
+fn word_end(s: &str) -> usize {
+  let mut quote = None;
+  let mut depth = 0;
+  let mut chars = s.char_indices();
+  while let Some((i, c)) = chars.next() {
+    match quote {
+      Some(q) if c == q => quote = None,
+      Some(_) => {}
+      None if c == '\'' || c == '"' => quote = Some(c),
+      None if s[i..].starts_with("{{") => {
+        depth += 1;
+        chars.next();
+      }
+      None if depth > 0 && s[i..].starts_with("}}") => {
+        depth -= 1;
+        chars.next();
+      }
+      None if c.is_whitespace() && depth == 0 => return i,
+      None => {}
+    }
+  }
+  s.len()
+}
+
+/// 写入一个着色的片段，其中的占位符单独着色
+fn push_span(out: &mut String, class: &str, text: &str) {
+  if !class.is_empty() {
+    out.push_str(&format!("<span class=\"{}\">", class));
+  }
+  let mut rest = text;
+  while let Some(start) = rest.find("{{") {
+    let Some(end) = rest[start + 2..].find("}}") else {
+      break;
+    };
+    out.push_str(&escape(&rest[..start]));
+    out.push_str(&format!(
+      "<span class=\"ph\">{}</span>",
+      escape(&rest[start + 2..start + 2 + end])
+    ));
+    rest = &rest[start + 2 + end + 2..];
+  }
+  out.push_str(&escape(rest));
+  if !class.is_empty() {
+    out.push_str("</span>");
+  }
+}
+
+fn escape(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn first_line(s: &str) -> &str {
+  s.lines().next().unwrap_or("")
+}
+
+/// 链接中的路径段和查询参数只保留不需要转义的字符
+fn url_encode(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  for byte in s.bytes() {
+    if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+      out.push(byte as char);
+    } else {
+      out.push_str(&format!("%{:02X}", byte));
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_highlight_shell() {
+    assert_eq!(
+      highlight_shell("tar {{[-x|--extract]}} -f {{path/to/a b.tar}} | grep 'x y' # list"),
+      "<span class=\"cmd\">tar</span> <span class=\"flag\"><span class=\"ph\">[-x|--extract]</span></span> \
+       <span class=\"flag\">-f</span> <span class=\"ph\">path/to/a b.tar</span> \
+       <span class=\"op\">|</span> <span class=\"cmd\">grep</span> \
+       <span class=\"str\">'x y'</span> <span class=\"comment\"># list</span>"
+    );
+    // 环境变量赋值之后才是命令名
+    assert_eq!(
+      highlight_shell("LANG=C sort"),
+      "LANG=C <span class=\"cmd\">sort</span>"
+    );
+  }
+
+  #[test]
+  fn test_markdown_html() {
+    let html =
+      markdown_html("# ls\n\n> List files <https://example.com>\n\n## All\n\n```sh\nls -a\n```\n");
+    assert!(html.contains("<h1>ls</h1>"));
+    assert!(html.contains("<a href=\"https://example.com\">"));
+    assert!(html.contains(
+      "<pre class=\"shell\"><code><span class=\"cmd\">ls</span> <span class=\"flag\">-a</span></code></pre>"
+    ));
+  }
+
+  #[test]
+  fn test_markdown_html_escapes_html() {
+    let cmd = Command {
+      name: "evil".to_string(),
+      description: "<script>alert(1)</script> reads <file>".to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: vec![],
+      content: String::new(),
+      sections: vec![],
+      options: vec![],
+      source_info: None,
+      notes: None,
+      schema: crate::storage::COMMAND_SCHEMA,
+    };
+    let html = markdown_html(&crate::output::command_markdown(&cmd));
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(html.contains("&lt;file&gt;"));
+
+    let html = markdown_html("[a](javascript:alert(1)) [b](https://x.org) [c](/cheatsheet/ls)");
+    assert!(!html.contains("javascript:"));
+    assert!(html.contains("href=\"https://x.org\""));
+    assert!(html.contains("href=\"/cheatsheet/ls\""));
+  }
+
+  #[test]
+  fn test_url_encode() {
+    assert_eq!(url_encode("git commit"), "git%20commit");
+    assert_eq!(url_encode("docker-compose"), "docker-compose");
+  }
+}
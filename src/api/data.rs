@@ -1,24 +1,61 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::extract::{Multipart, Path, Query, State};
-use axum::Json;
+use axum::extract::{ConnectInfo, Multipart, Path, Query, State};
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::storage::{Command, Metadata};
+use super::auth::{self, CurrentUser};
+use crate::audit::{self, Action};
+use crate::config::CommandPrefs;
+use crate::search::Platform;
+use crate::storage::{Command, Database, Metadata};
 use crate::update;
 use crate::AppState;
 
+/// 每页最多返回的命令数
+const MAX_PER_PAGE: usize = 1000;
+
+/// 默认每页的命令数
+const DEFAULT_PER_PAGE: usize = 100;
+
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct ListQuery {
   /// Language filter (default: zh)
   pub lang: Option<String>,
+  /// Platform filter, e.g. linux, osx, windows: that platform and common commands (common: only common commands)
+  pub platform: Option<String>,
+  /// Page number, starting at 1 (default: 1)
+  pub page: Option<usize>,
+  /// Commands per page (default: 100, at most 1000)
+  pub per_page: Option<usize>,
+  /// Sort order: name (default), category, or recently-updated (newest learned first)
+  pub sort: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommandList {
+  /// Number of commands matching the filters
+  pub total: usize,
+  /// Current page, starting at 1
+  pub page: usize,
+  /// Commands per page
+  pub per_page: usize,
+  /// Number of pages
+  pub pages: usize,
+  /// Commands on this page
+  pub commands: Vec<Command>,
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct CommandQuery {
   /// Language filter (default: zh)
   pub lang: Option<String>,
+  /// Merge the locally learned help into the tldr page (tldr examples first)
+  #[serde(default)]
+  pub merged: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -27,6 +64,14 @@ pub struct ErrorResponse {
   pub error: String,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MetadataResponse {
+  #[serde(flatten)]
+  pub metadata: Metadata,
+  /// Mutating endpoints are disabled (`rtfm serve --read-only`)
+  pub read_only: bool,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ImportResponse {
   /// Number of commands imported
@@ -42,7 +87,7 @@ pub struct ImportResponse {
     get,
     path = "/api/command/{name}",
     params(
-        ("name" = String, Path, description = "Command name"),
+        ("name" = String, Path, description = "Command name or alias (see rtfm alias)"),
         CommandQuery
     ),
     responses(
@@ -53,44 +98,216 @@ pub struct ImportResponse {
 )]
 pub async fn get_command(
   State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
   Path(name): Path<String>,
   Query(params): Query<CommandQuery>,
 ) -> Result<Json<Command>, Json<ErrorResponse>> {
   let lang = params.lang.as_deref().unwrap_or("zh");
+  let to_error = |error: String| Json(ErrorResponse { error });
+
+  // 多用户模式下用户自己学习的 local 命令优先
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let lookup = |name: &str| -> anyhow::Result<Option<Command>> {
+    match &user_data {
+      Some(data) if params.merged => crate::users::find_command(&state.db, &data.db, name, lang),
+      Some(data) if lang == "local" => data
+        .db
+        .get_command(name, lang)
+        .and_then(|own| match own {
+          Some(cmd) => Ok(Some(cmd)),
+          None => state.db.get_command(name, lang),
+        })
+        .map_err(Into::into),
+      _ if params.merged => state.db.find_command(name, lang).map_err(Into::into),
+      _ => state.db.get_command(name, lang).map_err(Into::into),
+    }
+  };
 
-  match state.db.get_command(&name, lang) {
-    Ok(Some(cmd)) => Ok(Json(cmd)),
-    Ok(None) => Err(Json(ErrorResponse {
-      error: format!("Command '{}' not found", name),
-    })),
-    Err(e) => Err(Json(ErrorResponse {
-      error: e.to_string(),
-    })),
+  // 别名（如 gco → git checkout）按指向的命令查找，页面名从长到短
+  let names = match state.db.get_alias(&crate::alias::normalize(&name)) {
+    Ok(Some(target)) => crate::alias::command_names(&target),
+    _ => vec![name.clone()],
+  };
+  let mut result = Ok(None);
+  for candidate in &names {
+    result = lookup(candidate);
+    if !matches!(result, Ok(None)) {
+      break;
+    }
+  }
+
+  match result {
+    Ok(Some(mut cmd)) => {
+      let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+      // 笔记单独存放（多用户模式下在用户自己的数据库中），未合并的查找不带笔记
+      cmd.notes = db
+        .get_note(&cmd.name)
+        .map_err(|e| to_error(e.to_string()))?;
+      crate::usage::record_lookup(db, &state.config(), &cmd.name, &cmd.lang, "api");
+      Ok(Json(cmd))
+    }
+    Ok(None) => Err(to_error(format!("Command '{}' not found", name))),
+    Err(e) => Err(to_error(e.to_string())),
+  }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteQuery {
+  /// Only delete the command in this language (default: all languages)
+  pub lang: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteResponse {
+  /// Command name
+  pub name: String,
+  /// Languages the command was deleted from
+  pub deleted: Vec<String>,
+  /// Status message
+  pub message: String,
+}
+
+/// Delete a command from the database and search index
+#[utoipa::path(
+    delete,
+    path = "/api/command/{name}",
+    params(
+        ("name" = String, Path, description = "Command name"),
+        DeleteQuery
+    ),
+    responses(
+        (status = 200, description = "Command deleted", body = DeleteResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 404, description = "Command not found", body = ErrorResponse)
+    ),
+    security((), ("api_key" = []), ("bearer" = [])),
+    tag = "Commands"
+)]
+pub async fn delete_command(
+  State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
+  Path(name): Path<String>,
+  Query(params): Query<DeleteQuery>,
+) -> Result<Json<DeleteResponse>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+
+  // 多用户模式下只删除用户自己学习的命令
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let (db, search) = match &user_data {
+    Some(data) => (&data.db, &data.search),
+    None => (&state.db, &state.search),
+  };
+
+  let deleted = {
+    let mut search = search.write().await;
+    crate::search::delete_command(db, &mut search, &name, params.lang.as_deref())
+      .map_err(|e| to_error(e.to_string()))?
+  };
+  if deleted.is_empty() {
+    return Err(to_error(format!("Command '{}' not found", name)));
   }
+  audit::record(
+    &state.db,
+    &auth::origin(client, user.as_deref()),
+    Action::Delete,
+    deleted.len(),
+    format!("{} ({})", name, deleted.join(", ")),
+  );
+
+  Ok(Json(DeleteResponse {
+    message: format!("Deleted '{}' ({})", name, deleted.join(", ")),
+    name,
+    deleted,
+  }))
 }
 
-/// List all commands
+/// List commands page by page
 #[utoipa::path(
     get,
     path = "/api/commands",
     params(ListQuery),
     responses(
-        (status = 200, description = "List of all commands", body = Vec<Command>),
-        (status = 500, description = "Internal error", body = ErrorResponse)
+        (status = 200, description = "One page of commands", body = CommandList),
+        (status = 500, description = "Unknown sort order or internal error", body = ErrorResponse)
     ),
     tag = "Commands"
 )]
 pub async fn list_commands(
   State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
   Query(params): Query<ListQuery>,
-) -> Result<Json<Vec<Command>>, Json<ErrorResponse>> {
+) -> Result<Json<CommandList>, Json<ErrorResponse>> {
   let lang = params.lang.as_deref().unwrap_or("zh");
+  let to_error = |error: String| Json(ErrorResponse { error });
+  let sort = params.sort.as_deref().unwrap_or("name");
+  if !["name", "category", "recently-updated"].contains(&sort) {
+    return Err(to_error(format!(
+      "Unknown sort '{}', expected name, category or recently-updated",
+      sort
+    )));
+  }
 
-  match state.db.get_all_commands(lang) {
-    Ok(commands) => Ok(Json(commands)),
-    Err(e) => Err(Json(ErrorResponse {
-      error: e.to_string(),
-    })),
+  let mut commands = state
+    .db
+    .get_all_commands(lang)
+    .map_err(|e| to_error(e.to_string()))?;
+
+  // 多用户模式下用户自己学习的命令替换同名的共享 local 命令
+  if lang == "local" {
+    if let Some(data) = auth::user_data(&state, user.as_deref()).map_err(to_error)? {
+      let own = data
+        .db
+        .get_all_commands(lang)
+        .map_err(|e| to_error(e.to_string()))?;
+      commands.retain(|cmd| !own.iter().any(|o| o.name == cmd.name));
+      commands.extend(own);
+    }
+  }
+
+  if let Some(platform) = &params.platform {
+    let platform = Platform::parse(platform);
+    commands.retain(|cmd| platform.matches(&cmd.platform));
+  }
+
+  sort_commands(&mut commands, sort);
+  Ok(Json(paginate(
+    commands,
+    params.page.unwrap_or(1),
+    params.per_page.unwrap_or(DEFAULT_PER_PAGE),
+  )))
+}
+
+/// 按 name、category 或 recently-updated 排序，同类的命令按名称排列
+fn sort_commands(commands: &mut [Command], sort: &str) {
+  match sort {
+    "category" => commands.sort_by(|a, b| (&a.category, &a.name).cmp(&(&b.category, &b.name))),
+    // 没有学习时间的 tldr 页面排在最后
+    "recently-updated" => commands.sort_by(|a, b| {
+      b.updated_at()
+        .cmp(&a.updated_at())
+        .then_with(|| a.name.cmp(&b.name))
+    }),
+    _ => commands.sort_by(|a, b| a.name.cmp(&b.name)),
+  }
+}
+
+/// 取出一页；page 从 1 开始，超出范围时返回空页
+fn paginate(commands: Vec<Command>, page: usize, per_page: usize) -> CommandList {
+  let page = page.max(1);
+  let per_page = per_page.clamp(1, MAX_PER_PAGE);
+  let total = commands.len();
+  let commands = commands
+    .into_iter()
+    .skip((page - 1).saturating_mul(per_page))
+    .take(per_page)
+    .collect();
+  CommandList {
+    total,
+    page,
+    per_page,
+    pages: total.div_ceil(per_page),
+    commands,
   }
 }
 
@@ -99,26 +316,34 @@ pub async fn list_commands(
     get,
     path = "/api/metadata",
     responses(
-        (status = 200, description = "Database metadata", body = Metadata),
+        (status = 200, description = "Database metadata", body = MetadataResponse),
         (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Commands"
 )]
 pub async fn get_metadata(
   State(state): State<Arc<AppState>>,
-) -> Result<Json<Metadata>, Json<ErrorResponse>> {
-  match state.db.get_metadata() {
-    Ok(Some(meta)) => Ok(Json(meta)),
-    Ok(None) => Ok(Json(Metadata {
+) -> Result<Json<MetadataResponse>, Json<ErrorResponse>> {
+  let metadata = match state.db.get_metadata() {
+    Ok(Some(meta)) => meta,
+    Ok(None) => Metadata {
       version: "0.0.0".to_string(),
       command_count: 0,
       last_update: "never".to_string(),
       languages: vec![],
-    })),
-    Err(e) => Err(Json(ErrorResponse {
-      error: e.to_string(),
-    })),
-  }
+      source: None,
+      selected_languages: None,
+    },
+    Err(e) => {
+      return Err(Json(ErrorResponse {
+        error: e.to_string(),
+      }))
+    }
+  };
+  Ok(Json(MetadataResponse {
+    metadata,
+    read_only: state.read_only,
+  }))
 }
 
 /// Import commands from JSON
@@ -128,12 +353,16 @@ pub async fn get_metadata(
     request_body = Vec<Command>,
     responses(
         (status = 200, description = "Import successful", body = ImportResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
         (status = 500, description = "Import failed", body = ErrorResponse)
     ),
+    security((), ("api_key" = []), ("bearer" = [])),
     tag = "Data"
 )]
 pub async fn import_json(
   State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
   Json(commands): Json<Vec<Command>>,
 ) -> Result<Json<ImportResponse>, Json<ErrorResponse>> {
   let count = commands.len();
@@ -145,9 +374,9 @@ pub async fn import_json(
     }));
   }
 
-  // 重建索引
+  // 索引导入的命令，其余命令不变
   let mut search = state.search.write().await;
-  if let Err(e) = search.index_commands(&commands) {
+  if let Err(e) = crate::search::index_commands(&state.db, &mut search, &commands) {
     return Err(Json(ErrorResponse {
       error: e.to_string(),
     }));
@@ -158,9 +387,18 @@ pub async fn import_json(
     version: chrono::Utc::now().format("%Y.%m.%d").to_string(),
     command_count: state.db.count_commands().unwrap_or(0),
     last_update: chrono::Utc::now().to_rfc3339(),
-    languages: state.config.update.languages.clone(),
+    languages: state.config().update.languages.clone(),
+    source: Some("import".to_string()),
+    selected_languages: None,
   };
   let _ = state.db.save_metadata(&meta);
+  audit::record(
+    &state.db,
+    &auth::origin(client, user.as_deref()),
+    Action::Import,
+    count,
+    "json",
+  );
 
   Ok(Json(ImportResponse {
     imported: count,
@@ -169,36 +407,93 @@ pub async fn import_json(
   }))
 }
 
+/// 监视 import.watch_dirs，新增或修改的 .md 文件自动导入
+pub fn spawn_import_watcher(state: Arc<AppState>) {
+  let dirs: Vec<std::path::PathBuf> = state
+    .config()
+    .import
+    .watch_dirs
+    .iter()
+    .filter(|dir| dir.is_dir())
+    .cloned()
+    .collect();
+  if dirs.is_empty() {
+    return;
+  }
+  tracing::info!("Watching {:?} for cheatsheet changes", dirs);
+  let detail = dirs
+    .iter()
+    .map(|dir| dir.display().to_string())
+    .collect::<Vec<_>>()
+    .join(", ");
+
+  // 通知在独立线程中阻塞等待，不占用运行时的线程
+  let spawned = std::thread::Builder::new()
+    .name("import-watcher".to_string())
+    .spawn(move || {
+      let result = crate::watch::watch(&dirs, |files| {
+        let mut search = state.search.blocking_write();
+        match crate::watch::import_files(&state.db, &mut search, &files) {
+          Ok(commands) if commands.is_empty() => {}
+          Ok(commands) => {
+            tracing::info!("Imported {} commands from {:?}", commands.len(), files);
+            audit::record(
+              &state.db,
+              &audit::Origin::watcher(),
+              Action::Import,
+              commands.len(),
+              detail.clone(),
+            );
+          }
+          Err(e) => tracing::warn!("Failed to import {:?}: {}", files, e),
+        }
+      });
+      if let Err(e) = result {
+        tracing::warn!("Import watcher stopped: {}", e);
+      }
+    });
+  if let Err(e) = spawned {
+    tracing::warn!("Failed to start import watcher: {}", e);
+  }
+}
+
 /// File upload request body for import
 #[derive(Debug, ToSchema)]
 #[allow(dead_code)]
 pub struct FileUpload {
-  /// File to import (md, zip, tar, tar.gz, tgz)
+  /// File to import (md, zip, tar, tar.gz, tgz, or a navi .cheat file)
   #[schema(value_type = String, format = Binary)]
   pub file: Vec<u8>,
 }
 
-/// Import commands from file upload (supports .md, .zip, .tar, .tar.gz, .tgz)
+/// Import commands from file upload (supports .md, .zip, .tar, .tar.gz, .tgz and navi .cheat)
 #[utoipa::path(
     post,
     path = "/api/import/file",
-    request_body(content_type = "multipart/form-data", content = FileUpload, description = "File to import in tldr-pages format"),
+    request_body(content_type = "multipart/form-data", content = FileUpload, description = "File to import in tldr-pages or navi format"),
     responses(
         (status = 200, description = "Import successful", body = ImportResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
         (status = 500, description = "Import failed", body = ErrorResponse)
     ),
+    security((), ("api_key" = []), ("bearer" = [])),
     tag = "Data"
 )]
 pub async fn import_file(
   State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
   mut multipart: Multipart,
 ) -> Result<Json<ImportResponse>, Json<ErrorResponse>> {
   let mut commands = Vec::new();
+  let mut filenames = Vec::new();
   let mut total_skipped = 0;
-  let languages = &state.config.update.languages;
+  let config = state.config();
+  let languages = &config.update.languages;
 
-  while let Ok(Some(field)) = multipart.next_field().await {
+  // 超过 max_upload_size 时读取失败，需要报告而不是当作没有文件
+  while let Some(field) = multipart.next_field().await.map_err(upload_error)? {
     let filename = field.file_name().unwrap_or("unknown").to_string();
     let data = field.bytes().await.map_err(|e| {
       Json(ErrorResponse {
@@ -207,16 +502,19 @@ pub async fn import_file(
     })?;
 
     // Parse based on file extension
-    let (parsed, skipped) = parse_file_data(&filename, &data, languages).map_err(|e| {
-      Json(ErrorResponse {
-        error: e.to_string(),
-      })
-    })?;
+    let (parsed, skipped) = parse_file_data(&filename, &data, languages, &config.commands)
+      .map_err(|e| {
+        Json(ErrorResponse {
+          error: e.to_string(),
+        })
+      })?;
 
     commands.extend(parsed);
     total_skipped += skipped;
+    filenames.push(filename);
   }
 
+  let commands = crate::sheets::combine(commands);
   if commands.is_empty() {
     return Err(Json(ErrorResponse {
             error: "No valid Markdown files found. Files must follow tldr-pages format with description or examples.".to_string(),
@@ -225,6 +523,8 @@ pub async fn import_file(
 
   let count = commands.len();
 
+  let commands = crate::sheets::merge_with_learned(&state.db, commands);
+
   // 保存到数据库
   if let Err(e) = state.db.save_commands(&commands) {
     return Err(Json(ErrorResponse {
@@ -232,9 +532,9 @@ pub async fn import_file(
     }));
   }
 
-  // 重建索引
+  // 索引导入的命令，其余命令不变
   let mut search = state.search.write().await;
-  if let Err(e) = search.index_commands(&commands) {
+  if let Err(e) = crate::search::index_commands(&state.db, &mut search, &commands) {
     return Err(Json(ErrorResponse {
       error: e.to_string(),
     }));
@@ -250,8 +550,17 @@ pub async fn import_file(
     } else {
       languages.clone()
     },
+    source: Some("import".to_string()),
+    selected_languages: None,
   };
   let _ = state.db.save_metadata(&meta);
+  audit::record(
+    &state.db,
+    &auth::origin(client, user.as_deref()),
+    Action::Import,
+    count,
+    filenames.join(", "),
+  );
 
   Ok(Json(ImportResponse {
     imported: count,
@@ -260,12 +569,22 @@ pub async fn import_file(
   }))
 }
 
+fn upload_error(e: axum::extract::multipart::MultipartError) -> Json<ErrorResponse> {
+  let error = if e.status() == axum::http::StatusCode::PAYLOAD_TOO_LARGE {
+    "Upload exceeds the size limit for this endpoint (see [server] in the config)".to_string()
+  } else {
+    format!("Failed to read upload: {}", e.body_text())
+  };
+  Json(ErrorResponse { error })
+}
+
 /// Parse file data based on filename extension
 /// Returns (commands, skipped_count)
 fn parse_file_data(
   filename: &str,
   data: &[u8],
   languages: &[String],
+  prefs: &HashMap<String, CommandPrefs>,
 ) -> anyhow::Result<(Vec<Command>, usize)> {
   let ext = std::path::Path::new(filename)
     .extension()
@@ -283,9 +602,16 @@ fn parse_file_data(
         Ok((vec![], 1))
       }
     }
+    "cheat" => {
+      // navi cheatsheet - one command per program
+      let content = String::from_utf8_lossy(data);
+      let commands = crate::sheets::parse_navi(&content, std::path::Path::new(filename));
+      let skipped = usize::from(commands.is_empty());
+      Ok((commands, skipped))
+    }
     "zip" | "gz" | "tgz" | "tar" => {
       // Archive file - use parse_tldr_archive with language filtering
-      match update::parse_tldr_archive(data, languages) {
+      match update::parse_tldr_archive(data, languages, prefs) {
         Ok(commands) => Ok((commands, 0)),
         Err(e) => Err(anyhow::anyhow!("Failed to parse archive: {}", e)),
       }
@@ -302,6 +628,130 @@ fn parse_file_data(
   }
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RestoreQuery {
+  /// Merge into the existing commands instead of replacing them
+  #[serde(default)]
+  pub merge: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreResponse {
+  /// Number of commands read from the backup
+  pub restored: usize,
+  /// Total number of commands after the restore
+  pub total: usize,
+  /// Status message
+  pub message: String,
+}
+
+/// Restore commands from a backup archive created by `rtfm backup`
+#[utoipa::path(
+    post,
+    path = "/api/restore",
+    params(RestoreQuery),
+    request_body(content_type = "multipart/form-data", content = FileUpload, description = "Backup archive (.tar.gz)"),
+    responses(
+        (status = 200, description = "Restore successful", body = RestoreResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 500, description = "Restore failed", body = ErrorResponse)
+    ),
+    security((), ("api_key" = []), ("bearer" = [])),
+    tag = "Data"
+)]
+pub async fn restore_backup(
+  State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
+  Query(params): Query<RestoreQuery>,
+  mut multipart: Multipart,
+) -> Result<Json<RestoreResponse>, Json<ErrorResponse>> {
+  let data = match multipart.next_field().await.map_err(upload_error)? {
+    Some(field) => field.bytes().await.map_err(upload_error)?,
+    None => {
+      return Err(Json(ErrorResponse {
+        error: "No backup archive uploaded".to_string(),
+      }))
+    }
+  };
+
+  // 解压与读取临时数据库都是阻塞 I/O，放到阻塞线程池
+  let db_filename = state.config().storage.db_filename.clone();
+  let (commands, metadata) = tokio::task::spawn_blocking(move || read_backup(&data, &db_filename))
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|result| result)
+    .map_err(|e| {
+      Json(ErrorResponse {
+        error: e.to_string(),
+      })
+    })?;
+
+  let to_error = |e: crate::storage::StorageError| {
+    Json(ErrorResponse {
+      error: e.to_string(),
+    })
+  };
+  if !params.merge {
+    state.db.clear_commands().map_err(to_error)?;
+    if let Some(meta) = &metadata {
+      state.db.save_metadata(meta).map_err(to_error)?;
+    }
+  }
+  state.db.save_commands(&commands).map_err(to_error)?;
+  audit::record(
+    &state.db,
+    &auth::origin(client, user.as_deref()),
+    Action::Restore,
+    commands.len(),
+    if params.merge { "merge" } else { "" },
+  );
+
+  // 合并后整体重建索引
+  let mut search = state.search.write().await;
+  let total = crate::search::rebuild_index(&state.db, &mut search).map_err(|e| {
+    Json(ErrorResponse {
+      error: e.to_string(),
+    })
+  })?;
+
+  Ok(Json(RestoreResponse {
+    restored: commands.len(),
+    total,
+    message: format!(
+      "{} {} commands from backup",
+      if params.merge { "Merged" } else { "Restored" },
+      commands.len()
+    ),
+  }))
+}
+
+/// 从备份归档中取出数据库，读取其中的命令与元数据
+fn read_backup(data: &[u8], db_filename: &str) -> anyhow::Result<(Vec<Command>, Option<Metadata>)> {
+  let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(data));
+  let temp_dir = tempfile::tempdir()?;
+  let db_path = temp_dir.path().join("backup.redb");
+
+  let mut found = false;
+  for entry in archive.entries()? {
+    let mut entry = entry?;
+    let path = entry.path()?.into_owned();
+    // 备份时的数据库文件名可能与当前配置不同
+    if path.as_os_str() == db_filename || path.extension().is_some_and(|e| e == "redb") {
+      entry.unpack(&db_path)?;
+      found = true;
+      break;
+    }
+  }
+  if !found {
+    anyhow::bail!("Not an rtfm backup: no database found in the archive");
+  }
+
+  let db = Database::open(&db_path)?;
+  Ok((db.list_all_commands()?, db.get_metadata()?))
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ResetResponse {
   /// Whether reset was successful
@@ -318,14 +768,19 @@ pub struct ResetResponse {
     path = "/api/reset",
     responses(
         (status = 200, description = "Reset successful", body = ResetResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
         (status = 500, description = "Reset failed", body = ErrorResponse)
     ),
+    security((), ("api_key" = []), ("bearer" = [])),
     tag = "Data"
 )]
 pub async fn reset_data(
   State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
 ) -> Result<Json<ResetResponse>, Json<ErrorResponse>> {
   let mut deleted = Vec::new();
+  let removed = state.db.count_commands().unwrap_or(0);
 
   // 清空数据库中的命令
   if let Err(e) = state.db.clear_commands() {
@@ -334,6 +789,13 @@ pub async fn reset_data(
     }));
   }
   deleted.push("commands".to_string());
+  audit::record(
+    &state.db,
+    &auth::origin(client, user.as_deref()),
+    Action::Reset,
+    removed,
+    "",
+  );
 
   // 清空元数据
   let empty_meta = crate::storage::Metadata {
@@ -341,6 +803,8 @@ pub async fn reset_data(
     command_count: 0,
     last_update: "never".to_string(),
     languages: vec![],
+    source: None,
+    selected_languages: None,
   };
   if let Err(e) = state.db.save_metadata(&empty_meta) {
     return Err(Json(ErrorResponse {
@@ -364,3 +828,106 @@ pub async fn reset_data(
     deleted,
   }))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{SourceInfo, COMMAND_SCHEMA};
+
+  fn command(name: &str, category: &str, learned_at: Option<&str>) -> Command {
+    Command {
+      name: name.to_string(),
+      description: String::new(),
+      category: category.to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: Vec::new(),
+      content: String::new(),
+      sections: Vec::new(),
+      options: Vec::new(),
+      source_info: learned_at.map(|learned_at| SourceInfo {
+        source: "--help".to_string(),
+        learned_at: learned_at.to_string(),
+        ..Default::default()
+      }),
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_get_command_notes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = crate::api::tests::state(temp_dir.path());
+    state
+      .db
+      .save_command(&command("tar", "common", None))
+      .unwrap();
+    state.db.set_note("tar", "shared note").unwrap();
+    let get = |user: Option<&str>, merged: bool| {
+      let user = user.map(|name| {
+        Extension(CurrentUser {
+          name: name.to_string(),
+        })
+      });
+      get_command(
+        State(state.clone()),
+        user,
+        Path("tar".to_string()),
+        Query(CommandQuery {
+          lang: Some("en".to_string()),
+          merged,
+        }),
+      )
+    };
+
+    for merged in [false, true] {
+      let Json(cmd) = get(None, merged).await.unwrap();
+      assert_eq!(cmd.notes.as_deref(), Some("shared note"));
+    }
+
+    // 多用户模式下只看到自己的笔记
+    let alice = state.users.get("alice", &state.config()).unwrap();
+    alice.db.set_note("tar", "alice's note").unwrap();
+    for merged in [false, true] {
+      let Json(cmd) = get(Some("alice"), merged).await.unwrap();
+      assert_eq!(cmd.notes.as_deref(), Some("alice's note"));
+      let Json(cmd) = get(Some("bob"), merged).await.unwrap();
+      assert!(cmd.notes.is_none());
+    }
+  }
+
+  fn names(commands: &[Command]) -> Vec<&str> {
+    commands.iter().map(|c| c.name.as_str()).collect()
+  }
+
+  #[test]
+  fn test_sort_and_paginate() {
+    let mut commands = vec![
+      command("tar", "common", None),
+      command("apt", "linux", Some("2024-01-01T00:00:00Z")),
+      command("git", "common", None),
+      command("brew", "osx", Some("2024-06-01T00:00:00Z")),
+    ];
+
+    sort_commands(&mut commands, "name");
+    assert_eq!(names(&commands), ["apt", "brew", "git", "tar"]);
+    sort_commands(&mut commands, "category");
+    assert_eq!(names(&commands), ["git", "tar", "apt", "brew"]);
+    sort_commands(&mut commands, "recently-updated");
+    assert_eq!(names(&commands), ["brew", "apt", "git", "tar"]);
+
+    let list = paginate(commands.clone(), 2, 3);
+    assert_eq!(
+      (list.total, list.page, list.per_page, list.pages),
+      (4, 2, 3, 2)
+    );
+    assert_eq!(names(&list.commands), ["tar"]);
+
+    // 超出范围的页为空，page 和 per_page 至少为 1
+    assert!(paginate(commands.clone(), 5, 3).commands.is_empty());
+    let list = paginate(commands, 0, 0);
+    assert_eq!((list.page, list.per_page, list.pages), (1, 1, 4));
+    assert_eq!(names(&list.commands), ["brew"]);
+  }
+}
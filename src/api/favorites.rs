@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::auth::{self, CurrentUser};
+use crate::storage::Favorite;
+use crate::AppState;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+  /// Error message
+  pub error: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FavoriteRequest {
+  /// Command name
+  pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FavoriteResponse {
+  /// Command name
+  pub name: String,
+  /// Whether the favorites changed (false if it already was, or was not, a favorite)
+  pub changed: bool,
+  /// Status message
+  pub message: String,
+}
+
+/// List favorite commands, sorted by name
+#[utoipa::path(
+    get,
+    path = "/api/favorites",
+    responses(
+        (status = 200, description = "Favorite commands", body = Vec<Favorite>),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Favorites"
+)]
+pub async fn list(
+  State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
+) -> Result<Json<Vec<Favorite>>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+  // 多用户模式下每个用户有自己的收藏
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+  db.list_favorites()
+    .map(Json)
+    .map_err(|e| to_error(e.to_string()))
+}
+
+/// Add a command to the favorites
+#[utoipa::path(
+    post,
+    path = "/api/favorites",
+    request_body = FavoriteRequest,
+    responses(
+        (status = 200, description = "Command added", body = FavoriteResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 404, description = "Command not found", body = ErrorResponse)
+    ),
+    security((), ("api_key" = []), ("bearer" = [])),
+    tag = "Favorites"
+)]
+pub async fn add(
+  State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
+  Json(request): Json<FavoriteRequest>,
+) -> Result<Json<FavoriteResponse>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+
+  // 共享数据或用户自己学习的命令都可以收藏
+  let name = request.name;
+  let mut known = !state
+    .db
+    .command_langs(&name)
+    .map_err(|e| to_error(e.to_string()))?
+    .is_empty();
+  if let (false, Some(data)) = (known, &user_data) {
+    known = !data
+      .db
+      .command_langs(&name)
+      .map_err(|e| to_error(e.to_string()))?
+      .is_empty();
+  }
+  if !known {
+    return Err(to_error(format!("Command '{}' not found", name)));
+  }
+
+  let changed = db
+    .add_favorite(&name)
+    .map_err(|e| to_error(e.to_string()))?;
+  Ok(Json(FavoriteResponse {
+    message: if changed {
+      format!("Added '{}' to favorites", name)
+    } else {
+      format!("'{}' is already a favorite", name)
+    },
+    name,
+    changed,
+  }))
+}
+
+/// Remove a command from the favorites
+#[utoipa::path(
+    delete,
+    path = "/api/favorites/{name}",
+    params(
+        ("name" = String, Path, description = "Command name")
+    ),
+    responses(
+        (status = 200, description = "Command removed", body = FavoriteResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    security((), ("api_key" = []), ("bearer" = [])),
+    tag = "Favorites"
+)]
+pub async fn remove(
+  State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
+  Path(name): Path<String>,
+) -> Result<Json<FavoriteResponse>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+
+  let changed = db
+    .remove_favorite(&name)
+    .map_err(|e| to_error(e.to_string()))?;
+  Ok(Json(FavoriteResponse {
+    message: if changed {
+      format!("Removed '{}' from favorites", name)
+    } else {
+      format!("'{}' is not a favorite", name)
+    },
+    name,
+    changed,
+  }))
+}
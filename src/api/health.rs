@@ -0,0 +1,222 @@
+//! 健康检查
+//!
+//! GET /api/health 总是返回 200（只要服务还能响应），正文列出各项检查的结果，供监控查看；
+//! GET /api/health/ready 在数据库不可读或索引格式版本不一致时返回 503，供负载均衡和编排系统判断是否转发流量。
+//! 更新、导入、学习和重建索引时持有索引的写锁，短时间内拿不到读锁说明搜索要等它们完成，
+//! 此时为 degraded：仍然就绪，只是响应变慢。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::AppState;
+
+/// 各项检查的结果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthResponse {
+  /// `ok` when every check passes, `degraded` while searches wait for an update, import or rebuild
+  /// of the index, `unavailable` when the database or the index cannot serve requests
+  pub status: String,
+  /// rtfm version
+  pub version: String,
+  /// Seconds since the server started
+  pub uptime_secs: u64,
+  /// Database check
+  pub database: DatabaseHealth,
+  /// Search index check
+  pub index: IndexHealth,
+  /// Disk space of the data directory (absent when it cannot be determined)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub disk: Option<DiskHealth>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DatabaseHealth {
+  /// Whether the database can be read
+  pub ok: bool,
+  /// Number of commands in the database
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub commands: Option<usize>,
+  /// Version of the downloaded tldr data
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub data_version: Option<String>,
+  /// Why the database cannot be read
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IndexHealth {
+  /// Whether the index can serve searches
+  pub ok: bool,
+  /// Number of documents visible to the index reader
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub documents: Option<u64>,
+  /// Why the index cannot serve searches
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiskHealth {
+  /// Bytes available to the server on the data directory's file system
+  pub available_bytes: u64,
+  /// Total bytes of that file system
+  pub total_bytes: u64,
+}
+
+/// 等待索引读锁的时间；学习单个命令等短暂的写入在此之内完成
+const INDEX_LOCK_TIMEOUT: Duration = Duration::from_millis(500);
+
+impl HealthResponse {
+  fn ready(&self) -> bool {
+    self.status != "unavailable"
+  }
+}
+
+/// 运行各项检查
+async fn check(state: &AppState) -> HealthResponse {
+  let database = match (state.db.count_commands(), state.db.get_metadata()) {
+    (Ok(commands), Ok(metadata)) => DatabaseHealth {
+      ok: true,
+      commands: Some(commands),
+      data_version: metadata.map(|m| m.version),
+      error: None,
+    },
+    (Err(e), _) | (_, Err(e)) => DatabaseHealth {
+      ok: false,
+      commands: None,
+      data_version: None,
+      error: Some(e.to_string()),
+    },
+  };
+
+  let mut busy = false;
+  let index = match tokio::time::timeout(INDEX_LOCK_TIMEOUT, state.search.read()).await {
+    Ok(search) if search.is_current() => IndexHealth {
+      ok: true,
+      documents: Some(search.num_docs()),
+      error: None,
+    },
+    Ok(_) => IndexHealth {
+      ok: false,
+      documents: None,
+      error: Some("Index format is outdated".to_string()),
+    },
+    Err(_) => {
+      busy = true;
+      IndexHealth {
+        ok: false,
+        documents: None,
+        error: Some("Index is busy with an update, import or rebuild".to_string()),
+      }
+    }
+  };
+
+  let disk = fs4::statvfs(&state.data_dir).ok().map(|stats| DiskHealth {
+    available_bytes: stats.available_space(),
+    total_bytes: stats.total_space(),
+  });
+
+  let status = if !database.ok || !(index.ok || busy) {
+    "unavailable"
+  } else if busy {
+    "degraded"
+  } else {
+    "ok"
+  };
+  HealthResponse {
+    status: status.to_string(),
+    version: env!("CARGO_PKG_VERSION").to_string(),
+    uptime_secs: state.started.elapsed().as_secs(),
+    database,
+    index,
+    disk,
+  }
+}
+
+/// Report the state of the database, the search index and the data directory
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    responses(
+        (status = 200, description = "The server is running; see status for readiness", body = HealthResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+  Json(check(&state).await)
+}
+
+/// Readiness probe: 503 while the database or the search index cannot serve requests
+#[utoipa::path(
+    get,
+    path = "/api/health/ready",
+    responses(
+        (status = 200, description = "Ready to serve requests", body = HealthResponse),
+        (status = 503, description = "The database or the search index is unavailable", body = HealthResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn ready(State(state): State<Arc<AppState>>) -> (StatusCode, Json<HealthResponse>) {
+  let response = check(&state).await;
+  let status = if response.ready() {
+    StatusCode::OK
+  } else {
+    StatusCode::SERVICE_UNAVAILABLE
+  };
+  (status, Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::tests::state;
+
+  #[tokio::test]
+  async fn test_ready() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    let (status, Json(response)) = ready(State(state)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response.status, "ok");
+    assert_eq!(response.database.commands, Some(0));
+    assert_eq!(response.index.documents, Some(0));
+  }
+
+  #[tokio::test]
+  async fn test_degraded() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    // 模拟正在导入：写锁一直被持有
+    let _writer = state.search.write().await;
+    let (status, Json(response)) = ready(State(state.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response.status, "degraded");
+    assert!(response.database.ok);
+    assert!(!response.index.ok);
+  }
+
+  #[tokio::test]
+  async fn test_unavailable() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    // 没有以当前格式提交过的索引视为旧格式
+    drop(state(temp_dir.path()));
+    let state = state(temp_dir.path());
+    let (status, Json(response)) = ready(State(state.clone())).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.status, "unavailable");
+    assert_eq!(
+      response.index.error.as_deref(),
+      Some("Index format is outdated")
+    );
+
+    let Json(response) = health(State(state)).await;
+    assert_eq!(response.status, "unavailable");
+  }
+}
@@ -1,12 +1,17 @@
 //! Learn API - capture and index command help
 
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::extract::{Query, State};
-use axum::Json;
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+use super::auth::{self, CurrentUser};
+use crate::audit::{self, Action};
 use crate::learn;
 use crate::AppState;
 
@@ -20,6 +25,11 @@ pub struct LearnQuery {
   /// Prefer man page over --help
   #[serde(default)]
   pub man: bool,
+  /// Learn from a documentation page or raw README URL instead of local help (needs server.allow_learn_url)
+  pub url: Option<String>,
+  /// Generate tldr-style examples with the configured LLM endpoint (needs learn.llm.enabled or server.allow_learn_llm)
+  #[serde(default)]
+  pub llm: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -30,6 +40,8 @@ pub struct LearnResponse {
   pub command: String,
   /// Source of help content (--help or man)
   pub source: String,
+  /// Parse quality score (0-100, below 50 is low quality)
+  pub quality: u8,
   /// Status message
   pub message: String,
 }
@@ -47,23 +59,57 @@ pub struct ErrorResponse {
     params(LearnQuery),
     responses(
         (status = 200, description = "Learn result", body = LearnResponse),
-        (status = 400, description = "Failed to learn command", body = ErrorResponse)
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 400, description = "Failed to learn command", body = ErrorResponse),
+        (status = 403, description = "URL or LLM learning is disabled on this server", body = ErrorResponse),
+        (status = 500, description = "Storage error", body = ErrorResponse)
     ),
+    security((), ("api_key" = []), ("bearer" = [])),
     tag = "Learn"
 )]
 pub async fn learn_command(
   State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
   Query(params): Query<LearnQuery>,
-) -> Result<Json<LearnResponse>, Json<ErrorResponse>> {
+) -> Result<Json<LearnResponse>, (StatusCode, Json<ErrorResponse>)> {
   let command = &params.command;
+  let fail = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+  let config = state.config();
+
+  // The server would fetch any address a client gives, including internal hosts
+  if params.url.is_some() && !config.server.allow_learn_url {
+    return Err(fail(
+      StatusCode::FORBIDDEN,
+      "Learning from a URL is disabled on this server (server.allow_learn_url)".to_string(),
+    ));
+  }
+  // The LLM call sends help text to learn.llm.endpoint with the server's API key
+  let llm = config.learn.llm.enabled || (params.llm && config.server.allow_learn_llm);
+  if params.llm && !llm {
+    return Err(fail(
+      StatusCode::FORBIDDEN,
+      "LLM summarization is disabled on this server (learn.llm.enabled, server.allow_learn_llm)"
+        .to_string(),
+    ));
+  }
+
+  // In multi-user mode learned commands go to the user's own namespace
+  let user_data = auth::user_data(&state, user.as_deref())
+    .map_err(|error| fail(StatusCode::INTERNAL_SERVER_ERROR, error))?;
+  let (db, search) = match &user_data {
+    Some(data) => (&data.db, &data.search),
+    None => (&state.db, &state.search),
+  };
 
   // Check if already exists
   if !params.force {
-    if let Ok(Some(_)) = state.db.get_command(command, "local") {
+    if let Ok(Some(_)) = db.get_command(command, "local") {
       return Ok(Json(LearnResponse {
         success: false,
         command: command.clone(),
         source: "".to_string(),
+        quality: 0,
         message: format!(
           "Command '{}' already learned. Use force=true to re-learn.",
           command
@@ -72,41 +118,76 @@ pub async fn learn_command(
     }
   }
 
-  // Get help content
-  let (content, source) = if params.man {
-    learn::get_man_page(command).or_else(|_| learn::get_help_output(command))
+  let (mut cmd, source) = if let Some(url) = &params.url {
+    // Fetch and parse remote documentation
+    let (cmd, _) = learn::from_url(command, url, &config.update.user_agent)
+      .await
+      .map_err(|e| {
+        fail(
+          StatusCode::BAD_REQUEST,
+          format!("Failed to learn '{}' from {}: {}", command, url, e),
+        )
+      })?;
+    (cmd, format!("url: {}", url))
   } else {
-    learn::get_help_output(command).or_else(|_| learn::get_man_page(command))
-  }
-  .map_err(|e| {
-    Json(ErrorResponse {
-      error: format!("Failed to get help for '{}': {}", command, e),
-    })
-  })?;
+    // Get help content
+    let (content, source) =
+      learn::get_local_help(command, params.man, &config.learn).map_err(|(e, _)| {
+        fail(
+          StatusCode::BAD_REQUEST,
+          format!("Failed to get help for '{}': {}", command, e),
+        )
+      })?;
+
+    // Parse help content and record the local binary version
+    let plugins = crate::plugin::Registry::load(&config);
+    let (mut cmd, plugin_failure) = plugins.parse_help_content(command, &content, &source);
+    if let Some(failure) = plugin_failure {
+      tracing::warn!("{}", failure);
+    }
+    learn::record_binary(&mut cmd);
+    (cmd, source)
+  };
 
-  // Parse help content
-  let cmd = learn::parse_help_content(command, &content, &source);
+  // Optional LLM summarization; failures keep the heuristic result
+  let mut message = format!("Learned '{}' successfully", command);
+  if llm {
+    match learn::llm::enhance(&mut cmd, &config.learn.llm).await {
+      Ok(count) => message.push_str(&format!(" ({} AI-generated examples)", count)),
+      Err(e) => message.push_str(&format!(" (LLM summarization failed: {})", e)),
+    }
+  }
 
   // Save to database
-  state.db.save_command(&cmd).map_err(|e| {
-    Json(ErrorResponse {
-      error: format!("Failed to save command: {}", e),
-    })
+  db.save_command(&cmd).map_err(|e| {
+    fail(
+      StatusCode::INTERNAL_SERVER_ERROR,
+      format!("Failed to save command: {}", e),
+    )
   })?;
+  audit::record(
+    &state.db,
+    &auth::origin(client, user.as_deref()),
+    Action::Learn,
+    1,
+    command,
+  );
 
   // Index for search
-  let mut search = state.search.write().await;
-  search.index_single_command(&cmd).map_err(|e| {
-    Json(ErrorResponse {
-      error: format!("Failed to index command: {}", e),
-    })
+  let mut search = search.write().await;
+  crate::search::index_commands(db, &mut search, std::slice::from_ref(&cmd)).map_err(|e| {
+    fail(
+      StatusCode::INTERNAL_SERVER_ERROR,
+      format!("Failed to index command: {}", e),
+    )
   })?;
 
   Ok(Json(LearnResponse {
     success: true,
     command: command.clone(),
     source,
-    message: format!("Learned '{}' successfully", command),
+    quality: learn::quality::assess(&cmd).score,
+    message,
   }))
 }
 
@@ -123,7 +204,7 @@ pub struct LearnAllQuery {
   pub skip_existing: bool,
   /// Filter by prefix
   pub prefix: Option<String>,
-  /// Source type: "man" (Linux/macOS), "powershell" (Windows), "path" (all platforms), "auto" (default)
+  /// Source type: "man" (Linux/macOS), "manpath" (parse MANPATH sources directly), "powershell" (Windows), "path" (all platforms), "builtin" (shell builtins), "auto" (default)
   #[serde(default = "default_source")]
   pub source: String,
 }
@@ -148,6 +229,10 @@ pub struct LearnAllResponse {
   pub skipped: usize,
   /// Failed count
   pub failed: usize,
+  /// Commands skipped because they ignore --help, grouped by reason (interactive, GUI)
+  pub unlearnable: BTreeMap<String, Vec<String>>,
+  /// Learned entries whose parse quality is low
+  pub low_quality: usize,
   /// Status message
   pub message: String,
 }
@@ -159,14 +244,26 @@ pub struct LearnAllResponse {
     params(LearnAllQuery),
     responses(
         (status = 200, description = "Batch learn result", body = LearnAllResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
         (status = 400, description = "Failed to learn", body = ErrorResponse)
     ),
+    security((), ("api_key" = []), ("bearer" = [])),
     tag = "Learn"
 )]
 pub async fn learn_all(
   State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
   Query(params): Query<LearnAllQuery>,
 ) -> Result<Json<LearnAllResponse>, Json<ErrorResponse>> {
+  // In multi-user mode learned commands go to the user's own namespace
+  let user_data =
+    auth::user_data(&state, user.as_deref()).map_err(|error| Json(ErrorResponse { error }))?;
+  let (db, search) = match &user_data {
+    Some(data) => (&data.db, &data.search),
+    None => (&state.db, &state.search),
+  };
+
   // Determine actual source based on platform
   let actual_source = if params.source == "auto" {
     #[cfg(target_os = "windows")]
@@ -175,7 +272,7 @@ pub async fn learn_all(
     }
     #[cfg(not(target_os = "windows"))]
     {
-      "man"
+      learn::default_man_source()
     }
   } else {
     params.source.as_str()
@@ -188,15 +285,18 @@ pub async fn learn_all(
         error: format!("Failed to list man pages: {}", e),
       })
     })?,
-    "powershell" | "path" => learn::list_available_commands(actual_source).map_err(|e| {
-      Json(ErrorResponse {
-        error: format!("Failed to list commands: {}", e),
-      })
-    })?,
+    "manpath" => learn::roff::list_pages(&params.section),
+    "powershell" | "path" | "builtin" => {
+      learn::list_available_commands(actual_source).map_err(|e| {
+        Json(ErrorResponse {
+          error: format!("Failed to list commands: {}", e),
+        })
+      })?
+    }
     _ => {
       return Err(Json(ErrorResponse {
         error: format!(
-          "Unknown source '{}'. Use 'man', 'powershell', 'path', or 'auto'.",
+          "Unknown source '{}'. Use 'man', 'manpath', 'powershell', 'path', 'builtin', or 'auto'.",
           params.source
         ),
       }))
@@ -210,6 +310,8 @@ pub async fn learn_all(
       learned: 0,
       skipped: 0,
       failed: 0,
+      unlearnable: BTreeMap::new(),
+      low_quality: 0,
       message: format!("No commands found for source '{}'", actual_source),
     }));
   }
@@ -226,6 +328,12 @@ pub async fn learn_all(
     })
     .collect();
 
+  // Apply the configured allow/deny lists
+  let pages: Vec<_> = pages
+    .into_iter()
+    .filter(|(name, _)| learn::is_learnable(name, &state.config().learn))
+    .collect();
+
   // Limit
   let pages: Vec<_> = if params.limit > 0 && pages.len() > params.limit {
     pages.into_iter().take(params.limit).collect()
@@ -237,13 +345,16 @@ pub async fn learn_all(
   let mut learned = 0;
   let mut skipped = 0;
   let mut failed = 0;
+  let mut low_quality = 0;
+  let mut unlearnable: BTreeMap<String, Vec<String>> = BTreeMap::new();
 
-  let mut search = state.search.write().await;
+  let plugins = crate::plugin::Registry::load(&state.config());
+  let mut search = search.write().await;
 
   for (name, _) in pages {
     // Skip existing
     if params.skip_existing {
-      if let Ok(Some(_)) = state.db.get_command(&name, "local") {
+      if let Ok(Some(_)) = db.get_command(&name, "local") {
         skipped += 1;
         continue;
       }
@@ -252,28 +363,55 @@ pub async fn learn_all(
     // Get help content based on source
     let result = match actual_source {
       "man" => learn::get_man_page_with_section(&name, &params.section),
-      _ => learn::get_help_output(&name),
+      "manpath" => learn::roff::get_page(&name, Some(&params.section)),
+      "builtin" => learn::get_builtin_help(&name),
+      _ => learn::get_help_output(&name, &state.config().learn),
     };
 
     match result {
       Ok((content, source)) => {
-        let cmd = learn::parse_help_content(&name, &content, &source);
-        if state.db.save_command(&cmd).is_ok() && search.index_single_command(&cmd).is_ok() {
+        let (mut cmd, plugin_failure) = plugins.parse_help_content(&name, &content, &source);
+        if let Some(failure) = plugin_failure {
+          tracing::warn!("{}", failure);
+        }
+        learn::record_binary(&mut cmd);
+        if db.save_command(&cmd).is_ok()
+          && crate::search::index_commands(db, &mut search, std::slice::from_ref(&cmd)).is_ok()
+        {
           learned += 1;
+          if learn::quality::assess(&cmd).is_low() {
+            low_quality += 1;
+          }
         }
       }
-      Err(_) => {
-        failed += 1;
-      }
+      Err(e) => match e.downcast_ref::<learn::Unlearnable>() {
+        Some(reason) => unlearnable
+          .entry(reason.reason().to_string())
+          .or_default()
+          .push(name),
+        None => failed += 1,
+      },
     }
   }
 
+  if learned > 0 {
+    audit::record(
+      &state.db,
+      &auth::origin(client, user.as_deref()),
+      Action::Learn,
+      learned,
+      format!("learn-all ({})", actual_source),
+    );
+  }
+
   Ok(Json(LearnAllResponse {
     success: true,
     total,
     learned,
     skipped,
     failed,
+    unlearnable,
+    low_quality,
     message: format!(
       "Learned {} commands from source '{}'",
       learned, actual_source
@@ -309,8 +447,8 @@ pub async fn backup_info(
   State(state): State<Arc<AppState>>,
 ) -> Result<Json<BackupInfo>, Json<ErrorResponse>> {
   let data_dir = &state.data_dir;
-  let db_path = data_dir.join(&state.config.storage.db_filename);
-  let index_path = data_dir.join(&state.config.storage.index_dirname);
+  let db_path = data_dir.join(&state.config().storage.db_filename);
+  let index_path = data_dir.join(&state.config().storage.index_dirname);
   let config_path = data_dir.join("config.toml");
 
   let db_size = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
@@ -346,3 +484,78 @@ fn walkdir_size(path: &std::path::Path) -> std::io::Result<u64> {
   }
   Ok(size)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::tests::state;
+  use crate::search::Platform;
+
+  fn query(command: &str) -> Query<LearnQuery> {
+    Query(LearnQuery {
+      command: command.to_string(),
+      force: false,
+      man: false,
+      url: None,
+      llm: false,
+    })
+  }
+
+  #[tokio::test]
+  async fn test_learn_url_disabled() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    // 默认不下载客户端给出的地址
+    let Query(mut params) = query("tool");
+    params.url = Some("http://169.254.169.254/latest/meta-data".to_string());
+    let (status, Json(error)) = learn_command(State(state.clone()), None, None, Query(params))
+      .await
+      .unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(error.error.contains("server.allow_learn_url"));
+    assert!(state.db.get_command("tool", "local").unwrap().is_none());
+  }
+
+  #[tokio::test]
+  async fn test_learn_llm_disabled() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    // 客户端不能让服务器用自己的 API 密钥调用 LLM
+    let Query(mut params) = query("ls");
+    params.llm = true;
+    let (status, Json(error)) = learn_command(State(state.clone()), None, None, Query(params))
+      .await
+      .unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(error.error.contains("learn.llm.enabled"));
+    assert!(state.db.get_command("ls", "local").unwrap().is_none());
+  }
+
+  #[tokio::test]
+  async fn test_learn_into_old_index() {
+    // 上一版本建立的索引：没有 key 等字段
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut builder = tantivy::schema::Schema::builder();
+    for name in ["name", "description", "content", "category", "lang"] {
+      builder.add_text_field(name, tantivy::schema::TEXT | tantivy::schema::STORED);
+    }
+    let index_dir = temp_dir.path().join("index");
+    std::fs::create_dir_all(&index_dir).unwrap();
+    tantivy::Index::create_in_dir(&index_dir, builder.build()).unwrap();
+    let state = state(temp_dir.path());
+    assert!(!state.search.read().await.is_current());
+
+    let Json(response) = learn_command(State(state.clone()), None, None, query("ls"))
+      .await
+      .unwrap();
+    assert!(response.success);
+    // 学习时从数据库重建索引，而不是写入旧索引
+    let search = state.search.read().await;
+    assert!(search.is_current());
+    let results = search
+      .search("ls", Some("local"), &Platform::All, 10)
+      .unwrap()
+      .results;
+    assert_eq!(results[0].name, "ls");
+  }
+}
@@ -0,0 +1,200 @@
+//! API 的限流与请求超时
+//!
+//! server.rate_limit_per_minute 大于 0 时按客户端 IP 限流：每个 IP（IPv6 按 /64 网段）一个令牌桶，
+//! 每分钟补充该数量的令牌，最多攒满一分钟的量，没有令牌时返回 429。server.request_timeout_secs 大于 0 时，
+//! 超过该时间仍未返回响应的请求以 408 结束。两项都在每个请求时读取当前配置（支持热重载）。
+
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use lru::LruCache;
+use parking_lot::Mutex;
+
+use crate::AppState;
+
+/// 不限流的路径前缀（健康检查与 Swagger UI 的静态文件）
+const UNLIMITED: &[&str] = &["/api/health", "/swagger-ui"];
+
+/// 不受超时限制的接口：上传大文件或同步执行耗时任务，中途取消会留下不完整的数据
+const UNTIMED: &[&str] = &[
+  "/api/update/download",
+  "/api/import/file",
+  "/api/restore",
+  "/api/learn-all",
+  "/api/sync/push",
+];
+
+/// 最多保留的令牌桶数量，超过时丢弃最久没有请求的客户端的桶
+const MAX_BUCKETS: usize = 16384;
+
+struct Bucket {
+  tokens: f64,
+  updated: Instant,
+}
+
+/// 各客户端的令牌桶
+pub struct RateLimiter {
+  buckets: Mutex<LruCache<IpAddr, Bucket>>,
+}
+
+impl Default for RateLimiter {
+  fn default() -> Self {
+    Self {
+      buckets: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_BUCKETS).unwrap())),
+    }
+  }
+}
+
+/// 限流的单位：IPv4 地址，IPv6 按 /64 网段（一个客户端通常拥有整个网段，可以随意换地址）
+fn client_key(ip: IpAddr) -> IpAddr {
+  match ip.to_canonical() {
+    IpAddr::V6(v6) => IpAddr::V6(Ipv6Addr::from(u128::from(v6) & !u128::from(u64::MAX))),
+    v4 => v4,
+  }
+}
+
+impl RateLimiter {
+  /// 为 ip 取一个令牌；没有令牌时返回下一个令牌补充前需要等待的时间
+  pub fn acquire(&self, ip: IpAddr, per_minute: u32, now: Instant) -> Result<(), Duration> {
+    let capacity = f64::from(per_minute);
+    let per_sec = capacity / 60.0;
+    let mut buckets = self.buckets.lock();
+    let bucket = buckets.get_or_insert_mut(client_key(ip), || Bucket {
+      tokens: capacity,
+      updated: now,
+    });
+    let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+    // 配置调低后桶中的令牌不超过新的容量
+    bucket.tokens = (bucket.tokens + elapsed * per_sec).min(capacity);
+    bucket.updated = now;
+    if bucket.tokens >= 1.0 {
+      bucket.tokens -= 1.0;
+      Ok(())
+    } else {
+      Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_sec))
+    }
+  }
+}
+
+fn reject(status: StatusCode, error: String) -> Response {
+  (status, Json(super::search::ErrorResponse { error })).into_response()
+}
+
+/// 按客户端 IP 限流；没有连接信息时（如测试中直接调用路由）不限流
+pub async fn rate_limit(
+  State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  request: Request,
+  next: Next,
+) -> Response {
+  let per_minute = state.config().server.rate_limit_per_minute;
+  let path = request.uri().path();
+  let unlimited = per_minute == 0 || UNLIMITED.iter().any(|prefix| path.starts_with(prefix));
+  let Some(ConnectInfo(addr)) = client.filter(|_| !unlimited) else {
+    return next.run(request).await;
+  };
+
+  match state.limiter.acquire(addr.ip(), per_minute, Instant::now()) {
+    Ok(()) => next.run(request).await,
+    Err(wait) => {
+      tracing::debug!("Rate limit exceeded for {}", addr.ip());
+      let mut response = reject(
+        StatusCode::TOO_MANY_REQUESTS,
+        format!(
+          "Rate limit exceeded ({} requests per minute), retry later",
+          per_minute
+        ),
+      );
+      let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+      response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, retry_after.into());
+      response
+    }
+  }
+}
+
+/// 超过 server.request_timeout_secs 仍未返回响应的请求返回 408
+/// 只限制产生响应头的时间，/api/update/progress 等流式响应的内容不受影响
+pub async fn timeout(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+  let secs = state.config().server.request_timeout_secs;
+  if secs == 0 || UNTIMED.contains(&request.uri().path()) {
+    return next.run(request).await;
+  }
+
+  let path = request.uri().path().to_string();
+  match tokio::time::timeout(Duration::from_secs(secs), next.run(request)).await {
+    Ok(response) => response,
+    Err(_) => {
+      tracing::warn!("Request to {} timed out after {}s", path, secs);
+      reject(
+        StatusCode::REQUEST_TIMEOUT,
+        format!("Request timed out after {} seconds", secs),
+      )
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rate_limiter() {
+    let limiter = RateLimiter::default();
+    let ip: IpAddr = "192.0.2.1".parse().unwrap();
+    let other: IpAddr = "192.0.2.2".parse().unwrap();
+    let start = Instant::now();
+
+    for _ in 0..60 {
+      assert!(limiter.acquire(ip, 60, start).is_ok());
+    }
+    // 每分钟 60 个令牌，每秒补充一个
+    let wait = limiter.acquire(ip, 60, start).unwrap_err();
+    assert_eq!(wait, Duration::from_secs(1));
+    // 各 IP 单独计算
+    assert!(limiter.acquire(other, 60, start).is_ok());
+
+    assert!(limiter
+      .acquire(ip, 60, start + Duration::from_millis(500))
+      .is_err());
+    assert!(limiter
+      .acquire(ip, 60, start + Duration::from_millis(1500))
+      .is_ok());
+  }
+
+  #[test]
+  fn test_rate_limiter_ipv6_and_capacity() {
+    let limiter = RateLimiter::default();
+    let start = Instant::now();
+    // 同一 /64 网段中换地址不会得到新的令牌
+    let a: IpAddr = "2001:db8::1".parse().unwrap();
+    let b: IpAddr = "2001:db8::ffff:1234".parse().unwrap();
+    assert!(limiter.acquire(a, 1, start).is_ok());
+    assert!(limiter.acquire(b, 1, start).is_err());
+    assert!(limiter
+      .acquire("2001:db8:0:1::1".parse().unwrap(), 1, start)
+      .is_ok());
+    // IPv4 映射地址与 IPv4 地址相同
+    assert!(limiter
+      .acquire("192.0.2.1".parse().unwrap(), 1, start)
+      .is_ok());
+    assert!(limiter
+      .acquire("::ffff:192.0.2.1".parse().unwrap(), 1, start)
+      .is_err());
+
+    // 令牌桶数量有上限
+    for i in 0..MAX_BUCKETS as u128 + 10 {
+      let ip = IpAddr::V6(Ipv6Addr::from((0x2001_0db8_u128 << 96) | (i << 64)));
+      let _ = limiter.acquire(ip, 1, start);
+    }
+    assert_eq!(limiter.buckets.lock().len(), MAX_BUCKETS);
+  }
+}
@@ -1,18 +1,40 @@
+mod audit;
+mod auth;
+mod cheatsheet;
 mod data;
+mod favorites;
+mod health;
 mod learn;
+mod limit;
+mod reload;
+mod scheduler;
 mod search;
+mod stats;
+mod sync;
 mod update;
+mod ws;
 
 use std::sync::Arc;
 
 use axum::extract::DefaultBodyLimit;
-use axum::routing::{get, post};
-use axum::Router;
-use utoipa::OpenApi;
+use axum::http::{HeaderName, Method, StatusCode};
+use axum::routing::{delete, get, on, post, MethodFilter};
+use axum::{Json, Router};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
 
+use crate::config::ServerConfig;
 use crate::AppState;
 
+pub use auth::authenticate;
+pub use data::spawn_import_watcher;
+pub use limit::{rate_limit, timeout, RateLimiter};
+pub use reload::spawn_config_watcher;
+pub use scheduler::{spawn_scheduler, JobHistory};
+pub use update::{spawn_auto_update, UpdateTracker};
+
 /// OpenAPI 文档定义
 #[derive(OpenApi)]
 #[openapi(
@@ -26,29 +48,59 @@ use crate::AppState;
         (url = "/", description = "Current server")
     ),
     paths(
+        health::health,
+        health::ready,
         search::search,
         data::get_command,
+        data::delete_command,
         data::list_commands,
         data::get_metadata,
         data::import_json,
         data::import_file,
         data::reset_data,
+        data::restore_backup,
         update::check_update,
         update::download_update,
+        update::progress,
+        update::history,
         learn::learn_command,
         learn::learn_all,
         learn::backup_info,
+        sync::manifest,
+        sync::fetch,
+        sync::personal,
+        sync::push,
+        stats::summary,
+        stats::usage,
+        scheduler::status,
+        audit::list,
+        favorites::list,
+        favorites::add,
+        favorites::remove,
     ),
     components(schemas(
+        health::HealthResponse,
+        health::DatabaseHealth,
+        health::IndexHealth,
+        health::DiskHealth,
         crate::storage::Command,
         crate::storage::Example,
+        crate::storage::Section,
+        crate::storage::OptionEntry,
+        crate::storage::SourceInfo,
         crate::storage::Metadata,
+        crate::storage::UpdateRun,
         crate::search::SearchResult,
+        crate::search::ExampleMatch,
         crate::search::SearchResponse,
         search::ErrorResponse,
         data::ErrorResponse,
+        data::MetadataResponse,
         data::ImportResponse,
+        data::CommandList,
+        data::DeleteResponse,
         data::ResetResponse,
+        data::RestoreResponse,
         data::FileUpload,
         update::UpdateInfo,
         update::UpdateProgress,
@@ -57,56 +109,339 @@ use crate::AppState;
         learn::LearnAllResponse,
         learn::BackupInfo,
         learn::ErrorResponse,
+        crate::sync::SyncEntry,
+        crate::sync::SyncKey,
+        crate::sync::SyncPolicy,
+        crate::sync::SyncReport,
+        crate::sync::PersonalData,
+        crate::storage::Note,
+        sync::FetchRequest,
+        sync::PushRequest,
+        sync::ErrorResponse,
+        crate::usage::UsageEntry,
+        crate::usage::LearningGap,
+        crate::usage::UsageReport,
+        stats::StatsResponse,
+        stats::ErrorResponse,
+        scheduler::Job,
+        scheduler::JobRun,
+        scheduler::JobStatus,
+        scheduler::SchedulerStatus,
+        crate::storage::AuditEntry,
+        audit::ErrorResponse,
+        crate::storage::Favorite,
+        favorites::FavoriteRequest,
+        favorites::FavoriteResponse,
+        favorites::ErrorResponse,
     )),
     tags(
+        (name = "Health", description = "Liveness and readiness checks"),
         (name = "Search", description = "Full-text search operations"),
         (name = "Commands", description = "Command CRUD operations"),
         (name = "Data", description = "Data import/backup/reset operations"),
         (name = "Update", description = "Update management"),
-        (name = "Learn", description = "Learn commands from system help")
-    )
+        (name = "Learn", description = "Learn commands from system help"),
+        (name = "Sync", description = "Exchange commands with other rtfm instances"),
+        (name = "Stats", description = "Local usage statistics"),
+        (name = "Scheduler", description = "Periodic maintenance jobs"),
+        (name = "Audit", description = "Log of data modifications"),
+        (name = "Favorites", description = "Favorite commands")
+    ),
+    modifiers(&SecuritySchemes)
 )]
 pub struct ApiDoc;
 
-pub fn routes(max_upload_size: usize) -> Router<Arc<AppState>> {
-  Router::new()
-    .route("/health", get(health))
+/// 修改数据的接口在配置了 server.api_keys 时使用的验证方式
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+  fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+    let components = openapi.components.get_or_insert_with(Default::default);
+    components.add_security_scheme(
+      "api_key",
+      SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+        "X-API-Key",
+        "One of server.api_keys or the keys in server.api_key_file",
+      ))),
+    );
+    components.add_security_scheme(
+      "bearer",
+      SecurityScheme::Http(
+        HttpBuilder::new()
+          .scheme(HttpAuthScheme::Bearer)
+          .description(Some(
+            "An API key, or a user token in multi-user mode (server.multi_user)",
+          ))
+          .build(),
+      ),
+    );
+  }
+}
+
+/// 修改数据的接口，serve --read-only 时不注册（相对 /api）
+const MUTATING_ROUTES: &[&str] = &[
+  "/update/download",
+  "/import",
+  "/import/file",
+  "/restore",
+  "/reset",
+  "/learn",
+  "/learn-all",
+  "/sync/push",
+];
+
+/// 用 DELETE 修改数据、或与 GET 接口同一路径的修改接口（相对 /api），只读模式下只去掉这个方法
+const MUTATING_METHODS: &[(&str, MethodFilter)] = &[
+  ("/command/:name", MethodFilter::DELETE),
+  ("/favorites", MethodFilter::POST),
+  ("/favorites/:name", MethodFilter::DELETE),
+];
+
+/// 请求是否调用修改数据的接口；path 为完整路径（含 /api）
+pub(crate) fn is_mutating(method: &Method, path: &str) -> bool {
+  let Some(path) = path.strip_prefix("/api") else {
+    return false;
+  };
+  if method == Method::POST && MUTATING_ROUTES.contains(&path) {
+    return true;
+  }
+  let Ok(filter) = MethodFilter::try_from(method.clone()) else {
+    return false;
+  };
+  MUTATING_METHODS
+    .iter()
+    .any(|(route, allowed)| *allowed == filter && route_matches(route, path))
+}
+
+/// 路径是否匹配路由，`:name` 段匹配任意非空段
+fn route_matches(route: &str, path: &str) -> bool {
+  let route: Vec<&str> = route.split('/').collect();
+  let path: Vec<&str> = path.split('/').collect();
+  route.len() == path.len()
+    && route
+      .iter()
+      .zip(&path)
+      .all(|(r, p)| r == p || (r.starts_with(':') && !p.is_empty()))
+}
+
+/// 请求体大小限制：上传接口各自配置，其余接口使用 max_json_size
+/// read_only: 修改数据的接口一律返回 403
+pub fn routes(server: &ServerConfig, read_only: bool) -> Router<Arc<AppState>> {
+  let router = Router::new()
+    .route("/health", get(health::health))
+    .route("/health/ready", get(health::ready))
     .route("/search", get(search::search))
-    .route("/command/{name}", get(data::get_command))
+    .route("/command/:name", get(data::get_command))
     .route("/commands", get(data::list_commands))
     .route("/metadata", get(data::get_metadata))
     .route("/update/check", get(update::check_update))
+    .route("/update/progress", get(update::progress))
+    .route("/update/history", get(update::history))
+    .route("/backup/info", get(learn::backup_info))
+    // Sync endpoints
+    .route("/sync/manifest", get(sync::manifest))
+    .route("/sync/fetch", post(sync::fetch))
+    .route("/sync/personal", get(sync::personal))
+    // Stats endpoints
+    .route("/stats", get(stats::summary))
+    .route("/stats/usage", get(stats::usage))
+    .route("/scheduler", get(scheduler::status))
+    .route("/audit", get(audit::list))
+    .route("/favorites", get(favorites::list));
+
+  let router = if read_only {
+    let router = MUTATING_ROUTES.iter().fold(router, |router, path| {
+      router.route(path, post(read_only_rejected))
+    });
+    MUTATING_METHODS
+      .iter()
+      .fold(router, |router, (path, method)| {
+        router.route(path, on(*method, read_only_rejected))
+      })
+  } else {
+    router.merge(mutating_routes(server))
+  };
+  router.layer(DefaultBodyLimit::max(server.max_json_size))
+}
+
+fn mutating_routes(server: &ServerConfig) -> Router<Arc<AppState>> {
+  Router::new()
     .route("/update/download", post(update::download_update))
     .route("/import", post(data::import_json))
     .route(
       "/import/file",
-      post(data::import_file).layer(DefaultBodyLimit::max(max_upload_size)),
+      post(data::import_file).layer(DefaultBodyLimit::max(server.max_upload_size)),
+    )
+    .route(
+      "/restore",
+      post(data::restore_backup).layer(DefaultBodyLimit::max(server.max_restore_size)),
     )
     .route("/reset", post(data::reset_data))
+    .route("/command/:name", delete(data::delete_command))
+    .route("/favorites", post(favorites::add))
+    .route("/favorites/:name", delete(favorites::remove))
     // Learn endpoints
     .route("/learn", post(learn::learn_command))
     .route("/learn-all", post(learn::learn_all))
-    .route("/backup/info", get(learn::backup_info))
+    .route(
+      "/sync/push",
+      post(sync::push).layer(DefaultBodyLimit::max(server.max_upload_size)),
+    )
+}
+
+async fn read_only_rejected() -> (StatusCode, Json<search::ErrorResponse>) {
+  (
+    StatusCode::FORBIDDEN,
+    Json(search::ErrorResponse {
+      error: "This server is read-only".to_string(),
+    }),
+  )
 }
 
-/// 创建包含 Swagger UI 的完整路由
-pub fn routes_with_docs(max_upload_size: usize) -> Router<Arc<AppState>> {
-  let api_routes = routes(max_upload_size);
+/// 创建包含 Swagger UI 和速查表网页的完整路由；只读模式下文档中不列出修改数据的接口
+pub fn routes_with_docs(server: &ServerConfig, read_only: bool) -> Router<Arc<AppState>> {
+  let api_routes = routes(server, read_only);
+
+  let mut doc = ApiDoc::openapi();
+  if read_only {
+    doc.paths.paths.retain(|path, _| {
+      !path
+        .strip_prefix("/api")
+        .is_some_and(|path| MUTATING_ROUTES.contains(&path))
+    });
+    for (path, method) in MUTATING_METHODS {
+      let path = format!("/api{}", path.replace(":name", "{name}"));
+      if let Some(item) = doc.paths.paths.get_mut(&path) {
+        if *method == MethodFilter::DELETE {
+          item.delete = None;
+        } else {
+          item.post = None;
+        }
+      }
+    }
+    // 只有修改接口的路径整个去掉
+    doc
+      .paths
+      .paths
+      .retain(|_, item| item.get.is_some() || item.post.is_some() || item.delete.is_some());
+  }
 
   Router::new()
-    .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+    .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", doc))
+    .route("/cheatsheet", get(cheatsheet::index))
+    .route("/cheatsheet/:name", get(cheatsheet::page))
+    .route("/ws/search", get(ws::search))
     .nest("/api", api_routes)
 }
 
-/// Health check endpoint
-#[utoipa::path(
-    get,
-    path = "/api/health",
-    responses(
-        (status = 200, description = "Service is healthy", body = String)
-    ),
-    tag = "Health"
-)]
-async fn health() -> &'static str {
-  "OK"
+/// 按 [server] 配置构建 CORS
+/// 来源在每个请求时读取当前配置（支持热重载），方法、请求头与缓存时间在启动时确定
+pub fn cors_layer(state: Arc<AppState>, server: &ServerConfig) -> CorsLayer {
+  let origins = AllowOrigin::predicate(move |origin, _| {
+    origin
+      .to_str()
+      .is_ok_and(|origin| state.config().server.allows_origin(origin))
+  });
+
+  let methods = if server.cors_methods.iter().any(|m| m == "*") {
+    AllowMethods::any()
+  } else {
+    AllowMethods::list(
+      server
+        .cors_methods
+        .iter()
+        .filter_map(|m| Method::from_bytes(m.as_bytes()).ok()),
+    )
+  };
+
+  let headers = if server.cors_headers.iter().any(|h| h == "*") {
+    AllowHeaders::any()
+  } else {
+    AllowHeaders::list(
+      server
+        .cors_headers
+        .iter()
+        .filter_map(|h| HeaderName::from_bytes(h.as_bytes()).ok()),
+    )
+  };
+
+  let mut cors = CorsLayer::new()
+    .allow_origin(origins)
+    .allow_methods(methods)
+    .allow_headers(headers);
+  if server.cors_max_age_secs > 0 {
+    cors = cors.max_age(std::time::Duration::from_secs(server.cors_max_age_secs));
+  }
+  cors
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use crate::config::AppConfig;
+  use crate::search::SearchEngine;
+  use crate::storage::Database;
+
+  /// 使用 dir 中的空数据库和空索引的服务状态
+  pub(crate) fn state(dir: &std::path::Path) -> Arc<AppState> {
+    let config = AppConfig::default();
+    let db = Database::open(&dir.join("data.redb")).unwrap();
+    let search = SearchEngine::open(&dir.join("index"), &config.search).unwrap();
+    Arc::new(AppState {
+      db,
+      search: tokio::sync::RwLock::new(search),
+      data_dir: dir.to_path_buf(),
+      read_only: false,
+      users: crate::users::UserStore::new(dir),
+      jobs: JobHistory::load(dir),
+      update: UpdateTracker::default(),
+      limiter: RateLimiter::default(),
+      started: std::time::Instant::now(),
+      config: parking_lot::RwLock::new(Arc::new(config)),
+    })
+  }
+
+  #[test]
+  fn test_is_mutating() {
+    assert!(is_mutating(&Method::POST, "/api/reset"));
+    assert!(is_mutating(&Method::DELETE, "/api/command/tar"));
+    assert!(is_mutating(&Method::POST, "/api/favorites"));
+    assert!(is_mutating(&Method::DELETE, "/api/favorites/git-commit"));
+
+    assert!(!is_mutating(&Method::GET, "/api/command/tar"));
+    assert!(!is_mutating(&Method::GET, "/api/favorites"));
+    assert!(!is_mutating(&Method::POST, "/api/sync/fetch"));
+    assert!(!is_mutating(&Method::DELETE, "/api/command/"));
+    assert!(!is_mutating(&Method::POST, "/reset"));
+  }
+
+  #[tokio::test]
+  async fn test_read_only_routes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let router = routes(&ServerConfig::default(), true).with_state(state(temp_dir.path()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let client = reqwest::Client::new();
+    let status = |request: reqwest::RequestBuilder| async move {
+      request.send().await.unwrap().status().as_u16()
+    };
+    assert_eq!(status(client.get(format!("{}/favorites", base))).await, 200);
+    assert_eq!(status(client.post(format!("{}/reset", base))).await, 403);
+    assert_eq!(status(client.post(format!("{}/learn", base))).await, 403);
+    assert_eq!(
+      status(client.delete(format!("{}/favorites/tar", base))).await,
+      403
+    );
+    assert_eq!(
+      status(client.delete(format!("{}/command/tar", base))).await,
+      403
+    );
+    // POST 的查询接口不受影响（缺少字段的请求体到达处理函数后被拒绝）
+    let fetch = client
+      .post(format!("{}/sync/fetch", base))
+      .json(&serde_json::json!({}));
+    assert_eq!(status(fetch).await, 422);
+  }
 }
@@ -0,0 +1,196 @@
+//! 服务模式下的配置热重载
+//!
+//! 定期检查配置文件的修改时间（Unix 下也响应 SIGHUP），校验通过后替换 AppState 中的配置。
+//! 日志级别、CORS 来源、搜索数量限制等按请求读取的设置立即生效；
+//! 端口、绑定地址、请求体大小限制、CORS 方法与请求头、存储路径与搜索引擎的排序/分词设置在启动时使用，修改后需要重启。
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::config::{self, AppConfig, IssueLevel};
+use crate::{AppState, LogFilterHandle};
+
+/// 检查配置文件变化的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 配置文件的路径与修改时间，用于判断是否变化
+type Stamp = Option<(PathBuf, SystemTime)>;
+
+fn stamp() -> Stamp {
+  let path = AppConfig::default_path()?;
+  let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+  Some((path, modified))
+}
+
+/// 启动后台任务监视配置变化
+pub fn spawn_config_watcher(state: Arc<AppState>, log_handle: Option<LogFilterHandle>) {
+  tokio::spawn(async move {
+    let mut last = stamp();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    #[cfg(unix)]
+    let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+      Ok(signal) => Some(signal),
+      Err(e) => {
+        tracing::warn!("Failed to install SIGHUP handler: {}", e);
+        None
+      }
+    };
+
+    loop {
+      #[cfg(unix)]
+      let forced = tokio::select! {
+        _ = interval.tick() => false,
+        Some(()) = async { hangup.as_mut()?.recv().await } => true,
+      };
+      #[cfg(not(unix))]
+      let forced = {
+        interval.tick().await;
+        false
+      };
+
+      let current = stamp();
+      if forced {
+        tracing::info!("Received SIGHUP, reloading configuration");
+      } else if current == last {
+        continue;
+      }
+      last = current;
+      reload(&state, log_handle.as_ref());
+    }
+  });
+}
+
+/// 重新加载配置；校验失败时保留当前配置
+fn reload(state: &AppState, log_handle: Option<&LogFilterHandle>) {
+  let new = match AppConfig::default_path() {
+    Some(path) => {
+      let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+          tracing::warn!("Config reload skipped: failed to read {:?}: {}", path, e);
+          return;
+        }
+      };
+      let errors: Vec<String> = config::validate(&content, std::env::vars())
+        .into_iter()
+        .filter(|issue| issue.level == IssueLevel::Error)
+        .map(|issue| issue.message)
+        .collect();
+      if !errors.is_empty() {
+        tracing::warn!(
+          "Config reload rejected, keeping current configuration: {}",
+          errors.join("; ")
+        );
+        return;
+      }
+      AppConfig::load(&path)
+    }
+    None => AppConfig::load_default(),
+  };
+
+  let old = state.config();
+  for key in restart_required(&old, &new) {
+    tracing::warn!(
+      "Config change to '{}' requires a restart to take effect",
+      key
+    );
+  }
+
+  if new.logging.level != old.logging.level {
+    match log_handle {
+      Some(handle) => match handle.reload(tracing_subscriber::EnvFilter::new(&new.logging.level)) {
+        Ok(()) => tracing::info!("Log level changed to '{}'", new.logging.level),
+        Err(e) => tracing::warn!("Failed to reload log level: {}", e),
+      },
+      None => tracing::info!("RUST_LOG is set, ignoring logging.level change"),
+    }
+  }
+
+  state.set_config(new);
+  tracing::info!("Configuration reloaded");
+}
+
+/// 在启动时使用、无法热重载的设置中发生变化的键
+fn restart_required(old: &AppConfig, new: &AppConfig) -> Vec<&'static str> {
+  let mut keys = Vec::new();
+  if old.server.port != new.server.port {
+    keys.push("server.port");
+  }
+  if old.server.bind != new.server.bind {
+    keys.push("server.bind");
+  }
+  if old.server.read_only != new.server.read_only {
+    keys.push("server.read_only");
+  }
+  if old.server.max_upload_size != new.server.max_upload_size {
+    keys.push("server.max_upload_size");
+  }
+  if old.server.max_restore_size != new.server.max_restore_size {
+    keys.push("server.max_restore_size");
+  }
+  if old.server.max_json_size != new.server.max_json_size {
+    keys.push("server.max_json_size");
+  }
+  if (
+    &old.server.cors_methods,
+    &old.server.cors_headers,
+    old.server.cors_max_age_secs,
+  ) != (
+    &new.server.cors_methods,
+    &new.server.cors_headers,
+    new.server.cors_max_age_secs,
+  ) {
+    keys.push("server.cors_methods/cors_headers/cors_max_age_secs");
+  }
+  if serde_json::to_value(&old.storage).ok() != serde_json::to_value(&new.storage).ok() {
+    keys.push("storage");
+  }
+  // 搜索引擎在启动时按这些设置构建
+  let engine = |c: &AppConfig| {
+    (
+      c.search.index_buffer_size,
+      [
+        c.search.name_boost,
+        c.search.description_boost,
+        c.search.content_boost,
+      ],
+      c.search.exact_name_boost,
+      c.search.fuzzy_distance,
+      c.search.fuzzy_search_distance,
+      c.search.user_dict.clone(),
+      c.search.result_cache_size,
+      c.search.example_results,
+      c.search.default_lang.clone(),
+      c.search.cjk_lang.clone(),
+    )
+  };
+  if engine(old) != engine(new) {
+    keys.push("search (index and ranking settings)");
+  }
+  if old.import.watch_dirs != new.import.watch_dirs {
+    keys.push("import.watch_dirs");
+  }
+  keys
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_restart_required() {
+    let old = AppConfig::default();
+    let mut new = AppConfig::default();
+    new.search.max_limit = 50;
+    new.logging.level = "debug".to_string();
+    new.server.cors_origins = vec!["http://localhost:8080".to_string()];
+    assert!(restart_required(&old, &new).is_empty());
+
+    new.server.port = 8080;
+    new.storage.db_filename = "other.redb".to_string();
+    assert_eq!(restart_required(&old, &new), vec!["server.port", "storage"]);
+  }
+}
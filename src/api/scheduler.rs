@@ -0,0 +1,430 @@
+//! serve 模式的定时任务
+//!
+//! 按 [scheduler] 中的 cron 表达式（本地时间）运行维护任务：下载 tldr 更新、合并索引段、压缩数据库、
+//! 重新学习二进制已变化的命令、备份数据。每隔 TICK_INTERVAL 检查上次检查以来是否到了运行时间，
+//! 到期的任务依次运行（同一时间只运行一个）；停机期间错过的运行不会补做。
+//! 任务使用共享数据，--read-only 只限制 API 调用方，不影响定时任务。
+//! 最近的运行记录保存在数据目录的 scheduler-history.json 中，通过 GET /api/scheduler 查看。
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::Json;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::audit::{self, Action, Origin};
+use crate::config::{self, AppConfig, SchedulerConfig};
+use crate::AppState;
+
+/// 检查任务是否到期的间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 运行记录文件名（位于数据目录）
+const HISTORY_FILENAME: &str = "scheduler-history.json";
+
+/// 定时任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Job {
+  Update,
+  Optimize,
+  Compact,
+  RefreshLearned,
+  Backup,
+}
+
+impl Job {
+  const ALL: [Job; 5] = [
+    Job::Update,
+    Job::Optimize,
+    Job::Compact,
+    Job::RefreshLearned,
+    Job::Backup,
+  ];
+
+  fn schedule(self, config: &SchedulerConfig) -> &str {
+    match self {
+      Job::Update => &config.update,
+      Job::Optimize => &config.optimize,
+      Job::Compact => &config.compact,
+      Job::RefreshLearned => &config.refresh_learned,
+      Job::Backup => &config.backup,
+    }
+  }
+}
+
+/// 一次运行的结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobRun {
+  /// Job that ran
+  pub job: Job,
+  /// Start time (RFC 3339)
+  pub started_at: String,
+  /// Run time in milliseconds
+  pub duration_ms: u64,
+  /// Whether the job succeeded
+  pub success: bool,
+  /// Result summary or error message
+  pub message: String,
+}
+
+/// 一个任务的计划与最近一次运行
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobStatus {
+  /// Job name
+  pub job: Job,
+  /// Cron expression from [scheduler] (empty when disabled)
+  pub schedule: String,
+  /// Next scheduled run (RFC 3339), absent when disabled
+  pub next_run: Option<String>,
+  /// Most recent run
+  pub last_run: Option<JobRun>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SchedulerStatus {
+  /// Job currently running
+  pub running: Option<Job>,
+  /// Schedule and last run of every job
+  pub jobs: Vec<JobStatus>,
+  /// Recent runs, newest first
+  pub history: Vec<JobRun>,
+}
+
+/// 运行记录（新的在前）与正在运行的任务
+pub struct JobHistory {
+  path: PathBuf,
+  runs: parking_lot::Mutex<VecDeque<JobRun>>,
+  running: parking_lot::Mutex<Option<Job>>,
+}
+
+impl JobHistory {
+  /// 读取数据目录中的运行记录
+  pub fn load(data_dir: &Path) -> Self {
+    let path = data_dir.join(HISTORY_FILENAME);
+    let runs = std::fs::read_to_string(&path)
+      .ok()
+      .and_then(|s| serde_json::from_str(&s).ok())
+      .unwrap_or_default();
+    Self {
+      path,
+      runs: parking_lot::Mutex::new(runs),
+      running: Default::default(),
+    }
+  }
+
+  /// 记录一次运行，只保留最近 limit 条
+  fn push(&self, run: JobRun, limit: usize) {
+    let mut runs = self.runs.lock();
+    runs.push_front(run);
+    runs.truncate(limit);
+    if let Err(e) = serde_json::to_string_pretty(&*runs)
+      .map_err(std::io::Error::other)
+      .and_then(|s| std::fs::write(&self.path, s))
+    {
+      tracing::warn!("Failed to save {}: {}", self.path.display(), e);
+    }
+  }
+
+  fn status(&self, config: &SchedulerConfig, now: DateTime<Local>) -> SchedulerStatus {
+    let runs = self.runs.lock();
+    let jobs = Job::ALL
+      .iter()
+      .map(|&job| {
+        let schedule = job.schedule(config);
+        JobStatus {
+          job,
+          schedule: schedule.to_string(),
+          next_run: config::parse_schedule(schedule)
+            .ok()
+            .flatten()
+            .and_then(|cron| cron.find_next_occurrence(&now, false).ok())
+            .map(|time| time.to_rfc3339()),
+          last_run: runs.iter().find(|run| run.job == job).cloned(),
+        }
+      })
+      .collect();
+    SchedulerStatus {
+      running: *self.running.lock(),
+      jobs,
+      history: runs.iter().cloned().collect(),
+    }
+  }
+}
+
+/// (since, now] 之间是否有计划的运行时间；空或无效的表达式不运行
+fn is_due(schedule: &str, since: DateTime<Local>, now: DateTime<Local>) -> bool {
+  match config::parse_schedule(schedule) {
+    Ok(Some(cron)) => cron
+      .find_next_occurrence(&since, false)
+      .is_ok_and(|next| next <= now),
+    _ => false,
+  }
+}
+
+/// 启动后台任务，按配置运行到期的定时任务
+pub fn spawn_scheduler(state: Arc<AppState>) {
+  tokio::spawn(async move {
+    let mut since = Local::now();
+    let mut interval = tokio::time::interval(TICK_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+      interval.tick().await;
+      // 每次读取当前配置，修改 [scheduler] 随热重载生效
+      let config = state.config();
+      let now = Local::now();
+      for job in Job::ALL {
+        if is_due(job.schedule(&config.scheduler), since, now) {
+          run(&state, job).await;
+        }
+      }
+      since = now;
+    }
+  });
+}
+
+/// 运行一个任务并记录结果
+async fn run(state: &Arc<AppState>, job: Job) {
+  tracing::info!("Running scheduled job {:?}", job);
+  *state.jobs.running.lock() = Some(job);
+  let started_at = Local::now();
+  let start = Instant::now();
+
+  let result = match job {
+    Job::Update => super::update::apply_update(state, &Origin::scheduler()).await,
+    Job::Optimize => optimize(state).await,
+    Job::Compact => compact(state).await,
+    Job::RefreshLearned => refresh_learned(state).await,
+    Job::Backup => backup(state).await,
+  };
+
+  let (success, message) = match result {
+    Ok(message) => {
+      tracing::info!("Scheduled job {:?} finished: {}", job, message);
+      (true, message)
+    }
+    Err(error) => {
+      tracing::warn!("Scheduled job {:?} failed: {}", job, error);
+      (false, error)
+    }
+  };
+  *state.jobs.running.lock() = None;
+  state.jobs.push(
+    JobRun {
+      job,
+      started_at: started_at.to_rfc3339(),
+      duration_ms: start.elapsed().as_millis() as u64,
+      success,
+      message,
+    },
+    state.config().scheduler.history_size,
+  );
+}
+
+async fn optimize(state: &AppState) -> Result<String, String> {
+  let segments = state
+    .search
+    .write()
+    .await
+    .optimize()
+    .map_err(|e| e.to_string())?;
+  Ok(if segments > 1 {
+    format!("Merged {} index segments", segments)
+  } else {
+    "Index is already optimized".to_string()
+  })
+}
+
+async fn compact(state: &Arc<AppState>) -> Result<String, String> {
+  let db_path = state.data_dir.join(&state.config().storage.db_filename);
+  let size = || std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
+  let before = size();
+  let db_state = state.clone();
+  let compacted = tokio::task::spawn_blocking(move || db_state.db.compact())
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+  Ok(if compacted {
+    format!("Database compacted from {} to {} bytes", before, size())
+  } else {
+    "Nothing to compact".to_string()
+  })
+}
+
+/// 同 rtfm learn --refresh；learn.llm.enabled 时用 LLM 补充示例
+async fn refresh_learned(state: &Arc<AppState>) -> Result<String, String> {
+  let config = state.config();
+  let learned = state
+    .db
+    .get_all_commands("local")
+    .map_err(|e| e.to_string())?;
+  let plugins = Arc::new(crate::plugin::Registry::load(&config));
+  let (mut refreshed, mut unchanged, mut missing, mut skipped, mut failed) = (0, 0, 0, 0, 0);
+
+  for old in learned {
+    // 获取帮助需要运行外部命令
+    let (plugins, learn_config) = (plugins.clone(), config.clone());
+    let name = old.name.clone();
+    let outcome = tokio::task::spawn_blocking(move || {
+      crate::learn::refresh::refresh(
+        &old,
+        &plugins,
+        &learn_config.learn,
+        crate::learn::refresh::Check::Binary,
+      )
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    use crate::learn::refresh::Outcome;
+    match outcome {
+      Outcome::Refreshed {
+        mut cmd,
+        plugin_failure,
+        ..
+      } => {
+        if let Some(failure) = plugin_failure {
+          tracing::warn!("{}", failure);
+        }
+        if config.learn.llm.enabled {
+          if let Err(e) = crate::learn::llm::enhance(&mut cmd, &config.learn.llm).await {
+            tracing::warn!("LLM summarization of {} failed: {}", name, e);
+          }
+        }
+        state.db.save_command(&cmd).map_err(|e| e.to_string())?;
+        let mut search = state.search.write().await;
+        crate::search::index_commands(&state.db, &mut search, std::slice::from_ref(&cmd))
+          .map_err(|e| e.to_string())?;
+        refreshed += 1;
+      }
+      Outcome::Unchanged => unchanged += 1,
+      Outcome::Missing => missing += 1,
+      Outcome::Skipped => skipped += 1,
+      Outcome::Failed => failed += 1,
+    }
+  }
+  if refreshed > 0 {
+    audit::record(
+      &state.db,
+      &Origin::scheduler(),
+      Action::Learn,
+      refreshed,
+      "refresh",
+    );
+  }
+
+  Ok(format!(
+    "{} re-learned, {} up to date, {} missing, {} skipped, {} failed",
+    refreshed, unchanged, missing, skipped, failed
+  ))
+}
+
+/// 同 rtfm backup，写入备份目录并删除多余的旧备份
+async fn backup(state: &Arc<AppState>) -> Result<String, String> {
+  let config = state.config();
+  let dir = backup_dir(&config, &state.data_dir);
+  let output = crate::backup::archive_path(&dir, Local::now());
+  let backup_state = state.clone();
+  let archive = output.clone();
+
+  let removed = tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
+    std::fs::create_dir_all(&dir)?;
+    // 服务运行时数据库文件被独占打开，先写一份快照再归档
+    let snapshot_dir = tempfile::tempdir_in(&dir)?;
+    let snapshot = snapshot_dir.path().join(&config.storage.db_filename);
+    backup_state.db.snapshot(&snapshot)?;
+    {
+      // 持有读锁期间索引不会被写入
+      let _search = backup_state.search.blocking_read();
+      let index_path = backup_state.data_dir.join(&config.storage.index_dirname);
+      crate::backup::write_archive(
+        &archive,
+        &snapshot,
+        &index_path,
+        &backup_state.data_dir,
+        &config,
+      )?;
+    }
+    Ok(crate::backup::rotate(&dir, config.scheduler.backup_keep)?)
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())?;
+
+  let size = std::fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+  let mut message = format!("Wrote {} ({} bytes)", output.display(), size);
+  if removed > 0 {
+    message.push_str(&format!(", removed {} old backup(s)", removed));
+  }
+  Ok(message)
+}
+
+/// 定时备份的目录
+fn backup_dir(config: &AppConfig, data_dir: &Path) -> PathBuf {
+  config
+    .scheduler
+    .backup_dir
+    .clone()
+    .unwrap_or_else(|| data_dir.join("backups"))
+}
+
+/// List scheduled jobs, their next run and recent results
+#[utoipa::path(
+    get,
+    path = "/api/scheduler",
+    responses(
+        (status = 200, description = "Scheduler status", body = SchedulerStatus)
+    ),
+    tag = "Scheduler"
+)]
+pub async fn status(State(state): State<Arc<AppState>>) -> Json<SchedulerStatus> {
+  Json(state.jobs.status(&state.config().scheduler, Local::now()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn test_is_due() {
+    let at = |h, m, s| Local.with_ymd_and_hms(2024, 1, 7, h, m, s).unwrap();
+    assert!(is_due("0 4 * * *", at(3, 59, 45), at(4, 0, 15)));
+    assert!(!is_due("0 4 * * *", at(4, 0, 15), at(4, 0, 45)));
+    // 2024-01-07 是星期日
+    assert!(is_due("30 4 * * 0", at(4, 0, 0), at(5, 0, 0)));
+    assert!(!is_due("30 4 * * 1", at(4, 0, 0), at(5, 0, 0)));
+    assert!(!is_due("", at(0, 0, 0), at(23, 0, 0)));
+  }
+
+  #[test]
+  fn test_history() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let history = JobHistory::load(temp_dir.path());
+    let run = |job, success| JobRun {
+      job,
+      started_at: String::new(),
+      duration_ms: 0,
+      success,
+      message: String::new(),
+    };
+    history.push(run(Job::Compact, true), 2);
+    history.push(run(Job::Backup, false), 2);
+    history.push(run(Job::Compact, false), 2);
+
+    let config = SchedulerConfig::default();
+    let status = JobHistory::load(temp_dir.path()).status(&config, Local::now());
+    assert_eq!(status.history.len(), 2);
+    let compact = status.jobs.iter().find(|j| j.job == Job::Compact).unwrap();
+    assert!(!compact.last_run.as_ref().unwrap().success);
+    assert!(compact.next_run.is_some());
+    let update = status.jobs.iter().find(|j| j.job == Job::Update).unwrap();
+    assert!(update.next_run.is_none());
+  }
+}
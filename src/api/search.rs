@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use axum::extract::{Query, State};
-use axum::Json;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::search::SearchResponse;
+use super::auth::{self, CurrentUser};
+use crate::search::{Platform, SearchEngine, SearchResponse};
 use crate::AppState;
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -14,8 +15,13 @@ pub struct SearchQuery {
   pub q: String,
   /// Language filter (e.g., en, zh)
   pub lang: Option<String>,
+  /// Platform: auto (commands for the server's OS first), all, or a platform such as linux, osx, windows (that platform and common commands) or common (default: search.platform)
+  pub platform: Option<String>,
   /// Maximum results to return (default: 20, max: 100)
   pub limit: Option<usize>,
+  /// Tolerate typos: match terms within search.fuzzy_search_distance edits
+  #[serde(default)]
+  pub fuzzy: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -37,18 +43,56 @@ pub struct ErrorResponse {
 )]
 pub async fn search(
   State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
   Query(params): Query<SearchQuery>,
 ) -> Result<Json<SearchResponse>, Json<ErrorResponse>> {
-  let default_limit = state.config.search.default_limit;
-  let max_limit = state.config.search.max_limit;
+  run_search(&state, user.as_deref(), &params, true)
+    .await
+    .map(Json)
+    .map_err(|error| Json(ErrorResponse { error }))
+}
+
+/// 按查询参数搜索（/api/search 与 /ws/search 共用），多用户模式下叠加用户自己的命令
+/// record: 记入使用统计（实时搜索的中间输入不记录）
+pub async fn run_search(
+  state: &AppState,
+  user: Option<&CurrentUser>,
+  params: &SearchQuery,
+  record: bool,
+) -> Result<SearchResponse, String> {
+  let config = state.config();
+  let default_limit = config.search.default_limit;
+  let max_limit = config.search.max_limit;
   let limit = params.limit.unwrap_or(default_limit).min(max_limit);
   let lang = params.lang.as_deref();
+  let platform = Platform::parse(
+    params
+      .platform
+      .as_deref()
+      .unwrap_or(&config.search.platform),
+  );
+
+  let run = |engine: &SearchEngine| {
+    if params.fuzzy {
+      engine.search_fuzzy(&params.q, lang, &platform, limit)
+    } else {
+      engine.search(&params.q, lang, &platform, limit)
+    }
+    .map_err(|e| e.to_string())
+  };
+
+  let user_data = auth::user_data(state, user)?;
+  let mut response = run(&*state.search.read().await)?;
+
+  // 多用户模式下叠加用户自己学习的命令
+  if let Some(data) = &user_data {
+    let own = run(&*data.search.read().await)?;
+    response = crate::users::merge_results(response, own, limit);
+  }
 
-  let search = state.search.read().await;
-  match search.search(&params.q, lang, limit) {
-    Ok(response) => Ok(Json(response)),
-    Err(e) => Err(Json(ErrorResponse {
-      error: e.to_string(),
-    })),
+  if record {
+    let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+    crate::usage::record_query(db, &config, &params.q, response.results.len());
   }
+  Ok(response)
 }
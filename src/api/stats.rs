@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::auth::{self, CurrentUser};
+use crate::usage::{self, UsageEntry, UsageReport};
+use crate::AppState;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+  /// Error message
+  pub error: String,
+}
+
+/// 命令数与使用概况
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsResponse {
+  /// Total number of commands
+  pub commands: usize,
+  /// Commands per language
+  pub languages: BTreeMap<String, usize>,
+  /// Commands per category
+  pub categories: BTreeMap<String, usize>,
+  /// Most looked-up commands
+  pub most_used: Vec<UsageEntry>,
+  /// Most looked-up commands in the last 7 days
+  pub trending: Vec<UsageEntry>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UsageQuery {
+  /// Maximum entries per list (default: 10)
+  pub limit: Option<usize>,
+}
+
+/// Summarize local usage: top lookups, zero-result queries and learning gaps
+#[utoipa::path(
+    get,
+    path = "/api/stats/usage",
+    params(UsageQuery),
+    responses(
+        (status = 200, description = "Usage summary", body = UsageReport),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Stats"
+)]
+pub async fn usage(
+  State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
+  Query(params): Query<UsageQuery>,
+) -> Result<Json<UsageReport>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+  let limit = params
+    .limit
+    .unwrap_or(10)
+    .min(state.config().search.max_limit);
+  // 多用户模式下统计的是当前用户的使用情况
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+  usage::report(db, limit, crate::learn::find_binary)
+    .map(Json)
+    .map_err(|e| to_error(e.to_string()))
+}
+
+/// Summarize the database and the most used commands
+#[utoipa::path(
+    get,
+    path = "/api/stats",
+    params(UsageQuery),
+    responses(
+        (status = 200, description = "Command counts and most used commands", body = StatsResponse),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Stats"
+)]
+pub async fn summary(
+  State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
+  Query(params): Query<UsageQuery>,
+) -> Result<Json<StatsResponse>, Json<ErrorResponse>> {
+  let to_error = |error: String| Json(ErrorResponse { error });
+  let limit = params
+    .limit
+    .unwrap_or(10)
+    .min(state.config().search.max_limit);
+  let commands = state
+    .db
+    .list_all_commands()
+    .map_err(|e| to_error(e.to_string()))?;
+  let mut languages = BTreeMap::new();
+  let mut categories = BTreeMap::new();
+  for cmd in &commands {
+    *languages.entry(cmd.lang.clone()).or_default() += 1;
+    *categories.entry(cmd.category.clone()).or_default() += 1;
+  }
+
+  // 命令数据是共享的，使用统计按当前用户
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+  let most_used = usage::most_used(db, limit).map_err(|e| to_error(e.to_string()))?;
+  let trending =
+    usage::trending(db, usage::TRENDING_DAYS, limit).map_err(|e| to_error(e.to_string()))?;
+
+  Ok(Json(StatsResponse {
+    commands: commands.len(),
+    languages,
+    categories,
+    most_used,
+    trending,
+  }))
+}
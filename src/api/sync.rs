@@ -0,0 +1,365 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, Query, State};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::auth::{self, CurrentUser};
+use crate::audit::{self, Action};
+use crate::storage::{Command, Favorite, Note};
+use crate::sync::{self, PersonalData, SyncEntry, SyncKey, SyncPolicy, SyncReport};
+use crate::AppState;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+  /// Error message
+  pub error: String,
+}
+
+fn to_error(e: impl std::fmt::Display) -> Json<ErrorResponse> {
+  Json(ErrorResponse {
+    error: e.to_string(),
+  })
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ManifestQuery {
+  /// Only list commands in this language (e.g., local for learned commands)
+  pub lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FetchRequest {
+  /// Commands to return
+  pub keys: Vec<SyncKey>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PushRequest {
+  /// Commands to store
+  pub commands: Vec<Command>,
+  /// Favorites to add
+  #[serde(default)]
+  pub favorites: Vec<Favorite>,
+  /// Notes to store
+  #[serde(default)]
+  pub notes: Vec<Note>,
+  /// How to resolve conflicts with existing commands
+  #[serde(default)]
+  pub policy: SyncPolicy,
+}
+
+/// List every command with its content hash
+#[utoipa::path(
+    get,
+    path = "/api/sync/manifest",
+    params(ManifestQuery),
+    responses(
+        (status = 200, description = "Command hashes", body = Vec<SyncEntry>),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Sync"
+)]
+pub async fn manifest(
+  State(state): State<Arc<AppState>>,
+  Query(params): Query<ManifestQuery>,
+) -> Result<Json<Vec<SyncEntry>>, Json<ErrorResponse>> {
+  sync::manifest(&state.db, params.lang.as_deref())
+    .map(Json)
+    .map_err(to_error)
+}
+
+/// Return the requested commands
+#[utoipa::path(
+    post,
+    path = "/api/sync/fetch",
+    request_body = FetchRequest,
+    responses(
+        (status = 200, description = "Commands that exist on this server", body = Vec<Command>),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Sync"
+)]
+pub async fn fetch(
+  State(state): State<Arc<AppState>>,
+  Json(request): Json<FetchRequest>,
+) -> Result<Json<Vec<Command>>, Json<ErrorResponse>> {
+  sync::fetch(&state.db, &request.keys)
+    .map(Json)
+    .map_err(to_error)
+}
+
+/// List favorites and notes
+#[utoipa::path(
+    get,
+    path = "/api/sync/personal",
+    responses(
+        (status = 200, description = "Favorites and notes", body = PersonalData),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Sync"
+)]
+pub async fn personal(
+  State(state): State<Arc<AppState>>,
+  user: Option<Extension<CurrentUser>>,
+) -> Result<Json<PersonalData>, Json<ErrorResponse>> {
+  // 多用户模式下每个用户有自己的收藏和笔记
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let db = user_data.as_ref().map_or(&state.db, |data| &data.db);
+  PersonalData::collect(db).map(Json).map_err(to_error)
+}
+
+/// Store commands, favorites and notes pushed by another rtfm instance
+#[utoipa::path(
+    post,
+    path = "/api/sync/push",
+    request_body = PushRequest,
+    responses(
+        (status = 200, description = "Sync result", body = SyncReport),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 500, description = "Sync failed", body = ErrorResponse)
+    ),
+    security((), ("api_key" = []), ("bearer" = [])),
+    tag = "Sync"
+)]
+pub async fn push(
+  State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
+  Json(request): Json<PushRequest>,
+) -> Result<Json<SyncReport>, Json<ErrorResponse>> {
+  let user_data = auth::user_data(&state, user.as_deref()).map_err(to_error)?;
+  let (mut report, changed) =
+    sync::apply(&state.db, request.commands, request.policy).map_err(to_error)?;
+  // 命令进入共享数据，收藏和笔记属于推送的用户
+  let personal = PersonalData {
+    favorites: request.favorites,
+    notes: request.notes,
+  };
+  let (db, search) = match &user_data {
+    Some(data) => (&data.db, &data.search),
+    None => (&state.db, &state.search),
+  };
+  let noted = sync::apply_personal(db, &personal, request.policy, &mut report).map_err(to_error)?;
+
+  if report.changed() > 0 {
+    audit::record(
+      &state.db,
+      &auth::origin(client, user.as_deref()),
+      Action::Sync,
+      report.changed(),
+      "push",
+    );
+  }
+  // 客户端分批推送，每批只索引本批保存的命令；只有收藏和笔记变化时不动共享索引
+  if !changed.is_empty() {
+    let mut search = state.search.write().await;
+    crate::search::index_commands(&state.db, &mut search, &changed).map_err(to_error)?;
+  }
+  if !noted.is_empty() {
+    let mut search = search.write().await;
+    for name in &noted {
+      crate::search::reindex_command(db, &mut search, name).map_err(to_error)?;
+    }
+  }
+
+  tracing::info!(
+    "Sync push: {} added, {} updated, {} kept, {} favorites, {} notes",
+    report.added,
+    report.updated,
+    report.kept,
+    report.favorites,
+    report.notes
+  );
+  Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::tests::state;
+  use crate::config::ServerConfig;
+
+  #[tokio::test]
+  async fn test_sync_personal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    state.db.add_favorite("tar").unwrap();
+    state.db.set_note("tar", "use -z for gzip").unwrap();
+    let router = axum::Router::new()
+      .nest("/api", crate::api::routes(&ServerConfig::default(), false))
+      .with_state(state.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let client = sync::SyncClient::new(&base, "rtfm-test", None).unwrap();
+    let remote = client.personal().await.unwrap();
+    assert_eq!(remote.favorites[0].name, "tar");
+    assert_eq!(remote.notes[0].text, "use -z for gzip");
+
+    // 没有命令时也发送收藏和笔记
+    let personal = PersonalData {
+      favorites: vec![Favorite {
+        name: "git".to_string(),
+        added: String::new(),
+      }],
+      notes: vec![Note {
+        name: "tar".to_string(),
+        text: "prefer bsdtar".to_string(),
+      }],
+    };
+    let report = client
+      .push(&[], &personal, SyncPolicy::Merge)
+      .await
+      .unwrap();
+    assert_eq!((report.favorites, report.notes), (1, 1));
+    assert_eq!(state.db.list_favorites().unwrap().len(), 2);
+    assert_eq!(
+      state.db.get_note("tar").unwrap().as_deref(),
+      Some("use -z for gzip\n\nprefer bsdtar")
+    );
+  }
+
+  #[tokio::test]
+  async fn test_sync_personal_per_user() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    state.db.add_favorite("tar").unwrap();
+    let user = |name: &str| {
+      Some(Extension(CurrentUser {
+        name: name.to_string(),
+      }))
+    };
+
+    // 用户只看到自己的收藏和笔记，不是共享数据库中的
+    let Json(personal) = super::personal(State(state.clone()), user("alice"))
+      .await
+      .unwrap();
+    assert!(personal.favorites.is_empty());
+
+    let request = PushRequest {
+      commands: vec![],
+      favorites: vec![Favorite {
+        name: "git".to_string(),
+        added: String::new(),
+      }],
+      notes: vec![Note {
+        name: "git".to_string(),
+        text: "alice's note".to_string(),
+      }],
+      policy: SyncPolicy::Merge,
+    };
+    let Json(report) = push(State(state.clone()), None, user("alice"), Json(request))
+      .await
+      .unwrap();
+    assert_eq!((report.favorites, report.notes), (1, 1));
+    let alice = state.users.get("alice", &state.config()).unwrap();
+    assert_eq!(alice.db.list_favorites().unwrap()[0].name, "git");
+    assert_eq!(state.db.list_favorites().unwrap().len(), 1);
+    assert!(state.db.get_note("git").unwrap().is_none());
+
+    let Json(personal) = super::personal(State(state.clone()), user("bob"))
+      .await
+      .unwrap();
+    assert!(personal.favorites.is_empty() && personal.notes.is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_sync_with_token() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    let mut config = (*state.config()).clone();
+    config.server.api_keys = vec!["secret".to_string()];
+    state.set_config(config.clone());
+    let router = axum::Router::new()
+      .nest("/api", crate::api::routes(&config.server, false))
+      .layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        crate::api::authenticate,
+      ))
+      .with_state(state.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let personal = PersonalData {
+      favorites: vec![Favorite {
+        name: "git".to_string(),
+        added: String::new(),
+      }],
+      notes: vec![],
+    };
+    // 没有令牌时服务器拒绝推送
+    let anonymous = sync::SyncClient::new(&base, "rtfm-test", None).unwrap();
+    let error = anonymous
+      .push(&[], &personal, SyncPolicy::Merge)
+      .await
+      .unwrap_err();
+    assert!(error.to_string().contains("Missing or invalid API key"));
+
+    let client = sync::SyncClient::new(&base, "rtfm-test", Some("secret")).unwrap();
+    let report = client
+      .push(&[], &personal, SyncPolicy::Merge)
+      .await
+      .unwrap();
+    assert_eq!(report.favorites, 1);
+    assert_eq!(client.personal().await.unwrap().favorites[0].name, "git");
+  }
+
+  #[tokio::test]
+  async fn test_sync_push_indexes_changes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    let search = |search: &crate::search::SearchEngine, q: &str| {
+      search
+        .search(q, None, &crate::search::Platform::All, 10)
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|r| r.name)
+        .collect::<Vec<_>>()
+    };
+
+    let cmd = crate::learn::parse_help_content("mytool", "Usage: mytool [OPTIONS]", "--help");
+    let request = PushRequest {
+      commands: vec![cmd.clone()],
+      favorites: vec![],
+      notes: vec![],
+      policy: SyncPolicy::Lww,
+    };
+    let Json(report) = push(State(state.clone()), None, None, Json(request))
+      .await
+      .unwrap();
+    assert_eq!(report.added, 1);
+    assert_eq!(search(&*state.search.read().await, "mytool"), ["mytool"]);
+
+    // 多用户模式下笔记写入用户的数据库并更新用户的索引
+    let alice = state.users.get("alice", &state.config()).unwrap();
+    alice.db.save_command(&cmd).unwrap();
+    crate::search::index_commands(&alice.db, &mut *alice.search.write().await, &[cmd]).unwrap();
+    let request = PushRequest {
+      commands: vec![],
+      favorites: vec![],
+      notes: vec![Note {
+        name: "mytool".to_string(),
+        text: "frobnicate the widgets".to_string(),
+      }],
+      policy: SyncPolicy::Lww,
+    };
+    let user = Some(Extension(CurrentUser {
+      name: "alice".to_string(),
+    }));
+    let Json(report) = push(State(state.clone()), None, user, Json(request))
+      .await
+      .unwrap();
+    assert_eq!(report.notes, 1);
+    assert_eq!(
+      search(&*alice.search.read().await, "frobnicate"),
+      ["mytool"]
+    );
+    assert!(search(&*state.search.read().await, "frobnicate").is_empty());
+  }
+}
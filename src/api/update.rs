@@ -1,10 +1,20 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use axum::extract::State;
-use axum::Json;
+use axum::extract::{ConnectInfo, Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use tokio::sync::watch;
+use utoipa::{IntoParams, ToSchema};
 
+use super::auth::{self, CurrentUser};
+use crate::audit::{self, Action, Origin};
+use crate::storage::UpdateRun;
 use crate::AppState;
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -19,14 +29,106 @@ pub struct UpdateInfo {
   pub download_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
 pub struct UpdateProgress {
-  /// Update status (downloading, completed, failed)
+  /// Update status (idle, checking, downloading, parsing, indexing, completed, failed)
   pub status: String,
   /// Progress percentage (0-100)
   pub progress: f32,
   /// Status message
   pub message: String,
+  /// Bytes downloaded so far
+  pub downloaded: u64,
+  /// Archive size in bytes, if the server reported it
+  pub total: Option<u64>,
+}
+
+impl UpdateProgress {
+  fn new(status: &str, progress: f32, message: impl Into<String>) -> Self {
+    Self {
+      status: status.to_string(),
+      progress,
+      message: message.into(),
+      downloaded: 0,
+      total: None,
+    }
+  }
+
+  /// 更新是否已结束（或从未开始）
+  fn is_finished(&self) -> bool {
+    matches!(self.status.as_str(), "idle" | "completed" | "failed")
+  }
+}
+
+/// 下载阶段占总进度的比例，其余为解析和建立索引
+const DOWNLOAD_SHARE: f32 = 80.0;
+
+/// 保留的更新记录数
+const HISTORY_SIZE: usize = 100;
+
+/// 服务启动后首次自动更新前的等待，避免拖慢启动
+const AUTO_UPDATE_STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// 距下次自动更新的时间较长时，隔一段时间重新读取配置（可能热重载）
+const AUTO_UPDATE_MAX_SLEEP: Duration = Duration::from_secs(3600);
+
+/// 当前更新的进度；下载任务写入，/api/update/progress 订阅
+pub struct UpdateTracker {
+  sender: watch::Sender<UpdateProgress>,
+}
+
+impl Default for UpdateTracker {
+  fn default() -> Self {
+    Self {
+      sender: watch::Sender::new(UpdateProgress::new("idle", 0.0, "No update has run")),
+    }
+  }
+}
+
+impl UpdateTracker {
+  pub fn current(&self) -> UpdateProgress {
+    self.sender.borrow().clone()
+  }
+
+  /// 开始一次更新；已有更新在进行时返回 false
+  fn begin(&self) -> bool {
+    self.sender.send_if_modified(|progress| {
+      if !progress.is_finished() {
+        return false;
+      }
+      *progress = UpdateProgress::new("checking", 0.0, "Checking for updates");
+      true
+    })
+  }
+
+  fn set(&self, progress: UpdateProgress) {
+    self.sender.send_replace(progress);
+  }
+
+  fn downloaded(&self, downloaded: u64, total: Option<u64>) {
+    let percent = total.filter(|&total| total > 0).map_or(0.0, |total| {
+      (downloaded as f32 / total as f32).min(1.0) * DOWNLOAD_SHARE
+    });
+    self.sender.send_modify(|progress| {
+      progress.progress = percent;
+      progress.downloaded = downloaded;
+      progress.total = total;
+    });
+  }
+
+  /// 切换阶段，保留已下载的字节数
+  fn phase(&self, status: &str, progress: f32, message: impl Into<String>) {
+    let message = message.into();
+    self.sender.send_modify(|current| {
+      current.status = status.to_string();
+      current.progress = progress;
+      current.message = message;
+    });
+  }
+
+  fn subscribe(&self) -> watch::Receiver<UpdateProgress> {
+    self.sender.subscribe()
+  }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -60,6 +162,14 @@ struct GithubAsset {
 pub async fn check_update(
   State(state): State<Arc<AppState>>,
 ) -> Result<Json<UpdateInfo>, Json<ErrorResponse>> {
+  fetch_update_info(&state)
+    .await
+    .map(Json)
+    .map_err(|error| Json(ErrorResponse { error }))
+}
+
+/// 查询 tldr-pages 的最新版本
+async fn fetch_update_info(state: &AppState) -> Result<UpdateInfo, String> {
   // 获取当前版本
   let current_version = state
     .db
@@ -69,7 +179,8 @@ pub async fn check_update(
     .map(|m| m.version)
     .unwrap_or_else(|| "0.0.0".to_string());
 
-  let update_config = &state.config.update;
+  let config = state.config();
+  let update_config = &config.update;
 
   // 检查 tldr-pages 最新版本
   let client = reqwest::Client::new();
@@ -81,11 +192,7 @@ pub async fn check_update(
 
   match response {
     Ok(resp) if resp.status().is_success() => {
-      let release: GithubRelease = resp.json().await.map_err(|e| {
-        Json(ErrorResponse {
-          error: e.to_string(),
-        })
-      })?;
+      let release: GithubRelease = resp.json().await.map_err(|e| e.to_string())?;
 
       let latest_version = release.tag_name.trim_start_matches('v').to_string();
 
@@ -103,128 +210,403 @@ pub async fn check_update(
           )
         });
 
-      Ok(Json(UpdateInfo {
+      Ok(UpdateInfo {
         available: latest_version != current_version,
         current_version,
         latest_version,
         download_url,
-      }))
+      })
     }
-    Ok(resp) => Err(Json(ErrorResponse {
-      error: format!("GitHub API error: {}", resp.status()),
-    })),
-    Err(e) => Err(Json(ErrorResponse {
-      error: format!("Network error: {}", e),
-    })),
+    Ok(resp) => Err(format!("GitHub API error: {}", resp.status())),
+    Err(e) => Err(format!("Network error: {}", e)),
   }
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DownloadQuery {
+  /// Return immediately and run the update in the background (follow it with /api/update/progress)
+  #[serde(default)]
+  pub background: bool,
+}
+
 /// Download and apply updates
 #[utoipa::path(
     post,
     path = "/api/update/download",
+    params(DownloadQuery),
     responses(
         (status = 200, description = "Update progress", body = UpdateProgress),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
         (status = 500, description = "Update failed", body = ErrorResponse)
     ),
+    security((), ("api_key" = []), ("bearer" = [])),
     tag = "Update"
 )]
 pub async fn download_update(
   State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
+  Query(query): Query<DownloadQuery>,
 ) -> Result<Json<UpdateProgress>, Json<ErrorResponse>> {
-  // 检查更新
-  let update_info = match check_update(State(state.clone())).await {
-    Ok(Json(info)) => info,
-    Err(e) => return Err(e),
-  };
+  let origin = auth::origin(client, user.as_deref());
+  if query.background {
+    if !state.update.begin() {
+      return Err(Json(ErrorResponse {
+        error: ALREADY_RUNNING.to_string(),
+      }));
+    }
+    let progress = state.update.current();
+    tokio::spawn(async move {
+      // 结果已记录在进度和更新历史中
+      let _ = run_and_record(&state, &origin).await;
+    });
+    return Ok(Json(progress));
+  }
 
-  if !update_info.available {
-    return Ok(Json(UpdateProgress {
-      status: "completed".to_string(),
-      progress: 100.0,
-      message: "Already up to date".to_string(),
-    }));
+  apply_update(&state, &origin)
+    .await
+    .map_err(|error| Json(ErrorResponse { error }))?;
+  Ok(Json(state.update.current()))
+}
+
+/// Stream the progress of the running update as Server-Sent Events
+///
+/// Each `progress` event carries an UpdateProgress as JSON. The current state is sent
+/// first; the stream ends after the update completes or fails.
+#[utoipa::path(
+    get,
+    path = "/api/update/progress",
+    responses(
+        (status = 200, description = "Stream of progress events", content_type = "text/event-stream", body = UpdateProgress)
+    ),
+    tag = "Update"
+)]
+pub async fn progress(
+  State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+  Sse::new(progress_stream(state.update.subscribe()))
+    .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
+
+/// 先发送当前进度，之后每次变化发送一次，更新结束后关闭
+fn progress_stream(
+  receiver: watch::Receiver<UpdateProgress>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+  futures_util::stream::unfold(
+    (receiver, true, false),
+    |(mut receiver, first, done)| async move {
+      if done || (!first && receiver.changed().await.is_err()) {
+        return None;
+      }
+      let progress = receiver.borrow_and_update().clone();
+      // 连接时没有更新在进行，等待下一次更新
+      let waiting = first && progress.status == "idle";
+      let event = Event::default()
+        .event("progress")
+        .json_data(&progress)
+        .unwrap_or_default();
+      let done = !waiting && progress.is_finished();
+      Some((Ok(event), (receiver, false, done)))
+    },
+  )
+}
+
+const ALREADY_RUNNING: &str = "An update is already running";
+
+/// 有新版本时下载并导入，返回结果说明（也用于定时任务）
+pub async fn apply_update(state: &AppState, origin: &Origin) -> Result<String, String> {
+  if !state.update.begin() {
+    return Err(ALREADY_RUNNING.to_string());
   }
+  run_and_record(state, origin).await
+}
 
-  let download_url = match update_info.download_url {
-    Some(url) => url,
-    None => {
-      return Err(Json(ErrorResponse {
-        error: "Download URL not found".to_string(),
-      }))
-    }
+/// 运行已开始的更新，结果写入进度和更新历史
+async fn run_and_record(state: &AppState, origin: &Origin) -> Result<String, String> {
+  let started_at = Utc::now();
+  let start = Instant::now();
+  let from_version = dataset_version(state);
+  let result = finish(state, run_update(state, origin).await);
+
+  let run = UpdateRun {
+    started_at: started_at.to_rfc3339(),
+    source: origin.source().to_string(),
+    success: result.is_ok(),
+    from_version,
+    to_version: dataset_version(state),
+    duration_ms: start.elapsed().as_millis() as u64,
+    message: match &result {
+      Ok(message) | Err(message) => message.clone(),
+    },
   };
+  if let Err(e) = state.db.push_update_run(&run, HISTORY_SIZE) {
+    tracing::warn!("Failed to record the update: {}", e);
+  }
+  result
+}
 
-  // 下载并解析 tldr-pages
-  tracing::info!("Starting download: {}", download_url);
+/// 已导入的 tldr 版本
+fn dataset_version(state: &AppState) -> Option<String> {
+  state.db.get_metadata().ok().flatten().map(|m| m.version)
+}
 
-  let client = reqwest::Client::new();
-  let response = client
-    .get(&download_url)
-    .header("User-Agent", &state.config.update.user_agent)
-    .send()
-    .await
-    .map_err(|e| {
-      Json(ErrorResponse {
-        error: e.to_string(),
-      })
-    })?;
+/// 按 update.auto_check_interval_hours 定时检查 tldr 新版本，有新版本时下载并重新索引
+pub fn spawn_auto_update(state: Arc<AppState>) {
+  tokio::spawn(async move {
+    // 上次自动更新的时间从更新历史中取得，重启后不会立即重复
+    let source = Origin::auto_update().source();
+    let mut last_run = state
+      .db
+      .list_update_history()
+      .unwrap_or_default()
+      .into_iter()
+      .find(|run| run.source == source)
+      .and_then(|run| DateTime::parse_from_rfc3339(&run.started_at).ok())
+      .map(|time| time.with_timezone(&Utc));
+    tokio::time::sleep(AUTO_UPDATE_STARTUP_DELAY).await;
+
+    loop {
+      // 每次读取当前配置，修改间隔随热重载生效
+      let hours = state.config().update.auto_check_interval_hours;
+      match auto_update_wait(last_run, hours, Utc::now()) {
+        Some(Duration::ZERO) => {
+          last_run = Some(Utc::now());
+          tracing::info!("Checking for a new tldr dataset");
+          match apply_update(&state, &Origin::auto_update()).await {
+            Ok(message) => tracing::info!("Automatic update finished: {}", message),
+            Err(error) => tracing::warn!("Automatic update failed: {}", error),
+          }
+        }
+        wait => {
+          let wait = wait.map_or(AUTO_UPDATE_MAX_SLEEP, |wait| {
+            wait.min(AUTO_UPDATE_MAX_SLEEP)
+          });
+          tokio::time::sleep(wait).await;
+        }
+      }
+    }
+  });
+}
 
-  if !response.status().is_success() {
-    return Err(Json(ErrorResponse {
-      error: format!("Download failed: {}", response.status()),
-    }));
+/// 距下次自动更新的时间；间隔为 0（不自动更新）时为 None
+fn auto_update_wait(
+  last_run: Option<DateTime<Utc>>,
+  hours: u64,
+  now: DateTime<Utc>,
+) -> Option<Duration> {
+  if hours == 0 {
+    return None;
   }
+  let Some(last_run) = last_run else {
+    return Some(Duration::ZERO);
+  };
+  let due = last_run + chrono::Duration::hours(hours as i64);
+  Some((due - now).to_std().unwrap_or(Duration::ZERO))
+}
 
-  let bytes = response.bytes().await.map_err(|e| {
+/// List recent tldr dataset updates, newest first
+///
+/// Covers updates started through the API, by the scheduler and by
+/// `update.auto_check_interval_hours`, including checks that found nothing new.
+#[utoipa::path(
+    get,
+    path = "/api/update/history",
+    responses(
+        (status = 200, description = "Recent updates", body = Vec<UpdateRun>),
+        (status = 500, description = "Storage error", body = ErrorResponse)
+    ),
+    tag = "Update"
+)]
+pub async fn history(
+  State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<UpdateRun>>, Json<ErrorResponse>> {
+  state.db.list_update_history().map(Json).map_err(|e| {
     Json(ErrorResponse {
       error: e.to_string(),
     })
-  })?;
+  })
+}
+
+/// 记录更新的结果
+fn finish(state: &AppState, result: Result<String, String>) -> Result<String, String> {
+  let mut progress = state.update.current();
+  match &result {
+    Ok(message) => {
+      progress.status = "completed".to_string();
+      progress.progress = 100.0;
+      progress.message = message.clone();
+    }
+    Err(error) => {
+      tracing::warn!("Update failed: {}", error);
+      progress.status = "failed".to_string();
+      progress.message = error.clone();
+    }
+  }
+  state.update.set(progress);
+  result
+}
+
+async fn run_update(state: &AppState, origin: &Origin) -> Result<String, String> {
+  // 检查更新
+  let update_info = fetch_update_info(state).await?;
 
-  tracing::info!("Download complete, size: {} bytes", bytes.len());
+  if !update_info.available {
+    return Ok("Already up to date".to_string());
+  }
+
+  let Some(download_url) = update_info.download_url else {
+    return Err("Download URL not found".to_string());
+  };
+
+  // 下载到数据目录中的临时文件（每个请求一个，结束后删除），再从磁盘解析
+  tracing::info!("Starting download: {}", download_url);
+  state.update.phase(
+    "downloading",
+    0.0,
+    format!("Downloading tldr-pages {}", update_info.latest_version),
+  );
+  let config = state.config();
+
+  let download = tempfile::Builder::new()
+    .prefix("update-")
+    .suffix(".download")
+    .tempfile_in(&state.data_dir)
+    .map_err(|e| format!("Failed to create download file: {}", e))?;
+  let size = crate::update::download_to_file(
+    &download_url,
+    &config.update,
+    download.path(),
+    |downloaded, total| state.update.downloaded(downloaded, total),
+  )
+  .await
+  .map_err(|e| e.to_string())?;
+
+  tracing::info!("Download complete, size: {} bytes", size);
+  state
+    .update
+    .phase("parsing", DOWNLOAD_SHARE, "Parsing the archive");
 
   // 解析并导入数据
-  let languages = &state.config.update.languages;
-  let commands = crate::update::parse_tldr_archive(&bytes, languages).map_err(|e| {
-    Json(ErrorResponse {
-      error: e.to_string(),
-    })
-  })?;
+  let languages = &config.update.languages;
+  let parse_config = config.clone();
+  let commands = tokio::task::spawn_blocking(move || {
+    crate::update::parse_tldr_archive_file(
+      download.path(),
+      &parse_config.update.languages,
+      &parse_config.commands,
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+  .map_err(|e| e.to_string())?;
 
   tracing::info!("Parse complete, command count: {}", commands.len());
+  state.update.phase(
+    "indexing",
+    90.0,
+    format!("Indexing {} commands", commands.len()),
+  );
 
   // 保存到数据库
-  state.db.save_commands(&commands).map_err(|e| {
-    Json(ErrorResponse {
-      error: e.to_string(),
-    })
-  })?;
+  state
+    .db
+    .save_commands(&commands)
+    .map_err(|e| e.to_string())?;
 
-  // 重建索引
+  // 索引新的页面，学习的命令不受影响
   let mut search = state.search.write().await;
-  search.index_commands(&commands).map_err(|e| {
-    Json(ErrorResponse {
-      error: e.to_string(),
-    })
-  })?;
+  crate::search::index_commands(&state.db, &mut search, &commands).map_err(|e| e.to_string())?;
 
   // 更新元数据
+  audit::record(
+    &state.db,
+    origin,
+    Action::Update,
+    commands.len(),
+    &update_info.latest_version,
+  );
   let meta = crate::storage::Metadata {
     version: update_info.latest_version,
     command_count: commands.len(),
     last_update: chrono::Utc::now().to_rfc3339(),
-    languages: if languages.is_empty() {
-      vec!["en".to_string(), "zh".to_string()]
-    } else {
-      languages.clone()
-    },
+    languages: crate::update::imported_languages(languages, &commands),
+    source: Some("github".to_string()),
+    selected_languages: Some(languages.clone()),
   };
   let _ = state.db.save_metadata(&meta);
 
-  Ok(Json(UpdateProgress {
-    status: "completed".to_string(),
-    progress: 100.0,
-    message: format!("Successfully updated {} commands", commands.len()),
-  }))
+  Ok(format!("Successfully updated {} commands", commands.len()))
+}
+
+#[cfg(test)]
+mod tests {
+  use futures_util::StreamExt;
+
+  use super::*;
+
+  #[test]
+  fn test_tracker() {
+    let tracker = UpdateTracker::default();
+    assert_eq!(tracker.current().status, "idle");
+    assert!(tracker.begin());
+    // 同一时间只有一次更新
+    assert!(!tracker.begin());
+
+    tracker.phase("downloading", 0.0, "Downloading");
+    tracker.downloaded(500, Some(1000));
+    let progress = tracker.current();
+    assert_eq!(progress.progress, DOWNLOAD_SHARE / 2.0);
+    assert_eq!((progress.downloaded, progress.total), (500, Some(1000)));
+    tracker.downloaded(700, None);
+    assert_eq!(tracker.current().progress, 0.0);
+
+    tracker.phase("indexing", 90.0, "Indexing");
+    assert_eq!(tracker.current().downloaded, 700);
+    tracker.set(UpdateProgress::new("completed", 100.0, "Done"));
+    assert!(tracker.begin());
+    assert_eq!(tracker.current().downloaded, 0);
+  }
+
+  #[test]
+  fn test_auto_update_wait() {
+    let now = Utc::now();
+    assert_eq!(auto_update_wait(None, 0, now), None);
+    assert_eq!(auto_update_wait(None, 6, now), Some(Duration::ZERO));
+    let last_run = now - chrono::Duration::hours(2);
+    assert_eq!(
+      auto_update_wait(Some(last_run), 6, now),
+      Some(Duration::from_secs(4 * 3600))
+    );
+    assert_eq!(
+      auto_update_wait(Some(last_run), 1, now),
+      Some(Duration::ZERO)
+    );
+  }
+
+  #[test]
+  fn test_progress_stream() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_all()
+      .build()
+      .unwrap();
+    runtime.block_on(async {
+      // 更新已结束时只发送最终状态
+      let tracker = UpdateTracker::default();
+      tracker.set(UpdateProgress::new("failed", 0.0, "Network error"));
+      assert_eq!(progress_stream(tracker.subscribe()).count().await, 1);
+
+      // 空闲时等待更新开始，结束后关闭
+      let tracker = Arc::new(UpdateTracker::default());
+      let stream = progress_stream(tracker.subscribe());
+      let writer = tracker.clone();
+      let task = tokio::spawn(async move {
+        for status in ["checking", "downloading", "completed"] {
+          tokio::time::sleep(Duration::from_millis(10)).await;
+          writer.set(UpdateProgress::new(status, 0.0, ""));
+        }
+      });
+      assert_eq!(stream.count().await, 4);
+      task.await.unwrap();
+    });
+  }
 }
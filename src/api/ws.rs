@@ -0,0 +1,237 @@
+//! 实时搜索的 WebSocket 接口（/ws/search）
+//!
+//! 客户端每次输入变化时发送一条文本消息：查询字符串，或与 /api/search 参数相同的 JSON 对象
+//! （如 `{"q": "tar", "lang": "zh", "fuzzy": true}`）。服务端对每条查询回复一条结果，
+//! 附带查询本身以便客户端对应；新查询到达时，尚未完成的旧查询不再回复。
+//! 实时搜索的中间输入不计入使用统计。启用 server.rate_limit_per_minute 时每条查询与 HTTP 请求
+//! 一样消耗客户端的令牌，没有令牌时回复错误而不搜索。
+//! 握手、分片、ping/pong 和关闭由 axum 的 WebSocket 支持处理，只接受文本消息。
+
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::ws::rejection::WebSocketUpgradeRejection;
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use serde::Serialize;
+
+use super::auth::CurrentUser;
+use super::search::{run_search, ErrorResponse, SearchQuery};
+use crate::search::SearchResponse;
+use crate::AppState;
+
+/// 对一条查询的回复
+#[derive(Debug, Serialize)]
+struct Reply {
+  /// 收到的查询
+  query: String,
+  #[serde(flatten, skip_serializing_if = "Option::is_none")]
+  response: Option<SearchResponse>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+}
+
+type PendingSearch = Pin<Box<dyn Future<Output = Reply> + Send>>;
+
+/// Live search over WebSocket: send queries as text messages, receive a result list for each
+pub async fn search(
+  State(state): State<Arc<AppState>>,
+  client: Option<ConnectInfo<SocketAddr>>,
+  user: Option<Extension<CurrentUser>>,
+  upgrade: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+  let Ok(upgrade) = upgrade else {
+    return (
+      StatusCode::UPGRADE_REQUIRED,
+      [(header::UPGRADE, "websocket")],
+      Json(ErrorResponse {
+        error: "Expected a WebSocket upgrade (version 13)".to_string(),
+      }),
+    )
+      .into_response();
+  };
+  let max_size = state.config().server.max_json_size;
+  let user = user.map(|Extension(user)| user);
+  let client = client.map(|ConnectInfo(addr)| addr.ip());
+  upgrade
+    .max_message_size(max_size)
+    .max_frame_size(max_size)
+    .on_upgrade(move |socket| serve(state, client, user, socket))
+}
+
+/// 处理一个连接；ping 的回复和关闭握手由 axum 完成
+async fn serve(
+  state: Arc<AppState>,
+  client: Option<IpAddr>,
+  user: Option<CurrentUser>,
+  mut socket: WebSocket,
+) {
+  let mut pending: Option<PendingSearch> = None;
+  let result: Result<(), axum::Error> = async {
+    loop {
+      tokio::select! {
+        // 先处理已收到的消息，输入过程中较早的查询不再回复
+        biased;
+        message = socket.recv() => match message {
+          Some(Ok(Message::Text(text))) => {
+            if let Some(reply) = rate_limited(&state, client, &text) {
+              let text = serde_json::to_string(&reply).map_err(axum::Error::new)?;
+              socket.send(Message::Text(text)).await?;
+              continue;
+            }
+            // 新查询取代未完成的旧查询
+            pending = Some(Box::pin(answer(state.clone(), user.clone(), text)));
+          }
+          Some(Ok(Message::Binary(_))) => {
+            socket
+              .send(Message::Close(Some(CloseFrame {
+                code: close_code::UNSUPPORTED,
+                reason: "Only text messages are supported".into(),
+              })))
+              .await?;
+            return Ok(());
+          }
+          Some(Ok(Message::Ping(_) | Message::Pong(_))) => {}
+          Some(Ok(Message::Close(_))) | None => return Ok(()),
+          // 消息过大或不符合协议
+          Some(Err(e)) => return Err(e),
+        },
+        reply = async { pending.as_mut().expect("guarded by if").await }, if pending.is_some() => {
+          pending = None;
+          let text = serde_json::to_string(&reply).map_err(axum::Error::new)?;
+          socket.send(Message::Text(text)).await?;
+        }
+      }
+    }
+  }
+  .await;
+  if let Err(e) = result {
+    tracing::debug!("WebSocket connection closed: {}", e);
+  }
+}
+
+/// 为一条查询取客户端的令牌；没有令牌时返回错误回复
+fn rate_limited(state: &AppState, client: Option<IpAddr>, text: &str) -> Option<Reply> {
+  let per_minute = state.config().server.rate_limit_per_minute;
+  let ip = client.filter(|_| per_minute > 0)?;
+  let wait = state
+    .limiter
+    .acquire(ip, per_minute, Instant::now())
+    .err()?;
+  tracing::debug!("WebSocket rate limit exceeded for {}", ip);
+  Some(Reply {
+    query: text.to_string(),
+    response: None,
+    error: Some(format!(
+      "Rate limit exceeded ({} requests per minute), retry in {}s",
+      per_minute,
+      wait.as_secs_f64().ceil().max(1.0) as u64
+    )),
+  })
+}
+
+/// 搜索一条消息中的查询
+async fn answer(state: Arc<AppState>, user: Option<CurrentUser>, text: String) -> Reply {
+  let params = if text.trim_start().starts_with('{') {
+    match serde_json::from_str::<SearchQuery>(&text) {
+      Ok(params) => params,
+      Err(e) => {
+        return Reply {
+          query: text,
+          response: None,
+          error: Some(format!("Invalid query: {}", e)),
+        }
+      }
+    }
+  } else {
+    SearchQuery {
+      q: text,
+      lang: None,
+      platform: None,
+      limit: None,
+      fuzzy: false,
+    }
+  };
+
+  // 清空输入时不必搜索
+  let result = if params.q.trim().is_empty() {
+    Ok(SearchResponse {
+      total: 0,
+      lang: params
+        .lang
+        .clone()
+        .unwrap_or_else(|| state.config().search.default_lang.clone()),
+      results: Vec::new(),
+      examples: Vec::new(),
+      suggestions: Vec::new(),
+      took_ms: 0,
+    })
+  } else {
+    run_search(&state, user.as_ref(), &params, false).await
+  };
+  let (response, error) = match result {
+    Ok(response) => (Some(response), None),
+    Err(error) => (None, Some(error)),
+  };
+  Reply {
+    query: params.q,
+    response,
+    error,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::tests::state;
+
+  #[tokio::test]
+  async fn test_answer() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+
+    let reply = answer(state.clone(), None, "  ".to_string()).await;
+    assert_eq!(reply.response.unwrap().total, 0);
+    assert!(reply.error.is_none());
+
+    let reply = answer(
+      state.clone(),
+      None,
+      r#"{"q": "tar", "lang": "zh"}"#.to_string(),
+    )
+    .await;
+    assert_eq!(reply.query, "tar");
+    assert_eq!(reply.response.unwrap().lang, "zh");
+
+    let reply = answer(state, None, "{bad".to_string()).await;
+    assert_eq!(reply.query, "{bad");
+    assert!(reply.error.unwrap().starts_with("Invalid query"));
+  }
+
+  #[test]
+  fn test_rate_limited() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let state = state(temp_dir.path());
+    let client = Some("192.0.2.1".parse().unwrap());
+    // 未启用限流
+    assert!(rate_limited(&state, client, "tar").is_none());
+
+    let mut config = (*state.config()).clone();
+    config.server.rate_limit_per_minute = 2;
+    state.set_config(config);
+    assert!(rate_limited(&state, client, "t").is_none());
+    assert!(rate_limited(&state, client, "ta").is_none());
+    // 同一连接中的每条查询都消耗令牌
+    let reply = rate_limited(&state, client, "tar").unwrap();
+    assert_eq!(reply.query, "tar");
+    assert!(reply.error.unwrap().starts_with("Rate limit exceeded"));
+    // 没有连接信息时不限流
+    assert!(rate_limited(&state, None, "tar").is_none());
+  }
+}
@@ -0,0 +1,181 @@
+//! 数据修改的审计日志（rtfm audit、/api/audit）
+//!
+//! 导入、学习、更新、删除、重置、恢复和同步在数据库的 audit 表中追加一条记录：时间、来源（cli、api 及客户端地址、
+//! scheduler、监视目录的 watch）、多用户模式下的用户、命令数和说明。记录只追加不修改，也不随备份导出；
+//! rtfm reset 和 rtfm restore 替换数据库文件时保留原有记录。
+
+use std::net::SocketAddr;
+
+use crate::storage::{AuditEntry, Database, StorageError};
+
+/// 修改数据的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+  Import,
+  Learn,
+  Update,
+  Reset,
+  Restore,
+  Sync,
+  Delete,
+}
+
+impl Action {
+  pub const ALL: [Action; 7] = [
+    Action::Import,
+    Action::Learn,
+    Action::Update,
+    Action::Reset,
+    Action::Restore,
+    Action::Sync,
+    Action::Delete,
+  ];
+
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Action::Import => "import",
+      Action::Learn => "learn",
+      Action::Update => "update",
+      Action::Reset => "reset",
+      Action::Restore => "restore",
+      Action::Sync => "sync",
+      Action::Delete => "delete",
+    }
+  }
+
+  pub fn parse(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|a| a.as_str() == name)
+  }
+}
+
+/// 修改的来源
+#[derive(Debug, Clone)]
+pub struct Origin {
+  source: &'static str,
+  client: Option<String>,
+  user: Option<String>,
+}
+
+impl Origin {
+  pub fn cli() -> Self {
+    Self {
+      source: "cli",
+      client: None,
+      user: None,
+    }
+  }
+
+  /// serve 模式的定时任务
+  pub fn scheduler() -> Self {
+    Self {
+      source: "scheduler",
+      client: None,
+      user: None,
+    }
+  }
+
+  /// serve 模式的自动更新（update.auto_check_interval_hours）
+  pub fn auto_update() -> Self {
+    Self {
+      source: "auto-update",
+      client: None,
+      user: None,
+    }
+  }
+
+  /// 监视目录的自动导入（rtfm import --watch、import.watch_dirs）
+  pub fn watcher() -> Self {
+    Self {
+      source: "watch",
+      client: None,
+      user: None,
+    }
+  }
+
+  /// 来源名称（cli、api、scheduler 等）
+  pub fn source(&self) -> &'static str {
+    self.source
+  }
+
+  /// API 请求；client 为对端地址，user 为多用户模式下验证通过的用户
+  pub fn api(client: Option<SocketAddr>, user: Option<&str>) -> Self {
+    Self {
+      source: "api",
+      client: client.map(|addr| addr.ip().to_string()),
+      user: user.map(str::to_string),
+    }
+  }
+}
+
+/// 追加一条记录；写入失败只记录日志，不影响修改本身
+pub fn record(
+  db: &Database,
+  origin: &Origin,
+  action: Action,
+  count: usize,
+  detail: impl Into<String>,
+) {
+  let entry = AuditEntry {
+    id: 0,
+    timestamp: chrono::Utc::now().to_rfc3339(),
+    action: action.as_str().to_string(),
+    source: origin.source.to_string(),
+    client: origin.client.clone(),
+    user: origin.user.clone(),
+    count,
+    detail: detail.into(),
+  };
+  if let Err(e) = db.append_audit(&entry) {
+    tracing::warn!("Failed to record audit entry: {}", e);
+  }
+}
+
+/// 把旧数据库的记录按原顺序追加到新数据库，返回条数
+pub fn carry_over(old: &Database, new: &Database) -> Result<usize, StorageError> {
+  let entries = old.list_audit(usize::MAX, None)?;
+  for entry in entries.iter().rev() {
+    new.append_audit(entry)?;
+  }
+  Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_record_and_carry_over() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let old = Database::open(&temp_dir.path().join("old.redb")).unwrap();
+    let client: SocketAddr = "192.0.2.7:51234".parse().unwrap();
+    record(&old, &Origin::cli(), Action::Import, 12, "pages.zip");
+    record(
+      &old,
+      &Origin::api(Some(client), Some("alice")),
+      Action::Learn,
+      1,
+      "tar",
+    );
+
+    let entries = old.list_audit(10, None).unwrap();
+    assert_eq!(entries[0].source, "api");
+    assert_eq!(entries[0].client.as_deref(), Some("192.0.2.7"));
+    assert_eq!(entries[0].user.as_deref(), Some("alice"));
+    assert_eq!(entries[1].action, "import");
+
+    let new = Database::open(&temp_dir.path().join("new.redb")).unwrap();
+    assert_eq!(carry_over(&old, &new).unwrap(), 2);
+    record(&new, &Origin::cli(), Action::Reset, 0, "");
+    let actions: Vec<String> = new
+      .list_audit(10, None)
+      .unwrap()
+      .into_iter()
+      .map(|e| e.action)
+      .collect();
+    assert_eq!(actions, ["reset", "learn", "import"]);
+
+    assert_eq!(Action::parse("sync"), Some(Action::Sync));
+    assert_eq!(Action::parse("delete"), Some(Action::Delete));
+    assert_eq!(Action::parse("remove"), None);
+  }
+}
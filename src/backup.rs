@@ -0,0 +1,495 @@
+//! 备份归档（rtfm backup、serve 模式的定时备份）
+//!
+//! 归档为 tar.gz，包含数据库、索引目录、配置文件、README 和元数据，rtfm restore 与 /api/restore 读取同样的格式。
+//! 定时备份写入备份目录，文件名带时间戳，超出 scheduler.backup_keep 的旧备份会被删除。
+//! 只含用户数据的备份（--user-only）不含 tldr 数据集：归档中只有 user-data.json，恢复时合并到现有数据。
+
+use std::path::{Component, Path, PathBuf};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tar::Builder;
+
+use crate::config::AppConfig;
+use crate::storage::{Alias, Command, Database, Favorite, Note};
+
+/// 用户数据在归档中的文件名
+pub const USER_DATA_FILE: &str = "user-data.json";
+
+/// 用户数据格式的版本
+const USER_DATA_VERSION: u32 = 1;
+
+/// 定时备份的文件名前缀
+const ARCHIVE_PREFIX: &str = "rtfm-backup-";
+
+/// 定时备份的文件名后缀
+const ARCHIVE_SUFFIX: &str = ".tar.gz";
+
+/// 写入备份归档，返回加入的条目（用于显示）
+/// db_path 和 index_path 可以是快照，归档中总是使用配置的文件名
+pub fn write_archive(
+  output: &Path,
+  db_path: &Path,
+  index_path: &Path,
+  data_dir: &Path,
+  config: &AppConfig,
+) -> anyhow::Result<Vec<String>> {
+  let file = std::fs::File::create(output)?;
+  let enc = GzEncoder::new(file, Compression::default());
+  let mut tar = Builder::new(enc);
+  let mut entries = Vec::new();
+
+  // 添加数据库文件
+  tar.append_path_with_name(db_path, &config.storage.db_filename)?;
+  entries.push(config.storage.db_filename.clone());
+
+  // 添加索引目录
+  if index_path.exists() {
+    tar.append_dir_all(&config.storage.index_dirname, index_path)?;
+    entries.push(format!("{}/", config.storage.index_dirname));
+  }
+
+  // 添加配置文件（从数据目录）
+  let config_path = data_dir.join("config.toml");
+  if config_path.exists() {
+    tar.append_path_with_name(&config_path, "config.toml")?;
+    entries.push("config.toml".to_string());
+  } else {
+    // 如果数据目录没有配置文件，导出当前配置
+    append_bytes(&mut tar, "config.toml", config.to_toml().as_bytes())?;
+    entries.push("config.toml (current config)".to_string());
+  }
+
+  // 创建 README
+  append_bytes(&mut tar, "README.md", readme().as_bytes())?;
+
+  // 添加元数据文件
+  let db = Database::open(db_path)?;
+  if let Ok(Some(meta)) = db.get_metadata() {
+    let meta_json = serde_json::to_string_pretty(&meta)?;
+    append_bytes(&mut tar, "metadata.json", meta_json.as_bytes())?;
+    entries.push("metadata.json".to_string());
+  }
+
+  tar.into_inner()?.finish()?;
+  Ok(entries)
+}
+
+fn append_bytes<W: std::io::Write>(
+  tar: &mut Builder<W>,
+  path: &str,
+  bytes: &[u8],
+) -> std::io::Result<()> {
+  let mut header = tar::Header::new_gnu();
+  header.set_path(path)?;
+  header.set_size(bytes.len() as u64);
+  header.set_mode(0o644);
+  header.set_cksum();
+  tar.append(&header, bytes)
+}
+
+/// 备份 README
+fn readme() -> String {
+  format!(
+    r#"# RTFM Backup
+
+This archive contains backup data from RTFM (Read The F***ing Manual).
+
+## Contents
+
+- `data.redb` - Command database (redb format)
+- `index/` - Full-text search index (Tantivy format)
+- `config.toml` - Application configuration
+- `metadata.json` - Backup metadata (version, command count, etc.)
+
+## Restore
+
+To restore this backup on another machine:
+
+```bash
+rtfm restore rtfm-backup.tar.gz
+```
+
+Options:
+- `--merge` - Merge the commands into the existing data instead of replacing it
+- `--only db,index,config` - Restore only some of the parts
+
+## Version Info
+
+- Backup date: {}
+- RTFM version: {}
+
+## Data Format
+
+The database uses redb (Rust embedded database) format.
+The search index uses Tantivy format.
+
+These files are cross-platform compatible (Windows/Linux/macOS).
+"#,
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+    env!("CARGO_PKG_VERSION")
+  )
+}
+
+/// Personal data without the tldr dataset: learned commands, notes, favorites and aliases
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserData {
+  /// Format version
+  pub version: u32,
+  /// rtfm version that wrote the file
+  #[serde(default)]
+  pub rtfm_version: String,
+  /// When it was exported (RFC 3339)
+  #[serde(default)]
+  pub exported_at: String,
+  /// Locally learned and imported commands (lang "local"), tags included in their descriptions
+  #[serde(default)]
+  pub commands: Vec<Command>,
+  /// Personal notes
+  #[serde(default)]
+  pub notes: Vec<Note>,
+  /// Favorite commands
+  #[serde(default)]
+  pub favorites: Vec<Favorite>,
+  /// Command aliases
+  #[serde(default)]
+  pub aliases: Vec<Alias>,
+}
+
+impl UserData {
+  /// 从数据库收集用户数据
+  pub fn collect(db: &Database) -> anyhow::Result<Self> {
+    Ok(Self {
+      version: USER_DATA_VERSION,
+      rtfm_version: env!("CARGO_PKG_VERSION").to_string(),
+      exported_at: chrono::Utc::now().to_rfc3339(),
+      commands: db.get_all_commands("local")?,
+      notes: db.list_notes()?,
+      favorites: db.list_favorites()?,
+      aliases: db.list_aliases()?,
+    })
+  }
+
+  /// 读取导出的 JSON
+  pub fn from_json(json: &str) -> anyhow::Result<Self> {
+    let data: Self = serde_json::from_str(json)?;
+    if data.version > USER_DATA_VERSION {
+      anyhow::bail!(
+        "User data format {} is newer than this rtfm supports ({})",
+        data.version,
+        USER_DATA_VERSION
+      );
+    }
+    Ok(data)
+  }
+
+  /// 条目总数
+  pub fn count(&self) -> usize {
+    self.commands.len() + self.notes.len() + self.favorites.len() + self.aliases.len()
+  }
+
+  /// 合并到数据库：命令和同名的笔记、别名被替换，收藏只增加；其余数据不变
+  /// 返回笔记改变了的命令名，它们需要重新索引
+  pub fn restore(&self, db: &Database) -> anyhow::Result<Vec<String>> {
+    db.save_commands(&self.commands)?;
+    let mut noted = Vec::new();
+    for note in &self.notes {
+      if db.set_note(&note.name, &note.text)? {
+        noted.push(note.name.clone());
+      }
+    }
+    for favorite in &self.favorites {
+      db.add_favorite(&favorite.name)?;
+    }
+    for alias in &self.aliases {
+      db.set_alias(&alias.name, &alias.target)?;
+    }
+    Ok(noted)
+  }
+}
+
+/// 写入只含用户数据的备份归档
+pub fn write_user_archive(output: &Path, data: &UserData) -> anyhow::Result<()> {
+  let file = std::fs::File::create(output)?;
+  let enc = GzEncoder::new(file, Compression::default());
+  let mut tar = Builder::new(enc);
+  append_bytes(
+    &mut tar,
+    USER_DATA_FILE,
+    serde_json::to_string_pretty(data)?.as_bytes(),
+  )?;
+  tar.into_inner()?.finish()?;
+  Ok(())
+}
+
+/// 从归档中解压出的各部分
+#[derive(Debug, Default)]
+pub struct Extracted {
+  pub db: Option<PathBuf>,
+  pub index: Option<PathBuf>,
+  pub config: Option<PathBuf>,
+  /// 只含用户数据的备份
+  pub user_data: Option<PathBuf>,
+}
+
+/// 把归档中的数据库、索引目录和配置文件解压到 dest（README 和元数据只用于说明，不解压）
+/// 备份时的数据库文件名和索引目录名可能与当前配置不同：任意 .redb 文件视为数据库，任意目录视为索引
+/// 只解压普通文件和目录：符号链接和硬链接可能指向 dest 之外，之后的条目会经由链接写到外面
+pub fn extract(archive: &Path, dest: &Path) -> anyhow::Result<Extracted> {
+  let file = std::fs::File::open(archive)?;
+  let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+  let mut extracted = Extracted::default();
+  let db_path = dest.join("data.redb");
+  let index_path = dest.join("index");
+  let config_path = dest.join("config.toml");
+  let user_data_path = dest.join(USER_DATA_FILE);
+
+  for entry in archive.entries()? {
+    let mut entry = entry?;
+    let path = entry.path()?.into_owned();
+    // 不解压到目标目录之外
+    let mut parts = Vec::new();
+    for component in path.components() {
+      match component {
+        Component::Normal(part) => parts.push(part.to_os_string()),
+        Component::CurDir => {}
+        _ => anyhow::bail!("Unsafe path in backup archive: {}", path.display()),
+      }
+    }
+    let Some((first, rest)) = parts.split_first() else {
+      continue;
+    };
+
+    // 数据库文件可能是稀疏文件，归档中为 GNU sparse 条目
+    let entry_type = entry.header().entry_type();
+    if !entry_type.is_file() && !entry_type.is_gnu_sparse() && !entry_type.is_dir() {
+      tracing::warn!(
+        "Skipping {} in backup archive: not a regular file or directory",
+        path.display()
+      );
+      continue;
+    }
+    let is_dir = entry_type.is_dir();
+    if !rest.is_empty() || is_dir {
+      let target: PathBuf = std::iter::once(index_path.as_os_str())
+        .chain(rest.iter().map(|p| p.as_os_str()))
+        .collect();
+      if is_dir {
+        std::fs::create_dir_all(&target)?;
+      } else {
+        if let Some(parent) = target.parent() {
+          std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&target)?;
+      }
+      extracted.index = Some(index_path.clone());
+    } else if first == "config.toml" {
+      entry.unpack(&config_path)?;
+      extracted.config = Some(config_path.clone());
+    } else if first == USER_DATA_FILE {
+      entry.unpack(&user_data_path)?;
+      extracted.user_data = Some(user_data_path.clone());
+    } else if Path::new(first).extension().is_some_and(|e| e == "redb") {
+      entry.unpack(&db_path)?;
+      extracted.db = Some(db_path.clone());
+    }
+  }
+  Ok(extracted)
+}
+
+/// 定时备份的路径：<dir>/rtfm-backup-<时间>.tar.gz
+pub fn archive_path(dir: &Path, time: chrono::DateTime<chrono::Local>) -> PathBuf {
+  dir.join(format!(
+    "{}{}{}",
+    ARCHIVE_PREFIX,
+    time.format("%Y%m%d-%H%M%S"),
+    ARCHIVE_SUFFIX
+  ))
+}
+
+/// 只保留最新的 keep 个定时备份（keep 为 0 时全部保留），返回删除的文件数
+/// 文件名中的时间戳按字典序即为时间顺序；其他文件不受影响
+pub fn rotate(dir: &Path, keep: usize) -> std::io::Result<usize> {
+  if keep == 0 {
+    return Ok(0);
+  }
+  let mut archives: Vec<PathBuf> = std::fs::read_dir(dir)?
+    .filter_map(|entry| entry.ok().map(|e| e.path()))
+    .filter(|path| {
+      path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with(ARCHIVE_PREFIX) && n.ends_with(ARCHIVE_SUFFIX))
+    })
+    .collect();
+  archives.sort();
+  let excess = archives.len().saturating_sub(keep);
+  for path in &archives[..excess] {
+    std::fs::remove_file(path)?;
+  }
+  Ok(excess)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn test_extract() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let data_dir = temp_dir.path().join("data");
+    std::fs::create_dir_all(data_dir.join("search")).unwrap();
+    std::fs::write(data_dir.join("search/meta.json"), b"{}").unwrap();
+    let db_path = data_dir.join("commands.redb");
+    Database::open(&db_path).unwrap();
+    let mut config = AppConfig::default();
+    config.storage.db_filename = "commands.redb".to_string();
+    config.storage.index_dirname = "search".to_string();
+
+    let archive = temp_dir.path().join("backup.tar.gz");
+    write_archive(
+      &archive,
+      &db_path,
+      &data_dir.join("search"),
+      &data_dir,
+      &config,
+    )
+    .unwrap();
+
+    let dest = temp_dir.path().join("restore");
+    std::fs::create_dir_all(&dest).unwrap();
+    let extracted = extract(&archive, &dest).unwrap();
+    assert!(extracted.db.unwrap().exists());
+    assert!(extracted.config.unwrap().exists());
+    assert!(extracted.index.unwrap().join("meta.json").exists());
+    assert!(!dest.join("README.md").exists());
+  }
+
+  #[test]
+  fn test_extract_skips_links() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let outside = temp_dir.path().join("outside");
+    std::fs::create_dir_all(&outside).unwrap();
+
+    // 先放一个指向目标目录之外的符号链接，再经由它写入文件
+    let archive = temp_dir.path().join("evil.tar.gz");
+    let mut builder = Builder::new(GzEncoder::new(
+      std::fs::File::create(&archive).unwrap(),
+      Compression::default(),
+    ));
+    let mut link = tar::Header::new_gnu();
+    link.set_entry_type(tar::EntryType::Symlink);
+    link.set_size(0);
+    link.set_mode(0o777);
+    builder
+      .append_link(&mut link, "index/evil", &outside)
+      .unwrap();
+    let mut hard = tar::Header::new_gnu();
+    hard.set_entry_type(tar::EntryType::Link);
+    hard.set_size(0);
+    hard.set_mode(0o644);
+    builder
+      .append_link(&mut hard, "data.redb", outside.join("passwd"))
+      .unwrap();
+    let content = b"owned";
+    let mut file = tar::Header::new_gnu();
+    file.set_size(content.len() as u64);
+    file.set_mode(0o644);
+    file.set_cksum();
+    builder
+      .append_data(&mut file, "index/evil/passwd", &content[..])
+      .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let dest = temp_dir.path().join("restore");
+    std::fs::create_dir_all(&dest).unwrap();
+    let extracted = extract(&archive, &dest).unwrap();
+    assert!(!outside.join("passwd").exists());
+    assert!(extracted.db.is_none());
+    let written = dest.join("index/evil/passwd");
+    assert!(!std::fs::symlink_metadata(dest.join("index/evil"))
+      .unwrap()
+      .file_type()
+      .is_symlink());
+    assert_eq!(std::fs::read(written).unwrap(), content);
+  }
+
+  #[test]
+  fn test_user_data() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("data.redb")).unwrap();
+    let command = |name: &str, lang: &str| Command {
+      name: name.to_string(),
+      description: String::new(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: lang.to_string(),
+      examples: Vec::new(),
+      content: String::new(),
+      sections: Vec::new(),
+      options: Vec::new(),
+      source_info: None,
+      notes: None,
+      schema: crate::storage::COMMAND_SCHEMA,
+    };
+    db.save_commands(&[command("tar", "en"), command("mytool", "local")])
+      .unwrap();
+    db.set_note("tar", "use -z for gzip").unwrap();
+    db.add_favorite("tar").unwrap();
+    db.set_alias("t", "tar").unwrap();
+
+    // 只有本地命令，不含 tldr 页面
+    let data = UserData::collect(&db).unwrap();
+    assert_eq!(data.commands.len(), 1);
+    assert_eq!(data.commands[0].name, "mytool");
+    assert_eq!(data.count(), 4);
+
+    let archive = temp_dir.path().join("user.tar.gz");
+    write_user_archive(&archive, &data).unwrap();
+    let dest = temp_dir.path().join("restore");
+    std::fs::create_dir_all(&dest).unwrap();
+    let extracted = extract(&archive, &dest).unwrap();
+    assert!(extracted.db.is_none());
+    let json = std::fs::read_to_string(extracted.user_data.unwrap()).unwrap();
+    let restored = UserData::from_json(&json).unwrap();
+
+    let other = Database::open(&temp_dir.path().join("other.redb")).unwrap();
+    other.set_note("ls", "keep me").unwrap();
+    assert_eq!(restored.restore(&other).unwrap(), ["tar"]);
+    assert!(other.get_command("mytool", "local").unwrap().is_some());
+    assert_eq!(other.get_note("ls").unwrap().as_deref(), Some("keep me"));
+    assert_eq!(other.list_favorites().unwrap()[0].name, "tar");
+    assert_eq!(other.get_alias("t").unwrap().as_deref(), Some("tar"));
+  }
+
+  #[test]
+  fn test_rotate() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    for day in 1..=4 {
+      let time = chrono::Local
+        .with_ymd_and_hms(2024, 1, day, 4, 0, 0)
+        .unwrap();
+      std::fs::write(archive_path(dir, time), b"").unwrap();
+    }
+    std::fs::write(dir.join("manual.tar.gz"), b"").unwrap();
+
+    assert_eq!(rotate(dir, 0).unwrap(), 0);
+    assert_eq!(rotate(dir, 2).unwrap(), 2);
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+      .unwrap()
+      .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+      .collect();
+    names.sort();
+    assert_eq!(
+      names,
+      [
+        "manual.tar.gz",
+        "rtfm-backup-20240103-040000.tar.gz",
+        "rtfm-backup-20240104-040000.tar.gz"
+      ]
+    );
+  }
+}
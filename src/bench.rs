@@ -0,0 +1,462 @@
+//! 性能基准（rtfm bench）
+//!
+//! 用本机的数据测量：数据库全量读取和按名查询、建立全文索引、冷/热搜索延迟、学习时的帮助解析速度。
+//! 索引建在数据目录下的临时目录中，不修改现有索引；搜索关闭结果缓存，测的是实际查询。
+//! 冷搜索为新打开的索引上每个查询的第一次执行，热搜索为之后的重复执行。
+//! 报告可以输出为 JSON，便于保存后对比不同版本或配置。
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::config::SearchConfig;
+use crate::i18n::t;
+use crate::search::{Platform, SearchEngine};
+use crate::storage::{Command, Database};
+
+/// 基准的参数
+#[derive(Debug, Clone)]
+pub struct Options {
+  /// 搜索的查询数
+  pub queries: usize,
+  /// 热搜索和按名查询的重复次数
+  pub iterations: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+  pub version: String,
+  pub os: String,
+  pub arch: String,
+  pub cpus: usize,
+  pub commands: usize,
+  /// 所有命令内容的总字节数
+  pub content_bytes: usize,
+  pub database: DatabaseStats,
+  pub index: IndexStats,
+  pub search: SearchStats,
+  /// 没有学习过的命令时为 None
+  pub learn: Option<LearnStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseStats {
+  pub scan_ms: f64,
+  pub scan_per_sec: f64,
+  pub lookup: Latency,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStats {
+  pub build_ms: f64,
+  pub build_per_sec: f64,
+  pub size_bytes: u64,
+  pub open_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchStats {
+  pub queries: usize,
+  pub cold: Latency,
+  pub warm: Latency,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LearnStats {
+  pub pages: usize,
+  pub bytes: usize,
+  pub ms: f64,
+  pub pages_per_sec: f64,
+  pub mb_per_sec: f64,
+}
+
+/// 一组耗时的统计，单位微秒
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Latency {
+  pub samples: usize,
+  pub mean_us: f64,
+  pub p50_us: f64,
+  pub p95_us: f64,
+  pub max_us: f64,
+}
+
+impl Latency {
+  pub fn from_samples(samples: &[Duration]) -> Self {
+    if samples.is_empty() {
+      return Self::default();
+    }
+    let mut us: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1e6).collect();
+    us.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f64| us[((us.len() - 1) as f64 * p).round() as usize];
+    Self {
+      samples: us.len(),
+      mean_us: us.iter().sum::<f64>() / us.len() as f64,
+      p50_us: percentile(0.5),
+      p95_us: percentile(0.95),
+      max_us: us[us.len() - 1],
+    }
+  }
+}
+
+/// 基准的阶段，用于显示进度
+#[derive(Debug, Clone, Copy)]
+pub enum Stage {
+  Database,
+  Index,
+  /// 查询数
+  Search(usize),
+  Learn,
+}
+
+/// 运行全部基准；data_dir 用于存放临时索引
+pub fn run(
+  db: &Database,
+  data_dir: &Path,
+  config: &SearchConfig,
+  options: &Options,
+  mut progress: impl FnMut(Stage),
+) -> anyhow::Result<Report> {
+  progress(Stage::Database);
+  let start = Instant::now();
+  let commands = db.list_all_commands()?;
+  let scan = start.elapsed();
+  if commands.is_empty() {
+    anyhow::bail!("The database has no commands to benchmark");
+  }
+  let sample = spread(&commands, options.queries);
+  let mut lookups = Vec::new();
+  for _ in 0..options.iterations {
+    for cmd in &sample {
+      let start = Instant::now();
+      db.get_command(&cmd.name, &cmd.lang)?;
+      lookups.push(start.elapsed());
+    }
+  }
+
+  progress(Stage::Index);
+  let dir = tempfile::Builder::new()
+    .prefix("bench-index")
+    .tempdir_in(data_dir)?;
+  let config = SearchConfig {
+    result_cache_size: 0,
+    ..config.clone()
+  };
+  let start = Instant::now();
+  SearchEngine::open(dir.path(), &config)?.rebuild(&commands)?;
+  let build = start.elapsed();
+  let start = Instant::now();
+  let engine = SearchEngine::open(dir.path(), &config)?;
+  let open = start.elapsed();
+
+  let platform = Platform::parse(&config.platform);
+  let queries = queries(&commands, options.queries);
+  progress(Stage::Search(queries.len()));
+  let mut cold = Vec::new();
+  for query in &queries {
+    let start = Instant::now();
+    engine.search(query, None, &platform, config.default_limit)?;
+    cold.push(start.elapsed());
+  }
+  let mut warm = Vec::new();
+  for _ in 0..options.iterations {
+    for query in &queries {
+      let start = Instant::now();
+      engine.search(query, None, &platform, config.default_limit)?;
+      warm.push(start.elapsed());
+    }
+  }
+  drop(engine);
+  let size_bytes = dir_size(dir.path());
+
+  progress(Stage::Learn);
+  let learn = learn(&commands);
+
+  Ok(Report {
+    version: env!("CARGO_PKG_VERSION").to_string(),
+    os: std::env::consts::OS.to_string(),
+    arch: std::env::consts::ARCH.to_string(),
+    cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+    commands: commands.len(),
+    content_bytes: commands.iter().map(|c| c.content.len()).sum(),
+    database: DatabaseStats {
+      scan_ms: millis(scan),
+      scan_per_sec: rate(commands.len(), scan),
+      lookup: Latency::from_samples(&lookups),
+    },
+    index: IndexStats {
+      build_ms: millis(build),
+      build_per_sec: rate(commands.len(), build),
+      size_bytes,
+      open_ms: millis(open),
+    },
+    search: SearchStats {
+      queries: queries.len(),
+      cold: Latency::from_samples(&cold),
+      warm: Latency::from_samples(&warm),
+    },
+    learn,
+  })
+}
+
+/// 文本报告：各项一行，数值列对齐，便于两次运行的结果并排比较
+pub fn format_report(report: &Report) -> String {
+  let mut out = format!(
+    "rtfm {} ({} {}, {} CPUs)\n{}\n",
+    report.version,
+    report.os,
+    report.arch,
+    report.cpus,
+    t!(
+      "bench.dataset",
+      commands = report.commands,
+      mb = mb(report.content_bytes as u64)
+    )
+  );
+  let line = |out: &mut String, label: &str, value: String| {
+    out.push_str(&format!("  {:<18} {}\n", label, value));
+  };
+
+  out.push_str(&format!("\n{}\n", t!("bench.database")));
+  line(
+    &mut out,
+    t!("bench.scan"),
+    format!(
+      "{:>10.1} ms  {:>10.0} /s",
+      report.database.scan_ms, report.database.scan_per_sec
+    ),
+  );
+  line(
+    &mut out,
+    t!("bench.lookup"),
+    latency(&report.database.lookup),
+  );
+
+  out.push_str(&format!("\n{}\n", t!("bench.index")));
+  line(
+    &mut out,
+    t!("bench.build"),
+    format!(
+      "{:>10.1} ms  {:>10.0} /s  {} MB",
+      report.index.build_ms,
+      report.index.build_per_sec,
+      mb(report.index.size_bytes)
+    ),
+  );
+  line(
+    &mut out,
+    t!("bench.open"),
+    format!("{:>10.1} ms", report.index.open_ms),
+  );
+
+  out.push_str(&format!(
+    "\n{}\n",
+    t!("bench.search", queries = report.search.queries)
+  ));
+  line(&mut out, t!("bench.cold"), latency(&report.search.cold));
+  line(&mut out, t!("bench.warm"), latency(&report.search.warm));
+
+  out.push_str(&format!("\n{}\n", t!("bench.learn")));
+  match &report.learn {
+    Some(learn) => line(
+      &mut out,
+      &t!("bench.pages", pages = learn.pages),
+      format!(
+        "{:>10.1} ms  {:>10.0} /s  {:.1} MB/s",
+        learn.ms, learn.pages_per_sec, learn.mb_per_sec
+      ),
+    ),
+    None => out.push_str(&format!("  {}\n", t!("bench.no_learned"))),
+  }
+  out
+}
+
+fn latency(latency: &Latency) -> String {
+  format!(
+    "mean {:>8.0} µs  p50 {:>8.0} µs  p95 {:>8.0} µs  max {:>8.0} µs",
+    latency.mean_us, latency.p50_us, latency.p95_us, latency.max_us
+  )
+}
+
+fn mb(bytes: u64) -> String {
+  format!("{:.1}", bytes as f64 / 1_048_576.0)
+}
+
+/// 重新解析学习到的帮助文本（man、--help 等），与 rtfm learn 的解析相同
+fn learn(commands: &[Command]) -> Option<LearnStats> {
+  let pages: Vec<(&str, &str, &str)> = commands
+    .iter()
+    .filter(|c| c.lang == "local")
+    .filter_map(|c| {
+      let source = &c.source_info.as_ref()?.source;
+      // 导入的速查表不是帮助文本
+      if source.starts_with("file: ") {
+        return None;
+      }
+      let body = c.content.strip_prefix(&format!("Source: {}\n\n", source))?;
+      Some((c.name.as_str(), body, source.as_str()))
+    })
+    .collect();
+  if pages.is_empty() {
+    return None;
+  }
+
+  let bytes: usize = pages.iter().map(|(_, body, _)| body.len()).sum();
+  let start = Instant::now();
+  for (name, body, source) in &pages {
+    std::hint::black_box(crate::learn::parse_help_content(name, body, source));
+  }
+  let elapsed = start.elapsed();
+  Some(LearnStats {
+    pages: pages.len(),
+    bytes,
+    ms: millis(elapsed),
+    pages_per_sec: rate(pages.len(), elapsed),
+    mb_per_sec: bytes as f64 / 1_048_576.0 / elapsed.as_secs_f64().max(1e-9),
+  })
+}
+
+/// 均匀选取最多 n 条命令，结果与数据顺序有关但每次运行相同
+fn spread(commands: &[Command], n: usize) -> Vec<&Command> {
+  let step = commands.len().div_ceil(n.max(1)).max(1);
+  commands.iter().step_by(step).take(n).collect()
+}
+
+/// 搜索的查询：交替使用命令名和描述中的词
+pub fn queries(commands: &[Command], n: usize) -> Vec<String> {
+  spread(commands, n)
+    .into_iter()
+    .enumerate()
+    .map(|(i, cmd)| {
+      let word = cmd
+        .description
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|w| w.chars().count() >= 4);
+      match word {
+        Some(word) if i % 2 == 1 => word.to_lowercase(),
+        _ => cmd.name.clone(),
+      }
+    })
+    .collect()
+}
+
+fn dir_size(path: &Path) -> u64 {
+  let Ok(entries) = std::fs::read_dir(path) else {
+    return 0;
+  };
+  entries
+    .filter_map(Result::ok)
+    .map(|entry| match entry.metadata() {
+      Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+      Ok(meta) => meta.len(),
+      Err(_) => 0,
+    })
+    .sum()
+}
+
+fn millis(d: Duration) -> f64 {
+  d.as_secs_f64() * 1000.0
+}
+
+fn rate(count: usize, d: Duration) -> f64 {
+  count as f64 / d.as_secs_f64().max(1e-9)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{Example, COMMAND_SCHEMA};
+
+  fn command(name: &str, lang: &str, description: &str) -> Command {
+    Command {
+      name: name.to_string(),
+      description: description.to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: lang.to_string(),
+      examples: vec![Example {
+        description: "Run it".to_string(),
+        code: name.to_string(),
+        source: None,
+      }],
+      content: format!("# {}\n\n> {}\n", name, description),
+      sections: Vec::new(),
+      options: Vec::new(),
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    }
+  }
+
+  #[test]
+  fn test_latency() {
+    let samples: Vec<Duration> = (1..=100).map(Duration::from_micros).collect();
+    let latency = Latency::from_samples(&samples);
+    assert_eq!(latency.samples, 100);
+    assert_eq!(latency.mean_us.round(), 51.0);
+    assert_eq!(latency.p50_us.round(), 51.0);
+    assert_eq!(latency.p95_us.round(), 95.0);
+    assert_eq!(latency.max_us.round(), 100.0);
+    assert_eq!(Latency::from_samples(&[]).samples, 0);
+  }
+
+  #[test]
+  fn test_run() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    for (name, description) in [
+      ("tar", "Archiving utility"),
+      ("grep", "Find patterns in files"),
+      ("curl", "Transfer data from or to a server"),
+    ] {
+      db.save_command(&command(name, "en", description)).unwrap();
+    }
+    let mut learned = crate::learn::parse_help_content(
+      "rg",
+      "Usage: rg [OPTIONS] PATTERN\n\nSearch recursively\n",
+      "--help",
+    );
+    learned.name = "rg".to_string();
+    db.save_command(&learned).unwrap();
+
+    let options = Options {
+      queries: 10,
+      iterations: 2,
+    };
+    let mut stages = Vec::new();
+    let report = run(
+      &db,
+      temp_dir.path(),
+      &SearchConfig::default(),
+      &options,
+      |stage| stages.push(format!("{:?}", stage)),
+    )
+    .unwrap();
+    assert_eq!(stages, ["Database", "Index", "Search(4)", "Learn"]);
+    assert_eq!(report.commands, 4);
+    assert_eq!(report.search.queries, 4);
+    assert_eq!(report.search.warm.samples, 8);
+    assert_eq!(report.database.lookup.samples, 8);
+    assert!(report.index.size_bytes > 0);
+    assert_eq!(report.learn.as_ref().unwrap().pages, 1);
+    assert!(format_report(&report).contains("rtfm "));
+
+    // 临时索引已删除
+    let entries: Vec<_> = std::fs::read_dir(temp_dir.path())
+      .unwrap()
+      .filter_map(Result::ok)
+      .filter(|e| e.file_name().to_string_lossy().starts_with("bench-index"))
+      .collect();
+    assert!(entries.is_empty());
+  }
+
+  #[test]
+  fn test_queries() {
+    let commands = vec![
+      command("tar", "en", "Archiving utility"),
+      command("ls", "en", "List directory contents"),
+    ];
+    assert_eq!(queries(&commands, 10), ["tar", "list"]);
+    assert_eq!(queries(&commands, 1), ["tar"]);
+  }
+}
@@ -0,0 +1,191 @@
+//! 导出闪卡
+//!
+//! 每个示例生成一张卡片：正面是命令名与示例描述，背面是示例代码。
+//! - anki：Anki 可直接导入的制表符分隔文本，文件头声明分隔符、笔记类型、牌组和标签列；
+//!   GUID 由命令与示例代码决定，重新导出后再导入会更新已有卡片而不是重复添加
+//! - csv：带表头的通用 CSV，可导入其他间隔重复工具
+
+use std::io::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::storage::Command;
+
+/// 闪卡格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardFormat {
+  Anki,
+  Csv,
+}
+
+impl CardFormat {
+  pub fn parse(s: &str) -> Option<Self> {
+    match s {
+      "anki" => Some(CardFormat::Anki),
+      "csv" => Some(CardFormat::Csv),
+      _ => None,
+    }
+  }
+}
+
+/// 一张卡片
+#[derive(Debug, Clone)]
+pub struct Card {
+  pub guid: String,
+  pub command: String,
+  pub lang: String,
+  pub description: String,
+  pub code: String,
+  pub tags: Vec<String>,
+}
+
+/// 每个示例一张卡片
+pub fn cards(commands: &[Command]) -> Vec<Card> {
+  let mut cards = Vec::new();
+  for cmd in commands {
+    let name_tag = format!("rtfm::{}", tag(&cmd.name));
+    for example in &cmd.examples {
+      let key = format!("{}\0{}\0{}", cmd.lang, cmd.name, example.code);
+      let hash = format!("{:x}", Sha256::digest(key.as_bytes()));
+      cards.push(Card {
+        guid: format!("rtfm-{}", &hash[..16]),
+        command: cmd.name.clone(),
+        lang: cmd.lang.clone(),
+        description: example.description.clone(),
+        code: example.code.clone(),
+        tags: vec![name_tag.clone(), tag(&cmd.platform), tag(&cmd.lang)],
+      });
+    }
+  }
+  cards
+}
+
+/// 标签不能包含空白
+fn tag(s: &str) -> String {
+  s.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// 写出卡片
+pub fn write(
+  cards: &[Card],
+  format: CardFormat,
+  deck: &str,
+  out: &mut impl Write,
+) -> std::io::Result<()> {
+  match format {
+    CardFormat::Anki => {
+      writeln!(out, "#separator:tab")?;
+      writeln!(out, "#html:true")?;
+      writeln!(out, "#notetype:Basic")?;
+      writeln!(out, "#deck:{}", deck)?;
+      writeln!(out, "#guid column:1")?;
+      writeln!(out, "#tags column:4")?;
+      for card in cards {
+        let front = format!(
+          "<b>{}</b><br>{}",
+          html_escape(&card.command),
+          html_escape(&card.description)
+        );
+        let back = format!("<code>{}</code>", placeholders(&html_escape(&card.code)));
+        writeln!(
+          out,
+          "{}\t{}\t{}\t{}",
+          card.guid,
+          anki_field(&front),
+          anki_field(&back),
+          card.tags.join(" ")
+        )?;
+      }
+    }
+    CardFormat::Csv => {
+      writeln!(out, "command,lang,front,back,tags")?;
+      for card in cards {
+        let fields = [
+          card.command.as_str(),
+          card.lang.as_str(),
+          card.description.as_str(),
+          card.code.as_str(),
+          &card.tags.join(" "),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        writeln!(out, "{}", line.join(","))?;
+      }
+    }
+  }
+  Ok(())
+}
+
+fn html_escape(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// tldr 占位符 `{{path/to/file}}` 显示为下划线
+fn placeholders(code: &str) -> String {
+  code.replace("{{", "<u>").replace("}}", "</u>")
+}
+
+/// 制表符分隔的字段中不能有制表符和换行
+fn anki_field(s: &str) -> String {
+  s.replace('\t', " ")
+    .replace("\r\n", "<br>")
+    .replace('\n', "<br>")
+}
+
+fn csv_field(s: &str) -> String {
+  if s.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", s.replace('"', "\"\""))
+  } else {
+    s.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{Example, COMMAND_SCHEMA};
+
+  #[test]
+  fn test_write_cards() {
+    let cmd = Command {
+      name: "tar".to_string(),
+      description: "Archiving utility".to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: vec![Example {
+        description: "Extract an archive, \"verbosely\"".to_string(),
+        code: "tar xvf {{source.tar}} > log".to_string(),
+        source: None,
+      }],
+      content: String::new(),
+      sections: Vec::new(),
+      options: Vec::new(),
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    };
+    let cards = cards(&[cmd]);
+    assert_eq!(cards.len(), 1);
+    assert_eq!(cards[0].tags, ["rtfm::tar", "common", "en"]);
+
+    let mut anki = Vec::new();
+    write(&cards, CardFormat::Anki, "Shell", &mut anki).unwrap();
+    let anki = String::from_utf8(anki).unwrap();
+    assert!(anki.contains("#deck:Shell\n"));
+    let row = anki.lines().last().unwrap();
+    let fields: Vec<&str> = row.split('\t').collect();
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[2], "<code>tar xvf <u>source.tar</u> &gt; log</code>");
+
+    let mut csv = Vec::new();
+    write(&cards, CardFormat::Csv, "Shell", &mut csv).unwrap();
+    assert_eq!(
+      String::from_utf8(csv).unwrap(),
+      "command,lang,front,back,tags\n\
+       tar,en,\"Extract an archive, \"\"verbosely\"\"\",tar xvf {{source.tar}} > log,rtfm::tar common en\n"
+    );
+  }
+}
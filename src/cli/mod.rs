@@ -1,31 +1,131 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{ArgGroup, CommandFactory, FromArgMatches, Parser, Subcommand};
+
+use crate::i18n;
 
 #[derive(Parser)]
 #[command(name = "rtfm")]
 #[command(author, version, about = "Read The F***ing Manual - CLI cheatsheet")]
+#[command(group(ArgGroup::new("target").args(["query", "list"]).multiple(true)))]
+#[command(group(ArgGroup::new("output_format").args(["output", "json", "md", "plain"])))]
 pub struct Cli {
-  /// Command name to look up (e.g., rtfm tar)
+  /// Command name to look up (e.g., rtfm tar); several words are looked up as one page (rtfm git checkout)
   #[arg(value_name = "NAME")]
-  pub query: Option<String>,
+  pub query: Vec<String>,
+
+  /// Preferred language, e.g. en, zh (default: commands.<name>.lang, then search.default_lang)
+  #[arg(short, long, short_alias = 'L')]
+  pub lang: Option<String>,
+
+  /// Print the names of all pages for the platform (--platform, default: this OS) and common, one per line
+  #[arg(long, conflicts_with = "query")]
+  pub list: bool,
+
+  /// Look up and print pages like a tldr client: exact names only, tldr formatting, exit code 1 when missing (default: ui.tldr_compat)
+  #[arg(long, requires = "target", conflicts_with_all = ["copy", "fill", "render", "semantic", "fuzzy"])]
+  pub tldr_compat: bool,
+
+  /// Copy an example to the clipboard, numbered from 1 (default: commands.<name>.example, then 1)
+  #[arg(long, value_name = "N", requires = "query")]
+  pub copy: Option<Option<usize>>,
+
+  /// Fill in the placeholders of an example, numbered from 1, and print the completed command (copied with --copy)
+  #[arg(long, value_name = "N", requires = "query")]
+  pub fill: Option<Option<usize>>,
+
+  /// Blend semantic matches from the embeddings index into full-text results
+  #[arg(long, requires = "query")]
+  pub semantic: bool,
 
-  /// Preferred language (e.g., en, zh)
-  #[arg(short, long, default_value = "en")]
-  pub lang: String,
+  /// Tolerate typos in the full-text search (e.g. dokcer finds docker)
+  #[arg(long, requires = "query")]
+  pub fuzzy: bool,
+
+  /// Platform of full-text results: auto (this OS first), all, linux, osx, windows (that platform and common) or common (default: search.platform)
+  #[arg(short, long, requires = "target")]
+  pub platform: Option<String>,
+
+  /// Render the command with a renderer plugin instead of the built-in output
+  #[arg(long, value_name = "PLUGIN", requires = "query")]
+  pub render: Option<String>,
+
+  /// Output format for scripts: json, markdown or plain (default: colored text, plain when piped or NO_COLOR is set); also applies to a list of search results
+  #[arg(short, long, value_parser = ["json", "markdown", "plain"], requires = "query", conflicts_with_all = ["render", "fill", "tldr_compat"])]
+  pub output: Option<String>,
+
+  /// Same as --output json
+  #[arg(long, requires = "query", conflicts_with_all = ["render", "fill", "tldr_compat"])]
+  pub json: bool,
+
+  /// Same as --output markdown
+  #[arg(long, requires = "query", conflicts_with_all = ["render", "fill", "tldr_compat"])]
+  pub md: bool,
+
+  /// Same as --output plain
+  #[arg(long, requires = "query", conflicts_with_all = ["render", "fill", "tldr_compat"])]
+  pub plain: bool,
 
   /// Enable debug mode (show logs panel in TUI)
   #[arg(long)]
   pub debug: bool,
 
-  /// UI style: modern or classic
-  #[arg(long)]
+  /// UI style (default: tui.default_style in config)
+  #[arg(long, value_parser = ["modern", "classic"])]
   pub style: Option<String>,
 
   #[command(subcommand)]
   pub command: Option<Commands>,
 }
 
+impl Cli {
+  /// --output 或其简写指定的格式
+  pub fn output_format(&self) -> Option<&str> {
+    if self.json {
+      Some("json")
+    } else if self.md {
+      Some("markdown")
+    } else if self.plain {
+      Some("plain")
+    } else {
+      self.output.as_deref()
+    }
+  }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
+  /// Search commands and print the results for scripts and desktop launchers
+  Search {
+    /// Search query; an exact command name lists its examples instead
+    #[arg(default_value = "")]
+    query: String,
+
+    /// Output format: text, alfred (Script Filter JSON), raycast (JSON) or rofi (script mode)
+    #[arg(short, long, default_value = "text", value_parser = ["text", "alfred", "raycast", "rofi"])]
+    format: String,
+
+    /// Only search this language (examples use commands.<name>.lang, then search.default_lang)
+    #[arg(short, long)]
+    lang: Option<String>,
+
+    /// Maximum number of results (default: search.cli_limit)
+    #[arg(short = 'n', long)]
+    limit: Option<usize>,
+
+    /// Blend semantic matches from the embeddings index into the results
+    #[arg(short, long)]
+    semantic: bool,
+
+    /// Tolerate typos (e.g. dokcer finds docker)
+    #[arg(long)]
+    fuzzy: bool,
+
+    /// Platform: auto (this OS first), all, linux, osx, windows (that platform and common) or common (default: search.platform)
+    #[arg(long)]
+    platform: Option<String>,
+  },
+
   /// Start HTTP API server
   Serve {
     /// Listen port
@@ -40,19 +140,97 @@ pub enum Commands {
     #[arg(long)]
     detach: bool,
 
+    /// Stop the server started with --detach
+    #[arg(long, conflicts_with_all = ["detach", "status", "systemd"])]
+    stop: bool,
+
+    /// Install and start a service that runs this server at boot: a systemd user unit on Linux, a launchd agent on macOS, a Windows service on Windows
+    #[arg(long, conflicts_with_all = ["detach", "stop", "status", "systemd", "debug"])]
+    install_service: bool,
+
+    /// Stop and remove the service installed with --install-service
+    #[arg(long, conflicts_with_all = ["detach", "stop", "status", "systemd", "debug", "install_service", "read_only"])]
+    uninstall_service: bool,
+
+    /// Show whether the server started with --detach is running
+    #[arg(long, conflicts_with_all = ["detach", "systemd"])]
+    status: bool,
+
     /// Debug mode: also print logs to console
     #[arg(long)]
     debug: bool,
+
+    /// Disable all endpoints that change data (import, update, learn, restore, reset, sync push); also server.read_only
+    #[arg(long)]
+    read_only: bool,
+
+    /// Run under systemd: use the socket passed by socket activation, report readiness and log to journald
+    #[arg(long, conflicts_with = "detach")]
+    systemd: bool,
+  },
+
+  /// Run the HTTP server as a Windows service
+  #[command(
+    after_long_help = "EXAMPLES (from an Administrator prompt):\n  rtfm service install --port 3030\n  rtfm service start\n  rtfm service stop\n  rtfm service uninstall"
+  )]
+  Service {
+    #[command(subcommand)]
+    action: ServiceAction,
+  },
+
+  /// Write a systemd unit that runs 'rtfm serve' for the current data directory
+  #[command(
+    after_long_help = "EXAMPLES:\n  rtfm install-service\n  systemctl --user daemon-reload\n  systemctl --user enable --now rtfm.service"
+  )]
+  InstallService {
+    /// Listen port
+    #[arg(short, long, default_value = "3030")]
+    port: u16,
+
+    /// Bind address
+    #[arg(short, long, default_value = "127.0.0.1")]
+    bind: String,
+
+    /// Serve read-only (see 'rtfm serve --read-only')
+    #[arg(long)]
+    read_only: bool,
+
+    /// Also write rtfm.socket: systemd listens on the port and starts the server on the first request
+    #[arg(long)]
+    socket: bool,
+
+    /// Install a system-wide unit instead of a user unit
+    #[arg(long)]
+    system: bool,
+
+    /// Directory for the unit files (default: ~/.config/systemd/user, or /etc/systemd/system with --system)
+    #[arg(long)]
+    dir: Option<PathBuf>,
   },
 
   /// Update command cheatsheet data
+  #[command(
+    after_long_help = "EXAMPLES:\n  rtfm update\n  rtfm update --force\n  rtfm update --langs en,zh          # only English and Chinese pages\n  rtfm update --from-file tldr.zip   # archive downloaded on another machine"
+  )]
   Update {
     /// Force update (ignore version check)
     #[arg(short, long)]
     force: bool,
+
+    /// Apply an archive (.zip or .tar.gz) of the source downloaded elsewhere instead of checking GitHub (e.g., for air-gapped machines)
+    #[arg(long, value_name = "FILE", conflicts_with = "force")]
+    from_file: Option<PathBuf>,
+
+    /// Where to update from: tldr (tldr-pages) or cheat (cheat/cheatsheets, merged into the tldr pages)
+    #[arg(long, default_value = "tldr", value_parser = ["tldr", "cheat"])]
+    source: String,
+
+    /// Only import tldr pages in these languages, overriding update.languages (e.g. en,zh,es; "all" for every language)
+    #[arg(long, value_delimiter = ',', value_name = "LANGS")]
+    langs: Vec<String>,
   },
 
-  /// Import Markdown files in tldr-pages format (.md, .zip, .tar, .tar.gz, .tgz, or directory)
+  /// Import tldr pages (.md, .zip, .tar, .tar.gz, .tgz, or directory), navi .cheat files, cheat sheets, Dash/Zeal docsets or JSON exports, from paths or URLs
   #[command(after_long_help = r#"FORMAT:
   Files must follow the tldr-pages Markdown format:
   
@@ -64,16 +242,61 @@ pub enum Commands {
     `command --option {{arg}}`
     
   Files without valid description or examples will be skipped.
-  See: https://github.com/tldr-pages/tldr/blob/main/contributing-guides/style-guide.md"#)]
+  See: https://github.com/tldr-pages/tldr/blob/main/contributing-guides/style-guide.md
+
+  Pages under pages.<lang>/<platform>/ keep that language and platform, so
+  'rtfm export --format tldr' output can be imported again, as can .json files
+  written by 'rtfm export --format json':
+
+    rtfm import learned.tar.gz
+    rtfm import commands.json
+
+  navi .cheat files and cheat sheets (one file per command, named after it) are
+  imported as local commands and merged with the tldr page of the same name;
+  <variables> become {{placeholders}}:
+
+    rtfm import ~/.local/share/navi/cheats
+    rtfm import --format cheat ~/.config/cheat/cheatsheets/personal
+
+  Dash and Zeal docsets (.docset directories, or a directory of them) are
+  imported entry by entry as local commands tagged with the docset keyword and
+  entry type; code blocks become examples:
+
+    rtfm import ~/.local/share/Zeal/Zeal/docsets/Python_3.docset
+    rtfm import --format docset ~/.local/share/Zeal/Zeal/docsets
+
+  Several paths can be imported at once, and https:// URLs are downloaded
+  first. A GitHub repository URL imports the repository's archive; archives
+  that do not follow the tldr-pages layout are unpacked and imported like a
+  directory:
+
+    rtfm import git.md docker.md ~/cheats
+    rtfm import https://github.com/alice/cheatsheets
+    rtfm import https://example.com/team/pages.tar.gz
+
+  With --watch, rtfm keeps running after the import and imports .md files as
+  they are added or changed (e.g. a cheatsheet folder shared via Dropbox or git):
+
+    rtfm import --watch ~/Dropbox/cheatsheets"#)]
   Import {
-    /// File or directory path (auto-detects archive format)
-    path: String,
+    /// Files, directories or https:// URLs (auto-detects archive format)
+    #[arg(required = true)]
+    paths: Vec<String>,
+
+    /// Input format: auto (.md and archives are tldr, .cheat is navi, .docset is docset), tldr, navi, cheat or docset
+    #[arg(short, long, default_value = "auto", value_parser = ["auto", "tldr", "navi", "cheat", "docset"])]
+    format: String,
+
+    /// Keep watching the directory and import new or changed .md files
+    #[arg(short, long)]
+    watch: bool,
   },
 
   /// Learn a command from --help or man page
   Learn {
     /// Command to learn (e.g., rtfm learn docker)
-    command: String,
+    #[arg(required_unless_present_any = ["refresh", "refresh_outdated"])]
+    command: Option<String>,
 
     /// Force re-learn even if already exists
     #[arg(short, long)]
@@ -82,6 +305,30 @@ pub enum Commands {
     /// Prefer man page over --help
     #[arg(long)]
     man: bool,
+
+    /// Learn from a documentation page or raw README instead of the local system
+    #[arg(long, value_name = "URL", conflicts_with = "man")]
+    url: Option<String>,
+
+    /// Learn from a file containing captured help/man output (e.g., for air-gapped machines)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["man", "url"])]
+    from_file: Option<PathBuf>,
+
+    /// Generate concise tldr-style examples with the configured LLM endpoint
+    #[arg(long)]
+    llm: bool,
+
+    /// Re-learn only learned commands whose binary changed since they were learned
+    #[arg(long, conflicts_with_all = ["command", "url", "from_file"])]
+    refresh: bool,
+
+    /// Re-learn only learned commands whose installed version differs from the learned one
+    #[arg(long, conflicts_with_all = ["command", "url", "from_file", "refresh"])]
+    refresh_outdated: bool,
+
+    /// Also learn every subcommand (e.g., git-commit), using the tool's own listing
+    #[arg(long, conflicts_with_all = ["url", "from_file"])]
+    subcommands: bool,
   },
 
   /// Learn commands from the system (man pages, PowerShell, or PATH)
@@ -103,9 +350,38 @@ pub enum Commands {
     #[arg(long)]
     prefix: Option<String>,
 
-    /// Source type: "man" (Linux/macOS), "powershell" (Windows), "path" (all platforms)
+    /// Source type: "man" (Linux/macOS), "manpath" (parse roff sources from MANPATH directly),
+    /// "powershell" (Windows), "path" (all platforms), "builtin" (bash/zsh builtins),
+    /// "history" (commands from shell history, most used first)
     #[arg(long, default_value = "auto")]
     source: String,
+
+    /// Resume an interrupted run, skipping commands already processed
+    #[arg(long, conflicts_with = "retry_failed")]
+    resume: bool,
+
+    /// Only retry the commands that failed in the previous run
+    #[arg(long)]
+    retry_failed: bool,
+
+    /// Number of commands whose help is captured in parallel (default: learn.jobs; 0 = CPU count)
+    #[arg(short, long)]
+    jobs: Option<usize>,
+  },
+
+  /// Suggest frequently used commands from shell history that are not in the database yet
+  SuggestLearn {
+    /// Maximum number of suggestions
+    #[arg(short, long, default_value = "20")]
+    limit: usize,
+
+    /// Only suggest commands used at least this many times
+    #[arg(long, default_value = "3")]
+    min_count: usize,
+
+    /// Learn the suggestions without asking
+    #[arg(short = 'y', long)]
+    yes: bool,
   },
 
   /// Backup all application data (database, index, config) to archive
@@ -113,16 +389,224 @@ pub enum Commands {
     /// Output file path
     #[arg(short, long, default_value = "rtfm-backup.tar.gz")]
     output: String,
+
+    /// Only back up your own data: learned commands, notes, favorites and aliases (no tldr pages, index or config)
+    #[arg(long)]
+    user_only: bool,
   },
 
   /// Restore application data from backup archive
   Restore {
-    /// Archive file path
+    /// Archive file path (a --user-only backup or an export --user-data JSON file is merged into the existing data)
     path: String,
 
-    /// Merge with existing data (default: replace all)
+    /// Merge the backup's commands into the existing data (default: replace all)
     #[arg(long)]
     merge: bool,
+
+    /// Restore only these parts (db, index, config; comma separated)
+    #[arg(long, value_delimiter = ',', value_parser = ["db", "index", "config"])]
+    only: Vec<String>,
+  },
+
+  /// Show database statistics and learned-content quality
+  Stats {
+    /// List learned commands whose parse quality is low
+    #[arg(long)]
+    low_quality: bool,
+
+    /// Show local usage: top lookups, zero-result queries and learning gaps
+    #[arg(long, conflicts_with = "low_quality")]
+    dashboard: bool,
+
+    /// Entries per dashboard list
+    #[arg(short = 'n', long, default_value = "10", requires = "dashboard")]
+    limit: usize,
+
+    /// Delete the recorded usage statistics
+    #[arg(long, conflicts_with_all = ["low_quality", "dashboard"])]
+    clear_usage: bool,
+  },
+
+  /// Measure indexing, search, database and parsing speed on the local data
+  #[command(after_long_help = "EXAMPLES:\n  rtfm bench\n  rtfm bench --json > before.json")]
+  Bench {
+    /// Number of search queries and database lookups
+    #[arg(short = 'n', long, default_value = "100")]
+    queries: usize,
+
+    /// Times the warm searches and lookups are repeated
+    #[arg(short, long, default_value = "5")]
+    iterations: usize,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+  },
+
+  /// Show the log of data modifications (imports, learning, updates, deletions, resets, restores, syncs)
+  Audit {
+    /// Number of entries to show, newest first
+    #[arg(short = 'n', long, default_value = "20")]
+    limit: usize,
+
+    /// Only show this operation
+    #[arg(short, long, value_parser = ["import", "learn", "update", "delete", "reset", "restore", "sync"])]
+    action: Option<String>,
+  },
+
+  /// Export commands as navi cheatsheets, JSON, Markdown or tldr pages
+  #[command(
+    after_long_help = "EXAMPLES:\n  rtfm export -c 'git*,docker' -o ~/.local/share/navi/cheats/rtfm\n  rtfm export --lang local > learned.cheat\n  rtfm export -f tldr --lang local -o learned.tar.gz\n  rtfm export -f json -o commands.json\n  rtfm export --user-data -o user-data.json"
+  )]
+  Export {
+    /// Output path (default: stdout): a directory for navi and tldr (or a .tar.gz for tldr), a file for json and markdown
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Export format: navi (.cheat files for the navi fuzzy runner), json, markdown (tldr-style pages in one file) or tldr (pages.<lang>/<platform>/<name>.md)
+    #[arg(short, long, default_value = "navi", value_parser = ["navi", "json", "markdown", "tldr"])]
+    format: String,
+
+    /// Only export commands in this language (default: search.default_lang plus learned commands)
+    #[arg(short, long)]
+    lang: Option<String>,
+
+    /// Only export these commands; a trailing * matches a prefix (e.g. git*,docker)
+    #[arg(short, long, value_delimiter = ',')]
+    commands: Vec<String>,
+
+    /// Only export commands for this platform (e.g. linux, osx, windows, common)
+    #[arg(short, long)]
+    platform: Option<String>,
+
+    /// Export your learned commands, notes, favorites and aliases as JSON for rtfm restore
+    #[arg(long, conflicts_with_all = ["format", "lang", "commands", "platform"])]
+    user_data: bool,
+  },
+
+  /// Export examples as flashcards for Anki or other spaced-repetition apps
+  ExportCards {
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Card format: anki (tab-separated text that Anki imports directly) or csv
+    #[arg(short, long, default_value = "anki", value_parser = ["anki", "csv"])]
+    format: String,
+
+    /// Only export commands in this language (default: search.default_lang plus learned commands)
+    #[arg(short, long)]
+    lang: Option<String>,
+
+    /// Only export these commands; a trailing * matches a prefix (e.g. git*,docker)
+    #[arg(short, long, value_delimiter = ',')]
+    commands: Vec<String>,
+
+    /// Only export commands for this platform (e.g. linux, osx, windows, common)
+    #[arg(short, long)]
+    platform: Option<String>,
+
+    /// Anki deck the cards are imported into
+    #[arg(long, default_value = "rtfm")]
+    deck: String,
+  },
+
+  /// Render a command as a man page, or install pages for commands without one
+  #[command(
+    after_long_help = "EXAMPLES:\n  rtfm render tar --format man | man -l -\n  rtfm render --install"
+  )]
+  Render {
+    /// Command name
+    #[arg(required_unless_present = "install")]
+    name: Option<String>,
+
+    /// Output format
+    #[arg(short, long, default_value = "man", value_parser = ["man"])]
+    format: String,
+
+    /// Preferred language (default: commands.<name>.lang, then search.default_lang)
+    #[arg(short, long)]
+    lang: Option<String>,
+
+    /// Write man pages for all commands that have no manual of their own
+    #[arg(long, conflicts_with = "name")]
+    install: bool,
+
+    /// Man directory for --install; it must be in MANPATH (default: ~/.local/share/man)
+    #[arg(long, requires = "install")]
+    dir: Option<PathBuf>,
+  },
+
+  /// Compare a learned command with its tldr page to spot options the page doesn't cover
+  #[command(after_long_help = "EXAMPLES:\n  rtfm diff tar\n  rtfm diff git -u | less -R")]
+  Diff {
+    /// Command name
+    name: String,
+
+    /// Language of the tldr page
+    #[arg(short, long, default_value = "en")]
+    lang: String,
+
+    /// Unified diff instead of side by side
+    #[arg(short, long)]
+    unified: bool,
+  },
+
+  /// Rebuild the search index from the database
+  Reindex,
+
+  /// Delete a command from the database and search index
+  #[command(after_long_help = "EXAMPLES:\n  rtfm delete mytool --lang local\n  rtfm delete tar")]
+  Delete {
+    /// Command name
+    name: String,
+
+    /// Only delete the command in this language (default: all languages)
+    #[arg(short, long)]
+    lang: Option<String>,
+  },
+
+  /// Manage favorite commands (Ctrl+F in the TUI shows only favorites)
+  Fav {
+    #[command(subcommand)]
+    action: FavAction,
+  },
+
+  /// Manage command aliases (e.g. gco for git checkout), resolved before looking up a command
+  Alias {
+    #[command(subcommand)]
+    action: AliasAction,
+  },
+
+  /// Write personal notes for a command in $EDITOR; they are shown with the page and searchable
+  #[command(after_long_help = "EXAMPLES:\n  rtfm note tar\n  rtfm note tar --show\n  rtfm note")]
+  Note {
+    /// Command name (omit to list all notes)
+    name: Option<String>,
+
+    /// Print the note instead of editing it
+    #[arg(long, requires = "name", conflicts_with = "clear")]
+    show: bool,
+
+    /// Delete the note
+    #[arg(long, requires = "name")]
+    clear: bool,
+  },
+
+  /// Show recently viewed commands
+  History {
+    /// Number of entries to show, newest first
+    #[arg(short = 'n', long, default_value = "20")]
+    limit: usize,
+
+    /// List every view instead of each command once
+    #[arg(short, long)]
+    all: bool,
+
+    /// Clear the history
+    #[arg(long, conflicts_with = "all")]
+    clear: bool,
   },
 
   /// Reset all data (factory reset)
@@ -131,4 +615,260 @@ pub enum Commands {
     #[arg(short = 'y', long)]
     yes: bool,
   },
+
+  /// Inspect configuration
+  Config {
+    #[command(subcommand)]
+    action: ConfigAction,
+  },
+
+  /// Exchange commands, favorites and notes with a remote rtfm server
+  Sync {
+    #[command(subcommand)]
+    action: SyncAction,
+  },
+
+  /// Manage the embeddings index used by --semantic search
+  Embed {
+    #[command(subcommand)]
+    action: EmbedAction,
+  },
+
+  /// Manage plugins that provide data sources, help parsers and renderers
+  Plugin {
+    #[command(subcommand)]
+    action: PluginAction,
+  },
+
+  /// Manage users of the multi-user HTTP server (server.multi_user)
+  User {
+    #[command(subcommand)]
+    action: UserAction,
+  },
+}
+
+#[derive(Subcommand)]
+pub enum FavAction {
+  /// Add commands to the favorites
+  Add {
+    /// Command names
+    #[arg(required = true)]
+    names: Vec<String>,
+  },
+  /// Remove commands from the favorites
+  Remove {
+    /// Command names
+    #[arg(required = true)]
+    names: Vec<String>,
+  },
+  /// List favorite commands
+  List,
+}
+
+#[derive(Subcommand)]
+pub enum AliasAction {
+  /// Add an alias or point an existing one at another command
+  Add {
+    /// Alias name (e.g., gco)
+    name: String,
+    /// Command it stands for (e.g., "git checkout")
+    target: String,
+  },
+  /// Remove aliases
+  Remove {
+    /// Alias names
+    #[arg(required = true)]
+    names: Vec<String>,
+  },
+  /// List aliases
+  List,
+  /// Import aliases from shell config files (bash, zsh, fish) and git config
+  Import,
+}
+
+#[derive(Subcommand)]
+pub enum EmbedAction {
+  /// Embed new and changed commands (unchanged ones keep their vectors)
+  Rebuild {
+    /// Embed every command again, e.g. after switching models
+    #[arg(short, long)]
+    force: bool,
+
+    /// Languages to embed (default: embeddings.languages, or search.default_lang plus learned commands)
+    #[arg(short, long, value_delimiter = ',')]
+    lang: Vec<String>,
+  },
+  /// Show the embeddings index model, size and how many commands changed since it was built
+  Status,
+}
+
+#[derive(Subcommand)]
+pub enum PluginAction {
+  /// List plugins found in the plugins directory
+  List,
+  /// Import the commands provided by a source plugin
+  Fetch {
+    /// Plugin name
+    name: String,
+  },
+}
+
+#[derive(Subcommand)]
+pub enum UserAction {
+  /// Add a user and print their API token
+  Add {
+    /// User name (letters, digits, - and _)
+    name: String,
+    /// Allow the user to modify the shared dataset
+    #[arg(long)]
+    admin: bool,
+  },
+  /// List users
+  List,
+  /// Issue a new API token for a user (the old one stops working)
+  Token {
+    /// User name
+    name: String,
+  },
+  /// Remove a user
+  Remove {
+    /// User name
+    name: String,
+    /// Also delete the user's learned commands and statistics
+    #[arg(long)]
+    purge: bool,
+  },
+}
+
+#[derive(Subcommand)]
+pub enum ServiceAction {
+  /// Register the service for the current data directory and config file
+  Install {
+    /// Listen port
+    #[arg(short, long, default_value = "3030")]
+    port: u16,
+
+    /// Bind address
+    #[arg(short, long, default_value = "127.0.0.1")]
+    bind: String,
+
+    /// Serve read-only (see 'rtfm serve --read-only')
+    #[arg(long)]
+    read_only: bool,
+
+    /// Start only on request instead of automatically with Windows
+    #[arg(long)]
+    manual: bool,
+  },
+  /// Start the service
+  Start,
+  /// Stop the service
+  Stop,
+  /// Stop and remove the service
+  Uninstall,
+  /// Entry point used by the service control manager
+  #[command(hide = true)]
+  Run {
+    #[arg(short, long, default_value = "3030")]
+    port: u16,
+
+    #[arg(short, long, default_value = "127.0.0.1")]
+    bind: String,
+
+    #[arg(long)]
+    read_only: bool,
+
+    /// Data directory of the user who installed the service
+    #[arg(long)]
+    data_dir: PathBuf,
+
+    /// Config file of the user who installed the service
+    #[arg(long)]
+    config: Option<PathBuf>,
+  },
+}
+
+#[derive(Subcommand)]
+pub enum SyncAction {
+  /// Send local commands, favorites and notes that the remote lacks or has in a different version
+  Push(SyncArgs),
+  /// Fetch remote commands, favorites and notes that are missing or different locally
+  Pull(SyncArgs),
+}
+
+#[derive(clap::Args)]
+pub struct SyncArgs {
+  /// Remote server, e.g. http://team-rtfm:3030 (default: sync.remote in config)
+  #[arg(short, long)]
+  pub remote: Option<String>,
+
+  /// Conflict policy: lww keeps the newer version, merge also adds the other side's examples and note text (default: sync.policy)
+  #[arg(short, long, value_parser = ["lww", "merge"])]
+  pub policy: Option<String>,
+
+  /// Only sync commands in this language (e.g. local for learned commands); favorites and notes are always synced
+  #[arg(short, long)]
+  pub lang: Option<String>,
+
+  /// API key or user token for the remote (default: sync.token, then the RTFM_SYNC_TOKEN environment variable)
+  #[arg(long)]
+  pub token: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+  /// Check the config file for unknown keys, invalid values and conflicting settings
+  Validate {
+    /// Config file to check (default: the first file found by 'rtfm config path')
+    path: Option<PathBuf>,
+  },
+  /// Show where config files are looked for and which one is in use
+  Path,
+  /// Print a JSON Schema for the config file (for editor completion and validation)
+  Schema,
+}
+
+/// 解析命令行；命令的说明使用当前界面语言（参数说明仍为英文）
+pub fn parse() -> Cli {
+  let matches = localize(Cli::command(), "cli").get_matches();
+  Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+}
+
+/// 按消息表替换命令及其子命令的说明，键为 cli.<子命令>.<子命令>
+fn localize(command: clap::Command, key: &str) -> clap::Command {
+  let command = match i18n::lookup(key) {
+    Some(about) => command.about(about),
+    None => command,
+  };
+  command.mut_subcommands(|sub| {
+    let key = format!("{}.{}", key, sub.get_name());
+    localize(sub, &key)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 英文消息表中的说明与文档注释一致，且每个可见命令都有说明
+  #[test]
+  fn test_localized_about() {
+    fn check(command: &clap::Command, key: &str) {
+      if !command.is_hide_set() {
+        assert_eq!(
+          i18n::lookup(key),
+          command
+            .get_about()
+            .map(|about| about.to_string())
+            .as_deref(),
+          "{}",
+          key
+        );
+      }
+      for sub in command.get_subcommands() {
+        check(sub, &format!("{}.{}", key, sub.get_name()));
+      }
+    }
+    check(&Cli::command(), "cli");
+  }
 }
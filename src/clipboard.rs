@@ -0,0 +1,95 @@
+//! 系统剪贴板
+//!
+//! 依次尝试平台自带的剪贴板工具；都不可用时向终端发送 OSC 52 序列，
+//! 支持该序列的终端模拟器会写入剪贴板（SSH 会话中同样有效）。
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// 当前平台可用的剪贴板工具（程序名与参数）
+fn tools() -> Vec<(&'static str, &'static [&'static str])> {
+  if cfg!(target_os = "macos") {
+    vec![("pbcopy", &[])]
+  } else if cfg!(windows) {
+    vec![("clip", &[])]
+  } else {
+    vec![
+      ("wl-copy", &[]),
+      ("xclip", &["-selection", "clipboard"]),
+      ("xsel", &["--clipboard", "--input"]),
+    ]
+  }
+}
+
+/// 复制文本到剪贴板，返回使用的方式
+pub fn copy(text: &str) -> anyhow::Result<&'static str> {
+  for (program, args) in tools() {
+    if pipe_to(program, args, text) {
+      return Ok(program);
+    }
+  }
+
+  let mut stderr = std::io::stderr();
+  if stderr.is_terminal() {
+    write!(stderr, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stderr.flush()?;
+    return Ok("OSC 52");
+  }
+  anyhow::bail!("No clipboard available (install wl-clipboard, xclip or xsel)")
+}
+
+/// 把文本写入程序的标准输入，返回程序是否成功退出
+fn pipe_to(program: &str, args: &[&str], text: &str) -> bool {
+  let Ok(mut child) = Command::new(program)
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+  else {
+    return false;
+  };
+  let written = child
+    .stdin
+    .take()
+    .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+  child.wait().is_ok_and(|status| status.success()) && written
+}
+
+fn base64(data: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+  let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+  for chunk in data.chunks(3) {
+    let bytes = [
+      chunk[0],
+      *chunk.get(1).unwrap_or(&0),
+      *chunk.get(2).unwrap_or(&0),
+    ];
+    let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+    for i in 0..4 {
+      if i <= chunk.len() {
+        out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+      } else {
+        out.push('=');
+      }
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_base64() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(
+      base64("tar -xf 文件.tar".as_bytes()),
+      "dGFyIC14ZiDmlofku7YudGFy"
+    );
+  }
+}
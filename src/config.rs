@@ -2,13 +2,34 @@
 //!
 //! 提供应用配置的加载、解析和默认值管理。
 //! 配置文件采用 TOML 格式，支持从文件加载或使用内置默认值。
+//! 任意配置项都可以用 `RTFM_<SECTION>__<KEY>` 形式的环境变量覆盖
+//! （如 `RTFM_SERVER__PORT=8080`、`RTFM_LEARN__LLM__MODEL=llama3`），便于容器和 CI 使用。
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// 环境变量覆盖的前缀
+const ENV_PREFIX: &str = "RTFM_";
+/// 环境变量中分隔层级的分隔符（单个下划线属于键名本身）
+const ENV_SEPARATOR: &str = "__";
+
+/// 键名由用户决定的表（不检查未知键）
+const FREE_FORM_TABLES: &[&str] = &["learn.overrides"];
+
+/// 键名由用户决定、每项结构相同的表（检查每项内部的键）
+const NAMED_TABLES: &[&str] = &["commands", "users"];
+
+/// 改过名的键：旧键名仍可读取，校验时提示改用新键名
+const RENAMED_KEYS: &[(&str, &str)] = &[("tui.style", "tui.default_style")];
+
+/// tantivy 索引写入缓冲区的下限（字节），低于此值时使用下限
+pub const MIN_INDEX_BUFFER_SIZE: usize = 15_000_000;
+
 /// 应用配置
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct AppConfig {
   /// 服务器配置
@@ -17,42 +38,116 @@ pub struct AppConfig {
   pub search: SearchConfig,
   /// TUI 配置
   pub tui: TuiConfig,
+  /// 界面语言（CLI 与 TUI）
+  pub ui: UiConfig,
   /// 存储配置
   pub storage: StorageConfig,
   /// 日志配置
   pub logging: LoggingConfig,
   /// 更新配置
   pub update: UpdateConfig,
+  /// 学习配置
+  pub learn: LearnConfig,
+  /// 同步配置
+  pub sync: SyncConfig,
+  /// 插件配置
+  pub plugins: PluginsConfig,
+  /// 语义搜索（向量索引）配置
+  pub embeddings: EmbeddingsConfig,
+  /// serve 模式的定时任务
+  pub scheduler: SchedulerConfig,
+  /// 导入配置
+  pub import: ImportConfig,
+  /// 按命令名设置的偏好（[commands.<name>]）
+  pub commands: HashMap<String, CommandPrefs>,
+  /// HTTP 服务的用户（[users.<name>]，server.multi_user 开启时使用）
+  pub users: HashMap<String, UserConfig>,
 }
 
 /// HTTP 服务器配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ServerConfig {
   /// 监听端口
   pub port: u16,
   /// 绑定地址
   pub bind: String,
-  /// 最大上传文件大小（字节）
+  /// /api/import/file 的最大上传大小（字节）
   pub max_upload_size: usize,
+  /// /api/restore 的最大上传大小（字节）
+  pub max_restore_size: usize,
+  /// 其余接口的最大请求体大小（字节）
+  pub max_json_size: usize,
+  /// 允许跨域访问的来源，"*" 表示任意来源
+  pub cors_origins: Vec<String>,
+  /// 允许的跨域请求方法，"*" 表示任意方法
+  pub cors_methods: Vec<String>,
+  /// 允许的跨域请求头，"*" 表示任意请求头
+  pub cors_headers: Vec<String>,
+  /// 预检结果的缓存时间（秒），0 表示不发送 Access-Control-Max-Age
+  pub cors_max_age_secs: u64,
+  /// 多用户模式：API 需要 [users] 中的令牌，每个用户有自己的学习命令与使用统计
+  pub multi_user: bool,
+  /// 修改数据的接口需要的 API 密钥（多用户模式下改用用户令牌）
+  pub api_keys: Vec<String>,
+  /// 保存 API 密钥的文件，每行一个，忽略空行和 # 开头的行
+  pub api_key_file: Option<PathBuf>,
+  /// 每个客户端 IP 每分钟的请求数上限，0 表示不限制
+  pub rate_limit_per_minute: u32,
+  /// 请求的处理时间上限（秒），超时返回 408，0 表示不限制
+  pub request_timeout_secs: u64,
+  /// 只读：不提供修改数据的接口，与 serve --read-only 相同
+  pub read_only: bool,
+  /// 允许 /api/learn 的 url 参数（服务器会下载客户端给出的任意地址，包括内网）
+  pub allow_learn_url: bool,
+  /// 允许 /api/learn 的 llm 参数（服务器用自己的 API Key 把帮助文本发送给 learn.llm.endpoint）
+  pub allow_learn_llm: bool,
 }
 
 /// 搜索配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct SearchConfig {
   /// 默认搜索结果数量
   pub default_limit: usize,
   /// 最大搜索结果数量
   pub max_limit: usize,
+  /// 命令行查询（rtfm <name>）的结果数量
+  pub cli_limit: usize,
+  /// TUI 搜索的结果数量
+  pub tui_limit: usize,
   /// 索引写入缓冲区大小（字节）
   pub index_buffer_size: usize,
   /// 默认语言
   pub default_lang: String,
+  /// 查询中有中日韩文字时优先的语言（空表示不检测，总是 default_lang）
+  pub cjk_lang: String,
+  /// 结果的平台：auto（当前系统的命令优先）、all（不区分）或平台名（只保留该平台和 common）
+  pub platform: String,
+  /// 命令名字段的权重
+  pub name_boost: f32,
+  /// 描述字段的权重
+  pub description_boost: f32,
+  /// 内容字段的权重
+  pub content_boost: f32,
+  /// 名称与查询完全相同的命令的分数倍数（1 关闭）
+  pub exact_name_boost: f32,
+  /// 模糊匹配允许的编辑距离（0 关闭，最大 2）
+  pub fuzzy_distance: u8,
+  /// 模糊搜索（--fuzzy、fuzzy=true、TUI 无结果时）允许的编辑距离（1 或 2）
+  pub fuzzy_search_distance: u8,
+  /// jieba 用户词典路径（每行：词 [词频] [词性]）
+  pub user_dict: Option<PathBuf>,
+  /// 在本地数据库中记录查看的命令和搜索（rtfm stats --dashboard）
+  pub record_usage: bool,
+  /// 缓存最近查询结果的条数（0 关闭），索引提交后清空
+  pub result_cache_size: usize,
+  /// 每次搜索另外返回的匹配示例数（0 关闭）
+  pub example_results: usize,
 }
 
 /// TUI 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct TuiConfig {
   /// 事件轮询超时（毫秒）
@@ -61,12 +156,95 @@ pub struct TuiConfig {
   pub log_buffer_size: usize,
   /// 详情滚动步长
   pub scroll_step: u16,
-  /// 界面风格：modern 或 classic
-  pub style: String,
+  /// 输入停顿多久后开始搜索（毫秒，0 表示每次输入立即搜索）
+  pub search_debounce_ms: u64,
+  /// 默认界面风格：modern 或 classic（旧键名 style 仍可用）
+  #[serde(alias = "style")]
+  #[schemars(schema_with = "style_schema")]
+  pub default_style: String,
+  /// 记住运行时切换的界面风格（写回配置文件）
+  pub remember_style: bool,
+  /// 启用鼠标：点击切换焦点和选择结果、滚轮滚动、拖动选中详情中的文字
+  pub mouse: bool,
+  /// 查询为空时在最近查看之后列出的最常用命令数（0 表示不显示）
+  pub frequent_commands: usize,
+  /// 允许在 TUI 中确认后运行示例（run_example 快捷键）
+  pub allow_execute: bool,
+  /// 快捷键
+  pub keys: KeysConfig,
+}
+
+/// CLI 与 TUI 的界面配置
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct UiConfig {
+  /// 界面语言：auto（按系统语言）、en 或 zh
+  #[schemars(schema_with = "locale_schema")]
+  pub locale: String,
+  /// rtfm <name> 按 tldr 客户端规范查找和显示页面（同 --tldr-compat）
+  pub tldr_compat: bool,
+}
+
+/// 快捷键配置：动作 -> 按键列表（如 "ctrl+q"、"pagedown"、"j"），空列表表示不绑定
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KeysConfig {
+  /// 退出
+  pub quit: Vec<String>,
+  /// 显示/关闭帮助
+  pub help: Vec<String>,
+  /// 显示/隐藏调试日志面板
+  pub toggle_logs: Vec<String>,
+  /// 切换界面风格
+  pub toggle_style: Vec<String>,
+  /// 清空搜索框
+  pub clear_search: Vec<String>,
+  /// 向上移动/滚动
+  pub up: Vec<String>,
+  /// 向下移动/滚动
+  pub down: Vec<String>,
+  /// 上一个结果（Modern）/返回列表（Classic）
+  pub prev: Vec<String>,
+  /// 下一个结果（Modern）/查看详情（Classic）
+  pub next: Vec<String>,
+  /// 向上翻页
+  pub page_up: Vec<String>,
+  /// 向下翻页
+  pub page_down: Vec<String>,
+  /// 跳到开头
+  pub top: Vec<String>,
+  /// 跳到末尾
+  pub bottom: Vec<String>,
+  /// 切换焦点
+  pub next_focus: Vec<String>,
+  /// 聚焦搜索框
+  pub search: Vec<String>,
+  /// 清空/返回/退出
+  pub back: Vec<String>,
+  /// 查看详情
+  pub open: Vec<String>,
+  /// 收藏/取消收藏选中的命令
+  pub toggle_favorite: Vec<String>,
+  /// 只显示收藏的命令
+  pub favorites: Vec<String>,
+  /// 显示/关闭查看历史
+  pub history: Vec<String>,
+  /// 切换选中命令的语言
+  pub cycle_lang: Vec<String>,
+  /// 用编辑器编辑选中命令的笔记
+  pub notes: Vec<String>,
+  /// 详情中的下一个示例
+  pub next_example: Vec<String>,
+  /// 详情中的上一个示例
+  pub prev_example: Vec<String>,
+  /// 复制选中的示例
+  pub copy_example: Vec<String>,
+  /// 确认后运行选中的示例（需开启 tui.allow_execute）
+  pub run_example: Vec<String>,
 }
 
 /// 存储配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct StorageConfig {
   /// 数据目录（空表示使用默认路径）
@@ -77,10 +255,12 @@ pub struct StorageConfig {
   pub index_dirname: String,
   /// 日志目录名
   pub log_dirname: String,
+  /// 向量索引文件名（rtfm embed rebuild 生成）
+  pub embeddings_filename: String,
 }
 
 /// 日志配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct LoggingConfig {
   /// 默认日志级别
@@ -90,7 +270,7 @@ pub struct LoggingConfig {
 }
 
 /// 更新配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct UpdateConfig {
   /// tldr-pages GitHub API 地址（获取最新版本）
@@ -103,6 +283,223 @@ pub struct UpdateConfig {
   pub fallback_version: String,
   /// 允许导入的语言列表（空表示全部）
   pub languages: Vec<String>,
+  /// cheat/cheatsheets 压缩包下载地址（rtfm update --source cheat）
+  pub cheat_url: String,
+  /// serve/TUI 运行期间检查数据集与 rtfm 新版本的间隔（小时，0 表示不检查）
+  pub check_interval_hours: u64,
+  /// serve 运行期间自动下载并导入 tldr 新版本的间隔（小时，0 表示不自动更新）
+  pub auto_check_interval_hours: u64,
+  /// 发现新版本时发送桌面通知
+  pub notify: bool,
+  /// rtfm 最新发布的 GitHub API 地址
+  pub release_api_url: String,
+}
+
+/// 学习配置
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LearnConfig {
+  /// learn-all 跳过的命令（glob 模式，支持 * 和 ?）
+  pub deny: Vec<String>,
+  /// learn-all 只学习匹配的命令（glob 模式，空表示全部）
+  pub allow: Vec<String>,
+  /// 命令名 -> 完整的帮助调用方式（如 java = "java -help"）
+  pub overrides: HashMap<String, String>,
+  /// 运行 --help 的超时（秒），超时未输出的命令视为交互式程序并跳过
+  pub help_timeout_secs: u64,
+  /// rtfm learn 学习命令后导入指向它的 shell 和 git 别名（同 rtfm alias import）
+  pub import_aliases: bool,
+  /// learn-all 同时获取帮助的命令数，0 表示 CPU 核数
+  pub jobs: usize,
+  /// LLM 辅助生成 tldr 风格示例
+  pub llm: LlmConfig,
+}
+
+/// LLM 辅助摘要配置（OpenAI 兼容接口，可指向本地模型）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LlmConfig {
+  /// 是否在学习时自动调用（默认关闭，也可通过 `rtfm learn --llm` 单次启用）
+  pub enabled: bool,
+  /// Chat Completions 接口地址
+  pub endpoint: String,
+  /// 模型名称
+  pub model: String,
+  /// API Key（留空则读取 api_key_env 指定的环境变量）
+  pub api_key: Option<String>,
+  /// 存放 API Key 的环境变量名
+  pub api_key_env: String,
+  /// 发送给模型的最大字符数（超出部分截断）
+  pub max_input_chars: usize,
+  /// 生成的最大示例数
+  pub max_examples: usize,
+  /// 请求超时（秒）
+  pub timeout_secs: u64,
+}
+
+/// ui.locale 只接受 auto、en 和 zh
+fn locale_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+  schemars::schema::SchemaObject {
+    instance_type: Some(schemars::schema::InstanceType::String.into()),
+    enum_values: Some(vec!["auto".into(), "en".into(), "zh".into()]),
+    ..Default::default()
+  }
+  .into()
+}
+
+/// tui.default_style 只接受 modern 和 classic
+fn style_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+  schemars::schema::SchemaObject {
+    instance_type: Some(schemars::schema::InstanceType::String.into()),
+    enum_values: Some(vec!["modern".into(), "classic".into()]),
+    ..Default::default()
+  }
+  .into()
+}
+
+/// 同步配置（rtfm sync push/pull）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SyncConfig {
+  /// 默认的远程服务器地址（如 http://team-rtfm:3030）
+  pub remote: Option<String>,
+  /// 冲突处理策略：lww（保留较新的版本）或 merge（合并示例）
+  pub policy: String,
+  /// 远程服务器的 API 密钥或用户令牌（留空则读取 token_env 指定的环境变量）
+  pub token: Option<String>,
+  /// 存放令牌的环境变量名
+  pub token_env: String,
+}
+
+impl SyncConfig {
+  /// 同步使用的令牌：配置中的 token，否则为 token_env 指定的环境变量
+  pub fn token(&self) -> Option<String> {
+    self
+      .token
+      .clone()
+      .or_else(|| std::env::var(&self.token_env).ok())
+      .filter(|token| !token.trim().is_empty())
+  }
+}
+
+/// 语义搜索配置（默认在本机运行 ONNX 模型，也可以使用 OpenAI 兼容的 embeddings 接口）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+  /// 生成向量的方式：local（本机 ONNX 模型）或 http（embeddings 接口）
+  pub provider: String,
+  /// Embeddings 接口地址（provider 为 http 时）
+  pub endpoint: String,
+  /// 模型名称：local 为 fastembed 支持的模型代码，http 为接口的模型名（更换模型后需要 rtfm embed rebuild）
+  pub model: String,
+  /// API Key（留空则读取 api_key_env 指定的环境变量）
+  pub api_key: Option<String>,
+  /// 存放 API Key 的环境变量名
+  pub api_key_env: String,
+  /// 每次请求的文本数
+  pub batch_size: usize,
+  /// 请求超时（秒）
+  pub timeout_secs: u64,
+  /// 与关键词结果融合时语义结果的权重（0-1）
+  pub weight: f32,
+  /// 建立向量的语言（空表示 search.default_lang 和本机学习的命令）
+  pub languages: Vec<String>,
+}
+
+/// 插件配置
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PluginsConfig {
+  /// 插件目录（空表示配置目录下的 plugins，如 ~/.config/rtfm/plugins）
+  pub dir: Option<PathBuf>,
+  /// 单次调用插件的超时时间（秒）
+  pub timeout_secs: u64,
+  /// 运行 WASM 插件的 WASI 运行时（需支持 `<runtime> run <module>`）
+  pub wasm_runtime: String,
+  /// 停用的插件名
+  pub disabled: Vec<String>,
+}
+
+/// serve 模式的定时任务：cron 表达式（5 个字段或 @daily 等，按本地时间），空字符串表示不运行
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SchedulerConfig {
+  /// 检查并下载 tldr 更新
+  pub update: String,
+  /// 合并索引段
+  pub optimize: String,
+  /// 压缩数据库文件
+  pub compact: String,
+  /// 重新学习二进制已变化的命令（同 rtfm learn --refresh）
+  pub refresh_learned: String,
+  /// 备份数据（同 rtfm backup）
+  pub backup: String,
+  /// 备份目录（空表示数据目录下的 backups）
+  pub backup_dir: Option<PathBuf>,
+  /// 保留的备份数，0 表示全部保留
+  pub backup_keep: usize,
+  /// 保留的运行记录数
+  pub history_size: usize,
+}
+
+/// 导入配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ImportConfig {
+  /// serve 模式下监视的目录：其中新增或修改的 .md 文件自动导入（修改后需重启服务）
+  pub watch_dirs: Vec<PathBuf>,
+}
+
+impl SchedulerConfig {
+  /// 任务名与对应的 cron 表达式
+  pub fn schedules(&self) -> [(&'static str, &str); 5] {
+    [
+      ("update", &self.update),
+      ("optimize", &self.optimize),
+      ("compact", &self.compact),
+      ("refresh_learned", &self.refresh_learned),
+      ("backup", &self.backup),
+    ]
+  }
+}
+
+/// 单个命令的偏好设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CommandPrefs {
+  /// 优先显示的语言（命令行 --lang 优先）
+  pub lang: Option<String>,
+  /// 固定的平台页面（如 linux、osx）：导入 tldr 时同名页面保留该平台的版本
+  pub platform: Option<String>,
+  /// `rtfm <name> --copy` 默认复制的示例序号（从 1 开始）
+  pub example: Option<usize>,
+}
+
+/// HTTP 服务的一个用户
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct UserConfig {
+  /// 访问令牌的 SHA-256（十六进制，由 rtfm user add 生成）
+  pub token_sha256: String,
+  /// 管理员可以修改共享数据（导入、恢复、重置、更新、同步推送）
+  pub admin: bool,
+}
+
+/// 解析定时任务的 cron 表达式，空字符串表示不运行
+pub fn parse_schedule(schedule: &str) -> Result<Option<croner::Cron>, croner::errors::CronError> {
+  if schedule.trim().is_empty() {
+    return Ok(None);
+  }
+  croner::Cron::new(schedule.trim()).parse().map(Some)
+}
+
+/// 用户名用作数据目录名，只允许字母、数字、- 和 _
+pub fn is_valid_user_name(name: &str) -> bool {
+  !name.is_empty()
+    && name.len() <= 64
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
 }
 
 // 默认值实现
@@ -112,8 +509,52 @@ impl Default for ServerConfig {
     Self {
       port: 3030,
       bind: "127.0.0.1".to_string(),
-      max_upload_size: 100 * 1024 * 1024, // 100MB
+      max_upload_size: 100 * 1024 * 1024,  // 100MB
+      max_restore_size: 500 * 1024 * 1024, // 500MB
+      max_json_size: 2 * 1024 * 1024,      // 2MB
+      cors_origins: vec!["*".to_string()],
+      cors_methods: vec!["*".to_string()],
+      cors_headers: vec!["*".to_string()],
+      cors_max_age_secs: 0,
+      multi_user: false,
+      api_keys: Vec::new(),
+      api_key_file: None,
+      rate_limit_per_minute: 0,
+      request_timeout_secs: 60,
+      read_only: false,
+      allow_learn_url: false,
+      allow_learn_llm: false,
+    }
+  }
+}
+
+impl ServerConfig {
+  /// 修改数据的接口是否需要 API 密钥
+  pub fn requires_api_key(&self) -> bool {
+    !self.multi_user && (!self.api_keys.is_empty() || self.api_key_file.is_some())
+  }
+
+  /// 配置中的密钥加上密钥文件中的密钥；文件在每次调用时读取，修改后不必重启
+  pub fn load_api_keys(&self) -> std::io::Result<Vec<String>> {
+    let mut keys = self.api_keys.clone();
+    if let Some(path) = &self.api_key_file {
+      keys.extend(
+        std::fs::read_to_string(path)?
+          .lines()
+          .map(str::trim)
+          .filter(|line| !line.is_empty() && !line.starts_with('#'))
+          .map(str::to_string),
+      );
     }
+    Ok(keys)
+  }
+
+  /// 来源是否在 CORS 白名单中
+  pub fn allows_origin(&self, origin: &str) -> bool {
+    self
+      .cors_origins
+      .iter()
+      .any(|allowed| allowed == "*" || allowed.trim_end_matches('/') == origin)
   }
 }
 
@@ -122,19 +563,91 @@ impl Default for SearchConfig {
     Self {
       default_limit: 20,
       max_limit: 100,
+      cli_limit: 10,
+      tui_limit: 100,
       index_buffer_size: 50_000_000,
       default_lang: "en".to_string(),
+      cjk_lang: "zh".to_string(),
+      platform: "auto".to_string(),
+      name_boost: 4.0,
+      description_boost: 2.0,
+      content_boost: 1.0,
+      exact_name_boost: 3.0,
+      fuzzy_distance: 0,
+      fuzzy_search_distance: 1,
+      user_dict: None,
+      record_usage: true,
+      result_cache_size: 256,
+      example_results: 5,
     }
   }
 }
 
+impl SearchConfig {
+  /// 查询应优先的语言：有中日韩文字时为 cjk_lang，否则为 default_lang
+  pub fn query_lang(&self, query: &str) -> &str {
+    crate::search::query_lang(query, &self.default_lang, &self.cjk_lang)
+  }
+}
+
 impl Default for TuiConfig {
   fn default() -> Self {
     Self {
       poll_timeout_ms: 100,
       log_buffer_size: 100,
       scroll_step: 1,
-      style: "modern".to_string(),
+      search_debounce_ms: 150,
+      default_style: "modern".to_string(),
+      remember_style: true,
+      mouse: true,
+      frequent_commands: 5,
+      allow_execute: false,
+      keys: KeysConfig::default(),
+    }
+  }
+}
+
+impl Default for UiConfig {
+  fn default() -> Self {
+    Self {
+      locale: "auto".to_string(),
+      tldr_compat: false,
+    }
+  }
+}
+
+impl Default for KeysConfig {
+  fn default() -> Self {
+    fn keys(keys: &[&str]) -> Vec<String> {
+      keys.iter().map(|k| k.to_string()).collect()
+    }
+    Self {
+      quit: keys(&["ctrl+c", "ctrl+q"]),
+      help: keys(&["ctrl+h", "?"]),
+      toggle_logs: keys(&["ctrl+l"]),
+      toggle_style: keys(&["ctrl+t"]),
+      clear_search: keys(&["ctrl+u"]),
+      up: keys(&["up", "k"]),
+      down: keys(&["down", "j"]),
+      prev: keys(&["left", "h"]),
+      next: keys(&["right", "l"]),
+      page_up: keys(&["pageup"]),
+      page_down: keys(&["pagedown"]),
+      top: keys(&["home", "g"]),
+      bottom: keys(&["end", "G"]),
+      next_focus: keys(&["tab"]),
+      search: keys(&["/"]),
+      back: keys(&["esc"]),
+      open: keys(&["enter"]),
+      toggle_favorite: keys(&["f"]),
+      favorites: keys(&["ctrl+f"]),
+      history: keys(&["ctrl+r"]),
+      cycle_lang: keys(&["L"]),
+      notes: keys(&["n"]),
+      next_example: keys(&["]"]),
+      prev_example: keys(&["["]),
+      copy_example: keys(&["y"]),
+      run_example: keys(&["x"]),
     }
   }
 }
@@ -146,6 +659,7 @@ impl Default for StorageConfig {
       db_filename: "data.redb".to_string(),
       index_dirname: "index".to_string(),
       log_dirname: "logs".to_string(),
+      embeddings_filename: "embeddings.bin".to_string(),
     }
   }
 }
@@ -168,18 +682,130 @@ impl Default for UpdateConfig {
       user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36".to_string(),
       fallback_version: "v2.3".to_string(),
       languages: vec!["en".to_string(), "zh".to_string()],
+      cheat_url: "https://github.com/cheat/cheatsheets/archive/refs/heads/master.zip".to_string(),
+      check_interval_hours: 24,
+      auto_check_interval_hours: 0,
+      notify: true,
+      release_api_url: "https://api.github.com/repos/F2077/rtfm/releases/latest".to_string(),
+    }
+  }
+}
+
+impl Default for LearnConfig {
+  fn default() -> Self {
+    Self {
+      // 有副作用或会打开图形界面的命令，以及个人脚本
+      deny: [
+        "shutdown", "reboot", "halt", "poweroff", "init", "telinit", "kexec", "mkfs*", "xeyes",
+        "xclock", "xcalc", "xlogo", "xterm", "xev", "xmessage", "*.sh", "*.py",
+      ]
+      .iter()
+      .map(|s| s.to_string())
+      .collect(),
+      allow: Vec::new(),
+      overrides: [
+        ("java", "java -help"),
+        ("ffmpeg", "ffmpeg -h full"),
+        ("terraform", "terraform -help"),
+      ]
+      .iter()
+      .map(|(k, v)| (k.to_string(), v.to_string()))
+      .collect(),
+      help_timeout_secs: 5,
+      import_aliases: false,
+      jobs: 0,
+      llm: LlmConfig::default(),
+    }
+  }
+}
+
+impl Default for SyncConfig {
+  fn default() -> Self {
+    Self {
+      remote: None,
+      policy: "lww".to_string(),
+      token: None,
+      token_env: "RTFM_SYNC_TOKEN".to_string(),
+    }
+  }
+}
+
+impl Default for SchedulerConfig {
+  fn default() -> Self {
+    Self {
+      update: String::new(),
+      optimize: "0 4 * * 0".to_string(),
+      compact: "30 4 * * 0".to_string(),
+      refresh_learned: String::new(),
+      backup: String::new(),
+      backup_dir: None,
+      backup_keep: 7,
+      history_size: 50,
+    }
+  }
+}
+
+impl Default for EmbeddingsConfig {
+  fn default() -> Self {
+    Self {
+      provider: "local".to_string(),
+      endpoint: "http://localhost:11434/v1/embeddings".to_string(),
+      model: "intfloat/multilingual-e5-small".to_string(),
+      api_key: None,
+      api_key_env: "OPENAI_API_KEY".to_string(),
+      batch_size: 64,
+      timeout_secs: 60,
+      weight: 0.5,
+      languages: Vec::new(),
+    }
+  }
+}
+
+impl Default for PluginsConfig {
+  fn default() -> Self {
+    Self {
+      dir: None,
+      timeout_secs: 30,
+      wasm_runtime: "wasmtime".to_string(),
+      disabled: Vec::new(),
+    }
+  }
+}
+
+impl Default for LlmConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      endpoint: "https://api.openai.com/v1/chat/completions".to_string(),
+      model: "gpt-4o-mini".to_string(),
+      api_key: None,
+      api_key_env: "OPENAI_API_KEY".to_string(),
+      max_input_chars: 12_000,
+      max_examples: 8,
+      timeout_secs: 60,
     }
   }
 }
 
 impl AppConfig {
-  /// 从 TOML 文件加载配置
-  /// 如果文件不存在，返回默认配置
+  /// 从 TOML 文件加载配置，并应用环境变量覆盖
+  /// 如果文件不存在，使用默认配置
   pub fn load(path: &Path) -> Self {
+    let mut table = toml::Table::new();
     if path.exists() {
       match std::fs::read_to_string(path) {
-        Ok(content) => match toml::from_str(&content) {
-          Ok(config) => return config,
+        Ok(content) => match content.parse::<toml::Table>() {
+          Ok(parsed) => {
+            // 拼写错误的键会被默认值静默替代，这里至少给出提示
+            for key in unknown_keys(&parsed) {
+              eprintln!(
+                "Warning: Unknown config key '{}' in {} (run 'rtfm config validate')",
+                key,
+                path.display()
+              );
+            }
+            table = parsed;
+          }
           Err(e) => {
             eprintln!("Warning: Failed to parse config file: {}", e);
           }
@@ -189,30 +815,406 @@ impl AppConfig {
         }
       }
     }
-    Self::default()
+    Self::from_table(table, std::env::vars())
+  }
+
+  /// 合并环境变量覆盖后反序列化
+  /// 覆盖值类型不对时忽略全部覆盖并给出警告，避免一个错误的变量让整个配置回退为默认值
+  fn from_table(table: toml::Table, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+    let mut merged = table.clone();
+    let applied = apply_env_overrides(&mut merged, vars);
+    if !applied.is_empty() {
+      match toml::Value::Table(merged).try_into() {
+        Ok(config) => return config,
+        Err(e) => {
+          eprintln!(
+            "Warning: Ignoring environment overrides ({}): {}",
+            applied.join(", "),
+            e
+          );
+        }
+      }
+    }
+
+    toml::Value::Table(table).try_into().unwrap_or_else(|e| {
+      eprintln!("Warning: Failed to parse config file: {}", e);
+      Self::default()
+    })
   }
 
   /// 从默认位置加载配置
   /// 优先级：
   /// 1. 当前目录下的 rtfm.toml
-  /// 2. 数据目录下的 config.toml
-  /// 3. 内置默认值
+  /// 2. 配置目录下的 rtfm/config.toml（$XDG_CONFIG_HOME 或平台对应目录）
+  /// 3. 数据目录下的 config.toml（旧位置）
+  /// 4. 内置默认值
   pub fn load_default() -> Self {
-    // 当前目录
-    let current_config = PathBuf::from("rtfm.toml");
-    if current_config.exists() {
-      return Self::load(&current_config);
+    match Self::default_path() {
+      Some(path) => Self::load(&path),
+      // 默认值（环境变量覆盖仍然生效）
+      None => Self::from_table(toml::Table::new(), std::env::vars()),
+    }
+  }
+
+  /// 按优先级查找存在的配置文件
+  pub fn default_path() -> Option<PathBuf> {
+    Self::search_paths().into_iter().find(|p| p.exists())
+  }
+
+  /// 配置文件的查找位置（按优先级），RTFM_CONFIG 指定的文件优先
+  /// macOS 上配置目录与数据目录相同，去重后只保留一项
+  pub fn search_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::env::var_os("RTFM_CONFIG")
+      .map(PathBuf::from)
+      .into_iter()
+      .collect();
+    paths.push(PathBuf::from("rtfm.toml"));
+    if let Some(config_dir) = get_config_dir() {
+      paths.push(config_dir.join("config.toml"));
+    }
+    let legacy = get_default_data_dir().join("config.toml");
+    if !paths.contains(&legacy) {
+      paths.push(legacy);
+    }
+    paths
+  }
+
+  /// 配置文件的 JSON Schema，供编辑器补全和校验 rtfm.toml
+  pub fn json_schema() -> serde_json::Value {
+    let mut schema = schemars::schema_for!(AppConfig);
+    let metadata = schema.schema.metadata();
+    metadata.title = Some("rtfm configuration".to_string());
+    metadata.description = Some("Configuration file for rtfm (rtfm.toml)".to_string());
+    serde_json::to_value(schema).expect("config schema is always serializable")
+  }
+
+  /// 命令的偏好设置；名称中的空格按 tldr 规范视为 `-`
+  pub fn command_prefs(&self, name: &str) -> Option<&CommandPrefs> {
+    let name = name.trim();
+    self
+      .commands
+      .get(name)
+      .or_else(|| self.commands.get(&name.replace(' ', "-")))
+  }
+
+  /// 检查取值范围与相互冲突的设置
+  pub fn check(&self) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let mut error = |message: String| issues.push(ConfigIssue::error(message));
+
+    if self.server.port == 0 {
+      error("server.port must be between 1 and 65535".to_string());
+    }
+    if format!("{}:{}", self.server.bind, self.server.port)
+      .parse::<std::net::SocketAddr>()
+      .is_err()
+    {
+      error(format!(
+        "server.bind '{}' is not a valid IP address",
+        self.server.bind
+      ));
+    }
+    for (key, size) in [
+      ("max_upload_size", self.server.max_upload_size),
+      ("max_restore_size", self.server.max_restore_size),
+      ("max_json_size", self.server.max_json_size),
+    ] {
+      if size == 0 {
+        error(format!("server.{} must be greater than 0", key));
+      }
+    }
+    for method in self.server.cors_methods.iter().filter(|m| *m != "*") {
+      if axum::http::Method::from_bytes(method.as_bytes()).is_err() {
+        error(format!(
+          "server.cors_methods entry '{}' is not a valid HTTP method",
+          method
+        ));
+      }
+    }
+    for header in self.server.cors_headers.iter().filter(|h| *h != "*") {
+      if axum::http::HeaderName::from_bytes(header.as_bytes()).is_err() {
+        error(format!(
+          "server.cors_headers entry '{}' is not a valid header name",
+          header
+        ));
+      }
+    }
+    for origin in &self.server.cors_origins {
+      if origin != "*" && !origin.starts_with("http://") && !origin.starts_with("https://") {
+        error(format!(
+          "server.cors_origins entry '{}' must be \"*\" or start with http:// or https://",
+          origin
+        ));
+      }
+    }
+    if self.search.default_limit == 0 {
+      error("search.default_limit must be at least 1".to_string());
+    }
+    if self.search.max_limit == 0 {
+      error("search.max_limit must be at least 1".to_string());
+    }
+    if self.search.default_limit > self.search.max_limit {
+      error(format!(
+        "search.default_limit ({}) is greater than search.max_limit ({})",
+        self.search.default_limit, self.search.max_limit
+      ));
+    }
+    if self.search.default_lang.trim().is_empty() {
+      error("search.default_lang must not be empty".to_string());
+    }
+    for (key, limit) in [
+      ("cli_limit", self.search.cli_limit),
+      ("tui_limit", self.search.tui_limit),
+    ] {
+      if limit == 0 {
+        error(format!("search.{} must be at least 1", key));
+      }
+    }
+    for (key, boost) in [
+      ("name_boost", self.search.name_boost),
+      ("description_boost", self.search.description_boost),
+      ("content_boost", self.search.content_boost),
+    ] {
+      if !boost.is_finite() || boost <= 0.0 {
+        error(format!("search.{} ({}) must be greater than 0", key, boost));
+      }
+    }
+    let exact = self.search.exact_name_boost;
+    if !exact.is_finite() || exact < 1.0 {
+      error(format!(
+        "search.exact_name_boost ({}) must be at least 1",
+        exact
+      ));
+    }
+    if self.search.fuzzy_distance > 2 {
+      error(format!(
+        "search.fuzzy_distance ({}) must be between 0 and 2",
+        self.search.fuzzy_distance
+      ));
+    }
+    if !(1..=2).contains(&self.search.fuzzy_search_distance) {
+      error(format!(
+        "search.fuzzy_search_distance ({}) must be 1 or 2",
+        self.search.fuzzy_search_distance
+      ));
+    }
+    if self.server.api_keys.iter().any(|key| key.trim().is_empty()) {
+      error("server.api_keys must not contain empty keys".to_string());
+    }
+    if let (Some(path), Err(e)) = (&self.server.api_key_file, self.server.load_api_keys()) {
+      error(format!(
+        "server.api_key_file '{}' cannot be read: {}",
+        path.display(),
+        e
+      ));
+    }
+    if let Some(path) = &self.search.user_dict {
+      if !path.is_file() {
+        error(format!(
+          "search.user_dict '{}' does not exist",
+          path.display()
+        ));
+      }
+    }
+    if !["modern", "classic"].contains(&self.tui.default_style.as_str()) {
+      error(format!(
+        "tui.default_style '{}' must be \"modern\" or \"classic\"",
+        self.tui.default_style
+      ));
+    }
+    if !["auto", "en", "zh"].contains(&self.ui.locale.as_str()) {
+      error(format!(
+        "ui.locale '{}' must be \"auto\", \"en\" or \"zh\"",
+        self.ui.locale
+      ));
+    }
+    if self.tui.poll_timeout_ms == 0 {
+      error("tui.poll_timeout_ms must be greater than 0".to_string());
+    }
+    if let Err(errors) = crate::tui::keymap::Keymap::from_config(&self.tui.keys) {
+      for message in errors {
+        error(format!("tui.keys: {}", message));
+      }
+    }
+    if self.tui.scroll_step == 0 {
+      error("tui.scroll_step must be at least 1".to_string());
+    }
+    for (key, name) in [
+      ("storage.db_filename", &self.storage.db_filename),
+      ("storage.index_dirname", &self.storage.index_dirname),
+      ("storage.log_dirname", &self.storage.log_dirname),
+    ] {
+      if name.trim().is_empty() {
+        error(format!("{} must not be empty", key));
+      }
+    }
+    for (key, level) in [
+      ("logging.level", &self.logging.level),
+      ("logging.debug_level", &self.logging.debug_level),
+    ] {
+      if tracing_subscriber::EnvFilter::try_new(level).is_err() {
+        error(format!("{} '{}' is not a valid log filter", key, level));
+      }
+    }
+    if self.learn.llm.enabled && self.learn.llm.endpoint.trim().is_empty() {
+      error("learn.llm.enabled is true but learn.llm.endpoint is empty".to_string());
+    }
+    if self.learn.llm.timeout_secs == 0 {
+      error("learn.llm.timeout_secs must be greater than 0".to_string());
+    }
+    if let Some(remote) = &self.sync.remote {
+      if !remote.starts_with("http://") && !remote.starts_with("https://") {
+        error(format!(
+          "sync.remote '{}' must start with http:// or https://",
+          remote
+        ));
+      }
+    }
+    if crate::sync::SyncPolicy::parse(&self.sync.policy).is_none() {
+      error(format!(
+        "sync.policy '{}' must be \"lww\" or \"merge\"",
+        self.sync.policy
+      ));
+    }
+    match crate::embed::Provider::parse(&self.embeddings.provider) {
+      Some(crate::embed::Provider::Local) => {
+        if let Err(e) = crate::embed::local_model(&self.embeddings.model) {
+          error(format!("embeddings.model {}", e));
+        }
+      }
+      Some(crate::embed::Provider::Http) => {
+        if self.embeddings.endpoint.trim().is_empty() {
+          error("embeddings.endpoint must not be empty".to_string());
+        }
+      }
+      None => error(format!(
+        "embeddings.provider '{}' must be \"local\" or \"http\"",
+        self.embeddings.provider
+      )),
+    }
+    if self.embeddings.batch_size == 0 {
+      error("embeddings.batch_size must be at least 1".to_string());
+    }
+    if self.embeddings.timeout_secs == 0 {
+      error("embeddings.timeout_secs must be greater than 0".to_string());
+    }
+    if !(0.0..=1.0).contains(&self.embeddings.weight) {
+      error(format!(
+        "embeddings.weight must be between 0 and 1, got {}",
+        self.embeddings.weight
+      ));
+    }
+    if self.plugins.timeout_secs == 0 {
+      error("plugins.timeout_secs must be greater than 0".to_string());
+    }
+    if self.plugins.wasm_runtime.trim().is_empty() {
+      error("plugins.wasm_runtime must not be empty".to_string());
+    }
+    let mut names: Vec<&String> = self.commands.keys().collect();
+    names.sort();
+    for name in names {
+      let prefs = &self.commands[name];
+      for (key, value) in [("lang", &prefs.lang), ("platform", &prefs.platform)] {
+        if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+          error(format!("commands.{}.{} must not be empty", name, key));
+        }
+      }
+      if prefs.example == Some(0) {
+        error(format!(
+          "commands.{}.example must be at least 1 (examples are numbered from 1)",
+          name
+        ));
+      }
     }
 
-    // 数据目录
-    let data_dir = get_default_data_dir();
-    let data_config = data_dir.join("config.toml");
-    if data_config.exists() {
-      return Self::load(&data_config);
+    let mut users: Vec<&String> = self.users.keys().collect();
+    users.sort();
+    for name in users {
+      if !is_valid_user_name(name) {
+        error(format!(
+          "users.{} is not a valid user name (use letters, digits, - and _)",
+          name
+        ));
+      }
+      let hash = &self.users[name].token_sha256;
+      if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        error(format!(
+          "users.{}.token_sha256 must be a SHA-256 hex digest (use 'rtfm user add')",
+          name
+        ));
+      }
+    }
+
+    if self.server.multi_user && self.users.is_empty() {
+      issues.push(ConfigIssue::warning(
+        "server.multi_user is on but [users] is empty; every API request will be rejected"
+          .to_string(),
+      ));
+    }
+    if self.server.multi_user
+      && (!self.server.api_keys.is_empty() || self.server.api_key_file.is_some())
+    {
+      issues.push(ConfigIssue::warning(
+        "server.api_keys and server.api_key_file are ignored in multi-user mode; user tokens are used instead"
+          .to_string(),
+      ));
+    } else if self.server.requires_api_key()
+      && self
+        .server
+        .load_api_keys()
+        .is_ok_and(|keys| keys.is_empty())
+    {
+      issues.push(ConfigIssue::warning(
+        "server.api_key_file has no keys; every data modification will be rejected".to_string(),
+      ));
+    }
+    for dir in &self.import.watch_dirs {
+      if !dir.is_dir() {
+        issues.push(ConfigIssue::warning(format!(
+          "import.watch_dirs entry '{}' is not a directory and will not be watched",
+          dir.display()
+        )));
+      }
+    }
+    if self.search.index_buffer_size < MIN_INDEX_BUFFER_SIZE {
+      issues.push(ConfigIssue::warning(format!(
+        "search.index_buffer_size ({}) is below the index minimum of {} bytes, the minimum is used",
+        self.search.index_buffer_size, MIN_INDEX_BUFFER_SIZE
+      )));
+    }
+    if !self.update.download_url_template.contains("{version}") {
+      issues.push(ConfigIssue::warning(
+        "update.download_url_template has no {version} placeholder".to_string(),
+      ));
+    }
+    if self.learn.help_timeout_secs == 0 {
+      issues.push(ConfigIssue::warning(
+        "learn.help_timeout_secs is 0; 1 second is used instead".to_string(),
+      ));
+    }
+    if self.learn.llm.max_examples == 0 {
+      issues.push(ConfigIssue::warning(
+        "learn.llm.max_examples is 0; LLM enhancement will add nothing".to_string(),
+      ));
+    }
+    for pattern in &self.learn.allow {
+      if self.learn.deny.contains(pattern) {
+        issues.push(ConfigIssue::warning(format!(
+          "'{}' is in both learn.allow and learn.deny (deny wins)",
+          pattern
+        )));
+      }
+    }
+    for (job, schedule) in self.scheduler.schedules() {
+      if let Err(e) = parse_schedule(schedule) {
+        issues.push(ConfigIssue::error(format!(
+          "scheduler.{} '{}' is not a valid cron expression: {}",
+          job, schedule, e
+        )));
+      }
     }
 
-    // 默认值
-    Self::default()
+    issues
   }
 
   /// 获取数据目录
@@ -225,13 +1227,350 @@ impl AppConfig {
       .unwrap_or_else(get_default_data_dir)
   }
 
+  /// 插件目录
+  pub fn plugins_dir(&self) -> PathBuf {
+    self
+      .plugins
+      .dir
+      .clone()
+      .or_else(|| get_config_dir().map(|dir| dir.join("plugins")))
+      .unwrap_or_else(|| self.get_data_dir().join("plugins"))
+  }
+
+  /// 把运行时切换的界面风格写回配置文件，没有配置文件时写入配置目录
+  /// 返回写入的路径
+  pub fn save_default_style(style: &str) -> anyhow::Result<PathBuf> {
+    Self::edit_file(|content| set_default_style(content, style))
+  }
+
+  /// 在配置文件中添加、替换（Some）或删除（None）一个用户
+  /// 返回写入的路径
+  pub fn save_user(name: &str, user: Option<&UserConfig>) -> anyhow::Result<PathBuf> {
+    Self::edit_file(|content| set_user(content, name, user))
+  }
+
+  /// 修改正在使用的配置文件，没有配置文件时写入配置目录
+  fn edit_file(edit: impl FnOnce(&str) -> anyhow::Result<String>) -> anyhow::Result<PathBuf> {
+    let path = Self::default_path()
+      .or_else(|| get_config_dir().map(|dir| dir.join("config.toml")))
+      .ok_or_else(|| anyhow::anyhow!("No config directory available"))?;
+    let content = if path.exists() {
+      std::fs::read_to_string(&path)?
+    } else {
+      String::new()
+    };
+    let updated = edit(&content)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, updated)?;
+    Ok(path)
+  }
+
   /// 生成默认配置文件内容
   pub fn to_toml(&self) -> String {
     toml::to_string_pretty(self).unwrap_or_default()
   }
 }
 
+/// 配置问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueLevel {
+  Error,
+  Warning,
+}
+
+/// 配置检查发现的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+  pub level: IssueLevel,
+  pub message: String,
+}
+
+impl ConfigIssue {
+  fn error(message: String) -> Self {
+    Self {
+      level: IssueLevel::Error,
+      message,
+    }
+  }
+
+  fn warning(message: String) -> Self {
+    Self {
+      level: IssueLevel::Warning,
+      message,
+    }
+  }
+}
+
+/// 严格校验配置文件内容（已合并环境变量覆盖）：语法、未知键、类型、取值范围与冲突
+pub fn validate(
+  content: &str,
+  vars: impl IntoIterator<Item = (String, String)>,
+) -> Vec<ConfigIssue> {
+  let mut table = match content.parse::<toml::Table>() {
+    Ok(table) => table,
+    Err(e) => return vec![ConfigIssue::error(format!("invalid TOML: {}", e))],
+  };
+  apply_env_overrides(&mut table, vars);
+
+  let mut issues: Vec<ConfigIssue> = unknown_keys(&table)
+    .into_iter()
+    .map(|key| {
+      let hint = suggest_key(&key)
+        .map(|s| format!(" (did you mean '{}'?)", s))
+        .unwrap_or_default();
+      ConfigIssue::warning(format!("unknown key '{}'{}", key, hint))
+    })
+    .collect();
+  for (old, new) in RENAMED_KEYS {
+    let path: Vec<String> = old.split('.').map(String::from).collect();
+    if lookup(&table, &path).is_some() {
+      issues.push(ConfigIssue::warning(format!(
+        "'{}' is deprecated, use '{}'",
+        old, new
+      )));
+    }
+  }
+
+  match toml::Value::Table(table).try_into::<AppConfig>() {
+    Ok(config) => issues.extend(config.check()),
+    Err(e) => issues.push(ConfigIssue::error(e.to_string().trim().to_string())),
+  }
+
+  issues
+}
+
+/// 已知键的结构：默认配置加上默认值为空（不会被序列化）的可选项
+fn known_keys() -> toml::Table {
+  let mut config = AppConfig::default();
+  config.storage.data_dir = Some(PathBuf::new());
+  config.learn.llm.api_key = Some(String::new());
+  config.search.user_dict = Some(PathBuf::new());
+  config.server.api_key_file = Some(PathBuf::new());
+  config.sync.remote = Some(String::new());
+  config.plugins.dir = Some(PathBuf::new());
+  config.embeddings.api_key = Some(String::new());
+  // NAMED_TABLES 中每项的结构用 "*" 表示
+  config.commands.insert(
+    "*".to_string(),
+    CommandPrefs {
+      lang: Some(String::new()),
+      platform: Some(String::new()),
+      example: Some(1),
+    },
+  );
+  config.users.insert("*".to_string(), UserConfig::default());
+  toml::Table::try_from(config).unwrap_or_default()
+}
+
+/// 把 NAMED_TABLES 中的用户键名换成 known_keys 中的 "*"
+fn known_path(keys: &[String]) -> Vec<String> {
+  let mut keys = keys.to_vec();
+  if keys.len() >= 2 && NAMED_TABLES.contains(&keys[0].as_str()) {
+    keys[1] = "*".to_string();
+  }
+  keys
+}
+
+/// 列出配置表中不存在于 AppConfig 的键（点分路径）
+fn unknown_keys(table: &toml::Table) -> Vec<String> {
+  fn walk(table: &toml::Table, known: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in table {
+      let path = if prefix.is_empty() {
+        key.clone()
+      } else {
+        format!("{}.{}", prefix, key)
+      };
+      match known.get(key) {
+        None if RENAMED_KEYS.iter().any(|(old, _)| *old == path) => {}
+        None => out.push(path),
+        Some(toml::Value::Table(known_sub)) if NAMED_TABLES.contains(&path.as_str()) => {
+          let (Some(sub), Some(toml::Value::Table(entry))) = (value.as_table(), known_sub.get("*"))
+          else {
+            continue;
+          };
+          for (name, value) in sub {
+            if let Some(value) = value.as_table() {
+              walk(value, entry, &format!("{}.{}", path, name), out);
+            }
+          }
+        }
+        Some(toml::Value::Table(known_sub)) => {
+          if let (Some(sub), false) = (value.as_table(), FREE_FORM_TABLES.contains(&path.as_str()))
+          {
+            walk(sub, known_sub, &path, out);
+          }
+        }
+        Some(_) => {}
+      }
+    }
+  }
+
+  let mut out = Vec::new();
+  walk(table, &known_keys(), "", &mut out);
+  out
+}
+
+/// 为拼错的键找最接近的已知键（同一层级，编辑距离不超过 2）
+fn suggest_key(path: &str) -> Option<String> {
+  let known = known_keys();
+  let (parent, key) = path.rsplit_once('.').unwrap_or(("", path));
+  let siblings = if parent.is_empty() {
+    &known
+  } else {
+    let keys: Vec<String> = parent.split('.').map(str::to_string).collect();
+    let keys = known_path(&keys);
+    lookup(&known, &keys)?.as_table()?
+  };
+
+  siblings
+    .keys()
+    .map(|k| (edit_distance(k, key), k))
+    .filter(|(d, _)| *d <= 2)
+    .min()
+    .map(|(_, k)| k.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut prev: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut cur = vec![i + 1];
+    for (j, cb) in b.iter().enumerate() {
+      let cost = usize::from(ca != *cb);
+      cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+    }
+    prev = cur;
+  }
+  prev[b.len()]
+}
+
+/// 把 `RTFM_<SECTION>__<KEY>` 环境变量写入配置表，返回已应用的变量名
+/// 值按默认配置中对应项的类型解析：字符串项保持原样，其余按 TOML 值解析（数字、布尔、数组）
+fn apply_env_overrides(
+  table: &mut toml::Table,
+  vars: impl IntoIterator<Item = (String, String)>,
+) -> Vec<String> {
+  let defaults = known_keys();
+  let mut applied = Vec::new();
+
+  let mut vars: Vec<(String, String)> = vars.into_iter().collect();
+  vars.sort();
+  for (name, raw) in vars {
+    let Some(path) = name.strip_prefix(ENV_PREFIX) else {
+      continue;
+    };
+    // RTFM_DATA_DIR 等不含分隔符的变量不属于配置覆盖
+    if !path.contains(ENV_SEPARATOR) {
+      continue;
+    }
+    let keys: Vec<String> = path
+      .split(ENV_SEPARATOR)
+      .map(|k| k.to_lowercase())
+      .collect();
+    if keys.iter().any(String::is_empty) {
+      continue;
+    }
+
+    let value = match lookup(&defaults, &known_path(&keys)) {
+      Some(toml::Value::String(_)) | None => toml::Value::String(raw),
+      Some(_) => parse_env_value(&raw).unwrap_or(toml::Value::String(raw)),
+    };
+    if insert(table, &keys, value) {
+      applied.push(name);
+    } else {
+      eprintln!(
+        "Warning: Ignoring {}: conflicts with a non-table value",
+        name
+      );
+    }
+  }
+
+  applied
+}
+
+fn parse_env_value(raw: &str) -> Option<toml::Value> {
+  format!("v = {}", raw)
+    .parse::<toml::Table>()
+    .ok()?
+    .remove("v")
+}
+
+fn lookup<'a>(table: &'a toml::Table, keys: &[String]) -> Option<&'a toml::Value> {
+  let (last, parents) = keys.split_last()?;
+  let mut current = table;
+  for key in parents {
+    current = current.get(key)?.as_table()?;
+  }
+  current.get(last)
+}
+
+fn insert(table: &mut toml::Table, keys: &[String], value: toml::Value) -> bool {
+  let Some((last, parents)) = keys.split_last() else {
+    return false;
+  };
+  let mut current = table;
+  for key in parents {
+    let entry = current
+      .entry(key.clone())
+      .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let Some(next) = entry.as_table_mut() else {
+      return false;
+    };
+    current = next;
+  }
+  current.insert(last.clone(), value);
+  true
+}
+
 /// 获取默认数据目录
+/// 修改配置内容中的 tui.default_style，保留其余内容、注释与格式
+fn set_default_style(content: &str, style: &str) -> anyhow::Result<String> {
+  let mut doc = content.parse::<toml_edit::DocumentMut>()?;
+  let tui = doc
+    .entry("tui")
+    .or_insert(toml_edit::table())
+    .as_table_like_mut()
+    .ok_or_else(|| anyhow::anyhow!("'tui' is not a table"))?;
+  // 沿用旧键名，避免同时出现两个键
+  let key = if tui.contains_key("style") && !tui.contains_key("default_style") {
+    "style"
+  } else {
+    "default_style"
+  };
+  tui.insert(key, toml_edit::value(style));
+  Ok(doc.to_string())
+}
+
+/// 修改配置内容中的 [users.<name>]，保留其余内容、注释与格式
+fn set_user(content: &str, name: &str, user: Option<&UserConfig>) -> anyhow::Result<String> {
+  let mut doc = content.parse::<toml_edit::DocumentMut>()?;
+  let users = doc
+    .entry("users")
+    .or_insert(toml_edit::table())
+    .as_table_mut()
+    .ok_or_else(|| anyhow::anyhow!("'users' is not a table"))?;
+  users.set_implicit(true);
+  match user {
+    Some(user) => {
+      let mut table = toml_edit::Table::new();
+      table.insert("token_sha256", toml_edit::value(&user.token_sha256));
+      table.insert("admin", toml_edit::value(user.admin));
+      users.insert(name, toml_edit::Item::Table(table));
+    }
+    None => {
+      users.remove(name);
+    }
+  }
+  Ok(doc.to_string())
+}
+
+/// 配置目录：Linux 上为 $XDG_CONFIG_HOME/rtfm（默认 ~/.config/rtfm）
+fn get_config_dir() -> Option<PathBuf> {
+  dirs::config_dir().map(|dir| dir.join("rtfm"))
+}
+
 fn get_default_data_dir() -> PathBuf {
   dirs::data_local_dir()
     .unwrap_or_else(|| PathBuf::from("."))
@@ -276,4 +1615,237 @@ default_limit = 50
     // 未指定的字段使用默认值
     assert_eq!(config.search.max_limit, 100);
   }
+
+  #[test]
+  fn test_default_style() {
+    // 旧键名仍可读取，校验时提示改名
+    let config: AppConfig = toml::from_str("[tui]\nstyle = \"classic\"\n").unwrap();
+    assert_eq!(config.tui.default_style, "classic");
+    let issues = validate("[tui]\nstyle = \"classic\"\n", Vec::new());
+    assert_eq!(
+      issues,
+      vec![ConfigIssue::warning(
+        "'tui.style' is deprecated, use 'tui.default_style'".to_string()
+      )]
+    );
+
+    // 写回时保留注释，没有 [tui] 表时新建
+    let content = "# my config\n[server]\nport = 8080 # custom\n";
+    let updated = set_default_style(content, "classic").unwrap();
+    assert!(updated.starts_with("# my config\n[server]\nport = 8080 # custom\n"));
+    let config: AppConfig = toml::from_str(&updated).unwrap();
+    assert_eq!(config.tui.default_style, "classic");
+
+    let updated = set_default_style("[tui]\nstyle = \"modern\"\n", "classic").unwrap();
+    assert_eq!(updated, "[tui]\nstyle = \"classic\"\n");
+  }
+
+  #[test]
+  fn test_set_user() {
+    let user = UserConfig {
+      token_sha256: "a".repeat(64),
+      admin: true,
+    };
+    let content = "# team server\n[server]\nmulti_user = true\n";
+    let updated = set_user(content, "alice", Some(&user)).unwrap();
+    assert!(updated.starts_with(content));
+    assert!(updated.contains("[users.alice]\n"));
+    assert!(!updated.contains("[users]\n"));
+    let config: AppConfig = toml::from_str(&updated).unwrap();
+    assert_eq!(config.users["alice"], user);
+    assert!(validate(&updated, Vec::new()).is_empty());
+
+    let updated = set_user(&updated, "alice", None).unwrap();
+    let config: AppConfig = toml::from_str(&updated).unwrap();
+    assert!(config.users.is_empty());
+
+    let issues = validate("[users.\"bad name\"]\ntoken_sha256 = \"x\"\n", Vec::new());
+    assert_eq!(issues.len(), 2);
+  }
+
+  #[test]
+  fn test_validate() {
+    let issues = validate(
+      "[search]\ndefalt_limit = 5\ndefault_limit = 500\n[learn.overrides]\njava = \"java -help\"\n[nonsense]\n",
+      Vec::new(),
+    );
+    let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+    assert!(messages.contains(&"unknown key 'search.defalt_limit' (did you mean 'default_limit'?)"));
+    assert!(messages.contains(&"unknown key 'nonsense'"));
+    assert!(messages.contains(&"search.default_limit (500) is greater than search.max_limit (100)"));
+    // 自由键名的表和可选项不算未知键
+    assert!(!messages.iter().any(|m| m.contains("overrides")));
+    assert!(validate("[storage]\ndata_dir = \"/tmp\"\n", Vec::new()).is_empty());
+
+    let issues = validate("[server]\nport = \"http\"\n", Vec::new());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].level, IssueLevel::Error);
+
+    assert!(AppConfig::default().check().is_empty());
+
+    let issues = validate("[server]\ncors_origins = [\"localhost\"]\n", Vec::new());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].level, IssueLevel::Error);
+
+    let issues = validate(
+      "[scheduler]\nbackup = \"@daily\"\nupdate = \"0 25 * * *\"\n",
+      Vec::new(),
+    );
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0]
+      .message
+      .starts_with("scheduler.update '0 25 * * *'"));
+
+    let issues = validate("[ui]\nlocale = \"zh_CN\"\n", Vec::new());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].level, IssueLevel::Error);
+
+    // 本地模型必须是 fastembed 支持的模型，接口模型名不受限制
+    let issues = validate("[embeddings]\nmodel = \"nomic-embed-text\"\n", Vec::new());
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].message.starts_with("embeddings.model"));
+    assert!(validate(
+      "[embeddings]\nprovider = \"http\"\nmodel = \"nomic-embed-text\"\n",
+      Vec::new()
+    )
+    .is_empty());
+    let issues = validate("[embeddings]\nprovider = \"ollama\"\n", Vec::new());
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0]
+      .message
+      .starts_with("embeddings.provider 'ollama'"));
+  }
+
+  #[test]
+  fn test_command_prefs() {
+    let content =
+      "[commands.tar]\nlang = \"zh\"\nexmaple = 2\n[commands.git-commit]\nexample = 0\n";
+    let messages: Vec<String> = validate(content, Vec::new())
+      .into_iter()
+      .map(|i| i.message)
+      .collect();
+    assert_eq!(
+      messages,
+      [
+        "unknown key 'commands.tar.exmaple' (did you mean 'example'?)",
+        "commands.git-commit.example must be at least 1 (examples are numbered from 1)",
+      ]
+    );
+
+    let vars = [("RTFM_COMMANDS__TAR__EXAMPLE".to_string(), "3".to_string())];
+    let config = AppConfig::from_table(content.parse().unwrap(), vars);
+    let tar = config.command_prefs("tar").unwrap();
+    assert_eq!(tar.lang.as_deref(), Some("zh"));
+    assert_eq!(tar.example, Some(3));
+    assert!(config.command_prefs("git commit").is_some());
+    assert!(config.command_prefs("ls").is_none());
+  }
+
+  #[test]
+  fn test_json_schema() {
+    let schema = AppConfig::json_schema();
+    let definitions = &schema["definitions"];
+    assert_eq!(
+      definitions["ServerConfig"]["properties"]["port"]["default"],
+      3030
+    );
+    assert_eq!(
+      definitions["TuiConfig"]["properties"]["default_style"]["enum"],
+      serde_json::json!(["modern", "classic"])
+    );
+    // 每个配置项都出现在 schema 中
+    let defaults = serde_json::to_value(AppConfig::default()).unwrap();
+    for (section, values) in defaults.as_object().unwrap() {
+      // [commands] 等键名由用户决定的表没有固定的键
+      if values.as_object().unwrap().is_empty() {
+        continue;
+      }
+      let reference = schema["properties"][section]["allOf"][0]["$ref"]
+        .as_str()
+        .unwrap_or_else(|| panic!("missing section '{}'", section));
+      let name = reference.trim_start_matches("#/definitions/");
+      for key in values.as_object().unwrap().keys() {
+        assert!(
+          !definitions[name]["properties"][key].is_null(),
+          "missing key '{}.{}'",
+          section,
+          key
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_cors_origins() {
+    let mut server = ServerConfig::default();
+    assert!(server.allows_origin("https://example.com"));
+
+    server.cors_origins = vec!["http://localhost:8080/".to_string()];
+    assert!(server.allows_origin("http://localhost:8080"));
+    assert!(!server.allows_origin("https://example.com"));
+  }
+
+  #[test]
+  fn test_api_keys() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let key_file = temp_dir.path().join("keys");
+    std::fs::write(&key_file, "# deploy bot\n  k2  \n\nk3\n").unwrap();
+
+    let mut server = ServerConfig::default();
+    assert!(!server.requires_api_key());
+    server.api_keys = vec!["k1".to_string()];
+    server.api_key_file = Some(key_file.clone());
+    assert!(server.requires_api_key());
+    assert_eq!(server.load_api_keys().unwrap(), ["k1", "k2", "k3"]);
+    // 多用户模式使用用户令牌
+    server.multi_user = true;
+    assert!(!server.requires_api_key());
+
+    let issues = validate(
+      "[server]\napi_keys = [\"\"]\napi_key_file = \"/nonexistent/keys\"\n",
+      Vec::new(),
+    );
+    assert_eq!(issues.len(), 2);
+    assert!(issues.iter().all(|i| i.level == IssueLevel::Error));
+
+    std::fs::write(&key_file, "# no keys yet\n").unwrap();
+    let content = format!("[server]\napi_key_file = {:?}\n", key_file);
+    let issues = validate(&content, Vec::new());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].level, IssueLevel::Warning);
+  }
+
+  #[test]
+  fn test_env_overrides() {
+    let table: toml::Table = "[server]\nport = 8080\nbind = \"0.0.0.0\"\n"
+      .parse()
+      .unwrap();
+    let vars = [
+      ("RTFM_SERVER__PORT", "9090"),
+      ("RTFM_SEARCH__DEFAULT_LANG", "zh"),
+      ("RTFM_UPDATE__FALLBACK_VERSION", "2.3"),
+      ("RTFM_UPDATE__LANGUAGES", "[\"en\", \"ja\"]"),
+      ("RTFM_LEARN__LLM__ENABLED", "true"),
+      ("RTFM_LEARN__OVERRIDES__GO", "go help"),
+      ("RTFM_DATA_DIR", "/ignored"),
+      ("HOME", "/root"),
+    ]
+    .map(|(k, v)| (k.to_string(), v.to_string()));
+
+    let config = AppConfig::from_table(table.clone(), vars);
+    assert_eq!(config.server.port, 9090);
+    // 文件中的其他值保留
+    assert_eq!(config.server.bind, "0.0.0.0");
+    assert_eq!(config.search.default_lang, "zh");
+    // 字符串项不会被解析为数字
+    assert_eq!(config.update.fallback_version, "2.3");
+    assert_eq!(config.update.languages, ["en", "ja"]);
+    assert!(config.learn.llm.enabled);
+    assert_eq!(config.learn.overrides["go"], "go help");
+
+    // 无效的覆盖值被忽略，文件配置仍然生效
+    let bad = [("RTFM_SERVER__PORT".to_string(), "not-a-port".to_string())];
+    let config = AppConfig::from_table(table, bad);
+    assert_eq!(config.server.port, 8080);
+  }
 }
@@ -0,0 +1,179 @@
+//! 后台运行的服务（rtfm serve --detach、--status、--stop）
+//!
+//! --detach 启动的子进程的 PID 与监听地址写入数据目录中的 server.pid；--status 通过 /api/health
+//! 检查服务是否响应，--stop 结束该进程（Unix 上发送 SIGTERM，服务正常关闭；Windows 上使用 taskkill）。
+//! 操作前检查进程名，PID 被其他进程复用时不会误结束它。
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// 数据目录中的 PID 文件名
+pub const PID_FILENAME: &str = "server.pid";
+
+/// 健康检查的超时时间
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// --detach 启动的服务
+#[derive(Debug, Clone, PartialEq)]
+pub struct Server {
+  pub pid: u32,
+  pub bind: String,
+  pub port: u16,
+}
+
+pub fn pid_path(data_dir: &Path) -> PathBuf {
+  data_dir.join(PID_FILENAME)
+}
+
+impl Server {
+  /// PID 文件内容：每行依次为 PID、监听地址和端口
+  fn parse(content: &str) -> Option<Self> {
+    let mut lines = content.lines().map(str::trim);
+    Some(Self {
+      pid: lines.next()?.parse().ok()?,
+      bind: lines.next()?.to_string(),
+      port: lines.next()?.parse().ok()?,
+    })
+  }
+
+  fn format(&self) -> String {
+    format!("{}\n{}\n{}\n", self.pid, self.bind, self.port)
+  }
+
+  /// 读取 PID 文件；不存在或内容无效时返回 None
+  pub fn read(data_dir: &Path) -> Option<Self> {
+    Self::parse(&std::fs::read_to_string(pid_path(data_dir)).ok()?)
+  }
+
+  pub fn write(&self, data_dir: &Path) -> std::io::Result<()> {
+    std::fs::write(pid_path(data_dir), self.format())
+  }
+
+  pub fn remove(data_dir: &Path) {
+    let _ = std::fs::remove_file(pid_path(data_dir));
+  }
+
+  /// 显示用的地址，如 127.0.0.1:3030
+  pub fn addr(&self) -> String {
+    format!("{}:{}", self.bind, self.port)
+  }
+
+  /// 健康检查地址；监听所有地址时通过本机回环地址访问
+  pub fn health_url(&self) -> String {
+    let host = match self.bind.as_str() {
+      "0.0.0.0" => "127.0.0.1".to_string(),
+      "::" | "[::]" => "[::1]".to_string(),
+      bind if bind.contains(':') && !bind.starts_with('[') => format!("[{}]", bind),
+      bind => bind.to_string(),
+    };
+    format!("http://{}:{}/api/health", host, self.port)
+  }
+
+  /// 进程是否仍在运行（且仍是 rtfm）
+  pub fn is_running(&self) -> bool {
+    process_name(self.pid).is_some_and(|name| name.to_lowercase().contains("rtfm"))
+  }
+
+  /// 服务是否响应 /api/health
+  pub async fn is_healthy(&self) -> bool {
+    let Ok(client) = reqwest::Client::builder().timeout(HEALTH_TIMEOUT).build() else {
+      return false;
+    };
+    match client.get(self.health_url()).send().await {
+      Ok(response) => response.status().is_success(),
+      Err(_) => false,
+    }
+  }
+
+  /// 请求进程退出
+  pub fn terminate(&self) -> anyhow::Result<()> {
+    let pid = self.pid.to_string();
+    #[cfg(unix)]
+    let status = Command::new("kill")
+      .args(["-TERM", &pid])
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status()?;
+    // 后台进程没有窗口，不能响应 WM_CLOSE
+    #[cfg(windows)]
+    let status = Command::new("taskkill")
+      .args(["/PID", &pid, "/F"])
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status()?;
+    if !status.success() {
+      anyhow::bail!("Failed to terminate process {}", self.pid);
+    }
+    Ok(())
+  }
+}
+
+/// 进程的可执行文件名；进程不存在时返回 None
+fn process_name(pid: u32) -> Option<String> {
+  // Linux 上直接读取 /proc，精简的容器中可能没有 ps
+  #[cfg(unix)]
+  if Path::new("/proc/self/comm").exists() {
+    let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    return Some(name.trim().to_string());
+  }
+  #[cfg(unix)]
+  let output = Command::new("ps")
+    .args(["-p", &pid.to_string(), "-o", "comm="])
+    .stderr(Stdio::null())
+    .output()
+    .ok()?;
+  #[cfg(windows)]
+  let output = Command::new("tasklist")
+    .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+    .stderr(Stdio::null())
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  // Windows 上为 "rtfm.exe","1234",...；找不到进程时输出一行提示，不含引号
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let name = stdout.lines().next()?.split(',').next()?.trim();
+  let name = name.trim_matches('"');
+  (!name.is_empty() && (cfg!(unix) || stdout.starts_with('"'))).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_pid_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    assert_eq!(Server::read(temp_dir.path()), None);
+
+    let server = Server {
+      pid: std::process::id(),
+      bind: "0.0.0.0".to_string(),
+      port: 3030,
+    };
+    server.write(temp_dir.path()).unwrap();
+    assert_eq!(Server::read(temp_dir.path()), Some(server.clone()));
+    assert_eq!(server.health_url(), "http://127.0.0.1:3030/api/health");
+    assert_eq!(
+      Server {
+        bind: "::1".to_string(),
+        ..server.clone()
+      }
+      .health_url(),
+      "http://[::1]:3030/api/health"
+    );
+
+    Server::remove(temp_dir.path());
+    assert_eq!(Server::read(temp_dir.path()), None);
+    std::fs::write(pid_path(temp_dir.path()), "not a pid\n").unwrap();
+    assert_eq!(Server::read(temp_dir.path()), None);
+  }
+
+  #[test]
+  fn test_process_name() {
+    assert!(process_name(std::process::id()).is_some());
+    assert!(process_name(u32::MAX - 1).is_none());
+  }
+}
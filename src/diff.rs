@@ -0,0 +1,337 @@
+//! 比较学习的命令与 tldr 页面（rtfm diff）
+//!
+//! 两者格式不同（--help 输出与 tldr Markdown），因此先把两边整理成同样的提纲：描述、示例的代码、
+//! 按名称排序的选项，再逐行比较。tldr 页面为旧版（-），学习的命令为新版（+）。
+//! 另外列出本地帮助中有、tldr 页面中完全没有提到的选项，以及反过来的情况，便于发现版本差异。
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::i18n::t;
+use crate::storage::{Command, OptionEntry};
+
+/// unified 格式每处差异前后保留的相同行数
+const CONTEXT: usize = 3;
+
+/// 比较结果中的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+  Same(&'a str),
+  /// 只在 tldr 页面中
+  Removed(&'a str),
+  /// 只在学习的命令中
+  Added(&'a str),
+}
+
+/// 命令的提纲，两种来源用同样的格式
+pub fn outline(cmd: &Command) -> Vec<String> {
+  let mut lines: Vec<String> = cmd
+    .description
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .map(str::to_string)
+    .collect();
+  if !cmd.examples.is_empty() {
+    lines.push(String::new());
+    lines.push(format!("{}:", t!("diff.examples")));
+    for example in &cmd.examples {
+      lines.extend(
+        example
+          .code
+          .lines()
+          .map(|line| format!("  {}", line.trim_end())),
+      );
+    }
+  }
+  if !cmd.options.is_empty() {
+    lines.push(String::new());
+    lines.push(format!("{}:", t!("diff.options")));
+    let mut labels: Vec<String> = cmd.options.iter().map(OptionEntry::label).collect();
+    labels.sort_by_key(|label| label.trim_start_matches('-').to_lowercase());
+    lines.extend(labels.into_iter().map(|label| format!("  {}", label)));
+  }
+  lines
+}
+
+/// 按最长公共子序列逐行比较
+pub fn diff_lines<'a>(old: &'a [String], new: &'a [String]) -> Vec<DiffLine<'a>> {
+  let (n, m) = (old.len(), new.len());
+  // lcs[i][j]: old[i..] 与 new[j..] 的最长公共子序列长度
+  let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+  for i in (0..n).rev() {
+    for j in (0..m).rev() {
+      lcs[i][j] = if old[i] == new[j] {
+        lcs[i + 1][j + 1] + 1
+      } else {
+        lcs[i + 1][j].max(lcs[i][j + 1])
+      };
+    }
+  }
+
+  let (mut i, mut j) = (0, 0);
+  let mut lines = Vec::with_capacity(n.max(m));
+  while i < n || j < m {
+    if i < n && j < m && old[i] == new[j] {
+      lines.push(DiffLine::Same(&old[i]));
+      i += 1;
+      j += 1;
+    } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+      lines.push(DiffLine::Removed(&old[i]));
+      i += 1;
+    } else {
+      lines.push(DiffLine::Added(&new[j]));
+      j += 1;
+    }
+  }
+  lines
+}
+
+fn paint(color: bool, code: &str, text: &str) -> String {
+  if color {
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+  } else {
+    text.to_string()
+  }
+}
+
+/// unified 格式，只显示差异及其前后 CONTEXT 行
+pub fn unified(lines: &[DiffLine], old_name: &str, new_name: &str, color: bool) -> String {
+  let mut out = format!(
+    "{}\n{}\n",
+    paint(color, "1", &format!("--- {}", old_name)),
+    paint(color, "1", &format!("+++ {}", new_name))
+  );
+  let changed: Vec<usize> = (0..lines.len())
+    .filter(|&i| !matches!(lines[i], DiffLine::Same(_)))
+    .collect();
+  let Some(&first) = changed.first() else {
+    return out;
+  };
+
+  // 相邻差异的上下文重叠时合并为一段
+  let mut hunks = vec![(first.saturating_sub(CONTEXT), first)];
+  for &i in &changed[1..] {
+    let last = hunks.last_mut().expect("hunks is not empty");
+    if i.saturating_sub(CONTEXT) <= last.1 + CONTEXT + 1 {
+      last.1 = i;
+    } else {
+      hunks.push((i.saturating_sub(CONTEXT), i));
+    }
+  }
+
+  for (start, end) in hunks {
+    let end = (end + CONTEXT + 1).min(lines.len());
+    // 段首在两边的行号
+    let count = |f: fn(&DiffLine) -> bool, range: std::ops::Range<usize>| {
+      lines[range].iter().filter(|l| f(l)).count()
+    };
+    let in_old = |l: &DiffLine| !matches!(l, DiffLine::Added(_));
+    let in_new = |l: &DiffLine| !matches!(l, DiffLine::Removed(_));
+    let (old_start, new_start) = (count(in_old, 0..start), count(in_new, 0..start));
+    let (old_len, new_len) = (count(in_old, start..end), count(in_new, start..end));
+    out.push_str(&paint(
+      color,
+      "36",
+      &format!(
+        "@@ -{},{} +{},{} @@",
+        old_start + 1,
+        old_len,
+        new_start + 1,
+        new_len
+      ),
+    ));
+    out.push('\n');
+    for line in &lines[start..end] {
+      let text = match line {
+        DiffLine::Same(text) => format!(" {}", text),
+        DiffLine::Removed(text) => paint(color, "31", &format!("-{}", text)),
+        DiffLine::Added(text) => paint(color, "32", &format!("+{}", text)),
+      };
+      out.push_str(&text);
+      out.push('\n');
+    }
+  }
+  out
+}
+
+/// 左右对照格式：左为 tldr 页面，右为学习的命令；中间的 | < > 同 sdiff
+pub fn side_by_side(
+  lines: &[DiffLine],
+  old_name: &str,
+  new_name: &str,
+  width: usize,
+  color: bool,
+) -> String {
+  let column = width.saturating_sub(3) / 2;
+  let row = |left: &str, mark: char, right: &str| {
+    let left = fit(left, column);
+    let right = truncate(right, column);
+    let (left, right) = match mark {
+      '<' => (paint(color, "31", &left), right),
+      '>' => (left, paint(color, "32", &right)),
+      '|' => (paint(color, "31", &left), paint(color, "32", &right)),
+      _ => (left, right),
+    };
+    format!("{} {} {}", left, mark, right)
+      .trim_end()
+      .to_string()
+      + "\n"
+  };
+
+  let mut out = paint(color, "1", &row(old_name, ' ', new_name));
+  let mut removed: Vec<&str> = Vec::new();
+  let mut added: Vec<&str> = Vec::new();
+  // 连续的删除与新增逐行配对
+  let flush = |removed: &mut Vec<&str>, added: &mut Vec<&str>, out: &mut String| {
+    for k in 0..removed.len().max(added.len()) {
+      out.push_str(&match (removed.get(k), added.get(k)) {
+        (Some(left), Some(right)) => row(left, '|', right),
+        (Some(left), None) => row(left, '<', ""),
+        (None, Some(right)) => row("", '>', right),
+        (None, None) => unreachable!(),
+      });
+    }
+    removed.clear();
+    added.clear();
+  };
+  for line in lines {
+    match line {
+      DiffLine::Same(text) => {
+        flush(&mut removed, &mut added, &mut out);
+        out.push_str(&row(text, ' ', text));
+      }
+      DiffLine::Removed(text) => removed.push(text),
+      DiffLine::Added(text) => added.push(text),
+    }
+  }
+  flush(&mut removed, &mut added, &mut out);
+  out
+}
+
+/// 截断到指定的显示宽度
+fn truncate(text: &str, width: usize) -> String {
+  if text.width() <= width {
+    return text.to_string();
+  }
+  let mut out = String::new();
+  let mut used = 0;
+  for c in text.chars() {
+    let w = c.width().unwrap_or(0);
+    if used + w + 1 > width {
+      break;
+    }
+    out.push(c);
+    used += w;
+  }
+  out.push('…');
+  out
+}
+
+/// 截断并补齐到指定的显示宽度
+fn fit(text: &str, width: usize) -> String {
+  let text = truncate(text, width);
+  let pad = width.saturating_sub(text.width());
+  format!("{}{}", text, " ".repeat(pad))
+}
+
+/// 选项的任一写法是否作为独立的词出现在文本中；短选项也可以与其他短选项连写（如 -lh 中的 -l）
+fn mentions(text: &str, option: &OptionEntry) -> bool {
+  let is_word = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+  let bundled = |short: char| {
+    text
+      .split(|c: char| !is_word(c))
+      .filter_map(|word| word.strip_prefix('-'))
+      .any(|letters| {
+        !letters.starts_with('-')
+          && letters.chars().all(|c| c.is_ascii_alphanumeric())
+          && letters.contains(short)
+      })
+  };
+  option.flags.iter().any(|flag| {
+    let mut chars = flag.chars();
+    if let (Some('-'), Some(short), None) = (chars.next(), chars.next(), chars.next()) {
+      if short != '-' && bundled(short) {
+        return true;
+      }
+    }
+    text.match_indices(flag.as_str()).any(|(i, _)| {
+      let before = text[..i].chars().next_back();
+      let after = text[i + flag.len()..].chars().next();
+      !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+  })
+}
+
+/// other 中既没有同名选项、原文中也没有提到的选项
+pub fn unmentioned<'a>(options: &'a [OptionEntry], other: &Command) -> Vec<&'a OptionEntry> {
+  options
+    .iter()
+    .filter(|option| {
+      !other.options.iter().any(|o| o.overlaps(option)) && !mentions(&other.content, option)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lines(text: &str) -> Vec<String> {
+    text.split(' ').map(str::to_string).collect()
+  }
+
+  #[test]
+  fn test_diff() {
+    let old = lines("a b c d e f g h i j k l");
+    let new = lines("a b x d e f g h i j k l m");
+    let diff = diff_lines(&old, &new);
+    assert_eq!(diff[2], DiffLine::Removed("c"));
+    assert_eq!(diff[3], DiffLine::Added("x"));
+    assert_eq!(diff.last(), Some(&DiffLine::Added("m")));
+
+    // 两处差异相距较远，分为两段
+    assert_eq!(
+      unified(&diff, "tldr", "local", false),
+      "--- tldr\n+++ local\n@@ -1,6 +1,6 @@\n a\n b\n-c\n+x\n d\n e\n f\n\
+       @@ -10,3 +10,4 @@\n j\n k\n l\n+m\n"
+    );
+    let side = side_by_side(&diff, "tldr", "local", 13, false);
+    assert_eq!(side.lines().nth(3), Some("c     | x"));
+    assert_eq!(side.lines().last(), Some("      > m"));
+  }
+
+  #[test]
+  fn test_unmentioned() {
+    let option = |flags: &[&str]| OptionEntry {
+      flags: flags.iter().map(|f| f.to_string()).collect(),
+      arg: None,
+      description: String::new(),
+    };
+    let page = Command {
+      name: "tar".to_string(),
+      description: "Archiving utility".to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: vec![],
+      content: "- Extract:\n\n`tar -xv -f {{file}} --zstd-level=3`".to_string(),
+      sections: vec![],
+      options: vec![],
+      source_info: None,
+      notes: None,
+      schema: crate::storage::COMMAND_SCHEMA,
+    };
+    let local = [
+      option(&["-x", "--extract"]),
+      option(&["-f", "--file"]),
+      option(&["-v", "--verbose"]),
+      option(&["-z", "--gzip"]),
+      option(&["--zstd"]),
+    ];
+    let names: Vec<_> = unmentioned(&local, &page)
+      .iter()
+      .map(|o| o.flags[0].as_str())
+      .collect();
+    assert_eq!(names, ["-z", "--zstd"]);
+  }
+}
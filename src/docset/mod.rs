@@ -0,0 +1,475 @@
+//! Dash / Zeal 文档集（rtfm import --format docset）
+//!
+//! 文档集是 `名称.docset` 目录：`Contents/Resources/docSet.dsidx` 是 SQLite 索引，HTML 页面在
+//! `Contents/Resources/Documents/` 下，`Contents/Info.plist` 中的 DocSetPlatformFamily 是 Dash 的搜索关键字。
+//! 索引有两种格式：Dash 的 searchIndex 表（name、type、path），以及 Xcode 格式的 ZTOKEN 等 Core Data 表。
+//!
+//! 每个条目导入为一条本机命令（lang 为 local，分类为 docset），同名条目合并。内容为页面中从条目的锚点
+//! 到下一个条目锚点之间的 HTML 转换成的纯文本，其中的 `<pre>` 代码块作为示例；关键字和条目类型写入描述的标签，
+//! 可以用 `tag:python`、`tag:function` 筛选。
+
+mod sqlite;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path};
+
+use crate::learn::html_to_text;
+use crate::storage::{Command, Example, SourceInfo, COMMAND_SCHEMA};
+
+/// 每个条目最多保留的内容长度（字符）
+const MAX_CONTENT_CHARS: usize = 16 * 1024;
+
+/// 每个条目最多的示例数
+const MAX_EXAMPLES: usize = 8;
+
+/// 描述的最大长度（字符）
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// 索引中的一个条目
+#[derive(Debug)]
+struct Entry {
+  name: String,
+  kind: String,
+  /// 相对 Documents 的页面路径
+  page: String,
+  anchor: Option<String>,
+}
+
+/// 是否为 .docset 目录
+pub fn is_docset(path: &Path) -> bool {
+  path.is_dir()
+    && (path
+      .extension()
+      .is_some_and(|ext| ext.eq_ignore_ascii_case("docset"))
+      || path.join("Contents/Resources/docSet.dsidx").is_file())
+}
+
+/// 导入文档集；path 为 .docset 目录，或包含多个文档集的目录（如 Zeal 的 docsets 目录）
+/// 返回 (命令, 文档集数)
+pub fn parse_docsets(path: &Path) -> anyhow::Result<(Vec<Command>, usize)> {
+  if is_docset(path) {
+    return Ok((parse_docset(path)?, 1));
+  }
+  let mut docsets: Vec<_> = std::fs::read_dir(path)?
+    .filter_map(|entry| entry.ok().map(|e| e.path()))
+    .filter(|p| is_docset(p))
+    .collect();
+  if docsets.is_empty() {
+    anyhow::bail!("No .docset found in {}", path.display());
+  }
+  docsets.sort();
+  let mut commands = Vec::new();
+  for docset in &docsets {
+    commands.extend(parse_docset(docset)?);
+  }
+  Ok((commands, docsets.len()))
+}
+
+/// 导入一个文档集的所有条目
+pub fn parse_docset(path: &Path) -> anyhow::Result<Vec<Command>> {
+  let resources = path.join("Contents/Resources");
+  let db = sqlite::Database::open(&resources.join("docSet.dsidx"))?;
+  let entries = read_entries(&db)?;
+  let keyword = keyword(path);
+  let documents = resources.join("Documents");
+
+  // 同一页面的条目一起处理，每个页面只读一次
+  let mut pages: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+  for entry in &entries {
+    pages.entry(&entry.page).or_default().push(entry);
+  }
+
+  let mut commands = Vec::new();
+  for (page, entries) in pages {
+    let file = documents.join(page);
+    let Ok(html) = std::fs::read(&file) else {
+      tracing::debug!("Docset page not found: {}", file.display());
+      continue;
+    };
+    let html = String::from_utf8_lossy(&html);
+
+    // 各条目在页面中的起点，条目的内容到下一个起点为止
+    let starts: Vec<Option<usize>> = entries
+      .iter()
+      .map(|e| e.anchor.as_deref().and_then(|a| find_anchor(&html, a)))
+      .collect();
+    let mut sorted: Vec<usize> = starts.iter().flatten().copied().collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    for (entry, start) in entries.iter().zip(starts) {
+      let fragment = match start {
+        Some(start) => {
+          let end = sorted
+            .iter()
+            .find(|&&s| s > start)
+            .copied()
+            .unwrap_or(html.len());
+          &html[start..end]
+        }
+        None => &html[..],
+      };
+      let source = format!("file: {}", file.display());
+      commands.push(command(entry, fragment, &keyword, source));
+    }
+  }
+  Ok(commands)
+}
+
+/// 读取索引中的条目（Dash 格式或 Xcode 格式）
+fn read_entries(db: &sqlite::Database) -> anyhow::Result<Vec<Entry>> {
+  if let Some(table) = db.table("searchIndex")? {
+    let (Some(name), Some(kind), Some(path)) = (
+      table.column("name"),
+      table.column("type"),
+      table.column("path"),
+    ) else {
+      anyhow::bail!("searchIndex table without name, type and path columns");
+    };
+    return Ok(
+      db.rows(&table)?
+        .iter()
+        .filter_map(|row| {
+          entry(
+            row[name].as_str()?,
+            row[kind].as_str().unwrap_or_default(),
+            row[path].as_str()?,
+            None,
+          )
+        })
+        .collect(),
+    );
+  }
+  if let Some(table) = db.table("ZTOKEN")? {
+    return read_core_data(db, &table);
+  }
+  anyhow::bail!("Unknown docset index format (no searchIndex or ZTOKEN table)")
+}
+
+/// Xcode 格式：ZTOKEN（名称、类型、元信息）-> ZTOKENMETAINFORMATION（文件、锚点）-> ZFILEPATH（路径）
+fn read_core_data(db: &sqlite::Database, table: &sqlite::Table) -> anyhow::Result<Vec<Entry>> {
+  // 表的一列，按主键 Z_PK 建立映射
+  let by_pk = |table: &str, column: &str| -> anyhow::Result<HashMap<i64, sqlite::Value>> {
+    let Some(table) = db.table(table)? else {
+      return Ok(HashMap::new());
+    };
+    let (Some(pk), Some(column)) = (table.column("Z_PK"), table.column(column)) else {
+      return Ok(HashMap::new());
+    };
+    Ok(
+      db.rows(&table)?
+        .into_iter()
+        .filter_map(|mut row| {
+          Some((
+            row[pk].as_i64()?,
+            std::mem::replace(&mut row[column], sqlite::Value::Null),
+          ))
+        })
+        .collect(),
+    )
+  };
+  let types = by_pk("ZTOKENTYPE", "ZTYPENAME")?;
+  let files = by_pk("ZTOKENMETAINFORMATION", "ZFILE")?;
+  let anchors = by_pk("ZTOKENMETAINFORMATION", "ZANCHOR")?;
+  let paths = by_pk("ZFILEPATH", "ZPATH")?;
+
+  let (Some(name), Some(kind), Some(meta)) = (
+    table.column("ZTOKENNAME"),
+    table.column("ZTOKENTYPE"),
+    table.column("ZMETAINFORMATION"),
+  ) else {
+    anyhow::bail!("ZTOKEN table without ZTOKENNAME, ZTOKENTYPE and ZMETAINFORMATION columns");
+  };
+  Ok(
+    db.rows(table)?
+      .iter()
+      .filter_map(|row| {
+        let meta = row[meta].as_i64()?;
+        let file = files.get(&meta)?.as_i64()?;
+        let kind = row[kind]
+          .as_i64()
+          .and_then(|k| types.get(&k))
+          .and_then(sqlite::Value::as_str)
+          .unwrap_or_default();
+        let anchor = anchors.get(&meta).and_then(sqlite::Value::as_str);
+        entry(
+          row[name].as_str()?,
+          kind,
+          paths.get(&file)?.as_str()?,
+          anchor,
+        )
+      })
+      .collect(),
+  )
+}
+
+/// 整理索引中的路径：去掉 Dash 的 `<dash_entry_...>` 标记和查询参数，分出锚点；
+/// 在线页面和 Documents 之外的路径返回 None
+fn entry(name: &str, kind: &str, path: &str, anchor: Option<&str>) -> Option<Entry> {
+  let mut path = path.trim();
+  while path.starts_with("<dash_") {
+    path = &path[path.find('>')? + 1..];
+  }
+  if path.contains("://") || name.trim().is_empty() {
+    return None;
+  }
+  let (page, fragment) = match path.split_once('#') {
+    Some((page, fragment)) => (page, Some(fragment)),
+    None => (path, None),
+  };
+  let page = percent_decode(page.split('?').next().unwrap_or(page));
+  let safe = Path::new(&page)
+    .components()
+    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+  if page.is_empty() || !safe {
+    return None;
+  }
+  Some(Entry {
+    name: name.trim().to_string(),
+    kind: kind.to_string(),
+    page,
+    anchor: anchor
+      .or(fragment)
+      .filter(|a| !a.is_empty())
+      .map(percent_decode),
+  })
+}
+
+fn percent_decode(s: &str) -> String {
+  let bytes = s.as_bytes();
+  let mut out = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let hex = bytes
+      .get(i + 1..i + 3)
+      .and_then(|h| std::str::from_utf8(h).ok())
+      .and_then(|h| u8::from_str_radix(h, 16).ok());
+    match (bytes[i], hex) {
+      (b'%', Some(byte)) => {
+        out.push(byte);
+        i += 3;
+      }
+      (byte, _) => {
+        out.push(byte);
+        i += 1;
+      }
+    }
+  }
+  String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 锚点（id 或 name 属性）所在标签的起点
+fn find_anchor(html: &str, anchor: &str) -> Option<usize> {
+  ["id", "name"]
+    .iter()
+    .flat_map(|attr| {
+      [
+        format!("{}=\"{}\"", attr, anchor),
+        format!("{}='{}'", attr, anchor),
+      ]
+    })
+    .filter_map(|pattern| {
+      html
+        .match_indices(&pattern)
+        // 属性名前须为空白，避免 data-id="..." 之类
+        .find(|(i, _)| html[..*i].ends_with(char::is_whitespace))
+        .map(|(i, _)| i)
+    })
+    .min()
+    .map(|i| html[..i].rfind('<').unwrap_or(i))
+}
+
+/// Dash 的搜索关键字（Info.plist 中的 DocSetPlatformFamily），没有时用目录名
+fn keyword(path: &Path) -> String {
+  let plist = std::fs::read_to_string(path.join("Contents/Info.plist")).unwrap_or_default();
+  let value = plist
+    .split_once("<key>DocSetPlatformFamily</key>")
+    .and_then(|(_, rest)| {
+      let rest = rest.trim_start().strip_prefix("<string>")?;
+      Some(rest[..rest.find("</string>")?].trim().to_string())
+    });
+  value
+    .filter(|v| !v.is_empty())
+    .unwrap_or_else(|| {
+      path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+    })
+    .to_lowercase()
+    .replace([' ', ','], "_")
+}
+
+/// 条目转换为命令
+fn command(entry: &Entry, fragment: &str, keyword: &str, source: String) -> Command {
+  let text = html_to_text(fragment);
+  let mut content: String = text.chars().take(MAX_CONTENT_CHARS).collect();
+  if content.len() < text.len() {
+    content.push_str("\n...");
+  }
+
+  let mut tags = vec![keyword.to_string()];
+  if !entry.kind.is_empty() {
+    tags.push(entry.kind.to_lowercase());
+  }
+  let summary = summary(&text, &entry.name);
+
+  Command {
+    name: entry.name.clone(),
+    description: format!("{} (tags: {})", summary, tags.join(", ")),
+    category: "docset".to_string(),
+    platform: "common".to_string(),
+    lang: "local".to_string(),
+    examples: examples(fragment),
+    content: format!("Source: {}\n\n{}", source, content),
+    sections: vec![],
+    options: vec![],
+    source_info: Some(SourceInfo {
+      source,
+      learned_at: chrono::Utc::now().to_rfc3339(),
+      ..Default::default()
+    }),
+    notes: None,
+    schema: COMMAND_SCHEMA,
+  }
+}
+
+/// 描述：第一个像句子的行（至少 4 个词，跳过标题），只取第一句
+fn summary(text: &str, name: &str) -> String {
+  let lines: Vec<&str> = text
+    .lines()
+    .map(|l| {
+      l.trim()
+        .trim_start_matches("- ")
+        .trim_end_matches('¶')
+        .trim()
+    })
+    .filter(|l| !l.is_empty() && *l != name)
+    .collect();
+  let line = lines
+    .iter()
+    .find(|l| l.split_whitespace().count() >= 4)
+    .or(lines.first())
+    .copied()
+    .unwrap_or(name);
+  let sentence = match line.find(". ") {
+    Some(end) => &line[..=end],
+    None => line,
+  };
+  let mut summary: String = sentence.chars().take(MAX_DESCRIPTION_CHARS).collect();
+  if summary.len() < sentence.len() {
+    summary.push_str("...");
+  }
+  summary
+}
+
+/// `<pre>` 代码块作为示例，描述为代码块之前的最后一行文字
+fn examples(fragment: &str) -> Vec<Example> {
+  let lower = fragment.to_ascii_lowercase();
+  let mut examples = Vec::new();
+  let mut pos = 0;
+  while examples.len() < MAX_EXAMPLES {
+    let Some(open) = lower[pos..].find("<pre").map(|i| pos + i) else {
+      break;
+    };
+    let Some(body) = lower[open..].find('>').map(|i| open + i + 1) else {
+      break;
+    };
+    let close = lower[body..]
+      .find("</pre")
+      .map_or(fragment.len(), |i| body + i);
+
+    let code = html_to_text(&fragment[body..close]);
+    let code = code.trim_matches('\n');
+    if !code.trim().is_empty() {
+      let before = html_to_text(&fragment[pos..open]);
+      let description = before
+        .lines()
+        .rev()
+        .map(|l| {
+          l.trim()
+            .trim_start_matches("- ")
+            .trim_end_matches(':')
+            .trim()
+        })
+        .find(|l| !l.is_empty())
+        .unwrap_or("Example");
+      examples.push(Example {
+        description: description.to_string(),
+        code: code.to_string(),
+        source: Some("docset".to_string()),
+      });
+    }
+    pos = close;
+  }
+  examples
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_entry() {
+    let entry = entry(
+      "os.path.join",
+      "Function",
+      "<dash_entry_name=os.path.join>library/os%20path.html?v=1#os.path.join",
+      None,
+    )
+    .unwrap();
+    assert_eq!(entry.page, "library/os path.html");
+    assert_eq!(entry.anchor.as_deref(), Some("os.path.join"));
+    assert!(super::entry("x", "Guide", "https://example.com/x.html", None).is_none());
+    assert!(super::entry("x", "Guide", "../../etc/passwd", None).is_none());
+  }
+
+  #[test]
+  fn test_parse_docset() {
+    let dir = tempfile::tempdir().unwrap();
+    let docset = dir.path().join("Python_3.docset");
+    let resources = docset.join("Contents/Resources");
+    std::fs::create_dir_all(resources.join("Documents/library")).unwrap();
+    std::fs::write(
+      docset.join("Contents/Info.plist"),
+      "<plist><dict><key>DocSetPlatformFamily</key>\n<string>Python</string></dict></plist>",
+    )
+    .unwrap();
+    // 用 SQLite 生成的索引：1 到 40 号条目（页大小 512，含内部页和溢出页）
+    std::fs::write(
+      resources.join("docSet.dsidx"),
+      include_bytes!("testdata/docSet.dsidx"),
+    )
+    .unwrap();
+    std::fs::write(
+      resources.join("Documents/library/os.path.html"),
+      "<html><body><h1>os.path</h1><p>Common pathname manipulations.</p>\
+       <dl><dt id=\"os.path.join\">os.path.join(path, *paths)</dt>\
+       <dd><p>Join one or more path segments intelligently. The return value is a string.</p>\
+       <p>For example:</p><pre><span>os.path.join(</span>&quot;a&quot;, &quot;b&quot;)</pre></dd>\
+       <dt id=\"os.path.split\">os.path.split(path)</dt><dd><p>Split the pathname path into a pair.</p></dd>\
+       </dl></body></html>",
+    )
+    .unwrap();
+
+    let (commands, count) = parse_docsets(dir.path()).unwrap();
+    assert_eq!(count, 1);
+    // 没有页面的条目跳过
+    let names: Vec<&str> = commands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["os.path", "os.path.join", "os.path.split"]);
+
+    let join = &commands[1];
+    assert_eq!(
+      join.description,
+      "Join one or more path segments intelligently. (tags: python, function)"
+    );
+    assert_eq!(join.lang, "local");
+    assert_eq!(join.category, "docset");
+    assert_eq!(join.examples.len(), 1);
+    assert_eq!(join.examples[0].description, "For example");
+    assert_eq!(join.examples[0].code, "os.path.join(\"a\", \"b\")");
+    assert!(!join.content.contains("Split the pathname"));
+    // 没有锚点的条目为整个页面
+    assert!(commands[0].content.contains("Split the pathname"));
+  }
+}
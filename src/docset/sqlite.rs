@@ -0,0 +1,424 @@
+//! 只读的 SQLite 表读取（文档集的 docSet.dsidx）
+//!
+//! 只实现逐行读取整张表需要的部分：文件头、表 B 树（内部页与叶子页）、溢出页和记录格式。
+//! 不支持 WITHOUT ROWID 表和 UTF-16 编码的数据库，也不读取 WAL 文件中尚未写回的内容。
+//! 格式说明见 https://www.sqlite.org/fileformat2.html
+
+use std::path::Path;
+
+use anyhow::{bail, Context};
+
+const HEADER: &[u8] = b"SQLite format 3\0";
+
+/// 读取的页数上限相对文件总页数的倍数，防止损坏的文件中 B 树成环
+const MAX_VISITS_FACTOR: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+  Null,
+  Integer(i64),
+  Real(f64),
+  Text(String),
+  Blob(Vec<u8>),
+}
+
+impl Value {
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Value::Text(s) => Some(s),
+      _ => None,
+    }
+  }
+
+  pub fn as_i64(&self) -> Option<i64> {
+    match self {
+      Value::Integer(i) => Some(*i),
+      _ => None,
+    }
+  }
+}
+
+/// 表的根页和列名（来自 CREATE TABLE 语句）
+#[derive(Debug, Clone)]
+pub struct Table {
+  pub name: String,
+  root: u32,
+  pub columns: Vec<String>,
+  /// INTEGER PRIMARY KEY 列：记录中存为 NULL，值为 rowid
+  rowid_column: Option<usize>,
+}
+
+impl Table {
+  /// 列的位置（不区分大小写）
+  pub fn column(&self, name: &str) -> Option<usize> {
+    self
+      .columns
+      .iter()
+      .position(|c| c.eq_ignore_ascii_case(name))
+  }
+}
+
+pub struct Database {
+  data: Vec<u8>,
+  page_size: usize,
+  /// 每页可用的字节数（页大小减去保留区）
+  usable: usize,
+}
+
+impl Database {
+  pub fn open(path: &Path) -> anyhow::Result<Self> {
+    let data = std::fs::read(path).with_context(|| path.display().to_string())?;
+    Self::from_bytes(data).with_context(|| path.display().to_string())
+  }
+
+  pub fn from_bytes(data: Vec<u8>) -> anyhow::Result<Self> {
+    if data.len() < 100 || !data.starts_with(HEADER) {
+      bail!("not a SQLite database");
+    }
+    let page_size = match u16::from_be_bytes([data[16], data[17]]) {
+      1 => 65536,
+      size => size as usize,
+    };
+    if page_size < 512 || !page_size.is_power_of_two() {
+      bail!("invalid page size {}", page_size);
+    }
+    let encoding = u32::from_be_bytes([data[56], data[57], data[58], data[59]]);
+    if encoding > 1 {
+      bail!("UTF-16 databases are not supported");
+    }
+    let usable = page_size - data[20] as usize;
+    Ok(Self {
+      data,
+      page_size,
+      usable,
+    })
+  }
+
+  fn page(&self, number: u32) -> anyhow::Result<&[u8]> {
+    let start = (number as usize)
+      .checked_sub(1)
+      .map(|n| n * self.page_size)
+      .filter(|start| start + self.page_size <= self.data.len())
+      .with_context(|| format!("page {} out of range", number))?;
+    Ok(&self.data[start..start + self.page_size])
+  }
+
+  /// 数据库中的表
+  pub fn tables(&self) -> anyhow::Result<Vec<Table>> {
+    let master = Table {
+      name: "sqlite_master".to_string(),
+      root: 1,
+      columns: ["type", "name", "tbl_name", "rootpage", "sql"]
+        .map(str::to_string)
+        .to_vec(),
+      rowid_column: None,
+    };
+    let mut tables = Vec::new();
+    for row in self.rows(&master)? {
+      let text = |i: usize| row.get(i).and_then(Value::as_str).unwrap_or_default();
+      let root = row.get(3).and_then(Value::as_i64).unwrap_or(0);
+      if text(0) != "table" || root <= 0 {
+        continue;
+      }
+      let (columns, rowid_column) = parse_columns(text(4));
+      tables.push(Table {
+        name: text(1).to_string(),
+        root: root as u32,
+        columns,
+        rowid_column,
+      });
+    }
+    Ok(tables)
+  }
+
+  /// 按名称查找表（不区分大小写）
+  pub fn table(&self, name: &str) -> anyhow::Result<Option<Table>> {
+    Ok(
+      self
+        .tables()?
+        .into_iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name)),
+    )
+  }
+
+  /// 表的所有行，按 rowid 排序；每行的值与 columns 对应，记录中缺少的列为 NULL
+  pub fn rows(&self, table: &Table) -> anyhow::Result<Vec<Vec<Value>>> {
+    let mut rows = Vec::new();
+    let mut stack = vec![table.root];
+    let mut visits = 0;
+    let max_visits = self.data.len() / self.page_size * MAX_VISITS_FACTOR;
+    while let Some(number) = stack.pop() {
+      visits += 1;
+      if visits > max_visits {
+        bail!("corrupt database: b-tree of {} loops", table.name);
+      }
+      let page = self.page(number)?;
+      let header = if number == 1 { 100 } else { 0 };
+      let kind = page[header];
+      let cells = u16::from_be_bytes([page[header + 3], page[header + 4]]) as usize;
+      match kind {
+        // 内部页：左子页按 rowid 升序，最右子页在页头
+        0x05 => {
+          let right = u32::from_be_bytes(page[header + 8..header + 12].try_into()?);
+          stack.push(right);
+          for i in (0..cells).rev() {
+            let cell = cell_offset(page, header + 12, i)?;
+            let child = page
+              .get(cell..cell + 4)
+              .context("corrupt database: cell out of range")?;
+            stack.push(u32::from_be_bytes(child.try_into()?));
+          }
+        }
+        0x0d => {
+          for i in 0..cells {
+            let cell = cell_offset(page, header + 8, i)?;
+            let (payload, rowid) = self.leaf_payload(page, cell)?;
+            let mut values = parse_record(&payload)?;
+            values.resize(table.columns.len().max(values.len()), Value::Null);
+            if let Some(column) = table.rowid_column {
+              values[column] = Value::Integer(rowid);
+            }
+            rows.push(values);
+          }
+        }
+        _ => bail!("{} is not a rowid table", table.name),
+      }
+    }
+    Ok(rows)
+  }
+
+  /// 叶子页单元的内容（含溢出页中的部分）和 rowid
+  fn leaf_payload(&self, page: &[u8], cell: usize) -> anyhow::Result<(Vec<u8>, i64)> {
+    let (size, n) = varint(page.get(cell..).unwrap_or_default())?;
+    let (rowid, m) = varint(page.get(cell + n..).unwrap_or_default())?;
+    let start = cell + n + m;
+    // 大小来自文件本身，内容不可能比整个文件还大
+    if size > self.data.len() as u64 {
+      bail!(
+        "corrupt database: payload size {} exceeds the file size",
+        size
+      );
+    }
+    let size = size as usize;
+
+    // 超过 max_local 的部分存入溢出页，本页保留的字节数按文件格式的规则计算
+    let max_local = self.usable - 35;
+    let min_local = (self.usable - 12) * 32 / 255 - 23;
+    let local = if size <= max_local {
+      size
+    } else {
+      let k = min_local + (size - min_local) % (self.usable - 4);
+      if k <= max_local {
+        k
+      } else {
+        min_local
+      }
+    };
+    let mut payload = page
+      .get(start..start + local)
+      .context("corrupt database: payload out of range")?
+      .to_vec();
+    if local < size {
+      let pointer = page
+        .get(start + local..start + local + 4)
+        .context("corrupt database: overflow pointer out of range")?;
+      let mut next = u32::from_be_bytes(pointer.try_into()?);
+      // 溢出链最多经过每页一次，重复访问说明链成环
+      let mut visited = std::collections::HashSet::new();
+      let max_hops = self.data.len() / self.page_size;
+      while payload.len() < size {
+        if next == 0 {
+          bail!("corrupt database: overflow chain ends early");
+        }
+        if !visited.insert(next) || visited.len() > max_hops {
+          bail!("corrupt database: overflow chain loops at page {}", next);
+        }
+        let overflow = self.page(next)?;
+        next = u32::from_be_bytes(overflow[..4].try_into()?);
+        let take = (size - payload.len()).min(self.usable - 4);
+        payload.extend_from_slice(&overflow[4..4 + take]);
+      }
+    }
+    Ok((payload, rowid as i64))
+  }
+}
+
+fn cell_offset(page: &[u8], pointers: usize, i: usize) -> anyhow::Result<usize> {
+  let at = pointers + i * 2;
+  let bytes = page
+    .get(at..at + 2)
+    .context("corrupt database: cell pointer out of range")?;
+  Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+}
+
+/// SQLite 的变长整数（1 到 9 字节，大端），返回值和占用的字节数
+fn varint(bytes: &[u8]) -> anyhow::Result<(u64, usize)> {
+  let mut value = 0u64;
+  for (i, &byte) in bytes.iter().take(9).enumerate() {
+    if i == 8 {
+      return Ok(((value << 8) | byte as u64, 9));
+    }
+    value = (value << 7) | (byte & 0x7f) as u64;
+    if byte & 0x80 == 0 {
+      return Ok((value, i + 1));
+    }
+  }
+  bail!("corrupt database: truncated varint")
+}
+
+/// 解析记录：头部为各列的类型，之后依次是各列的值
+fn parse_record(record: &[u8]) -> anyhow::Result<Vec<Value>> {
+  let (header_size, mut pos) = varint(record)?;
+  let header_size = header_size as usize;
+  let mut types = Vec::new();
+  while pos < header_size {
+    let (serial, n) = varint(record.get(pos..header_size).unwrap_or_default())?;
+    types.push(serial);
+    pos += n;
+  }
+
+  let mut body = record
+    .get(header_size..)
+    .context("corrupt database: record header out of range")?;
+  let mut values = Vec::with_capacity(types.len());
+  for serial in types {
+    let len = match serial {
+      0 | 8 | 9 => 0,
+      1..=4 => serial as usize,
+      5 => 6,
+      6 | 7 => 8,
+      10 | 11 => bail!("corrupt database: reserved serial type"),
+      n => (n as usize - 12) / 2,
+    };
+    let bytes = body
+      .get(..len)
+      .context("corrupt database: record value out of range")?;
+    values.push(match serial {
+      0 => Value::Null,
+      8 => Value::Integer(0),
+      9 => Value::Integer(1),
+      1..=6 => {
+        // 大端补码，按最高位补齐符号
+        let mut value = if bytes[0] & 0x80 != 0 { -1i64 } else { 0 };
+        for &b in bytes {
+          value = (value << 8) | b as i64;
+        }
+        Value::Integer(value)
+      }
+      7 => Value::Real(f64::from_be_bytes(bytes.try_into()?)),
+      n if n % 2 == 0 => Value::Blob(bytes.to_vec()),
+      _ => Value::Text(String::from_utf8_lossy(bytes).into_owned()),
+    });
+    body = &body[len..];
+  }
+  Ok(values)
+}
+
+/// 从 CREATE TABLE 语句中取出列名，以及 INTEGER PRIMARY KEY 列的位置
+fn parse_columns(sql: &str) -> (Vec<String>, Option<usize>) {
+  let (Some(open), Some(close)) = (sql.find('('), sql.rfind(')')) else {
+    return (Vec::new(), None);
+  };
+  if close < open {
+    return (Vec::new(), None);
+  }
+
+  // 按不在括号内的逗号分隔各列的定义
+  let mut definitions = Vec::new();
+  let mut depth = 0;
+  let mut start = open + 1;
+  for (i, c) in sql[..close].char_indices().skip_while(|(i, _)| *i <= open) {
+    match c {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      ',' if depth == 0 => {
+        definitions.push(&sql[start..i]);
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  definitions.push(&sql[start..close]);
+
+  let mut columns = Vec::new();
+  let mut rowid_column = None;
+  for definition in definitions {
+    let definition = definition.trim();
+    let Some(first) = definition.split_whitespace().next() else {
+      continue;
+    };
+    // 表级约束不是列
+    if ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"]
+      .iter()
+      .any(|k| first.eq_ignore_ascii_case(k))
+    {
+      continue;
+    }
+    let words: Vec<String> = definition
+      .split_whitespace()
+      .map(str::to_uppercase)
+      .collect();
+    if words.get(1..4) == Some(&["INTEGER".into(), "PRIMARY".into(), "KEY".into()]) {
+      rowid_column = Some(columns.len());
+    }
+    columns.push(
+      first
+        .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'))
+        .to_string(),
+    );
+  }
+  (columns, rowid_column)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_columns() {
+    let (columns, rowid) = parse_columns(
+      "CREATE TABLE searchIndex(id INTEGER PRIMARY KEY, name TEXT, \"type\" TEXT, path TEXT, \
+       UNIQUE (name, type, path))",
+    );
+    assert_eq!(columns, ["id", "name", "type", "path"]);
+    assert_eq!(rowid, Some(0));
+  }
+
+  #[test]
+  fn test_overflow_cycle() {
+    // 两页的数据库：第 2 页是指向自己的溢出页
+    let mut data = vec![0u8; 1024];
+    data[..HEADER.len()].copy_from_slice(HEADER);
+    data[16..18].copy_from_slice(&512u16.to_be_bytes());
+    data[56..60].copy_from_slice(&1u32.to_be_bytes());
+    data[512..516].copy_from_slice(&2u32.to_be_bytes());
+    let db = Database::from_bytes(data).unwrap();
+
+    // 单元：大小 1000（本页保留 39 字节）、rowid 1、本页内容和溢出页指针
+    let mut page = vec![0x87, 0x68, 0x01];
+    page.extend([b'x'; 39]);
+    page.extend(2u32.to_be_bytes());
+    let err = db.leaf_payload(&page, 0).unwrap_err();
+    assert!(err.to_string().contains("loops at page 2"));
+
+    // 大小超过文件
+    let page = [0x81, 0x80, 0x80, 0x00, 0x01];
+    let err = db.leaf_payload(&page, 0).unwrap_err();
+    assert!(err.to_string().contains("exceeds the file size"));
+  }
+
+  #[test]
+  fn test_record() {
+    assert_eq!(varint(&[0x81, 0x00]).unwrap(), (128, 2));
+    // 头部 4 字节：NULL、1 字节整数、长度 2 的文本
+    let record = [4, 0, 1, 17, 0xff, b'h', b'i'];
+    assert_eq!(
+      parse_record(&record).unwrap(),
+      [
+        Value::Null,
+        Value::Integer(-1),
+        Value::Text("hi".to_string())
+      ]
+    );
+  }
+}
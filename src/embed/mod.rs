@@ -0,0 +1,616 @@
+//! 语义搜索的向量索引
+//!
+//! 为每个命令的名称、描述和示例描述生成向量，保存在数据目录下的独立文件中，与 tantivy 索引互不影响。
+//! 默认用 fastembed 在本机运行 ONNX 模型（首次使用时下载到数据目录下的 models，运行时加载系统的
+//! ONNX Runtime），也可以改用 OpenAI 兼容的 embeddings 接口（如 Ollama）。
+//! 重建时按文本哈希复用未变化的向量，只为新增或修改的命令生成。
+//!
+//! 文件格式：`RTFMVEC1` + 头部长度（u64 LE）+ JSON 头部（模型、维度、条目）+ 归一化后的 f32 LE 向量。
+//!
+//! 搜索时把查询向量与所有条目做余弦相似度，再用加权的倒数排名融合（RRF）与关键词结果合并。
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::EmbeddingsConfig;
+use crate::i18n::t;
+use crate::search::SearchResult;
+use crate::storage::Command;
+
+const MAGIC: &[u8; 8] = b"RTFMVEC1";
+
+/// 发送给模型的最大字符数
+const MAX_TEXT_CHARS: usize = 2000;
+
+/// RRF 的平滑常数
+const RRF_K: f32 = 60.0;
+
+/// 索引中的一个命令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+  pub name: String,
+  pub lang: String,
+  pub description: String,
+  pub category: String,
+  /// 旧版本建立的索引没有平台
+  #[serde(default)]
+  pub platform: String,
+  /// 生成向量所用文本的哈希
+  pub hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+  model: String,
+  dim: usize,
+  entries: Vec<Entry>,
+}
+
+/// 向量索引
+#[derive(Debug, Clone, Default)]
+pub struct VectorIndex {
+  pub model: String,
+  pub dim: usize,
+  pub entries: Vec<Entry>,
+  /// entries.len() * dim 个分量
+  vectors: Vec<f32>,
+}
+
+impl VectorIndex {
+  pub fn load(path: &Path) -> anyhow::Result<Self> {
+    let file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut file = std::io::BufReader::new(file);
+    let mut magic = [0u8; 8];
+    file.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+      anyhow::bail!("{} is not an rtfm embeddings index", path.display());
+    }
+    let mut len = [0u8; 8];
+    file.read_exact(&mut len)?;
+    // 头部长度来自文件内容，超过文件剩余大小时不分配
+    let len = u64::from_le_bytes(len);
+    if len > size.saturating_sub(16) {
+      anyhow::bail!("{} is truncated, run 'rtfm embed rebuild'", path.display());
+    }
+    let mut header = vec![0u8; len as usize];
+    file.read_exact(&mut header)?;
+    let header: Header = serde_json::from_slice(&header)?;
+
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    let expected = header
+      .entries
+      .len()
+      .checked_mul(header.dim)
+      .and_then(|n| n.checked_mul(4));
+    if expected != Some(data.len()) {
+      anyhow::bail!("{} is truncated, run 'rtfm embed rebuild'", path.display());
+    }
+    let vectors = data
+      .chunks_exact(4)
+      .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+      .collect();
+    Ok(Self {
+      model: header.model,
+      dim: header.dim,
+      entries: header.entries,
+      vectors,
+    })
+  }
+
+  /// 先写临时文件再替换，避免中断时留下损坏的索引
+  pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+    let tmp = path.with_extension("tmp");
+    {
+      let mut file = std::io::BufWriter::new(std::fs::File::create(&tmp)?);
+      let header = serde_json::to_vec(&Header {
+        model: self.model.clone(),
+        dim: self.dim,
+        entries: self.entries.clone(),
+      })?;
+      file.write_all(MAGIC)?;
+      file.write_all(&(header.len() as u64).to_le_bytes())?;
+      file.write_all(&header)?;
+      for value in &self.vectors {
+        file.write_all(&value.to_le_bytes())?;
+      }
+      file.flush()?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+  }
+
+  fn vector(&self, i: usize) -> &[f32] {
+    &self.vectors[i * self.dim..(i + 1) * self.dim]
+  }
+
+  fn push(&mut self, entry: Entry, vector: &[f32]) {
+    self.entries.push(entry);
+    self.vectors.extend_from_slice(vector);
+  }
+
+  /// 与查询最相似的命令（余弦相似度从高到低）
+  pub fn nearest(&self, query: &[f32], lang: Option<&str>, limit: usize) -> Vec<(&Entry, f32)> {
+    if query.len() != self.dim {
+      return Vec::new();
+    }
+    let query = normalize(query.to_vec());
+    let mut scored: Vec<(&Entry, f32)> = self
+      .entries
+      .iter()
+      .enumerate()
+      .filter(|(_, e)| lang.is_none_or(|l| e.lang == l))
+      .map(|(i, e)| (e, dot(&query, self.vector(i))))
+      .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+    scored
+  }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+  a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+  let norm = dot(&v, &v).sqrt();
+  if norm > 0.0 {
+    v.iter_mut().for_each(|x| *x /= norm);
+  }
+  v
+}
+
+/// 生成向量所用的文本：名称、描述和示例描述
+pub fn embedding_text(cmd: &Command) -> String {
+  let mut text = format!("{}: {}", cmd.name, cmd.description.trim());
+  for example in &cmd.examples {
+    text.push('\n');
+    text.push_str(example.description.trim());
+  }
+  text.chars().take(MAX_TEXT_CHARS).collect()
+}
+
+pub fn text_hash(text: &str) -> String {
+  format!("{:x}", Sha256::digest(text.as_bytes()))[..16].to_string()
+}
+
+/// 生成向量的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+  /// 本机运行 ONNX 模型
+  Local,
+  /// OpenAI 兼容的 embeddings 接口
+  Http,
+}
+
+impl Provider {
+  pub fn parse(s: &str) -> Option<Self> {
+    match s {
+      "local" => Some(Provider::Local),
+      "http" => Some(Provider::Http),
+      _ => None,
+    }
+  }
+}
+
+/// 本地模型：embeddings.model 为 fastembed 支持的模型代码（如 intfloat/multilingual-e5-small）
+pub fn local_model(name: &str) -> anyhow::Result<EmbeddingModel> {
+  name
+    .parse()
+    .map_err(|_| anyhow::anyhow!("'{}' is not a supported local embeddings model", name))
+}
+
+/// fastembed 与 ort 加载 ONNX Runtime 的路径：ORT_DYLIB_PATH，否则按系统默认的库名
+fn onnxruntime_path() -> PathBuf {
+  let name = match std::env::var("ORT_DYLIB_PATH") {
+    Ok(path) if !path.is_empty() => return PathBuf::from(path),
+    _ if cfg!(windows) => "onnxruntime.dll",
+    _ if cfg!(target_os = "macos") => "libonnxruntime.dylib",
+    _ => "libonnxruntime.so",
+  };
+  // ort 优先使用可执行文件旁边的库
+  std::env::current_exe()
+    .ok()
+    .and_then(|exe| exe.parent().map(|dir| dir.join(name)))
+    .filter(|path| path.exists())
+    .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// ort 在找不到 ONNX Runtime 时直接 panic（release 构建会中止进程），先自行加载一次以给出提示
+fn check_onnxruntime() -> anyhow::Result<()> {
+  let path = onnxruntime_path();
+  // SAFETY: 与 ort 随后加载的是同一个库，这里只检查能否加载
+  unsafe { libloading::Library::new(&path) }
+    .map(drop)
+    .map_err(|e| {
+      anyhow::anyhow!(
+        "{}",
+        t!("embed.no_onnxruntime", path = path.display(), error = e)
+      )
+    })
+}
+
+enum Backend {
+  Local(Arc<TextEmbedding>),
+  Http(reqwest::Client),
+}
+
+/// 生成向量的客户端
+pub struct EmbeddingClient {
+  backend: Backend,
+  config: EmbeddingsConfig,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+  model: &'a str,
+  input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+  data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+  embedding: Vec<f32>,
+  #[serde(default)]
+  index: usize,
+}
+
+impl EmbeddingClient {
+  /// 本地模型在这里加载（首次使用时下载到 data_dir/models）
+  pub async fn new(config: &EmbeddingsConfig, data_dir: &Path) -> anyhow::Result<Self> {
+    let provider = Provider::parse(&config.provider).ok_or_else(|| {
+      anyhow::anyhow!(
+        "embeddings.provider '{}' must be \"local\" or \"http\"",
+        config.provider
+      )
+    })?;
+    let backend = match provider {
+      Provider::Local => {
+        let model = local_model(&config.model)?;
+        check_onnxruntime()?;
+        let options = InitOptions::new(model)
+          .with_cache_dir(data_dir.join("models"))
+          .with_show_download_progress(false);
+        let model = tokio::task::spawn_blocking(move || TextEmbedding::try_new(options))
+          .await?
+          .map_err(|e| anyhow::anyhow!("Failed to load embeddings model: {}", e))?;
+        Backend::Local(Arc::new(model))
+      }
+      Provider::Http => Backend::Http(
+        reqwest::Client::builder()
+          .timeout(Duration::from_secs(config.timeout_secs))
+          .build()?,
+      ),
+    };
+    Ok(Self {
+      backend,
+      config: config.clone(),
+    })
+  }
+
+  /// 为一批文本生成向量，顺序与输入一致
+  pub async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+    let client = match &self.backend {
+      Backend::Local(model) => {
+        // 推理占用 CPU，放到阻塞线程中
+        let model = model.clone();
+        let texts = texts.to_vec();
+        let batch_size = self.config.batch_size;
+        return tokio::task::spawn_blocking(move || model.embed(texts, Some(batch_size)))
+          .await?
+          .map_err(|e| anyhow::anyhow!("Failed to embed with the local model: {}", e));
+      }
+      Backend::Http(client) => client,
+    };
+    let api_key = self
+      .config
+      .api_key
+      .clone()
+      .or_else(|| std::env::var(&self.config.api_key_env).ok())
+      .filter(|k| !k.is_empty());
+    let mut request = client.post(&self.config.endpoint).json(&EmbeddingRequest {
+      model: &self.config.model,
+      input: texts,
+    });
+    if let Some(key) = api_key {
+      request = request.bearer_auth(key);
+    }
+
+    let response = request.send().await.map_err(|e| {
+      anyhow::anyhow!(
+        "Failed to reach embeddings endpoint {}: {}",
+        self.config.endpoint,
+        e
+      )
+    })?;
+    if !response.status().is_success() {
+      let status = response.status();
+      let body = response.text().await.unwrap_or_default();
+      anyhow::bail!(
+        "Embeddings endpoint returned {}: {}",
+        status,
+        body.chars().take(200).collect::<String>()
+      );
+    }
+    let mut data = response.json::<EmbeddingResponse>().await?.data;
+    if data.len() != texts.len() {
+      anyhow::bail!(
+        "Embeddings endpoint returned {} vectors for {} inputs",
+        data.len(),
+        texts.len()
+      );
+    }
+    data.sort_by_key(|d| d.index);
+    Ok(data.into_iter().map(|d| d.embedding).collect())
+  }
+}
+
+/// 重建结果
+#[derive(Debug, Default)]
+pub struct RebuildReport {
+  pub embedded: usize,
+  pub reused: usize,
+}
+
+/// 为命令建立向量索引；previous 中文本未变化的向量直接复用
+/// progress 在每批完成后以（已完成数，待生成总数）调用
+pub async fn rebuild(
+  commands: &[Command],
+  previous: Option<VectorIndex>,
+  client: &EmbeddingClient,
+  mut progress: impl FnMut(usize, usize),
+) -> anyhow::Result<(VectorIndex, RebuildReport)> {
+  let model = client.config.model.clone();
+  let previous = previous.filter(|p| p.model == model);
+  let known: HashMap<(&str, &str, &str), usize> = previous
+    .iter()
+    .flat_map(|p| p.entries.iter().enumerate())
+    .map(|(i, e)| ((e.lang.as_str(), e.name.as_str(), e.hash.as_str()), i))
+    .collect();
+
+  let mut index = VectorIndex {
+    model,
+    dim: previous.as_ref().map(|p| p.dim).unwrap_or(0),
+    ..Default::default()
+  };
+  let mut report = RebuildReport::default();
+  let mut pending: Vec<(Entry, String)> = Vec::new();
+
+  for cmd in commands {
+    let text = embedding_text(cmd);
+    let entry = Entry {
+      name: cmd.name.clone(),
+      lang: cmd.lang.clone(),
+      description: cmd.description.clone(),
+      category: cmd.category.clone(),
+      platform: cmd.platform.clone(),
+      hash: text_hash(&text),
+    };
+    let reused = known.get(&(
+      entry.lang.as_str(),
+      entry.name.as_str(),
+      entry.hash.as_str(),
+    ));
+    match (reused, &previous) {
+      (Some(&i), Some(previous)) => {
+        index.push(entry, previous.vector(i));
+        report.reused += 1;
+      }
+      _ => pending.push((entry, text)),
+    }
+  }
+
+  let total = pending.len();
+  for batch in pending.chunks(client.config.batch_size.max(1)) {
+    let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+    let vectors = client.embed(&texts).await?;
+    for ((entry, _), vector) in batch.iter().zip(vectors) {
+      if index.dim == 0 {
+        index.dim = vector.len();
+      }
+      if vector.len() != index.dim {
+        anyhow::bail!(
+          "Model returned {}-dimensional vectors, expected {}; run 'rtfm embed rebuild --force'",
+          vector.len(),
+          index.dim
+        );
+      }
+      index.push(entry.clone(), &normalize(vector));
+      report.embedded += 1;
+    }
+    progress(report.embedded, total);
+  }
+
+  Ok((index, report))
+}
+
+/// 加权 RRF：按两个列表中的排名融合，weight 为语义结果的权重
+pub fn blend(
+  keyword: Vec<SearchResult>,
+  semantic: Vec<SearchResult>,
+  weight: f32,
+  limit: usize,
+) -> Vec<SearchResult> {
+  let mut merged: Vec<(SearchResult, f32)> = Vec::new();
+  let mut add = |results: Vec<SearchResult>, weight: f32| {
+    for (rank, result) in results.into_iter().enumerate() {
+      let score = weight / (RRF_K + rank as f32 + 1.0);
+      match merged
+        .iter_mut()
+        .find(|(r, _)| r.name == result.name && r.lang == result.lang)
+      {
+        Some((_, total)) => *total += score,
+        None => merged.push((result, score)),
+      }
+    }
+  };
+  add(keyword, 1.0 - weight);
+  add(semantic, weight);
+
+  merged.sort_by(|a, b| b.1.total_cmp(&a.1));
+  merged
+    .into_iter()
+    .take(limit)
+    .map(|(result, score)| SearchResult { score, ..result })
+    .collect()
+}
+
+/// 语义搜索结果
+pub fn semantic_results(
+  index: &VectorIndex,
+  query: &[f32],
+  lang: Option<&str>,
+  limit: usize,
+) -> Vec<SearchResult> {
+  index
+    .nearest(query, lang, limit)
+    .into_iter()
+    .map(|(entry, score)| SearchResult {
+      name: entry.name.clone(),
+      description: entry.description.clone(),
+      category: entry.category.clone(),
+      lang: entry.lang.clone(),
+      platform: entry.platform.clone(),
+      score,
+      example: None,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(name: &str) -> Entry {
+    Entry {
+      name: name.to_string(),
+      lang: "en".to_string(),
+      description: String::new(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      hash: String::new(),
+    }
+  }
+
+  fn result(name: &str) -> SearchResult {
+    SearchResult {
+      name: name.to_string(),
+      description: String::new(),
+      category: "common".to_string(),
+      lang: "en".to_string(),
+      platform: "common".to_string(),
+      score: 0.0,
+      example: None,
+    }
+  }
+
+  #[test]
+  fn test_index_roundtrip() {
+    let mut index = VectorIndex {
+      model: "test".to_string(),
+      dim: 2,
+      ..Default::default()
+    };
+    index.push(entry("tar"), &normalize(vec![1.0, 0.0]));
+    index.push(entry("curl"), &normalize(vec![0.0, 3.0]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("embeddings.bin");
+    index.save(&path).unwrap();
+    let loaded = VectorIndex::load(&path).unwrap();
+    assert_eq!(loaded.model, "test");
+    assert_eq!(loaded.entries.len(), 2);
+
+    let nearest = loaded.nearest(&[0.1, 0.9], None, 1);
+    assert_eq!(nearest[0].0.name, "curl");
+    assert!(loaded.nearest(&[1.0], None, 1).is_empty());
+
+    // 损坏的头部长度不会导致巨大的分配
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(&u64::MAX.to_le_bytes());
+    std::fs::write(&path, &data).unwrap();
+    assert!(VectorIndex::load(&path).is_err());
+    // 维度过大，向量数据的长度溢出
+    let header = format!(
+      r#"{{"model":"test","dim":{},"entries":[{}]}}"#,
+      usize::MAX,
+      serde_json::to_string(&entry("tar")).unwrap()
+    );
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    data.extend_from_slice(header.as_bytes());
+    std::fs::write(&path, &data).unwrap();
+    assert!(VectorIndex::load(&path).is_err());
+  }
+
+  #[tokio::test]
+  async fn test_client_config() {
+    assert_eq!(Provider::parse("local"), Some(Provider::Local));
+    assert_eq!(Provider::parse("http"), Some(Provider::Http));
+    assert_eq!(Provider::parse("ollama"), None);
+    assert_eq!(
+      local_model("intfloat/multilingual-e5-small").unwrap(),
+      EmbeddingModel::MultilingualE5Small
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    // 不支持的本地模型在加载 ONNX Runtime 之前报错
+    let config = EmbeddingsConfig {
+      model: "nomic-embed-text".to_string(),
+      ..Default::default()
+    };
+    let error = EmbeddingClient::new(&config, dir.path())
+      .await
+      .err()
+      .unwrap();
+    assert!(error
+      .to_string()
+      .contains("not a supported local embeddings model"));
+
+    let config = EmbeddingsConfig {
+      provider: "ollama".to_string(),
+      ..Default::default()
+    };
+    assert!(EmbeddingClient::new(&config, dir.path()).await.is_err());
+
+    // 接口方式不加载模型
+    let config = EmbeddingsConfig {
+      provider: "http".to_string(),
+      model: "nomic-embed-text".to_string(),
+      ..Default::default()
+    };
+    assert!(EmbeddingClient::new(&config, dir.path()).await.is_ok());
+  }
+
+  #[test]
+  fn test_blend() {
+    let keyword = vec![result("tar"), result("zip")];
+    let semantic = vec![result("gzip"), result("zip")];
+    // zip 同时出现在两个列表中，排在最前
+    let names: Vec<String> = blend(keyword.clone(), semantic.clone(), 0.5, 3)
+      .into_iter()
+      .map(|r| r.name)
+      .collect();
+    assert_eq!(names, ["zip", "tar", "gzip"]);
+
+    // 权重为 0 时只看关键词排名
+    let names: Vec<String> = blend(keyword, semantic, 0.0, 2)
+      .into_iter()
+      .map(|r| r.name)
+      .collect();
+    assert_eq!(names, ["tar", "zip"]);
+  }
+}
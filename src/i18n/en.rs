@@ -0,0 +1,714 @@
+//! 英文消息：默认语言，其他语言缺少某条消息时也使用这里的文字
+
+pub const MESSAGES: &[(&str, &str)] = &[
+  // TUI
+  ("tui.searching", "Searching..."),
+  ("tui.no_results", "No results found"),
+  ("tui.did_you_mean", "Did you mean: {names}?"),
+  ("tui.command_not_found", "Command not found: {name}"),
+  ("tui.learned_from", "Learned from {version}"),
+  ("tui.learned_outdated", "⚠ Installed binary changed since it was learned; run rtfm learn --refresh"),
+  ("tui.from_local_help", "From local help"),
+  ("tui.matching_example", "Example {index}: "),
+  ("tui.invalid_keys", "Invalid [tui.keys] configuration"),
+  ("tui.status.total", "{count} commands total"),
+  ("tui.status.found", "Found {count} results ({ms}ms)"),
+  ("tui.status.search_failed", "Search failed: {error}"),
+  (
+    "tui.status.save_style_failed",
+    "Failed to save UI style: {error}",
+  ),
+  ("tui.classic.search_title", " Search (/ to focus) "),
+  (
+    "tui.classic.search_placeholder",
+    "Type to search commands...",
+  ),
+  (
+    "tui.classic.search_hints",
+    " [{switch}] Switch  [{help}] Help  [{back}] Back/Quit",
+  ),
+  ("tui.classic.results_title", " Results "),
+  ("tui.classic.results_count", " Results ({count}) "),
+  ("tui.classic.type_to_search", "Type to search"),
+  ("tui.classic.details_title", " Details "),
+  (
+    "tui.classic.select_command",
+    "Select a command to view details",
+  ),
+  ("tui.classic.logs_title", " Debug Logs [{key} close] "),
+  (
+    "tui.classic.status_hints",
+    "[{up}/{down}] Nav  [{open}] View ",
+  ),
+  ("tui.modern.search_title", " Search "),
+  (
+    "tui.modern.search_placeholder",
+    "Type to search... (↑↓ to navigate results)",
+  ),
+  ("tui.modern.search_hints", " [{help}] Help  [{back}] Quit "),
+  ("tui.modern.result_title", " Result "),
+  ("tui.modern.result_position", " Result [{index}/{count}] "),
+  (
+    "tui.modern.start_typing",
+    "Start typing to search commands...",
+  ),
+  ("tui.modern.tips", "Tips: Use ↑↓ or j/k to navigate results"),
+  (
+    "tui.modern.try_update",
+    "Try a different search term or run 'rtfm update'",
+  ),
+  (
+    "tui.modern.result_hints",
+    "{up}{down} Scroll  {prev}{next} Switch result  {search} Search  {help} Help",
+  ),
+  ("tui.modern.logs_title", " Logs [{key} close] "),
+  ("tui.help.title", "Keyboard Shortcuts"),
+  ("tui.help.border", " Help "),
+  ("tui.help.footer", "Keys can be changed in [tui.keys]"),
+  ("tui.action.quit", "Force quit"),
+  ("tui.action.help", "Toggle help"),
+  (
+    "tui.action.toggle_logs",
+    "Toggle debug logs (requires --debug)",
+  ),
+  (
+    "tui.action.toggle_style",
+    "Switch UI style (Modern/Classic)",
+  ),
+  ("tui.action.clear_search", "Clear search"),
+  ("tui.action.up", "Navigate / Scroll up"),
+  ("tui.action.down", "Navigate / Scroll down"),
+  (
+    "tui.action.prev",
+    "Previous result (Modern) / Back to list (Classic)",
+  ),
+  (
+    "tui.action.next",
+    "Next result (Modern) / View details (Classic)",
+  ),
+  ("tui.action.page_up", "Page up"),
+  ("tui.action.page_down", "Page down"),
+  ("tui.action.top", "Jump to first"),
+  ("tui.action.bottom", "Jump to last"),
+  ("tui.action.next_focus", "Switch focus"),
+  ("tui.action.search", "Focus search"),
+  ("tui.action.back", "Clear / Back / Quit"),
+  ("tui.action.open", "View details / fill in an example"),
+  ("tui.action.toggle_favorite", "Add / remove favorite"),
+  ("tui.action.favorites", "Show only favorites"),
+  // 更新提示
+  ("notice.dataset.title", "tldr pages {version} available"),
+  (
+    "notice.dataset.body",
+    "Installed: {current}. Run 'rtfm update' to download it.",
+  ),
+  ("notice.release.title", "rtfm {version} released"),
+  (
+    "notice.release.body",
+    "Installed: {current}. Download it from {url}",
+  ),
+  // 命令行帮助中的命令说明（英文与 src/cli 中的文档注释一致）
+  ("cli", "Read The F***ing Manual - CLI cheatsheet"),
+  (
+    "cli.search",
+    "Search commands and print the results for scripts and desktop launchers",
+  ),
+  ("cli.serve", "Start HTTP API server"),
+  ("cli.service", "Run the HTTP server as a Windows service"),
+  (
+    "cli.service.install",
+    "Register the service for the current data directory and config file",
+  ),
+  ("cli.service.start", "Start the service"),
+  ("cli.service.stop", "Stop the service"),
+  ("cli.service.uninstall", "Stop and remove the service"),
+  (
+    "cli.install-service",
+    "Write a systemd unit that runs 'rtfm serve' for the current data directory",
+  ),
+  ("cli.update", "Update command cheatsheet data"),
+  (
+    "cli.import",
+    "Import tldr pages (.md, .zip, .tar, .tar.gz, .tgz, or directory), navi .cheat files, cheat sheets, Dash/Zeal docsets or JSON exports, from paths or URLs",
+  ),
+  ("cli.learn", "Learn a command from --help or man page"),
+  (
+    "cli.learn-all",
+    "Learn commands from the system (man pages, PowerShell, or PATH)",
+  ),
+  (
+    "cli.suggest-learn",
+    "Suggest frequently used commands from shell history that are not in the database yet",
+  ),
+  (
+    "cli.backup",
+    "Backup all application data (database, index, config) to archive",
+  ),
+  (
+    "cli.restore",
+    "Restore application data from backup archive",
+  ),
+  (
+    "cli.stats",
+    "Show database statistics and learned-content quality",
+  ),
+  ("cli.audit", "Show the log of data modifications (imports, learning, updates, deletions, resets, restores, syncs)"),
+  (
+    "cli.export-cards",
+    "Export examples as flashcards for Anki or other spaced-repetition apps",
+  ),
+  (
+    "cli.render",
+    "Render a command as a man page, or install pages for commands without one",
+  ),
+  (
+    "cli.diff",
+    "Compare a learned command with its tldr page to spot options the page doesn't cover",
+  ),
+  ("cli.reset", "Reset all data (factory reset)"),
+  ("cli.config", "Inspect configuration"),
+  (
+    "cli.config.validate",
+    "Check the config file for unknown keys, invalid values and conflicting settings",
+  ),
+  (
+    "cli.config.path",
+    "Show where config files are looked for and which one is in use",
+  ),
+  (
+    "cli.config.schema",
+    "Print a JSON Schema for the config file (for editor completion and validation)",
+  ),
+  ("cli.sync", "Exchange commands, favorites and notes with a remote rtfm server"),
+  (
+    "cli.sync.push",
+    "Send local commands, favorites and notes that the remote lacks or has in a different version",
+  ),
+  (
+    "cli.sync.pull",
+    "Fetch remote commands, favorites and notes that are missing or different locally",
+  ),
+  (
+    "cli.embed",
+    "Manage the embeddings index used by --semantic search",
+  ),
+  (
+    "cli.embed.rebuild",
+    "Embed new and changed commands (unchanged ones keep their vectors)",
+  ),
+  (
+    "cli.embed.status",
+    "Show the embeddings index model, size and how many commands changed since it was built",
+  ),
+  (
+    "cli.plugin",
+    "Manage plugins that provide data sources, help parsers and renderers",
+  ),
+  (
+    "cli.plugin.list",
+    "List plugins found in the plugins directory",
+  ),
+  (
+    "cli.plugin.fetch",
+    "Import the commands provided by a source plugin",
+  ),
+  (
+    "cli.user",
+    "Manage users of the multi-user HTTP server (server.multi_user)",
+  ),
+  ("cli.user.add", "Add a user and print their API token"),
+  ("cli.user.list", "List users"),
+  (
+    "cli.user.token",
+    "Issue a new API token for a user (the old one stops working)",
+  ),
+  ("cli.user.remove", "Remove a user"),
+  (
+    "cli.fav",
+    "Manage favorite commands (Ctrl+F in the TUI shows only favorites)",
+  ),
+  ("cli.fav.add", "Add commands to the favorites"),
+  ("cli.fav.remove", "Remove commands from the favorites"),
+  ("cli.fav.list", "List favorite commands"),
+  ("cli.alias", "Manage command aliases (e.g. gco for git checkout), resolved before looking up a command"),
+  ("cli.alias.add", "Add an alias or point an existing one at another command"),
+  ("cli.alias.remove", "Remove aliases"),
+  ("cli.alias.list", "List aliases"),
+  ("cli.alias.import", "Import aliases from shell config files (bash, zsh, fish) and git config"),
+  // rtfm serve
+  ("serve.listening", "RTFM HTTP server listening on http://{addr}"),
+  ("serve.swagger", "Swagger UI: http://{addr}/swagger-ui"),
+  ("serve.cheatsheet", "Cheatsheets: http://{addr}/cheatsheet"),
+  ("serve.logs_journald", "Logs: journald (journalctl -t rtfm)"),
+  ("serve.logs", "Logs: {path}"),
+  ("serve.read_only", "Read-only mode: ON (import, update, learn, restore and reset are disabled)"),
+  ("serve.debug", "Debug mode: ON (logs also printed to console)"),
+  ("serve.press_ctrl_c", "Press Ctrl+C to stop"),
+  ("serve.stopped", "Server stopped gracefully"),
+  ("serve.detached", "RTFM server started in background"),
+  ("serve.detached.address", "  Address: http://{addr}"),
+  ("serve.detached.swagger", "  Swagger: http://{addr}/swagger-ui"),
+  ("serve.detached.cheatsheet", "  Cheatsheets: http://{addr}/cheatsheet"),
+  ("serve.detached.logs", "  Logs: {path}"),
+  ("serve.detached.read_only", "  Read-only: import, update, learn, restore and reset are disabled"),
+  ("serve.detached.stop", "Status: rtfm serve --status    Stop: rtfm serve --stop"),
+  ("serve.detached.failed", "The background server exited during startup, see the logs in {path}"),
+  ("serve.already_running", "A background server is already running (PID {pid}, http://{addr}); stop it with 'rtfm serve --stop'"),
+  ("serve.service.enabled", "The server is running on http://{addr} and starts again after a reboot"),
+  ("serve.service.command_failed", "Could not start the service; run these commands:"),
+  ("serve.service.enable_failed", "Service files were written, but '{command}' failed"),
+  ("serve.service.not_installed", "No service installed by rtfm serve --install-service"),
+  ("serve.service.removed", "Removed {path}"),
+  ("serve.service.no_dir", "No home directory available for the service files"),
+  ("serve.status.running", "Server is running (PID {pid}): http://{addr}"),
+  ("serve.status.unhealthy", "Server process {pid} is running but http://{addr}/api/health does not respond"),
+  ("serve.status.none", "No background server (start one with 'rtfm serve --detach')"),
+  ("serve.status.stale", "Server process {pid} is no longer running; removed the stale PID file"),
+  ("serve.stopping", "Stopping server (PID {pid})..."),
+  ("serve.stop_timeout", "Server process {pid} did not exit within {secs} seconds"),
+  // rtfm service / rtfm install-service
+  ("service.installed", "Installed service '{name}'"),
+  ("service.data_dir", "  Data directory: {path}"),
+  ("service.config_file", "  Config file:    {path}"),
+  ("service.address", "  Address:        http://{addr}"),
+  ("service.start_manual", "Start it with: rtfm service start"),
+  ("service.start_auto", "It starts with Windows. To start it now: rtfm service start"),
+  ("service.started", "Service '{name}' started"),
+  ("service.stopping", "Service '{name}' is stopping"),
+  ("service.removed", "Service '{name}' removed"),
+  ("install_service.no_dir", "No config directory available; use --dir"),
+  ("install_service.wrote", "Wrote {path}"),
+  ("install_service.enable", "To start it now and at boot:"),
+  ("install_service.linger", "To keep it running after you log out: loginctl enable-linger"),
+  // 共用
+  ("common.rebuilding_index", "Rebuilding search index..."),
+  ("common.no_data", "No data yet. Run 'rtfm update' or 'rtfm learn <command>' first."),
+  ("common.try", "Try:"),
+  ("common.details", "Details:"),
+  ("common.try_update", "  - Use 'rtfm update' to download cheatsheets from tldr-pages"),
+  ("common.none", "  (none)"),
+  ("common.cancelled", "Cancelled."),
+  // rtfm update
+  ("update.checking", "Checking for updates..."),
+  ("update.up_to_date", "Already up to date: {version}"),
+  ("update.found", "New version found: {version}"),
+  ("update.no_download_url", "Download URL not found"),
+  ("update.downloading", "Downloading: {url}"),
+  ("update.parsing", "Parsing cheatsheets..."),
+  ("update.languages", "Filtering languages: {languages}"),
+  ("update.parsed", "Parsed {count} commands"),
+  ("update.saving", "Saving to database..."),
+  ("update.done", "Update complete! Version: {version}"),
+  // rtfm import
+  ("import.not_found", "Path does not exist: {path}"),
+  ("import.none", "No valid Markdown files found."),
+  ("import.format", "Files must follow the tldr-pages format:"),
+  ("import.see", "See: {url}"),
+  ("import.importing", "Importing {count} commands..."),
+  ("import.skipped", "  (skipped {count} files without valid tldr format)"),
+  ("import.done", "Import complete! {count} commands imported."),
+  // 查询命令
+  ("common.no_database", "Database not found. Run 'rtfm update' first."),
+  ("query.no_results", "No results for '{query}'."),
+  ("query.did_you_mean", "Did you mean: {names}?"),
+  ("query.try_update", "Try 'rtfm update' to download the latest cheatsheets."),
+  ("query.found", "Found {count} results for '{query}':"),
+  ("query.view_details", "Use {command} to view details."),
+  ("query.copy_ambiguous", "Nothing copied: '{query}' matched several commands."),
+  ("query.fill_ambiguous", "Nothing to fill in: '{query}' matched several commands."),
+  ("query.platform_fallback", "Showing the '{shown}' page: no '{platform}' page was imported (run 'rtfm update --force' after changing [commands])"),
+  ("query.example_out_of_range", "No example {index}: '{name}' has {count} example(s)"),
+  ("query.copied", "Copied example {index} ({method}):"),
+  ("search.unknown_format", "Unknown format '{format}' (use text, alfred, raycast or rofi)"),
+  ("embed.no_index", "No embeddings index found. Run 'rtfm embed rebuild' first."),
+  // rtfm learn
+  ("common.warning", "Warning:"),
+  ("learn.exists", "Command '{command}' already learned. Use --force to re-learn."),
+  ("learn.learning", "Learning '{command}'..."),
+  ("learn.fetched", "Fetched {bytes} bytes from {source}"),
+  ("learn.read", "Read {bytes} bytes from {source}"),
+  ("learn.got", "Got {bytes} bytes from {source}"),
+  ("learn.subcommands.found", "Found {count} subcommands of '{tool}'"),
+  ("learn.subcommands.done", "Subcommands: {learned} learned, {skipped} skipped, {failed} failed"),
+  ("learn.refresh.checking", "Checking {count} learned commands..."),
+  ("learn.refresh.unknown_version", "unknown version"),
+  ("learn.refresh.missing", "{name}: binary not found"),
+  ("learn.refresh.failed", "{name}: failed to get help"),
+  ("learn.refresh.done", "Refresh complete: {refreshed} re-learned, {unchanged} up to date, {missing} missing, {skipped} skipped, {failed} failed"),
+  ("learn.llm.summarizing", "Summarizing with {model}..."),
+  ("learn.llm.added", "Added {count} AI-generated examples"),
+  ("learn.plugin_failed", "Parser plugin '{plugin}' failed for '{command}', using built-in parser: {error}"),
+  ("learn.llm.failed", "LLM summarization failed: {error}"),
+  ("learn.saved", "Saved to database"),
+  ("learn.indexed", "Indexed for search"),
+  ("learn.success", "Learned '{command}' successfully!"),
+  ("learn.low_quality", "Low quality ({score}/100): {issues}"),
+  ("learn.try_better", "Try --man, --url or --llm for better results."),
+  ("learn.try_command", "Try: rtfm {command}"),
+  // rtfm config
+  ("config.validating", "Validating {path}"),
+  ("config.read_failed", "Failed to read {path}: {error}"),
+  ("config.no_file", "No config file found, checking defaults and environment overrides."),
+  ("config.looked_for", "  (looked for {path})"),
+  ("config.error", "error:"),
+  ("config.warning", "warning:"),
+  ("config.invalid", "Config has {errors} error(s) and {warnings} warning(s)"),
+  ("config.valid", "Config is valid."),
+  ("config.valid_with_warnings", "Config is valid with {count} warning(s)."),
+  // rtfm config path
+  ("config.paths", "Config files, in order of precedence:"),
+  ("config.in_use", "(in use)"),
+  ("config.ignored", "(found, ignored)"),
+  ("config.not_found", "(not found)"),
+  ("config.defaults", "No config file found, using built-in defaults."),
+  ("config.data_dir", "Data directory: {path}"),
+  // rtfm stats / usage
+  ("stats.commands", "Commands:"),
+  ("stats.categories", "Categories:"),
+  ("stats.data_version", "Data version: {version} (updated {updated})"),
+  ("stats.most_used", "Most used:"),
+  ("stats.usage_dashboard", "Run 'rtfm stats --dashboard' for more usage statistics."),
+  ("stats.quality", "Learned quality:"),
+  ("stats.quality_counts", "{good} good, {low} low (score < {threshold})"),
+  ("stats.list_low_quality", "Run 'rtfm stats --low-quality' to list them."),
+  ("usage.empty", "No usage recorded yet. Lookups and searches are counted as you use rtfm."),
+  ("usage.recording_off", "Recording is off (search.record_usage = false)."),
+  ("usage.lookups", "Lookups:"),
+  ("usage.searches", "Searches:"),
+  ("usage.zero_results", "({count} with no results, {percent}%)"),
+  ("usage.top_lookups", "Top lookups:"),
+  ("usage.trending", "Trending (last {days} days):"),
+  ("usage.no_result_searches", "Searches with no results:"),
+  ("usage.gaps", "Learning gaps"),
+  ("usage.gaps_hint", "(installed, searched for, not documented):"),
+  ("usage.learn_gap", "Learn one with: {command}"),
+  ("usage.cleared", "Usage statistics cleared."),
+  // rtfm learn 失败时的说明
+  ("learn.error.failed", "Failed to learn '{command}'."),
+  ("learn.error.not_found", "Command '{command}' not found on this system."),
+  ("learn.error.reasons", "Possible reasons:\n  - The command is not installed\n  - The command is not in your PATH\n  - The command name is misspelled"),
+  ("learn.error.install_first", "  - Install the command first, then run: rtfm learn {command}"),
+  ("learn.error.not_in_path", "The command is not installed or not in PATH."),
+  ("learn.error.try_man", "  - Use 'rtfm learn {command} --man' to check if man page exists"),
+  ("learn.error.no_man", "Could not get help for '{command}', and 'man' is not available."),
+  ("learn.error.windows_no_man", "On Windows, 'man' pages are not available by default.\nThe command '{command}' exists but --help didn't provide usable output."),
+  ("learn.error.alternatives", "Alternatives:"),
+  ("learn.error.help_flag", "  - Check if '{command}' supports a different help flag (e.g., /?, -h)"),
+  ("learn.error.install_man", "  - Install man-db package (e.g., apt install man-db)"),
+  ("learn.error.unusable", "The command exists but didn't provide usable help output."),
+  ("learn.error.run_help", "  - Run '{command} --help' manually to check the output"),
+  // rtfm learn-all / suggest-learn
+  ("learn_all.no_checkpoint", "No interrupted learn-all run found. Run 'rtfm learn-all' first."),
+  ("learn_all.nothing_to_retry", "No failed commands to retry."),
+  ("learn_all.retrying", "Retrying {count} failed commands (source: {source})"),
+  ("learn_all.source", "Source: {source}"),
+  ("learn_all.listing_man", "Listing man pages in section {section}..."),
+  ("learn_all.reading_manpath", "Reading man page sources in section {section}..."),
+  ("learn_all.reading_history", "Reading shell history..."),
+  ("learn_all.unknown_source", "Unknown source '{source}'. Use 'man', 'manpath', 'powershell', 'path', 'builtin', 'history', or 'auto'."),
+  ("learn_all.none", "No commands found."),
+  ("learn_all.found", "Found {count} commands"),
+  ("learn_all.prefix", "Filtered to {count} commands with prefix '{prefix}'"),
+  ("learn_all.excluded", "Excluded {count} commands by [learn] allow/deny lists"),
+  ("learn_all.limit", "Limiting to {count} commands"),
+  ("learn_all.resuming", "Resuming: {processed} already processed, {remaining} remaining"),
+  ("learn_all.done", "Done!"),
+  ("learn_all.learned", "  Learned: {count}"),
+  ("learn_all.low_quality", "  Low quality: {count} (see 'rtfm stats --low-quality')"),
+  ("learn_all.skipped", "  Skipped: {count} (already exist)"),
+  ("learn_all.unlearnable", "  Skipped: {count} {reason} ({names})"),
+  ("learn_all.failed", "  Failed:  {count}"),
+  ("learn_all.retry_hint", "{count} commands failed. Retry them with: rtfm learn-all --retry-failed"),
+  ("learn_all.total", "Total commands in database: {count}"),
+  ("learn_all.man_sections", "Man sections:\n  1 - User commands\n  2 - System calls\n  3 - Library functions\n  4 - Special files\n  5 - File formats\n  6 - Games\n  7 - Miscellaneous\n  8 - System administration"),
+  ("learn_all.powershell_hint", "  rtfm learn-all --source path  # Learn from PATH executables\n  rtfm learn cargo              # Learn a specific command"),
+  ("learn_all.sources", "Available sources:"),
+  ("learn_all.sources.windows", "  --source powershell  # PowerShell cmdlets (default on Windows)\n  --source path        # Executables in PATH"),
+  ("learn_all.sources.unix", "  --source man         # Man pages (default on Linux/macOS)\n  --source manpath     # Man page sources in MANPATH (no man needed)\n  --source path        # Executables in PATH\n  --source builtin     # Shell builtins (bash/zsh)"),
+  ("suggest.none", "No suggestions: frequently used commands are already in the database."),
+  ("suggest.title", "Frequently used commands without a cheatsheet:"),
+  ("suggest.used", "used {count} times"),
+  ("suggest.confirm", "Learn these {count} commands? [y/N]"),
+  // 跳过的原因（learn::Unlearnable）
+  ("learn_all.reason.interactive", "interactive"),
+  ("learn_all.reason.gui", "GUI"),
+  // rtfm backup / restore
+  ("backup.no_data", "No data found. Run 'rtfm update' or 'rtfm learn' first."),
+  ("backup.backing_up", "Backing up data from {path}..."),
+  ("backup.added", "  Added {entry}"),
+  ("backup.user_data", "  {commands} learned commands, {notes} notes, {favorites} favorites, {aliases} aliases"),
+  ("backup.done", "Backup complete!"),
+  ("backup.output", "  Output: {path}"),
+  ("backup.size", "  Size:   {bytes} bytes ({mb} MB)"),
+  ("backup.restore_hint", "To restore on another machine:"),
+  ("restore.not_found", "Backup archive not found: {path}"),
+  ("restore.restoring", "Restoring from {path}..."),
+  ("restore.backup_db", "  Backing up existing database to {path}"),
+  ("restore.backup_index", "  Backing up existing index to {path}"),
+  ("restore.extracting", "  Extracting files..."),
+  ("restore.no_database", "Not an rtfm backup: no database found in {path}"),
+  ("restore.merge_index", "--merge updates the index from the merged commands; use --only db or omit --only"),
+  ("restore.merged", "  Merged {count} commands into the existing database and updated the index"),
+  ("restore.rebuilding_index", "  Rebuilding the search index..."),
+  ("restore.done", "Restore complete!"),
+  ("restore.commands", "  Commands: {count}"),
+  ("restore.config", "  Config:   restored to {path}"),
+  ("restore.user_data_only", "This backup only holds user data, which is always merged; omit --only"),
+  // rtfm sync
+  ("sync.no_remote", "No remote given: use --remote or set sync.remote in the config"),
+  ("sync.unknown_policy", "Unknown sync policy '{policy}' (use lww or merge)"),
+  ("sync.comparing", "Comparing with {remote}..."),
+  ("sync.up_to_date", "Already up to date."),
+  ("sync.fetching", "Fetching {count} command(s)..."),
+  ("sync.remote_up_to_date", "Remote is already up to date."),
+  ("sync.pushing", "Pushing {count} command(s)..."),
+  ("sync.done", "Sync complete!"),
+  ("sync.counts", "{added} added, {updated} updated, {kept} kept"),
+  ("sync.personal_counts", "{favorites} favorite(s) added, {notes} note(s) added or changed"),
+  ("sync.kept_local", "{count} command(s) kept the local version (newer, or nothing to merge)"),
+  ("sync.kept_remote", "{count} command(s) kept the remote version (newer, or nothing to merge)"),
+  // rtfm export-cards
+  ("cards.unknown_format", "Unknown card format '{format}' (use anki or csv)"),
+  ("cards.none", "No examples matched the filters"),
+  ("cards.exported", "Exported {cards} cards from {commands} commands"),
+  ("cards.exported_to", "Exported {cards} cards from {commands} commands to {path}"),
+  ("cards.anki_hint", "Import it in Anki with File > Import."),
+  // rtfm embed
+  ("embed.no_commands", "No commands in language(s) {langs}"),
+  ("embed.ignoring_index", "Ignoring existing index: {error}"),
+  ("embed.embedding", "Embedding {count} commands ({langs}) with '{model}' via {endpoint}"),
+  ("embed.embedding_local", "Embedding {count} commands ({langs}) with local model '{model}' (downloaded on first use)"),
+  ("embed.no_onnxruntime", "Cannot load ONNX Runtime from {path}: {error}\nInstall ONNX Runtime 1.20 or newer, set ORT_DYLIB_PATH to the library, or set embeddings.provider = \"http\"."),
+  ("embed.progress", "Embedding..."),
+  ("embed.ready", "Embeddings index ready!"),
+  ("embed.ready_counts", "{embedded} embedded, {reused} unchanged ({dim} dimensions)"),
+  ("embed.search_hint", "Search with: rtfm search --semantic <query>"),
+  ("embed.status.index", "Index: {path}"),
+  ("embed.status.not_built", "Not built yet. Run 'rtfm embed rebuild'."),
+  ("embed.status.model", "Model: {model}"),
+  ("embed.status.model_changed", "(embeddings.model is now '{model}', rebuild needed)"),
+  ("embed.status.commands", "Commands: {count} ({dim} dimensions)"),
+  ("embed.status.size", "Size: {mb} MB"),
+  ("embed.status.stale", "{count} command(s) are new or changed since the last rebuild"),
+  ("embed.status.up_to_date", "Up to date."),
+  // rtfm render
+  ("render.not_found", "No command named '{name}'"),
+  ("render.no_home", "No home directory; pass --dir"),
+  ("render.installed", "Installed {count} man pages in {path} ({documented} commands already have one)"),
+  ("render.kept", "Kept {count} existing pages that were not generated by rtfm"),
+  ("render.manpath_hint", "Try 'man <command>'. If man doesn't find the pages, add the directory to MANPATH:"),
+  // rtfm diff
+  ("diff.no_local", "'{name}' has not been learned. Learn it first: rtfm learn {name}"),
+  ("diff.no_tldr", "No tldr page for '{name}' in language '{lang}'"),
+  ("diff.examples", "Examples"),
+  ("diff.options", "Options"),
+  ("diff.tldr", "tldr ({lang})"),
+  ("diff.local", "learned ({when})"),
+  ("diff.same", "No differences."),
+  ("diff.undocumented", "Options in the local help that the tldr page doesn't mention ({count}):"),
+  ("diff.missing", "Options on the tldr page that the local help doesn't mention ({count}):"),
+  // rtfm user
+  ("user.invalid_name", "'{name}' is not a valid user name (use letters, digits, - and _)"),
+  ("user.exists", "User '{name}' already exists. Use 'rtfm user token {name}' for a new token."),
+  ("user.not_found_add", "No user '{name}'. Add one with 'rtfm user add {name}'."),
+  ("user.not_found", "No user '{name}'"),
+  ("user.saved", "User '{name}' saved to {path}"),
+  ("user.token", "API token (shown only once):"),
+  ("user.token_usage", "Send it as: Authorization: Bearer <token>"),
+  ("user.multi_user_off", "server.multi_user is off; tokens are only checked once it is enabled."),
+  ("user.none", "No users. Add one with 'rtfm user add <name>'."),
+  ("user.learned", "{count} learned"),
+  ("user.multi_user_on", "Multi-user mode: on"),
+  ("user.multi_user_disabled", "Multi-user mode: off (server.multi_user = false)"),
+  ("user.removed", "Removed user '{name}' from {path}"),
+  ("user.deleted", "Deleted {path}"),
+  ("user.data_kept", "Their data is kept in {path} (use --purge to delete it)"),
+  // rtfm plugin
+  ("plugin.dir", "Plugins directory: {path}"),
+  ("plugin.none", "No plugins installed. Add a directory containing {manifest} to install one."),
+  ("plugin.disabled", "disabled"),
+  ("plugin.parses", "parses: {commands}"),
+  ("plugin.fetching", "Fetching commands from plugin '{name}'..."),
+  ("plugin.no_commands", "Plugin returned no commands."),
+  ("plugin.imported", "Imported {count} command(s) from '{name}'"),
+  // rtfm reset
+  ("reset.no_dir", "No data directory found. Nothing to reset."),
+  ("reset.no_data", "No data found. Nothing to reset."),
+  ("reset.warning", "Warning: This will delete all RTFM data!"),
+  ("reset.will_delete", "The following will be deleted:"),
+  ("reset.item.database", "  - {name} (command database)"),
+  ("reset.item.index", "  - {name}/ (search index)"),
+  ("reset.item.embeddings", "  - {name} (semantic search index)"),
+  ("reset.item.config", "  - config.toml (local configuration)"),
+  ("reset.confirm", "Are you sure you want to continue? [y/N]"),
+  ("reset.aborted", "Aborted."),
+  ("reset.resetting", "Resetting..."),
+  ("reset.deleted", "  Deleted {name}"),
+  ("reset.done", "Reset complete!"),
+  ("reset.factory_state", "All data has been deleted. RTFM is now in factory state."),
+  ("reset.start_fresh", "To start fresh, run:\n  rtfm update    # Download cheatsheets from tldr\n  rtfm learn ls  # Learn from local commands"),
+  // rtfm import
+  ("import.parse_failed", "Failed to parse archive: {error}"),
+  // rtfm embed
+  ("embed.model_mismatch", "The embeddings index was built with '{built}' but embeddings.model is '{model}'. Run 'rtfm embed rebuild'."),
+  // rtfm audit
+  ("audit.empty", "No data modifications recorded yet."),
+  ("audit.commands", "{count} commands"),
+  // rtfm export
+  (
+    "cli.export",
+    "Export commands as navi cheatsheets, JSON, Markdown or tldr pages",
+  ),
+  ("export.unknown_format", "Unknown export format '{format}' (use navi, json, markdown or tldr)"),
+  ("export.exported", "Exported {commands} commands"),
+  ("export.user_data", "Exported {commands} learned commands, {notes} notes, {favorites} favorites and {aliases} aliases; restore them with rtfm restore"),
+  ("export.written", "Exported {commands} commands to {files} files in {path}"),
+  ("export.navi_hint", "Use them with: navi --path {path}"),
+  ("export.none", "No commands matched the filters"),
+  ("export.exported_to", "Exported {commands} commands to {path}"),
+  (
+    "export.tldr_hint",
+    "Import them again with 'rtfm import {path}'. To contribute pages, copy them into a tldr-pages checkout (learned commands are under pages.local/)",
+  ),
+  // rtfm bench
+  ("cli.bench", "Measure indexing, search, database and parsing speed on the local data"),
+  ("bench.running", "Benchmarking: {stage}..."),
+  ("bench.dataset", "Dataset: {commands} commands, {mb} MB of content"),
+  ("bench.database", "Database"),
+  ("bench.scan", "full scan"),
+  ("bench.lookup", "lookup by name"),
+  ("bench.index", "Index"),
+  ("bench.build", "build"),
+  ("bench.open", "open"),
+  ("bench.search", "Search ({queries} queries)"),
+  ("bench.cold", "cold"),
+  ("bench.warm", "warm"),
+  ("bench.learn", "Learn parsing"),
+  ("bench.pages", "{pages} pages"),
+  ("bench.no_learned", "No learned commands to parse"),
+  // rtfm delete
+  ("cli.delete", "Delete a command from the database and search index"),
+  ("delete.not_found", "Command '{name}' not found"),
+  ("delete.done", "Deleted '{name}' ({langs})"),
+  // fuzzy search
+  ("tui.status.found_fuzzy", "No exact matches; found {count} similar results ({ms}ms)"),
+  ("query.try_fuzzy", "Try --fuzzy to tolerate typos."),
+  // rtfm fav
+  ("fav.added", "Added {name} to favorites"),
+  ("fav.already", "{name} is already a favorite"),
+  ("fav.not_found", "Command not found: {name}"),
+  ("fav.removed", "Removed {name} from favorites"),
+  ("fav.not_favorite", "{name} is not a favorite"),
+  ("fav.none", "No favorites yet. Add one with 'rtfm fav add <name>' or press f on a result in the TUI"),
+  ("fav.missing", "(not in the database)"),
+  // favorites (TUI)
+  ("tui.status.favorites", "{count} favorites"),
+  ("tui.status.favorite_added", "Added {name} to favorites"),
+  ("tui.status.favorite_removed", "Removed {name} from favorites"),
+  ("tui.status.favorite_failed", "Failed to update favorites: {error}"),
+  ("tui.no_favorites", "No favorites yet. Press {key} on a result to add it"),
+  // history
+  ("cli.history", "Show recently viewed commands"),
+  ("history.empty", "No commands viewed yet"),
+  ("history.cleared", "History cleared"),
+  ("tui.action.history", "Show/close recently viewed commands"),
+  ("tui.history.border", " History "),
+  ("tui.history.empty", "No commands viewed yet"),
+  ("tui.frequently_used", "Frequently used"),
+  ("tui.status.recent_frequent", "{count} recently viewed, {frequent} frequently used"),
+  ("tui.status.recent", "{count} recently viewed"),
+  // picker
+  ("picker.hint", "↑↓ select, Enter show, type letters to filter or a number to jump, Esc cancel"),
+  ("picker.filter", "Filter: {filter}  (Backspace to edit, Esc to clear)"),
+  ("picker.number", "Number: {number}  (Enter to show)"),
+  ("picker.no_match", "No results match the filter"),
+  // reindex
+  ("cli.reindex", "Rebuild the search index from the database"),
+  ("reindex.done", "Indexed {count} commands"),
+  // update from file
+  ("update.from_file", "Reading archive: {path}"),
+  (
+    "update.cheat_done",
+    "Imported {count} cheat sheets ({merged} merged into existing pages, {removed} removed)",
+  ),
+  ("stats.data_source", "Data source: {source}"),
+  // language switch (TUI)
+  ("tui.action.cycle_lang", "Show the selected command in its next language"),
+  ("tui.status.lang", "{name}: {lang} ({langs})"),
+  ("tui.status.one_lang", "{name} is only available in one language"),
+  // import
+  ("import.watch_not_dir", "--watch needs a directory: {path}"),
+  ("import.docset_not_dir", "A docset is a directory (name.docset): {path}"),
+  ("import.docset_failed", "Failed to read docset: {error}"),
+  ("import.watching", "Watching {path} for new or changed .md files (Ctrl+C to stop)..."),
+  ("import.watch_imported", "Imported {count} commands: {names}"),
+  ("import.watch_failed", "Import failed: {error}"),
+  // rtfm alias
+  ("alias.added", "Added alias {name} → {target}"),
+  ("alias.updated", "Alias {name} now points to {target} (was {previous})"),
+  ("alias.invalid", "Alias name and command must not be empty"),
+  ("alias.self", "An alias cannot point to itself: {name}"),
+  ("alias.target_missing", "Note: {target} is not in the database yet"),
+  ("alias.removed", "Removed alias {name}"),
+  ("alias.not_found", "No such alias: {name}"),
+  ("alias.none", "No aliases yet. Add one with 'rtfm alias add <name> <command>' or run 'rtfm alias import'"),
+  ("alias.imported", "Imported {count} aliases"),
+  ("alias.import_none", "No new aliases found in shell config files or git config"),
+  ("alias.resolved", "{name} is an alias for {target}"),
+  // mouse (TUI)
+  ("tui.status.copied", "Copied the selected text ({method})"),
+  ("tui.status.copy_failed", "Copy failed: {error}"),
+  // options section
+  ("tui.options", "Options"),
+  // placeholder fill mode
+  ("fill.hint", "Fill in the placeholders (leave empty to keep the placeholder text):"),
+  ("tui.fill.pick", " Fill in an example "),
+  ("tui.fill.input", " Fill in {name} "),
+  ("tui.fill.pick_hint", "{open}: fill in  {back}: close"),
+  ("tui.fill.input_hint", "{open}: next placeholder  {back}: close  (empty keeps the placeholder)"),
+  ("tui.fill.no_examples", "{name} has no examples"),
+  ("tui.status.filled", "Copied ({method}): {command}"),
+  // rtfm note
+  (
+    "cli.note",
+    "Write personal notes for a command in $EDITOR; they are shown with the page and searchable",
+  ),
+  ("note.not_found", "Command not found: {name}"),
+  ("note.none", "No notes yet. Add one with 'rtfm note <name>' or press n on a result in the TUI"),
+  ("note.empty", "{name} has no notes"),
+  ("note.saved", "Saved the note for {name}"),
+  ("note.cleared", "Deleted the note for {name}"),
+  ("note.unchanged", "The note for {name} is unchanged"),
+  ("note.editor_failed", "Failed to start the editor {editor}: {error}"),
+  ("note.editor_exit", "The editor {editor} exited with {status}; the note was not saved"),
+  ("notes.title", "Notes"),
+  ("tui.action.notes", "Edit the notes of the selected command in $EDITOR"),
+  ("tui.status.note_saved", "Saved the note for {name}"),
+  ("tui.status.note_failed", "Failed to save the note: {error}"),
+  ("tui.status.filters", "filters: {filters}"),
+  // tldr client compatibility
+  (
+    "tldr.not_found",
+    "This page doesn't exist yet: {name}. Run 'rtfm update' to get the latest pages or 'rtfm learn {name}' to learn it from --help",
+  ),
+  ("tldr.other_platform", "Showing the {shown} page: there is no {platform} or common page"),
+  // TUI example navigation
+  ("tui.action.next_example", "Select the next example in the details"),
+  ("tui.action.prev_example", "Select the previous example in the details"),
+  ("tui.action.copy_example", "Copy the selected example"),
+  ("tui.status.example", "Example {index}/{count}: {description}"),
+  // TUI run example
+  ("tui.action.run_example", "Run the selected example after confirming (tui.allow_execute)"),
+  ("tui.run.disabled", "Running examples is off; set allow_execute = true in [tui] to enable it"),
+  ("tui.run.title", "Run example"),
+  ("tui.run.confirm", "Run this command in a shell?"),
+  ("tui.run.confirm_hint", "y: run  n / {back}: cancel"),
+  ("tui.run.running", "Running..."),
+  ("tui.run.stop_hint", "{back}: stop"),
+  ("tui.run.output_hint", "↑↓: scroll  {back}: close"),
+  ("tui.run.exit", "exit code {code}"),
+  ("tui.run.killed", "terminated by a signal"),
+  ("tui.run.failed", "failed to start: {error}"),
+  ("tui.run.output_limit", "stopped after {kb} KB of output"),
+  ("tui.run.timed_out", "stopped after {secs}s"),
+];
@@ -0,0 +1,212 @@
+//! 界面文字的多语言支持（CLI 与 TUI）
+//!
+//! 每种语言一张消息表（en.rs、zh.rs），按键查找，缺少的键回退到英文。
+//! 语言由 ui.locale 选择（auto、en、zh），auto 时按系统语言（LANGUAGE、LC_ALL、LC_MESSAGES、LANG，
+//! Windows 上为用户界面语言）判断。带参数的消息用 `{name}` 占位，通过 `t!` 宏替换：
+//!
+//! ```ignore
+//! println!("{}", t!("update.checking"));
+//! println!("{}", t!("update.done", version = info.tag_name));
+//! ```
+
+mod en;
+mod zh;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use once_cell::sync::Lazy;
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+  En,
+  Zh,
+}
+
+impl Locale {
+  /// 解析 ui.locale；auto 及无法识别的值按系统语言判断
+  pub fn resolve(setting: &str) -> Self {
+    match setting {
+      "en" => Locale::En,
+      "zh" => Locale::Zh,
+      _ => sys_locale::get_locale()
+        .map(|tag| Self::from_tag(&tag))
+        .unwrap_or(Locale::En),
+    }
+  }
+
+  /// 由语言标签（zh-CN、zh_TW.UTF-8、en-US 等）得到界面语言
+  fn from_tag(tag: &str) -> Self {
+    if tag.to_ascii_lowercase().starts_with("zh") {
+      Locale::Zh
+    } else {
+      Locale::En
+    }
+  }
+
+  fn messages(self) -> &'static HashMap<&'static str, &'static str> {
+    static EN: Lazy<HashMap<&str, &str>> = Lazy::new(|| en::MESSAGES.iter().copied().collect());
+    static ZH: Lazy<HashMap<&str, &str>> = Lazy::new(|| zh::MESSAGES.iter().copied().collect());
+    match self {
+      Locale::En => &EN,
+      Locale::Zh => &ZH,
+    }
+  }
+}
+
+/// 当前语言；启动时由 init 设置一次
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+/// 按配置设置界面语言
+pub fn init(config: &crate::config::UiConfig) {
+  set_locale(Locale::resolve(&config.locale));
+}
+
+pub fn set_locale(locale: Locale) {
+  LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+  match LOCALE.load(Ordering::Relaxed) {
+    1 => Locale::Zh,
+    _ => Locale::En,
+  }
+}
+
+/// 当前语言的消息，缺少时使用英文
+pub fn lookup(key: &str) -> Option<&'static str> {
+  locale()
+    .messages()
+    .get(key)
+    .or_else(|| Locale::En.messages().get(key))
+    .copied()
+}
+
+/// 当前语言的消息；两种语言都没有时返回键本身
+pub fn tr(key: &'static str) -> &'static str {
+  lookup(key).unwrap_or(key)
+}
+
+/// 当前语言的消息，替换其中的 `{name}` 占位符
+pub fn format(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+  let mut message = tr(key).to_string();
+  for (name, value) in args {
+    message = message.replace(&format!("{{{}}}", name), &value.to_string());
+  }
+  message
+}
+
+/// 查找界面文字：`t!("key")` 返回 `&'static str`，`t!("key", name = value, ..)` 返回替换占位符后的 String
+macro_rules! t {
+  ($key:literal) => {
+    $crate::i18n::tr($key)
+  };
+  ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+    $crate::i18n::format($key, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+])
+  };
+}
+
+pub(crate) use t;
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeSet;
+  use std::path::Path;
+
+  use super::*;
+
+  /// 消息中的占位符名
+  fn placeholders(message: &str) -> BTreeSet<&str> {
+    message
+      .split('{')
+      .skip(1)
+      .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+      .filter(|name| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_catalogs_match() {
+    for (name, messages) in [("en", en::MESSAGES), ("zh", zh::MESSAGES)] {
+      let keys: BTreeSet<&str> = messages.iter().map(|(key, _)| *key).collect();
+      assert_eq!(keys.len(), messages.len(), "duplicate keys in {}", name);
+    }
+
+    let zh: HashMap<&str, &str> = zh::MESSAGES.iter().copied().collect();
+    for (key, message) in en::MESSAGES {
+      let translated = zh
+        .get(key)
+        .unwrap_or_else(|| panic!("zh is missing {}", key));
+      assert_eq!(
+        placeholders(message),
+        placeholders(translated),
+        "placeholders differ for {}",
+        key
+      );
+    }
+    assert_eq!(zh.len(), en::MESSAGES.len(), "zh has keys that en lacks");
+  }
+
+  /// 源码中 t! 使用的键都在英文表中
+  #[test]
+  fn test_keys_exist() {
+    fn visit(dir: &Path, keys: &mut Vec<String>) {
+      for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+          // 跳过本模块的文档和测试
+          if !path.ends_with("i18n") {
+            visit(&path, keys);
+          }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+          let source = std::fs::read_to_string(&path).unwrap();
+          for (start, _) in source.match_indices("t!(") {
+            // 排除 format!( 、assert!( 等
+            let ident = source[..start]
+              .chars()
+              .next_back()
+              .is_some_and(|c| c.is_alphanumeric() || c == '_');
+            let rest = source[start + 3..].trim_start();
+            if let (false, Some(rest)) = (ident, rest.strip_prefix('"')) {
+              keys.push(rest.split('"').next().unwrap().to_string());
+            }
+          }
+        }
+      }
+    }
+
+    let mut keys = Vec::new();
+    visit(
+      &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+      &mut keys,
+    );
+    assert!(!keys.is_empty());
+    let messages = Locale::En.messages();
+    for key in &keys {
+      assert!(
+        messages.contains_key(key.as_str()),
+        "unknown message key {}",
+        key
+      );
+    }
+  }
+
+  #[test]
+  fn test_translate() {
+    assert_eq!(Locale::from_tag("zh_CN.UTF-8"), Locale::Zh);
+    assert_eq!(Locale::from_tag("zh-Hant-TW"), Locale::Zh);
+    assert_eq!(Locale::from_tag("en-US"), Locale::En);
+    assert_eq!(Locale::resolve("zh"), Locale::Zh);
+
+    set_locale(Locale::Zh);
+    assert_eq!(t!("tui.no_results"), "没有找到结果");
+    assert_eq!(t!("tui.status.total", count = 42), "共 42 条命令");
+    assert_eq!(t!("no.such.key"), "no.such.key");
+    set_locale(Locale::En);
+    assert_eq!(t!("tui.status.total", count = 42), "42 commands total");
+  }
+}
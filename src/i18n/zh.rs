@@ -0,0 +1,645 @@
+//! 中文消息
+
+pub const MESSAGES: &[(&str, &str)] = &[
+  // TUI
+  ("tui.searching", "搜索中..."),
+  ("tui.no_results", "没有找到结果"),
+  ("tui.did_you_mean", "你是不是要找：{names}？"),
+  ("tui.command_not_found", "找不到命令：{name}"),
+  ("tui.learned_from", "学习自 {version}"),
+  ("tui.learned_outdated", "⚠ 学习之后本机的程序已更新，运行 rtfm learn --refresh 重新学习"),
+  ("tui.from_local_help", "来自本机帮助"),
+  ("tui.matching_example", "示例 {index}："),
+  ("tui.invalid_keys", "[tui.keys] 配置有误"),
+  ("tui.status.total", "共 {count} 条命令"),
+  ("tui.status.found", "找到 {count} 条结果（{ms}ms）"),
+  ("tui.status.search_failed", "搜索失败：{error}"),
+  ("tui.status.save_style_failed", "保存界面风格失败：{error}"),
+  ("tui.classic.search_title", " 搜索（/ 聚焦） "),
+  ("tui.classic.search_placeholder", "输入以搜索命令..."),
+  (
+    "tui.classic.search_hints",
+    " [{switch}] 切换  [{help}] 帮助  [{back}] 返回/退出",
+  ),
+  ("tui.classic.results_title", " 结果 "),
+  ("tui.classic.results_count", " 结果（{count}） "),
+  ("tui.classic.type_to_search", "输入以搜索"),
+  ("tui.classic.details_title", " 详情 "),
+  ("tui.classic.select_command", "选择一条命令查看详情"),
+  ("tui.classic.logs_title", " 调试日志 [{key} 关闭] "),
+  (
+    "tui.classic.status_hints",
+    "[{up}/{down}] 导航  [{open}] 查看 ",
+  ),
+  ("tui.modern.search_title", " 搜索 "),
+  (
+    "tui.modern.search_placeholder",
+    "输入以搜索...（↑↓ 切换结果）",
+  ),
+  ("tui.modern.search_hints", " [{help}] 帮助  [{back}] 退出 "),
+  ("tui.modern.result_title", " 结果 "),
+  ("tui.modern.result_position", " 结果 [{index}/{count}] "),
+  ("tui.modern.start_typing", "输入关键字开始搜索命令..."),
+  ("tui.modern.tips", "提示：用 ↑↓ 或 j/k 切换结果"),
+  (
+    "tui.modern.try_update",
+    "换个关键字试试，或运行 'rtfm update'",
+  ),
+  (
+    "tui.modern.result_hints",
+    "{up}{down} 滚动  {prev}{next} 切换结果  {search} 搜索  {help} 帮助",
+  ),
+  ("tui.modern.logs_title", " 日志 [{key} 关闭] "),
+  ("tui.help.title", "快捷键"),
+  ("tui.help.border", " 帮助 "),
+  ("tui.help.footer", "可在 [tui.keys] 中修改按键"),
+  ("tui.action.quit", "强制退出"),
+  ("tui.action.help", "显示/隐藏帮助"),
+  (
+    "tui.action.toggle_logs",
+    "显示/隐藏调试日志（需要 --debug）",
+  ),
+  ("tui.action.toggle_style", "切换界面风格（Modern/Classic）"),
+  ("tui.action.clear_search", "清空搜索"),
+  ("tui.action.up", "上移 / 向上滚动"),
+  ("tui.action.down", "下移 / 向下滚动"),
+  (
+    "tui.action.prev",
+    "上一条结果（Modern）/ 返回列表（Classic）",
+  ),
+  (
+    "tui.action.next",
+    "下一条结果（Modern）/ 查看详情（Classic）",
+  ),
+  ("tui.action.page_up", "上一页"),
+  ("tui.action.page_down", "下一页"),
+  ("tui.action.top", "跳到第一条"),
+  ("tui.action.bottom", "跳到最后一条"),
+  ("tui.action.next_focus", "切换焦点"),
+  ("tui.action.search", "聚焦搜索框"),
+  ("tui.action.back", "清空 / 返回 / 退出"),
+  ("tui.action.open", "查看详情 / 填写示例"),
+  ("tui.action.toggle_favorite", "收藏 / 取消收藏"),
+  ("tui.action.favorites", "只显示收藏"),
+  // 更新提示
+  ("notice.dataset.title", "tldr pages {version} 已发布"),
+  (
+    "notice.dataset.body",
+    "当前版本：{current}。运行 'rtfm update' 下载。",
+  ),
+  ("notice.release.title", "rtfm {version} 已发布"),
+  (
+    "notice.release.body",
+    "当前版本：{current}。下载地址：{url}",
+  ),
+  // 命令行帮助中的命令说明（英文与 src/cli 中的文档注释一致）
+  ("cli", "Read The F***ing Manual - 命令行速查手册"),
+  ("cli.search", "搜索命令并输出结果，供脚本和桌面启动器使用"),
+  ("cli.serve", "启动 HTTP API 服务"),
+  ("cli.service", "以 Windows 服务运行 HTTP 服务"),
+  ("cli.service.install", "按当前数据目录和配置文件注册服务"),
+  ("cli.service.start", "启动服务"),
+  ("cli.service.stop", "停止服务"),
+  ("cli.service.uninstall", "停止并删除服务"),
+  (
+    "cli.install-service",
+    "生成为当前数据目录运行 'rtfm serve' 的 systemd unit",
+  ),
+  ("cli.update", "更新命令速查数据"),
+  (
+    "cli.import",
+    "从路径或网址导入 tldr 页面（.md、.zip、.tar、.tar.gz、.tgz 或目录）、navi 的 .cheat 文件、cheat 速查表、Dash/Zeal 文档集或 JSON 导出文件",
+  ),
+  ("cli.learn", "从 --help 或 man 页面学习命令"),
+  (
+    "cli.learn-all",
+    "从系统批量学习命令（man 页面、PowerShell 或 PATH）",
+  ),
+  (
+    "cli.suggest-learn",
+    "根据 shell 历史推荐常用但尚未收录的命令",
+  ),
+  ("cli.backup", "把全部数据（数据库、索引、配置）备份为归档"),
+  ("cli.restore", "从备份归档恢复数据"),
+  ("cli.stats", "显示数据库统计和学习内容的质量"),
+  ("cli.audit", "显示数据修改日志（导入、学习、更新、删除、重置、恢复、同步）"),
+  ("cli.export-cards", "把示例导出为 Anki 等间隔重复软件的卡片"),
+  (
+    "cli.render",
+    "把命令渲染为 man 页面，或为缺少 man 页面的命令安装页面",
+  ),
+  ("cli.diff", "比较学习的命令与 tldr 页面，找出页面中没有的选项"),
+  ("cli.reset", "清除全部数据（恢复出厂状态）"),
+  ("cli.config", "查看配置"),
+  (
+    "cli.config.validate",
+    "检查配置文件中的未知键、无效取值和相互冲突的设置",
+  ),
+  ("cli.config.path", "显示配置文件的查找位置和正在使用的文件"),
+  (
+    "cli.config.schema",
+    "输出配置文件的 JSON Schema（用于编辑器补全和校验）",
+  ),
+  ("cli.sync", "与远程 rtfm 服务交换命令、收藏和笔记"),
+  ("cli.sync.push", "发送远程缺少或版本不同的本地命令、收藏和笔记"),
+  ("cli.sync.pull", "获取本地缺少或版本不同的远程命令、收藏和笔记"),
+  ("cli.embed", "管理 --semantic 搜索使用的向量索引"),
+  (
+    "cli.embed.rebuild",
+    "为新增和变化的命令生成向量（未变化的保留原向量）",
+  ),
+  (
+    "cli.embed.status",
+    "显示向量索引的模型、大小以及建立后有多少命令发生变化",
+  ),
+  ("cli.plugin", "管理提供数据源、帮助解析器和渲染器的插件"),
+  ("cli.plugin.list", "列出插件目录中的插件"),
+  ("cli.plugin.fetch", "导入数据源插件提供的命令"),
+  (
+    "cli.user",
+    "管理多用户 HTTP 服务的用户（server.multi_user）",
+  ),
+  ("cli.user.add", "添加用户并输出其 API token"),
+  ("cli.user.list", "列出用户"),
+  (
+    "cli.user.token",
+    "为用户签发新的 API token（旧 token 失效）",
+  ),
+  ("cli.user.remove", "删除用户"),
+  ("cli.fav", "管理收藏的命令（TUI 中按 Ctrl+F 只显示收藏）"),
+  ("cli.fav.add", "收藏命令"),
+  ("cli.fav.remove", "取消收藏"),
+  ("cli.fav.list", "列出收藏的命令"),
+  ("cli.alias", "管理命令别名（如 gco 表示 git checkout），查找命令前先解析"),
+  ("cli.alias.add", "添加别名，或让已有的别名指向其他命令"),
+  ("cli.alias.remove", "删除别名"),
+  ("cli.alias.list", "列出别名"),
+  ("cli.alias.import", "从 shell 配置文件（bash、zsh、fish）和 git 配置导入别名"),
+  // rtfm serve
+  ("serve.listening", "RTFM HTTP 服务已启动：http://{addr}"),
+  ("serve.swagger", "Swagger UI：http://{addr}/swagger-ui"),
+  ("serve.cheatsheet", "速查表：http://{addr}/cheatsheet"),
+  ("serve.logs_journald", "日志：journald（journalctl -t rtfm）"),
+  ("serve.logs", "日志：{path}"),
+  ("serve.read_only", "只读模式：开启（导入、更新、学习、恢复和重置不可用）"),
+  ("serve.debug", "调试模式：开启（日志同时输出到控制台）"),
+  ("serve.press_ctrl_c", "按 Ctrl+C 停止"),
+  ("serve.stopped", "服务已正常停止"),
+  ("serve.detached", "RTFM 服务已在后台启动"),
+  ("serve.detached.address", "  地址：http://{addr}"),
+  ("serve.detached.swagger", "  Swagger：http://{addr}/swagger-ui"),
+  ("serve.detached.cheatsheet", "  速查表：http://{addr}/cheatsheet"),
+  ("serve.detached.logs", "  日志：{path}"),
+  ("serve.detached.read_only", "  只读：导入、更新、学习、恢复和重置不可用"),
+  ("serve.detached.stop", "状态：rtfm serve --status    停止：rtfm serve --stop"),
+  ("serve.detached.failed", "后台服务在启动时退出，请查看日志：{path}"),
+  ("serve.already_running", "后台服务已在运行（PID {pid}，http://{addr}），可用 'rtfm serve --stop' 停止"),
+  ("serve.service.enabled", "服务已在 http://{addr} 运行，重启后会自动启动"),
+  ("serve.service.command_failed", "无法启动服务，请运行以下命令："),
+  ("serve.service.enable_failed", "已写入服务文件，但 '{command}' 执行失败"),
+  ("serve.service.not_installed", "没有 rtfm serve --install-service 安装的服务"),
+  ("serve.service.removed", "已删除 {path}"),
+  ("serve.service.no_dir", "找不到存放服务文件的主目录"),
+  ("serve.status.running", "服务正在运行（PID {pid}）：http://{addr}"),
+  ("serve.status.unhealthy", "服务进程 {pid} 正在运行，但 http://{addr}/api/health 没有响应"),
+  ("serve.status.none", "没有后台服务（可用 'rtfm serve --detach' 启动）"),
+  ("serve.status.stale", "服务进程 {pid} 已不在运行，已删除过期的 PID 文件"),
+  ("serve.stopping", "正在停止服务（PID {pid}）..."),
+  ("serve.stop_timeout", "服务进程 {pid} 在 {secs} 秒内没有退出"),
+  // rtfm service / rtfm install-service
+  ("service.installed", "已安装服务 '{name}'"),
+  ("service.data_dir", "  数据目录：{path}"),
+  ("service.config_file", "  配置文件：{path}"),
+  ("service.address", "  地址：    http://{addr}"),
+  ("service.start_manual", "启动方法：rtfm service start"),
+  ("service.start_auto", "服务随 Windows 启动。立即启动：rtfm service start"),
+  ("service.started", "服务 '{name}' 已启动"),
+  ("service.stopping", "服务 '{name}' 正在停止"),
+  ("service.removed", "服务 '{name}' 已删除"),
+  ("install_service.no_dir", "找不到配置目录，请使用 --dir"),
+  ("install_service.wrote", "已写入 {path}"),
+  ("install_service.enable", "立即启动并随系统启动："),
+  ("install_service.linger", "退出登录后继续运行：loginctl enable-linger"),
+  // 共用
+  ("common.rebuilding_index", "正在重建搜索索引..."),
+  ("common.no_data", "还没有数据。请先运行 'rtfm update' 或 'rtfm learn <命令>'。"),
+  ("common.try", "可以尝试："),
+  ("common.details", "详细信息："),
+  ("common.try_update", "  - 运行 'rtfm update' 从 tldr-pages 下载速查数据"),
+  ("common.none", "  （无）"),
+  ("common.cancelled", "已取消。"),
+  // rtfm update
+  ("update.checking", "正在检查更新..."),
+  ("update.up_to_date", "已是最新版本：{version}"),
+  ("update.found", "发现新版本：{version}"),
+  ("update.no_download_url", "找不到下载地址"),
+  ("update.downloading", "正在下载：{url}"),
+  ("update.parsing", "正在解析速查数据..."),
+  ("update.languages", "只保留语言：{languages}"),
+  ("update.parsed", "解析了 {count} 条命令"),
+  ("update.saving", "正在保存到数据库..."),
+  ("update.done", "更新完成！版本：{version}"),
+  // rtfm import
+  ("import.not_found", "路径不存在：{path}"),
+  ("import.none", "没有找到有效的 Markdown 文件。"),
+  ("import.format", "文件必须符合 tldr-pages 格式："),
+  ("import.see", "参见：{url}"),
+  ("import.importing", "正在导入 {count} 条命令..."),
+  ("import.skipped", "  （跳过了 {count} 个不符合 tldr 格式的文件）"),
+  ("import.done", "导入完成！共导入 {count} 条命令。"),
+  // 查询命令
+  ("common.no_database", "找不到数据库。请先运行 'rtfm update'。"),
+  ("query.no_results", "没有找到 '{query}' 的结果。"),
+  ("query.did_you_mean", "你是不是要找：{names}？"),
+  ("query.try_update", "可以运行 'rtfm update' 下载最新的速查数据。"),
+  ("query.found", "'{query}' 共有 {count} 条结果："),
+  ("query.view_details", "使用 {command} 查看详情。"),
+  ("query.copy_ambiguous", "没有复制：'{query}' 匹配到多条命令。"),
+  ("query.fill_ambiguous", "无法填写：'{query}' 匹配到多条命令。"),
+  ("query.platform_fallback", "显示的是 '{shown}' 页面：没有导入 '{platform}' 页面（修改 [commands] 后请运行 'rtfm update --force'）"),
+  ("query.example_out_of_range", "没有示例 {index}：'{name}' 只有 {count} 个示例"),
+  ("query.copied", "已复制示例 {index}（{method}）："),
+  ("search.unknown_format", "未知的格式 '{format}'（可用 text、alfred、raycast 或 rofi）"),
+  ("embed.no_index", "找不到向量索引。请先运行 'rtfm embed rebuild'。"),
+  // rtfm learn
+  ("common.warning", "警告："),
+  ("learn.exists", "命令 '{command}' 已经学习过。使用 --force 重新学习。"),
+  ("learn.learning", "正在学习 '{command}'..."),
+  ("learn.fetched", "从 {source} 获取了 {bytes} 字节"),
+  ("learn.read", "从 {source} 读取了 {bytes} 字节"),
+  ("learn.got", "从 {source} 得到 {bytes} 字节"),
+  ("learn.subcommands.found", "'{tool}' 有 {count} 个子命令"),
+  ("learn.subcommands.done", "子命令：学习 {learned} 个，跳过 {skipped} 个，失败 {failed} 个"),
+  ("learn.refresh.checking", "正在检查 {count} 条已学习的命令..."),
+  ("learn.refresh.unknown_version", "未知版本"),
+  ("learn.refresh.missing", "{name}：找不到可执行文件"),
+  ("learn.refresh.failed", "{name}：无法获取帮助"),
+  ("learn.refresh.done", "刷新完成：重新学习 {refreshed} 条，无变化 {unchanged} 条，缺失 {missing} 条，跳过 {skipped} 条，失败 {failed} 条"),
+  ("learn.llm.summarizing", "正在使用 {model} 生成摘要..."),
+  ("learn.llm.added", "添加了 {count} 个 AI 生成的示例"),
+  ("learn.plugin_failed", "解析插件 '{plugin}' 处理 '{command}' 失败，改用内置解析器：{error}"),
+  ("learn.llm.failed", "LLM 摘要失败：{error}"),
+  ("learn.saved", "已保存到数据库"),
+  ("learn.indexed", "已加入搜索索引"),
+  ("learn.success", "成功学习 '{command}'！"),
+  ("learn.low_quality", "质量较低（{score}/100）：{issues}"),
+  ("learn.try_better", "可以尝试 --man、--url 或 --llm 获得更好的结果。"),
+  ("learn.try_command", "试试：rtfm {command}"),
+  // rtfm config
+  ("config.validating", "正在校验 {path}"),
+  ("config.read_failed", "读取 {path} 失败：{error}"),
+  ("config.no_file", "没有找到配置文件，检查默认值和环境变量覆盖。"),
+  ("config.looked_for", "  （查找过 {path}）"),
+  ("config.error", "错误："),
+  ("config.warning", "警告："),
+  ("config.invalid", "配置有 {errors} 个错误和 {warnings} 个警告"),
+  ("config.valid", "配置有效。"),
+  ("config.valid_with_warnings", "配置有效，但有 {count} 个警告。"),
+  // rtfm config path
+  ("config.paths", "配置文件（按优先级排列）："),
+  ("config.in_use", "（使用中）"),
+  ("config.ignored", "（存在，未使用）"),
+  ("config.not_found", "（不存在）"),
+  ("config.defaults", "没有找到配置文件，使用内置默认值。"),
+  ("config.data_dir", "数据目录：{path}"),
+  // rtfm stats / usage
+  ("stats.commands", "命令："),
+  ("stats.categories", "分类："),
+  ("stats.data_version", "数据版本：{version}（更新于 {updated}）"),
+  ("stats.most_used", "最常用："),
+  ("stats.usage_dashboard", "运行 'rtfm stats --dashboard' 查看更多使用统计。"),
+  ("stats.quality", "学习内容质量："),
+  ("stats.quality_counts", "良好 {good} 条，较低 {low} 条（得分 < {threshold}）"),
+  ("stats.list_low_quality", "运行 'rtfm stats --low-quality' 查看列表。"),
+  ("usage.empty", "还没有使用记录。使用 rtfm 时会统计查询和搜索。"),
+  ("usage.recording_off", "记录已关闭（search.record_usage = false）。"),
+  ("usage.lookups", "查询："),
+  ("usage.searches", "搜索："),
+  ("usage.zero_results", "（{count} 次无结果，{percent}%）"),
+  ("usage.top_lookups", "最常查询："),
+  ("usage.trending", "近期趋势（最近 {days} 天）："),
+  ("usage.no_result_searches", "没有结果的搜索："),
+  ("usage.gaps", "待学习"),
+  ("usage.gaps_hint", "（已安装、被搜索过、但没有文档）："),
+  ("usage.learn_gap", "学习方法：{command}"),
+  ("usage.cleared", "使用统计已清除。"),
+  // rtfm learn 失败时的说明
+  ("learn.error.failed", "学习 '{command}' 失败。"),
+  ("learn.error.not_found", "本机上找不到命令 '{command}'。"),
+  ("learn.error.reasons", "可能的原因：\n  - 命令没有安装\n  - 命令不在 PATH 中\n  - 命令名拼写错误"),
+  ("learn.error.install_first", "  - 先安装该命令，然后运行：rtfm learn {command}"),
+  ("learn.error.not_in_path", "命令没有安装或不在 PATH 中。"),
+  ("learn.error.try_man", "  - 运行 'rtfm learn {command} --man' 检查是否有 man 页面"),
+  ("learn.error.no_man", "无法获取 '{command}' 的帮助，且 'man' 不可用。"),
+  ("learn.error.windows_no_man", "Windows 默认没有 'man' 页面。\n命令 '{command}' 存在，但 --help 没有给出可用的输出。"),
+  ("learn.error.alternatives", "其他方法："),
+  ("learn.error.help_flag", "  - 检查 '{command}' 是否支持其他帮助参数（如 /?、-h）"),
+  ("learn.error.install_man", "  - 安装 man-db 软件包（如 apt install man-db）"),
+  ("learn.error.unusable", "命令存在，但没有给出可用的帮助输出。"),
+  ("learn.error.run_help", "  - 手动运行 '{command} --help' 检查输出"),
+  // rtfm learn-all / suggest-learn
+  ("learn_all.no_checkpoint", "没有找到中断的 learn-all 运行记录。请先运行 'rtfm learn-all'。"),
+  ("learn_all.nothing_to_retry", "没有需要重试的失败命令。"),
+  ("learn_all.retrying", "重试 {count} 条失败的命令（来源：{source}）"),
+  ("learn_all.source", "来源：{source}"),
+  ("learn_all.listing_man", "正在列出第 {section} 节的 man 页面..."),
+  ("learn_all.reading_manpath", "正在读取第 {section} 节的 man 页面源文件..."),
+  ("learn_all.reading_history", "正在读取 shell 历史..."),
+  ("learn_all.unknown_source", "未知的来源 '{source}'。可用 'man'、'manpath'、'powershell'、'path'、'builtin'、'history' 或 'auto'。"),
+  ("learn_all.none", "没有找到命令。"),
+  ("learn_all.found", "找到 {count} 条命令"),
+  ("learn_all.prefix", "以 '{prefix}' 开头的命令有 {count} 条"),
+  ("learn_all.excluded", "按 [learn] allow/deny 列表排除了 {count} 条命令"),
+  ("learn_all.limit", "只学习前 {count} 条命令"),
+  ("learn_all.resuming", "继续运行：已处理 {processed} 条，剩余 {remaining} 条"),
+  ("learn_all.done", "完成！"),
+  ("learn_all.learned", "  学习：{count}"),
+  ("learn_all.low_quality", "  质量较低：{count}（见 'rtfm stats --low-quality'）"),
+  ("learn_all.skipped", "  跳过：{count}（已存在）"),
+  ("learn_all.unlearnable", "  跳过：{count} 个{reason}（{names}）"),
+  ("learn_all.failed", "  失败：{count}"),
+  ("learn_all.retry_hint", "{count} 条命令失败。重试方法：rtfm learn-all --retry-failed"),
+  ("learn_all.total", "数据库中共有 {count} 条命令"),
+  ("learn_all.man_sections", "man 章节：\n  1 - 用户命令\n  2 - 系统调用\n  3 - 库函数\n  4 - 特殊文件\n  5 - 文件格式\n  6 - 游戏\n  7 - 杂项\n  8 - 系统管理"),
+  ("learn_all.powershell_hint", "  rtfm learn-all --source path  # 从 PATH 中的可执行文件学习\n  rtfm learn cargo              # 学习指定的命令"),
+  ("learn_all.sources", "可用的来源："),
+  ("learn_all.sources.windows", "  --source powershell  # PowerShell cmdlet（Windows 上的默认值）\n  --source path        # PATH 中的可执行文件"),
+  ("learn_all.sources.unix", "  --source man         # man 页面（Linux/macOS 上的默认值）\n  --source manpath     # MANPATH 中的 man 页面源文件（不需要 man）\n  --source path        # PATH 中的可执行文件\n  --source builtin     # shell 内建命令（bash/zsh）"),
+  ("suggest.none", "没有推荐：常用的命令都已收录。"),
+  ("suggest.title", "常用但没有速查的命令："),
+  ("suggest.used", "使用 {count} 次"),
+  ("suggest.confirm", "学习这 {count} 条命令吗？[y/N]"),
+  // 跳过的原因（learn::Unlearnable）
+  ("learn_all.reason.interactive", "交互式程序"),
+  ("learn_all.reason.gui", "图形界面程序"),
+  // rtfm backup / restore
+  ("backup.no_data", "没有数据。请先运行 'rtfm update' 或 'rtfm learn'。"),
+  ("backup.backing_up", "正在备份 {path} 中的数据..."),
+  ("backup.added", "  已加入 {entry}"),
+  ("backup.user_data", "  {commands} 条学习的命令、{notes} 条笔记、{favorites} 个收藏、{aliases} 个别名"),
+  ("backup.done", "备份完成！"),
+  ("backup.output", "  输出：{path}"),
+  ("backup.size", "  大小：{bytes} 字节（{mb} MB）"),
+  ("backup.restore_hint", "在其他机器上恢复："),
+  ("restore.not_found", "找不到备份归档：{path}"),
+  ("restore.restoring", "正在从 {path} 恢复..."),
+  ("restore.backup_db", "  现有数据库已备份到 {path}"),
+  ("restore.backup_index", "  现有索引已备份到 {path}"),
+  ("restore.extracting", "  正在解压文件..."),
+  ("restore.no_database", "不是 rtfm 备份：{path} 中没有数据库"),
+  ("restore.merge_index", "--merge 按合并的命令更新索引，请使用 --only db 或不指定 --only"),
+  ("restore.merged", "  已把 {count} 条命令合并到现有数据库并更新索引"),
+  ("restore.rebuilding_index", "  正在重建搜索索引..."),
+  ("restore.done", "恢复完成！"),
+  ("restore.commands", "  命令数：{count}"),
+  ("restore.config", "  配置：已恢复到 {path}"),
+  ("restore.user_data_only", "该备份只有用户数据，总是合并恢复；请去掉 --only"),
+  // rtfm sync
+  ("sync.no_remote", "没有指定远程服务：使用 --remote 或在配置中设置 sync.remote"),
+  ("sync.unknown_policy", "未知的同步策略 '{policy}'（可用 lww 或 merge）"),
+  ("sync.comparing", "正在与 {remote} 比较..."),
+  ("sync.up_to_date", "已是最新。"),
+  ("sync.fetching", "正在获取 {count} 条命令..."),
+  ("sync.remote_up_to_date", "远程已是最新。"),
+  ("sync.pushing", "正在推送 {count} 条命令..."),
+  ("sync.done", "同步完成！"),
+  ("sync.counts", "新增 {added} 条，更新 {updated} 条，保留 {kept} 条"),
+  ("sync.personal_counts", "新增 {favorites} 个收藏，新增或修改 {notes} 条笔记"),
+  ("sync.kept_local", "{count} 条命令保留了本地版本（更新，或没有可合并的内容）"),
+  ("sync.kept_remote", "{count} 条命令保留了远程版本（更新，或没有可合并的内容）"),
+  // rtfm export-cards
+  ("cards.unknown_format", "未知的卡片格式 '{format}'（可用 anki 或 csv）"),
+  ("cards.none", "没有符合条件的示例"),
+  ("cards.exported", "从 {commands} 条命令导出了 {cards} 张卡片"),
+  ("cards.exported_to", "从 {commands} 条命令导出了 {cards} 张卡片到 {path}"),
+  ("cards.anki_hint", "在 Anki 中通过 文件 > 导入 导入。"),
+  // rtfm embed
+  ("embed.no_commands", "语言 {langs} 中没有命令"),
+  ("embed.ignoring_index", "忽略现有索引：{error}"),
+  ("embed.embedding", "正在通过 {endpoint} 使用 '{model}' 为 {count} 条命令（{langs}）生成向量"),
+  ("embed.embedding_local", "正在使用本地模型 '{model}' 为 {count} 条命令（{langs}）生成向量（首次使用时下载）"),
+  ("embed.no_onnxruntime", "无法从 {path} 加载 ONNX Runtime：{error}\n请安装 1.20 或更高版本的 ONNX Runtime，用 ORT_DYLIB_PATH 指定库文件，或设置 embeddings.provider = \"http\"。"),
+  ("embed.progress", "生成向量中..."),
+  ("embed.ready", "向量索引已就绪！"),
+  ("embed.ready_counts", "新生成 {embedded} 条，未变化 {reused} 条（{dim} 维）"),
+  ("embed.search_hint", "搜索方法：rtfm search --semantic <查询>"),
+  ("embed.status.index", "索引：{path}"),
+  ("embed.status.not_built", "尚未建立。请运行 'rtfm embed rebuild'。"),
+  ("embed.status.model", "模型：{model}"),
+  ("embed.status.model_changed", "（embeddings.model 已改为 '{model}'，需要重建）"),
+  ("embed.status.commands", "命令：{count}（{dim} 维）"),
+  ("embed.status.size", "大小：{mb} MB"),
+  ("embed.status.stale", "{count} 条命令在上次重建后新增或有变化"),
+  ("embed.status.up_to_date", "已是最新。"),
+  // rtfm render
+  ("render.not_found", "没有名为 '{name}' 的命令"),
+  ("render.no_home", "找不到主目录，请使用 --dir"),
+  ("render.installed", "在 {path} 安装了 {count} 个 man 页面（{documented} 条命令已有 man 页面）"),
+  ("render.kept", "保留了 {count} 个不是由 rtfm 生成的已有页面"),
+  ("render.manpath_hint", "试试 'man <命令>'。如果 man 找不到这些页面，请把目录加入 MANPATH："),
+  // rtfm diff
+  ("diff.no_local", "还没有学习 '{name}'，请先学习：rtfm learn {name}"),
+  ("diff.no_tldr", "没有 '{name}' 的 tldr 页面（语言 '{lang}'）"),
+  ("diff.examples", "示例"),
+  ("diff.options", "选项"),
+  ("diff.tldr", "tldr（{lang}）"),
+  ("diff.local", "学习于 {when}"),
+  ("diff.same", "没有差异。"),
+  ("diff.undocumented", "本地帮助中有、tldr 页面没有提到的选项（{count}）："),
+  ("diff.missing", "tldr 页面中有、本地帮助没有提到的选项（{count}）："),
+  // rtfm user
+  ("user.invalid_name", "'{name}' 不是有效的用户名（只能使用字母、数字、- 和 _）"),
+  ("user.exists", "用户 '{name}' 已存在。使用 'rtfm user token {name}' 签发新 token。"),
+  ("user.not_found_add", "没有用户 '{name}'。使用 'rtfm user add {name}' 添加。"),
+  ("user.not_found", "没有用户 '{name}'"),
+  ("user.saved", "用户 '{name}' 已保存到 {path}"),
+  ("user.token", "API token（只显示一次）："),
+  ("user.token_usage", "使用方法：Authorization: Bearer <token>"),
+  ("user.multi_user_off", "server.multi_user 未开启，开启后才会校验 token。"),
+  ("user.none", "没有用户。使用 'rtfm user add <名称>' 添加。"),
+  ("user.learned", "已学习 {count} 条"),
+  ("user.multi_user_on", "多用户模式：开启"),
+  ("user.multi_user_disabled", "多用户模式：关闭（server.multi_user = false）"),
+  ("user.removed", "已从 {path} 删除用户 '{name}'"),
+  ("user.deleted", "已删除 {path}"),
+  ("user.data_kept", "该用户的数据保留在 {path}（使用 --purge 删除）"),
+  // rtfm plugin
+  ("plugin.dir", "插件目录：{path}"),
+  ("plugin.none", "没有安装插件。添加一个包含 {manifest} 的目录即可安装。"),
+  ("plugin.disabled", "已禁用"),
+  ("plugin.parses", "解析：{commands}"),
+  ("plugin.fetching", "正在从插件 '{name}' 获取命令..."),
+  ("plugin.no_commands", "插件没有返回命令。"),
+  ("plugin.imported", "从 '{name}' 导入了 {count} 条命令"),
+  // rtfm reset
+  ("reset.no_dir", "没有找到数据目录，无需重置。"),
+  ("reset.no_data", "没有数据，无需重置。"),
+  ("reset.warning", "警告：这会删除 RTFM 的全部数据！"),
+  ("reset.will_delete", "将删除以下内容："),
+  ("reset.item.database", "  - {name}（命令数据库）"),
+  ("reset.item.index", "  - {name}/（搜索索引）"),
+  ("reset.item.embeddings", "  - {name}（语义搜索索引）"),
+  ("reset.item.config", "  - config.toml（本地配置）"),
+  ("reset.confirm", "确定要继续吗？[y/N]"),
+  ("reset.aborted", "已取消。"),
+  ("reset.resetting", "正在重置..."),
+  ("reset.deleted", "  已删除 {name}"),
+  ("reset.done", "重置完成！"),
+  ("reset.factory_state", "全部数据已删除，RTFM 已恢复到出厂状态。"),
+  ("reset.start_fresh", "重新开始：\n  rtfm update    # 从 tldr 下载速查数据\n  rtfm learn ls  # 从本机命令学习"),
+  // rtfm import
+  ("import.parse_failed", "解析归档失败：{error}"),
+  // rtfm embed
+  ("embed.model_mismatch", "语义索引由 '{built}' 构建，但 embeddings.model 是 '{model}'。请运行 'rtfm embed rebuild'。"),
+  // rtfm audit
+  ("audit.empty", "还没有数据修改记录。"),
+  ("audit.commands", "{count} 条命令"),
+  // rtfm export
+  ("cli.export", "导出命令：navi 速查表、JSON、Markdown 或 tldr 页面"),
+  ("export.unknown_format", "未知的导出格式 '{format}'（可用 navi、json、markdown 或 tldr）"),
+  ("export.exported", "导出了 {commands} 条命令"),
+  ("export.user_data", "导出了 {commands} 条学习的命令、{notes} 条笔记、{favorites} 个收藏和 {aliases} 个别名，可用 rtfm restore 恢复"),
+  ("export.written", "导出了 {commands} 条命令到 {path} 中的 {files} 个文件"),
+  ("export.navi_hint", "使用方法：navi --path {path}"),
+  ("export.none", "没有符合条件的命令"),
+  ("export.exported_to", "导出了 {commands} 条命令到 {path}"),
+  ("export.tldr_hint", "可用 'rtfm import {path}' 重新导入。要贡献页面，把它们复制到 tldr-pages 仓库中（本机学习的命令在 pages.local/ 下）"),
+  // rtfm bench
+  ("cli.bench", "用本机数据测量索引、搜索、数据库和解析速度"),
+  ("bench.running", "正在测试：{stage}..."),
+  ("bench.dataset", "数据：{commands} 条命令，内容 {mb} MB"),
+  ("bench.database", "数据库"),
+  ("bench.scan", "全量读取"),
+  ("bench.lookup", "按名查询"),
+  ("bench.index", "索引"),
+  ("bench.build", "建立"),
+  ("bench.open", "打开"),
+  ("bench.search", "搜索（{queries} 个查询）"),
+  ("bench.cold", "冷"),
+  ("bench.warm", "热"),
+  ("bench.learn", "学习解析"),
+  ("bench.pages", "{pages} 个页面"),
+  ("bench.no_learned", "没有可解析的学习命令"),
+  // rtfm delete
+  ("cli.delete", "从数据库和搜索索引中删除命令"),
+  ("delete.not_found", "没有找到命令 '{name}'"),
+  ("delete.done", "已删除 '{name}'（{langs}）"),
+  // fuzzy search
+  ("tui.status.found_fuzzy", "没有精确匹配；找到 {count} 条相近的结果（{ms}ms）"),
+  ("query.try_fuzzy", "可以加上 --fuzzy 容忍拼写错误。"),
+  // rtfm fav
+  ("fav.added", "已收藏 {name}"),
+  ("fav.already", "{name} 已在收藏中"),
+  ("fav.not_found", "未找到命令：{name}"),
+  ("fav.removed", "已取消收藏 {name}"),
+  ("fav.not_favorite", "{name} 不在收藏中"),
+  ("fav.none", "还没有收藏。使用 'rtfm fav add <命令>' 或在 TUI 中对结果按 f 收藏"),
+  ("fav.missing", "（数据库中没有此命令）"),
+  // favorites (TUI)
+  ("tui.status.favorites", "共 {count} 条收藏"),
+  ("tui.status.favorite_added", "已收藏 {name}"),
+  ("tui.status.favorite_removed", "已取消收藏 {name}"),
+  ("tui.status.favorite_failed", "更新收藏失败：{error}"),
+  ("tui.no_favorites", "还没有收藏。对结果按 {key} 收藏"),
+  // history
+  ("cli.history", "显示最近查看的命令"),
+  ("history.empty", "还没有查看过命令"),
+  ("history.cleared", "已清空查看历史"),
+  ("tui.action.history", "显示/关闭最近查看的命令"),
+  ("tui.history.border", " 查看历史 "),
+  ("tui.history.empty", "还没有查看过命令"),
+  ("tui.frequently_used", "最常用"),
+  ("tui.status.recent_frequent", "最近查看 {count} 条，最常用 {frequent} 条"),
+  ("tui.status.recent", "最近查看 {count} 条"),
+  // picker
+  ("picker.hint", "↑↓ 选择，回车显示，输入字母筛选或输入序号跳转，Esc 取消"),
+  ("picker.filter", "筛选：{filter}（退格修改，Esc 清除）"),
+  ("picker.number", "序号：{number}（回车显示）"),
+  ("picker.no_match", "没有符合筛选的结果"),
+  // reindex
+  ("cli.reindex", "从数据库重建搜索索引"),
+  ("reindex.done", "已索引 {count} 个命令"),
+  // update from file
+  ("update.from_file", "正在读取压缩包：{path}"),
+  (
+    "update.cheat_done",
+    "已导入 {count} 个速查表（{merged} 个并入已有页面，删除 {removed} 个）",
+  ),
+  ("stats.data_source", "数据来源：{source}"),
+  // language switch (TUI)
+  ("tui.action.cycle_lang", "切换选中命令的语言"),
+  ("tui.status.lang", "{name}：{lang}（{langs}）"),
+  ("tui.status.one_lang", "{name} 只有一种语言"),
+  // import
+  ("import.watch_not_dir", "--watch 需要一个目录：{path}"),
+  ("import.docset_not_dir", "文档集是一个目录（名称.docset）：{path}"),
+  ("import.docset_failed", "读取文档集失败：{error}"),
+  ("import.watching", "正在监视 {path} 中新增或修改的 .md 文件（按 Ctrl+C 停止）..."),
+  ("import.watch_imported", "已导入 {count} 个命令：{names}"),
+  ("import.watch_failed", "导入失败：{error}"),
+  // rtfm alias
+  ("alias.added", "已添加别名 {name} → {target}"),
+  ("alias.updated", "别名 {name} 现在指向 {target}（原为 {previous}）"),
+  ("alias.invalid", "别名和命令都不能为空"),
+  ("alias.self", "别名不能指向自身：{name}"),
+  ("alias.target_missing", "注意：数据库中还没有 {target}"),
+  ("alias.removed", "已删除别名 {name}"),
+  ("alias.not_found", "没有这个别名：{name}"),
+  ("alias.none", "还没有别名。用 'rtfm alias add <别名> <命令>' 添加，或运行 'rtfm alias import'"),
+  ("alias.imported", "已导入 {count} 个别名"),
+  ("alias.import_none", "shell 配置文件和 git 配置中没有新的别名"),
+  ("alias.resolved", "{name} 是 {target} 的别名"),
+  // mouse (TUI)
+  ("tui.status.copied", "已复制选中的文字（{method}）"),
+  ("tui.status.copy_failed", "复制失败：{error}"),
+  // options section
+  ("tui.options", "选项"),
+  // placeholder fill mode
+  ("fill.hint", "填写占位符（留空则保留占位符中的文字）："),
+  ("tui.fill.pick", " 填写示例 "),
+  ("tui.fill.input", " 填写 {name} "),
+  ("tui.fill.pick_hint", "{open}：填写  {back}：关闭"),
+  ("tui.fill.input_hint", "{open}：下一个占位符  {back}：关闭（留空保留占位符）"),
+  ("tui.fill.no_examples", "{name} 没有示例"),
+  ("tui.status.filled", "已复制（{method}）：{command}"),
+  // rtfm note
+  ("cli.note", "用 $EDITOR 为命令写个人笔记，笔记随页面显示并可以搜索"),
+  ("note.not_found", "未找到命令：{name}"),
+  ("note.none", "还没有笔记。用 'rtfm note <name>' 添加，或在 TUI 中对结果按 n"),
+  ("note.empty", "{name} 没有笔记"),
+  ("note.saved", "已保存 {name} 的笔记"),
+  ("note.cleared", "已删除 {name} 的笔记"),
+  ("note.unchanged", "{name} 的笔记没有变化"),
+  ("note.editor_failed", "无法启动编辑器 {editor}：{error}"),
+  ("note.editor_exit", "编辑器 {editor} 退出状态为 {status}，笔记未保存"),
+  ("notes.title", "笔记"),
+  ("tui.action.notes", "用 $EDITOR 编辑选中命令的笔记"),
+  ("tui.status.note_saved", "已保存 {name} 的笔记"),
+  ("tui.status.note_failed", "保存笔记失败：{error}"),
+  ("tui.status.filters", "筛选：{filters}"),
+  // tldr client compatibility
+  (
+    "tldr.not_found",
+    "页面还不存在：{name}。运行 'rtfm update' 获取最新页面，或用 'rtfm learn {name}' 从 --help 学习",
+  ),
+  ("tldr.other_platform", "显示的是 {shown} 页面：没有 {platform} 或 common 页面"),
+  // TUI example navigation
+  ("tui.action.next_example", "选中详情中的下一个示例"),
+  ("tui.action.prev_example", "选中详情中的上一个示例"),
+  ("tui.action.copy_example", "复制选中的示例"),
+  ("tui.status.example", "示例 {index}/{count}：{description}"),
+  // TUI run example
+  ("tui.action.run_example", "确认后运行选中的示例（tui.allow_execute）"),
+  ("tui.run.disabled", "未开启运行示例，在 [tui] 中设置 allow_execute = true 开启"),
+  ("tui.run.title", "运行示例"),
+  ("tui.run.confirm", "在 shell 中运行这条命令？"),
+  ("tui.run.confirm_hint", "y：运行  n / {back}：取消"),
+  ("tui.run.running", "正在运行..."),
+  ("tui.run.stop_hint", "{back}：停止"),
+  ("tui.run.output_hint", "↑↓：滚动  {back}：关闭"),
+  ("tui.run.exit", "退出码 {code}"),
+  ("tui.run.killed", "被信号终止"),
+  ("tui.run.failed", "无法启动：{error}"),
+  ("tui.run.output_limit", "输出超过 {kb} KB，已停止"),
+  ("tui.run.timed_out", "运行超过 {secs} 秒，已停止"),
+];
@@ -0,0 +1,153 @@
+//! launchd 集成（macOS 上的 rtfm serve --install-service）
+//!
+//! 在 ~/Library/LaunchAgents 写入运行 `rtfm serve` 的 plist：登录时启动，异常退出时重启。
+//! launchd 启动的进程工作目录为 /，看不到安装时的环境变量，所以 plist 带上安装时的数据目录和配置文件。
+
+use std::path::{Path, PathBuf};
+
+/// launchd 中的服务标签，也是 plist 的文件名
+pub const LABEL: &str = "io.github.f2077.rtfm";
+
+/// 生成的 plist 中的注释
+const GENERATED_MARKER: &str = "<!-- Generated by rtfm serve --install-service -->";
+
+/// plist 的选项
+#[derive(Debug, Clone)]
+pub struct AgentOptions {
+  /// rtfm 可执行文件
+  pub exe: PathBuf,
+  pub data_dir: PathBuf,
+  /// 安装时使用的配置文件（绝对路径）
+  pub config: Option<PathBuf>,
+  pub bind: String,
+  pub port: u16,
+  pub read_only: bool,
+}
+
+/// 生成 plist
+pub fn plist(options: &AgentOptions) -> String {
+  let mut arguments = vec![
+    options.exe.display().to_string(),
+    "serve".to_string(),
+    "--bind".to_string(),
+    options.bind.clone(),
+    "--port".to_string(),
+    options.port.to_string(),
+  ];
+  if options.read_only {
+    arguments.push("--read-only".to_string());
+  }
+  let mut environment = vec![("RTFM_DATA_DIR", options.data_dir.display().to_string())];
+  if let Some(config) = &options.config {
+    environment.push(("RTFM_CONFIG", config.display().to_string()));
+  }
+
+  let mut plist = format!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n",
+    GENERATED_MARKER
+  );
+  plist.push_str(&format!(
+    "  <key>Label</key>\n  <string>{}</string>\n",
+    LABEL
+  ));
+  plist.push_str("  <key>ProgramArguments</key>\n  <array>\n");
+  for argument in &arguments {
+    plist.push_str(&format!("    <string>{}</string>\n", escape(argument)));
+  }
+  plist.push_str("  </array>\n  <key>EnvironmentVariables</key>\n  <dict>\n");
+  for (key, value) in &environment {
+    plist.push_str(&format!(
+      "    <key>{}</key>\n    <string>{}</string>\n",
+      key,
+      escape(value)
+    ));
+  }
+  // 异常退出时重启，rtfm serve 正常停止（退出码 0）时不重启
+  plist.push_str(
+    "  </dict>\n  <key>RunAtLoad</key>\n  <true/>\n  <key>KeepAlive</key>\n  <dict>\n    <key>SuccessfulExit</key>\n    <false/>\n  </dict>\n</dict>\n</plist>\n",
+  );
+  plist
+}
+
+fn escape(value: &str) -> String {
+  value
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
+
+/// 当前用户的 LaunchAgents 目录
+pub fn agent_dir() -> Option<PathBuf> {
+  dirs::home_dir().map(|home| home.join("Library").join("LaunchAgents"))
+}
+
+/// plist 的路径
+pub fn plist_path(dir: &Path) -> PathBuf {
+  dir.join(format!("{}.plist", LABEL))
+}
+
+/// 已存在的文件不是 rtfm 生成的时返回错误
+fn check_generated(path: &Path) -> anyhow::Result<()> {
+  match std::fs::read_to_string(path) {
+    Ok(existing) if !existing.contains(GENERATED_MARKER) => {
+      anyhow::bail!("{} exists and was not written by rtfm", path.display())
+    }
+    _ => Ok(()),
+  }
+}
+
+/// 写入 plist，返回写入的路径；已存在且不是 rtfm 生成的文件不会被覆盖
+pub fn install(dir: &Path, options: &AgentOptions) -> anyhow::Result<PathBuf> {
+  let path = plist_path(dir);
+  check_generated(&path)?;
+  std::fs::create_dir_all(dir)?;
+  std::fs::write(&path, plist(options))?;
+  Ok(path)
+}
+
+/// 删除 rtfm 生成的 plist，返回删除的路径；没有安装时返回 None
+pub fn uninstall(dir: &Path) -> anyhow::Result<Option<PathBuf>> {
+  let path = plist_path(dir);
+  if !path.exists() {
+    return Ok(None);
+  }
+  check_generated(&path)?;
+  std::fs::remove_file(&path)?;
+  Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_plist() {
+    let options = AgentOptions {
+      exe: PathBuf::from("/usr/local/bin/rtfm"),
+      data_dir: PathBuf::from("/Users/me/Library/Application Support/R&D"),
+      config: Some(PathBuf::from("/Users/me/rtfm.toml")),
+      bind: "127.0.0.1".to_string(),
+      port: 3030,
+      read_only: true,
+    };
+    let plist = plist(&options);
+    assert!(plist.contains(GENERATED_MARKER));
+    assert!(plist.contains(
+      "    <string>/usr/local/bin/rtfm</string>\n    <string>serve</string>\n    <string>--bind</string>\n    <string>127.0.0.1</string>\n"
+    ));
+    assert!(plist.contains("    <string>--read-only</string>\n  </array>"));
+    assert!(plist.contains("<string>/Users/me/Library/Application Support/R&amp;D</string>"));
+    assert!(plist.contains("<key>RTFM_CONFIG</key>\n    <string>/Users/me/rtfm.toml</string>"));
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = install(temp_dir.path(), &options).unwrap();
+    assert!(path.ends_with("io.github.f2077.rtfm.plist"));
+    assert!(install(temp_dir.path(), &options).is_ok());
+    assert_eq!(uninstall(temp_dir.path()).unwrap(), Some(path.clone()));
+    assert_eq!(uninstall(temp_dir.path()).unwrap(), None);
+
+    std::fs::write(&path, "<plist/>").unwrap();
+    assert!(install(temp_dir.path(), &options).is_err());
+    assert!(uninstall(temp_dir.path()).is_err());
+  }
+}
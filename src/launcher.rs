@@ -0,0 +1,176 @@
+//! 桌面启动器的输出格式（rtfm search --format）
+//!
+//! - alfred：Script Filter JSON（`{"items": [...]}`，含 uid、arg、autocomplete 与复制文本）
+//! - raycast：与 Raycast List.Item 属性对应的 JSON，供脚本或扩展直接渲染
+//! - rofi：rofi script 模式的行，`\0info\x1f` 携带选中后的值（ROFI_INFO）
+//!
+//! 查询与命令名完全一致时列出该命令的示例（选中即得到代码），否则列出匹配的命令。
+
+use serde_json::json;
+
+use crate::search::SearchResult;
+use crate::storage::Command;
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LauncherFormat {
+  Text,
+  Alfred,
+  Raycast,
+  Rofi,
+}
+
+impl LauncherFormat {
+  pub fn parse(s: &str) -> Option<Self> {
+    match s {
+      "text" => Some(LauncherFormat::Text),
+      "alfred" => Some(LauncherFormat::Alfred),
+      "raycast" => Some(LauncherFormat::Raycast),
+      "rofi" => Some(LauncherFormat::Rofi),
+      _ => None,
+    }
+  }
+}
+
+/// 一行结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+  pub uid: String,
+  pub title: String,
+  pub subtitle: String,
+  /// 选中后传给启动器动作的值：命令名或示例代码
+  pub arg: String,
+  /// 补全为命令名后可继续查看示例
+  pub autocomplete: Option<String>,
+  pub lang: String,
+}
+
+/// 搜索结果中的命令
+pub fn command_items(results: &[SearchResult]) -> Vec<Item> {
+  results
+    .iter()
+    .map(|r| Item {
+      uid: format!("{}:{}", r.lang, r.name),
+      title: r.name.clone(),
+      subtitle: r.description.clone(),
+      arg: r.name.clone(),
+      autocomplete: Some(r.name.clone()),
+      lang: r.lang.clone(),
+    })
+    .collect()
+}
+
+/// 命令的示例
+pub fn example_items(cmd: &Command) -> Vec<Item> {
+  cmd
+    .examples
+    .iter()
+    .enumerate()
+    .map(|(i, example)| Item {
+      uid: format!("{}:{}:{}", cmd.lang, cmd.name, i + 1),
+      title: example.code.clone(),
+      subtitle: example.description.clone(),
+      arg: example.code.clone(),
+      autocomplete: None,
+      lang: cmd.lang.clone(),
+    })
+    .collect()
+}
+
+/// 按格式输出
+pub fn render(items: &[Item], format: LauncherFormat) -> String {
+  match format {
+    LauncherFormat::Text => items
+      .iter()
+      .map(|item| format!("{}\t{}\n", item.title, item.subtitle))
+      .collect(),
+    LauncherFormat::Alfred => {
+      let items: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+          let mut value = json!({
+            "uid": item.uid,
+            "title": item.title,
+            "subtitle": item.subtitle,
+            "arg": item.arg,
+            "text": { "copy": item.arg, "largetype": item.title },
+          });
+          if let Some(autocomplete) = &item.autocomplete {
+            value["autocomplete"] = json!(autocomplete);
+          }
+          value
+        })
+        .collect();
+      format!("{}\n", json!({ "items": items }))
+    }
+    LauncherFormat::Raycast => {
+      let items: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+          json!({
+            "id": item.uid,
+            "title": item.title,
+            "subtitle": item.subtitle,
+            "accessories": [{ "text": item.lang }],
+            "arg": item.arg,
+          })
+        })
+        .collect();
+      format!("{}\n", json!({ "items": items }))
+    }
+    LauncherFormat::Rofi => {
+      let mut out = String::from("\0prompt\x1frtfm\n\0markup-rows\x1ftrue\n");
+      for item in items {
+        out.push_str(&format!(
+          "{} <span alpha=\"60%\">{}</span>\0info\x1f{}\n",
+          markup_escape(&item.title),
+          markup_escape(&item.subtitle),
+          item.arg.replace('\n', " ")
+        ));
+      }
+      out
+    }
+  }
+}
+
+/// Pango markup 转义，rofi 行中不能有换行
+fn markup_escape(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render() {
+    let results = [SearchResult {
+      name: "tar".to_string(),
+      description: "Archive <files>".to_string(),
+      category: "common".to_string(),
+      lang: "en".to_string(),
+      platform: "common".to_string(),
+      score: 1.0,
+      example: None,
+    }];
+    let items = command_items(&results);
+
+    let alfred: serde_json::Value =
+      serde_json::from_str(&render(&items, LauncherFormat::Alfred)).unwrap();
+    assert_eq!(alfred["items"][0]["uid"], "en:tar");
+    assert_eq!(alfred["items"][0]["autocomplete"], "tar");
+
+    let raycast: serde_json::Value =
+      serde_json::from_str(&render(&items, LauncherFormat::Raycast)).unwrap();
+    assert_eq!(raycast["items"][0]["accessories"][0]["text"], "en");
+
+    let rofi = render(&items, LauncherFormat::Rofi);
+    assert_eq!(
+      rofi.lines().last().unwrap(),
+      "tar <span alpha=\"60%\">Archive &lt;files&gt;</span>\0info\x1ftar"
+    );
+  }
+}
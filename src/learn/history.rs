@@ -0,0 +1,231 @@
+//! 从 shell 历史统计常用命令
+//!
+//! 支持 bash（~/.bash_history，跳过 HISTTIMEFORMAT 写入的时间戳行）、
+//! zsh（扩展格式 `: <时间>:<耗时>;<命令>`，行尾 `\` 续行）和 fish（fish_history 中的 `- cmd:` 行）。
+//! 每行按 `|`、`;`、`&&`、`||` 拆分，取每段的命令名，跳过环境变量赋值和 sudo 等前缀。
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+  Bash,
+  Zsh,
+  Fish,
+}
+
+/// 命令前的包装命令，真正的命令在其后
+const WRAPPERS: &[&str] = &[
+  "sudo",
+  "doas",
+  "time",
+  "nohup",
+  "env",
+  "exec",
+  "command",
+  "builtin",
+  "nice",
+  "caffeinate",
+];
+
+/// 后面紧跟命令的 shell 关键字
+const LEADING_KEYWORDS: &[&str] = &[
+  "if", "then", "else", "elif", "while", "until", "do", "!", "{", "and", "or", "not",
+];
+
+/// 其他 shell 关键字，所在的片段不含命令
+const KEYWORDS: &[&str] = &[
+  "fi", "for", "done", "case", "esac", "function", "select", "in", "end", "begin", "}",
+];
+
+/// 存在的历史文件
+pub fn history_files() -> Vec<(Shell, PathBuf)> {
+  let Some(home) = dirs::home_dir() else {
+    return Vec::new();
+  };
+  let zdotdir = std::env::var_os("ZDOTDIR")
+    .map(PathBuf::from)
+    .unwrap_or_else(|| home.clone());
+  let xdg_data = std::env::var_os("XDG_DATA_HOME")
+    .map(PathBuf::from)
+    .unwrap_or_else(|| home.join(".local/share"));
+
+  let candidates = [
+    (Shell::Bash, home.join(".bash_history")),
+    (Shell::Zsh, zdotdir.join(".zsh_history")),
+    (Shell::Zsh, zdotdir.join(".zhistory")),
+    (Shell::Fish, xdg_data.join("fish/fish_history")),
+  ];
+  let mut seen = HashSet::new();
+  candidates
+    .into_iter()
+    .filter(|(_, path)| path.is_file() && seen.insert(path.clone()))
+    .collect()
+}
+
+/// 解析历史文件内容为命令行
+pub fn parse(shell: Shell, content: &str) -> Vec<String> {
+  match shell {
+    Shell::Bash => content
+      .lines()
+      .filter(|line| !is_timestamp(line))
+      .map(str::to_string)
+      .collect(),
+    Shell::Zsh => {
+      let mut lines = Vec::new();
+      let mut pending: Option<String> = None;
+      for line in content.lines() {
+        let line = match pending.take() {
+          Some(mut previous) => {
+            previous.push(' ');
+            previous.push_str(line);
+            previous
+          }
+          None => strip_zsh_prefix(line).to_string(),
+        };
+        match line.strip_suffix('\\') {
+          Some(rest) => pending = Some(rest.to_string()),
+          None => lines.push(line),
+        }
+      }
+      lines.extend(pending);
+      lines
+    }
+    Shell::Fish => content
+      .lines()
+      .filter_map(|line| line.strip_prefix("- cmd: "))
+      .map(str::to_string)
+      .collect(),
+  }
+}
+
+fn is_timestamp(line: &str) -> bool {
+  line
+    .strip_prefix('#')
+    .is_some_and(|rest| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// 去掉 zsh 扩展历史的 `: 1700000000:0;` 前缀
+fn strip_zsh_prefix(line: &str) -> &str {
+  if let Some(rest) = line.strip_prefix(": ") {
+    if let Some((meta, command)) = rest.split_once(';') {
+      if meta.bytes().all(|b| b.is_ascii_digit() || b == b':') {
+        return command;
+      }
+    }
+  }
+  line
+}
+
+/// 命令行中调用的命令名
+pub fn command_names(line: &str) -> Vec<String> {
+  line
+    .replace("&&", ";")
+    .replace("||", ";")
+    .split(['|', ';', '&'])
+    .filter_map(segment_command)
+    .collect()
+}
+
+fn segment_command(segment: &str) -> Option<String> {
+  let mut tokens = segment.split_whitespace().peekable();
+  loop {
+    let token = *tokens.peek()?;
+    if is_assignment(token) || LEADING_KEYWORDS.contains(&token) {
+      tokens.next();
+    } else if WRAPPERS.contains(&token) {
+      tokens.next();
+      // sudo -u root 等包装命令的选项
+      while tokens.peek().is_some_and(|t| t.starts_with('-')) {
+        tokens.next();
+      }
+    } else {
+      break;
+    }
+  }
+
+  let name = tokens.next()?;
+  let valid = name
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphanumeric())
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+    && !KEYWORDS.contains(&name);
+  valid.then(|| name.to_string())
+}
+
+fn is_assignment(token: &str) -> bool {
+  token.split_once('=').is_some_and(|(name, _)| {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+  })
+}
+
+/// 统计所有历史文件中的命令使用次数，按次数从高到低排列
+pub fn frequent_commands() -> anyhow::Result<Vec<(String, usize)>> {
+  let files = history_files();
+  if files.is_empty() {
+    anyhow::bail!("No shell history found (looked for bash, zsh and fish history files)");
+  }
+
+  let mut counts: HashMap<String, usize> = HashMap::new();
+  for (shell, path) in files {
+    // zsh 历史中的非 ASCII 字符经过 metafy 处理，按有损 UTF-8 读取即可
+    let content = String::from_utf8_lossy(&std::fs::read(&path)?).into_owned();
+    for line in parse(shell, &content) {
+      for name in command_names(&line) {
+        *counts.entry(name).or_default() += 1;
+      }
+    }
+  }
+
+  let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+  counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+  Ok(counts)
+}
+
+/// 历史中出现过、且在本机可以运行（PATH 中的程序或 shell 内建命令）的命令
+pub fn list_commands() -> anyhow::Result<Vec<(String, usize)>> {
+  let builtins: HashSet<String> = super::list_available_commands("builtin")
+    .unwrap_or_default()
+    .into_iter()
+    .map(|(name, _)| name)
+    .collect();
+  Ok(
+    frequent_commands()?
+      .into_iter()
+      .filter(|(name, _)| builtins.contains(name) || super::find_binary(name).is_some())
+      .collect(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_history() {
+    let bash = "#1700000000\ngit status\nls -la\n";
+    assert_eq!(parse(Shell::Bash, bash), ["git status", "ls -la"]);
+
+    let zsh = ": 1700000000:0;docker ps\n: 1700000001:2;kubectl get \\\npods\nplain\n";
+    assert_eq!(
+      parse(Shell::Zsh, zsh),
+      ["docker ps", "kubectl get  pods", "plain"]
+    );
+
+    let fish = "- cmd: cargo build\n  when: 1700000000\n- cmd: rg foo\n";
+    assert_eq!(parse(Shell::Fish, fish), ["cargo build", "rg foo"]);
+  }
+
+  #[test]
+  fn test_command_names() {
+    assert_eq!(
+      command_names("RUST_LOG=debug sudo -E cargo run | grep foo && ./x.sh; time make"),
+      ["cargo", "grep", "make"]
+    );
+    assert_eq!(command_names("for f in *; do echo $f; done"), ["echo"]);
+    assert!(command_names("   ").is_empty());
+  }
+}
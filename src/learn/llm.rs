@@ -0,0 +1,193 @@
+//! LLM 辅助摘要
+//!
+//! 可选的后处理步骤：把原始 --help / man 文本发送给 OpenAI 兼容接口（或本地模型），
+//! 生成 tldr 风格的精简示例。生成的示例与启发式解析结果一起保存，并通过
+//! `Example::source` 标明来源。
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::LlmConfig;
+use crate::storage::{Command as StorageCommand, Example};
+use crate::update;
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+  model: &'a str,
+  messages: Vec<ChatMessage>,
+  temperature: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChatMessage {
+  role: String,
+  content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+  choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+  message: ChatMessage,
+}
+
+/// 用 LLM 生成的示例增强已解析的命令
+/// 返回新增的示例数量
+pub async fn enhance(cmd: &mut StorageCommand, config: &LlmConfig) -> anyhow::Result<usize> {
+  let page = summarize(config, &cmd.name, &cmd.content).await?;
+  let label = format!("llm:{}", config.model);
+
+  // 启发式解析没找到描述时，使用模型给出的描述
+  if cmd.description.ends_with("(learned from local system)") && page.description != cmd.name {
+    cmd.description = page.description;
+  }
+
+  let generated: Vec<Example> = page
+    .examples
+    .into_iter()
+    .take(config.max_examples)
+    .map(|e| Example {
+      source: Some(label.clone()),
+      ..e
+    })
+    .collect();
+  let count = generated.len();
+
+  // LLM 示例在前（更精简），启发式示例保留在后
+  cmd
+    .examples
+    .retain(|e| e.source.as_deref() != Some(label.as_str()));
+  cmd.examples.splice(0..0, generated);
+
+  Ok(count)
+}
+
+/// 调用 Chat Completions 接口，将原始帮助文本转换为 tldr 页面
+async fn summarize(config: &LlmConfig, name: &str, raw: &str) -> anyhow::Result<StorageCommand> {
+  let api_key = config
+    .api_key
+    .clone()
+    .or_else(|| std::env::var(&config.api_key_env).ok())
+    .filter(|k| !k.is_empty());
+
+  let request = ChatRequest {
+    model: &config.model,
+    messages: vec![
+      ChatMessage {
+        role: "system".to_string(),
+        content: system_prompt(config.max_examples),
+      },
+      ChatMessage {
+        role: "user".to_string(),
+        content: format!(
+          "Command: {}\n\nHelp text:\n{}",
+          name,
+          truncate_chars(raw, config.max_input_chars)
+        ),
+      },
+    ],
+    temperature: 0.2,
+  };
+
+  let client = reqwest::Client::builder()
+    .timeout(Duration::from_secs(config.timeout_secs))
+    .build()?;
+  let mut builder = client.post(&config.endpoint).json(&request);
+  if let Some(key) = api_key {
+    builder = builder.bearer_auth(key);
+  }
+
+  let response = builder.send().await?;
+  if !response.status().is_success() {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    anyhow::bail!(
+      "LLM endpoint returned {}: {}",
+      status,
+      truncate_chars(&body, 200)
+    );
+  }
+
+  let reply: ChatResponse = response.json().await?;
+  let content = reply
+    .choices
+    .into_iter()
+    .next()
+    .map(|c| c.message.content)
+    .ok_or_else(|| anyhow::anyhow!("LLM response contained no choices"))?;
+
+  parse_reply(name, &content)
+}
+
+/// 解析模型回复（tldr Markdown 格式）
+fn parse_reply(name: &str, content: &str) -> anyhow::Result<StorageCommand> {
+  // 模型有时会把整页包在代码围栏里
+  let content = content
+    .trim()
+    .trim_start_matches("```markdown")
+    .trim_start_matches("```md")
+    .trim_start_matches("```")
+    .trim_end_matches("```");
+
+  let page = update::parse_tldr_markdown(
+    content,
+    name.to_string(),
+    "local".to_string(),
+    "common".to_string(),
+  )
+  .ok_or_else(|| anyhow::anyhow!("LLM reply is not a valid tldr page"))?;
+
+  if page.examples.is_empty() {
+    anyhow::bail!("LLM reply contained no examples");
+  }
+
+  Ok(page)
+}
+
+fn system_prompt(max_examples: usize) -> String {
+  format!(
+    "You write pages for tldr-pages. Given a command's help text, reply with ONLY a tldr page \
+     in Markdown:\n\
+     # command\n\n\
+     > One-line description.\n\n\
+     - Short description of an example:\n\n\
+     `command --flag {{{{placeholder}}}}`\n\n\
+     Give at most {} of the most useful examples. Use {{{{double braces}}}} for user-supplied \
+     values. Only use options that appear in the help text.",
+    max_examples
+  )
+}
+
+/// 按字符截断（避免切断多字节字符）
+fn truncate_chars(s: &str, max_chars: usize) -> &str {
+  match s.char_indices().nth(max_chars) {
+    Some((idx, _)) => &s[..idx],
+    None => s,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_reply() {
+    let reply = "```markdown\n# rg\n\n> Search files recursively.\n\n- Search for a pattern:\n\n`rg {{pattern}}`\n\n- Include hidden files:\n\n`rg --hidden {{pattern}}`\n```";
+    let page = parse_reply("rg", reply).unwrap();
+    assert_eq!(page.description, "Search files recursively.");
+    assert_eq!(page.examples.len(), 2);
+    assert_eq!(page.examples[1].code, "rg --hidden {{pattern}}");
+
+    assert!(parse_reply("rg", "I cannot help with that.").is_err());
+  }
+
+  #[test]
+  fn test_truncate_chars() {
+    assert_eq!(truncate_chars("复制文件", 2), "复制");
+    assert_eq!(truncate_chars("abc", 10), "abc");
+  }
+}
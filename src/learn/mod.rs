@@ -5,56 +5,82 @@
 //! - macOS: --help, -h, man
 //! - Linux: --help, -h, man
 
+pub mod history;
+pub mod llm;
+pub mod powershell;
+pub mod probe;
+pub mod quality;
+pub mod refresh;
+pub mod roff;
+pub mod subcommands;
+pub mod windows;
+
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
 
-use crate::storage::{Command as StorageCommand, Example};
+use crate::config::LearnConfig;
+use crate::storage::{
+  Command as StorageCommand, Example, OptionEntry, Section, SourceInfo, COMMAND_SCHEMA,
+};
+
+pub use probe::Unlearnable;
+
+/// 获取版本号等附带信息时的超时
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// 获取命令帮助的统一入口（跨平台自适应）
 /// 返回 (内容, 来源) 或错误
-pub fn get_help_output(cmd: &str) -> anyhow::Result<(String, String)> {
+/// 命令忽略 --help（等待输入或需要图形界面）时返回 [`Unlearnable`] 错误
+pub fn get_help_output(cmd: &str, config: &LearnConfig) -> anyhow::Result<(String, String)> {
+  let timeout = Duration::from_secs(config.help_timeout_secs.max(1));
+
+  // 配置了专用调用方式的命令（如 `java -help`、`ffmpeg -h full`）
+  if let Some(invocation) = config.overrides.get(cmd) {
+    return run_help_invocation(invocation, timeout);
+  }
+
   // 根据平台选择帮助获取策略
   #[cfg(target_os = "windows")]
   {
-    get_help_windows(cmd)
+    get_help_windows(cmd, timeout)
   }
 
   #[cfg(target_os = "macos")]
   {
-    get_help_unix(cmd)
+    get_help_unix(cmd, timeout)
   }
 
   #[cfg(target_os = "linux")]
   {
-    get_help_unix(cmd)
+    get_help_unix(cmd, timeout)
   }
 
   #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
   {
-    get_help_unix(cmd)
+    get_help_unix(cmd, timeout)
   }
 }
 
 /// Windows 平台帮助获取
 /// 尝试顺序: --help -> -h -> /? -> Get-Help (PowerShell)
 #[cfg(target_os = "windows")]
-fn get_help_windows(cmd: &str) -> anyhow::Result<(String, String)> {
+fn get_help_windows(cmd: &str, timeout: Duration) -> anyhow::Result<(String, String)> {
   // 1. 尝试 --help（跨平台通用）
-  if let Ok(result) = try_help_flag(cmd, "--help") {
-    return Ok(result);
-  }
-
   // 2. 尝试 -h
-  if let Ok(result) = try_help_flag(cmd, "-h") {
-    return Ok(result);
-  }
-
   // 3. 尝试 /? (Windows 传统风格)
-  if let Ok(result) = try_help_flag(cmd, "/?") {
-    return Ok(result);
+  // 3.1 尝试 ? (netsh 等上下文帮助风格)
+  for flag in ["--help", "-h", "/?", "?"] {
+    match try_help_flag(cmd, flag, timeout) {
+      Ok(result) => return Ok(result),
+      // 打开窗口或等待输入的程序换个参数也一样，不再继续尝试
+      Err(e) if e.is::<Unlearnable>() => return Err(e),
+      Err(_) => {}
+    }
   }
 
-  // 4. 尝试 PowerShell Get-Help（对 PowerShell cmdlet 有效）
-  if let Ok(result) = get_powershell_help(cmd) {
+  // 4. 尝试 PowerShell Get-Help（对 PowerShell cmdlet 有效），优先取结构化的帮助对象
+  if let Ok(result) = powershell::get_help_json(cmd).or_else(|_| get_powershell_help(cmd)) {
     return Ok(result);
   }
 
@@ -79,15 +105,16 @@ fn get_help_windows(cmd: &str) -> anyhow::Result<(String, String)> {
 /// Unix 平台帮助获取 (Linux/macOS)
 /// 尝试顺序: --help -> -h
 #[cfg(any(target_os = "linux", target_os = "macos", not(target_os = "windows")))]
-fn get_help_unix(cmd: &str) -> anyhow::Result<(String, String)> {
+fn get_help_unix(cmd: &str, timeout: Duration) -> anyhow::Result<(String, String)> {
   // 1. 尝试 --help
-  if let Ok(result) = try_help_flag(cmd, "--help") {
-    return Ok(result);
-  }
-
   // 2. 尝试 -h
-  if let Ok(result) = try_help_flag(cmd, "-h") {
-    return Ok(result);
+  for flag in ["--help", "-h"] {
+    match try_help_flag(cmd, flag, timeout) {
+      Ok(result) => return Ok(result),
+      // 打开窗口或等待输入的程序换个参数也一样，不再继续尝试
+      Err(e) if e.is::<Unlearnable>() => return Err(e),
+      Err(_) => {}
+    }
   }
 
   // 检查命令是否存在
@@ -106,25 +133,80 @@ fn get_help_unix(cmd: &str) -> anyhow::Result<(String, String)> {
   }
 }
 
+/// 执行配置中的完整帮助调用（按空白分隔参数，支持双引号），来源即调用本身
+fn run_help_invocation(invocation: &str, timeout: Duration) -> anyhow::Result<(String, String)> {
+  let args = split_invocation(invocation);
+  let Some((program, args)) = args.split_first() else {
+    anyhow::bail!("Empty help invocation override");
+  };
+
+  let output = probe::run(Command::new(program).args(args), timeout).map_err(|e| {
+    if e.kind() == std::io::ErrorKind::NotFound {
+      anyhow::anyhow!("Command '{}' not found (program not found)", program)
+    } else {
+      anyhow::anyhow!("Failed to execute '{}': {}", invocation, e)
+    }
+  })?;
+
+  // 有些命令（如 java -help）把帮助输出到 stderr
+  for content in [&output.stdout, &output.stderr] {
+    if output.is_help(content) {
+      return Ok((strip_ansi_codes(content), invocation.to_string()));
+    }
+  }
+  if let Some(reason) = output.unlearnable(program, timeout) {
+    return Err(reason.into());
+  }
+  anyhow::bail!("No usable output from '{}'", invocation)
+}
+
+fn split_invocation(invocation: &str) -> Vec<String> {
+  let mut args = Vec::new();
+  let mut current = String::new();
+  let mut in_quotes = false;
+  let mut has_arg = false;
+
+  for c in invocation.chars() {
+    match c {
+      '"' => {
+        in_quotes = !in_quotes;
+        has_arg = true;
+      }
+      c if c.is_whitespace() && !in_quotes => {
+        if has_arg {
+          args.push(std::mem::take(&mut current));
+          has_arg = false;
+        }
+      }
+      c => {
+        current.push(c);
+        has_arg = true;
+      }
+    }
+  }
+  if has_arg {
+    args.push(current);
+  }
+  args
+}
+
 /// 尝试使用指定的帮助标志获取帮助
-fn try_help_flag(cmd: &str, flag: &str) -> anyhow::Result<(String, String)> {
-  let output = Command::new(cmd).arg(flag).output();
+/// 超时或报告无法连接显示器时返回 [`Unlearnable`]
+fn try_help_flag(cmd: &str, flag: &str, timeout: Duration) -> anyhow::Result<(String, String)> {
+  let output = probe::run(Command::new(cmd).arg(flag), timeout);
 
   match output {
     Ok(output) => {
       // 检查 stdout
-      if output.status.success() || !output.stdout.is_empty() {
-        let content = String::from_utf8_lossy(&output.stdout).to_string();
-        if is_valid_help_content(&content) {
-          return Ok((content, flag.to_string()));
-        }
+      if (output.success || !output.stdout.is_empty()) && output.is_help(&output.stdout) {
+        return Ok((output.stdout, flag.to_string()));
       }
       // 有些命令把帮助输出到 stderr
-      if !output.stderr.is_empty() {
-        let content = String::from_utf8_lossy(&output.stderr).to_string();
-        if is_valid_help_content(&content) {
-          return Ok((content, format!("{} (stderr)", flag)));
-        }
+      if output.is_help(&output.stderr) {
+        return Ok((output.stderr, format!("{} (stderr)", flag)));
+      }
+      if let Some(reason) = output.unlearnable(cmd, timeout) {
+        return Err(reason.into());
       }
       anyhow::bail!("No usable output from {} {}", cmd, flag)
     }
@@ -163,7 +245,7 @@ fn get_powershell_help(cmd: &str) -> anyhow::Result<(String, String)> {
       "-NoProfile",
       "-Command",
       &format!(
-        "Get-Help {} -ErrorAction SilentlyContinue | Out-String -Width 120",
+        "Get-Help {} -Full -ErrorAction SilentlyContinue | Out-String -Width 120",
         cmd
       ),
     ])
@@ -187,7 +269,7 @@ fn get_powershell_help(cmd: &str) -> anyhow::Result<(String, String)> {
       }
 
       // 有 NAME/SYNTAX 等有效内容，即使有警告也接受
-      Ok((content, "Get-Help (PowerShell)".to_string()))
+      Ok((content, POWERSHELL_SOURCE.to_string()))
     }
     Ok(_) => anyhow::bail!("Get-Help failed for '{}'", cmd),
     Err(e) => anyhow::bail!("Failed to run PowerShell Get-Help: {}", e),
@@ -261,17 +343,139 @@ fn get_man_page_unix(cmd: &str) -> anyhow::Result<(String, String)> {
       }
       anyhow::bail!("man failed for '{}': {}", cmd, stderr.trim())
     }
-    Err(e) => {
-      if e.kind() == std::io::ErrorKind::NotFound {
-        anyhow::bail!("'man' command not available (program not found)");
+    // 未安装 man 时直接解析 MANPATH 中的源文件
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => roff::get_page(cmd, None),
+    Err(e) => anyhow::bail!("Failed to run man: {}", e),
+  }
+}
+
+/// 获取本机帮助内容（shell 内建命令优先使用 `help`，其余按优先级尝试 man 与 --help）
+/// 全部失败时返回 (--help 错误, man 错误)
+pub fn get_local_help(
+  cmd: &str,
+  prefer_man: bool,
+  config: &LearnConfig,
+) -> Result<(String, String), (anyhow::Error, anyhow::Error)> {
+  // 配置的专用调用方式优先于 man 和内建命令
+  let prefer_man = prefer_man && !config.overrides.contains_key(cmd);
+
+  if is_shell_builtin(cmd) {
+    if let Ok(result) = get_builtin_help(cmd) {
+      return Ok(result);
+    }
+  }
+
+  if prefer_man {
+    get_man_page(cmd)
+      .or_else(|man_e| get_help_output(cmd, config).map_err(|help_e| (help_e, man_e)))
+  } else {
+    get_help_output(cmd, config)
+      .or_else(|help_e| get_man_page(cmd).map_err(|man_e| (help_e, man_e)))
+  }
+}
+
+/// 判断是否为 shell 内建命令（如 cd、export、ulimit）
+/// 通过 `bash -c 'type -t <cmd>'` 判断，Windows 上始终返回 false
+pub fn is_shell_builtin(cmd: &str) -> bool {
+  #[cfg(target_os = "windows")]
+  {
+    let _ = cmd;
+    false
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    // 命令名作为位置参数传入，避免被 shell 解释
+    Command::new("bash")
+      .args(["-c", "type -t -- \"$1\"", "bash", cmd])
+      .stdin(std::process::Stdio::null())
+      .output()
+      .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "builtin")
+      .unwrap_or(false)
+  }
+}
+
+/// 获取 shell 内建命令的帮助
+/// 尝试顺序: bash `help -m`（man 格式）-> zsh `run-help`（取自 zshbuiltins）
+pub fn get_builtin_help(cmd: &str) -> anyhow::Result<(String, String)> {
+  #[cfg(target_os = "windows")]
+  {
+    let _ = cmd;
+    anyhow::bail!("Shell builtins are not available on Windows");
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let bash = Command::new("bash")
+      .args(["-c", "help -m -- \"$1\"", "bash", cmd])
+      .stdin(std::process::Stdio::null())
+      .output();
+    if let Ok(output) = bash {
+      let content = String::from_utf8_lossy(&output.stdout).to_string();
+      if output.status.success() && !content.trim().is_empty() {
+        return Ok((content, "builtin".to_string()));
+      }
+    }
+
+    // zsh 的 run-help 对内建命令显示 zshbuiltins 手册，从中截取对应条目
+    let zsh = Command::new("zsh")
+      .args(["-c", "autoload -Uz run-help && run-help \"$1\"", "zsh", cmd])
+      .env("PAGER", "cat")
+      .env("MANPAGER", "cat")
+      .env("MANWIDTH", "80")
+      .stdin(std::process::Stdio::null())
+      .output();
+    if let Ok(output) = zsh {
+      let page = strip_ansi_codes(&String::from_utf8_lossy(&output.stdout));
+      if let Some(entry) = extract_zsh_builtin(&page, cmd) {
+        return Ok((entry, "builtin".to_string()));
       }
-      anyhow::bail!("Failed to run man: {}", e)
     }
+
+    anyhow::bail!("No builtin help for '{}'", cmd)
   }
 }
 
-/// 移除 ANSI 转义序列（仅 Unix 平台使用）
-#[cfg(not(target_os = "windows"))]
+/// 从 zshbuiltins 手册中截取某个内建命令的条目
+/// 条目标题行缩进较浅（如 "       cd [ -qsLP ] [ arg ]"），正文缩进更深
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn extract_zsh_builtin(page: &str, name: &str) -> Option<String> {
+  let indent_of = |l: &str| l.len() - l.trim_start().len();
+  let is_entry = |l: &str| {
+    let t = l.trim_start();
+    t == name || t.starts_with(&format!("{} ", name))
+  };
+
+  let lines: Vec<&str> = page.lines().collect();
+  let start = lines.iter().position(|l| indent_of(l) > 0 && is_entry(l))?;
+  let entry_indent = indent_of(lines[start]);
+
+  let mut entry = Vec::new();
+  for line in &lines[start..] {
+    let is_heading = !line.trim().is_empty() && indent_of(line) <= entry_indent;
+    if is_heading && !is_entry(line) && !entry.is_empty() {
+      break;
+    }
+    entry.push(*line);
+  }
+
+  let body = dedent(&entry);
+  let mut heading = body.lines().take_while(|l| !l.starts_with(' '));
+  let synopsis = heading.next()?.to_string();
+  Some(format!(
+    "NAME\n    {} - zsh builtin\n\nSYNOPSIS\n    {}\n\nDESCRIPTION\n{}",
+    name,
+    synopsis,
+    body
+      .lines()
+      .skip_while(|l| !l.starts_with(' '))
+      .map(|l| format!("  {}", l))
+      .collect::<Vec<_>>()
+      .join("\n")
+  ))
+}
+
+/// 移除 ANSI 转义序列和 backspace 效果
 fn strip_ansi_codes(s: &str) -> String {
   let mut result = String::with_capacity(s.len());
   let mut chars = s.chars().peekable();
@@ -300,8 +504,278 @@ fn strip_ansi_codes(s: &str) -> String {
   result
 }
 
+/// 从已捕获的帮助输出文件学习命令
+/// 适用于离线机器或 CI 中导出的 `--help` / man 输出
+pub fn from_file(name: &str, path: &Path) -> anyhow::Result<StorageCommand> {
+  let bytes = std::fs::read(path)
+    .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", path.display(), e))?;
+  let content = strip_ansi_codes(&String::from_utf8_lossy(&bytes));
+
+  if content.trim().is_empty() {
+    anyhow::bail!("File '{}' is empty", path.display());
+  }
+
+  Ok(parse_help_content(
+    name,
+    &content,
+    &format!("file: {}", path.display()),
+  ))
+}
+
+/// 从 URL 下载的最大字节数，文档页面和 README 远小于此
+pub const MAX_URL_BYTES: usize = 5 * 1024 * 1024;
+
+/// 从 URL 下载的总超时，包括读取响应体
+const URL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 连接远端服务器的超时
+const URL_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 从 URL 学习命令（文档页面或原始 README），同时返回下载的字节数
+/// 适用于本机未安装的命令：下载内容、去除 HTML/Markdown 标记后交给 `parse_help_content`
+pub async fn from_url(
+  name: &str,
+  url: &str,
+  user_agent: &str,
+) -> anyhow::Result<(StorageCommand, usize)> {
+  let url = normalize_doc_url(url);
+  let client = reqwest::Client::builder()
+    .user_agent(user_agent)
+    .timeout(URL_TIMEOUT)
+    .connect_timeout(URL_CONNECT_TIMEOUT)
+    .build()?;
+  let mut response = client.get(&url).send().await?;
+
+  if !response.status().is_success() {
+    anyhow::bail!("Failed to fetch '{}': HTTP {}", url, response.status());
+  }
+  let too_large = || {
+    anyhow::anyhow!(
+      "'{}' is larger than {} MB",
+      url,
+      MAX_URL_BYTES / (1024 * 1024)
+    )
+  };
+  if response
+    .content_length()
+    .is_some_and(|len| len > MAX_URL_BYTES as u64)
+  {
+    return Err(too_large());
+  }
+
+  let content_type = response
+    .headers()
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|v| v.to_str().ok())
+    .unwrap_or("")
+    .to_lowercase();
+  // 没有 Content-Length 或与实际不符时，边读边检查大小
+  let mut bytes = Vec::new();
+  while let Some(chunk) = response.chunk().await? {
+    if bytes.len() + chunk.len() > MAX_URL_BYTES {
+      return Err(too_large());
+    }
+    bytes.extend_from_slice(&chunk);
+  }
+  let size = bytes.len();
+  let body = String::from_utf8_lossy(&bytes).into_owned();
+
+  let text = if content_type.contains("html") || looks_like_html(&body) {
+    html_to_text(&body)
+  } else if content_type.contains("markdown") || is_markdown_url(&url) {
+    strip_markdown(&body)
+  } else {
+    body
+  };
+
+  if text.trim().is_empty() {
+    anyhow::bail!("No usable text content at '{}'", url);
+  }
+
+  Ok((
+    parse_help_content(name, &text, &format!("url: {}", url)),
+    size,
+  ))
+}
+
+/// GitHub 页面链接转换为原始文件地址
+/// 例如: https://github.com/o/r/blob/main/README.md -> https://raw.githubusercontent.com/o/r/main/README.md
+fn normalize_doc_url(url: &str) -> String {
+  if let Some(rest) = url.strip_prefix("https://github.com/") {
+    if let Some((repo, path)) = rest.split_once("/blob/") {
+      return format!("https://raw.githubusercontent.com/{}/{}", repo, path);
+    }
+  }
+  url.to_string()
+}
+
+fn is_markdown_url(url: &str) -> bool {
+  let lower = url.to_lowercase();
+  lower.ends_with(".md") || lower.ends_with(".markdown") || lower.contains("readme")
+}
+
+fn looks_like_html(body: &str) -> bool {
+  let head: String = body
+    .trim_start()
+    .chars()
+    .take(100)
+    .collect::<String>()
+    .to_lowercase();
+  head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+/// 将 HTML 转换为纯文本（保留段落和列表的换行结构）
+pub fn html_to_text(html: &str) -> String {
+  let mut text = String::with_capacity(html.len() / 2);
+  let mut rest = html;
+
+  while let Some(start) = rest.find('<') {
+    text.push_str(&rest[..start]);
+    let after = &rest[start..];
+    let Some(end) = after.find('>') else {
+      rest = "";
+      break;
+    };
+
+    let tag = after[1..end].trim().to_lowercase();
+    let tag_name: String = tag
+      .trim_start_matches('/')
+      .chars()
+      .take_while(|c| c.is_ascii_alphanumeric())
+      .collect();
+
+    rest = &after[end + 1..];
+
+    // script/style 的内容整体跳过
+    if !tag.starts_with('/') && (tag_name == "script" || tag_name == "style") {
+      let close = format!("</{}", tag_name);
+      let found = rest
+        .as_bytes()
+        .windows(close.len())
+        .position(|w| w.eq_ignore_ascii_case(close.as_bytes()));
+      rest = match found {
+        Some(idx) => rest[idx..]
+          .find('>')
+          .map(|e| &rest[idx + e + 1..])
+          .unwrap_or(""),
+        None => "",
+      };
+      continue;
+    }
+
+    match tag_name.as_str() {
+      "br" | "p" | "div" | "tr" | "pre" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "dt" | "dd" => {
+        text.push('\n')
+      }
+      "li" if !tag.starts_with('/') => text.push_str("\n- "),
+      _ => {}
+    }
+  }
+  text.push_str(rest);
+
+  let decoded = decode_html_entities(&text);
+
+  // 合并多余空行
+  let mut result = String::new();
+  let mut blank = false;
+  for line in decoded.lines() {
+    let line = line.trim_end();
+    if line.trim().is_empty() {
+      if !blank && !result.is_empty() {
+        result.push('\n');
+      }
+      blank = true;
+    } else {
+      result.push_str(line);
+      result.push('\n');
+      blank = false;
+    }
+  }
+  result
+}
+
+fn decode_html_entities(s: &str) -> String {
+  s.replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&#39;", "'")
+    .replace("&#x27;", "'")
+    .replace("&nbsp;", " ")
+    .replace("&amp;", "&")
+}
+
+/// 去除 Markdown 标记，保留文本和代码内容
+fn strip_markdown(md: &str) -> String {
+  let mut result = String::with_capacity(md.len());
+
+  for line in md.lines() {
+    let trimmed = line.trim_start();
+
+    // 代码围栏本身去掉，内容保留
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      continue;
+    }
+
+    let line = trimmed.trim_start_matches('#').trim_start();
+    let line = line.strip_prefix("> ").unwrap_or(line);
+
+    // [text](url) -> text
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+      let after = &rest[open + 1..];
+      match (after.find("]("), after.find(')')) {
+        (Some(close), Some(paren)) if close < paren => {
+          out.push_str(&rest[..open]);
+          out.push_str(&after[..close]);
+          rest = &after[paren + 1..];
+        }
+        _ => {
+          out.push_str(&rest[..=open]);
+          rest = after;
+        }
+      }
+    }
+    out.push_str(rest);
+
+    result.push_str(&out.replace("**", "").replace('`', ""));
+    result.push('\n');
+  }
+
+  result
+}
+
+/// PowerShell `Get-Help -Full` 来源标识
+const POWERSHELL_SOURCE: &str = "Get-Help (PowerShell)";
+
 /// 解析帮助内容为结构化命令
 pub fn parse_help_content(name: &str, content: &str, source: &str) -> StorageCommand {
+  // PowerShell 结构化帮助：按帮助对象的字段取示例和参数，内容保存为排版后的文本
+  if source == powershell::JSON_SOURCE {
+    if let Some(help) = powershell::parse_json(name, content) {
+      let mut cmd =
+        parse_powershell_content(name, &help.text, source, parse_man_sections(&help.text));
+      if !help.examples.is_empty() {
+        cmd.examples = help.examples;
+      }
+      cmd.options = help.options;
+      return cmd;
+    }
+  }
+
+  let sections = parse_man_sections(content);
+
+  // PowerShell 完整帮助：SYNOPSIS / PARAMETERS / 编号示例（结构化帮助重新解析时也是这种文本）
+  let powershell = source == POWERSHELL_SOURCE || source == powershell::JSON_SOURCE;
+  if powershell && !sections.is_empty() {
+    return parse_powershell_content(name, content, source, sections);
+  }
+
+  // man 页面按章节解析
+  if sections.iter().any(|s| s.title == "NAME") && sections.len() >= 2 {
+    return parse_man_content(name, content, source, sections);
+  }
+
   let lines: Vec<&str> = content.lines().collect();
 
   // 提取描述（通常在开头几行）
@@ -313,14 +787,583 @@ pub fn parse_help_content(name: &str, content: &str, source: &str) -> StorageCom
   StorageCommand {
     name: name.to_string(),
     description,
-    category: "local".to_string(),
+    category: local_category(source),
+    platform: get_platform(),
+    lang: "local".to_string(),
+    examples,
+    content: format!("Source: {}\n\n{}", source, content),
+    sections: vec![],
+    options: extract_option_entries(content),
+    source_info: Some(source_info(source)),
+    notes: None,
+    schema: COMMAND_SCHEMA,
+  }
+}
+
+/// 解析本机帮助内容；man 页面的示例改为从 MANPATH 中的 roff 源结构化提取（参数为 `{{占位符}}`），
+/// 找不到源文件或没有提取到示例时保留按文本解析的示例
+pub fn parse_local_help(name: &str, content: &str, source: &str) -> StorageCommand {
+  let mut cmd = parse_help_content(name, content, source);
+  let examples = roff::page_examples(name, source);
+  if !examples.is_empty() {
+    cmd.examples = examples;
+  }
+  cmd
+}
+
+/// 根据来源确定分类：shell 内建命令单独归类
+fn local_category(source: &str) -> String {
+  if source == "builtin" {
+    "builtin".to_string()
+  } else {
+    "local".to_string()
+  }
+}
+
+fn source_info(source: &str) -> SourceInfo {
+  SourceInfo {
+    source: source.to_string(),
+    learned_at: chrono::Utc::now().to_rfc3339(),
+    ..Default::default()
+  }
+}
+
+/// 记录本机二进制信息（版本号、路径、修改时间），用于显示和过期检测
+/// Windows 上帮助中没有描述时，用 scoop / winget 清单中的描述补全
+pub fn record_binary(cmd: &mut StorageCommand) {
+  if cmd.description.ends_with("(learned from local system)") {
+    if let Some(description) = windows::package_description(&cmd.name) {
+      cmd.description = description;
+    }
+  }
+
+  let binary = find_binary(&cmd.name);
+  let version = get_version(&cmd.name);
+  if let Some(info) = cmd.source_info.as_mut() {
+    info.version_number = version.as_deref().and_then(parse_version_number);
+    info.version = version;
+    info.binary_mtime = binary.as_deref().and_then(binary_mtime);
+    info.binary = binary.map(|p| p.to_string_lossy().to_string());
+  }
+}
+
+/// 在 PATH 中查找命令的可执行文件
+pub fn find_binary(cmd: &str) -> Option<PathBuf> {
+  let extensions: Vec<String> = if cfg!(target_os = "windows") {
+    std::env::var("PATHEXT")
+      .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+      .split(';')
+      .map(|e| e.to_lowercase())
+      .chain(std::iter::once(String::new()))
+      .collect()
+  } else {
+    vec![String::new()]
+  };
+
+  let path = std::env::var_os("PATH")?;
+  std::env::split_paths(&path).find_map(|dir| {
+    extensions
+      .iter()
+      .map(|ext| dir.join(format!("{}{}", cmd, ext)))
+      .find(|candidate| candidate.is_file())
+  })
+}
+
+/// 可执行文件的修改时间（Unix 秒）
+pub fn binary_mtime(path: &Path) -> Option<i64> {
+  let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+  let secs = modified
+    .duration_since(std::time::UNIX_EPOCH)
+    .ok()?
+    .as_secs();
+  i64::try_from(secs).ok()
+}
+
+/// 获取命令版本号
+/// 取 `--version` 输出（stdout 为空时取 stderr）的首个非空行，且必须包含数字
+pub fn get_version(cmd: &str) -> Option<String> {
+  let output = probe::run(Command::new(cmd).arg("--version"), VERSION_TIMEOUT).ok()?;
+  if output.timed_out {
+    return None;
+  }
+
+  let text = if output.stdout.trim().is_empty() {
+    output.stderr
+  } else {
+    output.stdout
+  };
+
+  parse_version_line(&strip_ansi_codes(&text))
+}
+
+fn parse_version_line(text: &str) -> Option<String> {
+  let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+  let lower = line.to_lowercase();
+  let looks_like_error = ["unknown", "unrecognized", "invalid", "illegal", "usage"]
+    .iter()
+    .any(|w| lower.contains(w));
+
+  if looks_like_error || line.len() > 120 || !line.chars().any(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  Some(line.to_string())
+}
+
+/// 版本行中的版本号（如 "git version 2.43.0" 中的 2.43.0）：以数字开头的词，优先取带点的
+pub fn parse_version_number(line: &str) -> Option<String> {
+  let words: Vec<&str> = line
+    .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '/' | '-'))
+    .map(|word| word.strip_prefix(['v', 'V']).unwrap_or(word))
+    .map(|word| word.trim_end_matches(|c: char| !c.is_ascii_alphanumeric()))
+    .filter(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+    .collect();
+  words
+    .iter()
+    .find(|word| word.contains('.'))
+    .or(words.first())
+    .map(|word| word.to_string())
+}
+
+/// 按章节解析 man 页面
+/// 描述取自 NAME（其次 DESCRIPTION），示例优先取 EXAMPLES，其次 OPTIONS，最后 SYNOPSIS
+fn parse_man_content(
+  name: &str,
+  content: &str,
+  source: &str,
+  sections: Vec<Section>,
+) -> StorageCommand {
+  let find = |titles: &[&str]| {
+    sections
+      .iter()
+      .find(|s| titles.contains(&s.title.as_str()))
+      .map(|s| s.content.as_str())
+  };
+
+  let description = find(&["NAME"])
+    .and_then(man_name_description)
+    .or_else(|| find(&["DESCRIPTION"]).and_then(first_paragraph))
+    .unwrap_or_else(|| format!("{} command (learned from local system)", name));
+
+  let mut examples = find(&["EXAMPLES", "EXAMPLE"])
+    .map(|body| extract_man_examples(body, name))
+    .unwrap_or_default();
+
+  // GNU 风格的 man 页面常把选项写在 DESCRIPTION 中
+  let options_body = find(&["OPTIONS"]).or_else(|| find(&["DESCRIPTION"]));
+  let options = options_body.map(extract_option_entries).unwrap_or_default();
+
+  if examples.is_empty() {
+    if let Some(body) = options_body {
+      examples = extract_man_options(body, name);
+    }
+  }
+
+  if examples.is_empty() {
+    if let Some(synopsis) = find(&["SYNOPSIS"]) {
+      examples.extend(
+        synopsis
+          .lines()
+          .take(3)
+          .filter(|l| !l.trim().is_empty())
+          .map(|line| Example {
+            description: "Synopsis".to_string(),
+            code: line.trim().to_string(),
+            source: None,
+          }),
+      );
+    }
+  }
+
+  StorageCommand {
+    name: name.to_string(),
+    description,
+    category: local_category(source),
     platform: get_platform(),
     lang: "local".to_string(),
     examples,
     content: format!("Source: {}\n\n{}", source, content),
+    sections,
+    options,
+    source_info: Some(source_info(source)),
+    notes: None,
+    schema: COMMAND_SCHEMA,
   }
 }
 
+/// 解析 PowerShell `Get-Help -Full` 输出
+/// 描述取自 SYNOPSIS，示例取自 "--- Example N: ... ---" 段落，无示例时使用 PARAMETERS
+fn parse_powershell_content(
+  name: &str,
+  content: &str,
+  source: &str,
+  sections: Vec<Section>,
+) -> StorageCommand {
+  let find = |title: &str| {
+    sections
+      .iter()
+      .find(|s| s.title == title)
+      .map(|s| s.content.as_str())
+  };
+
+  let description = find("SYNOPSIS")
+    .and_then(first_paragraph)
+    .or_else(|| find("DESCRIPTION").and_then(first_paragraph))
+    .unwrap_or_else(|| format!("{} command (learned from local system)", name));
+
+  let mut examples = extract_powershell_examples(content);
+  if examples.is_empty() {
+    if let Some(body) = find("PARAMETERS") {
+      examples = extract_powershell_parameters(body, name);
+    }
+  }
+  let options = find("PARAMETERS")
+    .map(powershell_options)
+    .unwrap_or_default();
+
+  StorageCommand {
+    name: name.to_string(),
+    description,
+    category: local_category(source),
+    platform: get_platform(),
+    lang: "local".to_string(),
+    examples,
+    content: format!("Source: {}\n\n{}", source, content),
+    sections,
+    options,
+    source_info: Some(source_info(source)),
+    notes: None,
+    schema: COMMAND_SCHEMA,
+  }
+}
+
+/// 提取 PowerShell 示例
+/// 标题形如 "---------- Example 1: Get child items ----------" 或 "---------- EXAMPLE 1 ----------"；
+/// 代码为标题后的首个非空行（去掉 "PS C:\>" 提示符）及其续行，无标题说明时取代码后的首段说明
+fn extract_powershell_examples(content: &str) -> Vec<Example> {
+  let mut examples = Vec::new();
+  let mut lines = content.lines().peekable();
+
+  while let Some(line) = lines.next() {
+    let trimmed = line.trim();
+    if !(trimmed.starts_with("---") && trimmed.to_lowercase().contains("example")) {
+      continue;
+    }
+
+    // 标题："Example 1: xxx" -> "xxx"
+    let title = trimmed.trim_matches('-').trim();
+    let mut description = title
+      .split_once(':')
+      .map(|(_, t)| t.trim().to_string())
+      .unwrap_or_default();
+
+    // 代码块：跳过空行后的连续非空行
+    while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+      lines.next();
+    }
+    let mut code_lines = Vec::new();
+    while let Some(next) = lines.peek() {
+      let next = next.trim();
+      if next.is_empty() || next.starts_with("---") {
+        break;
+      }
+      code_lines.push(strip_ps_prompt(next).to_string());
+      lines.next();
+    }
+
+    // 说明：无标题说明时，取代码后的第一段文字
+    if description.is_empty() {
+      while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+        lines.next();
+      }
+      let mut para = Vec::new();
+      while let Some(next) = lines.peek() {
+        let next = next.trim();
+        if next.is_empty() || next.starts_with("---") {
+          break;
+        }
+        para.push(next);
+        lines.next();
+      }
+      description = para.join(" ");
+    }
+
+    if code_lines.is_empty() {
+      continue;
+    }
+    if description.is_empty() {
+      description = title.to_string();
+    }
+
+    examples.push(Example {
+      description,
+      code: code_lines.join("\n"),
+      source: None,
+    });
+    if examples.len() >= 10 {
+      break;
+    }
+  }
+
+  examples
+}
+
+/// 去掉 "PS C:\>" / "PS>" 提示符
+fn strip_ps_prompt(line: &str) -> &str {
+  if line.starts_with("PS ") || line.starts_with("PS>") {
+    if let Some(idx) = line.find('>') {
+      return line[idx + 1..].trim_start();
+    }
+  }
+  line
+}
+
+/// 解析 PARAMETERS 章节：参数行如 "-Path <String[]>"，下一行缩进的文字为说明
+fn powershell_parameters(body: &str) -> Vec<(&str, &str)> {
+  let mut params = Vec::new();
+  let mut lines = body.lines().peekable();
+
+  while let Some(line) = lines.next() {
+    // 参数行顶格（章节内已去除公共缩进），示例分隔线之后不再是参数
+    if line.starts_with("---") {
+      break;
+    }
+    if !line.starts_with('-') {
+      continue;
+    }
+    let param = line.trim();
+    let description = lines
+      .peek()
+      .copied()
+      .map(str::trim)
+      .filter(|l| !l.is_empty() && !l.starts_with('-'))
+      .unwrap_or(param);
+    params.push((param, description));
+  }
+
+  params
+}
+
+/// 前几个参数作为示例
+fn extract_powershell_parameters(body: &str, name: &str) -> Vec<Example> {
+  powershell_parameters(body)
+    .into_iter()
+    .take(5)
+    .map(|(param, description)| Example {
+      description: description.to_string(),
+      code: format!("{} {}", name, param),
+      source: None,
+    })
+    .collect()
+}
+
+/// 参数列表作为选项；开关参数（SwitchParameter）没有参数值
+fn powershell_options(body: &str) -> Vec<OptionEntry> {
+  powershell_parameters(body)
+    .into_iter()
+    .map(|(param, description)| {
+      let (flag, arg) = param.split_once(' ').unwrap_or((param, ""));
+      OptionEntry {
+        flags: vec![flag.to_string()],
+        arg: Some(arg.trim())
+          .filter(|arg| !arg.is_empty() && !arg.contains("SwitchParameter"))
+          .map(String::from),
+        description: description.to_string(),
+      }
+    })
+    .collect()
+}
+
+/// 将 man 输出切分为章节
+/// 章节标题顶格且全大写（如 NAME、SEE ALSO），正文缩进；页眉页脚顶格但含小写或括号，跳过
+fn parse_man_sections(content: &str) -> Vec<Section> {
+  let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+
+  for line in content.lines() {
+    let indented = line.starts_with(' ') || line.starts_with('\t');
+    if !indented && is_man_section_header(line) {
+      sections.push((line.trim().to_string(), Vec::new()));
+    } else if indented || line.trim().is_empty() {
+      if let Some((_, body)) = sections.last_mut() {
+        body.push(line);
+      }
+    }
+  }
+
+  sections
+    .into_iter()
+    .map(|(title, body)| Section {
+      title,
+      content: dedent(&body),
+    })
+    .collect()
+}
+
+fn is_man_section_header(line: &str) -> bool {
+  let line = line.trim_end();
+  !line.is_empty()
+    && line.len() <= 40
+    && line.chars().any(|c| c.is_ascii_uppercase())
+    && line
+      .chars()
+      .all(|c| c.is_ascii_uppercase() || c == ' ' || c == '-' || c == '/')
+}
+
+/// 去除公共缩进及首尾空行
+fn dedent(lines: &[&str]) -> String {
+  let indent = lines
+    .iter()
+    .filter(|l| !l.trim().is_empty())
+    .map(|l| l.len() - l.trim_start().len())
+    .min()
+    .unwrap_or(0);
+
+  lines
+    .iter()
+    .map(|l| l.get(indent..).unwrap_or("").trim_end())
+    .collect::<Vec<_>>()
+    .join("\n")
+    .trim_matches('\n')
+    .to_string()
+}
+
+/// 从 NAME 章节提取描述，如 "ls - list directory contents"
+fn man_name_description(body: &str) -> Option<String> {
+  let joined = body.split_whitespace().collect::<Vec<_>>().join(" ");
+  [" - ", " \u{2014} ", " \u{2013} "]
+    .iter()
+    .find_map(|sep| joined.split_once(sep))
+    .map(|(_, desc)| desc.trim().to_string())
+    .filter(|desc| !desc.is_empty())
+}
+
+/// 章节的第一段（合并为一行，最多约 200 字符）
+fn first_paragraph(body: &str) -> Option<String> {
+  let para = body
+    .split("\n\n")
+    .next()?
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ");
+  if para.is_empty() {
+    return None;
+  }
+  match para.char_indices().nth(200) {
+    Some((idx, _)) => Some(format!("{}...", &para[..idx])),
+    None => Some(para),
+  }
+}
+
+/// 解析 EXAMPLES 章节
+/// 命令行以命令名或 "$ " 开头；描述取前面的说明段落，或紧随其后的注释行
+fn extract_man_examples(body: &str, name: &str) -> Vec<Example> {
+  let mut examples: Vec<Example> = Vec::new();
+  let mut current_desc = String::new();
+  let mut needs_desc = false;
+
+  for line in body.lines() {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+
+    let code = trimmed.strip_prefix("$ ").unwrap_or(trimmed);
+    let is_code = code == name || code.starts_with(&format!("{} ", name));
+
+    if is_code {
+      let description = std::mem::take(&mut current_desc);
+      needs_desc = description.is_empty();
+      examples.push(Example {
+        description: if needs_desc {
+          extract_inline_description(code)
+        } else {
+          description.trim_end_matches(':').to_string()
+        },
+        code: code.to_string(),
+        source: None,
+      });
+      if examples.len() >= 10 {
+        break;
+      }
+    } else if needs_desc {
+      // 命令在前、说明在后的格式（如 tar(1)）
+      if let Some(last) = examples.last_mut() {
+        last.description = trimmed.trim_start_matches('#').trim().to_string();
+      }
+      needs_desc = false;
+    } else {
+      if !current_desc.is_empty() {
+        current_desc.push(' ');
+      }
+      current_desc.push_str(trimmed);
+    }
+  }
+
+  examples
+}
+
+/// 解析 man 风格的选项列表，前几个选项作为示例
+fn extract_man_options(body: &str, name: &str) -> Vec<Example> {
+  option_paragraphs(body)
+    .iter()
+    .filter_map(|text| parse_option_line(text))
+    .take(5)
+    .map(|(opt, desc)| Example {
+      description: desc,
+      code: format!("{} {}", name, opt),
+      source: None,
+    })
+    .collect()
+}
+
+/// 提取选项列表，同一选项只保留第一条
+fn extract_option_entries(body: &str) -> Vec<OptionEntry> {
+  let mut options: Vec<OptionEntry> = Vec::new();
+  for option in option_paragraphs(body)
+    .iter()
+    .filter_map(|text| parse_option_entry(text))
+  {
+    if !options.iter().any(|o| o.overlaps(&option)) {
+      options.push(option);
+    }
+  }
+  options
+}
+
+/// 以 "-" 开头的选项段落，续行合并到一起
+/// 支持 "-l     use a long listing format" 与选项、说明分行两种格式
+fn option_paragraphs(body: &str) -> Vec<String> {
+  let mut paragraphs = Vec::new();
+  let mut lines = body.lines().peekable();
+
+  while let Some(line) = lines.next() {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('-') {
+      continue;
+    }
+
+    // 说明可能与选项同行，也可能在下一行；续行缩进更深
+    let indent = line.len() - line.trim_start().len();
+    let mut text = trimmed.to_string();
+    let mut has_desc = parse_option_line(trimmed).is_some();
+    while let Some(next) = lines.peek() {
+      let next_trimmed = next.trim();
+      if next_trimmed.is_empty()
+        || next_trimmed.starts_with('-')
+        || next.len() - next.trim_start().len() <= indent
+      {
+        break;
+      }
+      text.push_str(if has_desc { " " } else { "  " });
+      text.push_str(next_trimmed);
+      has_desc = true;
+      lines.next();
+    }
+    paragraphs.push(text);
+  }
+
+  paragraphs
+}
+
 /// 提取描述
 fn extract_description(lines: &[&str], name: &str) -> String {
   let mut description = String::new();
@@ -410,6 +1453,7 @@ fn extract_examples(lines: &[&str], name: &str) -> Vec<Example> {
       examples.push(Example {
         description: desc,
         code,
+        source: None,
       });
 
       if examples.len() >= 10 {
@@ -454,6 +1498,7 @@ fn extract_options_as_examples(lines: &[&str], name: &str) -> Vec<Example> {
         examples.push(Example {
           description: desc,
           code: format!("{} {}", name, opt),
+          source: None,
         });
 
         if examples.len() >= 5 {
@@ -468,8 +1513,15 @@ fn extract_options_as_examples(lines: &[&str], name: &str) -> Vec<Example> {
 
 /// 解析选项行
 fn parse_option_line(line: &str) -> Option<(String, String)> {
-  // 格式: "-v, --verbose  Description"
-  let parts: Vec<&str> = line.splitn(2, "  ").collect();
+  // 格式: "-v, --verbose  Description"（或以制表符分隔，如 bash help）
+  let sep = [line.find("  "), line.find('\t')]
+    .into_iter()
+    .flatten()
+    .min();
+  let parts: Vec<&str> = match sep {
+    Some(idx) => vec![&line[..idx], &line[idx..]],
+    None => vec![line],
+  };
 
   if parts.len() == 2 {
     let opt = parts[0].trim();
@@ -491,6 +1543,41 @@ fn parse_option_line(line: &str) -> Option<(String, String)> {
   None
 }
 
+/// 解析选项段落，如 "-f, --file=FILE  read from FILE"、"--color[=WHEN]  colorize the output"
+fn parse_option_entry(text: &str) -> Option<OptionEntry> {
+  let sep = [text.find("  "), text.find('\t')]
+    .into_iter()
+    .flatten()
+    .min()?;
+  let (spec, description) = (text[..sep].trim(), text[sep..].trim());
+  if description.is_empty() {
+    return None;
+  }
+
+  let mut flags = Vec::new();
+  let mut arg = None;
+  for part in spec.split(',').map(str::trim) {
+    if !part.starts_with('-') {
+      continue;
+    }
+    let end = part.find(['=', ' ', '[', '<']).unwrap_or(part.len());
+    flags.push(part[..end].to_string());
+    let rest = part[end..].trim_start_matches('=').trim();
+    if !rest.is_empty() {
+      arg = Some(rest.replace("[=", "["));
+    }
+  }
+  if flags.is_empty() {
+    return None;
+  }
+
+  Some(OptionEntry {
+    flags,
+    arg,
+    description: description.split_whitespace().collect::<Vec<_>>().join(" "),
+  })
+}
+
 /// 提取行内描述
 fn extract_inline_description(line: &str) -> String {
   // 尝试提取 # comment 或命令后的描述
@@ -501,8 +1588,8 @@ fn extract_inline_description(line: &str) -> String {
   "Example usage".to_string()
 }
 
-/// 获取当前平台
-fn get_platform() -> String {
+/// 获取当前平台（tldr 的平台名）
+pub fn get_platform() -> String {
   if cfg!(target_os = "windows") {
     "windows".to_string()
   } else if cfg!(target_os = "macos") {
@@ -512,6 +1599,42 @@ fn get_platform() -> String {
   }
 }
 
+/// 根据配置的 allow / deny 列表判断 learn-all 是否应学习该命令
+/// deny 优先；allow 为空表示不限制
+pub fn is_learnable(name: &str, config: &LearnConfig) -> bool {
+  if config.deny.iter().any(|p| glob_match(p, name)) {
+    return false;
+  }
+  config.allow.is_empty() || config.allow.iter().any(|p| glob_match(p, name))
+}
+
+/// 简单 glob 匹配（`*` 匹配任意字符序列，`?` 匹配单个字符）
+fn glob_match(pattern: &str, name: &str) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let name: Vec<char> = name.chars().collect();
+  let (mut p, mut n) = (0, 0);
+  // 最近一个 * 的位置及其匹配到的名称位置，用于回溯
+  let mut star: Option<(usize, usize)> = None;
+
+  while n < name.len() {
+    if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+      p += 1;
+      n += 1;
+    } else if p < pattern.len() && pattern[p] == '*' {
+      star = Some((p, n));
+      p += 1;
+    } else if let Some((sp, sn)) = star {
+      p = sp + 1;
+      n = sn + 1;
+      star = Some((sp, sn + 1));
+    } else {
+      return false;
+    }
+  }
+
+  pattern[p..].iter().all(|&c| c == '*')
+}
+
 /// 获取指定 section 的所有 man 页面列表
 /// 返回 (命令名, 描述) 列表
 /// 注意：仅在 Unix 系统 (Linux/macOS) 上可用
@@ -651,12 +1774,18 @@ fn get_man_page_with_section_unix(cmd: &str, section: &str) -> anyhow::Result<(S
         stderr.trim()
       )
     }
-    Err(e) => {
-      if e.kind() == std::io::ErrorKind::NotFound {
-        anyhow::bail!("'man' command not available (program not found)");
-      }
-      anyhow::bail!("Failed to run man: {}", e)
-    }
+    // 未安装 man 时直接解析 MANPATH 中的源文件
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => roff::get_page(cmd, Some(section)),
+    Err(e) => anyhow::bail!("Failed to run man: {}", e),
+  }
+}
+
+/// learn-all 在 Linux/macOS 上的默认来源：有 man 时用 man，否则直接解析 MANPATH
+pub fn default_man_source() -> &'static str {
+  if find_binary("man").is_some() {
+    "man"
+  } else {
+    "manpath"
   }
 }
 
@@ -666,6 +1795,7 @@ pub fn list_available_commands(source: &str) -> anyhow::Result<Vec<(String, Stri
   match source {
     "powershell" => list_powershell_cmdlets(),
     "path" => list_path_commands(),
+    "builtin" => list_shell_builtins(),
     #[cfg(not(target_os = "windows"))]
     "man" => list_man_pages("1"),
     #[cfg(target_os = "windows")]
@@ -685,12 +1815,33 @@ pub fn list_available_commands(source: &str) -> anyhow::Result<Vec<(String, Stri
       }
     }
     _ => anyhow::bail!(
-      "Unknown source '{}'. Use 'man', 'powershell', 'path', or 'auto'.",
+      "Unknown source '{}'. Use 'man', 'powershell', 'path', 'builtin', or 'auto'.",
       source
     ),
   }
 }
 
+/// 列出 bash 内建命令（`compgen -b`）
+fn list_shell_builtins() -> anyhow::Result<Vec<(String, String)>> {
+  let output = Command::new("bash")
+    .args(["-c", "compgen -b"])
+    .stdin(std::process::Stdio::null())
+    .output()
+    .map_err(|e| anyhow::anyhow!("Failed to run bash: {}", e))?;
+
+  let mut builtins: Vec<(String, String)> = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .map(str::trim)
+    // 跳过 "." ":" "[" 等符号命令
+    .filter(|name| name.chars().next().is_some_and(|c| c.is_ascii_alphabetic()))
+    .map(|name| (name.to_string(), "Shell builtin".to_string()))
+    .collect();
+
+  builtins.sort();
+  builtins.dedup();
+  Ok(builtins)
+}
+
 /// 列出 PowerShell cmdlet
 fn list_powershell_cmdlets() -> anyhow::Result<Vec<(String, String)>> {
   #[cfg(not(target_os = "windows"))]
@@ -837,8 +1988,33 @@ fn list_path_commands_unix() -> anyhow::Result<Vec<(String, String)>> {
 mod tests {
   use super::*;
 
+  #[tokio::test]
+  async fn test_from_url() {
+    let page = "Usage: tool [OPTIONS]\n\nOptions:\n  -v, --verbose  Print more\n";
+    let router = axum::Router::new()
+      .route("/tool.txt", axum::routing::get(move || async move { page }))
+      .route(
+        "/huge.txt",
+        axum::routing::get(|| async { "x".repeat(MAX_URL_BYTES + 1) }),
+      );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    // 报告的是下载的字节数，而不是解析后内容的长度
+    let (cmd, bytes) = from_url("tool", &format!("{}/tool.txt", base), "rtfm-test")
+      .await
+      .unwrap();
+    assert_eq!(bytes, page.len());
+    assert_eq!(cmd.name, "tool");
+
+    let error = from_url("tool", &format!("{}/huge.txt", base), "rtfm-test")
+      .await
+      .unwrap_err();
+    assert!(error.to_string().contains("larger than 5 MB"));
+  }
+
   #[test]
-  #[cfg(not(target_os = "windows"))]
   fn test_strip_ansi_codes() {
     let input = "\x1b[1mBold\x1b[0m text";
     let output = strip_ansi_codes(input);
@@ -862,6 +2038,9 @@ Usage: mycmd [OPTIONS] <FILE>
 
 Options:
   -v, --verbose  Enable verbose output
+  -o, --output=FILE
+                 Write to FILE instead of
+                 standard output
   -h, --help     Show help
 
 Examples:
@@ -871,6 +2050,303 @@ Examples:
     let cmd = parse_help_content("mycmd", content, "--help");
     assert_eq!(cmd.name, "mycmd");
     assert!(!cmd.description.is_empty());
+
+    let labels: Vec<String> = cmd.options.iter().map(OptionEntry::label).collect();
+    assert_eq!(labels, ["-v, --verbose", "-o, --output FILE", "-h, --help"]);
+    assert_eq!(
+      cmd.options[1].description,
+      "Write to FILE instead of standard output"
+    );
+  }
+
+  #[test]
+  fn test_parse_option_entry() {
+    let option = parse_option_entry("--color[=WHEN]  colorize the output").unwrap();
+    assert_eq!(option.flags, ["--color"]);
+    assert_eq!(option.arg.as_deref(), Some("[WHEN]"));
+    let option = parse_option_entry("-n, --lines=[-]NUM  print the first NUM lines").unwrap();
+    assert_eq!(option.flags, ["-n", "--lines"]);
+    assert_eq!(option.arg.as_deref(), Some("[-]NUM"));
+    assert!(parse_option_entry("--verbose").is_none());
+  }
+
+  #[test]
+  fn test_parse_man_sections() {
+    let content = "LS(1)                    User Commands                    LS(1)
+
+NAME
+       ls - list directory contents
+
+SYNOPSIS
+       ls [OPTION]... [FILE]...
+
+DESCRIPTION
+       List information about the FILEs (the current directory by default).
+
+       -a, --all
+              do not ignore entries starting with .
+
+       -l     use a long listing format
+
+EXAMPLES
+       List all files in long format:
+
+           ls -la
+
+       ls -t
+              Sort by modification time.
+
+GNU coreutils 9.1                September 2022                   LS(1)
+";
+    let cmd = parse_help_content("ls", content, "man");
+    assert_eq!(cmd.description, "list directory contents");
+
+    let titles: Vec<&str> = cmd.sections.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles, ["NAME", "SYNOPSIS", "DESCRIPTION", "EXAMPLES"]);
+    assert_eq!(
+      cmd.section("synopsis").unwrap().content,
+      "ls [OPTION]... [FILE]..."
+    );
+
+    // EXAMPLES 章节优先
+    assert_eq!(cmd.examples.len(), 2);
+    assert_eq!(cmd.examples[0].description, "List all files in long format");
+    assert_eq!(cmd.examples[0].code, "ls -la");
+    assert_eq!(cmd.examples[1].description, "Sort by modification time.");
+    // 选项取自 DESCRIPTION
+    assert_eq!(cmd.options.len(), 2);
+    assert_eq!(cmd.options[0].flags, ["-a", "--all"]);
+    assert_eq!(
+      cmd.options[0].description,
+      "do not ignore entries starting with ."
+    );
+    assert_eq!(cmd.options[1].label(), "-l");
+
+    // 无 EXAMPLES 时退回到选项
+    let without_examples = &content[..content.find("EXAMPLES").unwrap()];
+    let cmd = parse_help_content("ls", without_examples, "man");
+    assert_eq!(cmd.examples[0].code, "ls --all");
+    assert_eq!(
+      cmd.examples[0].description,
+      "do not ignore entries starting with ."
+    );
+    assert_eq!(cmd.examples[1].code, "ls -l");
+  }
+
+  #[test]
+  fn test_parse_version_line() {
+    assert_eq!(
+      parse_version_line("\nripgrep 13.0.0\n-SIMD -AVX (compiled)\n"),
+      Some("ripgrep 13.0.0".to_string())
+    );
+    assert_eq!(
+      parse_version_line("mycmd: unrecognized option '--version' (1)"),
+      None
+    );
+    assert_eq!(parse_version_line("no digits here"), None);
+    assert_eq!(parse_version_line(""), None);
+  }
+
+  #[test]
+  fn test_parse_version_number() {
+    for (line, number) in [
+      ("git version 2.43.0", "2.43.0"),
+      ("ls (GNU coreutils) 9.4", "9.4"),
+      (
+        "GNU bash, version 5.2.21(1)-release (x86_64-pc-linux-gnu)",
+        "5.2.21",
+      ),
+      ("v20.11.0", "20.11.0"),
+      ("tmux 3.3a", "3.3a"),
+      ("curl 8.5.0 (x86_64-pc-linux-gnu) libcurl/8.5.0", "8.5.0"),
+      ("jq-1.7.1", "1.7.1"),
+      ("htop 3", "3"),
+    ] {
+      assert_eq!(
+        parse_version_number(line).as_deref(),
+        Some(number),
+        "{}",
+        line
+      );
+    }
+    assert_eq!(parse_version_number("no digits here"), None);
+  }
+
+  #[test]
+  fn test_is_learnable() {
+    assert!(glob_match("mkfs*", "mkfs.ext4"));
+    assert!(glob_match("*.sh", "deploy.sh"));
+    assert!(glob_match("git-?", "git-a"));
+    assert!(!glob_match("git-?", "git-ab"));
+    assert!(!glob_match("mkfs*", "xmkfs"));
+
+    let mut config = LearnConfig::default();
+    assert!(!is_learnable("reboot", &config));
+    assert!(is_learnable("xargs", &config));
+
+    config.allow = vec!["git*".to_string(), "cargo".to_string()];
+    assert!(is_learnable("git-log", &config));
+    assert!(is_learnable("cargo", &config));
+    assert!(!is_learnable("ls", &config));
+  }
+
+  #[test]
+  fn test_builtin_help() {
+    let content = "NAME\n    cd - Change the shell working directory.\n\nSYNOPSIS\n    cd [-L|[-P [-e]] [-@]] [dir]\n\nDESCRIPTION\n    Change the shell working directory.\n\n    Options:\n      -L\tforce symbolic links to be followed\n";
+    let cmd = parse_help_content("cd", content, "builtin");
+    assert_eq!(cmd.category, "builtin");
+    assert_eq!(cmd.description, "Change the shell working directory.");
+    assert_eq!(cmd.examples[0].code, "cd -L");
+
+    let page = "ZSHBUILTINS(1)\n\nSHELL BUILTIN COMMANDS\n       bye    Same as exit.\n\n       cd [ -qsLP ] [ arg ]\n       cd [ -qsLP ] old new\n              Change the current directory.\n\n       chdir  Same as cd.\n";
+    let entry = extract_zsh_builtin(page, "cd").unwrap();
+    assert!(entry.contains("SYNOPSIS\n    cd [ -qsLP ] [ arg ]"));
+    assert!(entry.contains("Change the current directory."));
+    assert!(!entry.contains("chdir"));
+  }
+
+  #[test]
+  fn test_parse_powershell_full_help() {
+    let content = "
+NAME
+    Get-ChildItem
+
+SYNOPSIS
+    Gets the items and child items in one or more specified locations.
+
+
+SYNTAX
+    Get-ChildItem [[-Path] <String[]>] [-Recurse] [<CommonParameters>]
+
+
+PARAMETERS
+    -Path <String[]>
+        Specifies a path to one or more locations.
+
+        Required?                    false
+
+    -Recurse [<SwitchParameter>]
+        Gets the items in the specified locations and in all child items.
+
+    ----------- Example 1: Get child items from a file system directory -----------
+
+    PS C:\\> Get-ChildItem -Path C:\\Test
+
+    ---------- EXAMPLE 2 ----------
+
+    Get-ChildItem -Path C:\\Test\\*.txt -Recurse
+
+    This command lists all text files recursively.
+
+RELATED LINKS
+    about_Providers
+";
+    let cmd = parse_help_content("Get-ChildItem", content, POWERSHELL_SOURCE);
+    assert_eq!(
+      cmd.description,
+      "Gets the items and child items in one or more specified locations."
+    );
+    assert_eq!(cmd.examples.len(), 2);
+    assert_eq!(
+      cmd.examples[0].description,
+      "Get child items from a file system directory"
+    );
+    assert_eq!(cmd.examples[0].code, "Get-ChildItem -Path C:\\Test");
+    assert_eq!(
+      cmd.examples[1].description,
+      "This command lists all text files recursively."
+    );
+    assert!(cmd.section("PARAMETERS").is_some());
+
+    let params =
+      extract_powershell_parameters(&cmd.section("PARAMETERS").unwrap().content, "Get-ChildItem");
+    assert_eq!(params[0].code, "Get-ChildItem -Path <String[]>");
+    assert_eq!(
+      params[0].description,
+      "Specifies a path to one or more locations."
+    );
+    assert_eq!(params.len(), 2);
+
+    assert_eq!(cmd.options.len(), 2);
+    assert_eq!(cmd.options[0].label(), "-Path <String[]>");
+    assert_eq!(cmd.options[1].label(), "-Recurse");
+  }
+
+  #[test]
+  fn test_split_invocation() {
+    assert_eq!(split_invocation("ffmpeg -h full"), ["ffmpeg", "-h", "full"]);
+    assert_eq!(
+      split_invocation(r#"sh -c "mytool help all""#),
+      ["sh", "-c", "mytool help all"]
+    );
+    assert_eq!(split_invocation(r#"tool """#), ["tool", ""]);
+    assert!(split_invocation("   ").is_empty());
+  }
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn test_help_override() {
+    let mut config = LearnConfig::default();
+    config.overrides.insert(
+      "mytool".to_string(),
+      "echo Usage: mytool [OPTIONS] --frobnicate".to_string(),
+    );
+    let (content, source) = get_help_output("mytool", &config).unwrap();
+    assert!(content.starts_with("Usage: mytool"));
+    assert_eq!(source, "echo Usage: mytool [OPTIONS] --frobnicate");
+  }
+
+  #[test]
+  fn test_from_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("mycmd.txt");
+    std::fs::write(
+      &path,
+      "mycmd - frobnicate things\n\nUsage: mycmd [OPTIONS]\n\x1b[1mOptions:\x1b[0m\n  -v, --verbose  Be loud\n",
+    )
+    .unwrap();
+
+    let cmd = from_file("mycmd", &path).unwrap();
+    assert_eq!(cmd.name, "mycmd");
+    assert_eq!(cmd.description, "frobnicate things");
+    assert!(cmd.content.starts_with("Source: file: "));
+    assert!(!cmd.content.contains('\x1b'));
+
+    assert!(from_file("mycmd", &temp_dir.path().join("missing.txt")).is_err());
+  }
+
+  #[test]
+  fn test_html_to_text() {
+    let html = "<html><head><style>body{}</style></head><body><h1>rg</h1>\
+                <p>Search &amp; replace</p><ul><li>rg foo</li></ul><script>x()</script></body></html>";
+    let text = html_to_text(html);
+    assert!(text.contains("rg\n"));
+    assert!(text.contains("Search & replace"));
+    assert!(text.contains("- rg foo"));
+    assert!(!text.contains("body{}"));
+    assert!(!text.contains("x()"));
+  }
+
+  #[test]
+  fn test_strip_markdown() {
+    let md = "# ripgrep\n\nSee [the guide](GUIDE.md) for **details**.\n\n```\n$ rg foo\n```\n";
+    let text = strip_markdown(md);
+    assert!(text.contains("ripgrep"));
+    assert!(text.contains("See the guide for details."));
+    assert!(text.contains("$ rg foo"));
+    assert!(!text.contains("```"));
+  }
+
+  #[test]
+  fn test_normalize_doc_url() {
+    assert_eq!(
+      normalize_doc_url("https://github.com/BurntSushi/ripgrep/blob/master/README.md"),
+      "https://raw.githubusercontent.com/BurntSushi/ripgrep/master/README.md"
+    );
+    assert_eq!(
+      normalize_doc_url("https://example.com/doc"),
+      "https://example.com/doc"
+    );
   }
 
   #[test]
@@ -0,0 +1,416 @@
+//! PowerShell 结构化帮助
+//!
+//! `Get-Help <cmd> -Full | ConvertTo-Json` 给出帮助对象的各个字段（Synopsis、syntax、parameters、examples），
+//! 比 `Out-String` 排版后的文本更可靠：示例代码可以有多行，参数的类型、别名和是否必需都有单独的字段。
+//! 解析后按 `Get-Help -Full` 的文本格式重新排版保存，重新解析（如 rtfm bench）时按文本解析。
+//!
+//! ConvertTo-Json 把只有一个元素的集合输出为对象而不是数组，文字段落是 `[{"Text": "..."}]`，
+//! 取值时两种形式都要接受。
+
+use serde_json::Value;
+
+use crate::storage::{Example, OptionEntry};
+
+/// 结构化帮助的来源标识
+pub const JSON_SOURCE: &str = "Get-Help -Full (PowerShell JSON)";
+
+/// 最多保留的示例数（与文本解析相同）
+const MAX_EXAMPLES: usize = 10;
+
+/// 从 syntax 生成示例时最多取的参数集
+const MAX_SYNTAX_EXAMPLES: usize = 5;
+
+/// 解析出的帮助
+pub struct Help {
+  /// 按 `Get-Help -Full` 格式排版的文本
+  pub text: String,
+  /// 帮助中的示例；没有示例时由各参数集的必需参数生成
+  pub examples: Vec<Example>,
+  /// 参数
+  pub options: Vec<OptionEntry>,
+}
+
+/// Windows: 获取 cmdlet 的结构化帮助，返回 (JSON, 来源)
+#[cfg(target_os = "windows")]
+pub fn get_help_json(cmd: &str) -> anyhow::Result<(String, String)> {
+  // 名称匹配多个主题时 Get-Help 返回摘要列表，不是某个命令的帮助
+  let script = format!(
+    "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+     $help = Get-Help '{}' -Full -ErrorAction Stop; \
+     if ($help -is [array]) {{ exit 1 }}; \
+     $help | ConvertTo-Json -Depth 8 -Compress",
+    cmd.replace('\'', "''")
+  );
+  let output = std::process::Command::new("powershell")
+    .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+    .output()
+    .map_err(|e| anyhow::anyhow!("Failed to run PowerShell Get-Help: {}", e))?;
+  let json = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  if !output.status.success() || !json.starts_with('{') {
+    anyhow::bail!("Get-Help found no help for '{}'", cmd);
+  }
+  Ok((json, JSON_SOURCE.to_string()))
+}
+
+/// 解析 `Get-Help -Full | ConvertTo-Json` 的输出；不是单个命令的帮助对象时返回 None
+pub fn parse_json(name: &str, json: &str) -> Option<Help> {
+  let help: Value = serde_json::from_str(json).ok()?;
+  let help = help.as_object()?;
+  let title = help
+    .get("Name")
+    .or_else(|| help.get("details").and_then(|d| d.get("name")))
+    .and_then(Value::as_str)
+    .map(str::trim)
+    .filter(|n| !n.is_empty())
+    .unwrap_or(name);
+
+  let syntax: Vec<String> = items(help.get("syntax").and_then(|s| s.get("syntaxItem")))
+    .into_iter()
+    .map(syntax_line)
+    .collect();
+  // 没有安装帮助文件时 Synopsis 只是语法
+  let synopsis = help
+    .get("Synopsis")
+    .and_then(Value::as_str)
+    .map(str::trim)
+    .filter(|s| !s.is_empty() && !s.starts_with(title))
+    .map(String::from)
+    .or_else(|| {
+      help
+        .get("details")
+        .map(|d| text(d.get("description")))
+        .filter(|s| !s.is_empty())
+    });
+  let description = text(help.get("description"));
+  let parameters = items(help.get("parameters").and_then(|p| p.get("parameter")));
+  let examples: Vec<Example> = items(help.get("examples").and_then(|e| e.get("example")))
+    .into_iter()
+    .filter_map(example)
+    .take(MAX_EXAMPLES)
+    .collect();
+
+  let mut text = format!("NAME\n    {}\n\n", title);
+  if let Some(synopsis) = &synopsis {
+    text.push_str(&format!("SYNOPSIS\n{}\n", indent(synopsis, 4)));
+  }
+  if !syntax.is_empty() {
+    text.push_str("SYNTAX\n");
+    for line in &syntax {
+      text.push_str(&format!("    {}\n\n", line));
+    }
+  }
+  if !description.is_empty() {
+    text.push_str(&format!("DESCRIPTION\n{}\n", indent(&description, 4)));
+  }
+  if !parameters.is_empty() {
+    text.push_str("PARAMETERS\n");
+    for param in &parameters {
+      text.push_str(&format!("    {}\n", parameter_label(param)));
+      let description = text_of(param, "description");
+      if !description.is_empty() {
+        text.push_str(&indent(&description, 8));
+      }
+      text.push('\n');
+    }
+  }
+  if !examples.is_empty() {
+    text.push_str("EXAMPLES\n");
+    for (i, example) in examples.iter().enumerate() {
+      text.push_str(&format!(
+        "    ---------- Example {}: {} ----------\n\n{}\n",
+        i + 1,
+        example.description,
+        indent(&example.code, 4)
+      ));
+    }
+  }
+
+  let examples = if examples.is_empty() {
+    items(help.get("syntax").and_then(|s| s.get("syntaxItem")))
+      .into_iter()
+      .take(MAX_SYNTAX_EXAMPLES)
+      .map(|item| Example {
+        description: "Usage".to_string(),
+        code: usage(title, item),
+        source: None,
+      })
+      .collect()
+  } else {
+    examples
+  };
+
+  Some(Help {
+    text,
+    examples,
+    options: parameters.into_iter().map(option).collect(),
+  })
+}
+
+/// 集合字段：数组，或只有一个元素时的对象
+fn items(value: Option<&Value>) -> Vec<&Value> {
+  match value {
+    Some(Value::Array(values)) => values.iter().collect(),
+    Some(Value::Null) | None => Vec::new(),
+    Some(value) => vec![value],
+  }
+}
+
+/// 文字字段：字符串，或 `[{"Text": "..."}]` 段落（空段落之间用空行分隔）
+fn text(value: Option<&Value>) -> String {
+  match value {
+    Some(Value::String(s)) => s.trim().to_string(),
+    Some(value @ (Value::Array(_) | Value::Object(_))) => items(Some(value))
+      .into_iter()
+      .filter_map(|p| {
+        p.get("Text")
+          .or_else(|| p.get("text"))
+          .and_then(Value::as_str)
+      })
+      .map(str::trim)
+      .filter(|p| !p.is_empty())
+      .collect::<Vec<_>>()
+      .join("\n\n"),
+    _ => String::new(),
+  }
+}
+
+fn text_of(value: &Value, key: &str) -> String {
+  text(value.get(key))
+}
+
+/// 参数的字符串字段（required 等在不同版本中是字符串或布尔值）
+fn field(value: &Value, key: &str) -> String {
+  match value.get(key) {
+    Some(Value::String(s)) => s.trim().to_string(),
+    Some(Value::Bool(b)) => b.to_string(),
+    Some(Value::Object(o)) => o
+      .get("value")
+      .or_else(|| o.get("#text"))
+      .and_then(Value::as_str)
+      .unwrap_or_default()
+      .trim()
+      .to_string(),
+    _ => String::new(),
+  }
+}
+
+/// 参数值的类型；开关参数没有
+fn value_type(param: &Value) -> Option<String> {
+  let kind = Some(field(param, "parameterValue"))
+    .filter(|t| !t.is_empty())
+    .or_else(|| param.get("type").map(|t| field(t, "name")))
+    .filter(|t| !t.is_empty())?;
+  (!kind.contains("SwitchParameter")).then_some(kind)
+}
+
+fn is_required(param: &Value) -> bool {
+  field(param, "required").eq_ignore_ascii_case("true")
+}
+
+/// PARAMETERS 中的参数行，如 `-Path <String[]>`、`-Recurse [<SwitchParameter>]`
+fn parameter_label(param: &Value) -> String {
+  let name = field(param, "name");
+  match value_type(param) {
+    Some(kind) => format!("-{} <{}>", name, kind),
+    None => format!("-{} [<SwitchParameter>]", name),
+  }
+}
+
+/// 参数集的语法行，如 `Get-ChildItem [[-Path] <String[]>] [-Recurse]`
+fn syntax_line(item: &Value) -> String {
+  let mut line = field(item, "name");
+  for param in items(item.get("parameter")) {
+    let name = field(param, "name");
+    let positional = !matches!(field(param, "position").as_str(), "" | "named" | "Named");
+    let flag = if positional {
+      format!("[-{}]", name)
+    } else {
+      format!("-{}", name)
+    };
+    let part = match value_type(param) {
+      Some(kind) => format!("{} <{}>", flag, kind),
+      None => format!("-{}", name),
+    };
+    line.push(' ');
+    if is_required(param) {
+      line.push_str(&part);
+    } else {
+      line.push_str(&format!("[{}]", part));
+    }
+  }
+  line
+}
+
+/// 由参数集的必需参数生成的用法，参数值为 `{{占位符}}`
+fn usage(name: &str, item: &Value) -> String {
+  let mut code = name.to_string();
+  for param in items(item.get("parameter"))
+    .into_iter()
+    .filter(|p| is_required(p))
+  {
+    let param_name = field(param, "name");
+    code.push_str(&format!(" -{}", param_name));
+    if value_type(param).is_some() {
+      code.push_str(&format!(" {{{{{}}}}}", param_name.to_lowercase()));
+    }
+  }
+  code
+}
+
+/// 帮助中的示例：说明取标题冒号后的部分，没有时取备注的第一段；代码去掉 PS 提示符
+fn example(value: &Value) -> Option<Example> {
+  let code = field(value, "code");
+  let code = code
+    .lines()
+    .map(|line| super::strip_ps_prompt(line.trim_end()))
+    .collect::<Vec<_>>()
+    .join("\n")
+    .trim()
+    .to_string();
+  if code.is_empty() {
+    return None;
+  }
+
+  let title = field(value, "title");
+  let title = title.trim_matches(|c: char| c == '-' || c.is_whitespace());
+  let remarks = text_of(value, "remarks");
+  let description = title
+    .split_once(':')
+    .map(|(_, t)| t.trim().to_string())
+    .filter(|t| !t.is_empty())
+    .or_else(|| super::first_paragraph(&remarks))
+    .unwrap_or_else(|| title.to_string());
+  Some(Example {
+    description,
+    code,
+    source: None,
+  })
+}
+
+/// 参数作为选项：名称和别名，参数值的类型，说明的第一段
+fn option(param: &Value) -> OptionEntry {
+  let mut flags = vec![format!("-{}", field(param, "name"))];
+  let aliases = field(param, "aliases");
+  if aliases != "None" {
+    flags.extend(
+      aliases
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|a| format!("-{}", a)),
+    );
+  }
+  OptionEntry {
+    flags,
+    arg: value_type(param).map(|kind| format!("<{}>", kind)),
+    description: super::first_paragraph(&text_of(param, "description")).unwrap_or_default(),
+  }
+}
+
+fn indent(text: &str, width: usize) -> String {
+  let pad = " ".repeat(width);
+  let mut out = String::new();
+  for line in text.lines() {
+    if line.trim().is_empty() {
+      out.push('\n');
+    } else {
+      out.push_str(&format!("{}{}\n", pad, line.trim_end()));
+    }
+  }
+  out.push('\n');
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::learn::parse_help_content;
+
+  const HELP: &str = r#"{
+    "Name": "Get-ChildItem",
+    "Synopsis": "Gets the items and child items in one or more specified locations.",
+    "details": {"name": "Get-ChildItem", "description": [{"Text": "Gets the items."}]},
+    "description": [{"Text": "The Get-ChildItem cmdlet gets the items in one or more specified locations."}],
+    "syntax": {"syntaxItem": {"name": "Get-ChildItem", "parameter": [
+      {"name": "Path", "required": "false", "position": "0", "parameterValue": "String[]"},
+      {"name": "Recurse", "required": "false", "position": "named"}
+    ]}},
+    "parameters": {"parameter": [
+      {"name": "Path", "required": "false", "position": "0", "aliases": "None",
+       "parameterValue": "String[]", "type": {"name": "String[]"},
+       "description": [{"Text": "Specifies a path to one or more locations."}, {"Text": "Wildcards are accepted."}]},
+      {"name": "Recurse", "required": "false", "position": "named", "aliases": "s, r",
+       "type": {"name": "System.Management.Automation.SwitchParameter"},
+       "description": [{"Text": "Gets the items in all child containers."}]}
+    ]},
+    "examples": {"example": [
+      {"title": "---------- Example 1: Get child items from a directory ----------",
+       "code": "Get-ChildItem -Path C:\\Test\nGet-ChildItem -Path D:\\Test",
+       "remarks": [{"Text": "This example lists two directories."}]},
+      {"title": "---------- EXAMPLE 2 ----------",
+       "code": "PS C:\\> Get-ChildItem -Recurse",
+       "remarks": [{"Text": ""}, {"Text": "Lists items recursively."}]}
+    ]}
+  }"#;
+
+  #[test]
+  fn test_parse_json() {
+    let cmd = parse_help_content("Get-ChildItem", HELP, JSON_SOURCE);
+    assert_eq!(
+      cmd.description,
+      "Gets the items and child items in one or more specified locations."
+    );
+    assert_eq!(cmd.examples.len(), 2);
+    assert_eq!(
+      cmd.examples[0].description,
+      "Get child items from a directory"
+    );
+    assert_eq!(
+      cmd.examples[0].code,
+      "Get-ChildItem -Path C:\\Test\nGet-ChildItem -Path D:\\Test"
+    );
+    assert_eq!(cmd.examples[1].description, "Lists items recursively.");
+    assert_eq!(cmd.examples[1].code, "Get-ChildItem -Recurse");
+
+    assert_eq!(cmd.options.len(), 2);
+    assert_eq!(cmd.options[0].label(), "-Path <String[]>");
+    assert_eq!(
+      cmd.options[0].description,
+      "Specifies a path to one or more locations."
+    );
+    assert_eq!(cmd.options[1].label(), "-Recurse, -s, -r");
+    assert!(cmd
+      .content
+      .contains("Get-ChildItem [[-Path] <String[]>] [-Recurse]"));
+
+    // 保存的是排版后的文本，重新解析时得到相同的描述和参数
+    let body = cmd
+      .content
+      .strip_prefix(&format!("Source: {}\n\n", JSON_SOURCE))
+      .unwrap();
+    let reparsed = parse_help_content("Get-ChildItem", body, JSON_SOURCE);
+    assert_eq!(reparsed.description, cmd.description);
+    assert_eq!(reparsed.options[0].label(), "-Path <String[]>");
+    assert_eq!(reparsed.examples[0].code, cmd.examples[0].code);
+  }
+
+  #[test]
+  fn test_parse_json_without_examples() {
+    // 没有安装帮助文件：Synopsis 只是语法，没有示例
+    let json = r#"{
+      "Name": "Set-Thing",
+      "Synopsis": "Set-Thing -Name <string> [-Force]",
+      "syntax": {"syntaxItem": [{"name": "Set-Thing", "parameter": [
+        {"name": "Name", "required": true, "position": "named", "parameterValue": "string"},
+        {"name": "Force", "required": false, "position": "named"}
+      ]}]}
+    }"#;
+    let help = parse_json("Set-Thing", json).unwrap();
+    assert!(!help.text.contains("SYNOPSIS"));
+    assert_eq!(help.examples.len(), 1);
+    assert_eq!(help.examples[0].code, "Set-Thing -Name {{name}}");
+
+    // Get-Help 匹配多个主题时输出的是摘要列表
+    assert!(parse_json("Get-*", r#"[{"Name": "Get-Item"}]"#).is_none());
+  }
+}
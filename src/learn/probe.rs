@@ -0,0 +1,218 @@
+//! 带超时的帮助探测
+//!
+//! 有些命令并不理会 --help：交互式程序会等待终端输入，GUI 程序会尝试打开窗口。
+//! 探测时断开标准输入与显示器、放入独立进程组并设置超时，
+//! 再根据输出把这类命令识别为"不可学习"，让 learn-all 将其记为带原因的跳过而不是失败。
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// 进程退出后等待残留输出（如后台子进程仍持有管道）的时间
+const OUTPUT_GRACE: Duration = Duration::from_millis(300);
+
+/// 忽略 --help 的命令，应跳过而不是计为失败
+#[derive(Debug, thiserror::Error)]
+pub enum Unlearnable {
+  #[error("'{0}' looks interactive: no help output within {1}s")]
+  Interactive(String, u64),
+  #[error("'{0}' looks like a GUI application: {1}")]
+  Gui(String, String),
+}
+
+impl Unlearnable {
+  /// 用于统计的简短原因
+  pub fn reason(&self) -> &'static str {
+    match self {
+      Unlearnable::Interactive(..) => "interactive",
+      Unlearnable::Gui(..) => "GUI",
+    }
+  }
+}
+
+/// 探测结果
+#[derive(Debug, Default)]
+pub struct ProbeOutput {
+  pub success: bool,
+  pub stdout: String,
+  pub stderr: String,
+  /// 超时后被终止
+  pub timed_out: bool,
+}
+
+/// 运行命令，超时则终止整个进程组
+/// 标准输入为空、移除 DISPLAY / WAYLAND_DISPLAY，GUI 程序会因无法连接显示器而报错退出
+pub fn run(cmd: &mut Command, timeout: Duration) -> std::io::Result<ProbeOutput> {
+  cmd
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .env_remove("DISPLAY")
+    .env_remove("WAYLAND_DISPLAY");
+
+  // 独立进程组：读取终端的程序会被 SIGTTIN 暂停而不是抢占用户输入，超时后也能连同子进程一起终止
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::CommandExt;
+    cmd.process_group(0);
+  }
+
+  let mut child = cmd.spawn()?;
+  let stdout = spawn_reader(child.stdout.take());
+  let stderr = spawn_reader(child.stderr.take());
+
+  let deadline = Instant::now() + timeout;
+  let status = loop {
+    if let Some(status) = child.try_wait()? {
+      break Some(status);
+    }
+    if Instant::now() >= deadline {
+      kill(&mut child);
+      break None;
+    }
+    std::thread::sleep(Duration::from_millis(10));
+  };
+
+  let grace = Instant::now() + OUTPUT_GRACE;
+  Ok(ProbeOutput {
+    success: status.is_some_and(|s| s.success()),
+    stdout: collect(&stdout, grace),
+    stderr: collect(&stderr, grace),
+    timed_out: status.is_none(),
+  })
+}
+
+fn spawn_reader<R: Read + Send + 'static>(reader: Option<R>) -> mpsc::Receiver<Vec<u8>> {
+  let (tx, rx) = mpsc::channel();
+  if let Some(mut reader) = reader {
+    std::thread::spawn(move || {
+      let mut buf = [0u8; 8192];
+      while let Ok(n) = reader.read(&mut buf) {
+        if n == 0 || tx.send(buf[..n].to_vec()).is_err() {
+          break;
+        }
+      }
+    });
+  }
+  rx
+}
+
+/// 收集输出直到管道关闭或超过期限
+fn collect(rx: &mpsc::Receiver<Vec<u8>>, deadline: Instant) -> String {
+  let mut out = Vec::new();
+  while let Ok(chunk) = rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+    out.extend(chunk);
+  }
+  String::from_utf8_lossy(&out).into_owned()
+}
+
+fn kill(child: &mut std::process::Child) {
+  #[cfg(unix)]
+  {
+    let _ = Command::new("kill")
+      .args(["-KILL", "--", &format!("-{}", child.id())])
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status();
+  }
+  let _ = child.kill();
+  let _ = child.wait();
+}
+
+impl ProbeOutput {
+  /// 输出是否可作为帮助；超时的进程往往只打印了错误信息，要求包含 usage
+  pub fn is_help(&self, content: &str) -> bool {
+    super::is_valid_help_content(content)
+      && (!self.timed_out || content.to_lowercase().contains("usage"))
+  }
+
+  /// 没有得到帮助时判断原因：报告无法连接显示器为 GUI，超时或要求终端为交互式
+  pub fn unlearnable(&self, cmd: &str, timeout: Duration) -> Option<Unlearnable> {
+    let output = format!("{}\n{}", self.stdout, self.stderr);
+    if let Some(line) = display_error(&output) {
+      return Some(Unlearnable::Gui(cmd.to_string(), line));
+    }
+    if self.timed_out || needs_terminal(&output) {
+      return Some(Unlearnable::Interactive(cmd.to_string(), timeout.as_secs()));
+    }
+    None
+  }
+}
+
+fn display_error(output: &str) -> Option<String> {
+  const PATTERNS: &[&str] = &[
+    "cannot open display",
+    "can't open display",
+    "unable to open display",
+    "could not connect to display",
+    "failed to connect to wayland",
+    "no display name",
+    "qt.qpa.xcb",
+    "qt.qpa.plugin",
+  ];
+  output
+    .lines()
+    .find(|line| {
+      let lower = line.to_lowercase();
+      PATTERNS.iter().any(|p| lower.contains(p))
+    })
+    .map(|line| line.trim().to_string())
+}
+
+fn needs_terminal(output: &str) -> bool {
+  const PATTERNS: &[&str] = &[
+    "not a tty",
+    "not a terminal",
+    "inappropriate ioctl for device",
+    "must be run from a terminal",
+    "requires a terminal",
+    "no tty present",
+    "/dev/tty",
+  ];
+  let lower = output.to_lowercase();
+  PATTERNS.iter().any(|p| lower.contains(p))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn test_probe_classification() {
+    let timeout = Duration::from_millis(300);
+
+    // 等待输入的命令：超时且没有输出
+    let mut waits = Command::new("sh");
+    waits.args(["-c", "read line < /dev/tty; sleep 5"]);
+    let output = run(&mut waits, timeout).unwrap();
+    assert!(output.timed_out);
+    assert!(!output.is_help(&output.stderr));
+    assert!(matches!(
+      output.unlearnable("waits", timeout),
+      Some(Unlearnable::Interactive(..))
+    ));
+
+    // GUI 程序：报告无法打开显示器
+    let mut gui = Command::new("sh");
+    gui.args([
+      "-c",
+      "echo 'xeyes: Error: Can'\\''t open display: ' >&2; exit 1",
+    ]);
+    let output = run(&mut gui, timeout).unwrap();
+    assert!(!output.timed_out);
+    assert_eq!(
+      output.unlearnable("xeyes", timeout).unwrap().reason(),
+      "GUI"
+    );
+
+    // 正常的帮助输出
+    let mut help = Command::new("sh");
+    help.args(["-c", "echo 'Usage: tool [OPTIONS]'"]);
+    let output = run(&mut help, timeout).unwrap();
+    assert!(output.success);
+    assert_eq!(output.stdout.trim(), "Usage: tool [OPTIONS]");
+    assert!(output.unlearnable("tool", timeout).is_none());
+  }
+}
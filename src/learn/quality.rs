@@ -0,0 +1,105 @@
+//! 学习结果质量评分
+//!
+//! 启发式解析并不总能得到可用的结果（如只有一行 usage、把错误信息当成帮助）。
+//! 这里按描述、示例、选项和内容长度打分，用于在 learn / learn-all / stats 中标记低质量条目。
+
+use crate::storage::Command as StorageCommand;
+
+/// 低于此分数视为低质量
+pub const LOW_QUALITY_THRESHOLD: u8 = 50;
+
+/// 质量评估结果
+#[derive(Debug, Clone)]
+pub struct Quality {
+  /// 0-100
+  pub score: u8,
+  /// 扣分原因
+  pub issues: Vec<&'static str>,
+}
+
+impl Quality {
+  pub fn is_low(&self) -> bool {
+    self.score < LOW_QUALITY_THRESHOLD
+  }
+}
+
+/// 评估命令的质量
+/// 描述 30 分、示例 30 分、选项/章节 20 分、内容长度 20 分
+pub fn assess(cmd: &StorageCommand) -> Quality {
+  let mut score = 0u8;
+  let mut issues = Vec::new();
+
+  // 描述
+  let description = cmd.description.trim();
+  if description.is_empty() || description.ends_with("(learned from local system)") {
+    issues.push("no description");
+  } else if description.len() < 10 || description.eq_ignore_ascii_case(&cmd.name) {
+    score += 10;
+    issues.push("description too short");
+  } else {
+    score += 30;
+  }
+
+  // 示例
+  match cmd.examples.len() {
+    0 => issues.push("no examples"),
+    1 | 2 => {
+      score += 15;
+      issues.push("few examples");
+    }
+    _ => score += 30,
+  }
+
+  // 选项（选项列表或以选项形式出现的示例）或结构化章节
+  let has_options = !cmd.options.is_empty()
+    || cmd
+      .examples
+      .iter()
+      .any(|e| e.code.split_whitespace().any(|w| w.starts_with('-')));
+  if has_options || !cmd.sections.is_empty() {
+    score += 20;
+  } else {
+    issues.push("no options or sections");
+  }
+
+  // 内容长度（去掉 "Source: ..." 前缀的原始帮助）
+  let raw_len = cmd
+    .content
+    .split_once("\n\n")
+    .map(|(_, body)| body)
+    .unwrap_or(&cmd.content)
+    .trim()
+    .len();
+  if raw_len < 100 {
+    issues.push("help text very short");
+  } else if raw_len > 500_000 {
+    score += 10;
+    issues.push("help text unusually long");
+  } else {
+    score += 20;
+  }
+
+  Quality { score, issues }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::learn::parse_help_content;
+
+  #[test]
+  fn test_assess_quality() {
+    let good = parse_help_content(
+      "mycmd",
+      "mycmd - frobnicate files in place\n\nUsage: mycmd [OPTIONS] <FILE>\n\nOptions:\n  -v, --verbose  Enable verbose output\n  -q, --quiet    Suppress all output\n  -n, --dry-run  Show what would be done\n",
+      "--help",
+    );
+    let quality = assess(&good);
+    assert!(!quality.is_low(), "{:?}", quality);
+
+    let junk = parse_help_content("mycmd", "error: unknown flag", "--help");
+    let quality = assess(&junk);
+    assert!(quality.is_low());
+    assert!(quality.issues.contains(&"help text very short"));
+  }
+}
@@ -0,0 +1,187 @@
+//! 重新学习二进制已变化的命令（rtfm learn --refresh / --refresh-outdated、serve 模式的定时任务）
+//!
+//! 比较当前的版本号和修改时间与学习时记录的值，只重新获取有变化的命令的帮助；
+//! 保存、索引以及 LLM 增强由调用方负责。
+
+use crate::config::LearnConfig;
+use crate::plugin::{ParseFailure, Registry};
+use crate::storage::{Command, SourceInfo};
+
+/// 判断命令是否有变化的依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+  /// 版本行或二进制修改时间不同（--refresh）
+  Binary,
+  /// 只比较版本号（--refresh-outdated），重新安装同一版本不算变化
+  Version,
+}
+
+/// 一个已学习命令的检查结果
+#[derive(Debug)]
+pub enum Outcome {
+  /// 二进制有变化，已重新解析帮助
+  Refreshed {
+    cmd: Box<Command>,
+    from: Option<String>,
+    to: Option<String>,
+    /// parser 插件失败，使用了内置解析器
+    plugin_failure: Option<ParseFailure>,
+  },
+  /// 版本号和修改时间都没有变化
+  Unchanged,
+  /// 本机找不到二进制
+  Missing,
+  /// URL / 文件来源及 shell 内建命令，与本机二进制无关
+  Skipped,
+  /// 无法获取帮助
+  Failed,
+}
+
+/// URL / 文件来源及 shell 内建命令
+fn unrelated(info: &SourceInfo) -> bool {
+  info.source.starts_with("url:") || info.source.starts_with("file:") || info.source == "builtin"
+}
+
+/// 学习时记录的版本号，较早学习的命令从版本行中解析
+fn learned_version(info: &SourceInfo) -> Option<String> {
+  info.version_number.clone().or_else(|| {
+    info
+      .version
+      .as_deref()
+      .and_then(super::parse_version_number)
+  })
+}
+
+/// 检查一个已学习的命令，有变化时重新获取并解析帮助
+pub fn refresh(old: &Command, plugins: &Registry, config: &LearnConfig, check: Check) -> Outcome {
+  let info = old.source_info.clone().unwrap_or_default();
+  if unrelated(&info) {
+    return Outcome::Skipped;
+  }
+
+  let Some(binary) = super::find_binary(&old.name) else {
+    return Outcome::Missing;
+  };
+
+  let version = super::get_version(&old.name);
+  let changed = match check {
+    Check::Binary => {
+      let mtime = super::binary_mtime(&binary);
+      info.learned_at.is_empty()
+        || (info.version.is_some() && info.version != version)
+        || (info.binary_mtime.is_some() && info.binary_mtime != mtime)
+    }
+    // 任一方没有版本号时无法比较，不重新学习
+    Check::Version => {
+      let installed = version.as_deref().and_then(super::parse_version_number);
+      let learned = learned_version(&info);
+      learned.is_some() && installed.is_some() && learned != installed
+    }
+  };
+  if !changed {
+    return Outcome::Unchanged;
+  }
+
+  let prefer_man = info.source.starts_with("man");
+  let Ok((content, source)) = super::get_local_help(&old.name, prefer_man, config) else {
+    return Outcome::Failed;
+  };
+
+  let (mut cmd, plugin_failure) = plugins.parse_help_content(&old.name, &content, &source);
+  super::record_binary(&mut cmd);
+  Outcome::Refreshed {
+    cmd: Box::new(cmd),
+    from: info.version,
+    to: version,
+    plugin_failure,
+  }
+}
+
+/// 本机的二进制在学习之后是否有变化（修改时间不同）；只读取文件信息、不运行命令，TUI 详情据此提示。
+/// 与 Check::Binary 的依据相同，rtfm learn --refresh 重新学习后记录新的修改时间，提示随之消失
+pub fn is_stale(cmd: &Command) -> bool {
+  let Some(info) = &cmd.source_info else {
+    return false;
+  };
+  let Some(mtime) = info.binary_mtime else {
+    return false;
+  };
+  if unrelated(info) {
+    return false;
+  }
+  super::find_binary(&cmd.name).is_some_and(|binary| super::binary_mtime(&binary) != Some(mtime))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{SourceInfo, COMMAND_SCHEMA};
+
+  fn learned(name: &str, source: &str) -> Command {
+    Command {
+      name: name.to_string(),
+      description: String::new(),
+      category: "local".to_string(),
+      platform: "common".to_string(),
+      lang: "local".to_string(),
+      examples: Vec::new(),
+      content: String::new(),
+      sections: Vec::new(),
+      options: Vec::new(),
+      source_info: Some(SourceInfo {
+        source: source.to_string(),
+        learned_at: "2024-01-01T00:00:00Z".to_string(),
+        ..Default::default()
+      }),
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    }
+  }
+
+  #[test]
+  fn test_refresh_skips_unrelated_sources() {
+    let plugins = Registry::default();
+    let config = LearnConfig::default();
+    for source in ["url:https://example.com", "file:/tmp/help.txt", "builtin"] {
+      assert!(matches!(
+        refresh(&learned("cd", source), &plugins, &config, Check::Binary),
+        Outcome::Skipped
+      ));
+    }
+    assert!(matches!(
+      refresh(
+        &learned("rtfm-no-such-binary", "help"),
+        &plugins,
+        &config,
+        Check::Version
+      ),
+      Outcome::Missing
+    ));
+  }
+
+  #[test]
+  fn test_learned_version() {
+    let mut cmd = learned("git", "help");
+    let info = cmd.source_info.as_mut().unwrap();
+    assert_eq!(learned_version(info), None);
+    // 较早学习的命令只有版本行
+    info.version = Some("git version 2.43.0".to_string());
+    assert_eq!(learned_version(info).as_deref(), Some("2.43.0"));
+    info.version_number = Some("2.44.0".to_string());
+    assert_eq!(learned_version(info).as_deref(), Some("2.44.0"));
+    assert!(!is_stale(&cmd));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_is_stale() {
+    let binary = crate::learn::find_binary("sh").unwrap();
+    let mut cmd = learned("sh", "help");
+    let info = cmd.source_info.as_mut().unwrap();
+    info.binary_mtime = crate::learn::binary_mtime(&binary);
+    assert!(!is_stale(&cmd));
+    // 与 --refresh（Check::Binary）相同的依据：修改时间不同即提示
+    cmd.source_info.as_mut().unwrap().binary_mtime = Some(0);
+    assert!(is_stale(&cmd));
+  }
+}
@@ -0,0 +1,1714 @@
+//! 直接解析 MANPATH 中的 roff 源文件
+//!
+//! `man` 每页都要启动 groff，批量学习数千个页面时很慢，输出还随 locale 变化，
+//! 而精简的容器 / 发行版常常根本没有安装 man-db。这里直接遍历 MANPATH，
+//! 解压 .gz 源文件，把常用的 man(7) 与 mdoc(7) 宏渲染成与 `man` 输出相同布局的文本
+//! （章节标题顶格、正文缩进 7 格），交给 `parse_help_content` 按章节解析。
+//!
+//! 渲染后的文本丢失了字体等结构，示例另由 [`extract_examples`] 直接从源文件提取：
+//! EXAMPLES 中的命令行（斜体参数写成 `{{占位符}}`）与选项列表的标签。
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::storage::Example;
+
+/// 未指定章节时的查找顺序（与 man-db 默认一致）
+const SECTION_ORDER: &[&str] = &["1", "8", "3", "2", "5", "4", "9", "6", "7"];
+
+/// MANPATH 未设置（或含空项）时使用的默认目录
+const DEFAULT_MAN_DIRS: &[&str] = &[
+  "/usr/local/share/man",
+  "/usr/local/man",
+  "/usr/share/man",
+  "/opt/homebrew/share/man",
+  "/opt/local/share/man",
+];
+
+/// 正文缩进（与 man 输出一致）
+const INDENT: usize = 7;
+
+/// 渲染宽度
+const WIDTH: usize = 80;
+
+/// 从 EXAMPLES 章节提取的示例上限
+const MAX_EXAMPLES: usize = 10;
+
+/// 从选项列表提取的示例上限
+const MAX_OPTIONS: usize = 5;
+
+/// 来源标识，如 "manpath(1)"
+pub fn source_name(section: &str) -> String {
+  format!("manpath({})", section)
+}
+
+/// 要搜索的 man 目录
+/// MANPATH 中的空项（如 ":/opt/man" 或 "/opt/man::"）表示插入默认目录
+pub fn man_dirs() -> Vec<PathBuf> {
+  let defaults = || DEFAULT_MAN_DIRS.iter().map(PathBuf::from);
+
+  let dirs: Vec<PathBuf> = match std::env::var_os("MANPATH") {
+    Some(manpath) if !manpath.is_empty() => {
+      let mut dirs = Vec::new();
+      for dir in std::env::split_paths(&manpath) {
+        if dir.as_os_str().is_empty() {
+          dirs.extend(defaults());
+        } else {
+          dirs.push(dir);
+        }
+      }
+      dirs
+    }
+    _ => defaults().collect(),
+  };
+
+  let mut seen = HashSet::new();
+  dirs
+    .into_iter()
+    .filter(|d| d.is_dir() && seen.insert(d.clone()))
+    .collect()
+}
+
+/// 列出某章节的所有页面，返回 (名称, 描述)；描述需要解析页面，这里留空
+pub fn list_pages(section: &str) -> Vec<(String, String)> {
+  let mut seen = HashSet::new();
+  let mut pages = Vec::new();
+
+  for dir in man_dirs() {
+    let Ok(entries) = std::fs::read_dir(dir.join(format!("man{}", section))) else {
+      continue;
+    };
+    let mut names: Vec<String> = entries
+      .flatten()
+      .filter_map(|e| page_name(&e.file_name().to_string_lossy(), section))
+      .collect();
+    names.sort();
+    for name in names {
+      // 靠前的 MANPATH 目录优先
+      if seen.insert(name.clone()) {
+        pages.push((name, String::new()));
+      }
+    }
+  }
+
+  pages
+}
+
+/// 各章节中所有页面的名称，exclude 目录（如 rtfm 生成页面的目录）不计入
+pub fn page_names(exclude: &Path) -> HashSet<String> {
+  let mut names = HashSet::new();
+  for dir in man_dirs().into_iter().filter(|d| d != exclude) {
+    for section in SECTION_ORDER {
+      let Ok(entries) = std::fs::read_dir(dir.join(format!("man{}", section))) else {
+        continue;
+      };
+      names.extend(
+        entries
+          .flatten()
+          .filter_map(|e| page_name(&e.file_name().to_string_lossy(), section)),
+      );
+    }
+  }
+  names
+}
+
+/// 从文件名解析页面名："ls.1.gz" -> "ls"，"CA.pl.1ssl.gz" -> "CA.pl"
+fn page_name(file_name: &str, section: &str) -> Option<String> {
+  let stem = strip_compression(file_name);
+  let (name, ext) = stem.rsplit_once('.')?;
+  (!name.is_empty() && ext.starts_with(section)).then(|| name.to_string())
+}
+
+fn strip_compression(file_name: &str) -> &str {
+  [".gz", ".bz2", ".xz", ".lzma", ".zst", ".Z"]
+    .iter()
+    .find_map(|ext| file_name.strip_suffix(ext))
+    .unwrap_or(file_name)
+}
+
+/// 获取并渲染页面，返回 (内容, 来源)
+/// 未指定章节时按 man 的默认顺序查找
+pub fn get_page(name: &str, section: Option<&str>) -> anyhow::Result<(String, String)> {
+  let (roff, section) = find_source(name, section)?;
+  let content = render(&roff);
+  if content.trim().is_empty() {
+    anyhow::bail!("man page source for '{}' is empty", name);
+  }
+  Ok((content, source_name(&section)))
+}
+
+/// 按来源（"man"、"man(8)"、"manpath(1)"）重新读取页面的 roff 源并提取结构化示例，
+/// 找不到源文件时返回空列表
+pub fn page_examples(name: &str, source: &str) -> Vec<Example> {
+  let section = if source == "man" {
+    None
+  } else {
+    let section = ["man(", "manpath("]
+      .iter()
+      .find_map(|prefix| source.strip_prefix(prefix))
+      .and_then(|rest| rest.strip_suffix(')'));
+    match section {
+      Some(section) => Some(section),
+      None => return Vec::new(),
+    }
+  };
+  find_source(name, section)
+    .map(|(roff, _)| extract_examples(&roff, name))
+    .unwrap_or_default()
+}
+
+/// 查找并读取页面的 roff 源，返回 (源文件内容, 章节)
+fn find_source(name: &str, section: Option<&str>) -> anyhow::Result<(String, String)> {
+  let dirs = man_dirs();
+  if dirs.is_empty() {
+    anyhow::bail!("No man directories found (set MANPATH)");
+  }
+
+  let sections = match section {
+    Some(s) => vec![s],
+    None => SECTION_ORDER.to_vec(),
+  };
+
+  for section in sections {
+    if let Some(path) = find_page(&dirs, name, section) {
+      return Ok((read_page(&path)?, section.to_string()));
+    }
+  }
+
+  anyhow::bail!("No man page source for '{}' in MANPATH", name)
+}
+
+/// 在各目录中查找页面文件：先尝试常见文件名，再扫描目录匹配带后缀的章节（如 1ssl）
+fn find_page(dirs: &[PathBuf], name: &str, section: &str) -> Option<PathBuf> {
+  for dir in dirs {
+    let section_dir = dir.join(format!("man{}", section));
+    for ext in ["gz", ""] {
+      let mut file_name = format!("{}.{}", name, section);
+      if !ext.is_empty() {
+        file_name = format!("{}.{}", file_name, ext);
+      }
+      let path = section_dir.join(file_name);
+      if path.is_file() {
+        return Some(path);
+      }
+    }
+  }
+
+  dirs.iter().find_map(|dir| {
+    std::fs::read_dir(dir.join(format!("man{}", section)))
+      .ok()?
+      .flatten()
+      .find(|e| page_name(&e.file_name().to_string_lossy(), section).as_deref() == Some(name))
+      .map(|e| e.path())
+  })
+}
+
+/// 读取 roff 源文件（支持 .gz），并跟随 `.so` 重定向
+fn read_page(path: &Path) -> anyhow::Result<String> {
+  let mut path = path.to_path_buf();
+
+  // `.so man1/foo.1` 相对于 man 根目录；限制层数避免循环
+  for _ in 0..4 {
+    let content = read_source(&path)?;
+    let Some(target) = so_target(&content) else {
+      return Ok(content);
+    };
+    let root = path
+      .parent()
+      .and_then(Path::parent)
+      .ok_or_else(|| anyhow::anyhow!("Cannot resolve .so {} from {}", target, path.display()))?;
+    let target = root.join(target);
+    path = if target.is_file() {
+      target
+    } else {
+      let mut gz = target.into_os_string();
+      gz.push(".gz");
+      PathBuf::from(gz)
+    };
+  }
+
+  anyhow::bail!("Too many .so redirections in {}", path.display())
+}
+
+fn read_source(path: &Path) -> anyhow::Result<String> {
+  let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+  let mut bytes = Vec::new();
+  if file_name.ends_with(".gz") {
+    flate2::read::GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut bytes)?;
+  } else if strip_compression(&file_name) != file_name {
+    anyhow::bail!("Unsupported compression: {}", path.display());
+  } else {
+    bytes = std::fs::read(path)?;
+  }
+  Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 页面只有一条 `.so` 请求时返回目标路径
+fn so_target(content: &str) -> Option<&str> {
+  let mut lines = content
+    .lines()
+    .map(str::trim)
+    .filter(|l| !l.is_empty() && !is_comment(l));
+  let target = lines.next()?.strip_prefix(".so")?.trim();
+  (lines.next().is_none() && !target.is_empty()).then_some(target)
+}
+
+fn is_comment(line: &str) -> bool {
+  line.starts_with(".\\\"") || line.starts_with("'\\\"") || line.starts_with("\\\"")
+}
+
+/// mdoc 列表类型
+#[derive(Clone, Copy, PartialEq)]
+enum ListKind {
+  Tag,
+  Bullet,
+  Dash,
+  Enum,
+  Item,
+  Column,
+}
+
+/// 预处理后的源文件行（已合并续行，去掉注释、宏定义和条件块）
+enum Line {
+  /// 请求或宏的名称与参数（转义尚未展开）
+  Request(String, Vec<String>),
+  Text(String),
+}
+
+fn parse_lines(roff: &str) -> Vec<Line> {
+  let mut lines = Vec::new();
+  let mut skip_braces = 0i32;
+  let mut in_macro_def = false;
+  let mut pending = String::new();
+
+  for raw in roff.lines() {
+    // 行尾反斜杠表示续行
+    if raw.ends_with('\\') && !raw.ends_with("\\\\") {
+      pending.push_str(&raw[..raw.len() - 1]);
+      continue;
+    }
+    let owned;
+    let line = if pending.is_empty() {
+      raw
+    } else {
+      pending.push_str(raw);
+      owned = std::mem::take(&mut pending);
+      owned.as_str()
+    };
+
+    // 宏定义 / 忽略块：直到 ".."
+    if in_macro_def {
+      in_macro_def = line.trim() != "..";
+      continue;
+    }
+
+    // 条件块：跳过直到花括号配平（man 页面中几乎只用于排版预设）
+    if skip_braces > 0 {
+      skip_braces += brace_delta(line);
+      continue;
+    }
+
+    if line.starts_with('.') || line.starts_with('\'') {
+      if is_comment(line) {
+        continue;
+      }
+      let args = split_args(line[1..].trim_start());
+      let Some((name, args)) = args.split_first() else {
+        continue;
+      };
+      match name.as_str() {
+        "de" | "de1" | "am" | "ig" => in_macro_def = true,
+        "if" | "ie" | "el" | "while" => skip_braces = brace_delta(line).max(0),
+        _ => lines.push(Line::Request(name.clone(), args.to_vec())),
+      }
+    } else {
+      lines.push(Line::Text(line.to_string()));
+    }
+  }
+
+  lines
+}
+
+/// 把 roff 源渲染为 man 风格的纯文本
+pub fn render(roff: &str) -> String {
+  let mut r = Renderer::new();
+  for line in parse_lines(roff) {
+    match line {
+      Line::Request(name, args) => r.request(&name, &args),
+      Line::Text(text) => r.text_line(&text),
+    }
+  }
+  r.finish()
+}
+
+/// `\{` 与 `\}` 的数量差
+fn brace_delta(line: &str) -> i32 {
+  line.matches("\\{").count() as i32 - line.matches("\\}").count() as i32
+}
+
+/// 按空白切分请求参数，支持双引号（"" 表示字面引号）与 `\ ` 转义空格
+fn split_args(s: &str) -> Vec<String> {
+  let mut args = Vec::new();
+  let mut chars = s.chars().peekable();
+
+  loop {
+    while chars.peek().is_some_and(|c| *c == ' ' || *c == '\t') {
+      chars.next();
+    }
+    let Some(&first) = chars.peek() else {
+      break;
+    };
+    // 行内注释
+    if first == '\\' && s.contains("\\\"") {
+      let rest: String = chars.clone().collect();
+      if rest.starts_with("\\\"") {
+        break;
+      }
+    }
+
+    let mut arg = String::new();
+    if first == '"' {
+      chars.next();
+      while let Some(c) = chars.next() {
+        match c {
+          '"' if chars.peek() == Some(&'"') => {
+            chars.next();
+            arg.push('"');
+          }
+          '"' => break,
+          '\\' => {
+            arg.push(c);
+            if let Some(next) = chars.next() {
+              arg.push(next);
+            }
+          }
+          _ => arg.push(c),
+        }
+      }
+    } else {
+      while let Some(&c) = chars.peek() {
+        if c == ' ' || c == '\t' {
+          break;
+        }
+        chars.next();
+        arg.push(c);
+        if c == '\\' {
+          if let Some(next) = chars.next() {
+            arg.push(next);
+          }
+        }
+      }
+    }
+    args.push(arg);
+  }
+
+  args
+}
+
+/// 展开转义序列：去掉字体/字号等排版控制，特殊字符转换为文本
+fn expand(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut chars = s.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      out.push(c);
+      continue;
+    }
+    let Some(esc) = chars.next() else {
+      break;
+    };
+    match esc {
+      // 注释
+      '"' | '#' => break,
+      'e' | '\\' => out.push('\\'),
+      '-' => out.push('-'),
+      '.' => out.push('.'),
+      '\'' => out.push('\''),
+      '`' => out.push('`'),
+      ' ' | '~' | '0' | 't' => out.push(' '),
+      '&' | '|' | '^' | ')' | 'c' | 'd' | 'u' | 'p' | 'z' | '%' | ':' | '/' | ',' => {}
+      '(' => {
+        let name: String = chars.by_ref().take(2).collect();
+        out.push_str(special_char(&name));
+      }
+      '[' => {
+        let name = take_bracketed(&mut chars);
+        out.push_str(&named_char(&name));
+      }
+      '*' => {
+        let name = take_name(&mut chars);
+        out.push_str(string_value(&name));
+      }
+      // 字体、寄存器、颜色等：跳过名称
+      'f' | 'F' | 'n' | 'g' | 'm' | 'M' | 'V' | 'Y' | '$' | 'k' => {
+        take_name(&mut chars);
+      }
+      // 字号：\s0、\s-2、\s(12、\s[12]
+      's' => {
+        if chars.peek().is_some_and(|c| *c == '+' || *c == '-') {
+          chars.next();
+        }
+        match chars.peek() {
+          Some('(') | Some('[') => {
+            take_name(&mut chars);
+          }
+          _ => {
+            for _ in 0..2 {
+              if !chars.peek().is_some_and(char::is_ascii_digit) {
+                break;
+              }
+              chars.next();
+            }
+          }
+        }
+      }
+      // 带定界参数的定位/绘图序列：\h'1n'、\w'...' 等
+      'h' | 'v' | 'w' | 'o' | 'l' | 'L' | 'D' | 'X' | 'b' | 'x' | 'Z' | 'A' | 'B' | 'N' | 'R'
+      | 'S' | 'H' | 'C' => {
+        if let Some(delim) = chars.next() {
+          for c in chars.by_ref() {
+            if c == delim {
+              break;
+            }
+          }
+        }
+      }
+      other => out.push(other),
+    }
+  }
+
+  out
+}
+
+/// 展开示例命令行中的转义，斜体（参数）写成 tldr 占位符：`\fIfile\fR` -> `{{file}}`
+fn code_text(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut raw = String::new();
+  let mut italic = false;
+  let mut chars = s.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      raw.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('f') => {
+        let font = take_name(&mut chars);
+        out.push_str(&expand(&std::mem::take(&mut raw)));
+        let to_italic = matches!(font.as_str(), "I" | "2" | "BI" | "CI");
+        if to_italic != italic {
+          out.push_str(if to_italic { "{{" } else { "}}" });
+          italic = to_italic;
+        }
+      }
+      Some(esc) => {
+        raw.push('\\');
+        raw.push(esc);
+      }
+      None => raw.push('\\'),
+    }
+  }
+  out.push_str(&expand(&raw));
+  if italic {
+    out.push_str("}}");
+  }
+
+  tidy_placeholders(&out)
+}
+
+/// 去掉空占位符，并把占位符两端的空白移到外面
+fn tidy_placeholders(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut rest = s;
+  while let Some(start) = rest.find("{{") {
+    out.push_str(&rest[..start]);
+    let after = &rest[start + 2..];
+    let Some(end) = after.find("}}") else {
+      out.push_str(after);
+      return out;
+    };
+    let inner = &after[..end];
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+      out.push_str(inner);
+    } else {
+      if !out.ends_with(char::is_whitespace) {
+        out.push_str(&inner[..inner.len() - inner.trim_start().len()]);
+      }
+      out.push_str(&format!("{{{{{}}}}}", trimmed));
+      out.push_str(&inner[inner.trim_end().len()..]);
+    }
+    rest = &after[end + 2..];
+  }
+  out.push_str(rest);
+  out
+}
+
+/// man(7) 字体宏（.B、.I、.BR 等）的文本；placeholders 为 true 时斜体部分写成占位符
+fn font_macro(name: &str, args: &[String], placeholders: bool) -> Option<String> {
+  let roman = |s: &str| {
+    if placeholders {
+      code_text(s)
+    } else {
+      expand(s)
+    }
+  };
+  let italic = |s: &str| {
+    if placeholders {
+      tidy_placeholders(&format!("{{{{{}}}}}", code_text(s)))
+    } else {
+      expand(s)
+    }
+  };
+  if args.is_empty() {
+    return None;
+  }
+  match name {
+    "B" | "SM" | "SB" => Some(roman(&args.join(" "))),
+    "I" => Some(italic(&args.join(" "))),
+    "BR" | "RB" | "IR" | "RI" | "BI" | "IB" => {
+      // 交替字体：第一个参数用宏名第一个字母的字体，依此类推
+      let fonts: Vec<char> = name.chars().collect();
+      Some(
+        args
+          .iter()
+          .enumerate()
+          .map(|(i, arg)| {
+            if fonts[i % 2] == 'I' {
+              italic(arg)
+            } else {
+              roman(arg)
+            }
+          })
+          .collect(),
+      )
+    }
+    _ => None,
+  }
+}
+
+/// 读取转义名称：`(xx`、`[name]` 或单个字符
+fn take_name(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+  match chars.next() {
+    Some('(') => chars.by_ref().take(2).collect(),
+    Some('[') => take_bracketed(chars),
+    Some(c) => c.to_string(),
+    None => String::new(),
+  }
+}
+
+fn take_bracketed(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+  chars.by_ref().take_while(|c| *c != ']').collect()
+}
+
+/// 两字符特殊字符 `\(xx`
+fn special_char(name: &str) -> &'static str {
+  match name {
+    "em" => "\u{2014}",
+    "en" => "\u{2013}",
+    "hy" | "mi" | "\\-" => "-",
+    "bu" => "\u{2022}",
+    "aq" | "oq" | "cq" | "fm" => "'",
+    "dq" | "lq" | "rq" | "Fo" | "Fc" => "\"",
+    "ga" => "`",
+    "ha" | "a^" => "^",
+    "ti" | "a~" => "~",
+    "rs" => "\\",
+    "sl" => "/",
+    "ul" | "ru" => "_",
+    "pl" => "+",
+    "eq" => "=",
+    "mu" => "\u{00d7}",
+    "de" => "\u{00b0}",
+    "co" => "\u{00a9}",
+    "rg" => "\u{00ae}",
+    "tm" => "\u{2122}",
+    "<=" => "\u{2264}",
+    ">=" => "\u{2265}",
+    "!=" => "\u{2260}",
+    "->" => "\u{2192}",
+    "<-" => "\u{2190}",
+    "ba" | "or" => "|",
+    "lB" => "[",
+    "rB" => "]",
+    "lC" => "{",
+    "rC" => "}",
+    "la" => "<",
+    "ra" => ">",
+    "at" => "@",
+    "sh" => "#",
+    "Do" => "$",
+    "ct" => "\u{00a2}",
+    "ss" => "\u{00df}",
+    "*W" => "-",
+    _ => "",
+  }
+}
+
+/// `\[name]`：支持 `\[u2014]` 形式的 Unicode 码点
+fn named_char(name: &str) -> String {
+  if let Some(hex) = name.strip_prefix('u') {
+    if let Some(c) = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+      return c.to_string();
+    }
+  }
+  special_char(name).to_string()
+}
+
+/// 预定义字符串 `\*(xx`（含 Pod::Man 生成页面的常用定义）
+fn string_value(name: &str) -> &'static str {
+  match name {
+    "lq" | "rq" | "L\"" | "R\"" | "Lq" | "Rq" => "\"",
+    "Aq" => "'",
+    "--" => "--",
+    "R" | "rg" => "(R)",
+    "Tm" | "tm" => "(TM)",
+    "PI" => "pi",
+    "C+" => "C++",
+    _ => "",
+  }
+}
+
+/// mdoc 中可在行内调用的宏
+fn is_mdoc_inline(token: &str) -> bool {
+  matches!(
+    token,
+    "Fl"
+      | "Ar"
+      | "Cm"
+      | "Op"
+      | "Oo"
+      | "Oc"
+      | "Xr"
+      | "Ns"
+      | "Pa"
+      | "Ev"
+      | "Li"
+      | "Em"
+      | "Sy"
+      | "Dq"
+      | "Qq"
+      | "Sq"
+      | "Ql"
+      | "Pq"
+      | "Aq"
+      | "Ic"
+      | "Va"
+      | "Dv"
+      | "Nm"
+      | "No"
+      | "Sx"
+      | "Er"
+      | "Fn"
+      | "Fa"
+      | "Ft"
+      | "Lk"
+      | "Mt"
+      | "Ta"
+      | "Ux"
+      | "Bx"
+      | "Ox"
+      | "Nx"
+      | "Fx"
+      | "Dx"
+      | "At"
+  )
+}
+
+fn is_punctuation(token: &str) -> bool {
+  matches!(token, "." | "," | ";" | ":" | "?" | "!" | ")" | "]" | "|")
+}
+
+struct Renderer {
+  lines: Vec<String>,
+  /// 当前段落的单词
+  words: Vec<String>,
+  /// 当前左边距
+  indent: usize,
+  /// .RS / .Bl 保存的边距
+  indent_stack: Vec<usize>,
+  /// 标签段落的正文相对边距再缩进
+  tagged: bool,
+  /// 下一行文本是 .TP 的标签
+  pending_tag: bool,
+  /// 段落首行前缀（如列表项目符号）
+  prefix: Option<String>,
+  /// .nf / .EX 不填充模式
+  nofill: bool,
+  /// .UR / .MT 的链接地址
+  link: Option<String>,
+  /// mdoc 页面名（.Nm 无参数时使用）
+  mdoc_name: String,
+  /// 当前章节标题
+  section: String,
+  /// mdoc 列表栈
+  lists: Vec<(ListKind, usize)>,
+}
+
+impl Renderer {
+  fn new() -> Self {
+    Self {
+      lines: Vec::new(),
+      words: Vec::new(),
+      indent: INDENT,
+      indent_stack: Vec::new(),
+      tagged: false,
+      pending_tag: false,
+      prefix: None,
+      nofill: false,
+      link: None,
+      mdoc_name: String::new(),
+      section: String::new(),
+      lists: Vec::new(),
+    }
+  }
+
+  fn body_indent(&self) -> usize {
+    if self.tagged {
+      self.indent + INDENT
+    } else {
+      self.indent
+    }
+  }
+
+  /// 输出当前段落（按宽度折行）
+  fn flush(&mut self) {
+    if self.words.is_empty() {
+      return;
+    }
+    let indent = self.body_indent();
+    let pad = " ".repeat(indent);
+    let mut line = pad.clone();
+    if let Some(prefix) = self.prefix.take() {
+      line.push_str(&prefix);
+    }
+    let mut empty = true;
+    for word in std::mem::take(&mut self.words) {
+      if !empty && line.chars().count() + 1 + word.chars().count() > WIDTH {
+        self.lines.push(std::mem::replace(&mut line, pad.clone()));
+        empty = true;
+      }
+      if !empty {
+        line.push(' ');
+      }
+      line.push_str(&word);
+      empty = false;
+    }
+    self.lines.push(line);
+  }
+
+  /// 空行（不在开头、章节标题后或已有空行时重复输出）
+  fn blank(&mut self) {
+    self.flush();
+    let Some(last) = self.lines.last() else {
+      return;
+    };
+    let is_header = !last.is_empty() && !last.starts_with(' ');
+    if !last.is_empty() && !is_header {
+      self.lines.push(String::new());
+    }
+  }
+
+  /// 追加文本（已展开转义）
+  fn text(&mut self, text: &str) {
+    if self.pending_tag {
+      self.pending_tag = false;
+      self.flush();
+      self.tagged = false;
+      self
+        .lines
+        .push(format!("{}{}", " ".repeat(self.indent), text.trim()));
+      self.tagged = true;
+      return;
+    }
+    if self.nofill {
+      self.flush();
+      let line = format!("{}{}", " ".repeat(self.body_indent()), text);
+      self.lines.push(line.trim_end().to_string());
+      return;
+    }
+    self
+      .words
+      .extend(text.split_whitespace().map(str::to_string));
+  }
+
+  fn text_line(&mut self, line: &str) {
+    if line.trim().is_empty() {
+      if self.nofill {
+        self.lines.push(String::new());
+      } else {
+        self.blank();
+      }
+      return;
+    }
+    // 填充模式下以空格开头的行另起一行
+    if !self.nofill && line.starts_with(' ') {
+      self.flush();
+    }
+    self.text(&expand(line));
+  }
+
+  fn heading(&mut self, title: &str, indent: usize) {
+    self.flush();
+    if !self.lines.is_empty() {
+      self.lines.push(String::new());
+    }
+    self.indent_stack.clear();
+    self.lists.clear();
+    self.indent = INDENT;
+    self.tagged = false;
+    self.pending_tag = false;
+    self.nofill = false;
+    let title = title.trim();
+    if indent == 0 {
+      self.section = title.to_uppercase();
+      self.lines.push(self.section.clone());
+    } else {
+      self.lines.push(format!("{}{}", " ".repeat(indent), title));
+    }
+  }
+
+  fn push_indent(&mut self, by: usize) {
+    self.flush();
+    self.indent_stack.push(self.indent);
+    self.indent = self.body_indent() + by;
+    self.tagged = false;
+  }
+
+  fn pop_indent(&mut self) {
+    self.flush();
+    if let Some(indent) = self.indent_stack.pop() {
+      self.indent = indent;
+    }
+    self.tagged = false;
+  }
+
+  fn request(&mut self, name: &str, args: &[String]) {
+    let expanded: Vec<String> = args.iter().map(|a| expand(a)).collect();
+    let joined = expanded.join(" ");
+
+    match name {
+      // man(7)
+      "SH" => self.heading(&joined, 0),
+      "SS" => self.heading(&joined, 3),
+      "PP" | "P" | "LP" | "HP" => {
+        self.blank();
+        self.tagged = false;
+      }
+      "TP" | "TQ" => {
+        if name == "TP" {
+          self.blank();
+        } else {
+          self.flush();
+        }
+        self.pending_tag = true;
+      }
+      "IP" => {
+        self.blank();
+        let tag = expanded.first().map(String::as_str).unwrap_or("");
+        if tag.chars().count() <= 2 && !tag.is_empty() {
+          self.tagged = false;
+          self.prefix = Some(format!("{} ", tag));
+        } else if !tag.is_empty() {
+          self.pending_tag = true;
+          self.text(tag);
+        } else {
+          self.tagged = true;
+        }
+      }
+      "B" | "I" | "SM" | "SB" if !joined.is_empty() => self.text(&joined),
+      "BR" | "RB" | "IR" | "RI" | "BI" | "IB" => self.text(&expanded.concat()),
+      "nf" | "EX" | "Vb" => {
+        self.flush();
+        self.nofill = true;
+      }
+      "fi" | "EE" | "Ve" => {
+        self.flush();
+        self.nofill = false;
+      }
+      "br" => self.flush(),
+      "sp" | "Sp" => self.blank(),
+      "RS" => self.push_indent(INDENT),
+      "RE" => self.pop_indent(),
+      "UR" | "MT" => self.link = expanded.first().cloned(),
+      "UE" | "ME" => {
+        if let Some(link) = self.link.take() {
+          let suffix = expanded.first().map(String::as_str).unwrap_or("");
+          self.text(&format!("<{}>{}", link, suffix));
+        }
+      }
+      "SY" => {
+        self.blank();
+        self.text(&joined);
+      }
+      "OP" => self.text(&format!("[{}]", joined)),
+      "YS" => self.blank(),
+
+      // mdoc(7)
+      "Dd" | "Dt" | "Os" => {}
+      "Sh" => self.heading(&joined, 0),
+      "Ss" => self.heading(&joined, 3),
+      "Pp" | "Lp" => {
+        self.blank();
+        if self.lists.is_empty() {
+          self.tagged = false;
+        }
+      }
+      "Nm" if self.mdoc_name.is_empty() => {
+        self.mdoc_name = expanded.first().cloned().unwrap_or_default();
+        self.mdoc_line(name, args);
+      }
+      "Nm" if self.section == "SYNOPSIS" => {
+        self.flush();
+        self.mdoc_line(name, args);
+      }
+      "Nd" => self.text(&format!("- {}", joined)),
+      "Bl" => {
+        let kind = match args.first().map(String::as_str) {
+          Some("-bullet") => ListKind::Bullet,
+          Some("-dash") | Some("-hyphen") => ListKind::Dash,
+          Some("-enum") => ListKind::Enum,
+          Some("-item") => ListKind::Item,
+          Some("-column") => ListKind::Column,
+          _ => ListKind::Tag,
+        };
+        self.blank();
+        self.push_indent(if self.lists.is_empty() { 0 } else { INDENT });
+        self.lists.push((kind, 0));
+      }
+      "El" => {
+        self.lists.pop();
+        self.pop_indent();
+      }
+      "It" => self.mdoc_item(args),
+      "Bd" => {
+        self.blank();
+        self.push_indent(if args.iter().any(|a| a == "-offset") {
+          INDENT
+        } else {
+          0
+        });
+        self.nofill = args.iter().any(|a| a == "-literal" || a == "-unfilled");
+      }
+      "Ed" => {
+        self.nofill = false;
+        self.pop_indent();
+      }
+      "Dl" | "D1" => {
+        self.flush();
+        let line = format!(
+          "{}{}",
+          " ".repeat(self.body_indent() + INDENT),
+          self.mdoc_inline(args)
+        );
+        self.lines.push(line);
+      }
+      "Bf" | "Ef" | "Bk" | "Ek" | "Pf" | "Rs" | "Re" | "An" => {}
+      _ if is_mdoc_inline(name) => self.mdoc_line(name, args),
+
+      // 其余请求（.TH、.IX、.ds、.nr、.ft、.ad、.ne、.in 等）只影响排版，忽略
+      _ => {}
+    }
+  }
+
+  /// 以行内宏开头的 mdoc 行
+  fn mdoc_line(&mut self, name: &str, args: &[String]) {
+    let mut tokens = vec![name.to_string()];
+    tokens.extend(args.iter().cloned());
+    let text = self.mdoc_inline(&tokens);
+    // 标点直接附在上一个词后
+    if let Some(rest) = text.strip_prefix(|c: char| is_punctuation(&c.to_string())) {
+      if let Some(last) = self.words.last_mut() {
+        last.push_str(&text[..text.len() - rest.len()]);
+        if !rest.trim().is_empty() {
+          self.text(rest);
+        }
+        return;
+      }
+    }
+    self.text(&text);
+  }
+
+  /// .It：标签列表输出标签行，符号列表作为段落前缀
+  fn mdoc_item(&mut self, args: &[String]) {
+    let Some((kind, count)) = self.lists.last_mut() else {
+      self.blank();
+      self.pending_tag = true;
+      let tag = self.mdoc_inline(args);
+      self.text(&tag);
+      return;
+    };
+    *count += 1;
+    let (kind, count) = (*kind, *count);
+
+    self.blank();
+    self.tagged = false;
+    match kind {
+      ListKind::Tag => {
+        self.pending_tag = true;
+        let tag = self.mdoc_inline(args);
+        self.text(&tag);
+      }
+      ListKind::Bullet => self.prefix = Some("\u{2022} ".to_string()),
+      ListKind::Dash => self.prefix = Some("- ".to_string()),
+      ListKind::Enum => self.prefix = Some(format!("{}. ", count)),
+      ListKind::Item => {}
+      ListKind::Column => {
+        let cells = args
+          .split(|a| a == "Ta")
+          .map(|cell| self.mdoc_inline(cell))
+          .collect::<Vec<_>>()
+          .join("  ");
+        self.text(&cells);
+        self.flush();
+      }
+    }
+  }
+
+  fn mdoc_inline(&self, tokens: &[String]) -> String {
+    mdoc_inline(tokens, &self.mdoc_name, false)
+  }
+
+  fn finish(mut self) -> String {
+    self.flush();
+    let mut text = self.lines.join("\n");
+    text.truncate(text.trim_end().len());
+    text.push('\n');
+    text
+  }
+}
+
+/// 渲染 mdoc 行内宏序列，如 `Fl o Ar file` -> "-o file"
+/// placeholders 为 true 时参数写成 tldr 占位符："-o {{file}}"
+fn mdoc_inline(tokens: &[String], name: &str, placeholders: bool) -> String {
+  let mut out = String::new();
+  let mut no_space = true;
+  let mut i = 0;
+
+  let push = |out: &mut String, text: &str, no_space: &mut bool| {
+    if !*no_space && !is_punctuation(text) && !out.is_empty() {
+      out.push(' ');
+    }
+    out.push_str(text);
+    *no_space = false;
+  };
+
+  while i < tokens.len() {
+    let token = tokens[i].as_str();
+    i += 1;
+    let take_args = |i: &mut usize| {
+      let start = *i;
+      while *i < tokens.len() && !is_mdoc_inline(&tokens[*i]) {
+        *i += 1;
+      }
+      tokens[start..*i]
+        .iter()
+        .map(|t| expand(t))
+        .collect::<Vec<_>>()
+    };
+
+    match token {
+      "Fl" => {
+        let args = take_args(&mut i);
+        if args.is_empty() {
+          push(&mut out, "-", &mut no_space);
+          no_space = true;
+        }
+        for arg in args {
+          if is_punctuation(&arg) {
+            push(&mut out, &arg, &mut no_space);
+          } else {
+            push(&mut out, &format!("-{}", arg), &mut no_space);
+          }
+        }
+      }
+      "Nm" => {
+        let args = take_args(&mut i);
+        if args.is_empty() {
+          push(&mut out, name, &mut no_space);
+        }
+        for arg in args {
+          push(&mut out, &arg, &mut no_space);
+        }
+      }
+      "Ar" => {
+        let args = take_args(&mut i);
+        if args.is_empty() {
+          let text = if placeholders { "{{file}}" } else { "file ..." };
+          push(&mut out, text, &mut no_space);
+        }
+        for arg in args {
+          if placeholders && !is_punctuation(&arg) {
+            push(&mut out, &format!("{{{{{}}}}}", arg), &mut no_space);
+          } else {
+            push(&mut out, &arg, &mut no_space);
+          }
+        }
+      }
+      "Xr" => {
+        let args = take_args(&mut i);
+        let text = match args.as_slice() {
+          [page, section, rest @ ..] => format!("{}({}){}", page, section, rest.concat()),
+          [page] => page.clone(),
+          [] => String::new(),
+        };
+        push(&mut out, &text, &mut no_space);
+      }
+      "Op" | "Dq" | "Qq" | "Sq" | "Ql" | "Pq" | "Aq" => {
+        // 包围宏作用到行尾
+        let (open, close) = match token {
+          "Op" => ("[", "]"),
+          "Dq" | "Qq" => ("\"", "\""),
+          "Sq" | "Ql" => ("'", "'"),
+          "Pq" => ("(", ")"),
+          _ => ("<", ">"),
+        };
+        let inner = mdoc_inline(&tokens[i..], name, placeholders);
+        i = tokens.len();
+        push(
+          &mut out,
+          &format!("{}{}{}", open, inner, close),
+          &mut no_space,
+        );
+      }
+      "Oo" => {
+        push(&mut out, "[", &mut no_space);
+        no_space = true;
+      }
+      "Oc" => out.push(']'),
+      "Ns" => no_space = true,
+      "Ta" => push(&mut out, " ", &mut no_space),
+      "Ux" | "Bx" | "Ox" | "Nx" | "Fx" | "Dx" | "At" => {
+        let system = match token {
+          "Ux" => "UNIX",
+          "Bx" => "BSD",
+          "Ox" => "OpenBSD",
+          "Nx" => "NetBSD",
+          "Fx" => "FreeBSD",
+          "Dx" => "DragonFly",
+          _ => "AT&T UNIX",
+        };
+        push(&mut out, system, &mut no_space);
+      }
+      _ if is_mdoc_inline(token) => {
+        for arg in take_args(&mut i) {
+          push(&mut out, &arg, &mut no_space);
+        }
+      }
+      _ => push(&mut out, &expand(token), &mut no_space),
+    }
+  }
+
+  out
+}
+
+/// 从 roff 源中提取结构化示例：优先 EXAMPLES 章节中以命令名开头的命令行（斜体参数写成
+/// `{{占位符}}`，说明取前面的段落、行尾注释或随后的段落），其次 OPTIONS（或 DESCRIPTION）
+/// 中的选项标签，说明取标签段落的首句
+pub fn extract_examples(roff: &str, name: &str) -> Vec<Example> {
+  let mut x = Extractor::new(name);
+  for line in parse_lines(roff) {
+    match line {
+      Line::Request(request, args) => x.request(&request, &args),
+      Line::Text(text) => x.text_line(&text),
+    }
+  }
+  x.end_paragraph();
+
+  if !x.examples.is_empty() {
+    x.examples
+  } else if !x.options.is_empty() {
+    x.options
+  } else {
+    x.description_options
+  }
+}
+
+/// 段落首句
+fn first_sentence(text: &str) -> &str {
+  match text.find(". ") {
+    Some(idx) => &text[..idx + 1],
+    None => text,
+  }
+}
+
+/// 选项标签（如 "-w, --width={{COLS}}"）中用作示例的写法：优先带参数的，其次第一个
+fn option_variant(tag: &str) -> &str {
+  let variants: Vec<&str> = tag
+    .split(", ")
+    .map(str::trim)
+    .filter(|v| v.starts_with('-'))
+    .collect();
+  variants
+    .iter()
+    .find(|v| v.contains("{{"))
+    .or(variants.first())
+    .copied()
+    .unwrap_or(tag)
+}
+
+#[derive(Default)]
+struct Extractor {
+  /// 页面名及其可能的写法（git-commit 的示例写作 git commit）
+  names: Vec<String>,
+  /// mdoc 页面名（.Nm 无参数时使用）
+  mdoc_name: String,
+  /// 当前章节标题
+  section: String,
+  /// 当前段落的单词
+  paragraph: Vec<String>,
+  /// 当前段落保留占位符的写法（段落后跟 .RS 缩进的说明时用作标签）
+  paragraph_code: Vec<String>,
+  /// 上一个段落，作为随后命令行的说明
+  lead: String,
+  /// lead 已被当前不填充块（或连续的 .Dl）中的命令行使用，块结束后不再用于之后的命令行
+  lead_used: bool,
+  /// 最后一个示例没有说明，由随后的段落补全（命令在前、说明在后的格式）
+  needs_description: bool,
+  /// .nf / .EX / .Bd -literal 不填充块
+  literal: bool,
+  /// 下一行文本是 .TP 的标签
+  pending_tag: bool,
+  /// 当前列表项的标签，说明为随后的段落
+  tag: Option<String>,
+  examples: Vec<Example>,
+  options: Vec<Example>,
+  description_options: Vec<Example>,
+}
+
+impl Extractor {
+  fn new(name: &str) -> Self {
+    let mut names = vec![name.to_string()];
+    if name.contains('-') {
+      names.push(name.replace('-', " "));
+    }
+    Self {
+      names,
+      mdoc_name: name.to_string(),
+      ..Default::default()
+    }
+  }
+
+  fn in_examples(&self) -> bool {
+    matches!(self.section.as_str(), "EXAMPLES" | "EXAMPLE")
+  }
+
+  /// 段落结束：列表项的段落是标签的说明，其余段落作为随后命令行的说明
+  fn end_paragraph(&mut self) {
+    let text = std::mem::take(&mut self.paragraph).join(" ");
+    self.paragraph_code.clear();
+    if let Some(tag) = self.tag.take() {
+      self.tagged(&tag, &text);
+      return;
+    }
+    if text.is_empty() {
+      return;
+    }
+    if self.needs_description {
+      self.needs_description = false;
+      if let Some(last) = self.examples.last_mut() {
+        last.description = first_sentence(&text).to_string();
+      }
+    } else {
+      self.lead = text;
+    }
+  }
+
+  /// 列表项：EXAMPLES 中标签为命令行，其余章节中标签为选项
+  fn tagged(&mut self, tag: &str, text: &str) {
+    if self.in_examples() {
+      if let Some(code) = self.command_line(tag) {
+        if self.examples.len() < MAX_EXAMPLES {
+          self.examples.push(Example {
+            description: text.trim_end_matches(':').to_string(),
+            code,
+            source: None,
+          });
+        }
+      }
+      return;
+    }
+    if !tag.starts_with('-') || text.is_empty() {
+      return;
+    }
+    let example = Example {
+      description: first_sentence(text).to_string(),
+      code: format!("{} {}", self.names[0], option_variant(tag)),
+      source: None,
+    };
+    let list = match self.section.as_str() {
+      "OPTIONS" => &mut self.options,
+      "DESCRIPTION" => &mut self.description_options,
+      _ => return,
+    };
+    if list.len() < MAX_OPTIONS {
+      list.push(example);
+    }
+  }
+
+  /// 以页面名开头的命令行（去掉 shell 提示符）
+  fn command_line(&self, line: &str) -> Option<String> {
+    let line = line.trim();
+    let line = ["$ ", "% ", "# "]
+      .iter()
+      .find_map(|prompt| line.strip_prefix(prompt))
+      .unwrap_or(line)
+      .trim_start();
+    self
+      .names
+      .iter()
+      .chain(std::iter::once(&self.mdoc_name))
+      .any(|name| line == name || line.starts_with(&format!("{} ", name)))
+      .then(|| line.to_string())
+  }
+
+  /// 不填充块中的一行：EXAMPLES 中以页面名开头的行是示例
+  fn code_line(&mut self, line: &str) {
+    if !self.in_examples() || self.examples.len() >= MAX_EXAMPLES {
+      return;
+    }
+    let Some(code) = self.command_line(line) else {
+      return;
+    };
+    let (code, description) = match code.split_once(" # ") {
+      Some((code, comment)) => (code.trim_end().to_string(), comment.trim().to_string()),
+      None => {
+        self.lead_used = !self.lead.is_empty();
+        (code, self.lead.trim_end_matches(':').to_string())
+      }
+    };
+    self.needs_description = description.is_empty();
+    self.examples.push(Example {
+      description: if self.needs_description {
+        "Example usage".to_string()
+      } else {
+        description
+      },
+      code,
+      source: None,
+    });
+  }
+
+  /// 不填充块之外的内容：已使用的 lead 失效
+  fn close_block(&mut self) {
+    if self.lead_used && !self.literal {
+      self.lead_used = false;
+      self.lead.clear();
+    }
+  }
+
+  fn set_tag(&mut self, tag: String) {
+    // .TQ 的其余标签只保留第一个
+    if self.tag.is_none() && !tag.is_empty() {
+      self.tag = Some(tag);
+    }
+  }
+
+  /// 追加文本：plain 为展开后的纯文本，code 为保留占位符的写法（用于标签和命令行）
+  fn text(&mut self, plain: &str, code: &str) {
+    if self.pending_tag {
+      self.pending_tag = false;
+      self.set_tag(code.trim().to_string());
+    } else if self.literal {
+      self.code_line(code);
+    } else {
+      self
+        .paragraph
+        .extend(plain.split_whitespace().map(str::to_string));
+      self
+        .paragraph_code
+        .extend(code.split_whitespace().map(str::to_string));
+    }
+  }
+
+  /// .RS：紧接在段落后的缩进块是该段落的说明（DocBook 生成的页面，如 git(1)），
+  /// 段落为命令行或选项时作为标签
+  fn indent(&mut self) {
+    if self.tag.is_some() {
+      return;
+    }
+    let code = self.paragraph_code.join(" ");
+    let is_tag = if self.in_examples() {
+      self.command_line(&code).is_some()
+    } else {
+      code.starts_with('-')
+    };
+    if is_tag {
+      self.paragraph.clear();
+      self.paragraph_code.clear();
+      self.set_tag(code);
+    } else {
+      self.end_paragraph();
+    }
+  }
+
+  fn text_line(&mut self, line: &str) {
+    self.close_block();
+    if line.trim().is_empty() {
+      if !self.literal {
+        self.end_paragraph();
+      }
+      return;
+    }
+    self.text(&expand(line), &code_text(line));
+  }
+
+  fn request(&mut self, name: &str, args: &[String]) {
+    if name != "Dl" && name != "D1" {
+      self.close_block();
+    }
+    match name {
+      "SH" | "Sh" => {
+        self.end_paragraph();
+        self.section = expand(&args.join(" ")).trim().to_uppercase();
+        self.lead.clear();
+        self.needs_description = false;
+        self.literal = false;
+        self.pending_tag = false;
+      }
+      // 小节标题通常就是随后示例的说明
+      "SS" | "Ss" => {
+        self.end_paragraph();
+        self.lead = expand(&args.join(" ")).trim().to_string();
+        self.needs_description = false;
+      }
+      "PP" | "P" | "LP" | "HP" | "Pp" | "Lp" | "sp" | "Sp" | "Bl" | "El" => self.end_paragraph(),
+      "TP" => {
+        self.end_paragraph();
+        self.pending_tag = true;
+      }
+      "TQ" => self.pending_tag = true,
+      "RS" => self.indent(),
+      "RE" => self.end_paragraph(),
+      "IP" => {
+        self.end_paragraph();
+        if let Some(tag) = args.first() {
+          self.set_tag(code_text(tag).trim().to_string());
+        }
+      }
+      "It" => {
+        self.end_paragraph();
+        let tag = mdoc_inline(args, &self.mdoc_name, true);
+        self.set_tag(tag.trim().to_string());
+      }
+      "nf" | "EX" | "Vb" => {
+        self.end_paragraph();
+        self.literal = true;
+      }
+      "fi" | "EE" | "Ve" | "Ed" => self.literal = false,
+      "Bd" => {
+        self.end_paragraph();
+        self.literal = args.iter().any(|a| a == "-literal" || a == "-unfilled");
+      }
+      "Dl" | "D1" => {
+        self.end_paragraph();
+        let line = mdoc_inline(args, &self.mdoc_name, true);
+        self.code_line(&line);
+      }
+      "Nm" if self.section == "NAME" => {
+        if let Some(first) = args.first() {
+          self.mdoc_name = expand(first);
+        }
+      }
+      "Nd" => {}
+      _ => {
+        let text = |placeholders| {
+          font_macro(name, args, placeholders).or_else(|| {
+            is_mdoc_inline(name).then(|| {
+              let mut tokens = vec![name.to_string()];
+              tokens.extend(args.iter().cloned());
+              mdoc_inline(&tokens, &self.mdoc_name, placeholders)
+            })
+          })
+        };
+        if let (Some(plain), Some(code)) = (text(false), text(true)) {
+          self.text(&plain, &code);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::learn::parse_help_content;
+
+  #[test]
+  fn test_render_man_page() {
+    let roff = r#".\" Comment line
+.de Vb
+.nf
+..
+.TH LS 1 "2024" "GNU coreutils"
+.SH NAME
+ls \- list directory contents
+.SH SYNOPSIS
+.B ls
+[\fI\,OPTION\/\fR]... [\fI\,FILE\/\fR]...
+.SH DESCRIPTION
+List information about the FILEs
+(the current directory by default).
+.TP
+\fB\-a\fR, \fB\-\-all\fR
+do not ignore entries starting with .
+.TP
+.BR \-l
+use a long listing format
+.ie n \{\
+.  ds -- \(*W-
+.\}
+.SH EXAMPLES
+.nf
+ls \-la /tmp
+.fi
+"#;
+    let text = render(roff);
+    assert!(text.starts_with("NAME\n       ls - list directory contents\n"));
+    assert!(
+      text.contains("\n       -a, --all\n              do not ignore entries starting with .\n")
+    );
+    assert!(!text.contains("ds"));
+
+    let cmd = parse_help_content("ls", &text, &source_name("1"));
+    assert_eq!(cmd.description, "list directory contents");
+    assert_eq!(
+      cmd.section("SYNOPSIS").unwrap().content,
+      "ls [OPTION]... [FILE]..."
+    );
+    assert_eq!(cmd.examples[0].code, "ls -la /tmp");
+  }
+
+  #[test]
+  fn test_render_mdoc_page() {
+    let roff = ".Dd May 1, 2024\n.Dt CAT 1\n.Os\n.Sh NAME\n.Nm cat\n.Nd concatenate and print files\n.Sh SYNOPSIS\n.Nm\n.Op Fl benstuv\n.Op Ar\n.Sh DESCRIPTION\nThe\n.Nm\nutility reads files sequentially.\n.Bl -tag -width Ds\n.It Fl n\nNumber the output lines, starting at 1.\n.El\n";
+    let text = render(roff);
+    let cmd = parse_help_content("cat", &text, &source_name("1"));
+    assert_eq!(cmd.description, "concatenate and print files");
+    assert_eq!(
+      cmd.section("SYNOPSIS").unwrap().content,
+      "cat [-benstuv] [file ...]"
+    );
+    assert!(text.contains("The cat utility reads files sequentially."));
+    assert_eq!(cmd.examples[0].code, "cat -n");
+    assert_eq!(
+      cmd.examples[0].description,
+      "Number the output lines, starting at 1."
+    );
+  }
+
+  #[test]
+  fn test_extract_man_examples() {
+    let roff = r#".TH TAR 1
+.SH NAME
+tar \- an archiving utility
+.SH OPTIONS
+.TP
+\fB\-f\fR, \fB\-\-file\fR=\fIARCHIVE\fR
+Use archive file or device ARCHIVE.  Default is stdin.
+.SH EXAMPLES
+Create archive.tar from files foo and bar:
+.PP
+.EX
+.B tar \-cf archive.tar foo bar
+.EE
+.nf
+$ tar \-xf \fIarchive.tar\fR \-C \fI dir \fR
+.fi
+Extract into a directory.
+.PP
+.RS
+.nf
+tar \-tvf archive.tar  # List all files verbosely
+echo not an example
+.fi
+.RE
+.SS Compressing
+.TP
+.BI "tar \-czf " out.tar.gz " " dir
+Use gzip.
+"#;
+    let examples = extract_examples(roff, "tar");
+    let pairs: Vec<(&str, &str)> = examples
+      .iter()
+      .map(|e| (e.code.as_str(), e.description.as_str()))
+      .collect();
+    assert_eq!(
+      pairs,
+      [
+        (
+          "tar -cf archive.tar foo bar",
+          "Create archive.tar from files foo and bar"
+        ),
+        (
+          "tar -xf {{archive.tar}} -C {{dir}}",
+          "Extract into a directory."
+        ),
+        ("tar -tvf archive.tar", "List all files verbosely"),
+        ("tar -czf {{out.tar.gz}} {{dir}}", "Use gzip."),
+      ]
+    );
+
+    // 没有 EXAMPLES 时使用 OPTIONS 中的选项
+    let options = extract_examples(&roff[..roff.find(".SH EXAMPLES").unwrap()], "tar");
+    assert_eq!(options[0].code, "tar --file={{ARCHIVE}}");
+    assert_eq!(
+      options[0].description,
+      "Use archive file or device ARCHIVE."
+    );
+
+    // DocBook 生成的页面：命令行段落后跟缩进的说明
+    let docbook = ".SH \"EXAMPLES\"\n.PP\n\\fBgit log \\-\\-no\\-merges\\fR\n.RS 4\nShow the history, but skip any merges\n.RE\n";
+    let examples = extract_examples(docbook, "git-log");
+    assert_eq!(examples[0].code, "git log --no-merges");
+    assert_eq!(
+      examples[0].description,
+      "Show the history, but skip any merges"
+    );
+  }
+
+  #[test]
+  fn test_extract_mdoc_examples() {
+    let roff = ".Dd May 1, 2024\n.Dt GIT-GREP 1\n.Sh NAME\n.Nm git-grep\n.Nd print lines matching a pattern\n.Sh DESCRIPTION\n.Bl -tag -width Ds\n.It Fl e Ar pattern\nUse\n.Ar pattern\nfor matching.\n.El\n.Sh EXAMPLES\nSearch tracked files for a word:\n.Pp\n.Dl $ git grep Fl w Ar word\n.Bd -literal -offset indent\ngit-grep -n TODO\n.Ed\n";
+    let examples = extract_examples(roff, "git-grep");
+    assert_eq!(examples.len(), 2);
+    assert_eq!(examples[0].code, "git grep -w {{word}}");
+    assert_eq!(examples[0].description, "Search tracked files for a word");
+    assert_eq!(examples[1].code, "git-grep -n TODO");
+
+    let options = extract_examples(&roff[..roff.find(".Sh EXAMPLES").unwrap()], "git-grep");
+    assert_eq!(options[0].code, "git-grep -e {{pattern}}");
+    assert_eq!(options[0].description, "Use pattern for matching.");
+  }
+
+  #[test]
+  fn test_read_manpath_sources() {
+    use std::io::Write;
+
+    let root = tempfile::tempdir().unwrap();
+    let man1 = root.path().join("man1");
+    std::fs::create_dir_all(&man1).unwrap();
+
+    let mut gz = flate2::write::GzEncoder::new(
+      std::fs::File::create(man1.join("foo.1.gz")).unwrap(),
+      flate2::Compression::default(),
+    );
+    gz.write_all(b".SH NAME\nfoo \\- frobnicate\n").unwrap();
+    gz.finish().unwrap();
+    std::fs::write(man1.join("bar.1"), ".so man1/foo.1\n").unwrap();
+    std::fs::write(man1.join("CA.pl.1ssl"), ".SH NAME\nCA.pl \\- CA tool\n").unwrap();
+
+    assert_eq!(page_name("CA.pl.1ssl.gz", "1").as_deref(), Some("CA.pl"));
+    assert_eq!(page_name("foo.8.gz", "1"), None);
+
+    let dirs = vec![root.path().to_path_buf()];
+    let bar = find_page(&dirs, "bar", "1").unwrap();
+    assert!(read_page(&bar).unwrap().contains("frobnicate"));
+    let ca = find_page(&dirs, "CA.pl", "1").unwrap();
+    assert!(render(&read_page(&ca).unwrap()).contains("CA tool"));
+    assert!(find_page(&dirs, "missing", "1").is_none());
+  }
+}
@@ -0,0 +1,263 @@
+//! 子命令枚举
+//!
+//! 针对常见工具使用其自带的列表命令获取准确的子命令清单
+//! （`cargo --list`、`git help -a`、`npm help`、`kubectl api-resources`、`netsh ?`、`wmic /?`），
+//! 其他工具则从 `--help` 的 Commands 段落中解析。
+
+use std::process::Command;
+
+use super::{is_valid_help_content, strip_ansi_codes};
+
+/// 可学习的子命令
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subcommand {
+  /// 存储名称（tldr 风格，如 git-commit）
+  pub name: String,
+  /// 列表中给出的简介（可能为空）
+  pub description: String,
+  /// 获取帮助时传给工具的参数（如 ["commit", "--help"]）
+  pub help_args: Vec<String>,
+}
+
+impl Subcommand {
+  fn new(tool: &str, sub: &str, description: &str) -> Self {
+    Self {
+      name: format!("{}-{}", tool, sub),
+      description: description.to_string(),
+      help_args: vec![sub.to_string(), "--help".to_string()],
+    }
+  }
+}
+
+/// 列出工具的子命令
+pub fn list_subcommands(tool: &str) -> anyhow::Result<Vec<Subcommand>> {
+  let mut subcommands: Vec<Subcommand> = match tool {
+    "cargo" => parse_cargo_list(&run_tool(tool, &["--list"])?)
+      .into_iter()
+      .map(|(sub, desc)| Subcommand::new(tool, &sub, &desc))
+      .collect(),
+    "git" => parse_git_help_all(&run_tool(tool, &["help", "-a"])?)
+      .into_iter()
+      .map(|(sub, desc)| Subcommand::new(tool, &sub, &desc))
+      .collect(),
+    "npm" => parse_npm_help(&run_tool(tool, &["help"])?)
+      .into_iter()
+      .map(|sub| Subcommand::new(tool, &sub, ""))
+      .collect(),
+    "kubectl" => list_kubectl(),
+    // Windows 上下文帮助：`netsh <context> ?`、`wmic <alias> /?`
+    "netsh" | "wmic" => {
+      let flag = if tool == "netsh" { "?" } else { "/?" };
+      super::windows::parse_context_commands(&run_tool(tool, &[flag])?)
+        .into_iter()
+        .map(|(sub, desc)| Subcommand {
+          name: format!("{}-{}", tool, sub.to_lowercase()),
+          description: desc,
+          help_args: vec![sub, flag.to_string()],
+        })
+        .collect()
+    }
+    _ => parse_help_command_list(&run_tool(tool, &["--help"])?)
+      .into_iter()
+      .map(|(sub, desc)| Subcommand::new(tool, &sub, &desc))
+      .collect(),
+  };
+
+  let mut seen = std::collections::HashSet::new();
+  subcommands.retain(|s| seen.insert(s.name.clone()));
+  if subcommands.is_empty() {
+    anyhow::bail!("No subcommands found for '{}'", tool);
+  }
+  Ok(subcommands)
+}
+
+/// kubectl：子命令来自 --help，资源类型来自 api-resources（需要连接集群，失败时忽略）
+fn list_kubectl() -> Vec<Subcommand> {
+  let mut subcommands: Vec<Subcommand> = run_tool("kubectl", &["--help"])
+    .map(|help| parse_help_command_list(&help))
+    .unwrap_or_default()
+    .into_iter()
+    .map(|(sub, desc)| Subcommand::new("kubectl", &sub, &desc))
+    .collect();
+
+  if let Ok(resources) = run_tool("kubectl", &["api-resources", "-o", "name"]) {
+    subcommands.extend(
+      resources
+        .lines()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|resource| Subcommand {
+          name: format!("kubectl-explain-{}", resource),
+          description: format!("Documentation for the {} resource", resource),
+          help_args: vec!["explain".to_string(), resource.to_string()],
+        }),
+    );
+  }
+
+  subcommands
+}
+
+/// 获取子命令帮助，返回 (内容, 来源)
+/// `<sub> --help` 失败时（如 git 找不到 man）退回到 `<sub> -h`
+pub fn get_subcommand_help(tool: &str, sub: &Subcommand) -> anyhow::Result<(String, String)> {
+  let args: Vec<&str> = sub.help_args.iter().map(String::as_str).collect();
+  let (success, content) = run_tool_with_status(tool, &args)?;
+  if success && is_valid_help_content(&content) {
+    return Ok((content, format!("{} {}", tool, args.join(" "))));
+  }
+
+  if let [sub_name, "--help"] = args.as_slice() {
+    let (_, content) = run_tool_with_status(tool, &[sub_name, "-h"])?;
+    if is_valid_help_content(&content) {
+      return Ok((content, format!("{} {} -h", tool, sub_name)));
+    }
+  }
+
+  anyhow::bail!("No usable help output for '{}'", sub.name)
+}
+
+/// 运行工具并返回输出（stdout 为空时取 stderr）
+fn run_tool(tool: &str, args: &[&str]) -> anyhow::Result<String> {
+  run_tool_with_status(tool, args).map(|(_, output)| output)
+}
+
+/// 运行工具，返回 (是否成功退出, 输出)
+/// 分页器统一设为 cat，避免 `git commit --help` 等命令打开交互式 man
+fn run_tool_with_status(tool: &str, args: &[&str]) -> anyhow::Result<(bool, String)> {
+  let output = Command::new(tool)
+    .args(args)
+    .env("PAGER", "cat")
+    .env("MANPAGER", "cat")
+    .env("GIT_PAGER", "cat")
+    .env("MANWIDTH", "80")
+    .env("GROFF_NO_SGR", "1")
+    .stdin(std::process::Stdio::null())
+    .output()
+    .map_err(|e| anyhow::anyhow!("Failed to run '{} {}': {}", tool, args.join(" "), e))?;
+
+  let text = if output.stdout.iter().all(u8::is_ascii_whitespace) {
+    output.stderr
+  } else {
+    output.stdout
+  };
+  Ok((
+    output.status.success(),
+    strip_ansi_codes(&String::from_utf8_lossy(&text)),
+  ))
+}
+
+/// 解析 `cargo --list`，跳过别名
+fn parse_cargo_list(output: &str) -> Vec<(String, String)> {
+  parse_indented_table(output)
+    .into_iter()
+    .filter(|(_, desc)| !desc.starts_with("alias:"))
+    .collect()
+}
+
+/// 解析 `git help -a`：按分组缩进列出，跳过外部命令（通常是个人脚本）
+fn parse_git_help_all(output: &str) -> Vec<(String, String)> {
+  let end = output.find("External commands").unwrap_or(output.len());
+  parse_indented_table(&output[..end])
+}
+
+/// 解析 `npm help` 的 "All commands:" 段落（逗号分隔）
+fn parse_npm_help(output: &str) -> Vec<String> {
+  let Some(start) = output.find("All commands:") else {
+    return Vec::new();
+  };
+
+  output[start..]
+    .lines()
+    .skip(1)
+    .skip_while(|l| l.trim().is_empty())
+    .take_while(|l| !l.trim().is_empty())
+    .flat_map(|l| l.split(','))
+    .map(str::trim)
+    .filter(|s| is_subcommand_name(s))
+    .map(str::to_string)
+    .collect()
+}
+
+/// 通用：从 --help 中以 "Commands:" 结尾的标题（含 "Basic Commands (Beginner):" 等）下解析子命令表
+/// 适用于 clap、cobra（kubectl、docker）等风格
+fn parse_help_command_list(help: &str) -> Vec<(String, String)> {
+  let mut result = Vec::new();
+  let mut in_commands = false;
+
+  for line in help.lines() {
+    let trimmed = line.trim();
+    if !line.starts_with(' ') && !trimmed.is_empty() {
+      let lower = trimmed.to_lowercase();
+      in_commands = lower.ends_with(':') && lower.contains("commands");
+      continue;
+    }
+    if in_commands {
+      result.extend(parse_table_row(line));
+    }
+  }
+
+  result.retain(|(name, _)| name != "help");
+  result
+}
+
+/// 解析所有缩进的 "name   description" 行
+fn parse_indented_table(output: &str) -> Vec<(String, String)> {
+  output
+    .lines()
+    .filter(|l| l.starts_with(' '))
+    .filter_map(parse_table_row)
+    .collect()
+}
+
+fn parse_table_row(line: &str) -> Option<(String, String)> {
+  let trimmed = line.trim();
+  let (name, desc) = match trimmed.split_once(char::is_whitespace) {
+    Some((name, desc)) => (name, desc.trim()),
+    None => (trimmed, ""),
+  };
+  let name = name.trim_end_matches(',');
+  is_subcommand_name(name).then(|| (name.to_string(), desc.to_string()))
+}
+
+fn is_subcommand_name(s: &str) -> bool {
+  s.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+    && s
+      .chars()
+      .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_tool_lists() {
+    let cargo = "Installed Commands:\n    add                  Add dependencies\n    b                    alias: build\n    build                Compile a local package\n";
+    assert_eq!(
+      parse_cargo_list(cargo),
+      vec![
+        ("add".to_string(), "Add dependencies".to_string()),
+        ("build".to_string(), "Compile a local package".to_string()),
+      ]
+    );
+
+    let git = "See 'git help <command>'\n\nMain Porcelain Commands\n   add                     Add file contents to the index\n   cherry-pick             Apply the changes\n\nExternal commands\n   my-script.sh\n";
+    let names: Vec<String> = parse_git_help_all(git)
+      .into_iter()
+      .map(|(n, _)| n)
+      .collect();
+    assert_eq!(names, ["add", "cherry-pick"]);
+
+    let npm = "npm <command>\n\nAll commands:\n\n    access, adduser, audit,\n    ci, run-script\n\nSpecify configs in the ini-formatted file:\n    /root/.npmrc\n";
+    assert_eq!(
+      parse_npm_help(npm),
+      ["access", "adduser", "audit", "ci", "run-script"]
+    );
+
+    let clap = "A tool\n\nUsage: tool <COMMAND>\n\nCommands:\n  build  Build it\n  help   Print this message\n\nOptions:\n  -h, --help  Print help\n";
+    assert_eq!(
+      parse_help_command_list(clap),
+      vec![("build".to_string(), "Build it".to_string())]
+    );
+  }
+}
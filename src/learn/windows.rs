@@ -0,0 +1,142 @@
+//! Windows 原生帮助来源
+//!
+//! Windows 上没有 man，许多管理工具（wmic、netsh）使用上下文帮助，
+//! 而通过 winget / scoop 安装的程序往往只在包清单中有描述。
+
+use std::path::PathBuf;
+
+/// 从包管理器清单获取程序描述（scoop 优先，其次 winget）
+/// 仅在 Windows 上可用，其他平台返回 None
+pub fn package_description(cmd: &str) -> Option<String> {
+  #[cfg(target_os = "windows")]
+  {
+    scoop_description(cmd).or_else(|| winget_description(cmd))
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = cmd;
+    None
+  }
+}
+
+/// scoop 清单：`%SCOOP%\apps\<cmd>\current\manifest.json` 的 description 字段
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn scoop_description(cmd: &str) -> Option<String> {
+  let root = std::env::var_os("SCOOP")
+    .map(PathBuf::from)
+    .or_else(|| dirs::home_dir().map(|h| h.join("scoop")))?;
+  let manifest = root
+    .join("apps")
+    .join(cmd)
+    .join("current")
+    .join("manifest.json");
+  let content = std::fs::read_to_string(manifest).ok()?;
+  parse_scoop_manifest(&content)
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_scoop_manifest(content: &str) -> Option<String> {
+  let manifest: serde_json::Value = serde_json::from_str(content).ok()?;
+  manifest
+    .get("description")?
+    .as_str()
+    .map(|d| d.trim().to_string())
+    .filter(|d| !d.is_empty())
+}
+
+/// winget：`winget show --exact <cmd>` 输出中的 Description 字段
+#[cfg(target_os = "windows")]
+fn winget_description(cmd: &str) -> Option<String> {
+  let output = std::process::Command::new("winget")
+    .args([
+      "show",
+      "--exact",
+      "--disable-interactivity",
+      "--accept-source-agreements",
+      cmd,
+    ])
+    .stdin(std::process::Stdio::null())
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  parse_winget_show(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// 解析 `winget show` 输出，描述可能跨多行（续行缩进）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_winget_show(output: &str) -> Option<String> {
+  let mut lines = output
+    .lines()
+    .skip_while(|l| !l.starts_with("Description:"));
+  let first = lines.next()?.trim_start_matches("Description:").trim();
+
+  let mut description = first.to_string();
+  for line in lines.take_while(|l| l.starts_with(' ')) {
+    if !description.is_empty() {
+      description.push(' ');
+    }
+    description.push_str(line.trim());
+  }
+
+  (!description.is_empty()).then_some(description)
+}
+
+/// 解析上下文帮助中的 "name   - description" 列表
+/// 适用于 `netsh ?`（"add            - Adds a configuration entry"）
+/// 与 `wmic /?`（"BASEBOARD                - Base board management."）
+pub fn parse_context_commands(output: &str) -> Vec<(String, String)> {
+  output
+    .lines()
+    .filter_map(|line| {
+      let (name, desc) = line.split_once(" - ")?;
+      let name = name.trim();
+      let valid = !name.is_empty()
+        && !line.starts_with(' ')
+        && name
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+      valid.then(|| (name.to_string(), desc.trim().to_string()))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_windows_sources() {
+    let netsh = "Usage: netsh [-a AliasFile] [-c Context]\n\nCommands in this context:\n?              - Displays a list of commands.\nadd            - Adds a configuration entry to a list of entries.\nadvfirewall    - Changes to the `netsh advfirewall' context.\n";
+    assert_eq!(
+      parse_context_commands(netsh),
+      vec![
+        (
+          "add".to_string(),
+          "Adds a configuration entry to a list of entries.".to_string()
+        ),
+        (
+          "advfirewall".to_string(),
+          "Changes to the `netsh advfirewall' context.".to_string()
+        ),
+      ]
+    );
+
+    let wmic = "The following alias/es are available in the current role:\nBASEBOARD                - Base board management.\n";
+    assert_eq!(parse_context_commands(wmic)[0].0, "BASEBOARD");
+
+    let winget = "Found ripgrep [BurntSushi.ripgrep.MSVC]\nVersion: 14.1.0\nDescription: ripgrep recursively searches directories\n  for a regex pattern.\nHomepage: https://github.com/BurntSushi/ripgrep\n";
+    assert_eq!(
+      parse_winget_show(winget).as_deref(),
+      Some("ripgrep recursively searches directories for a regex pattern.")
+    );
+
+    assert_eq!(
+      parse_scoop_manifest(r#"{"version": "1.0", "description": "A fast grep"}"#).as_deref(),
+      Some("A fast grep")
+    );
+    assert_eq!(parse_scoop_manifest(r#"{"version": "1.0"}"#), None);
+  }
+}
@@ -1,34 +1,92 @@
+mod alias;
 mod api;
+mod audit;
+mod backup;
+mod bench;
+mod cards;
 mod cli;
+mod clipboard;
 mod config;
+mod daemon;
+mod diff;
+mod docset;
+mod embed;
+mod i18n;
+mod launchd;
+mod launcher;
 mod learn;
+mod manpage;
+mod notes;
+mod output;
+mod picker;
+mod placeholder;
+mod plugin;
 mod search;
+mod service;
+mod sheets;
 mod storage;
+mod sync;
+mod systemd;
+mod tldr;
 mod tui;
 mod update;
+mod usage;
+mod users;
+mod watch;
 
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use axum::Router;
-use clap::Parser;
 use tokio::sync::RwLock;
-use tower_http::cors::{Any, CorsLayer};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-
-use cli::{Cli, Commands};
-use config::AppConfig;
-use search::SearchEngine;
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt};
+
+use cli::{
+  AliasAction, Commands, ConfigAction, EmbedAction, FavAction, PluginAction, ServiceAction,
+  SyncAction, UserAction,
+};
+use config::{AppConfig, CommandPrefs};
+use i18n::t;
+use search::{Platform, QueryFilters, SearchEngine};
 use storage::Database;
 
 pub struct AppState {
   pub db: Database,
   pub search: RwLock<SearchEngine>,
   pub data_dir: PathBuf,
-  pub config: AppConfig,
+  /// serve --read-only：不提供修改数据的接口
+  pub read_only: bool,
+  /// 多用户模式下各用户的数据
+  pub users: users::UserStore,
+  /// 定时任务的运行记录
+  pub jobs: api::JobHistory,
+  /// /api/update/download 的进度
+  pub update: api::UpdateTracker,
+  /// server.rate_limit_per_minute 的令牌桶
+  pub limiter: api::RateLimiter,
+  /// 服务启动时间（/api/health 的 uptime）
+  pub started: std::time::Instant,
+  config: parking_lot::RwLock<Arc<AppConfig>>,
+}
+
+impl AppState {
+  /// 当前配置的快照，热重载后返回新配置
+  pub fn config(&self) -> Arc<AppConfig> {
+    self.config.read().clone()
+  }
+
+  /// 替换配置（热重载）
+  pub fn set_config(&self, config: AppConfig) {
+    *self.config.write() = Arc::new(config);
+  }
 }
 
+/// 服务器日志过滤器的重载句柄
+pub type LogFilterHandle =
+  reload::Handle<tracing_subscriber::EnvFilter, tracing_subscriber::Registry>;
+
 fn get_data_dir(config: &AppConfig) -> PathBuf {
   config.get_data_dir()
 }
@@ -43,48 +101,58 @@ fn init_console_logging(config: &AppConfig) {
     .init();
 }
 
-/// 初始化服务器日志（输出到文件）
-fn init_server_logging(log_dir: &std::path::Path, config: &AppConfig, debug: bool) {
-  let file_appender = tracing_appender::rolling::daily(log_dir, "rtfm.log");
-  let (non_blocking_file, guard) = tracing_appender::non_blocking(file_appender);
+/// 初始化服务器日志（输出到文件，journald 为 true 时改为写入 journald）
+/// 未设置 RUST_LOG 时返回过滤器句柄，用于热重载 logging.level
+fn init_server_logging(
+  log_dir: &std::path::Path,
+  config: &AppConfig,
+  debug: bool,
+  journald: bool,
+) -> Option<LogFilterHandle> {
+  let journald = if journald {
+    systemd::journald_layer()
+  } else {
+    None
+  };
+  let file = journald.is_none().then(|| {
+    let file_appender = tracing_appender::rolling::daily(log_dir, "rtfm.log");
+    let (non_blocking_file, guard) = tracing_appender::non_blocking(file_appender);
+
+    // Keep guard alive
+    Box::leak(Box::new(guard));
 
-  // Keep guard alive
-  Box::leak(Box::new(guard));
+    tracing_subscriber::fmt::layer()
+      .with_writer(non_blocking_file)
+      .with_ansi(false)
+  });
 
+  let rust_log = std::env::var("RUST_LOG").ok();
   let env_filter = tracing_subscriber::EnvFilter::new(
-    std::env::var("RUST_LOG").unwrap_or_else(|_| config.logging.level.clone()),
+    rust_log
+      .clone()
+      .unwrap_or_else(|| config.logging.level.clone()),
   );
+  let (filter_layer, handle) = reload::Layer::new(env_filter);
 
-  if debug {
-    // Debug mode: dual-write to file and console
-    tracing_subscriber::registry()
-      .with(
-        tracing_subscriber::fmt::layer()
-          .with_writer(non_blocking_file)
-          .with_ansi(false),
-      )
-      .with(tracing_subscriber::fmt::layer().with_writer(std::io::stdout))
-      .with(env_filter)
-      .init();
-  } else {
-    // Normal mode: file only
-    tracing_subscriber::registry()
-      .with(
-        tracing_subscriber::fmt::layer()
-          .with_writer(non_blocking_file)
-          .with_ansi(false),
-      )
-      .with(env_filter)
-      .init();
-  }
+  // Debug mode: also print to console
+  let console = debug.then(|| tracing_subscriber::fmt::layer().with_writer(std::io::stdout));
+  tracing_subscriber::registry()
+    .with(filter_layer)
+    .with(file)
+    .with(journald)
+    .with(console)
+    .init();
+
+  // RUST_LOG 优先于配置文件，此时不随配置重载
+  rust_log.is_none().then_some(handle)
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-  let cli = Cli::parse();
-
-  // 加载配置
+  // 加载配置，界面语言在解析命令行之前确定，帮助信息也使用该语言
   let config = AppConfig::load_default();
+  i18n::init(&config.ui);
+  let cli = cli::parse();
 
   match cli.command {
     // 启动 HTTP 服务模式
@@ -92,25 +160,95 @@ async fn main() -> anyhow::Result<()> {
       port,
       bind,
       detach,
+      stop,
+      status,
+      install_service,
+      uninstall_service,
       debug,
+      read_only,
+      systemd,
     }) => {
-      if detach {
-        run_server_detached(&bind, port, &config)
+      let read_only = read_only || config.server.read_only;
+      if install_service {
+        run_serve_install_service(bind, port, read_only, &config)
+      } else if uninstall_service {
+        run_serve_uninstall_service(&config)
+      } else if stop {
+        run_server_stop(&config)
+      } else if status {
+        run_server_status(&config).await
+      } else if detach {
+        run_server_detached(&bind, port, read_only, &config).await
       } else {
-        run_server(&bind, port, debug, config).await
+        run_server(&bind, port, debug, read_only, systemd, config).await
       }
     }
 
+    Some(Commands::Service { action }) => run_service(action, &config),
+
+    Some(Commands::InstallService {
+      port,
+      bind,
+      read_only,
+      socket,
+      system,
+      dir,
+    }) => run_install_service(
+      systemd::ServiceOptions {
+        exe: std::env::current_exe()?,
+        data_dir: get_data_dir(&config),
+        working_dir: None,
+        bind,
+        port,
+        read_only,
+        socket,
+        system,
+      },
+      dir,
+    ),
+
+    // 搜索（启动器格式）
+    Some(Commands::Search {
+      query,
+      format,
+      lang,
+      limit,
+      semantic,
+      fuzzy,
+      platform,
+    }) => {
+      let options = SearchOptions::new(semantic, fuzzy, platform.as_deref(), &config);
+      run_search(&query, &format, lang.as_deref(), limit, &options, &config).await
+    }
+
     // 更新命令
-    Some(Commands::Update { force }) => {
+    Some(Commands::Update {
+      force,
+      from_file,
+      source,
+      langs,
+    }) => {
       init_console_logging(&config);
-      run_update(force, &config).await
+      // --langs 覆盖 update.languages
+      let mut config = config;
+      if let Some(langs) = update::parse_langs(&langs) {
+        config.update.languages = langs;
+      }
+      match (source.as_str(), from_file) {
+        ("cheat", from_file) => run_update_cheat(from_file.as_deref(), &config).await,
+        (_, Some(path)) => run_update_from_file(&path, &config),
+        (_, None) => run_update(force, &config).await,
+      }
     }
 
     // 导入命令
-    Some(Commands::Import { path }) => {
+    Some(Commands::Import {
+      paths,
+      format,
+      watch,
+    }) => {
       init_console_logging(&config);
-      run_import(&path, &config).await
+      run_import(&paths, &format, watch, &config).await
     }
 
     // 从 --help 或 man 学习命令
@@ -118,7 +256,34 @@ async fn main() -> anyhow::Result<()> {
       command,
       force,
       man,
-    }) => run_learn(&command, force, man, &config).await,
+      url,
+      from_file,
+      llm,
+      refresh,
+      refresh_outdated,
+      subcommands,
+    }) => {
+      if refresh || refresh_outdated {
+        let check = if refresh_outdated {
+          learn::refresh::Check::Version
+        } else {
+          learn::refresh::Check::Binary
+        };
+        return run_learn_refresh(check, llm, &config).await;
+      }
+      let source = match (url, from_file) {
+        (Some(url), _) => LearnSource::Url(url),
+        (_, Some(path)) => LearnSource::File(path),
+        _ => LearnSource::System { prefer_man: man },
+      };
+      let command =
+        command.expect("clap requires a command unless --refresh or --refresh-outdated is set");
+      run_learn(&command, force, source, llm, &config).await?;
+      if subcommands {
+        run_learn_subcommands(&command, force, &config)?;
+      }
+      Ok(())
+    }
 
     // 批量学习系统 man 页面
     Some(Commands::LearnAll {
@@ -127,36 +292,245 @@ async fn main() -> anyhow::Result<()> {
       skip_existing,
       prefix,
       source,
+      resume,
+      retry_failed,
+      jobs,
     }) => {
+      // --jobs 覆盖 learn.jobs
+      let mut config = config;
+      if let Some(jobs) = jobs {
+        config.learn.jobs = jobs;
+      }
+      let mode = if retry_failed {
+        LearnAllMode::RetryFailed
+      } else if resume {
+        LearnAllMode::Resume
+      } else {
+        LearnAllMode::Fresh
+      };
       run_learn_all(
         &section,
         limit,
         skip_existing,
         prefix.as_deref(),
         &source,
+        mode,
         &config,
       )
       .await
     }
 
+    // 从数据库重建索引
+    Some(Commands::Reindex) => run_reindex(&config),
+
+    // 删除单个命令
+    Some(Commands::Delete { name, lang }) => run_delete(&name, lang.as_deref(), &config),
+
+    // 收藏
+    Some(Commands::Fav {
+      action: FavAction::Add { names },
+    }) => run_fav_add(&names, &config),
+    Some(Commands::Fav {
+      action: FavAction::Remove { names },
+    }) => run_fav_remove(&names, &config),
+    Some(Commands::Fav {
+      action: FavAction::List,
+    }) => run_fav_list(&config),
+
+    // 命令别名
+    Some(Commands::Alias { action }) => run_alias(action, &config),
+
+    // 个人笔记
+    Some(Commands::Note { name, show, clear }) => run_note(name.as_deref(), show, clear, &config),
+
+    // 查看历史
+    Some(Commands::History { limit, all, clear }) => run_history(limit, all, clear, &config),
+
     // 备份应用数据
-    Some(Commands::Backup { output }) => run_backup(&output, &config).await,
+    Some(Commands::Backup { output, user_only }) => run_backup(&output, user_only, &config).await,
 
     // 从备份恢复数据
-    Some(Commands::Restore { path, merge }) => run_restore(&path, merge, &config).await,
+    Some(Commands::Restore { path, merge, only }) => {
+      run_restore(&path, merge, &only, &config).await
+    }
 
     // 重置所有数据
     Some(Commands::Reset { yes }) => run_reset(yes, &config).await,
 
+    // 导出速查表
+    Some(Commands::Export {
+      output,
+      user_data: true,
+      ..
+    }) => run_export_user_data(output, &config),
+    Some(Commands::Export {
+      output,
+      format,
+      lang,
+      commands,
+      platform,
+      ..
+    }) => run_export(
+      output,
+      &format,
+      lang.as_deref(),
+      &commands,
+      platform.as_deref(),
+      &config,
+    ),
+
+    // 导出闪卡
+    Some(Commands::ExportCards {
+      output,
+      format,
+      lang,
+      commands,
+      platform,
+      deck,
+    }) => run_export_cards(
+      output,
+      &format,
+      lang.as_deref(),
+      &commands,
+      platform.as_deref(),
+      &deck,
+      &config,
+    ),
+
+    // man 页面
+    Some(Commands::Render {
+      install: true,
+      lang,
+      dir,
+      ..
+    }) => run_install_man_pages(lang.as_deref(), dir, &config),
+    Some(Commands::Render { name, lang, .. }) => run_render(
+      name.as_deref().unwrap_or_default(),
+      lang.as_deref(),
+      &config,
+    ),
+
+    // 比较学习的命令与 tldr 页面
+    Some(Commands::Diff {
+      name,
+      lang,
+      unified,
+    }) => run_diff(&name, &lang, unified, &config),
+
+    // 统计信息
+    Some(Commands::Stats {
+      dashboard: true,
+      limit,
+      ..
+    }) => run_usage_dashboard(limit, &config),
+    Some(Commands::Stats {
+      clear_usage: true, ..
+    }) => run_clear_usage(&config),
+    Some(Commands::Stats { low_quality, .. }) => run_stats(low_quality, &config),
+
+    // 数据修改日志
+    Some(Commands::Audit { limit, action }) => run_audit(limit, action.as_deref(), &config),
+
+    // 性能基准
+    Some(Commands::Bench {
+      queries,
+      iterations,
+      json,
+    }) => run_bench(queries, iterations, json, &config),
+
+    // 校验配置文件
+    Some(Commands::Config {
+      action: ConfigAction::Validate { path },
+    }) => run_config_validate(path),
+    Some(Commands::Config {
+      action: ConfigAction::Path,
+    }) => run_config_path(&config),
+    Some(Commands::Config {
+      action: ConfigAction::Schema,
+    }) => {
+      println!(
+        "{}",
+        serde_json::to_string_pretty(&AppConfig::json_schema())?
+      );
+      Ok(())
+    }
+
+    // 根据 shell 历史推荐要学习的命令
+    Some(Commands::SuggestLearn {
+      limit,
+      min_count,
+      yes,
+    }) => run_suggest_learn(limit, min_count, yes, &config).await,
+
+    // 与远程服务器同步
+    Some(Commands::Sync { action }) => run_sync(action, &config).await,
+
+    // 向量索引
+    Some(Commands::Embed {
+      action: EmbedAction::Rebuild { force, lang },
+    }) => run_embed_rebuild(force, lang, &config).await,
+    Some(Commands::Embed {
+      action: EmbedAction::Status,
+    }) => run_embed_status(&config),
+
+    // 插件
+    Some(Commands::Plugin {
+      action: PluginAction::List,
+    }) => run_plugin_list(&config),
+    Some(Commands::Plugin {
+      action: PluginAction::Fetch { name },
+    }) => run_plugin_fetch(&name, &config),
+
+    // 多用户服务的用户
+    Some(Commands::User {
+      action: UserAction::Add { name, admin },
+    }) => run_user_add(&name, admin, &config),
+    Some(Commands::User {
+      action: UserAction::List,
+    }) => run_user_list(&config),
+    Some(Commands::User {
+      action: UserAction::Token { name },
+    }) => run_user_token(&name, &config),
+    Some(Commands::User {
+      action: UserAction::Remove { name, purge },
+    }) => run_user_remove(&name, purge, &config),
+
     // 无子命令时
     None => {
-      // 如果有查询参数，直接输出命令信息
-      if let Some(query) = cli.query {
-        run_query(&query, &cli.lang, &config).await
+      // 配置开启兼容模式时，使用 rtfm 自己的选项的查询仍按原来的方式显示
+      let tldr_compat = cli.tldr_compat
+        || (config.ui.tldr_compat
+          && cli.copy.is_none()
+          && cli.fill.is_none()
+          && cli.render.is_none()
+          && cli.output_format().is_none()
+          && !cli.semantic
+          && !cli.fuzzy);
+      if cli.list {
+        run_list(cli.lang.as_deref(), cli.platform.as_deref(), &config)
+      } else if !cli.query.is_empty() && tldr_compat {
+        run_tldr_query(
+          &cli.query.join(" "),
+          cli.lang.as_deref(),
+          cli.platform.as_deref(),
+          &config,
+        )
+      } else if !cli.query.is_empty() {
+        // 如果有查询参数，直接输出命令信息
+        let query = cli.query.join(" ");
+        let options = SearchOptions::new(cli.semantic, cli.fuzzy, cli.platform.as_deref(), &config);
+        let show = ShowOptions {
+          copy: cli.copy,
+          fill: cli.fill,
+          render: cli.render.as_deref(),
+          output: output::Output::new(cli.output_format()),
+        };
+        run_query(&query, cli.lang.as_deref(), &show, &options, &config).await
       } else {
         // 否则启动 TUI
         // 确定 UI 风格：命令行参数优先，否则使用配置
-        let style_str = cli.style.as_deref().unwrap_or(&config.tui.style);
+        // 命令行参数优先于配置
+        let style_str = cli.style.as_deref().unwrap_or(&config.tui.default_style);
         let ui_style = tui::UiStyle::from_str(style_str);
         run_tui(cli.debug, config, ui_style).await
       }
@@ -179,14 +553,21 @@ async fn run_tui(
 
   // 初始化搜索引擎
   let index_path = data_dir.join(&config.storage.index_dirname);
-  let search = SearchEngine::open(&index_path)?;
+  let search = search::open_index(&db, &index_path, &config.search)?;
 
   // 启动 TUI（日志初始化在 tui::run 内部）
   tui::run(db, search, data_dir, debug_mode, config, ui_style).await
 }
 
 /// 运行 HTTP 服务
-async fn run_server(bind: &str, port: u16, debug: bool, config: AppConfig) -> anyhow::Result<()> {
+async fn run_server(
+  bind: &str,
+  port: u16,
+  debug: bool,
+  read_only: bool,
+  systemd: bool,
+  config: AppConfig,
+) -> anyhow::Result<()> {
   let data_dir = get_data_dir(&config);
   std::fs::create_dir_all(&data_dir)?;
 
@@ -194,7 +575,9 @@ async fn run_server(bind: &str, port: u16, debug: bool, config: AppConfig) -> an
   let log_dir = data_dir.join(&config.storage.log_dirname);
   std::fs::create_dir_all(&log_dir)?;
 
-  init_server_logging(&log_dir, &config, debug);
+  // --systemd 时日志写入 journald（没有 journald 时仍写入日志文件）
+  let journald = systemd && systemd::journald_available();
+  let log_handle = init_server_logging(&log_dir, &config, debug, journald);
 
   tracing::info!("Data directory: {:?}", data_dir);
 
@@ -205,100 +588,573 @@ async fn run_server(bind: &str, port: u16, debug: bool, config: AppConfig) -> an
 
   // 初始化搜索引擎
   let index_path = data_dir.join(&config.storage.index_dirname);
-  let search = SearchEngine::open(&index_path)?;
+  let search = search::open_index(&db, &index_path, &config.search)?;
   tracing::info!("Search index opened: {:?}", index_path);
 
   // 创建应用状态
-  let max_upload_size = config.server.max_upload_size;
+  let server_config = config.server.clone();
   let state = Arc::new(AppState {
     db,
     search: RwLock::new(search),
+    users: users::UserStore::new(&data_dir),
+    jobs: api::JobHistory::load(&data_dir),
+    update: api::UpdateTracker::default(),
+    limiter: api::RateLimiter::default(),
+    started: std::time::Instant::now(),
     data_dir: data_dir.clone(),
-    config,
+    read_only,
+    config: parking_lot::RwLock::new(Arc::new(config)),
   });
 
-  // 配置 CORS
-  let cors = CorsLayer::new()
-    .allow_origin(Any)
-    .allow_methods(Any)
-    .allow_headers(Any);
+  // 配置文件变化或收到 SIGHUP 时重载配置
+  api::spawn_config_watcher(state.clone(), log_handle);
+
+  // 按 [scheduler] 运行维护任务
+  api::spawn_scheduler(state.clone());
+
+  // 按 update.auto_check_interval_hours 自动下载 tldr 新版本
+  api::spawn_auto_update(state.clone());
+
+  // 自动导入 import.watch_dirs 中的速查表（只读模式不修改数据）
+  if !read_only {
+    api::spawn_import_watcher(state.clone());
+  }
+
+  // 定时检查数据集和 rtfm 新版本（结果写入日志并发送桌面通知）
+  let config_state = state.clone();
+  let db_state = state.clone();
+  update::check::spawn(
+    data_dir.clone(),
+    move || config_state.config(),
+    move || db_state.db.get_metadata().ok().flatten().map(|m| m.version),
+  );
 
   // 构建路由
   let app = Router::new()
-    .merge(api::routes_with_docs(max_upload_size))
-    .layer(cors)
+    .merge(api::routes_with_docs(&server_config, read_only))
+    .layer(axum::middleware::from_fn_with_state(
+      state.clone(),
+      api::timeout,
+    ))
+    .layer(axum::middleware::from_fn_with_state(
+      state.clone(),
+      api::authenticate,
+    ))
+    .layer(axum::middleware::from_fn_with_state(
+      state.clone(),
+      api::rate_limit,
+    ))
+    .layer(api::cors_layer(state.clone(), &server_config))
     .with_state(state);
 
-  // 启动服务器
-  let addr: SocketAddr = format!("{}:{}", bind, port).parse()?;
-  println!("RTFM HTTP server listening on http://{}", addr);
-  println!("Swagger UI: http://{}/swagger-ui", addr);
-  println!("Logs: {}", log_dir.display());
+  // 启动服务器；socket activation 时使用 systemd 传入的套接字
+  let activated = if systemd {
+    systemd::activated_listener()?
+  } else {
+    None
+  };
+  let listener = match activated {
+    Some(listener) => tokio::net::TcpListener::from_std(listener)?,
+    None => {
+      let addr: SocketAddr = format!("{}:{}", bind, port).parse()?;
+      tokio::net::TcpListener::bind(addr).await?
+    }
+  };
+  let addr = listener.local_addr()?;
+  println!("{}", t!("serve.listening", addr = addr));
+  println!("{}", t!("serve.swagger", addr = addr));
+  println!("{}", t!("serve.cheatsheet", addr = addr));
+  if journald {
+    println!("{}", t!("serve.logs_journald"));
+  } else {
+    println!("{}", t!("serve.logs", path = log_dir.display()));
+  }
+  if read_only {
+    println!("{}", t!("serve.read_only"));
+    tracing::info!("Read-only mode");
+  }
   if debug {
-    println!("Debug mode: ON (logs also printed to console)");
+    println!("{}", t!("serve.debug"));
   }
-  println!("Press Ctrl+C to stop");
+  println!("{}", t!("serve.press_ctrl_c"));
   tracing::info!("HTTP server listening on http://{}", addr);
+  if systemd {
+    systemd::notify_ready();
+  }
 
-  let listener = tokio::net::TcpListener::bind(addr).await?;
-
-  // Graceful shutdown with Ctrl+C
-  axum::serve(listener, app)
-    .with_graceful_shutdown(shutdown_signal())
-    .await?;
+  // Graceful shutdown with Ctrl+C or SIGTERM
+  // 审计日志记录 API 请求的客户端地址
+  axum::serve(
+    listener,
+    app.into_make_service_with_connect_info::<SocketAddr>(),
+  )
+  .with_graceful_shutdown(shutdown_signal(systemd))
+  .await?;
 
-  println!("\nServer stopped gracefully");
+  println!("\n{}", t!("serve.stopped"));
   tracing::info!("Server stopped");
 
   Ok(())
 }
 
-/// Wait for Ctrl+C signal
-async fn shutdown_signal() {
-  tokio::signal::ctrl_c()
-    .await
-    .expect("Failed to install Ctrl+C handler");
+/// Wait for Ctrl+C, SIGTERM on Unix (sent by systemd and kill) or a Windows service stop request
+async fn shutdown_signal(systemd: bool) {
+  let ctrl_c = async {
+    tokio::signal::ctrl_c()
+      .await
+      .expect("Failed to install Ctrl+C handler");
+  };
+  #[cfg(unix)]
+  let terminate = async {
+    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+      .expect("Failed to install SIGTERM handler")
+      .recv()
+      .await;
+  };
+  #[cfg(not(unix))]
+  let terminate = std::future::pending::<()>();
+
+  tokio::select! {
+    _ = ctrl_c => {}
+    _ = terminate => {}
+    _ = service::stop_requested() => {}
+  }
+  if systemd {
+    systemd::notify_stopping();
+  }
 }
 
 /// Run server in detached/background mode
-fn run_server_detached(bind: &str, port: u16, config: &AppConfig) -> anyhow::Result<()> {
+async fn run_server_detached(
+  bind: &str,
+  port: u16,
+  read_only: bool,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
   use std::process::{Command, Stdio};
 
+  let data_dir = get_data_dir(config);
+  if let Some(server) = daemon::Server::read(&data_dir).filter(daemon::Server::is_running) {
+    anyhow::bail!(
+      "{}",
+      t!(
+        "serve.already_running",
+        pid = server.pid,
+        addr = server.addr()
+      )
+    );
+  }
+
   let exe = std::env::current_exe()?;
-  let log_dir = get_data_dir(config).join(&config.storage.log_dirname);
+  let port_arg = port.to_string();
+  let mut args = vec!["serve", "--port", &port_arg, "--bind", bind];
+  if read_only {
+    args.push("--read-only");
+  }
+  let log_dir = data_dir.join(&config.storage.log_dirname);
   std::fs::create_dir_all(&log_dir)?;
 
   #[cfg(windows)]
-  {
+  let mut child = {
     use std::os::windows::process::CommandExt;
     const CREATE_NO_WINDOW: u32 = 0x08000000;
     const DETACHED_PROCESS: u32 = 0x00000008;
 
     Command::new(&exe)
-      .args(["serve", "--port", &port.to_string(), "--bind", bind])
+      .args(&args)
       .stdin(Stdio::null())
       .stdout(Stdio::null())
       .stderr(Stdio::null())
       .creation_flags(CREATE_NO_WINDOW | DETACHED_PROCESS)
-      .spawn()?;
-  }
+      .spawn()?
+  };
 
   #[cfg(unix)]
-  {
+  let mut child = {
     Command::new(&exe)
-      .args(["serve", "--port", &port.to_string(), "--bind", bind])
+      .args(&args)
       .stdin(Stdio::null())
       .stdout(Stdio::null())
       .stderr(Stdio::null())
-      .spawn()?;
+      .spawn()?
+  };
+
+  let server = daemon::Server {
+    pid: child.id(),
+    bind: bind.to_string(),
+    port,
+  };
+  server.write(&data_dir)?;
+
+  // 等待服务响应；启动失败（如端口被占用）时进程很快退出
+  for _ in 0..50 {
+    if child.try_wait()?.is_some() {
+      daemon::Server::remove(&data_dir);
+      anyhow::bail!("{}", t!("serve.detached.failed", path = log_dir.display()));
+    }
+    if server.is_healthy().await {
+      break;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+  }
+
+  let addr = server.addr();
+  println!("{}", t!("serve.detached"));
+  println!("{}", t!("serve.detached.address", addr = addr));
+  println!("{}", t!("serve.detached.swagger", addr = addr));
+  println!("{}", t!("serve.detached.cheatsheet", addr = addr));
+  println!("{}", t!("serve.detached.logs", path = log_dir.display()));
+  if read_only {
+    println!("{}", t!("serve.detached.read_only"));
+  }
+  println!("\n{}", t!("serve.detached.stop"));
+
+  Ok(())
+}
+
+/// 显示 --detach 启动的服务的状态；服务没有响应时以状态码 1 退出
+async fn run_server_status(config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let Some(server) = daemon::Server::read(&data_dir) else {
+    println!("{}", t!("serve.status.none"));
+    std::process::exit(1);
+  };
+  if !server.is_running() {
+    daemon::Server::remove(&data_dir);
+    println!("{}", t!("serve.status.stale", pid = server.pid));
+    std::process::exit(1);
+  }
+  if !server.is_healthy().await {
+    println!(
+      "{}",
+      t!(
+        "serve.status.unhealthy",
+        pid = server.pid,
+        addr = server.addr()
+      )
+    );
+    std::process::exit(1);
+  }
+  println!(
+    "{}",
+    t!(
+      "serve.status.running",
+      pid = server.pid,
+      addr = server.addr()
+    )
+  );
+  Ok(())
+}
+
+/// 停止 --detach 启动的服务
+fn run_server_stop(config: &AppConfig) -> anyhow::Result<()> {
+  /// 等待进程退出的时间（秒）
+  const STOP_TIMEOUT_SECS: u64 = 10;
+
+  let data_dir = get_data_dir(config);
+  let Some(server) = daemon::Server::read(&data_dir) else {
+    anyhow::bail!("{}", t!("serve.status.none"));
+  };
+  if !server.is_running() {
+    daemon::Server::remove(&data_dir);
+    println!("{}", t!("serve.status.stale", pid = server.pid));
+    return Ok(());
+  }
+
+  println!("{}", t!("serve.stopping", pid = server.pid));
+  server.terminate()?;
+  let deadline = std::time::Instant::now() + std::time::Duration::from_secs(STOP_TIMEOUT_SECS);
+  while server.is_running() {
+    if std::time::Instant::now() > deadline {
+      anyhow::bail!(
+        "{}",
+        t!(
+          "serve.stop_timeout",
+          pid = server.pid,
+          secs = STOP_TIMEOUT_SECS
+        )
+      );
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+  }
+  daemon::Server::remove(&data_dir);
+  println!("{}", t!("serve.stopped"));
+  Ok(())
+}
+
+/// 管理 Windows 服务
+fn run_service(action: ServiceAction, config: &AppConfig) -> anyhow::Result<()> {
+  match action {
+    ServiceAction::Install {
+      port,
+      bind,
+      read_only,
+      manual,
+    } => {
+      let options = service::InstallOptions {
+        exe: std::env::current_exe()?,
+        data_dir: std::path::absolute(get_data_dir(config))?,
+        config: AppConfig::default_path()
+          .map(std::path::absolute)
+          .transpose()?,
+        bind,
+        port,
+        read_only,
+        manual,
+      };
+      service::install(&options)?;
+      println!("{}", t!("service.installed", name = service::SERVICE_NAME));
+      println!(
+        "{}",
+        t!("service.data_dir", path = options.data_dir.display())
+      );
+      if let Some(path) = &options.config {
+        println!("{}", t!("service.config_file", path = path.display()));
+      }
+      println!(
+        "{}",
+        t!(
+          "service.address",
+          addr = format!("{}:{}", options.bind, options.port)
+        )
+      );
+      if options.manual {
+        println!("\n{}", t!("service.start_manual"));
+      } else {
+        println!("\n{}", t!("service.start_auto"));
+      }
+      Ok(())
+    }
+    ServiceAction::Start => {
+      service::start()?;
+      println!("{}", t!("service.started", name = service::SERVICE_NAME));
+      Ok(())
+    }
+    ServiceAction::Stop => {
+      service::stop()?;
+      println!("{}", t!("service.stopping", name = service::SERVICE_NAME));
+      Ok(())
+    }
+    ServiceAction::Uninstall => {
+      service::uninstall()?;
+      println!("{}", t!("service.removed", name = service::SERVICE_NAME));
+      Ok(())
+    }
+    ServiceAction::Run {
+      port,
+      bind,
+      read_only,
+      data_dir,
+      config,
+    } => {
+      // 服务账户看不到安装用户的目录，使用安装时记录的位置
+      std::env::set_var("RTFM_DATA_DIR", data_dir);
+      if let Some(config) = config {
+        std::env::set_var("RTFM_CONFIG", config);
+      }
+      service::run(service::RunOptions {
+        bind,
+        port,
+        read_only,
+      })
+    }
+  }
+}
+
+/// 写入 systemd unit 文件并提示启用方法
+fn run_install_service(
+  mut options: systemd::ServiceOptions,
+  dir: Option<PathBuf>,
+) -> anyhow::Result<()> {
+  let Some(dir) = dir.or_else(|| systemd::unit_dir(options.system)) else {
+    anyhow::bail!("{}", t!("install_service.no_dir"));
+  };
+  // 当前目录下的 rtfm.toml 只有在同一工作目录下才会被读取
+  if AppConfig::default_path().is_some_and(|path| path.is_relative()) {
+    options.working_dir = Some(std::env::current_dir()?);
+  }
+
+  for path in systemd::install(&dir, &options)? {
+    println!("{}", t!("install_service.wrote", path = path.display()));
+  }
+  println!(
+    "{}",
+    t!("service.data_dir", path = options.data_dir.display())
+  );
+
+  let systemctl = if options.system {
+    "systemctl"
+  } else {
+    "systemctl --user"
+  };
+  let unit = if options.socket {
+    systemd::SOCKET_NAME
+  } else {
+    systemd::SERVICE_NAME
+  };
+  println!("\n{}", t!("install_service.enable"));
+  println!("  {} daemon-reload", systemctl);
+  println!("  {} enable --now {}", systemctl, unit);
+  if !options.system {
+    println!("{}", t!("install_service.linger"));
+  }
+  Ok(())
+}
+
+/// 运行 systemctl / launchctl，返回是否成功；quiet 时不显示错误输出（失败也无妨的调用）
+fn service_manager(program: &str, args: &[&str], quiet: bool) -> bool {
+  let mut command = std::process::Command::new(program);
+  command.args(args).stdin(std::process::Stdio::null());
+  if quiet {
+    command.stdout(std::process::Stdio::null());
+    command.stderr(std::process::Stdio::null());
+  }
+  command.status().is_ok_and(|status| status.success())
+}
+
+/// serve --install-service：按平台安装并启动随系统启动的服务
+/// Linux 为 systemd 用户 unit，macOS 为 launchd agent，Windows 为 Windows 服务（与 rtfm service install 相同，不立即启动）
+fn run_serve_install_service(
+  bind: String,
+  port: u16,
+  read_only: bool,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let addr = format!("{}:{}", bind, port);
+  if cfg!(windows) {
+    return run_service(
+      ServiceAction::Install {
+        port,
+        bind,
+        read_only,
+        manual: false,
+      },
+      config,
+    );
+  }
+
+  let exe = std::env::current_exe()?;
+  let data_dir = std::path::absolute(get_data_dir(config))?;
+  // 需要手动执行的命令，自动执行失败时列出
+  let commands: Vec<Vec<String>> = if cfg!(target_os = "macos") {
+    let Some(dir) = launchd::agent_dir() else {
+      anyhow::bail!("{}", t!("serve.service.no_dir"));
+    };
+    let options = launchd::AgentOptions {
+      exe,
+      data_dir,
+      config: AppConfig::default_path()
+        .map(std::path::absolute)
+        .transpose()?,
+      bind,
+      port,
+      read_only,
+    };
+    let path = launchd::install(&dir, &options)?;
+    println!("{}", t!("install_service.wrote", path = path.display()));
+    let path = path.display().to_string();
+    // 重新安装时先卸载旧的配置，没有加载时失败也无妨
+    service_manager("launchctl", &["unload", &path], true);
+    vec![vec!["launchctl".into(), "load".into(), "-w".into(), path]]
+  } else {
+    let Some(dir) = systemd::unit_dir(false) else {
+      anyhow::bail!("{}", t!("serve.service.no_dir"));
+    };
+    let options = systemd::ServiceOptions {
+      exe,
+      data_dir,
+      // 当前目录下的 rtfm.toml 只有在同一工作目录下才会被读取
+      working_dir: AppConfig::default_path()
+        .is_some_and(|path| path.is_relative())
+        .then(std::env::current_dir)
+        .transpose()?,
+      bind,
+      port,
+      read_only,
+      socket: false,
+      system: false,
+    };
+    for path in systemd::install(&dir, &options)? {
+      println!("{}", t!("install_service.wrote", path = path.display()));
+    }
+    vec![
+      vec!["systemctl".into(), "--user".into(), "daemon-reload".into()],
+      vec![
+        "systemctl".into(),
+        "--user".into(),
+        "enable".into(),
+        "--now".into(),
+        systemd::SERVICE_NAME.into(),
+      ],
+    ]
+  };
+  println!(
+    "{}",
+    t!("service.data_dir", path = get_data_dir(config).display())
+  );
+
+  for (i, command) in commands.iter().enumerate() {
+    let args: Vec<&str> = command[1..].iter().map(String::as_str).collect();
+    if !service_manager(&command[0], &args, false) {
+      println!("\n{}", t!("serve.service.command_failed"));
+      for command in &commands[i..] {
+        println!("  {}", command.join(" "));
+      }
+      // 非零退出码，脚本可以知道服务没有启用
+      anyhow::bail!(
+        "{}",
+        t!("serve.service.enable_failed", command = command.join(" "))
+      );
+    }
+  }
+  println!("{}", t!("serve.service.enabled", addr = addr));
+  if !cfg!(target_os = "macos") {
+    println!("{}", t!("install_service.linger"));
+  }
+  Ok(())
+}
+
+/// serve --uninstall-service：停止并删除 --install-service 安装的服务
+fn run_serve_uninstall_service(config: &AppConfig) -> anyhow::Result<()> {
+  if cfg!(windows) {
+    return run_service(ServiceAction::Uninstall, config);
   }
 
-  println!("RTFM server started in background");
-  println!("  Address: http://{}:{}", bind, port);
-  println!("  Swagger: http://{}:{}/swagger-ui", bind, port);
-  println!("  Logs: {}", log_dir.display());
-  println!("\nTo stop: kill the rtfm process or use task manager");
+  let removed = if cfg!(target_os = "macos") {
+    let Some(dir) = launchd::agent_dir() else {
+      anyhow::bail!("{}", t!("serve.service.no_dir"));
+    };
+    let path = launchd::plist_path(&dir);
+    if path.exists() {
+      service_manager(
+        "launchctl",
+        &["unload", "-w", &path.display().to_string()],
+        true,
+      );
+    }
+    launchd::uninstall(&dir)?.into_iter().collect()
+  } else {
+    let Some(dir) = systemd::unit_dir(false) else {
+      anyhow::bail!("{}", t!("serve.service.no_dir"));
+    };
+    for unit in [systemd::SOCKET_NAME, systemd::SERVICE_NAME] {
+      if dir.join(unit).exists() {
+        service_manager("systemctl", &["--user", "disable", "--now", unit], true);
+      }
+    }
+    let removed = systemd::uninstall(&dir)?;
+    if !removed.is_empty() {
+      service_manager("systemctl", &["--user", "daemon-reload"], true);
+    }
+    removed
+  };
 
+  if removed.is_empty() {
+    println!("{}", t!("serve.service.not_installed"));
+  }
+  for path in removed {
+    println!("{}", t!("serve.service.removed", path = path.display()));
+  }
   Ok(())
 }
 
@@ -313,174 +1169,480 @@ async fn run_update(force: bool, config: &AppConfig) -> anyhow::Result<()> {
 
   // 初始化搜索引擎
   let index_path = data_dir.join(&config.storage.index_dirname);
-  let mut search = SearchEngine::open(&index_path)?;
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
 
   // 检查更新
-  println!("Checking for updates...");
+  println!("{}", t!("update.checking"));
   let update_info = update::check_github_release(&config.update).await?;
 
   if !force {
-    let current = db.get_metadata()?.map(|m| m.version).unwrap_or_default();
-    if current == update_info.tag_name {
-      println!("Already up to date: {}", current);
+    let (current, imported, selected) = db
+      .get_metadata()?
+      .map(|m| (m.version, m.languages, m.selected_languages))
+      .unwrap_or_default();
+    // 选择的语言变化时即使版本相同也重新导入
+    let languages_changed =
+      update::languages_changed(&config.update.languages, &imported, selected.as_deref());
+    if current == update_info.tag_name && !languages_changed {
+      println!("{}", t!("update.up_to_date", version = current));
       return Ok(());
     }
   }
 
-  println!("New version found: {}", update_info.tag_name);
+  println!("{}", t!("update.found", version = update_info.tag_name));
 
   // 下载
   let Some(url) = update_info.download_url else {
-    anyhow::bail!("Download URL not found");
+    anyhow::bail!("{}", t!("update.no_download_url"));
   };
 
-  println!("Downloading: {}", url);
+  println!("{}", t!("update.downloading", url = url));
   let client = reqwest::Client::new();
   let response = client.get(&url).send().await?;
   let bytes = response.bytes().await?;
 
   // 解析
-  println!("Parsing cheatsheets...");
-  let languages = &config.update.languages;
-  if !languages.is_empty() {
-    println!("Filtering languages: {:?}", languages);
-  }
-  let commands = update::parse_tldr_archive(&bytes, languages)?;
-  println!("Parsed {} commands", commands.len());
-
-  // 保存
-  println!("Saving to database...");
-  db.clear_commands()?;
-  db.save_commands(&commands)?;
+  println!("{}", t!("update.parsing"));
+  print_update_languages(config);
+  let commands = update::parse_tldr_archive(&bytes, &config.update.languages, &config.commands)?;
+  apply_update(
+    &db,
+    &mut search,
+    &commands,
+    &update_info.tag_name,
+    "github",
+    config,
+  )
+}
 
-  // 重建索引
-  println!("Rebuilding search index...");
-  search.index_commands(&commands)?;
+/// 应用在别处下载的 tldr-pages 压缩包，不访问 GitHub
+fn run_update_from_file(path: &Path, config: &AppConfig) -> anyhow::Result<()> {
+  if !path.is_file() {
+    anyhow::bail!("{}", t!("import.not_found", path = path.display()));
+  }
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+
+  println!("{}", t!("update.from_file", path = path.display()));
+  print_update_languages(config);
+  let commands = update::parse_tldr_archive_file(path, &config.update.languages, &config.commands)?;
+  // 压缩包中没有版本号，以文件名作为数据版本
+  let version = path
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  apply_update(&db, &mut search, &commands, &version, "local-file", config)
+}
 
-  // 更新元数据
-  let metadata = storage::Metadata {
-    version: update_info.tag_name.clone(),
-    command_count: commands.len(),
-    last_update: chrono::Utc::now().to_rfc3339(),
-    languages: if languages.is_empty() {
-      vec!["en".to_string(), "zh".to_string()]
-    } else {
-      languages.clone()
-    },
+/// 下载 cheat/cheatsheets（或读取 from_file），速查表并入同名的 tldr 页面，其余作为 cheat 分类的命令
+/// 与 tldr 更新不同，不清空数据库，也不改变数据版本
+async fn run_update_cheat(from_file: Option<&Path>, config: &AppConfig) -> anyhow::Result<()> {
+  let bytes = match from_file {
+    Some(path) => {
+      if !path.is_file() {
+        anyhow::bail!("{}", t!("import.not_found", path = path.display()));
+      }
+      println!("{}", t!("update.from_file", path = path.display()));
+      std::fs::read(path)?
+    }
+    None => {
+      let url = &config.update.cheat_url;
+      println!("{}", t!("update.downloading", url = url));
+      let response = reqwest::Client::builder()
+        .user_agent(&config.update.user_agent)
+        .build()?
+        .get(url)
+        .send()
+        .await?;
+      if !response.status().is_success() {
+        anyhow::bail!("Download failed: {}", response.status());
+      }
+      response.bytes().await?.to_vec()
+    }
   };
-  db.save_metadata(&metadata)?;
 
-  println!("Update complete! Version: {}", update_info.tag_name);
-  Ok(())
-}
+  println!("{}", t!("update.parsing"));
+  let sheets = update::parse_cheat_archive(&bytes)?;
+  println!("{}", t!("update.parsed", count = sheets.len()));
 
-/// 运行导入命令
-async fn run_import(path: &str, config: &AppConfig) -> anyhow::Result<()> {
   let data_dir = get_data_dir(config);
   std::fs::create_dir_all(&data_dir)?;
-
-  // 初始化数据库
-  let db_path = data_dir.join(&config.storage.db_filename);
-  let db = Database::open(&db_path)?;
-
-  // 初始化搜索引擎
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
   let index_path = data_dir.join(&config.storage.index_dirname);
-  let mut search = SearchEngine::open(&index_path)?;
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+
+  let names: HashSet<String> = sheets.iter().map(|sheet| sheet.name.clone()).collect();
+  let mut commands = Vec::with_capacity(sheets.len());
+  let mut merged = 0;
+  for sheet in sheets {
+    let existing = db.get_command(&sheet.name, "en")?;
+    if existing
+      .as_ref()
+      .is_some_and(|cmd| cmd.category != update::CHEAT_CATEGORY)
+    {
+      merged += 1;
+    }
+    commands.push(update::merge_cheat_sheet(existing, sheet));
+  }
 
-  let path = PathBuf::from(path);
-  if !path.exists() {
-    anyhow::bail!("Path does not exist: {:?}", path);
+  // 仓库中已删除的速查表：单独的命令删除，并入 tldr 页面的示例去掉
+  let mut stale = Vec::new();
+  for cmd in db.get_all_commands("en")? {
+    if names.contains(&cmd.name) {
+      continue;
+    }
+    if cmd.category == update::CHEAT_CATEGORY {
+      stale.push(cmd.name);
+    } else if cmd
+      .examples
+      .iter()
+      .any(|e| e.source.as_deref() == Some(update::CHEAT_CATEGORY))
+    {
+      commands.push(update::strip_cheat_examples(cmd));
+    }
+  }
+
+  println!("{}", t!("update.saving"));
+  db.save_commands(&commands)?;
+  search::index_commands(&db, &mut search, &commands)?;
+  for name in &stale {
+    search::delete_command(&db, &mut search, name, Some("en"))?;
+  }
+  audit::record(
+    &db,
+    &audit::Origin::cli(),
+    audit::Action::Update,
+    commands.len(),
+    format!("cheat/cheatsheets ({} removed)", stale.len()),
+  );
+
+  println!(
+    "{}",
+    t!(
+      "update.cheat_done",
+      count = names.len(),
+      merged = merged,
+      removed = stale.len()
+    )
+  );
+  Ok(())
+}
+
+fn print_update_languages(config: &AppConfig) {
+  let languages = &config.update.languages;
+  if !languages.is_empty() {
+    println!(
+      "{}",
+      t!("update.languages", languages = languages.join(", "))
+    );
+  }
+}
+
+/// 用解析出的页面替换数据库中的命令，重建索引并记录元数据
+fn apply_update(
+  db: &Database,
+  search: &mut SearchEngine,
+  commands: &[storage::Command],
+  version: &str,
+  source: &str,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  println!("{}", t!("update.parsed", count = commands.len()));
+
+  // 保存
+  println!("{}", t!("update.saving"));
+  db.clear_commands()?;
+  db.save_commands(commands)?;
+
+  // 重建索引
+  println!("{}", t!("common.rebuilding_index"));
+  search::rebuild_index(db, search)?;
+
+  // 更新元数据
+  let metadata = storage::Metadata {
+    version: version.to_string(),
+    command_count: commands.len(),
+    last_update: chrono::Utc::now().to_rfc3339(),
+    languages: update::imported_languages(&config.update.languages, commands),
+    source: Some(source.to_string()),
+    selected_languages: Some(config.update.languages.clone()),
+  };
+  db.save_metadata(&metadata)?;
+  audit::record(
+    db,
+    &audit::Origin::cli(),
+    audit::Action::Update,
+    commands.len(),
+    format!("{} ({})", version, source),
+  );
+
+  println!("{}", t!("update.done", version = version));
+  Ok(())
+}
+
+/// 运行导入命令
+/// 导入来源是否为网址（先下载再导入）
+fn is_url(source: &str) -> bool {
+  source.starts_with("https://") || source.starts_with("http://")
+}
+
+/// 下载网址到 dir 中，文件名取自网址的最后一段；GitHub 仓库地址下载其源码压缩包
+async fn download_import(url: &str, dir: &Path, config: &AppConfig) -> anyhow::Result<PathBuf> {
+  let url = update::github_archive_url(url);
+  let filename = url
+    .split(['?', '#'])
+    .next()
+    .and_then(|path| path.rsplit('/').next())
+    .filter(|name| !name.is_empty() && !name.contains(".."))
+    .unwrap_or("download");
+  std::fs::create_dir_all(dir)?;
+  let path = dir.join(filename);
+  println!("{}", t!("update.downloading", url = url));
+  update::download_to_file(&url, &config.update, &path, |_, _| {}).await?;
+  Ok(path)
+}
+
+async fn run_import(
+  sources: &[String],
+  format: &str,
+  watch: bool,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+
+  // 初始化数据库
+  let db_path = data_dir.join(&config.storage.db_filename);
+  let db = Database::open(&db_path)?;
+
+  // 初始化搜索引擎
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+
+  // 下载之前先检查所有本地路径
+  for source in sources {
+    if watch && (is_url(source) || !Path::new(source).is_dir()) {
+      anyhow::bail!("{}", t!("import.watch_not_dir", path = source));
+    }
+    if !is_url(source) && !Path::new(source).exists() {
+      anyhow::bail!("{}", t!("import.not_found", path = source));
+    }
   }
 
   let languages = &config.update.languages;
   if !languages.is_empty() {
-    println!("Filtering languages: {:?}", languages);
+    println!(
+      "{}",
+      t!("update.languages", languages = languages.join(", "))
+    );
   }
 
-  let (commands, _total_files, skipped) = import_from_path(&path, languages)?;
+  // 下载的文件在导入后删除
+  let downloads = tempfile::tempdir()?;
+  let mut commands = Vec::new();
+  let mut skipped = 0;
+  for (i, source) in sources.iter().enumerate() {
+    let path = if is_url(source) {
+      download_import(source, &downloads.path().join(i.to_string()), config).await?
+    } else {
+      PathBuf::from(source)
+    };
+    let (parsed, _total_files, skipped_files) =
+      import_from_path(&path, format, languages, &config.commands)?;
+    commands.extend(parsed);
+    skipped += skipped_files;
+  }
+  // 多个来源中的同名速查表合并为一条，其他同名同语言的页面以后面的来源为准
+  let mut commands = sheets::combine(commands);
+  let mut seen = HashSet::new();
+  commands.reverse();
+  commands.retain(|cmd| seen.insert((cmd.name.clone(), cmd.lang.clone())));
+  commands.reverse();
 
   if commands.is_empty() {
-    println!("No valid Markdown files found.");
+    println!("{}", t!("import.none"));
     println!();
-    println!("Files must follow the tldr-pages format:");
+    println!("{}", t!("import.format"));
     println!("  # command-name");
     println!("  > Brief description.");
     println!("  - Example description:");
     println!("  `command --option {{{{arg}}}}`");
     println!();
     println!(
-      "See: https://github.com/tldr-pages/tldr/blob/main/contributing-guides/style-guide.md"
+      "{}",
+      t!(
+        "import.see",
+        url = "https://github.com/tldr-pages/tldr/blob/main/contributing-guides/style-guide.md"
+      )
     );
-    return Ok(());
-  }
+  } else {
+    println!("{}", t!("import.importing", count = commands.len()));
+    if skipped > 0 {
+      println!("{}", t!("import.skipped", count = skipped));
+    }
 
-  println!("Importing {} commands...", commands.len());
-  if skipped > 0 {
-    println!("  (skipped {} files without valid tldr format)", skipped);
+    let commands = sheets::merge_with_learned(&db, commands);
+    db.save_commands(&commands)?;
+    search::index_commands(&db, &mut search, &commands)?;
+    audit::record(
+      &db,
+      &audit::Origin::cli(),
+      audit::Action::Import,
+      commands.len(),
+      sources.join(", "),
+    );
+
+    println!("{}", t!("import.done", count = commands.len()));
   }
 
-  db.save_commands(&commands)?;
-  search.index_commands(&commands)?;
+  if watch {
+    let dirs: Vec<PathBuf> = sources.iter().map(PathBuf::from).collect();
+    watch_import(&dirs, &db, &mut search)?;
+  }
+  Ok(())
+}
 
-  println!("Import complete! {} commands imported.", commands.len());
+/// 监视目录，新增或修改的 .md 文件自动导入，直到按下 Ctrl+C
+fn watch_import(dirs: &[PathBuf], db: &Database, search: &mut SearchEngine) -> anyhow::Result<()> {
+  let detail = dirs
+    .iter()
+    .map(|dir| dir.display().to_string())
+    .collect::<Vec<_>>()
+    .join(", ");
+  println!();
+  println!("{}", t!("import.watching", path = detail));
+  watch::watch(dirs, |files| {
+    match watch::import_files(db, search, &files) {
+      Ok(commands) if commands.is_empty() => {}
+      Ok(commands) => {
+        audit::record(
+          db,
+          &audit::Origin::watcher(),
+          audit::Action::Import,
+          commands.len(),
+          detail.clone(),
+        );
+        let names: Vec<&str> = commands.iter().map(|c| c.name.as_str()).collect();
+        println!(
+          "{}",
+          t!(
+            "import.watch_imported",
+            count = commands.len(),
+            names = names.join(", ")
+          )
+        );
+      }
+      Err(e) => eprintln!("{}", t!("import.watch_failed", error = e)),
+    }
+  })?;
   Ok(())
 }
 
 /// Import commands from a path (file, directory, or archive)
+/// format: auto, tldr, navi, cheat or docset (see `rtfm import --format`)
 /// Returns (commands, total_files_scanned, skipped_count)
 fn import_from_path(
   path: &PathBuf,
+  format: &str,
   languages: &[String],
+  prefs: &HashMap<String, CommandPrefs>,
 ) -> anyhow::Result<(Vec<storage::Command>, usize, usize)> {
   let mut commands = Vec::new();
   let mut total_files = 0;
   let mut skipped = 0;
 
-  if path.is_dir() {
-    // Directory of markdown files
+  if format == "docset" || (format == "auto" && docset::is_docset(path)) {
+    // Dash / Zeal 文档集：.docset 目录或包含文档集的目录
+    if !path.is_dir() {
+      anyhow::bail!("{}", t!("import.docset_not_dir", path = path.display()));
+    }
+    let (cmds, docsets) = docset::parse_docsets(path)
+      .map_err(|e| anyhow::anyhow!("{}", t!("import.docset_failed", error = e)))?;
+    commands = cmds;
+    total_files = docsets;
+  } else if path.is_dir() {
+    // Directory of markdown files or cheatsheets
     for entry in walkdir(path)? {
-      if entry.extension().map(|e| e == "md").unwrap_or(false) {
-        total_files += 1;
-        let content = std::fs::read_to_string(&entry)?;
-        let filename = entry
-          .file_name()
-          .and_then(|n| n.to_str())
-          .unwrap_or("unknown");
-        if let Some(cmd) = update::parse_local_markdown(&content, filename) {
-          commands.push(cmd);
-        } else {
-          skipped += 1;
-        }
+      let ext = entry.extension().and_then(|e| e.to_str()).unwrap_or("");
+      // 跳过 .git 等隐藏目录和文件
+      let hidden = entry.strip_prefix(path).is_ok_and(|relative| {
+        relative
+          .components()
+          .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+      });
+      let sheet = match (format, ext) {
+        ("auto" | "tldr", "md") => "tldr",
+        ("auto" | "navi", "cheat") => "navi",
+        // cheat 的速查表没有扩展名
+        ("cheat", "") if !hidden => "cheat",
+        _ => continue,
+      };
+      total_files += 1;
+      let parsed = parse_sheet_file(&entry, sheet)?;
+      if parsed.is_empty() {
+        skipped += 1;
       }
+      commands.extend(parsed);
     }
+  } else if path.is_file() && matches!(format, "navi" | "cheat") && !is_archive(path) {
+    total_files += 1;
+    let parsed = parse_sheet_file(path, format)?;
+    if parsed.is_empty() {
+      skipped += 1;
+    }
+    commands.extend(parsed);
   } else if path.is_file() {
     // Detect file type by extension
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
     let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
     match ext.to_lowercase().as_str() {
+      "cheat" if format == "auto" => {
+        total_files += 1;
+        let parsed = parse_sheet_file(path, "navi")?;
+        if parsed.is_empty() {
+          skipped += 1;
+        }
+        commands.extend(parsed);
+      }
       "md" => {
         // Single markdown file - no language filtering
         total_files += 1;
         let content = std::fs::read_to_string(path)?;
-        if let Some(cmd) = update::parse_local_markdown(&content, filename) {
+        if let Some(cmd) = update::parse_page_file(&content, path) {
           commands.push(cmd);
         } else {
           skipped += 1;
         }
       }
+      // rtfm export --format json 或 /api/import 的请求体
+      "json" => {
+        let content = std::fs::read_to_string(path)?;
+        commands = serde_json::from_str(&content)
+          .map_err(|e| anyhow::anyhow!("{}", t!("import.parse_failed", error = e)))?;
+        total_files = 1;
+      }
       "zip" | "gz" | "tgz" | "tar" => {
         // Archive file - use parse_tldr_archive with language filtering
-        let data = std::fs::read(path)?;
-        match update::parse_tldr_archive(&data, languages) {
-          Ok(cmds) => {
-            total_files = cmds.len();
-            commands = cmds;
-          }
-          Err(e) => {
-            anyhow::bail!("Failed to parse archive: {}", e);
-          }
+        let cmds = if format == "auto" || format == "tldr" {
+          let data = std::fs::read(path)?;
+          update::parse_tldr_archive(&data, languages, prefs)
+            .map_err(|e| anyhow::anyhow!("{}", t!("import.parse_failed", error = e)))?
+        } else {
+          Vec::new()
+        };
+        if cmds.is_empty() {
+          // 不是 tldr-pages 结构（如速查表仓库的压缩包）：解压后按目录导入
+          let dir = tempfile::tempdir()?;
+          update::unpack_archive(path, dir.path())
+            .map_err(|e| anyhow::anyhow!("{}", t!("import.parse_failed", error = e)))?;
+          return import_from_path(&dir.path().to_path_buf(), format, languages, prefs);
         }
+        total_files = cmds.len();
+        commands = cmds;
       }
       _ => {
         // Try to read as markdown anyway
@@ -498,7 +1660,28 @@ fn import_from_path(
     }
   }
 
-  Ok((commands, total_files, skipped))
+  Ok((sheets::combine(commands), total_files, skipped))
+}
+
+/// 按扩展名判断是否为压缩包
+fn is_archive(path: &Path) -> bool {
+  let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+  matches!(ext.to_lowercase().as_str(), "zip" | "gz" | "tgz" | "tar")
+}
+
+/// 按格式（tldr、navi、cheat）解析一个文件，没有有效内容时返回空列表
+fn parse_sheet_file(path: &std::path::Path, format: &str) -> anyhow::Result<Vec<storage::Command>> {
+  let content = std::fs::read_to_string(path)?;
+  let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+  Ok(match format {
+    "navi" => sheets::parse_navi(&content, path),
+    "cheat" => sheets::parse_cheat(&content, filename, path)
+      .into_iter()
+      .collect(),
+    _ => update::parse_page_file(&content, path)
+      .into_iter()
+      .collect(),
+  })
 }
 
 /// 简单的目录遍历
@@ -517,59 +1700,151 @@ fn walkdir(path: &PathBuf) -> anyhow::Result<Vec<PathBuf>> {
 }
 
 /// 直接查询命令并输出到终端
-async fn run_query(query: &str, lang: &str, config: &AppConfig) -> anyhow::Result<()> {
+/// show: 找到命令后的输出方式
+/// options: 按名称找不到时全文检索的选项
+async fn run_query(
+  query: &str,
+  lang: Option<&str>,
+  show: &ShowOptions<'_>,
+  options: &SearchOptions,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
   let data_dir = get_data_dir(config);
 
   // 初始化数据库
   let db_path = data_dir.join(&config.storage.db_filename);
   if !db_path.exists() {
-    eprintln!("Database not found. Run 'rtfm update' first.");
+    eprintln!("{}", t!("common.no_database"));
     std::process::exit(1);
   }
 
   let db = Database::open(&db_path)?;
 
-  // 初始化搜索引擎
-  let index_path = data_dir.join(&config.storage.index_dirname);
-  let search = SearchEngine::open(&index_path)?;
+  // lang:、platform:、category:、tag: 在检索前取出，lang: 优先于 --lang
+  let (filters, text) = QueryFilters::parse(query);
+  let lang = filters.lang.as_deref().or(lang);
+  let options = &SearchOptions {
+    platform: filters.platform_or(&options.platform),
+    ..*options
+  };
+  let query = text.as_str();
+
+  // 别名优先（如 gco → git checkout），之后按其指向的命令查找
+  let alias = alias::normalize(query);
+  let target = if query.is_empty() {
+    None
+  } else {
+    db.get_alias(&alias)?
+  };
+  if let Some(target) = &target {
+    eprintln!(
+      "\x1b[90m{}\x1b[0m",
+      t!("alias.resolved", name = alias, target = target)
+    );
+    for name in alias::command_names(target) {
+      let lang = lang
+        .or_else(|| config.command_prefs(&name)?.lang.as_deref())
+        .unwrap_or(config.search.query_lang(query));
+      if let Some(cmd) = db.find_command(&name, lang)? {
+        if filters.matches_command(&cmd) {
+          usage::record_lookup(&db, config, &cmd.name, &cmd.lang, "cli");
+          return show_command(&cmd, show, config);
+        }
+      }
+    }
+  }
+  let query = target.as_deref().unwrap_or(query);
 
-  // 尝试多种匹配方式
+  // 尝试多种匹配方式；索引只在需要全文检索时才打开，按名称查询不必付出这部分启动开销
   // 1. 精确匹配命令名
   let name = query.trim();
+  // 命令行 --lang 优先，其次是 [commands] 中的偏好，否则按查询中的文字选择（search.cjk_lang）
+  let lang = lang
+    .or_else(|| config.command_prefs(name)?.lang.as_deref())
+    .unwrap_or(config.search.query_lang(name));
   let cmd = db
-    .get_command(name, lang)
+    .find_command(name, lang)
     .ok()
     .flatten()
-    .or_else(|| db.get_command(name, "en").ok().flatten())
-    .or_else(|| db.get_command(name, "zh").ok().flatten());
+    .filter(|cmd| filters.matches_command(cmd));
 
   if let Some(cmd) = cmd {
-    print_command(&cmd);
-    return Ok(());
+    usage::record_lookup(&db, config, &cmd.name, &cmd.lang, "cli");
+    return show_command(&cmd, show, config);
   }
 
   // 2. 尝试把空格替换成 `-`（tldr 命名规范）
   let normalized = name.replace(' ', "-");
   if normalized != name {
     let cmd = db
-      .get_command(&normalized, lang)
+      .find_command(&normalized, lang)
       .ok()
       .flatten()
-      .or_else(|| db.get_command(&normalized, "en").ok().flatten())
-      .or_else(|| db.get_command(&normalized, "zh").ok().flatten());
+      .filter(|cmd| filters.matches_command(cmd));
 
     if let Some(cmd) = cmd {
-      print_command(&cmd);
-      return Ok(());
+      usage::record_lookup(&db, config, &cmd.name, &cmd.lang, "cli");
+      return show_command(&cmd, show, config);
     }
   }
 
-  // 3. 全文检索
-  let results = search.search(query, None, 10)?;
+  // 3. 全文检索（可融合语义结果）；只有筛选条件时列出符合条件的命令
+  let limit = config.search.cli_limit;
+  let mut results = if name.is_empty() {
+    search::SearchResponse {
+      results: filtered_commands(&db, &filters)?,
+      lang: lang.to_string(),
+      examples: Vec::new(),
+      suggestions: Vec::new(),
+      total: 0,
+      took_ms: 0,
+    }
+  } else {
+    let index_path = data_dir.join(&config.storage.index_dirname);
+    let search = search::open_index(&db, &index_path, &config.search)?;
+    // 按分类、标签筛选时多取一些结果再过滤
+    let fetch = if filters.needs_post_filter() {
+      config.search.max_limit
+    } else {
+      limit
+    };
+    let mut results = options.search(&search, query, filters.lang.as_deref(), fetch)?;
+    if options.semantic {
+      results.results = blend_semantic(
+        query,
+        filters.lang.as_deref(),
+        fetch,
+        results.results,
+        &options.platform,
+        config,
+      )
+      .await?;
+      search::prefer_lang(&mut results.results, &results.lang);
+    }
+    results
+  };
+  results.results.retain(|r| filters.matches_result(r));
+  results.results.truncate(limit);
+  let query = if name.is_empty() {
+    filters.describe()
+  } else {
+    query.to_string()
+  };
+  let query = query.as_str();
+  usage::record_query(&db, config, query, results.results.len());
 
   if results.results.is_empty() {
-    eprintln!("No results for '{}'.", query);
-    eprintln!("Try 'rtfm update' to download the latest cheatsheets.");
+    eprintln!("{}", t!("query.no_results", query = query));
+    if !results.suggestions.is_empty() {
+      eprintln!(
+        "{}",
+        t!("query.did_you_mean", names = results.suggestions.join(", "))
+      );
+    }
+    if !options.fuzzy {
+      eprintln!("{}", t!("query.try_fuzzy"));
+    }
+    eprintln!("{}", t!("query.try_update"));
     std::process::exit(1);
   }
 
@@ -577,29 +1852,342 @@ async fn run_query(query: &str, lang: &str, config: &AppConfig) -> anyhow::Resul
   if results.results.len() == 1 {
     let r = &results.results[0];
     if let Some(cmd) = db.get_command(&r.name, &r.lang).ok().flatten() {
-      print_command(&cmd);
+      usage::record_lookup(&db, config, &cmd.name, &cmd.lang, "cli");
+      return show_command(&cmd, show, config);
+    }
+  }
+
+  // 多个结果：在终端中交互选择，否则列出供选择
+  if show.output == output::Output::Color && picker::available() {
+    let Some(index) = picker::pick(query, &results.results)? else {
       return Ok(());
+    };
+    let r = &results.results[index];
+    if let Some(cmd) = db.get_command(&r.name, &r.lang)? {
+      usage::record_lookup(&db, config, &cmd.name, &cmd.lang, "cli");
+      return show_command(&cmd, show, config);
     }
   }
+  print!("{}", output::results(&results.results, query, show.output)?);
+  if show.fill.is_some() {
+    eprintln!("{}", t!("query.fill_ambiguous", query = query));
+  } else if show.copy.is_some() {
+    eprintln!("{}", t!("query.copy_ambiguous", query = query));
+  }
 
-  // 多个结果，列出供选择
-  println!(
-    "\x1b[1mFound {} results for '{}':\x1b[0m\n",
-    results.results.len(),
-    query
-  );
-  for (i, r) in results.results.iter().enumerate() {
-    println!(
-      "  \x1b[32m{:2}.\x1b[0m \x1b[1m{}\x1b[0m \x1b[90m[{}]\x1b[0m",
-      i + 1,
-      r.name,
-      r.lang
+  Ok(())
+}
+
+/// 符合筛选条件的命令，按名称排序
+fn filtered_commands(
+  db: &Database,
+  filters: &QueryFilters,
+) -> anyhow::Result<Vec<search::SearchResult>> {
+  let mut results: Vec<_> = db
+    .list_all_commands()?
+    .into_iter()
+    .filter(|cmd| filters.matches_command(cmd))
+    .map(|cmd| search::SearchResult {
+      name: cmd.name,
+      description: cmd.description,
+      category: cmd.category,
+      lang: cmd.lang,
+      platform: cmd.platform,
+      score: 0.0,
+      example: None,
+    })
+    .collect();
+  results.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.lang.cmp(&b.lang)));
+  Ok(results)
+}
+
+/// 搜索并按启动器格式输出
+/// 查询与命令名一致时输出该命令的示例，否则输出匹配的命令
+async fn run_search(
+  query: &str,
+  format: &str,
+  lang: Option<&str>,
+  limit: Option<usize>,
+  options: &SearchOptions,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let Some(format) = launcher::LauncherFormat::parse(format) else {
+    anyhow::bail!("{}", t!("search.unknown_format", format = format));
+  };
+
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+
+  let name = query.trim();
+  let items = if name.is_empty() {
+    Vec::new()
+  } else {
+    let example_lang = lang
+      .or_else(|| config.command_prefs(name)?.lang.as_deref())
+      .unwrap_or(&config.search.default_lang);
+    let exact = db
+      .find_command(name, example_lang)?
+      .or(db.find_command(&name.replace(' ', "-"), example_lang)?)
+      .filter(|cmd| !cmd.examples.is_empty());
+    match exact {
+      Some(cmd) => launcher::example_items(&cmd),
+      None => {
+        let index_path = data_dir.join(&config.storage.index_dirname);
+        let search = search::open_index(&db, &index_path, &config.search)?;
+        let limit = limit.unwrap_or(config.search.cli_limit);
+        let mut results = options.search(&search, name, lang, limit)?.results;
+        if options.semantic {
+          results = blend_semantic(name, lang, limit, results, &options.platform, config).await?;
+        }
+        launcher::command_items(&results)
+      }
+    }
+  };
+
+  print!("{}", launcher::render(&items, format));
+  Ok(())
+}
+
+/// 全文检索的选项（rtfm <query> 和 rtfm search）
+struct SearchOptions {
+  /// 融合向量索引的结果
+  semantic: bool,
+  /// 容忍拼写错误
+  fuzzy: bool,
+  /// 结果的平台，未指定时使用 search.platform
+  platform: Platform,
+}
+
+impl SearchOptions {
+  fn new(semantic: bool, fuzzy: bool, platform: Option<&str>, config: &AppConfig) -> Self {
+    Self {
+      semantic,
+      fuzzy,
+      platform: Platform::parse(platform.unwrap_or(&config.search.platform)),
+    }
+  }
+
+  fn search(
+    &self,
+    engine: &SearchEngine,
+    query: &str,
+    lang: Option<&str>,
+    limit: usize,
+  ) -> Result<search::SearchResponse, search::SearchError> {
+    if self.fuzzy {
+      engine.search_fuzzy(query, lang, &self.platform, limit)
+    } else {
+      engine.search(query, lang, &self.platform, limit)
+    }
+  }
+}
+
+/// 用向量索引中与查询语义最接近的命令补充关键词结果，语义结果同样按平台筛选
+async fn blend_semantic(
+  query: &str,
+  lang: Option<&str>,
+  limit: usize,
+  keyword: Vec<search::SearchResult>,
+  platform: &Platform,
+  config: &AppConfig,
+) -> anyhow::Result<Vec<search::SearchResult>> {
+  let path = get_data_dir(config).join(&config.storage.embeddings_filename);
+  if !path.exists() {
+    anyhow::bail!("{}", t!("embed.no_index"));
+  }
+  let index = embed::VectorIndex::load(&path)?;
+  if index.model != config.embeddings.model {
+    anyhow::bail!(
+      "{}",
+      t!(
+        "embed.model_mismatch",
+        built = index.model,
+        model = config.embeddings.model
+      )
     );
-    println!("      \x1b[90m{}\x1b[0m", truncate(&r.description, 60));
   }
-  println!();
-  println!("Use \x1b[36mrtfm <command>\x1b[0m to view details.");
 
+  let client = embed::EmbeddingClient::new(&config.embeddings, &get_data_dir(config)).await?;
+  let vector = client
+    .embed(&[query.to_string()])
+    .await?
+    .pop()
+    .unwrap_or_default();
+  let mut semantic = embed::semantic_results(&index, &vector, lang, limit);
+  semantic.retain(|result| platform.matches(&result.platform));
+  Ok(embed::blend(
+    keyword,
+    semantic,
+    config.embeddings.weight,
+    limit,
+  ))
+}
+
+/// tldr 客户端规范的平台：--platform（auto 和 all 除外），默认为当前系统
+fn tldr_platform(platform: Option<&str>) -> String {
+  match platform.map(|p| p.trim().to_lowercase()) {
+    Some(p) if !p.is_empty() && p != "auto" && p != "all" => p,
+    _ => learn::get_platform(),
+  }
+}
+
+/// 列出平台（及 common）的所有页面名，--platform all 时不区分平台
+fn run_list(lang: Option<&str>, platform: Option<&str>, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    eprintln!("{}", t!("common.no_database"));
+    std::process::exit(1);
+  }
+  let db = Database::open(&db_path)?;
+
+  let all = platform.is_some_and(|p| p.trim().eq_ignore_ascii_case("all"));
+  let platform = tldr_platform(platform);
+  let lang = lang.unwrap_or(&config.search.default_lang);
+  let mut names: Vec<String> = db
+    .get_all_commands(lang)?
+    .into_iter()
+    .filter(|cmd| all || tldr::is_preferred_platform(&cmd.platform, &platform))
+    .map(|cmd| cmd.name)
+    .collect();
+  names.sort();
+  names.dedup();
+  for name in names {
+    println!("{}", name);
+  }
+  Ok(())
+}
+
+/// 按 tldr 客户端规范查找并显示页面，找不到时退出码为 1
+fn run_tldr_query(
+  query: &str,
+  lang: Option<&str>,
+  platform: Option<&str>,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    eprintln!("{}", t!("common.no_database"));
+    std::process::exit(1);
+  }
+  let db = Database::open(&db_path)?;
+
+  let name = tldr::page_name(query);
+  let lang = lang
+    .or_else(|| config.command_prefs(&name)?.lang.as_deref())
+    .unwrap_or(&config.search.default_lang);
+  let Some(cmd) = db.find_command(&name, lang)? else {
+    eprintln!("{}", t!("tldr.not_found", name = name));
+    std::process::exit(1);
+  };
+  usage::record_lookup(&db, config, &cmd.name, &cmd.lang, "cli");
+
+  // 同名页面只保留了一个平台的版本，不是所需平台时说明
+  let platform = tldr_platform(platform);
+  if !tldr::is_preferred_platform(&cmd.platform, &platform) {
+    eprintln!(
+      "{}",
+      t!(
+        "tldr.other_platform",
+        shown = cmd.platform,
+        platform = platform
+      )
+    );
+  }
+  print!("{}", tldr::render(&cmd, tldr::use_color()));
+  Ok(())
+}
+
+/// 找到命令后的输出方式（rtfm <query>）
+#[derive(Clone, Copy)]
+struct ShowOptions<'a> {
+  /// --copy 的值（外层 None 表示未指定，内层 None 表示使用偏好设置中的序号）
+  copy: Option<Option<usize>>,
+  /// --fill 的值，含义同 copy
+  fill: Option<Option<usize>>,
+  /// --render 指定的渲染插件
+  render: Option<&'a str>,
+  /// --output 的格式，多个结果的列表也按此格式输出
+  output: output::Output,
+}
+
+/// 输出命令详情（或交给渲染插件），按 --copy 复制示例
+fn show_command(
+  cmd: &storage::Command,
+  show: &ShowOptions,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let ShowOptions {
+    copy,
+    fill,
+    render,
+    output,
+  } = *show;
+  let prefs = config.command_prefs(&cmd.name);
+  // 填写占位符时 stdout 只输出填好的命令，便于在脚本中使用
+  if fill.is_none() {
+    match render {
+      Some(name) => {
+        let plugins = plugin::Registry::load(config);
+        let renderer = plugins.get(name, plugin::Capability::Renderer)?;
+        println!("{}", plugins.render(renderer, cmd)?.trim_end_matches('\n'));
+      }
+      None => print!("{}", output::command(cmd, output)?),
+    }
+
+    // 固定的平台页面不存在，或在修改配置之前导入
+    if let Some(platform) = prefs.and_then(|p| p.platform.as_deref()) {
+      if cmd.lang != "local" && cmd.platform != platform {
+        eprintln!(
+          "\x1b[2m{}\x1b[0m",
+          t!(
+            "query.platform_fallback",
+            shown = cmd.platform,
+            platform = platform
+          )
+        );
+      }
+    }
+  }
+
+  let Some(index) = fill.or(copy) else {
+    return Ok(());
+  };
+  let index = index
+    .or(copy.flatten())
+    .or(prefs.and_then(|p| p.example))
+    .unwrap_or(1);
+  let Some(example) = index.checked_sub(1).and_then(|i| cmd.examples.get(i)) else {
+    anyhow::bail!(
+      "{}",
+      t!(
+        "query.example_out_of_range",
+        index = index,
+        name = cmd.name,
+        count = cmd.examples.len()
+      )
+    );
+  };
+
+  let code = if fill.is_some() {
+    eprintln!("\x1b[1m{}\x1b[0m", example.description);
+    let code = placeholder::prompt(&example.code)?;
+    println!("{}", code);
+    code
+  } else {
+    example.code.clone()
+  };
+  if copy.is_some() {
+    let method = clipboard::copy(&code)?;
+    eprintln!(
+      "\x1b[32m{}\x1b[0m {}",
+      t!("query.copied", index = index, method = method),
+      code
+    );
+  }
   Ok(())
 }
 
@@ -613,31 +2201,22 @@ fn truncate(s: &str, max_len: usize) -> String {
   }
 }
 
-/// 格式化输出命令信息
-fn print_command(cmd: &storage::Command) {
-  // 命令名（绿色粗体）
-  println!("\x1b[1;32m{}\x1b[0m", cmd.name);
-  println!();
-
-  // 描述
-  println!("{}", cmd.description);
-  println!();
-
-  // 示例
-  for example in &cmd.examples {
-    // 示例描述（黄色）
-    println!("\x1b[33m- {}\x1b[0m", example.description);
-    // 代码（青色）
-    println!("  \x1b[36m{}\x1b[0m", example.code);
-    println!();
-  }
+/// 单个命令的学习来源
+enum LearnSource {
+  /// 本机 --help / man
+  System { prefer_man: bool },
+  /// 远程文档页面或 README
+  Url(String),
+  /// 已捕获的帮助输出文件
+  File(PathBuf),
 }
 
 /// 从 --help 或 man 学习命令
 async fn run_learn(
   command: &str,
   force: bool,
-  prefer_man: bool,
+  source: LearnSource,
+  llm: bool,
   config: &AppConfig,
 ) -> anyhow::Result<()> {
   let data_dir = get_data_dir(config);
@@ -649,650 +2228,2836 @@ async fn run_learn(
 
   // 初始化搜索引擎
   let index_path = data_dir.join(&config.storage.index_dirname);
-  let mut search = SearchEngine::open(&index_path)?;
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
 
   // 检查是否已存在
   if !force {
     if let Ok(Some(_)) = db.get_command(command, "local") {
-      println!(
-        "Command '{}' already learned. Use --force to re-learn.",
-        command
-      );
+      println!("{}", t!("learn.exists", command = command));
       return Ok(());
     }
   }
 
-  println!("Learning '{}'...", command);
+  println!("{}", t!("learn.learning", command = command));
 
-  // 获取帮助内容，根据优先级尝试
-  let (content, source) = if prefer_man {
-    // 优先 man
-    match learn::get_man_page(command) {
-      Ok(result) => result,
-      Err(man_e) => {
-        // man 失败，尝试 --help
-        match learn::get_help_output(command) {
-          Ok(result) => result,
-          Err(help_e) => {
-            // 两个都失败
-            print_learn_error(command, &help_e, &man_e);
-            return Ok(());
-          }
-        }
+  let prefer_man = match source {
+    LearnSource::System { prefer_man } => prefer_man,
+    // 从 URL 学习（命令无需安装在本机）
+    LearnSource::Url(url) => {
+      let (mut cmd, bytes) = learn::from_url(command, &url, &config.update.user_agent).await?;
+      println!("{}", t!("learn.fetched", bytes = bytes, source = url));
+      if llm || config.learn.llm.enabled {
+        enhance_with_llm(&mut cmd, config).await;
       }
+      return save_learned_command(&db, &mut search, &cmd);
     }
-  } else {
-    // 优先 --help
-    match learn::get_help_output(command) {
-      Ok(result) => result,
-      Err(help_e) => {
-        // --help 失败，尝试 man
-        match learn::get_man_page(command) {
-          Ok(result) => result,
-          Err(man_e) => {
-            // 两个都失败
-            print_learn_error(command, &help_e, &man_e);
-            return Ok(());
-          }
-        }
+    // 从捕获的帮助输出文件学习
+    LearnSource::File(path) => {
+      let mut cmd = learn::from_file(command, &path)?;
+      println!(
+        "{}",
+        t!(
+          "learn.read",
+          bytes = cmd.content.len(),
+          source = path.display()
+        )
+      );
+      if llm || config.learn.llm.enabled {
+        enhance_with_llm(&mut cmd, config).await;
       }
+      return save_learned_command(&db, &mut search, &cmd);
     }
   };
 
-  println!("Got {} bytes from {}", content.len(), source);
+  // 获取帮助内容，根据优先级尝试
+  let (content, source) = match learn::get_local_help(command, prefer_man, &config.learn) {
+    Ok(result) => result,
+    Err((help_e, man_e)) => {
+      // 两个都失败
+      print_learn_error(command, &help_e, &man_e);
+      return Ok(());
+    }
+  };
 
-  // 解析帮助内容
-  let cmd = learn::parse_help_content(command, &content, &source);
+  println!(
+    "{}",
+    t!("learn.got", bytes = content.len(), source = source)
+  );
 
-  // 保存到数据库
-  db.save_command(&cmd)?;
-  println!("Saved to database");
+  // 解析帮助内容（有对应的 parser 插件时交给插件）
+  let (mut cmd, plugin_failure) =
+    plugin::Registry::load(config).parse_help_content(command, &content, &source);
+  if let Some(failure) = &plugin_failure {
+    warn_plugin_failure(failure);
+  }
+  learn::record_binary(&mut cmd);
 
-  // 更新索引（增量）
-  search.index_single_command(&cmd)?;
-  println!("Indexed for search");
+  // 可选：LLM 生成 tldr 风格示例
+  if llm || config.learn.llm.enabled {
+    enhance_with_llm(&mut cmd, config).await;
+  }
 
-  println!("\n\x1b[32mLearned '{}' successfully!\x1b[0m", command);
-  println!("Try: rtfm {}", command);
+  save_learned_command(&db, &mut search, &cmd)?;
 
+  // 可选：导入指向该命令的 shell 和 git 别名
+  if config.learn.import_aliases {
+    let added = alias::import(&db, Some(command))?;
+    print_imported_aliases(&added);
+  }
   Ok(())
 }
 
-/// 打印学习命令失败的人性化错误信息
-fn print_learn_error(command: &str, help_err: &anyhow::Error, man_err: &anyhow::Error) {
-  let help_err_str = help_err.to_string();
-  let man_err_str = man_err.to_string();
+/// 学习工具的所有子命令（如 git-commit、cargo-build）
+fn run_learn_subcommands(tool: &str, force: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  let db = Database::open(&db_path)?;
 
-  eprintln!("\n\x1b[1;31mFailed to learn '{}'.\x1b[0m\n", command);
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
 
-  // 分析错误类型
-  // 命令不存在：--help 返回 "program not found" 或类似错误
-  let cmd_not_found = help_err_str.contains("program not found")
-    || help_err_str.contains("No such file")
-    || help_err_str.contains("not recognized")
-    || help_err_str.contains("command not found");
+  let plugins = plugin::Registry::load(config);
+  let subcommands = learn::subcommands::list_subcommands(tool)?;
+  println!(
+    "\n{}",
+    t!(
+      "learn.subcommands.found",
+      count = subcommands.len(),
+      tool = tool
+    )
+  );
 
-  // man 不可用（Windows 常见情况）
-  let man_not_available = man_err_str.contains("program not found");
+  let total = subcommands.len();
+  let (mut learned, mut skipped, mut failed) = (0, 0, 0);
 
-  if cmd_not_found && man_not_available {
-    // 命令不存在，且 man 也不可用
-    eprintln!(
-      "\x1b[33mCommand '{}' not found on this system.\x1b[0m",
-      command
-    );
-    eprintln!();
-    eprintln!("Possible reasons:");
-    eprintln!("  - The command is not installed");
-    eprintln!("  - The command is not in your PATH");
-    eprintln!("  - The command name is misspelled");
-    eprintln!();
-    eprintln!("Try:");
-    eprintln!(
-      "  - Install the command first, then run: rtfm learn {}",
-      command
-    );
-    eprintln!("  - Use 'rtfm update' to download cheatsheets from tldr-pages");
-  } else if cmd_not_found {
-    // 命令不存在，但 man 可用（返回了其他错误）
-    eprintln!(
-      "\x1b[33mCommand '{}' not found on this system.\x1b[0m",
-      command
-    );
-    eprintln!();
-    eprintln!("The command is not installed or not in PATH.");
-    eprintln!();
-    eprintln!("Try:");
-    eprintln!(
-      "  - Install the command first, then run: rtfm learn {}",
-      command
-    );
-    eprintln!("  - Use 'rtfm update' to download cheatsheets from tldr-pages");
-    eprintln!(
-      "  - Use 'rtfm learn {} --man' to check if man page exists",
-      command
-    );
-  } else if man_not_available {
-    // 命令可能存在但 --help 失败了，且 man 不可用
-    eprintln!(
-      "\x1b[33mCould not get help for '{}', and 'man' is not available.\x1b[0m",
-      command
+  for (i, sub) in subcommands.iter().enumerate() {
+    if !force {
+      if let Ok(Some(_)) = db.get_command(&sub.name, "local") {
+        skipped += 1;
+        continue;
+      }
+    }
+
+    print!(
+      "\r[{}/{}] {}",
+      i + 1,
+      total,
+      t!("learn.learning", command = sub.name)
     );
-    eprintln!();
+    std::io::Write::flush(&mut std::io::stdout())?;
 
-    #[cfg(target_os = "windows")]
-    {
-      eprintln!("On Windows, 'man' pages are not available by default.");
-      eprintln!(
-        "The command '{}' exists but --help didn't provide usable output.",
-        command
-      );
-      eprintln!();
-      eprintln!("Details:");
-      eprintln!("  --help: {}", help_err_str);
-      eprintln!();
-      eprintln!("Alternatives:");
-      eprintln!("  - Use 'rtfm update' to download cheatsheets from tldr-pages");
-      eprintln!(
-        "  - Check if '{}' supports a different help flag (e.g., /?, -h)",
-        command
-      );
+    match learn::subcommands::get_subcommand_help(tool, sub) {
+      Ok((content, source)) => {
+        let (mut cmd, plugin_failure) = plugins.parse_help_content(&sub.name, &content, &source);
+        if let Some(failure) = &plugin_failure {
+          // 先清掉进度行
+          print!("\r{}\r", " ".repeat(60));
+          warn_plugin_failure(failure);
+        }
+        // 非 man 格式的帮助（如 `git commit -h`）通常没有描述行，使用列表给出的简介
+        if cmd.sections.is_empty() && !sub.description.is_empty() {
+          cmd.description = sub.description.clone();
+        }
+        if db.save_command(&cmd).is_ok()
+          && search::index_commands(&db, &mut search, std::slice::from_ref(&cmd)).is_ok()
+        {
+          learned += 1;
+        } else {
+          failed += 1;
+        }
+      }
+      Err(_) => failed += 1,
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-      eprintln!("Details:");
-      eprintln!("  --help: {}", help_err_str);
-      eprintln!();
-      eprintln!("Try:");
-      eprintln!("  - Install man-db package (e.g., apt install man-db)");
-      eprintln!("  - Use 'rtfm update' to download cheatsheets from tldr-pages");
-    }
-  } else {
-    // 其他错误，显示原始信息
-    eprintln!("The command exists but didn't provide usable help output.");
-    eprintln!();
-    eprintln!("Details:");
-    eprintln!("  --help: {}", help_err_str);
-    eprintln!("  man:    {}", man_err_str);
-    eprintln!();
-    eprintln!("Try:");
-    eprintln!("  - Run '{} --help' manually to check the output", command);
-    eprintln!("  - Use 'rtfm update' to download cheatsheets from tldr-pages");
+    print!("\r{}\r", " ".repeat(60));
+  }
+  if learned > 0 {
+    audit::record(
+      &db,
+      &audit::Origin::cli(),
+      audit::Action::Learn,
+      learned,
+      format!("{} subcommands", tool),
+    );
   }
 
-  eprintln!();
+  println!(
+    "{}",
+    t!(
+      "learn.subcommands.done",
+      learned = learned,
+      skipped = skipped,
+      failed = failed
+    )
+  );
+  Ok(())
 }
 
-/// 批量学习命令（跨平台）
-/// - Linux/macOS: 默认从 man 页面学习
-/// - Windows: 默认从 PowerShell cmdlet 学习
-/// - 所有平台: 可以从 PATH 中的可执行文件学习
-async fn run_learn_all(
-  section: &str,
-  limit: usize,
-  skip_existing: bool,
-  prefix: Option<&str>,
-  source: &str,
+/// 重新学习二进制已变化的命令
+async fn run_learn_refresh(
+  check: learn::refresh::Check,
+  llm: bool,
   config: &AppConfig,
 ) -> anyhow::Result<()> {
   let data_dir = get_data_dir(config);
   std::fs::create_dir_all(&data_dir)?;
 
-  // 初始化数据库
   let db_path = data_dir.join(&config.storage.db_filename);
   let db = Database::open(&db_path)?;
 
-  // 初始化搜索引擎
   let index_path = data_dir.join(&config.storage.index_dirname);
-  let mut search = SearchEngine::open(&index_path)?;
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+
+  let plugins = plugin::Registry::load(config);
+  let learned = db.get_all_commands("local")?;
+  println!("{}", t!("learn.refresh.checking", count = learned.len()));
+
+  let (mut refreshed, mut unchanged, mut missing, mut skipped, mut failed) = (0, 0, 0, 0, 0);
+
+  for old in &learned {
+    match learn::refresh::refresh(old, &plugins, &config.learn, check) {
+      learn::refresh::Outcome::Refreshed {
+        mut cmd,
+        from,
+        to,
+        plugin_failure,
+      } => {
+        if let Some(failure) = &plugin_failure {
+          warn_plugin_failure(failure);
+        }
+        if llm || config.learn.llm.enabled {
+          enhance_with_llm(&mut cmd, config).await;
+        }
+        db.save_command(&cmd)?;
+        search::index_commands(&db, &mut search, std::slice::from_ref(&cmd))?;
+
+        let from = from
+          .as_deref()
+          .unwrap_or(t!("learn.refresh.unknown_version"));
+        let to = to.as_deref().unwrap_or(t!("learn.refresh.unknown_version"));
+        println!("  \x1b[32m{}\x1b[0m: {} -> {}", old.name, from, to);
+        refreshed += 1;
+      }
+      learn::refresh::Outcome::Unchanged => unchanged += 1,
+      learn::refresh::Outcome::Missing => {
+        println!(
+          "  \x1b[2m{}\x1b[0m",
+          t!("learn.refresh.missing", name = old.name)
+        );
+        missing += 1;
+      }
+      learn::refresh::Outcome::Skipped => skipped += 1,
+      learn::refresh::Outcome::Failed => {
+        println!(
+          "  \x1b[31m{}\x1b[0m",
+          t!("learn.refresh.failed", name = old.name)
+        );
+        failed += 1;
+      }
+    }
+  }
+  if refreshed > 0 {
+    audit::record(
+      &db,
+      &audit::Origin::cli(),
+      audit::Action::Learn,
+      refreshed,
+      match check {
+        learn::refresh::Check::Binary => "refresh",
+        learn::refresh::Check::Version => "refresh-outdated",
+      },
+    );
+  }
 
-  // 确定实际使用的来源
-  let actual_source = if source == "auto" {
-    #[cfg(target_os = "windows")]
-    {
-      "powershell"
+  println!(
+    "\n{}",
+    t!(
+      "learn.refresh.done",
+      refreshed = refreshed,
+      unchanged = unchanged,
+      missing = missing,
+      skipped = skipped,
+      failed = failed
+    )
+  );
+
+  Ok(())
+}
+
+/// 使用 LLM 生成精简示例（失败只警告，不影响学习结果）
+async fn enhance_with_llm(cmd: &mut storage::Command, config: &AppConfig) {
+  println!(
+    "{}",
+    t!("learn.llm.summarizing", model = config.learn.llm.model)
+  );
+  match learn::llm::enhance(cmd, &config.learn.llm).await {
+    Ok(count) => println!("{}", t!("learn.llm.added", count = count)),
+    Err(e) => eprintln!(
+      "\x1b[33m{}\x1b[0m {}",
+      t!("common.warning"),
+      t!("learn.llm.failed", error = e)
+    ),
+  }
+}
+
+/// parser 插件失败时的警告（已使用内置解析器的结果）
+fn warn_plugin_failure(failure: &plugin::ParseFailure) {
+  eprintln!(
+    "\x1b[33m{}\x1b[0m {}",
+    t!("common.warning"),
+    t!(
+      "learn.plugin_failed",
+      plugin = failure.plugin,
+      command = failure.command,
+      error = failure.error
+    )
+  );
+}
+
+/// 保存学习结果到数据库并更新索引
+fn save_learned_command(
+  db: &Database,
+  search: &mut SearchEngine,
+  cmd: &storage::Command,
+) -> anyhow::Result<()> {
+  // 保存到数据库
+  db.save_command(cmd)?;
+  audit::record(
+    db,
+    &audit::Origin::cli(),
+    audit::Action::Learn,
+    1,
+    &cmd.name,
+  );
+  println!("{}", t!("learn.saved"));
+
+  // 更新索引（增量，旧格式的索引从数据库重建）
+  search::index_commands(db, search, std::slice::from_ref(cmd))?;
+  println!("{}", t!("learn.indexed"));
+
+  println!(
+    "\n\x1b[32m{}\x1b[0m",
+    t!("learn.success", command = cmd.name)
+  );
+
+  // 解析质量较差时提示
+  let quality = learn::quality::assess(cmd);
+  if quality.is_low() {
+    println!(
+      "\x1b[33m{}\x1b[0m",
+      t!(
+        "learn.low_quality",
+        score = quality.score,
+        issues = quality.issues.join(", ")
+      )
+    );
+    println!("{}", t!("learn.try_better"));
+  }
+  println!("{}", t!("learn.try_command", command = cmd.name));
+
+  Ok(())
+}
+
+/// 校验配置文件（含环境变量覆盖），有错误时返回失败
+fn run_config_validate(path: Option<PathBuf>) -> anyhow::Result<()> {
+  let path = path.or_else(AppConfig::default_path);
+  let content = match &path {
+    Some(path) => {
+      println!("{}", t!("config.validating", path = path.display()));
+      std::fs::read_to_string(path).map_err(|e| {
+        anyhow::anyhow!(
+          "{}",
+          t!("config.read_failed", path = path.display(), error = e)
+        )
+      })?
     }
-    #[cfg(not(target_os = "windows"))]
-    {
-      "man"
+    None => {
+      println!("{}", t!("config.no_file"));
+      for candidate in AppConfig::search_paths() {
+        println!("{}", t!("config.looked_for", path = candidate.display()));
+      }
+      String::new()
     }
-  } else {
-    source
   };
 
-  println!("Source: {}", actual_source);
+  let issues = config::validate(&content, std::env::vars());
+  let errors = issues
+    .iter()
+    .filter(|i| i.level == config::IssueLevel::Error)
+    .count();
 
-  // 获取命令列表
-  let commands = match actual_source {
-    "man" => {
-      println!("Listing man pages in section {}...", section);
-      learn::list_man_pages(section)?
-    }
-    "powershell" | "path" => learn::list_available_commands(actual_source)?,
-    _ => {
-      anyhow::bail!(
-        "Unknown source '{}'. Use 'man', 'powershell', 'path', or 'auto'.",
-        source
-      );
+  for issue in &issues {
+    match issue.level {
+      config::IssueLevel::Error => {
+        println!("  \x1b[31m{}\x1b[0m {}", t!("config.error"), issue.message)
+      }
+      config::IssueLevel::Warning => {
+        println!(
+          "  \x1b[33m{}\x1b[0m {}",
+          t!("config.warning"),
+          issue.message
+        )
+      }
     }
+  }
+
+  if errors > 0 {
+    anyhow::bail!(
+      "{}",
+      t!(
+        "config.invalid",
+        errors = errors,
+        warnings = issues.len() - errors
+      )
+    );
+  }
+  if issues.is_empty() {
+    println!("\x1b[32m{}\x1b[0m", t!("config.valid"));
+  } else {
+    println!("{}", t!("config.valid_with_warnings", count = issues.len()));
+  }
+  Ok(())
+}
+
+/// 列出配置文件的查找位置，标出正在使用的文件
+fn run_config_path(config: &AppConfig) -> anyhow::Result<()> {
+  let active = AppConfig::default_path();
+  println!("{}", t!("config.paths"));
+  for path in AppConfig::search_paths() {
+    let status = if active.as_ref() == Some(&path) {
+      format!("\x1b[32m{}\x1b[0m", t!("config.in_use"))
+    } else if path.exists() {
+      t!("config.ignored").to_string()
+    } else {
+      t!("config.not_found").to_string()
+    };
+    println!("  {} {}", path.display(), status);
+  }
+  if active.is_none() {
+    println!("{}", t!("config.defaults"));
+  }
+  println!();
+  println!(
+    "{}",
+    t!("config.data_dir", path = get_data_dir(config).display())
+  );
+  Ok(())
+}
+
+/// 显示数据库统计信息
+/// 用本机数据运行性能基准
+fn run_bench(
+  queries: usize,
+  iterations: usize,
+  json: bool,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+  let options = bench::Options {
+    queries: queries.max(1),
+    iterations: iterations.max(1),
   };
+  let report = bench::run(&db, &data_dir, &config.search, &options, |stage| {
+    let stage = match stage {
+      bench::Stage::Database => t!("bench.database").to_string(),
+      bench::Stage::Index => t!("bench.index").to_string(),
+      bench::Stage::Search(queries) => t!("bench.search", queries = queries),
+      bench::Stage::Learn => t!("bench.learn").to_string(),
+    };
+    eprintln!("{}", t!("bench.running", stage = stage));
+  })?;
 
-  if commands.is_empty() {
-    println!("No commands found.");
-    print_learn_all_help(actual_source);
+  if json {
+    println!("{}", serde_json::to_string_pretty(&report)?);
+  } else {
+    println!("{}", bench::format_report(&report));
+  }
+  Ok(())
+}
+
+/// rtfm stats 列出的最常用命令数
+const STATS_MOST_USED: usize = 5;
+
+fn run_stats(low_quality: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    println!("{}", t!("common.no_data"));
     return Ok(());
   }
+  let db = Database::open(&db_path)?;
+  let commands = db.list_all_commands()?;
 
-  println!("Found {} commands", commands.len());
+  let mut by_lang: std::collections::BTreeMap<&str, usize> = Default::default();
+  let mut by_category: std::collections::BTreeMap<&str, usize> = Default::default();
+  for cmd in &commands {
+    *by_lang.entry(cmd.lang.as_str()).or_default() += 1;
+    *by_category.entry(cmd.category.as_str()).or_default() += 1;
+  }
 
-  // 过滤
-  let commands: Vec<_> = commands
-    .into_iter()
-    .filter(|(name, _)| {
-      if let Some(p) = prefix {
-        name.to_lowercase().starts_with(&p.to_lowercase())
-      } else {
-        true
-      }
-    })
-    .collect();
+  println!("\x1b[1m{}\x1b[0m {}", t!("stats.commands"), commands.len());
+  for (lang, count) in &by_lang {
+    println!("  {:<10} {}", lang, count);
+  }
 
-  if let Some(p) = prefix {
+  println!("\n\x1b[1m{}\x1b[0m", t!("stats.categories"));
+  for (category, count) in &by_category {
+    println!("  {:<10} {}", category, count);
+  }
+
+  // 最常查看的命令
+  let most_used = usage::most_used(&db, STATS_MOST_USED)?;
+  if !most_used.is_empty() {
+    println!("\n\x1b[1m{}\x1b[0m", t!("stats.most_used"));
+    for entry in &most_used {
+      println!("  {:<24} {:>5}", truncate(&entry.name, 24), entry.count);
+    }
+    println!("\x1b[2m{}\x1b[0m", t!("stats.usage_dashboard"));
+  }
+
+  if let Some(meta) = db.get_metadata()? {
     println!(
-      "Filtered to {} commands with prefix '{}'",
-      commands.len(),
-      p
+      "\n{}",
+      t!(
+        "stats.data_version",
+        version = meta.version,
+        updated = meta.last_update
+      )
     );
+    if let Some(source) = &meta.source {
+      println!("{}", t!("stats.data_source", source = source));
+    }
   }
 
-  // 限制数量
-  let commands: Vec<_> = if limit > 0 && commands.len() > limit {
-    println!("Limiting to {} commands", limit);
-    commands.into_iter().take(limit).collect()
-  } else {
-    commands
-  };
-
-  let total = commands.len();
-  let mut learned = 0;
-  let mut skipped = 0;
-  let mut failed = 0;
+  // 学习内容质量
+  let mut low: Vec<(&storage::Command, learn::quality::Quality)> = commands
+    .iter()
+    .filter(|c| c.lang == "local")
+    .map(|c| (c, learn::quality::assess(c)))
+    .filter(|(_, q)| q.is_low())
+    .collect();
+  let learned = by_lang.get("local").copied().unwrap_or(0);
+  if learned > 0 {
+    println!(
+      "\n\x1b[1m{}\x1b[0m {}",
+      t!("stats.quality"),
+      t!(
+        "stats.quality_counts",
+        good = learned - low.len(),
+        low = low.len(),
+        threshold = learn::quality::LOW_QUALITY_THRESHOLD
+      )
+    );
+  }
 
-  for (i, (name, _desc)) in commands.iter().enumerate() {
-    // 跳过已存在的
-    if skip_existing {
-      if let Ok(Some(_)) = db.get_command(name, "local") {
-        skipped += 1;
-        continue;
-      }
+  if low_quality {
+    low.sort_by_key(|(_, q)| q.score);
+    for (cmd, quality) in &low {
+      println!(
+        "  \x1b[33m{:>3}\x1b[0m  {:<24} {}",
+        quality.score,
+        cmd.name,
+        quality.issues.join(", ")
+      );
     }
+  } else if !low.is_empty() {
+    println!("{}", t!("stats.list_low_quality"));
+  }
 
-    print!("\r[{}/{}] Learning '{}'...", i + 1, total, name);
-    std::io::Write::flush(&mut std::io::stdout())?;
+  Ok(())
+}
 
-    // 根据来源类型获取帮助
-    let result = match actual_source {
-      "man" => learn::get_man_page_with_section(name, section),
-      _ => learn::get_help_output(name),
+/// 显示审计日志，新的在前
+fn run_audit(limit: usize, action: Option<&str>, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    println!("{}", t!("common.no_data"));
+    return Ok(());
+  }
+  let db = Database::open(&db_path)?;
+  let entries = db.list_audit(limit, action)?;
+  if entries.is_empty() {
+    println!("{}", t!("audit.empty"));
+    return Ok(());
+  }
+
+  for entry in &entries {
+    let time = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+      .map(|t| {
+        t.with_timezone(&chrono::Local)
+          .format("%Y-%m-%d %H:%M:%S")
+          .to_string()
+      })
+      .unwrap_or_else(|_| entry.timestamp.clone());
+    let mut origin = entry.source.clone();
+    if let Some(client) = &entry.client {
+      origin.push_str(&format!(" {}", client));
+    }
+    if let Some(user) = &entry.user {
+      origin.push_str(&format!(" ({})", user));
+    }
+    println!(
+      "\x1b[2m{:>5}\x1b[0m  {}  \x1b[1m{:<8}\x1b[0m {:<24} {:>14}  {}",
+      entry.id,
+      time,
+      entry.action,
+      origin,
+      t!("audit.commands", count = entry.count),
+      entry.detail
+    );
+  }
+  Ok(())
+}
+
+/// 使用统计面板
+fn run_usage_dashboard(limit: usize, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    println!("{}", t!("common.no_data"));
+    return Ok(());
+  }
+  let db = Database::open(&db_path)?;
+  let report = usage::report(&db, limit, learn::find_binary)?;
+
+  if report.total_lookups == 0 && report.total_searches == 0 {
+    println!("{}", t!("usage.empty"));
+    if !config.search.record_usage {
+      println!("{}", t!("usage.recording_off"));
+    }
+    return Ok(());
+  }
+
+  let percent = |part: u64, total: u64| {
+    if total == 0 {
+      0.0
+    } else {
+      part as f64 * 100.0 / total as f64
+    }
+  };
+  println!(
+    "\x1b[1m{}\x1b[0m {}   \x1b[1m{}\x1b[0m {} {}",
+    t!("usage.lookups"),
+    report.total_lookups,
+    t!("usage.searches"),
+    report.total_searches,
+    t!(
+      "usage.zero_results",
+      count = report.zero_result_searches,
+      percent = format!(
+        "{:.0}",
+        percent(report.zero_result_searches, report.total_searches)
+      )
+    )
+  );
+
+  // 条形长度按列表中的最大值缩放
+  let bar = |count: u64, max: u64| "█".repeat(((count * 20).div_ceil(max.max(1))) as usize);
+
+  println!("\n\x1b[1m{}\x1b[0m", t!("usage.top_lookups"));
+  let max = report.top_lookups.first().map_or(0, |e| e.count);
+  for entry in &report.top_lookups {
+    println!(
+      "  {:<24} {:>5}  \x1b[36m{}\x1b[0m",
+      truncate(&entry.name, 24),
+      entry.count,
+      bar(entry.count, max)
+    );
+  }
+  if report.top_lookups.is_empty() {
+    println!("{}", t!("common.none"));
+  }
+
+  println!(
+    "\n\x1b[1m{}\x1b[0m",
+    t!("usage.trending", days = usage::TRENDING_DAYS)
+  );
+  let max = report.trending.first().map_or(0, |e| e.count);
+  for entry in &report.trending {
+    println!(
+      "  {:<24} {:>5}  \x1b[32m{}\x1b[0m",
+      truncate(&entry.name, 24),
+      entry.count,
+      bar(entry.count, max)
+    );
+  }
+  if report.trending.is_empty() {
+    println!("{}", t!("common.none"));
+  }
+
+  println!("\n\x1b[1m{}\x1b[0m", t!("usage.no_result_searches"));
+  let max = report
+    .zero_result_queries
+    .first()
+    .map_or(0, |e| e.zero_results);
+  for entry in &report.zero_result_queries {
+    println!(
+      "  {:<24} {:>5}  \x1b[33m{}\x1b[0m",
+      truncate(&entry.name, 24),
+      entry.zero_results,
+      bar(entry.zero_results, max)
+    );
+  }
+  if report.zero_result_queries.is_empty() {
+    println!("{}", t!("common.none"));
+  }
+
+  println!(
+    "\n\x1b[1m{}\x1b[0m {}",
+    t!("usage.gaps"),
+    t!("usage.gaps_hint")
+  );
+  for gap in &report.learning_gaps {
+    println!(
+      "  {:<24} {:>5}  \x1b[90m{}\x1b[0m",
+      truncate(&gap.name, 24),
+      gap.searches,
+      gap.binary
+    );
+  }
+  if report.learning_gaps.is_empty() {
+    println!("{}", t!("common.none"));
+  } else {
+    println!(
+      "\n{}",
+      t!(
+        "usage.learn_gap",
+        command = format!("\x1b[36mrtfm learn {}\x1b[0m", report.learning_gaps[0].name)
+      )
+    );
+  }
+
+  Ok(())
+}
+
+/// 清除使用统计
+fn run_clear_usage(config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if db_path.exists() {
+    Database::open(&db_path)?.clear_usage()?;
+  }
+  println!("{}", t!("usage.cleared"));
+  Ok(())
+}
+
+/// 打印学习命令失败的人性化错误信息
+fn print_learn_error(command: &str, help_err: &anyhow::Error, man_err: &anyhow::Error) {
+  let help_err_str = help_err.to_string();
+  let man_err_str = man_err.to_string();
+
+  eprintln!(
+    "\n\x1b[1;31m{}\x1b[0m\n",
+    t!("learn.error.failed", command = command)
+  );
+
+  // 分析错误类型
+  // 命令不存在：--help 返回 "program not found" 或类似错误
+  let cmd_not_found = help_err_str.contains("program not found")
+    || help_err_str.contains("No such file")
+    || help_err_str.contains("not recognized")
+    || help_err_str.contains("command not found");
+
+  // man 不可用（Windows 常见情况）
+  let man_not_available = man_err_str.contains("program not found");
+
+  if cmd_not_found && man_not_available {
+    // 命令不存在，且 man 也不可用
+    eprintln!(
+      "\x1b[33m{}\x1b[0m",
+      t!("learn.error.not_found", command = command)
+    );
+    eprintln!();
+    eprintln!("{}", t!("learn.error.reasons"));
+    eprintln!();
+    eprintln!("{}", t!("common.try"));
+    eprintln!("{}", t!("learn.error.install_first", command = command));
+    eprintln!("{}", t!("common.try_update"));
+  } else if cmd_not_found {
+    // 命令不存在，但 man 可用（返回了其他错误）
+    eprintln!(
+      "\x1b[33m{}\x1b[0m",
+      t!("learn.error.not_found", command = command)
+    );
+    eprintln!();
+    eprintln!("{}", t!("learn.error.not_in_path"));
+    eprintln!();
+    eprintln!("{}", t!("common.try"));
+    eprintln!("{}", t!("learn.error.install_first", command = command));
+    eprintln!("{}", t!("common.try_update"));
+    eprintln!("{}", t!("learn.error.try_man", command = command));
+  } else if man_not_available {
+    // 命令可能存在但 --help 失败了，且 man 不可用
+    eprintln!(
+      "\x1b[33m{}\x1b[0m",
+      t!("learn.error.no_man", command = command)
+    );
+    eprintln!();
+
+    #[cfg(target_os = "windows")]
+    {
+      eprintln!("{}", t!("learn.error.windows_no_man", command = command));
+      eprintln!();
+      eprintln!("{}", t!("common.details"));
+      eprintln!("  --help: {}", help_err_str);
+      eprintln!();
+      eprintln!("{}", t!("learn.error.alternatives"));
+      eprintln!("{}", t!("common.try_update"));
+      eprintln!("{}", t!("learn.error.help_flag", command = command));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+      eprintln!("{}", t!("common.details"));
+      eprintln!("  --help: {}", help_err_str);
+      eprintln!();
+      eprintln!("{}", t!("common.try"));
+      eprintln!("{}", t!("learn.error.install_man"));
+      eprintln!("{}", t!("common.try_update"));
+    }
+  } else {
+    // 其他错误，显示原始信息
+    eprintln!("{}", t!("learn.error.unusable"));
+    eprintln!();
+    eprintln!("{}", t!("common.details"));
+    eprintln!("  --help: {}", help_err_str);
+    eprintln!("  man:    {}", man_err_str);
+    eprintln!();
+    eprintln!("{}", t!("common.try"));
+    eprintln!("{}", t!("learn.error.run_help", command = command));
+    eprintln!("{}", t!("common.try_update"));
+  }
+
+  eprintln!();
+}
+
+/// learn-all 的运行方式
+#[derive(Clone, Copy, PartialEq)]
+enum LearnAllMode {
+  /// 全新运行（覆盖旧的断点）
+  Fresh,
+  /// 从断点继续，跳过已处理的命令
+  Resume,
+  /// 只重试上次失败的命令
+  RetryFailed,
+}
+
+/// 每学会多少个命令提交一次数据库与索引，并保存断点
+const CHECKPOINT_INTERVAL: usize = 25;
+
+/// 批量学习命令（跨平台）
+/// - Linux/macOS: 默认从 man 页面学习
+/// - Windows: 默认从 PowerShell cmdlet 学习
+/// - 所有平台: 可以从 PATH 中的可执行文件学习
+async fn run_learn_all(
+  section: &str,
+  limit: usize,
+  skip_existing: bool,
+  prefix: Option<&str>,
+  source: &str,
+  mode: LearnAllMode,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let jobs = config.learn.jobs;
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+
+  // 初始化数据库
+  let db_path = data_dir.join(&config.storage.db_filename);
+  let db = Database::open(&db_path)?;
+
+  // 初始化搜索引擎
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+
+  // 继续或重试时沿用上次运行的来源与章节
+  let previous = match mode {
+    LearnAllMode::Fresh => None,
+    _ => Some(
+      db.get_learn_checkpoint()?
+        .ok_or_else(|| anyhow::anyhow!("{}", t!("learn_all.no_checkpoint")))?,
+    ),
+  };
+  let (source, section) = match &previous {
+    Some(cp) => (cp.source.clone(), cp.section.clone()),
+    None => (source.to_string(), section.to_string()),
+  };
+  let (source, section) = (source.as_str(), section.as_str());
+
+  if mode == LearnAllMode::RetryFailed {
+    let previous = previous.unwrap_or_default();
+    if previous.failed.is_empty() {
+      println!("{}", t!("learn_all.nothing_to_retry"));
+      db.clear_learn_checkpoint()?;
+      return Ok(());
+    }
+    println!(
+      "{}",
+      t!(
+        "learn_all.retrying",
+        count = previous.failed.len(),
+        source = source
+      )
+    );
+    let commands = previous
+      .failed
+      .iter()
+      .map(|name| (name.clone(), String::new()))
+      .collect();
+    let checkpoint = storage::LearnCheckpoint {
+      failed: Vec::new(),
+      ..previous
+    };
+    return learn_all_batch(&db, &mut search, commands, false, checkpoint, jobs, config).await;
+  }
+
+  // 确定实际使用的来源
+  let actual_source = if source == "auto" {
+    #[cfg(target_os = "windows")]
+    {
+      "powershell"
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      learn::default_man_source()
+    }
+  } else {
+    source
+  };
+
+  println!("{}", t!("learn_all.source", source = actual_source));
+
+  // 获取命令列表
+  let commands = match actual_source {
+    "man" => {
+      println!("{}", t!("learn_all.listing_man", section = section));
+      learn::list_man_pages(section)?
+    }
+    "manpath" => {
+      println!("{}", t!("learn_all.reading_manpath", section = section));
+      learn::roff::list_pages(section)
+    }
+    "powershell" | "path" | "builtin" => learn::list_available_commands(actual_source)?,
+    "history" => {
+      println!("{}", t!("learn_all.reading_history"));
+      learn::history::list_commands()?
+        .into_iter()
+        .map(|(name, count)| (name, format!("used {} times", count)))
+        .collect()
+    }
+    _ => {
+      anyhow::bail!("{}", t!("learn_all.unknown_source", source = source));
+    }
+  };
+
+  if commands.is_empty() {
+    println!("{}", t!("learn_all.none"));
+    print_learn_all_help(actual_source);
+    return Ok(());
+  }
+
+  println!("{}", t!("learn_all.found", count = commands.len()));
+
+  // 过滤
+  let commands: Vec<_> = commands
+    .into_iter()
+    .filter(|(name, _)| {
+      if let Some(p) = prefix {
+        name.to_lowercase().starts_with(&p.to_lowercase())
+      } else {
+        true
+      }
+    })
+    .collect();
+
+  if let Some(p) = prefix {
+    println!(
+      "{}",
+      t!("learn_all.prefix", count = commands.len(), prefix = p)
+    );
+  }
+
+  // 按配置的 allow / deny 列表过滤
+  let before = commands.len();
+  let commands: Vec<_> = commands
+    .into_iter()
+    .filter(|(name, _)| learn::is_learnable(name, &config.learn))
+    .collect();
+  if commands.len() < before {
+    println!(
+      "{}",
+      t!("learn_all.excluded", count = before - commands.len())
+    );
+  }
+
+  // 限制数量
+  let commands: Vec<_> = if limit > 0 && commands.len() > limit {
+    println!("{}", t!("learn_all.limit", count = limit));
+    commands.into_iter().take(limit).collect()
+  } else {
+    commands
+  };
+
+  let checkpoint = match previous {
+    Some(previous) => {
+      let done: std::collections::HashSet<&str> =
+        previous.processed.iter().map(String::as_str).collect();
+      let remaining: Vec<_> = commands
+        .into_iter()
+        .filter(|(name, _)| !done.contains(name.as_str()))
+        .collect();
+      println!(
+        "{}",
+        t!(
+          "learn_all.resuming",
+          processed = previous.processed.len(),
+          remaining = remaining.len()
+        )
+      );
+      return learn_all_batch(
+        &db,
+        &mut search,
+        remaining,
+        skip_existing,
+        previous,
+        jobs,
+        config,
+      )
+      .await;
+    }
+    None => storage::LearnCheckpoint {
+      source: actual_source.to_string(),
+      section: section.to_string(),
+      ..Default::default()
+    },
+  };
+
+  learn_all_batch(
+    &db,
+    &mut search,
+    commands,
+    skip_existing,
+    checkpoint,
+    jobs,
+    config,
+  )
+  .await
+}
+
+/// 从 shell 历史中找出常用但数据库中没有的命令，确认后批量学习
+async fn run_suggest_learn(
+  limit: usize,
+  min_count: usize,
+  yes: bool,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
+
+  println!("{}", t!("learn_all.reading_history"));
+  let suggestions: Vec<(String, usize)> = learn::history::list_commands()?
+    .into_iter()
+    .filter(|(name, count)| {
+      *count >= min_count
+        && learn::is_learnable(name, &config.learn)
+        && db
+          .find_command(name, &config.search.default_lang)
+          .ok()
+          .flatten()
+          .is_none()
+    })
+    .take(limit)
+    .collect();
+
+  if suggestions.is_empty() {
+    println!("{}", t!("suggest.none"));
+    return Ok(());
+  }
+
+  println!("\n\x1b[1m{}\x1b[0m\n", t!("suggest.title"));
+  for (i, (name, count)) in suggestions.iter().enumerate() {
+    println!(
+      "  \x1b[32m{:2}.\x1b[0m \x1b[1m{:<24}\x1b[0m \x1b[90m{}\x1b[0m",
+      i + 1,
+      name,
+      t!("suggest.used", count = count)
+    );
+  }
+
+  if !yes {
+    println!(
+      "\n\x1b[1m{}\x1b[0m ",
+      t!("suggest.confirm", count = suggestions.len())
+    );
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+      println!("{}", t!("common.cancelled"));
+      return Ok(());
+    }
+  }
+
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+  let commands = suggestions
+    .into_iter()
+    .map(|(name, count)| (name, format!("used {} times", count)))
+    .collect();
+  let checkpoint = storage::LearnCheckpoint {
+    source: "history".to_string(),
+    ..Default::default()
+  };
+  learn_all_batch(
+    &db,
+    &mut search,
+    commands,
+    false,
+    checkpoint,
+    config.learn.jobs,
+    config,
+  )
+  .await
+}
+
+/// 并发获取帮助（最多 jobs 个阻塞任务，0 表示 CPU 核数），每学会 CHECKPOINT_INTERVAL 个命令
+/// 提交一次数据库与索引并保存断点，以便中断后继续
+async fn learn_all_batch(
+  db: &Database,
+  search: &mut SearchEngine,
+  commands: Vec<(String, String)>,
+  skip_existing: bool,
+  mut checkpoint: storage::LearnCheckpoint,
+  jobs: usize,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let plugins = Arc::new(plugin::Registry::load(config));
+  let jobs = match jobs {
+    0 => std::thread::available_parallelism().map_or(4, |n| n.get()),
+    n => n,
+  };
+  let learn_config = Arc::new(config.learn.clone());
+  let mut learned = 0;
+  let mut skipped = 0;
+  let mut failed = 0;
+  let mut low_quality = 0;
+  // 原因 -> 命令名
+  let mut unlearnable: std::collections::BTreeMap<&str, Vec<String>> = Default::default();
+
+  let progress = indicatif::ProgressBar::new(commands.len() as u64);
+  progress.set_style(
+    indicatif::ProgressStyle::with_template(
+      "{spinner:.green} [{elapsed_precise}] [{bar:30.cyan/blue}] {pos}/{len} (ETA {eta}) {msg}",
+    )?
+    .progress_chars("=> "),
+  );
+  progress.enable_steady_tick(std::time::Duration::from_millis(120));
+
+  let mut queue = commands.into_iter();
+  let mut pending = tokio::task::JoinSet::new();
+  // 已学会、尚未提交的命令
+  let mut batch: Vec<storage::Command> = Vec::new();
+  loop {
+    while pending.len() < jobs {
+      let Some((name, _desc)) = queue.next() else {
+        break;
+      };
+      // 跳过已存在的
+      if skip_existing {
+        if let Ok(Some(_)) = db.get_command(&name, "local") {
+          skipped += 1;
+          checkpoint.processed.push(name);
+          progress.inc(1);
+          continue;
+        }
+      }
+      let source = checkpoint.source.clone();
+      let section = checkpoint.section.clone();
+      let learn_config = learn_config.clone();
+      let plugins = plugins.clone();
+      // 解析和记录二进制信息（运行 --version）也在工作线程中进行，慢的程序不会拖住其他命令
+      pending.spawn_blocking(move || {
+        let result = capture_help(&name, &source, &section, &learn_config).map(|(content, src)| {
+          let (mut cmd, plugin_failure) = plugins.parse_help_content(&name, &content, &src);
+          learn::record_binary(&mut cmd);
+          (cmd, plugin_failure)
+        });
+        (name, result)
+      });
+    }
+
+    let Some(joined) = pending.join_next().await else {
+      break;
+    };
+    let (name, result) = joined?;
+    progress.set_message(t!("learn.learning", command = name));
+    progress.inc(1);
+
+    // 交互式 / GUI 程序不计为失败
+    match result {
+      Ok((cmd, plugin_failure)) => {
+        if let Some(failure) = &plugin_failure {
+          progress.suspend(|| warn_plugin_failure(failure));
+        }
+        batch.push(cmd);
+      }
+      Err(e) => {
+        match e.downcast_ref::<learn::Unlearnable>() {
+          Some(reason) => {
+            unlearnable
+              .entry(reason.reason())
+              .or_default()
+              .push(name.clone());
+          }
+          None => {
+            failed += 1;
+            if !checkpoint.failed.contains(&name) {
+              checkpoint.failed.push(name.clone());
+            }
+          }
+        }
+        if !checkpoint.processed.contains(&name) {
+          checkpoint.processed.push(name);
+        }
+      }
+    }
+
+    if batch.len() >= CHECKPOINT_INTERVAL {
+      let (saved, low, lost) = commit_learned(db, search, &mut batch, &mut checkpoint);
+      learned += saved;
+      low_quality += low;
+      failed += lost;
+      save_checkpoint(db, &mut checkpoint)?;
+    }
+  }
+  let (saved, low, lost) = commit_learned(db, search, &mut batch, &mut checkpoint);
+  learned += saved;
+  low_quality += low;
+  failed += lost;
+  progress.finish_and_clear();
+
+  if learned > 0 {
+    audit::record(
+      db,
+      &audit::Origin::cli(),
+      audit::Action::Learn,
+      learned,
+      format!("learn-all ({})", checkpoint.source),
+    );
+  }
+
+  // 全部成功则清除断点，否则保留以便 --retry-failed
+  if checkpoint.failed.is_empty() {
+    db.clear_learn_checkpoint()?;
+  } else {
+    save_checkpoint(db, &mut checkpoint)?;
+  }
+
+  println!("\x1b[32m{}\x1b[0m", t!("learn_all.done"));
+  println!("{}", t!("learn_all.learned", count = learned));
+  if low_quality > 0 {
+    println!("{}", t!("learn_all.low_quality", count = low_quality));
+  }
+  if skipped > 0 {
+    println!("{}", t!("learn_all.skipped", count = skipped));
+  }
+  for (reason, names) in &unlearnable {
+    let preview = names.iter().take(5).cloned().collect::<Vec<_>>().join(", ");
+    let more = if names.len() > 5 { ", ..." } else { "" };
+    println!(
+      "{}",
+      t!(
+        "learn_all.unlearnable",
+        count = names.len(),
+        reason = match *reason {
+          "interactive" => t!("learn_all.reason.interactive"),
+          "GUI" => t!("learn_all.reason.gui"),
+          other => other,
+        },
+        names = format!("{}{}", preview, more)
+      )
+    );
+  }
+  if failed > 0 {
+    println!("{}", t!("learn_all.failed", count = failed));
+  }
+  if !checkpoint.failed.is_empty() {
+    println!(
+      "\n{}",
+      t!("learn_all.retry_hint", count = checkpoint.failed.len())
+    );
+  }
+  println!("\n{}", t!("learn_all.total", count = db.count_commands()?));
+
+  Ok(())
+}
+
+/// 按来源获取一个命令的帮助内容（在阻塞任务中运行）
+fn capture_help(
+  name: &str,
+  source: &str,
+  section: &str,
+  config: &config::LearnConfig,
+) -> anyhow::Result<(String, String)> {
+  match source {
+    "man" => learn::get_man_page_with_section(name, section),
+    "manpath" => learn::roff::get_page(name, Some(section)),
+    "builtin" => learn::get_builtin_help(name),
+    // 历史中的命令可能是内建命令或只有 man 页面
+    "history" => learn::get_local_help(name, false, config).map_err(|(help_e, _)| help_e),
+    _ => learn::get_help_output(name, config),
+  }
+}
+
+/// 一次提交 batch 中的命令到数据库与索引，返回 (学会的数量, 其中低质量的数量, 失败的数量)
+/// 提交失败的命令记为失败；无论成功与否都计入已处理
+fn commit_learned(
+  db: &Database,
+  search: &mut SearchEngine,
+  batch: &mut Vec<storage::Command>,
+  checkpoint: &mut storage::LearnCheckpoint,
+) -> (usize, usize, usize) {
+  if batch.is_empty() {
+    return (0, 0, 0);
+  }
+  let commands = std::mem::take(batch);
+  let saved = match db
+    .save_commands(&commands)
+    .map_err(anyhow::Error::from)
+    .and_then(|()| search::index_commands(db, search, &commands))
+  {
+    Ok(()) => true,
+    Err(e) => {
+      tracing::warn!("Failed to save {} learned commands: {}", commands.len(), e);
+      false
+    }
+  };
+
+  let mut low_quality = 0;
+  for cmd in &commands {
+    if !saved && !checkpoint.failed.contains(&cmd.name) {
+      checkpoint.failed.push(cmd.name.clone());
+    }
+    if saved && learn::quality::assess(cmd).is_low() {
+      low_quality += 1;
+    }
+    if !checkpoint.processed.contains(&cmd.name) {
+      checkpoint.processed.push(cmd.name.clone());
+    }
+  }
+  if saved {
+    (commands.len(), low_quality, 0)
+  } else {
+    (0, 0, commands.len())
+  }
+}
+
+/// 保存 learn-all 断点
+fn save_checkpoint(db: &Database, checkpoint: &mut storage::LearnCheckpoint) -> anyhow::Result<()> {
+  checkpoint.updated_at = chrono::Utc::now().to_rfc3339();
+  db.save_learn_checkpoint(checkpoint)?;
+  Ok(())
+}
+
+/// 打印 learn-all 帮助信息
+fn print_learn_all_help(source: &str) {
+  match source {
+    "man" | "manpath" => {
+      println!("\n{}", t!("learn_all.man_sections"));
+    }
+    "powershell" => {
+      println!("\n{}", t!("common.try"));
+      println!("{}", t!("learn_all.powershell_hint"));
+    }
+    _ => {
+      println!("\n{}", t!("learn_all.sources"));
+      #[cfg(target_os = "windows")]
+      println!("{}", t!("learn_all.sources.windows"));
+      #[cfg(not(target_os = "windows"))]
+      println!("{}", t!("learn_all.sources.unix"));
+    }
+  }
+}
+
+/// 备份应用数据到归档文件；user_only 时只备份用户数据（本地命令、笔记、收藏和别名）
+async fn run_backup(output: &str, user_only: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+
+  // 检查数据目录
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("backup.no_data"));
+  }
+
+  println!("{}", t!("backup.backing_up", path = data_dir.display()));
+
+  let output_path = PathBuf::from(output);
+  if user_only {
+    let data = backup::UserData::collect(&Database::open(&db_path)?)?;
+    backup::write_user_archive(&output_path, &data)?;
+    print_user_data(&data);
+  } else {
+    let index_path = data_dir.join(&config.storage.index_dirname);
+    for entry in backup::write_archive(&output_path, &db_path, &index_path, &data_dir, config)? {
+      println!("{}", t!("backup.added", entry = entry));
+    }
+  }
+
+  let file_size = std::fs::metadata(&output_path)?.len();
+  println!("\n\x1b[32m{}\x1b[0m", t!("backup.done"));
+  println!("{}", t!("backup.output", path = output_path.display()));
+  println!(
+    "{}",
+    t!(
+      "backup.size",
+      bytes = file_size,
+      mb = format!("{:.2}", file_size as f64 / 1024.0 / 1024.0)
+    )
+  );
+  println!("\n{}", t!("backup.restore_hint"));
+  println!("  rtfm restore {}", output);
+
+  Ok(())
+}
+
+/// 列出用户数据各部分的条数
+fn print_user_data(data: &backup::UserData) {
+  println!(
+    "{}",
+    t!(
+      "backup.user_data",
+      commands = data.commands.len(),
+      notes = data.notes.len(),
+      favorites = data.favorites.len(),
+      aliases = data.aliases.len()
+    )
+  );
+}
+
+/// 导出用户数据（JSON），可用 rtfm restore 恢复
+fn run_export_user_data(output: Option<PathBuf>, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let data = backup::UserData::collect(&Database::open(&db_path)?)?;
+  let json = serde_json::to_string_pretty(&data)? + "\n";
+  match &output {
+    None => print!("{}", json),
+    Some(path) => std::fs::write(path, json)?,
+  }
+  eprintln!(
+    "{}",
+    t!(
+      "export.user_data",
+      commands = data.commands.len(),
+      notes = data.notes.len(),
+      favorites = data.favorites.len(),
+      aliases = data.aliases.len()
+    )
+  );
+  Ok(())
+}
+
+/// 合并用户数据（--user-only 备份或 export --user-data），只重新索引变化的命令
+fn restore_user_data(
+  data: &backup::UserData,
+  path: &str,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
+  print_user_data(data);
+  let noted = data.restore(&db)?;
+
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = search::open_index(&db, &index_path, &config.search)?;
+  search::index_commands(&db, &mut search, &data.commands)?;
+  for name in &noted {
+    search::reindex_command(&db, &mut search, name)?;
+  }
+
+  audit::record(
+    &db,
+    &audit::Origin::cli(),
+    audit::Action::Restore,
+    data.count(),
+    format!("{} (user data)", path),
+  );
+  println!("\n\x1b[32m{}\x1b[0m", t!("restore.done"));
+  println!("{}", t!("restore.commands", count = db.count_commands()?));
+  Ok(())
+}
+
+/// 从备份恢复数据
+/// merge: 把备份中的命令合并到现有数据库并增量更新索引，否则替换数据库和索引
+/// only: 只恢复这些部分（db、index、config），为空时恢复全部
+/// 只含用户数据的备份和导出的 JSON 总是合并，不影响其他数据
+async fn run_restore(
+  path: &str,
+  merge: bool,
+  only: &[String],
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let archive_path = PathBuf::from(path);
+  if !archive_path.exists() {
+    anyhow::bail!("{}", t!("restore.not_found", path = path));
+  }
+  let restore = |part: &str| only.is_empty() || only.iter().any(|p| p == part);
+  // 合并时索引由合并的命令更新，不使用备份中的索引
+  if merge && !restore("db") && restore("index") {
+    anyhow::bail!("{}", t!("restore.merge_index"));
+  }
+
+  println!("{}", t!("restore.restoring", path = path));
+
+  // export --user-data 导出的 JSON
+  if archive_path
+    .extension()
+    .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+  {
+    if !only.is_empty() {
+      anyhow::bail!("{}", t!("restore.user_data_only"));
+    }
+    let data = backup::UserData::from_json(&std::fs::read_to_string(&archive_path)?)?;
+    return restore_user_data(&data, path, config);
+  }
+
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db_path = data_dir.join(&config.storage.db_filename);
+  let index_path = data_dir.join(&config.storage.index_dirname);
+
+  // 先解压到数据目录中的临时目录，之后移动到位
+  println!("{}", t!("restore.extracting"));
+  let staging = tempfile::tempdir_in(&data_dir)?;
+  let extracted = backup::extract(&archive_path, staging.path())?;
+  if let (None, Some(user_data)) = (&extracted.db, &extracted.user_data) {
+    if !only.is_empty() {
+      anyhow::bail!("{}", t!("restore.user_data_only"));
+    }
+    let data = backup::UserData::from_json(&std::fs::read_to_string(user_data)?)?;
+    return restore_user_data(&data, path, config);
+  }
+  let backup_db = match (&extracted.db, restore("db")) {
+    (Some(db), true) => Some(db),
+    (None, true) => anyhow::bail!("{}", t!("restore.no_database", path = path)),
+    (_, false) => None,
+  };
+
+  let mut detail = path.to_string();
+  if merge {
+    detail.push_str(" --merge");
+  }
+  if !only.is_empty() {
+    detail.push_str(&format!(" --only {}", only.join(",")));
+  }
+
+  let mut rebuild = false;
+  let db = if let Some(backup_db) = backup_db {
+    if merge {
+      // 合并备份中的命令，只重新索引这些命令
+      let commands = Database::open(backup_db)?.list_all_commands()?;
+      let db = Database::open(&db_path)?;
+      db.save_commands(&commands)?;
+      let mut search = SearchEngine::open(&index_path, &config.search)?;
+      search::index_commands(&db, &mut search, &commands)?;
+      println!("{}", t!("restore.merged", count = commands.len()));
+      db
+    } else {
+      let db_backup_path = data_dir.join(format!("{}.backup", config.storage.db_filename));
+      let replaced = db_path.exists();
+      if replaced {
+        println!(
+          "{}",
+          t!("restore.backup_db", path = db_backup_path.display())
+        );
+        std::fs::rename(&db_path, &db_backup_path)?;
+      }
+      std::fs::rename(backup_db, &db_path)?;
+      let db = Database::open(&db_path)?;
+      // 审计记录属于本机，不使用备份中的
+      if replaced {
+        audit::carry_over(&Database::open(&db_backup_path)?, &db)?;
+      }
+      // 不恢复备份中的索引时按新的数据库重建
+      rebuild = true;
+      db
+    }
+  } else {
+    Database::open(&db_path)?
+  };
+
+  if !merge && restore("index") {
+    match &extracted.index {
+      Some(backup_index) => {
+        if index_path.exists() {
+          let backup_path = data_dir.join(format!("{}.backup", config.storage.index_dirname));
+          println!(
+            "{}",
+            t!("restore.backup_index", path = backup_path.display())
+          );
+          if backup_path.exists() {
+            std::fs::remove_dir_all(&backup_path)?;
+          }
+          std::fs::rename(&index_path, &backup_path)?;
+        }
+        std::fs::rename(backup_index, &index_path)?;
+        rebuild = false;
+      }
+      // 备份中没有索引时按数据库重建
+      None => rebuild = true,
+    }
+  }
+  if rebuild {
+    println!("{}", t!("restore.rebuilding_index"));
+    let mut search = SearchEngine::open(&index_path, &config.search)?;
+    search::rebuild_index(&db, &mut search)?;
+  }
+
+  let config_path = data_dir.join("config.toml");
+  let config_restored = match &extracted.config {
+    Some(backup_config) if restore("config") => {
+      std::fs::rename(backup_config, &config_path)?;
+      true
+    }
+    _ => false,
+  };
+
+  let count = db.count_commands()?;
+  audit::record(
+    &db,
+    &audit::Origin::cli(),
+    audit::Action::Restore,
+    count,
+    detail,
+  );
+
+  println!("\n\x1b[32m{}\x1b[0m", t!("restore.done"));
+  println!("{}", t!("restore.commands", count = count));
+  if config_restored {
+    println!("{}", t!("restore.config", path = config_path.display()));
+  }
+  println!("\n{}", t!("learn.try_command", command = "tar"));
+
+  Ok(())
+}
+
+/// 与远程 rtfm 服务器交换命令，只传输内容哈希不同的命令
+async fn run_sync(action: SyncAction, config: &AppConfig) -> anyhow::Result<()> {
+  let (pull, args) = match action {
+    SyncAction::Push(args) => (false, args),
+    SyncAction::Pull(args) => (true, args),
+  };
+  let Some(remote) = args.remote.or_else(|| config.sync.remote.clone()) else {
+    anyhow::bail!("{}", t!("sync.no_remote"));
+  };
+  let policy_name = args.policy.as_deref().unwrap_or(&config.sync.policy);
+  let Some(policy) = sync::SyncPolicy::parse(policy_name) else {
+    anyhow::bail!("{}", t!("sync.unknown_policy", policy = policy_name));
+  };
+  let lang = args.lang.as_deref();
+
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
+  let token = args.token.or_else(|| config.sync.token());
+  let client = sync::SyncClient::new(&remote, &config.update.user_agent, token.as_deref())?;
+
+  println!("{}", t!("sync.comparing", remote = remote));
+  let local = sync::manifest(&db, lang)?;
+  let remote_entries = client.manifest(lang).await?;
+
+  let local_personal = sync::PersonalData::collect(&db)?;
+  let remote_personal = client.personal().await?;
+
+  let report = if pull {
+    let keys = sync::diff(&remote_entries, &local);
+    let personal = remote_personal.missing_from(&local_personal);
+    if keys.is_empty() && personal.is_empty() {
+      println!("{}", t!("sync.up_to_date"));
+      return Ok(());
+    }
+    let (mut report, mut changed) = (sync::SyncReport::default(), Vec::new());
+    if !keys.is_empty() {
+      println!("{}", t!("sync.fetching", count = keys.len()));
+      let commands = client.fetch(&keys).await?;
+      (report, changed) = sync::apply(&db, commands, policy)?;
+    }
+    let noted = sync::apply_personal(&db, &personal, policy, &mut report)?;
+    if report.changed() > 0 {
+      audit::record(
+        &db,
+        &audit::Origin::cli(),
+        audit::Action::Sync,
+        report.changed(),
+        format!("pull from {}", remote),
+      );
+    }
+    // 只索引变化的命令和笔记有变化的命令
+    if !changed.is_empty() || !noted.is_empty() {
+      let index_path = data_dir.join(&config.storage.index_dirname);
+      let mut search = SearchEngine::open(&index_path, &config.search)?;
+      search::index_commands(&db, &mut search, &changed)?;
+      for name in &noted {
+        search::reindex_command(&db, &mut search, name)?;
+      }
+    }
+    report
+  } else {
+    let keys = sync::diff(&local, &remote_entries);
+    let personal = local_personal.missing_from(&remote_personal);
+    if keys.is_empty() && personal.is_empty() {
+      println!("{}", t!("sync.remote_up_to_date"));
+      return Ok(());
+    }
+    if !keys.is_empty() {
+      println!("{}", t!("sync.pushing", count = keys.len()));
+    }
+    let commands = sync::fetch(&db, &keys)?;
+    client.push(&commands, &personal, policy).await?
+  };
+
+  println!(
+    "\n\x1b[32m{}\x1b[0m {}",
+    t!("sync.done"),
+    t!(
+      "sync.counts",
+      added = report.added,
+      updated = report.updated,
+      kept = report.kept
+    )
+  );
+  if report.favorites + report.notes > 0 {
+    println!(
+      "{}",
+      t!(
+        "sync.personal_counts",
+        favorites = report.favorites,
+        notes = report.notes
+      )
+    );
+  }
+  if report.kept > 0 {
+    let message = if pull {
+      t!("sync.kept_local", count = report.kept)
+    } else {
+      t!("sync.kept_remote", count = report.kept)
     };
+    println!("\x1b[2m{}\x1b[0m", message);
+  }
+  Ok(())
+}
 
-    match result {
-      Ok((content, src)) => {
-        let cmd = learn::parse_help_content(name, &content, &src);
-        if db.save_command(&cmd).is_ok() && search.index_single_command(&cmd).is_ok() {
-          learned += 1;
+/// 按语言、命令名和平台选出要导出的命令
+/// 未指定语言时为默认语言的 tldr 页面和本机学习的命令
+fn export_selection(
+  lang: Option<&str>,
+  names: &[String],
+  platform: Option<&str>,
+  config: &AppConfig,
+) -> anyhow::Result<Vec<storage::Command>> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+
+  let mut commands = match lang {
+    Some(lang) => db.get_all_commands(lang)?,
+    None => {
+      let mut commands = db.get_all_commands(&config.search.default_lang)?;
+      if config.search.default_lang != "local" {
+        commands.extend(db.get_all_commands("local")?);
+      }
+      commands
+    }
+  };
+  commands.retain(|cmd| {
+    platform.is_none_or(|p| cmd.platform == p)
+      && (names.is_empty()
+        || names.iter().any(|pattern| match pattern.strip_suffix('*') {
+          Some(prefix) => cmd.name.starts_with(prefix),
+          None => cmd.name == *pattern,
+        }))
+  });
+  commands.sort_by(|a, b| (&a.name, &a.lang).cmp(&(&b.name, &b.lang)));
+  Ok(commands)
+}
+
+/// 导出命令；未指定输出时都写到标准输出
+/// - navi、tldr：指定输出时写入目录，每个命令一个文件（tldr 的输出以 .tar.gz 结尾时写成压缩包）
+/// - json、markdown：写入一个文件
+fn run_export(
+  output: Option<PathBuf>,
+  format: &str,
+  lang: Option<&str>,
+  names: &[String],
+  platform: Option<&str>,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let mut commands = export_selection(lang, names, platform, config)?;
+  // json 保留全部字段，其他格式只有示例
+  if format != "json" {
+    commands.retain(|cmd| !cmd.examples.is_empty());
+  }
+  if commands.is_empty() {
+    anyhow::bail!("{}", t!("export.none"));
+  }
+
+  let files = match format {
+    "json" => vec![(
+      String::new(),
+      serde_json::to_string_pretty(&commands)? + "\n",
+    )],
+    "navi" => sheets::export_navi(&commands),
+    "markdown" | "tldr" => update::export_tldr(&commands),
+    _ => anyhow::bail!("{}", t!("export.unknown_format", format = format)),
+  };
+  let single_file = matches!(format, "json" | "markdown");
+
+  match &output {
+    None => {
+      let contents: Vec<&str> = files.iter().map(|(_, content)| content.as_str()).collect();
+      print!("{}", contents.join("\n"));
+      eprintln!("{}", t!("export.exported", commands = commands.len()));
+    }
+    Some(path) if single_file => {
+      let contents: Vec<&str> = files.iter().map(|(_, content)| content.as_str()).collect();
+      std::fs::write(path, contents.join("\n"))?;
+      eprintln!(
+        "{}",
+        t!(
+          "export.exported_to",
+          commands = commands.len(),
+          path = path.display()
+        )
+      );
+    }
+    Some(path) if format == "tldr" && is_tar_gz(path) => {
+      write_tar_gz(path, &files)?;
+      eprintln!(
+        "{}",
+        t!(
+          "export.exported_to",
+          commands = commands.len(),
+          path = path.display()
+        )
+      );
+      eprintln!("{}", t!("export.tldr_hint", path = path.display()));
+    }
+    Some(dir) => {
+      for (name, content) in &files {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+          std::fs::create_dir_all(parent)?;
         }
+        std::fs::write(path, content)?;
       }
-      Err(_) => {
-        failed += 1;
+      eprintln!(
+        "{}",
+        t!(
+          "export.written",
+          commands = commands.len(),
+          files = files.len(),
+          path = dir.display()
+        )
+      );
+      if format == "navi" {
+        eprintln!("{}", t!("export.navi_hint", path = dir.display()));
+      } else {
+        eprintln!("{}", t!("export.tldr_hint", path = dir.display()));
+      }
+    }
+  }
+  Ok(())
+}
+
+fn is_tar_gz(path: &std::path::Path) -> bool {
+  let name = path.to_string_lossy().to_lowercase();
+  name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// 把（相对路径，内容）写成 tar.gz
+fn write_tar_gz(path: &std::path::Path, files: &[(String, String)]) -> anyhow::Result<()> {
+  let file = std::fs::File::create(path)?;
+  let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+    std::io::BufWriter::new(file),
+    flate2::Compression::default(),
+  ));
+  let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+  for (name, content) in files {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    builder.append_data(&mut header, name, content.as_bytes())?;
+  }
+  builder.into_inner()?.finish()?;
+  Ok(())
+}
+
+/// 导出闪卡
+fn run_export_cards(
+  output: Option<PathBuf>,
+  format: &str,
+  lang: Option<&str>,
+  names: &[String],
+  platform: Option<&str>,
+  deck: &str,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let Some(format) = cards::CardFormat::parse(format) else {
+    anyhow::bail!("{}", t!("cards.unknown_format", format = format));
+  };
+
+  let commands = export_selection(lang, names, platform, config)?;
+  let cards = cards::cards(&commands);
+  if cards.is_empty() {
+    anyhow::bail!("{}", t!("cards.none"));
+  }
+
+  match &output {
+    Some(path) => {
+      let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+      cards::write(&cards, format, deck, &mut file)?;
+      std::io::Write::flush(&mut file)?;
+    }
+    None => cards::write(&cards, format, deck, &mut std::io::stdout().lock())?,
+  }
+
+  let commands = commands.iter().filter(|c| !c.examples.is_empty()).count();
+  let message = match &output {
+    Some(path) => t!(
+      "cards.exported_to",
+      cards = cards.len(),
+      commands = commands,
+      path = path.display()
+    ),
+    None => t!("cards.exported", cards = cards.len(), commands = commands),
+  };
+  eprintln!("{}", message);
+  if format == cards::CardFormat::Anki && output.is_some() {
+    eprintln!("{}", t!("cards.anki_hint"));
+  }
+  Ok(())
+}
+
+/// 建立向量索引的语言：命令行参数优先，其次是配置，默认为默认语言和本机学习的命令
+fn embedding_languages(langs: Vec<String>, config: &AppConfig) -> Vec<String> {
+  let mut langs = if !langs.is_empty() {
+    langs
+  } else if !config.embeddings.languages.is_empty() {
+    config.embeddings.languages.clone()
+  } else {
+    vec![config.search.default_lang.clone(), "local".to_string()]
+  };
+  langs.dedup();
+  langs
+}
+
+/// 为新增或变化的命令生成向量
+async fn run_embed_rebuild(
+  force: bool,
+  langs: Vec<String>,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+  let path = data_dir.join(&config.storage.embeddings_filename);
+
+  let langs = embedding_languages(langs, config);
+  let mut commands = Vec::new();
+  for lang in &langs {
+    commands.extend(db.get_all_commands(lang)?);
+  }
+  if commands.is_empty() {
+    anyhow::bail!("{}", t!("embed.no_commands", langs = langs.join(", ")));
+  }
+
+  let previous = if force || !path.exists() {
+    None
+  } else {
+    match embed::VectorIndex::load(&path) {
+      Ok(index) => Some(index),
+      Err(e) => {
+        println!("{}", t!("embed.ignoring_index", error = e));
+        None
+      }
+    }
+  };
+
+  let message =
+    if embed::Provider::parse(&config.embeddings.provider) == Some(embed::Provider::Http) {
+      t!(
+        "embed.embedding",
+        count = commands.len(),
+        langs = langs.join(", "),
+        model = config.embeddings.model,
+        endpoint = config.embeddings.endpoint
+      )
+    } else {
+      t!(
+        "embed.embedding_local",
+        count = commands.len(),
+        langs = langs.join(", "),
+        model = config.embeddings.model
+      )
+    };
+  println!("{}", message);
+  let client = embed::EmbeddingClient::new(&config.embeddings, &data_dir).await?;
+  let (index, report) = embed::rebuild(&commands, previous, &client, |done, total| {
+    print!("\r[{}/{}] {}", done, total, t!("embed.progress"));
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+  })
+  .await?;
+  if report.embedded > 0 {
+    println!();
+  }
+  index.save(&path)?;
+
+  println!(
+    "\n\x1b[32m{}\x1b[0m {}",
+    t!("embed.ready"),
+    t!(
+      "embed.ready_counts",
+      embedded = report.embedded,
+      reused = report.reused,
+      dim = index.dim
+    )
+  );
+  println!("{}", t!("embed.search_hint"));
+  Ok(())
+}
+
+/// 显示向量索引状态
+fn run_embed_status(config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let path = data_dir.join(&config.storage.embeddings_filename);
+  println!("{}", t!("embed.status.index", path = path.display()));
+  if !path.exists() {
+    println!("{}", t!("embed.status.not_built"));
+    return Ok(());
+  }
+
+  let index = embed::VectorIndex::load(&path)?;
+  let size = std::fs::metadata(&path)?.len();
+  println!(
+    "{}{}",
+    t!("embed.status.model", model = index.model),
+    if index.model != config.embeddings.model {
+      format!(
+        " \x1b[33m{}\x1b[0m",
+        t!(
+          "embed.status.model_changed",
+          model = config.embeddings.model
+        )
+      )
+    } else {
+      String::new()
+    }
+  );
+  println!(
+    "{}",
+    t!(
+      "embed.status.commands",
+      count = index.entries.len(),
+      dim = index.dim
+    )
+  );
+  println!(
+    "{}",
+    t!(
+      "embed.status.size",
+      mb = format!("{:.1}", size as f64 / 1_048_576.0)
+    )
+  );
+
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if db_path.exists() {
+    let db = Database::open(&db_path)?;
+    let indexed: std::collections::HashSet<(&str, &str, &str)> = index
+      .entries
+      .iter()
+      .map(|e| (e.lang.as_str(), e.name.as_str(), e.hash.as_str()))
+      .collect();
+    let mut stale = 0;
+    for lang in embedding_languages(Vec::new(), config) {
+      for cmd in db.get_all_commands(&lang)? {
+        let hash = embed::text_hash(&embed::embedding_text(&cmd));
+        if !indexed.contains(&(cmd.lang.as_str(), cmd.name.as_str(), hash.as_str())) {
+          stale += 1;
+        }
       }
     }
+    if stale > 0 {
+      println!("\x1b[33m{}\x1b[0m", t!("embed.status.stale", count = stale));
+    } else {
+      println!("{}", t!("embed.status.up_to_date"));
+    }
   }
+  Ok(())
+}
 
-  println!("\r\x1b[K"); // 清除进度行
-  println!("\n\x1b[32mDone!\x1b[0m");
-  println!("  Learned: {}", learned);
-  if skipped > 0 {
-    println!("  Skipped: {} (already exist)", skipped);
-  }
-  if failed > 0 {
-    println!("  Failed:  {}", failed);
+/// 输出命令的 man 页面
+fn run_render(name: &str, lang: Option<&str>, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
   }
-  println!("\nTotal commands in database: {}", db.count_commands()?);
+  let db = Database::open(&db_path)?;
 
+  let lang = lang
+    .or_else(|| config.command_prefs(name)?.lang.as_deref())
+    .unwrap_or(&config.search.default_lang);
+  let Some(cmd) = db
+    .find_command(name, lang)?
+    .or(db.find_command(&name.replace(' ', "-"), lang)?)
+  else {
+    anyhow::bail!("{}", t!("render.not_found", name = name));
+  };
+  print!("{}", manpage::render(&cmd));
   Ok(())
 }
 
-/// 打印 learn-all 帮助信息
-fn print_learn_all_help(source: &str) {
-  match source {
-    "man" => {
-      println!("\nMan sections:");
-      println!("  1 - User commands");
-      println!("  2 - System calls");
-      println!("  3 - Library functions");
-      println!("  4 - Special files");
-      println!("  5 - File formats");
-      println!("  6 - Games");
-      println!("  7 - Miscellaneous");
-      println!("  8 - System administration");
+/// 比较学习的命令（lang: local）与 tldr 页面
+fn run_diff(name: &str, lang: &str, unified: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+
+  let get = |lang: &str| -> anyhow::Result<Option<storage::Command>> {
+    Ok(match db.get_command(name, lang)? {
+      Some(cmd) => Some(cmd),
+      None => db.get_command(&name.replace(' ', "-"), lang)?,
+    })
+  };
+  let Some(local) = get("local")? else {
+    anyhow::bail!("{}", t!("diff.no_local", name = name));
+  };
+  let Some(page) = get(lang)? else {
+    anyhow::bail!("{}", t!("diff.no_tldr", name = name, lang = lang));
+  };
+
+  let old_name = t!("diff.tldr", lang = lang);
+  let new_name = match &local.source_info {
+    Some(info) => {
+      let when = chrono::DateTime::parse_from_rfc3339(&info.learned_at)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| info.learned_at.clone());
+      let when = match &info.version {
+        Some(version) => format!("{}, {}", version, when),
+        None => when,
+      };
+      t!("diff.local", when = when)
     }
-    "powershell" => {
-      println!("\nTry:");
-      println!("  rtfm learn-all --source path  # Learn from PATH executables");
-      println!("  rtfm learn cargo              # Learn a specific command");
+    None => t!("diff.local", when = "local"),
+  };
+  let (old, new) = (diff::outline(&page), diff::outline(&local));
+  let lines = diff::diff_lines(&old, &new);
+  let color = tldr::use_color();
+  if old == new {
+    println!("{}", t!("diff.same"));
+  } else if unified {
+    print!("{}", diff::unified(&lines, &old_name, &new_name, color));
+  } else {
+    let width = crossterm::terminal::size().map_or(80, |(w, _)| w as usize);
+    print!(
+      "{}",
+      diff::side_by_side(&lines, &old_name, &new_name, width, color)
+    );
+  }
+
+  let report = |title: String, options: Vec<&storage::OptionEntry>| {
+    if options.is_empty() {
+      return;
     }
-    _ => {
-      println!("\nAvailable sources:");
-      #[cfg(target_os = "windows")]
-      {
-        println!("  --source powershell  # PowerShell cmdlets (default on Windows)");
-        println!("  --source path        # Executables in PATH");
-      }
-      #[cfg(not(target_os = "windows"))]
-      {
-        println!("  --source man         # Man pages (default on Linux/macOS)");
-        println!("  --source path        # Executables in PATH");
-      }
+    println!("\n{}", title);
+    for option in options {
+      println!("  {:<28} {}", option.label(), option.description);
     }
-  }
+  };
+  let undocumented = diff::unmentioned(&local.options, &page);
+  report(
+    t!("diff.undocumented", count = undocumented.len()),
+    undocumented,
+  );
+  let missing = diff::unmentioned(&page.options, &local);
+  report(t!("diff.missing", count = missing.len()), missing);
+  Ok(())
 }
 
-/// 备份应用数据到归档文件
-async fn run_backup(output: &str, config: &AppConfig) -> anyhow::Result<()> {
-  use flate2::write::GzEncoder;
-  use flate2::Compression;
-  use tar::Builder;
-
-  let data_dir = get_data_dir(config);
-
-  // 检查数据目录
-  let db_path = data_dir.join(&config.storage.db_filename);
+/// 为没有 man 手册的命令生成页面
+fn run_install_man_pages(
+  lang: Option<&str>,
+  dir: Option<PathBuf>,
+  config: &AppConfig,
+) -> anyhow::Result<()> {
+  let Some(dir) = dir.or_else(|| dirs::home_dir().map(|h| h.join(".local/share/man"))) else {
+    anyhow::bail!("{}", t!("render.no_home"));
+  };
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
   if !db_path.exists() {
-    anyhow::bail!("No data found. Run 'rtfm update' or 'rtfm learn' first.");
+    anyhow::bail!("{}", t!("common.no_database"));
   }
+  let db = Database::open(&db_path)?;
 
-  println!("Backing up data from {:?}...", data_dir);
-
-  // 创建 tar.gz 文件
-  let output_path = PathBuf::from(output);
-  let file = std::fs::File::create(&output_path)?;
-  let enc = GzEncoder::new(file, Compression::default());
-  let mut tar = Builder::new(enc);
-
-  // 添加数据库文件
-  if db_path.exists() {
-    println!("  Adding {}...", config.storage.db_filename);
-    tar.append_path_with_name(&db_path, &config.storage.db_filename)?;
+  let default_lang = lang.unwrap_or(&config.search.default_lang);
+  let mut names: Vec<String> = db
+    .get_all_commands(default_lang)?
+    .into_iter()
+    .chain(db.get_all_commands("local")?)
+    .map(|cmd| cmd.name)
+    .collect();
+  names.sort();
+  names.dedup();
+
+  let documented = learn::roff::page_names(&dir);
+  let (mut installed, mut kept) = (0, 0);
+  for name in names.iter().filter(|name| !documented.contains(*name)) {
+    let lang = lang
+      .or_else(|| config.command_prefs(name)?.lang.as_deref())
+      .unwrap_or(default_lang);
+    let Some(cmd) = db.find_command(name, lang)? else {
+      continue;
+    };
+    if manpage::install(&dir, &cmd)? {
+      installed += 1;
+    } else {
+      kept += 1;
+    }
   }
 
-  // 添加索引目录
-  let index_path = data_dir.join(&config.storage.index_dirname);
-  if index_path.exists() {
-    println!("  Adding {}/...", config.storage.index_dirname);
-    tar.append_dir_all(&config.storage.index_dirname, &index_path)?;
+  println!(
+    "{}",
+    t!(
+      "render.installed",
+      count = installed,
+      path = dir.join("man1").display(),
+      documented = names.len() - installed - kept
+    )
+  );
+  if kept > 0 {
+    println!("{}", t!("render.kept", count = kept));
   }
+  println!("{}", t!("render.manpath_hint"));
+  println!("  export MANPATH=\"{}:$MANPATH\"", dir.display());
+  Ok(())
+}
 
-  // 添加配置文件（从数据目录）
-  let config_path = data_dir.join("config.toml");
-  if config_path.exists() {
-    println!("  Adding config.toml...");
-    tar.append_path_with_name(&config_path, "config.toml")?;
-  } else {
-    // 如果数据目录没有配置文件，导出当前配置
-    let config_content = config.to_toml();
-    let config_bytes = config_content.as_bytes();
-    let mut header = tar::Header::new_gnu();
-    header.set_path("config.toml")?;
-    header.set_size(config_bytes.len() as u64);
-    header.set_mode(0o644);
-    header.set_cksum();
-    tar.append(&header, config_bytes)?;
-    println!("  Adding config.toml (current config)...");
+/// 添加用户并输出令牌（配置中只保存令牌的摘要）
+fn run_user_add(name: &str, admin: bool, config: &AppConfig) -> anyhow::Result<()> {
+  if !config::is_valid_user_name(name) {
+    anyhow::bail!("{}", t!("user.invalid_name", name = name));
+  }
+  if config.users.contains_key(name) {
+    anyhow::bail!("{}", t!("user.exists", name = name));
   }
+  save_user_token(name, admin, config)
+}
 
-  // 创建 README
-  let readme = create_backup_readme();
-  let readme_bytes = readme.as_bytes();
-  let mut header = tar::Header::new_gnu();
-  header.set_path("README.md")?;
-  header.set_size(readme_bytes.len() as u64);
-  header.set_mode(0o644);
-  header.set_cksum();
-  tar.append(&header, readme_bytes)?;
+/// 为已有用户签发新令牌
+fn run_user_token(name: &str, config: &AppConfig) -> anyhow::Result<()> {
+  let Some(user) = config.users.get(name) else {
+    anyhow::bail!("{}", t!("user.not_found_add", name = name));
+  };
+  save_user_token(name, user.admin, config)
+}
 
-  // 添加元数据文件
-  let db = Database::open(&db_path)?;
-  if let Ok(Some(meta)) = db.get_metadata() {
-    let meta_json = serde_json::to_string_pretty(&meta)?;
-    let meta_bytes = meta_json.as_bytes();
-    let mut header = tar::Header::new_gnu();
-    header.set_path("metadata.json")?;
-    header.set_size(meta_bytes.len() as u64);
-    header.set_mode(0o644);
-    header.set_cksum();
-    tar.append(&header, meta_bytes)?;
-    println!("  Adding metadata.json...");
-  }
+fn save_user_token(name: &str, admin: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let token = users::generate_token()?;
+  let user = config::UserConfig {
+    token_sha256: users::hash_token(&token),
+    admin,
+  };
+  let path = AppConfig::save_user(name, Some(&user))?;
 
-  tar.finish()?;
+  println!("{}", t!("user.saved", name = name, path = path.display()));
+  println!("\n{}\n\n  {}\n", t!("user.token"), token);
+  println!("{}", t!("user.token_usage"));
+  if !config.server.multi_user {
+    println!("\x1b[33m{}\x1b[0m", t!("user.multi_user_off"));
+  }
+  Ok(())
+}
 
-  let file_size = std::fs::metadata(&output_path)?.len();
-  println!("\n\x1b[32mBackup complete!\x1b[0m");
-  println!("  Output: {}", output_path.display());
+/// 列出用户
+fn run_user_list(config: &AppConfig) -> anyhow::Result<()> {
+  if config.users.is_empty() {
+    println!("{}", t!("user.none"));
+    return Ok(());
+  }
+  let users_dir = get_data_dir(config).join(users::USERS_DIRNAME);
+  let mut names: Vec<&String> = config.users.keys().collect();
+  names.sort();
+  for name in names {
+    let user = &config.users[name];
+    let learned = Database::open(&users_dir.join(name).join(&config.storage.db_filename))
+      .ok()
+      .and_then(|db| db.get_all_commands("local").ok())
+      .map_or(0, |commands| commands.len());
+    println!(
+      "  {:<20} {:<6} {}",
+      name,
+      if user.admin { "admin" } else { "" },
+      t!("user.learned", count = learned)
+    );
+  }
   println!(
-    "  Size:   {} bytes ({:.2} MB)",
-    file_size,
-    file_size as f64 / 1024.0 / 1024.0
+    "\n{}",
+    if config.server.multi_user {
+      t!("user.multi_user_on")
+    } else {
+      t!("user.multi_user_disabled")
+    }
   );
-  println!("\nTo restore on another machine:");
-  println!("  rtfm restore {}", output);
-
   Ok(())
 }
 
-/// 创建备份 README
-fn create_backup_readme() -> String {
-  format!(
-    r#"# RTFM Backup
-
-This archive contains backup data from RTFM (Read The F***ing Manual).
-
-## Contents
-
-- `data.redb` - Command database (redb format)
-- `index/` - Full-text search index (Tantivy format)
-- `config.toml` - Application configuration
-- `metadata.json` - Backup metadata (version, command count, etc.)
-
-## Restore
+/// 删除用户，--purge 同时删除其数据
+fn run_user_remove(name: &str, purge: bool, config: &AppConfig) -> anyhow::Result<()> {
+  if !config.users.contains_key(name) {
+    anyhow::bail!("{}", t!("user.not_found", name = name));
+  }
+  let path = AppConfig::save_user(name, None)?;
+  println!("{}", t!("user.removed", name = name, path = path.display()));
+
+  let dir = get_data_dir(config).join(users::USERS_DIRNAME).join(name);
+  if purge && dir.exists() {
+    std::fs::remove_dir_all(&dir)?;
+    println!("{}", t!("user.deleted", path = dir.display()));
+  } else if dir.exists() {
+    println!("{}", t!("user.data_kept", path = dir.display()));
+  }
+  Ok(())
+}
 
-To restore this backup on another machine:
+/// 列出插件目录中的插件
+fn run_plugin_list(config: &AppConfig) -> anyhow::Result<()> {
+  let dir = config.plugins_dir();
+  let plugins = plugin::Registry::load(config);
+  println!("{}", t!("plugin.dir", path = dir.display()));
 
-```bash
-rtfm restore rtfm-backup.tar.gz
-```
+  if plugins.plugins.is_empty() && plugins.errors.is_empty() {
+    println!("\n{}", t!("plugin.none", manifest = plugin::MANIFEST));
+    return Ok(());
+  }
 
-Options:
-- `--merge` - Merge with existing data instead of replacing
+  println!();
+  for p in &plugins.plugins {
+    let provides: Vec<String> = p.manifest.provides.iter().map(|c| c.to_string()).collect();
+    let status = match (p.enabled, p.check()) {
+      (false, _) => format!("\x1b[2m{}\x1b[0m", t!("plugin.disabled")),
+      (true, Ok(())) => "\x1b[32mok\x1b[0m".to_string(),
+      (true, Err(e)) => format!("\x1b[31m{}\x1b[0m", e),
+    };
+    println!(
+      "  \x1b[1m{}\x1b[0m{} [{}] {}",
+      p.name,
+      p.manifest
+        .version
+        .as_deref()
+        .map(|v| format!(" {}", v))
+        .unwrap_or_default(),
+      provides.join(", "),
+      status
+    );
+    if !p.manifest.description.is_empty() {
+      println!("      \x1b[90m{}\x1b[0m", p.manifest.description);
+    }
+    if !p.manifest.commands.is_empty() {
+      println!(
+        "      \x1b[90m{}\x1b[0m",
+        t!("plugin.parses", commands = p.manifest.commands.join(", "))
+      );
+    }
+  }
+  for (path, error) in &plugins.errors {
+    println!("  \x1b[31m{}: {}\x1b[0m", path.display(), error);
+  }
+  Ok(())
+}
 
-## Version Info
+/// 导入 source 插件提供的命令
+fn run_plugin_fetch(name: &str, config: &AppConfig) -> anyhow::Result<()> {
+  let plugins = plugin::Registry::load(config);
+  let source = plugins.get(name, plugin::Capability::Source)?;
 
-- Backup date: {}
-- RTFM version: {}
+  println!("{}", t!("plugin.fetching", name = name));
+  let commands = plugins.fetch(source)?;
+  if commands.is_empty() {
+    println!("{}", t!("plugin.no_commands"));
+    return Ok(());
+  }
 
-## Data Format
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
+  db.save_commands(&commands)?;
+  audit::record(
+    &db,
+    &audit::Origin::cli(),
+    audit::Action::Import,
+    commands.len(),
+    format!("plugin {}", name),
+  );
 
-The database uses redb (Rust embedded database) format.
-The search index uses Tantivy format.
+  println!("{}", t!("common.rebuilding_index"));
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+  search::rebuild_index(&db, &mut search)?;
 
-These files are cross-platform compatible (Windows/Linux/macOS).
-"#,
-    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
-    env!("CARGO_PKG_VERSION")
-  )
+  println!(
+    "\n\x1b[32m{}\x1b[0m",
+    t!("plugin.imported", count = commands.len(), name = name)
+  );
+  Ok(())
 }
 
-/// 从备份恢复数据
-async fn run_restore(path: &str, merge: bool, config: &AppConfig) -> anyhow::Result<()> {
-  use flate2::read::GzDecoder;
-  use tar::Archive;
-
-  let archive_path = PathBuf::from(path);
-  if !archive_path.exists() {
-    anyhow::bail!("Backup archive not found: {}", path);
+/// 从数据库和搜索索引中删除命令
+/// 清空索引后按数据库中的命令重新建立
+fn run_reindex(config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
   }
+  let db = Database::open(&db_path)?;
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
 
-  println!("Restoring from {}...", path);
+  println!("{}", t!("common.rebuilding_index"));
+  let count = search::rebuild_index(&db, &mut search)?;
+  println!("{}", t!("reindex.done", count = count));
+  Ok(())
+}
 
+fn run_delete(name: &str, lang: Option<&str>, config: &AppConfig) -> anyhow::Result<()> {
   let data_dir = get_data_dir(config);
-  std::fs::create_dir_all(&data_dir)?;
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
 
-  // 打开归档
-  let file = std::fs::File::open(&archive_path)?;
-  let dec = GzDecoder::new(file);
-  let mut archive = Archive::new(dec);
+  let deleted = search::delete_command(&db, &mut search, name, lang)?;
+  if deleted.is_empty() {
+    anyhow::bail!("{}", t!("delete.not_found", name = name));
+  }
+  let langs = deleted.join(", ");
+  audit::record(
+    &db,
+    &audit::Origin::cli(),
+    audit::Action::Delete,
+    deleted.len(),
+    format!("{} ({})", name, langs),
+  );
+  println!("{}", t!("delete.done", name = name, langs = langs));
+  Ok(())
+}
 
-  // 如果不是 merge 模式，先备份并清空
+/// 收藏命令；数据库中没有的命令不能收藏
+fn run_fav_add(names: &[String], config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
   let db_path = data_dir.join(&config.storage.db_filename);
-  let index_path = data_dir.join(&config.storage.index_dirname);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
+
+  let mut failed = false;
+  for name in names {
+    if db.command_langs(name)?.is_empty() {
+      eprintln!("\x1b[31m{}\x1b[0m", t!("fav.not_found", name = name));
+      failed = true;
+    } else if db.add_favorite(name)? {
+      println!("{}", t!("fav.added", name = name));
+    } else {
+      println!("{}", t!("fav.already", name = name));
+    }
+  }
+  if failed {
+    std::process::exit(1);
+  }
+  Ok(())
+}
 
-  if !merge && db_path.exists() {
-    let backup_path = data_dir.join(format!("{}.backup", config.storage.db_filename));
-    println!("  Backing up existing database to {:?}", backup_path);
-    std::fs::rename(&db_path, &backup_path)?;
+/// 取消收藏
+fn run_fav_remove(names: &[String], config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
   }
+  let db = Database::open(&db_path)?;
 
-  if !merge && index_path.exists() {
-    let backup_path = data_dir.join(format!("{}.backup", config.storage.index_dirname));
-    println!("  Backing up existing index to {:?}", backup_path);
-    if backup_path.exists() {
-      std::fs::remove_dir_all(&backup_path)?;
+  let mut failed = false;
+  for name in names {
+    if db.remove_favorite(name)? {
+      println!("{}", t!("fav.removed", name = name));
+    } else {
+      eprintln!("\x1b[31m{}\x1b[0m", t!("fav.not_favorite", name = name));
+      failed = true;
     }
-    std::fs::rename(&index_path, &backup_path)?;
   }
+  if failed {
+    std::process::exit(1);
+  }
+  Ok(())
+}
 
-  // 解压到数据目录
-  println!("  Extracting files...");
-  for entry in archive.entries()? {
-    let mut entry = entry?;
-    let path = entry.path()?;
-    let path_str = path.to_string_lossy();
+/// 管理命令别名
+/// 编辑、查看或删除命令的笔记；不带命令名时列出所有笔记
+fn run_note(name: Option<&str>, show: bool, clear: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  let db_path = data_dir.join(&config.storage.db_filename);
+  if !db_path.exists() {
+    anyhow::bail!("{}", t!("common.no_database"));
+  }
+  let db = Database::open(&db_path)?;
 
-    // 跳过 README 和 metadata（仅用于信息）
-    if path_str == "README.md" || path_str == "metadata.json" {
-      continue;
+  let Some(name) = name else {
+    let notes = db.list_notes()?;
+    if notes.is_empty() {
+      println!("{}", t!("note.none"));
+    }
+    for note in &notes {
+      println!("\x1b[1;32m{}\x1b[0m", note.name);
+      for line in note.text.lines() {
+        println!("  {}", line);
+      }
+      println!();
+    }
+    return Ok(());
+  };
+
+  let note = db.get_note(name)?;
+  if show {
+    match note {
+      Some(text) => println!("{}", text),
+      None => println!("{}", t!("note.empty", name = name)),
     }
+    return Ok(());
+  }
+  // 笔记按命令名保存，只能为数据库中已有的命令添加
+  if !clear && note.is_none() && db.command_langs(name)?.is_empty() {
+    anyhow::bail!("{}", t!("note.not_found", name = name));
+  }
+
+  let text = if clear {
+    String::new()
+  } else {
+    notes::edit(name, note.as_deref().unwrap_or_default())?
+  };
+  let index_path = data_dir.join(&config.storage.index_dirname);
+  let mut search = SearchEngine::open(&index_path, &config.search)?;
+  if !notes::save(&db, &mut search, name, &text)? {
+    println!("{}", t!("note.unchanged", name = name));
+  } else if text.trim().is_empty() {
+    println!("{}", t!("note.cleared", name = name));
+  } else {
+    println!("{}", t!("note.saved", name = name));
+  }
+  Ok(())
+}
 
-    let dest = data_dir.join(&*path);
-    println!("    {}", path.display());
+fn run_alias(action: AliasAction, config: &AppConfig) -> anyhow::Result<()> {
+  let data_dir = get_data_dir(config);
+  std::fs::create_dir_all(&data_dir)?;
+  let db = Database::open(&data_dir.join(&config.storage.db_filename))?;
 
-    if entry.header().entry_type().is_dir() {
-      std::fs::create_dir_all(&dest)?;
-    } else {
-      if let Some(parent) = dest.parent() {
-        std::fs::create_dir_all(parent)?;
+  match action {
+    AliasAction::Add { name, target } => {
+      let (name, target) = (alias::normalize(&name), alias::normalize(&target));
+      if name.is_empty() || target.is_empty() {
+        anyhow::bail!("{}", t!("alias.invalid"));
+      }
+      if name == target {
+        anyhow::bail!("{}", t!("alias.self", name = name));
+      }
+      match db.set_alias(&name, &target)? {
+        Some(previous) if previous != target => println!(
+          "{}",
+          t!(
+            "alias.updated",
+            name = name,
+            target = target,
+            previous = previous
+          )
+        ),
+        _ => println!("{}", t!("alias.added", name = name, target = target)),
+      }
+      let lang = &config.search.default_lang;
+      let mut names = alias::command_names(&target).into_iter();
+      if !names.any(|name| db.find_command(&name, lang).ok().flatten().is_some()) {
+        println!("{}", t!("alias.target_missing", target = target));
+      }
+    }
+    AliasAction::Remove { names } => {
+      let mut failed = false;
+      for name in names {
+        let name = alias::normalize(&name);
+        if db.remove_alias(&name)? {
+          println!("{}", t!("alias.removed", name = name));
+        } else {
+          eprintln!("\x1b[31m{}\x1b[0m", t!("alias.not_found", name = name));
+          failed = true;
+        }
+      }
+      if failed {
+        std::process::exit(1);
       }
-      entry.unpack(&dest)?;
     }
+    AliasAction::List => {
+      let aliases = db.list_aliases()?;
+      if aliases.is_empty() {
+        println!("{}", t!("alias.none"));
+        return Ok(());
+      }
+      let width = aliases.iter().map(|a| a.name.len()).max().unwrap_or(0);
+      for alias in &aliases {
+        println!(
+          "  \x1b[1m{:<width$}\x1b[0m  {}",
+          alias.name,
+          alias.target,
+          width = width
+        );
+      }
+    }
+    AliasAction::Import => {
+      let added = alias::import(&db, None)?;
+      if added.is_empty() {
+        println!("{}", t!("alias.import_none"));
+      }
+      print_imported_aliases(&added);
+    }
+  }
+  Ok(())
+}
+
+/// 列出新导入的别名
+fn print_imported_aliases(added: &[storage::Alias]) {
+  if added.is_empty() {
+    return;
+  }
+  for alias in added {
+    println!("  {} → {}", alias.name, alias.target);
   }
+  println!("{}", t!("alias.imported", count = added.len()));
+}
 
-  // 验证恢复
+/// 列出收藏的命令及其说明
+/// 最近查看的命令；all 时列出每一次查看
+fn run_history(limit: usize, all: bool, clear: bool, config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    println!("{}", t!("history.empty"));
+    return Ok(());
+  }
   let db = Database::open(&db_path)?;
-  let count = db.count_commands()?;
+  if clear {
+    db.clear_history()?;
+    println!("{}", t!("history.cleared"));
+    return Ok(());
+  }
 
-  // 检查配置文件是否恢复
-  let config_path = data_dir.join("config.toml");
-  let config_restored = config_path.exists();
+  let entries = if all {
+    db.list_history(limit)?
+  } else {
+    usage::recently_viewed(&db, limit)?
+  };
+  if entries.is_empty() {
+    println!("{}", t!("history.empty"));
+    if !config.search.record_usage {
+      println!("{}", t!("usage.recording_off"));
+    }
+    return Ok(());
+  }
 
-  println!("\n\x1b[32mRestore complete!\x1b[0m");
-  println!("  Commands: {}", count);
-  if config_restored {
-    println!("  Config:   restored to {:?}", config_path);
+  let width = entries.iter().map(|e| e.name.len()).max().unwrap_or(0);
+  for entry in &entries {
+    let time = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+      .map(|t| {
+        t.with_timezone(&chrono::Local)
+          .format("%Y-%m-%d %H:%M")
+          .to_string()
+      })
+      .unwrap_or_else(|_| entry.timestamp.clone());
+    println!(
+      "  {}  \x1b[1m{:<width$}\x1b[0m  \x1b[2m[{}] {}\x1b[0m",
+      time,
+      entry.name,
+      entry.lang,
+      entry.source,
+      width = width
+    );
+  }
+  Ok(())
+}
+
+fn run_fav_list(config: &AppConfig) -> anyhow::Result<()> {
+  let db_path = get_data_dir(config).join(&config.storage.db_filename);
+  if !db_path.exists() {
+    println!("{}", t!("fav.none"));
+    return Ok(());
+  }
+  let db = Database::open(&db_path)?;
+  let favorites = db.list_favorites()?;
+  if favorites.is_empty() {
+    println!("{}", t!("fav.none"));
+    return Ok(());
   }
-  println!("\nTry: rtfm tar");
 
+  let lang = &config.search.default_lang;
+  let width = favorites.iter().map(|f| f.name.len()).max().unwrap_or(0);
+  for favorite in &favorites {
+    let description = db
+      .find_command(&favorite.name, lang)?
+      .map(|cmd| cmd.description)
+      .unwrap_or_else(|| t!("fav.missing").to_string());
+    println!(
+      "  \x1b[1m{:<width$}\x1b[0m  {}",
+      favorite.name,
+      description,
+      width = width
+    );
+  }
   Ok(())
 }
 
-/// 重置所有数据（恢复出厂设置）
 async fn run_reset(skip_confirm: bool, config: &AppConfig) -> anyhow::Result<()> {
   let data_dir = get_data_dir(config);
 
   // 检查数据目录是否存在
   if !data_dir.exists() {
-    println!("No data directory found. Nothing to reset.");
+    println!("{}", t!("reset.no_dir"));
     return Ok(());
   }
 
   let db_path = data_dir.join(&config.storage.db_filename);
   let index_path = data_dir.join(&config.storage.index_dirname);
+  let embeddings_path = data_dir.join(&config.storage.embeddings_filename);
   let config_path = data_dir.join("config.toml");
 
   // 检查是否有数据
   let has_db = db_path.exists();
   let has_index = index_path.exists();
+  let has_embeddings = embeddings_path.exists();
   let has_config = config_path.exists();
 
-  if !has_db && !has_index && !has_config {
-    println!("No data found. Nothing to reset.");
+  if !has_db && !has_index && !has_embeddings && !has_config {
+    println!("{}", t!("reset.no_data"));
     return Ok(());
   }
 
   // 显示将要删除的内容
-  println!("\x1b[1;33mWarning: This will delete all RTFM data!\x1b[0m\n");
-  println!("Data directory: {:?}", data_dir);
-  println!("\nThe following will be deleted:");
+  println!("\x1b[1;33m{}\x1b[0m\n", t!("reset.warning"));
+  println!("{}", t!("config.data_dir", path = data_dir.display()));
+  println!("\n{}", t!("reset.will_delete"));
   if has_db {
-    println!("  - {} (command database)", config.storage.db_filename);
+    println!(
+      "{}",
+      t!("reset.item.database", name = config.storage.db_filename)
+    );
   }
   if has_index {
-    println!("  - {}/ (search index)", config.storage.index_dirname);
+    println!(
+      "{}",
+      t!("reset.item.index", name = config.storage.index_dirname)
+    );
+  }
+  if has_embeddings {
+    println!(
+      "{}",
+      t!(
+        "reset.item.embeddings",
+        name = config.storage.embeddings_filename
+      )
+    );
   }
   if has_config {
-    println!("  - config.toml (local configuration)");
+    println!("{}", t!("reset.item.config"));
   }
 
   // 确认
   if !skip_confirm {
-    println!("\n\x1b[1mAre you sure you want to continue? [y/N]\x1b[0m ");
+    println!("\n\x1b[1m{}\x1b[0m ", t!("reset.confirm"));
 
     let mut input = String::new();
     std::io::stdin().read_line(&mut input)?;
     let input = input.trim().to_lowercase();
 
     if input != "y" && input != "yes" {
-      println!("Aborted.");
+      println!("{}", t!("reset.aborted"));
       return Ok(());
     }
   }
 
-  println!("\nResetting...");
+  println!("\n{}", t!("reset.resetting"));
 
-  // 删除数据库
+  // 删除数据库；审计记录移到新数据库中保留
   if has_db {
-    std::fs::remove_file(&db_path)?;
-    println!("  Deleted {}", config.storage.db_filename);
+    let old_path = data_dir.join(format!("{}.reset", config.storage.db_filename));
+    std::fs::rename(&db_path, &old_path)?;
+    {
+      let old = Database::open(&old_path)?;
+      let db = Database::open(&db_path)?;
+      audit::carry_over(&old, &db)?;
+      audit::record(
+        &db,
+        &audit::Origin::cli(),
+        audit::Action::Reset,
+        old.count_commands()?,
+        "",
+      );
+    }
+    std::fs::remove_file(&old_path)?;
+    println!("{}", t!("reset.deleted", name = config.storage.db_filename));
   }
 
   // 删除索引目录
   if has_index {
     std::fs::remove_dir_all(&index_path)?;
-    println!("  Deleted {}/", config.storage.index_dirname);
+    println!(
+      "{}",
+      t!(
+        "reset.deleted",
+        name = format!("{}/", config.storage.index_dirname)
+      )
+    );
+  }
+
+  // 删除向量索引
+  if has_embeddings {
+    std::fs::remove_file(&embeddings_path)?;
+    println!(
+      "{}",
+      t!("reset.deleted", name = config.storage.embeddings_filename)
+    );
   }
 
   // 删除配置文件
   if has_config {
     std::fs::remove_file(&config_path)?;
-    println!("  Deleted config.toml");
+    println!("{}", t!("reset.deleted", name = "config.toml"));
   }
 
-  println!("\n\x1b[32mReset complete!\x1b[0m");
-  println!("All data has been deleted. RTFM is now in factory state.");
-  println!("\nTo start fresh, run:");
-  println!("  rtfm update    # Download cheatsheets from tldr");
-  println!("  rtfm learn ls  # Learn from local commands");
+  println!("\n\x1b[32m{}\x1b[0m", t!("reset.done"));
+  println!("{}", t!("reset.factory_state"));
+  println!("\n{}", t!("reset.start_fresh"));
 
   Ok(())
 }
@@ -0,0 +1,188 @@
+//! 把命令渲染为 man 页面（roff）
+//!
+//! `rtfm render <name> --format man | man -l -` 直接查看；`rtfm render --install` 为本机没有
+//! man 手册的命令生成页面，写入 MANPATH 中的目录（默认 ~/.local/share/man），之后 `man <name>`
+//! 即可显示 tldr 内容。生成的页面以 GENERATED_MARKER 注释开头，便于识别和覆盖。
+
+use std::path::{Path, PathBuf};
+
+use crate::storage::Command;
+
+/// 生成页面的首行注释
+pub const GENERATED_MARKER: &str = ".\\\" Generated by rtfm";
+
+/// 生成页面所在的章节
+const SECTION: &str = "1";
+
+/// 这些章节由页面头部和示例单独生成
+const SKIPPED_SECTIONS: &[&str] = &["NAME", "SYNOPSIS", "DESCRIPTION", "EXAMPLES"];
+
+/// 渲染为 man(7) 格式
+pub fn render(cmd: &Command) -> String {
+  let mut out = String::new();
+  let source = match &cmd.source_info {
+    Some(info) => format!("learned from {}", info.source),
+    None => format!("tldr pages ({})", cmd.lang),
+  };
+  out.push_str(&format!("{}: {}\n", GENERATED_MARKER, source));
+  out.push_str(&format!(
+    ".TH \"{}\" \"{}\" \"\" \"rtfm\" \"{}\"\n",
+    cmd.name.to_uppercase().replace('"', ""),
+    SECTION,
+    if cmd.lang == "local" {
+      "Learned Commands"
+    } else {
+      "tldr pages"
+    },
+  ));
+
+  // NAME 一行，完整描述放在 DESCRIPTION
+  let summary = cmd.description.lines().next().unwrap_or_default();
+  out.push_str(".SH NAME\n");
+  out.push_str(&format!("{} \\- {}\n", escape(&cmd.name), escape(summary)));
+
+  if let Some(synopsis) = cmd.section("SYNOPSIS") {
+    out.push_str(".SH SYNOPSIS\n");
+    preformatted(&mut out, &synopsis.content);
+  }
+
+  if !cmd.description.trim().is_empty() {
+    out.push_str(".SH DESCRIPTION\n");
+    for (i, line) in cmd
+      .description
+      .lines()
+      .filter(|l| !l.trim().is_empty())
+      .enumerate()
+    {
+      if i > 0 {
+        out.push_str(".br\n");
+      }
+      out.push_str(&escape_line(&escape(line.trim())));
+      out.push('\n');
+    }
+  }
+
+  if !cmd.examples.is_empty() {
+    out.push_str(".SH EXAMPLES\n");
+    for example in &cmd.examples {
+      out.push_str(".PP\n");
+      out.push_str(&escape_line(&escape(&example.description)));
+      out.push('\n');
+      out.push_str(".RS 4\n.nf\n");
+      out.push_str(&format!("\\fB{}\\fP", code(&example.code)));
+      out.push_str("\n.fi\n.RE\n");
+    }
+  }
+
+  for section in &cmd.sections {
+    if SKIPPED_SECTIONS
+      .iter()
+      .any(|s| section.title.eq_ignore_ascii_case(s))
+    {
+      continue;
+    }
+    out.push_str(&format!(".SH {}\n", escape(&section.title.to_uppercase())));
+    preformatted(&mut out, &section.content);
+  }
+
+  out
+}
+
+/// 原样保留换行和缩进的正文
+fn preformatted(out: &mut String, content: &str) {
+  out.push_str(".nf\n");
+  for line in content.trim_matches('\n').lines() {
+    out.push_str(&escape_line(&escape(line)));
+    out.push('\n');
+  }
+  out.push_str(".fi\n");
+}
+
+/// 转义 roff 的反斜杠和连字符
+fn escape(s: &str) -> String {
+  s.replace('\\', "\\e").replace('-', "\\-")
+}
+
+/// 以 . 或 ' 开头的行会被当作请求，用零宽字符 \& 保护
+/// 传入的行必须已经转义
+fn escape_line(s: &str) -> String {
+  if s.starts_with(['.', '\'']) {
+    format!("\\&{}", s)
+  } else {
+    s.to_string()
+  }
+}
+
+/// 示例代码：tldr 占位符 `{{path/to/file}}` 显示为斜体
+fn code(code: &str) -> String {
+  escape(code).replace("{{", "\\fI").replace("}}", "\\fB")
+}
+
+/// 页面文件在 dir 中的路径
+pub fn page_path(dir: &Path, name: &str) -> PathBuf {
+  dir
+    .join(format!("man{}", SECTION))
+    .join(format!("{}.{}", name, SECTION))
+}
+
+/// 写入页面；已存在且不是 rtfm 生成的文件不会被覆盖，返回是否写入
+pub fn install(dir: &Path, cmd: &Command) -> std::io::Result<bool> {
+  let path = page_path(dir, &cmd.name);
+  if let Ok(existing) = std::fs::read_to_string(&path) {
+    if !existing.starts_with(GENERATED_MARKER) {
+      return Ok(false);
+    }
+  }
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::fs::write(&path, render(cmd))?;
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{Example, Section, COMMAND_SCHEMA};
+
+  #[test]
+  fn test_render() {
+    let cmd = Command {
+      name: "tar".to_string(),
+      description: "Archiving utility.\nMore information: <https://www.gnu.org/software/tar>."
+        .to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: vec![Example {
+        description: ".tar files: extract verbosely".to_string(),
+        code: "tar -xvf {{source.tar}} 2>\\dev".to_string(),
+        source: None,
+      }],
+      content: String::new(),
+      sections: vec![Section {
+        title: "Options".to_string(),
+        content: "-x  extract\n.hidden".to_string(),
+      }],
+      options: Vec::new(),
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    };
+    let page = render(&cmd);
+    assert!(page.starts_with(GENERATED_MARKER));
+    assert!(page.contains(".TH \"TAR\" \"1\""));
+    assert!(page.contains(".SH NAME\ntar \\- Archiving utility.\n"));
+    assert!(page.contains("\\&.tar files: extract verbosely\n"));
+    assert!(page.contains(".br\nMore information: <https://www.gnu.org/software/tar>.\n"));
+    assert!(page.contains("\\fBtar \\-xvf \\fIsource.tar\\fB 2>\\edev\\fP\n"));
+    assert!(page.contains(".SH OPTIONS\n.nf\n\\-x  extract\n\\&.hidden\n.fi\n"));
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    assert!(install(temp_dir.path(), &cmd).unwrap());
+    assert!(install(temp_dir.path(), &cmd).unwrap());
+    let path = page_path(temp_dir.path(), "tar");
+    std::fs::write(&path, ".TH TAR 1\n").unwrap();
+    assert!(!install(temp_dir.path(), &cmd).unwrap());
+  }
+}
@@ -0,0 +1,65 @@
+//! 命令的个人笔记（rtfm note）
+//!
+//! 笔记按命令名单独存放，不随数据更新、重置命令页面而丢失，显示命令时附在描述之后，
+//! 也参与搜索。编辑时把笔记写入临时文件并打开 $VISUAL / $EDITOR，保存退出后读回。
+
+use std::io::Write;
+use std::process::Command;
+
+use crate::i18n::t;
+use crate::search::{self, SearchEngine};
+use crate::storage::Database;
+
+/// 未设置 $VISUAL 和 $EDITOR 时使用的编辑器
+fn default_editor() -> &'static str {
+  if cfg!(windows) {
+    "notepad"
+  } else {
+    "vi"
+  }
+}
+
+/// 用编辑器编辑笔记，返回保存后的内容
+pub fn edit(name: &str, initial: &str) -> anyhow::Result<String> {
+  let mut file = tempfile::Builder::new()
+    .prefix(&format!("rtfm-note-{}-", name.replace(['/', '\\'], "_")))
+    .suffix(".md")
+    .tempfile()?;
+  file.write_all(initial.as_bytes())?;
+  file.flush()?;
+
+  let editor = std::env::var("VISUAL")
+    .or_else(|_| std::env::var("EDITOR"))
+    .ok()
+    .filter(|editor| !editor.trim().is_empty())
+    .unwrap_or_else(|| default_editor().to_string());
+  // 编辑器可以带参数，如 "code --wait"
+  let mut words = editor.split_whitespace();
+  let program = words.next().unwrap_or(default_editor());
+  let status = Command::new(program)
+    .args(words)
+    .arg(file.path())
+    .status()
+    .map_err(|e| anyhow::anyhow!("{}", t!("note.editor_failed", editor = program, error = e)))?;
+  if !status.success() {
+    anyhow::bail!(
+      "{}",
+      t!("note.editor_exit", editor = program, status = status)
+    );
+  }
+  Ok(std::fs::read_to_string(file.path())?)
+}
+
+/// 保存笔记并重新索引该命令，返回笔记是否有变化
+pub fn save(
+  db: &Database,
+  search: &mut SearchEngine,
+  name: &str,
+  text: &str,
+) -> anyhow::Result<bool> {
+  let changed = db.set_note(name, text)?;
+  if changed {
+    search::reindex_command(db, search, name)?;
+  }
+  Ok(changed)
+}
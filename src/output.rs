@@ -0,0 +1,255 @@
+//! rtfm <query> 的输出格式（--output）
+//!
+//! - 默认：终端中带颜色；输出到管道或设置了 NO_COLOR 时同 plain
+//! - plain：与默认相同的排版，不含 ANSI 转义
+//! - json：单个命令输出 Command，多个结果输出 SearchResult 数组，供脚本解析
+//! - markdown：单个命令为 tldr 风格的 Markdown（与 /cheatsheet 相同），多个结果为列表
+
+use crate::i18n::t;
+use crate::search::SearchResult;
+use crate::storage::Command;
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+  /// 终端中的彩色文本
+  Color,
+  Plain,
+  Json,
+  Markdown,
+}
+
+impl Output {
+  /// --output 的值；未指定时按标准输出是否为终端选择 Color 或 Plain
+  pub fn new(format: Option<&str>) -> Self {
+    match format {
+      Some("json") => Output::Json,
+      Some("markdown") => Output::Markdown,
+      Some("plain") => Output::Plain,
+      _ if crate::tldr::use_color() => Output::Color,
+      _ => Output::Plain,
+    }
+  }
+
+  fn paint(self, code: &str, text: &str) -> String {
+    if self == Output::Color {
+      format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+      text.to_string()
+    }
+  }
+}
+
+/// 单个命令
+pub fn command(cmd: &Command, output: Output) -> anyhow::Result<String> {
+  Ok(match output {
+    Output::Json => serde_json::to_string_pretty(cmd)? + "\n",
+    Output::Markdown => command_markdown(cmd),
+    Output::Color | Output::Plain => command_text(cmd, output),
+  })
+}
+
+/// 命令写成 tldr 风格的 Markdown：说明、用法摘要、示例与选项
+pub fn command_markdown(cmd: &Command) -> String {
+  let mut md = format!("# {}\n\n", cmd.name);
+  for line in cmd.description.lines() {
+    md.push_str(&format!("> {}\n", line));
+  }
+  md.push('\n');
+  if let Some(synopsis) = cmd.section("SYNOPSIS") {
+    md.push_str(&format!("```\n{}\n```\n\n", synopsis.content));
+  }
+  for example in &cmd.examples {
+    md.push_str(&format!(
+      "## {}\n\n```sh\n{}\n```\n\n",
+      example.description, example.code
+    ));
+  }
+  if !cmd.options.is_empty() {
+    md.push_str("## Options\n\n");
+    for option in &cmd.options {
+      md.push_str(&format!("- `{}` {}\n", option.label(), option.description));
+    }
+  }
+  md
+}
+
+fn command_text(cmd: &Command, output: Output) -> String {
+  let mut out = String::new();
+  let mut line = |text: String| {
+    out.push_str(&text);
+    out.push('\n');
+  };
+
+  // 命令名（绿色粗体）
+  line(output.paint("1;32", &cmd.name));
+  line(String::new());
+
+  // 描述
+  line(cmd.description.clone());
+  line(String::new());
+
+  // 个人笔记（紫色边线）
+  if let Some(notes) = &cmd.notes {
+    line(output.paint("1", t!("notes.title")));
+    for note in notes.lines() {
+      line(format!("{} {}", output.paint("35", "│"), note));
+    }
+    line(String::new());
+  }
+
+  // 学习来源版本
+  if let Some(version) = cmd.source_info.as_ref().and_then(|i| i.version.as_deref()) {
+    line(output.paint("2", &t!("tui.learned_from", version = version)));
+    line(String::new());
+  }
+
+  // 用法（man 页面的 SYNOPSIS）
+  if let Some(synopsis) = cmd.section("SYNOPSIS") {
+    for text in synopsis.content.lines() {
+      line(format!("  {}", output.paint("2", text)));
+    }
+    line(String::new());
+  }
+
+  // 示例（合并视图中，本地学习的示例排在 tldr 示例之后）
+  let mut local_header = false;
+  for example in &cmd.examples {
+    if example.source.as_deref() == Some("local") && !local_header {
+      line(output.paint("2", &format!("── {} ──", t!("tui.from_local_help"))));
+      line(String::new());
+      local_header = true;
+    }
+    // 示例描述（黄色），AI 生成的示例标注来源
+    let description = output.paint("33", &format!("- {}", example.description));
+    match example
+      .source
+      .as_deref()
+      .and_then(|s| s.strip_prefix("llm:"))
+    {
+      Some(model) => line(format!(
+        "{} {}",
+        description,
+        output.paint("2", &format!("(AI: {})", model))
+      )),
+      None => line(description),
+    }
+    // 代码（青色）
+    line(format!("  {}", output.paint("36", &example.code)));
+    line(String::new());
+  }
+
+  // 选项（青色），说明缩进在下一行
+  if !cmd.options.is_empty() {
+    line(output.paint("1", t!("tui.options")));
+    line(String::new());
+    for option in &cmd.options {
+      line(format!("  {}", output.paint("36", &option.label())));
+      line(format!("      {}", option.description));
+    }
+    line(String::new());
+  }
+  out
+}
+
+/// 多个搜索结果
+pub fn results(results: &[SearchResult], query: &str, output: Output) -> anyhow::Result<String> {
+  let mut out = String::new();
+  match output {
+    Output::Json => out = serde_json::to_string_pretty(results)? + "\n",
+    Output::Markdown => {
+      for r in results {
+        out.push_str(&format!(
+          "- **{}** `{}`: {}\n",
+          r.name,
+          r.lang,
+          r.description.replace('\n', " ")
+        ));
+      }
+    }
+    Output::Color | Output::Plain => {
+      out.push_str(&output.paint(
+        "1",
+        &t!("query.found", count = results.len(), query = query),
+      ));
+      out.push_str("\n\n");
+      for (i, r) in results.iter().enumerate() {
+        out.push_str(&format!(
+          "  {} {} {}\n",
+          output.paint("32", &format!("{:2}.", i + 1)),
+          output.paint("1", &r.name),
+          output.paint("90", &format!("[{}]", r.lang))
+        ));
+        out.push_str(&format!(
+          "      {}\n",
+          output.paint("90", &crate::truncate(&r.description, 60))
+        ));
+      }
+      out.push('\n');
+      out.push_str(&t!(
+        "query.view_details",
+        command = output.paint("36", "rtfm <command>")
+      ));
+      out.push('\n');
+    }
+  }
+  Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{Example, COMMAND_SCHEMA};
+
+  #[test]
+  fn test_output() {
+    let cmd = Command {
+      name: "tar".to_string(),
+      description: "Archiving utility".to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: vec![Example {
+        description: "Extract an archive".to_string(),
+        code: "tar xf {{source.tar}}".to_string(),
+        source: None,
+      }],
+      content: String::new(),
+      sections: vec![],
+      options: vec![],
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    };
+
+    let plain = command(&cmd, Output::Plain).unwrap();
+    assert!(!plain.contains('\x1b'));
+    assert!(plain.contains("- Extract an archive\n  tar xf {{source.tar}}\n"));
+    assert!(command(&cmd, Output::Color).unwrap().contains("\x1b[36m"));
+
+    let json: serde_json::Value =
+      serde_json::from_str(&command(&cmd, Output::Json).unwrap()).unwrap();
+    assert_eq!(json["examples"][0]["code"], "tar xf {{source.tar}}");
+
+    assert!(command(&cmd, Output::Markdown)
+      .unwrap()
+      .starts_with("# tar\n\n> Archiving utility\n"));
+
+    let found = [SearchResult {
+      name: "tar".to_string(),
+      description: "Archiving\nutility".to_string(),
+      category: "common".to_string(),
+      lang: "en".to_string(),
+      platform: "common".to_string(),
+      score: 1.0,
+      example: None,
+    }];
+    assert_eq!(
+      results(&found, "archive", Output::Markdown).unwrap(),
+      "- **tar** `en`: Archiving utility\n"
+    );
+    let json: serde_json::Value =
+      serde_json::from_str(&results(&found, "archive", Output::Json).unwrap()).unwrap();
+    assert_eq!(json[0]["name"], "tar");
+  }
+}
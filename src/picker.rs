@@ -0,0 +1,362 @@
+//! rtfm <query> 有多个结果时的交互式选择
+//!
+//! 在终端中原地列出结果：上下键移动，输入序号直接选择，输入字母按命令名筛选（字母按顺序出现即可，
+//! 如 "gc" 匹配 git-commit），回车显示选中的命令，Esc 取消。
+//! 标准输入或输出不是终端时不使用，调用方照旧打印列表。
+
+use std::io::{self, IsTerminal, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, queue, terminal};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::i18n::t;
+use crate::search::SearchResult;
+
+/// 是否可以交互选择
+pub fn available() -> bool {
+  io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// 交互选择一个结果，返回其位置；取消时返回 None
+pub fn pick(query: &str, results: &[SearchResult]) -> io::Result<Option<usize>> {
+  let mut out = io::stdout();
+  let _guard = RawMode::enable()?;
+  let mut picker = Picker::new(results);
+  let mut drawn = 0;
+
+  loop {
+    // 有些伪终端报告的大小为 0
+    let (width, height) = terminal::size()
+      .ok()
+      .filter(|&(width, height)| width > 0 && height > 0)
+      .unwrap_or((80, 24));
+    drawn = picker.render(&mut out, query, width, height, drawn)?;
+    let Event::Key(key) = event::read()? else {
+      continue;
+    };
+    if key.kind != KeyEventKind::Press {
+      continue;
+    }
+    if let Some(outcome) = picker.handle(key) {
+      // 清除列表，之后的输出从列表原来的位置开始
+      clear(&mut out, drawn)?;
+      out.flush()?;
+      return Ok(match outcome {
+        Outcome::Pick(index) => Some(index),
+        Outcome::Cancel => None,
+      });
+    }
+  }
+}
+
+/// 进入原始模式，离开时（包括出错时）恢复终端
+struct RawMode;
+
+impl RawMode {
+  fn enable() -> io::Result<Self> {
+    terminal::enable_raw_mode()?;
+    queue!(io::stdout(), cursor::Hide)?;
+    Ok(Self)
+  }
+}
+
+impl Drop for RawMode {
+  fn drop(&mut self) {
+    let mut out = io::stdout();
+    let _ = queue!(out, cursor::Show);
+    let _ = out.flush();
+    let _ = terminal::disable_raw_mode();
+  }
+}
+
+/// 回到上次绘制的第一行并清除到屏幕末尾
+fn clear(out: &mut impl Write, drawn: u16) -> io::Result<()> {
+  queue!(out, cursor::MoveToColumn(0))?;
+  if drawn > 1 {
+    queue!(out, cursor::MoveUp(drawn - 1))?;
+  }
+  queue!(out, terminal::Clear(terminal::ClearType::FromCursorDown))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+  Pick(usize),
+  Cancel,
+}
+
+/// 选择的状态
+struct Picker<'a> {
+  results: &'a [SearchResult],
+  /// 筛选命令名的字母
+  filter: String,
+  /// 正在输入的序号
+  number: String,
+  /// 筛选后可见的结果在 results 中的位置
+  visible: Vec<usize>,
+  /// 选中的结果在 visible 中的位置
+  selected: usize,
+}
+
+impl<'a> Picker<'a> {
+  fn new(results: &'a [SearchResult]) -> Self {
+    Self {
+      results,
+      filter: String::new(),
+      number: String::new(),
+      visible: (0..results.len()).collect(),
+      selected: 0,
+    }
+  }
+
+  fn refilter(&mut self) {
+    let filter = self.filter.to_lowercase();
+    self.visible = (0..self.results.len())
+      .filter(|&i| is_subsequence(&filter, &self.results[i].name.to_lowercase()))
+      .collect();
+    self.selected = 0;
+  }
+
+  /// 处理按键，选定或取消时返回结果
+  fn handle(&mut self, key: KeyEvent) -> Option<Outcome> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+      KeyCode::Char('c') if ctrl => return Some(Outcome::Cancel),
+      // 先清除输入，再按一次取消
+      KeyCode::Esc if self.filter.is_empty() && self.number.is_empty() => {
+        return Some(Outcome::Cancel);
+      }
+      KeyCode::Esc => {
+        self.number.clear();
+        self.filter.clear();
+        self.refilter();
+      }
+      KeyCode::Up => {
+        self.number.clear();
+        self.selected = self.selected.saturating_sub(1);
+      }
+      KeyCode::Down => {
+        self.number.clear();
+        if self.selected + 1 < self.visible.len() {
+          self.selected += 1;
+        }
+      }
+      KeyCode::Enter => {
+        if let Some(index) = self.numbered() {
+          return Some(Outcome::Pick(index));
+        }
+        self.number.clear();
+        return self.visible.get(self.selected).map(|&i| Outcome::Pick(i));
+      }
+      KeyCode::Backspace if self.number.pop().is_none() && self.filter.pop().is_some() => {
+        self.refilter();
+      }
+      KeyCode::Char(c) if c.is_ascii_digit() && !ctrl => {
+        self.number.push(c);
+        let Some(index) = self.numbered() else {
+          self.number.clear();
+          return None;
+        };
+        // 再输入一位就超出范围时不必等回车
+        if (index + 1) * 10 > self.results.len() {
+          return Some(Outcome::Pick(index));
+        }
+        if let Some(position) = self.visible.iter().position(|&i| i == index) {
+          self.selected = position;
+        }
+      }
+      KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+        self.number.clear();
+        self.filter.push(c);
+        self.refilter();
+      }
+      _ => {}
+    }
+    None
+  }
+
+  /// 输入的序号对应的结果（序号从 1 开始）
+  fn numbered(&self) -> Option<usize> {
+    let number: usize = self.number.parse().ok()?;
+    (1..=self.results.len())
+      .contains(&number)
+      .then(|| number - 1)
+  }
+
+  /// 在上次绘制的位置重新绘制，返回绘制的行数
+  fn render(
+    &self,
+    out: &mut impl Write,
+    query: &str,
+    width: u16,
+    height: u16,
+    drawn: u16,
+  ) -> io::Result<u16> {
+    // 每行都不超过终端宽度，折行会打乱重新绘制的位置
+    let room = (width as usize).saturating_sub(1);
+    let found = t!("query.found", count = self.results.len(), query = query);
+    let mut lines = vec![format!("\x1b[1m{}\x1b[0m", fit(&found, room))];
+    let hint = if !self.number.is_empty() {
+      t!("picker.number", number = self.number)
+    } else if !self.filter.is_empty() {
+      t!("picker.filter", filter = self.filter)
+    } else {
+      t!("picker.hint").to_string()
+    };
+    lines.push(format!("\x1b[90m{}\x1b[0m", fit(&hint, room)));
+
+    if self.visible.is_empty() {
+      lines.push(format!("  \x1b[33m{}\x1b[0m", t!("picker.no_match")));
+    }
+    // 只显示选中项附近放得下的行
+    let rows = (height.saturating_sub(3) as usize).max(1);
+    let start = (self.selected + 1).saturating_sub(rows);
+    let number_width = self.results.len().to_string().len();
+    for (position, &index) in self.visible.iter().enumerate().skip(start).take(rows) {
+      let result = &self.results[index];
+      let selected = position == self.selected;
+      let prefix = format!(
+        "{} {:>number_width$}. {} [{}]  ",
+        if selected { ">" } else { " " },
+        index + 1,
+        result.name,
+        result.lang,
+      );
+      let description = fit(&result.description, room.saturating_sub(prefix.width()));
+      lines.push(if selected {
+        format!("\x1b[1;36m{}\x1b[0m\x1b[90m{}\x1b[0m", prefix, description)
+      } else {
+        format!("{}\x1b[90m{}\x1b[0m", prefix, description)
+      });
+    }
+
+    clear(out, drawn)?;
+    queue!(out, crossterm::style::Print(lines.join("\r\n")))?;
+    out.flush()?;
+    Ok(lines.len() as u16)
+  }
+}
+
+/// needle 的字符是否按顺序出现在 haystack 中
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+  let mut chars = haystack.chars();
+  needle.chars().all(|c| chars.any(|h| h == c))
+}
+
+/// 按显示宽度截断到 width 列以内，放不下时以 ... 结尾
+fn fit(text: &str, width: usize) -> String {
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  let mut used = 0;
+  let mut fitted = String::new();
+  for c in text.chars() {
+    used += c.width().unwrap_or(0);
+    if used > width {
+      let keep = width.saturating_sub(3);
+      let mut kept = 0;
+      fitted = fitted
+        .chars()
+        .take_while(|c| {
+          kept += c.width().unwrap_or(0);
+          kept <= keep
+        })
+        .collect();
+      if width >= 3 {
+        fitted.push_str("...");
+      }
+      return fitted;
+    }
+    fitted.push(c);
+  }
+  fitted
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn results(names: &[&str]) -> Vec<SearchResult> {
+    names
+      .iter()
+      .map(|name| SearchResult {
+        name: name.to_string(),
+        description: String::new(),
+        category: "common".to_string(),
+        lang: "en".to_string(),
+        platform: "common".to_string(),
+        score: 0.0,
+        example: None,
+      })
+      .collect()
+  }
+
+  fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+  }
+
+  #[test]
+  fn test_navigate_and_filter() {
+    let results = results(&["git-commit", "git-checkout", "tar", "gcc"]);
+    let mut picker = Picker::new(&results);
+    assert_eq!(picker.handle(key(KeyCode::Up)), None);
+    assert_eq!(picker.handle(key(KeyCode::Down)), None);
+    assert_eq!(picker.handle(key(KeyCode::Enter)), Some(Outcome::Pick(1)));
+
+    // 字母按顺序出现即匹配
+    let mut picker = Picker::new(&results);
+    picker.handle(key(KeyCode::Char('g')));
+    picker.handle(key(KeyCode::Char('c')));
+    assert_eq!(picker.visible, [0, 1, 3]);
+    picker.handle(key(KeyCode::Char('m')));
+    assert_eq!(picker.visible, [0]);
+    picker.handle(key(KeyCode::Backspace));
+    assert_eq!(picker.visible.len(), 3);
+    picker.handle(key(KeyCode::Char('x')));
+    assert!(picker.visible.is_empty());
+    assert_eq!(picker.handle(key(KeyCode::Enter)), None);
+    // Esc 先清除筛选，再取消
+    assert_eq!(picker.handle(key(KeyCode::Esc)), None);
+    assert_eq!(picker.visible.len(), 4);
+    assert_eq!(picker.handle(key(KeyCode::Esc)), Some(Outcome::Cancel));
+  }
+
+  #[test]
+  fn test_number_entry() {
+    // 不足 10 个结果时输入一位数字直接选定
+    let few = results(&["a", "b", "c"]);
+    let mut picker = Picker::new(&few);
+    assert_eq!(picker.handle(key(KeyCode::Char('9'))), None);
+    assert_eq!(
+      picker.handle(key(KeyCode::Char('2'))),
+      Some(Outcome::Pick(1))
+    );
+
+    // 可能是两位数时等待回车或下一位
+    let names: Vec<String> = (0..12).map(|i| format!("cmd{}", i)).collect();
+    let many = results(&names.iter().map(String::as_str).collect::<Vec<_>>());
+    let mut picker = Picker::new(&many);
+    assert_eq!(picker.handle(key(KeyCode::Char('1'))), None);
+    assert_eq!(picker.selected, 0);
+    assert_eq!(
+      picker.handle(key(KeyCode::Char('1'))),
+      Some(Outcome::Pick(10))
+    );
+
+    let mut picker = Picker::new(&many);
+    picker.handle(key(KeyCode::Char('1')));
+    assert_eq!(picker.handle(key(KeyCode::Enter)), Some(Outcome::Pick(0)));
+
+    let mut picker = Picker::new(&many);
+    assert_eq!(
+      picker.handle(key(KeyCode::Char('5'))),
+      Some(Outcome::Pick(4))
+    );
+  }
+
+  #[test]
+  fn test_fit() {
+    assert_eq!(fit("short", 10), "short");
+    assert_eq!(fit("a  long\n description", 12), "a long de...");
+    assert_eq!(fit("压缩文件夹", 7), "压缩...");
+    assert_eq!(fit("abc", 0), "");
+  }
+}
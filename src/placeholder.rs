@@ -0,0 +1,146 @@
+//! 示例中的 tldr 占位符
+//!
+//! `{{path/to/file}}` 是需要填写的值，同名的占位符只填一次；
+//! `{{[-f|--force]}}` 是同一选项的不同写法，不需要填写，展开为长选项（与 navi 导出一致）。
+
+use std::io::{BufRead, Write};
+
+use crate::i18n::t;
+
+/// 示例代码的片段
+enum Part<'a> {
+  Text(&'a str),
+  /// 需要填写的占位符
+  Value(&'a str),
+  /// 选项的不同写法
+  Choice(&'a str),
+}
+
+fn parts(code: &str) -> Vec<Part<'_>> {
+  let mut parts = Vec::new();
+  let mut rest = code;
+  while let Some(start) = rest.find("{{") {
+    let Some(end) = rest[start + 2..].find("}}") else {
+      break;
+    };
+    if start > 0 {
+      parts.push(Part::Text(&rest[..start]));
+    }
+    let inner = &rest[start + 2..start + 2 + end];
+    match inner
+      .strip_prefix('[')
+      .and_then(|s| s.strip_suffix(']'))
+      .filter(|s| s.contains('|'))
+    {
+      Some(options) => parts.push(Part::Choice(options.rsplit('|').next().unwrap_or(options))),
+      None => parts.push(Part::Value(inner)),
+    }
+    rest = &rest[start + 2 + end + 2..];
+  }
+  if !rest.is_empty() {
+    parts.push(Part::Text(rest));
+  }
+  parts
+}
+
+/// 需要填写的占位符，按出现顺序去重
+pub fn names(code: &str) -> Vec<String> {
+  let mut names: Vec<String> = Vec::new();
+  for part in parts(code) {
+    if let Part::Value(name) = part {
+      if !names.iter().any(|n| n == name) {
+        names.push(name.to_string());
+      }
+    }
+  }
+  names
+}
+
+/// 用 values（与 names 的顺序对应）替换占位符；没有填写的保留占位符中的文字
+pub fn fill(code: &str, values: &[String]) -> String {
+  let names = names(code);
+  let mut out = String::with_capacity(code.len());
+  for part in parts(code) {
+    match part {
+      Part::Text(text) | Part::Choice(text) => out.push_str(text),
+      Part::Value(name) => {
+        let value = names
+          .iter()
+          .position(|n| n == name)
+          .and_then(|i| values.get(i))
+          .filter(|v| !v.is_empty());
+        out.push_str(value.map_or(name, String::as_str));
+      }
+    }
+  }
+  out
+}
+
+/// 去掉占位符的花括号，值的前后加上 on、off（如终端颜色）；`\{\{` `\}\}` 显示为花括号
+pub fn highlight(code: &str, on: &str, off: &str) -> String {
+  let mut out = String::with_capacity(code.len());
+  for part in parts(code) {
+    match part {
+      Part::Text(text) => out.push_str(&text.replace("\\{\\{", "{{").replace("\\}\\}", "}}")),
+      Part::Choice(text) => out.push_str(text),
+      Part::Value(name) => {
+        out.push_str(on);
+        out.push_str(name);
+        out.push_str(off);
+      }
+    }
+  }
+  out
+}
+
+/// 在终端中依次询问每个占位符的值（提示写到 stderr），返回填好的命令
+pub fn prompt(code: &str) -> anyhow::Result<String> {
+  let names = names(code);
+  if names.is_empty() {
+    return Ok(fill(code, &[]));
+  }
+
+  eprintln!("\x1b[90m{}\x1b[0m", t!("fill.hint"));
+  let stdin = std::io::stdin();
+  let mut input = stdin.lock();
+  let mut values = Vec::with_capacity(names.len());
+  for name in &names {
+    eprint!("\x1b[36m{}\x1b[0m: ", name);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    // 输入结束后其余占位符保留原文
+    input.read_line(&mut line)?;
+    values.push(line.trim_end_matches(['\r', '\n']).to_string());
+  }
+  Ok(fill(code, &values))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_names() {
+    assert_eq!(
+      names("tar {{[-c|--create]}} -f {{target.tar}} {{file1}} {{file2}} {{file1}}"),
+      ["target.tar", "file1", "file2"]
+    );
+    assert!(names("ls -la").is_empty());
+  }
+
+  #[test]
+  fn test_fill() {
+    let code = "cp {{[-r|--recursive]}} {{path/to/source}} {{path/to/source}}.bak";
+    assert_eq!(
+      fill(code, &["src".to_string()]),
+      "cp --recursive src src.bak"
+    );
+    // 没有填写时保留占位符的文字
+    assert_eq!(
+      fill(code, &[String::new()]),
+      "cp --recursive path/to/source path/to/source.bak"
+    );
+    // 未闭合的括号原样保留
+    assert_eq!(fill("echo {{a}} {{b", &["x".to_string()]), "echo x {{b");
+  }
+}
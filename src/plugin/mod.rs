@@ -0,0 +1,574 @@
+//! 插件：提供数据源、帮助解析器和输出渲染器的外部程序
+//!
+//! 插件目录（默认 ~/.config/rtfm/plugins）下每个子目录是一个插件，包含清单 plugin.toml：
+//!
+//! ```toml
+//! description = "Kubernetes command reference"
+//! exec = "./kube-docs"          # 可执行文件（相对插件目录，或 PATH 中的程序名）
+//! # wasm = "kube-docs.wasm"     # 或 WASI 模块，用 plugins.wasm_runtime 运行
+//! provides = ["source", "parser"]
+//! commands = ["kubectl*"]       # parser 负责的命令，支持结尾的 * 通配
+//! ```
+//!
+//! 每次调用启动一次插件进程，向标准输入写入一行请求 `{"version":1,"method":...,"params":...}`
+//! 后关闭，插件在标准输出返回 `{"result": ...}` 或 `{"error": "..."}`：
+//! - source：`fetch`，返回命令列表（rtfm plugin fetch）
+//! - parser：`parse`，把帮助文本解析为命令（学习 commands 中列出的命令时）
+//! - renderer：`render`，把命令渲染为文本（rtfm <name> --render <plugin>）
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AppConfig, PluginsConfig};
+use crate::storage::{Command, Example, OptionEntry, Section, COMMAND_SCHEMA};
+
+/// 插件清单文件名
+pub const MANIFEST: &str = "plugin.toml";
+
+/// 协议版本，随请求发送
+const PROTOCOL_VERSION: u32 = 1;
+
+/// 插件提供的能力
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Capability {
+  Source,
+  Parser,
+  Renderer,
+}
+
+impl std::fmt::Display for Capability {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      Capability::Source => "source",
+      Capability::Parser => "parser",
+      Capability::Renderer => "renderer",
+    })
+  }
+}
+
+/// plugin.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+  /// 插件名，默认为目录名
+  pub name: Option<String>,
+  #[serde(default)]
+  pub description: String,
+  pub version: Option<String>,
+  /// 可执行文件
+  pub exec: Option<String>,
+  /// WASI 模块
+  pub wasm: Option<String>,
+  /// 额外的命令行参数
+  #[serde(default)]
+  pub args: Vec<String>,
+  pub provides: Vec<Capability>,
+  /// parser 负责的命令
+  #[serde(default)]
+  pub commands: Vec<String>,
+}
+
+/// 已发现的插件
+#[derive(Debug, Clone)]
+pub struct Plugin {
+  pub name: String,
+  pub dir: PathBuf,
+  pub manifest: Manifest,
+  pub enabled: bool,
+}
+
+/// 插件目录中的所有插件，以及无法加载的清单
+/// parser 插件解析失败，已改用内置解析器的结果；由调用方输出（CLI 打印警告，服务器写日志）
+#[derive(Debug)]
+pub struct ParseFailure {
+  pub plugin: String,
+  pub command: String,
+  pub error: anyhow::Error,
+}
+
+impl std::fmt::Display for ParseFailure {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Parser plugin '{}' failed for '{}', using built-in parser: {}",
+      self.plugin, self.command, self.error
+    )
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+  pub plugins: Vec<Plugin>,
+  /// 清单路径与错误信息
+  pub errors: Vec<(PathBuf, String)>,
+  config: PluginsConfig,
+}
+
+impl Registry {
+  /// 扫描配置中的插件目录；目录不存在时为空
+  pub fn load(config: &AppConfig) -> Self {
+    Self::discover(&config.plugins_dir(), &config.plugins)
+  }
+
+  pub fn discover(dir: &Path, config: &PluginsConfig) -> Self {
+    let mut registry = Registry {
+      config: config.clone(),
+      ..Default::default()
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+      return registry;
+    };
+    let mut dirs: Vec<PathBuf> = entries
+      .filter_map(|e| e.ok().map(|e| e.path()))
+      .filter(|p| p.join(MANIFEST).is_file())
+      .collect();
+    dirs.sort();
+
+    for dir in dirs {
+      let path = dir.join(MANIFEST);
+      match load_manifest(&path) {
+        Ok(manifest) => {
+          let name = manifest.name.clone().unwrap_or_else(|| {
+            dir
+              .file_name()
+              .map(|n| n.to_string_lossy().into_owned())
+              .unwrap_or_default()
+          });
+          if registry.plugins.iter().any(|p| p.name == name) {
+            registry
+              .errors
+              .push((path, format!("duplicate plugin name '{}'", name)));
+            continue;
+          }
+          registry.plugins.push(Plugin {
+            enabled: !config.disabled.contains(&name),
+            name,
+            dir,
+            manifest,
+          });
+        }
+        Err(e) => registry.errors.push((path, e.to_string())),
+      }
+    }
+    registry
+  }
+
+  /// 按名称查找启用且提供该能力的插件
+  pub fn get(&self, name: &str, capability: Capability) -> anyhow::Result<&Plugin> {
+    let plugin = self
+      .plugins
+      .iter()
+      .find(|p| p.name == name)
+      .ok_or_else(|| anyhow::anyhow!("Plugin '{}' not found (see 'rtfm plugin list')", name))?;
+    if !plugin.enabled {
+      anyhow::bail!("Plugin '{}' is disabled in plugins.disabled", name);
+    }
+    if !plugin.manifest.provides.contains(&capability) {
+      anyhow::bail!("Plugin '{}' does not provide a {}", name, capability);
+    }
+    Ok(plugin)
+  }
+
+  /// 负责解析该命令的 parser 插件（按目录顺序取第一个）
+  pub fn parser_for(&self, command: &str) -> Option<&Plugin> {
+    self.plugins.iter().find(|p| {
+      p.enabled
+        && p.manifest.provides.contains(&Capability::Parser)
+        && p
+          .manifest
+          .commands
+          .iter()
+          .any(|pattern| matches(pattern, command))
+    })
+  }
+
+  /// 解析帮助内容；有负责该命令的 parser 插件时用插件的结果覆盖内置解析器的结果，
+  /// 插件失败时保留内置解析结果，同时返回失败原因
+  pub fn parse_help_content(
+    &self,
+    name: &str,
+    content: &str,
+    source: &str,
+  ) -> (Command, Option<ParseFailure>) {
+    let mut cmd = crate::learn::parse_local_help(name, content, source);
+    let Some(plugin) = self.parser_for(name) else {
+      return (cmd, None);
+    };
+    match self.parse(plugin, name, content, source) {
+      Ok(parsed) => {
+        if !parsed.description.trim().is_empty() {
+          cmd.description = parsed.description;
+        }
+        if !parsed.examples.is_empty() {
+          cmd.examples = parsed.examples;
+        }
+        if !parsed.sections.is_empty() {
+          cmd.sections = parsed.sections;
+        }
+        if !parsed.options.is_empty() {
+          cmd.options = parsed.options;
+        }
+        if let Some(info) = cmd.source_info.as_mut() {
+          info.source = format!("{} (plugin: {})", info.source, plugin.name);
+        }
+        (cmd, None)
+      }
+      Err(error) => {
+        let failure = ParseFailure {
+          plugin: plugin.name.clone(),
+          command: name.to_string(),
+          error,
+        };
+        (cmd, Some(failure))
+      }
+    }
+  }
+
+  /// 调用 source 插件获取命令
+  pub fn fetch(&self, plugin: &Plugin) -> anyhow::Result<Vec<Command>> {
+    let commands: Vec<PluginCommand> = self.call(plugin, "fetch", serde_json::json!({}))?;
+    Ok(
+      commands
+        .into_iter()
+        .map(PluginCommand::into_command)
+        .collect(),
+    )
+  }
+
+  fn parse(
+    &self,
+    plugin: &Plugin,
+    name: &str,
+    content: &str,
+    source: &str,
+  ) -> anyhow::Result<PluginCommand> {
+    self.call(
+      plugin,
+      "parse",
+      serde_json::json!({ "name": name, "content": content, "source": source }),
+    )
+  }
+
+  /// 调用 renderer 插件渲染命令
+  pub fn render(&self, plugin: &Plugin, cmd: &Command) -> anyhow::Result<String> {
+    self.call(plugin, "render", serde_json::json!({ "command": cmd }))
+  }
+
+  /// 启动插件进程，发送请求并读取响应
+  fn call<T: DeserializeOwned>(
+    &self,
+    plugin: &Plugin,
+    method: &str,
+    params: serde_json::Value,
+  ) -> anyhow::Result<T> {
+    let request = serde_json::json!({
+      "version": PROTOCOL_VERSION,
+      "method": method,
+      "params": params,
+    });
+    let mut process = plugin.process(&self.config.wasm_runtime)?;
+    let timeout = Duration::from_secs(self.config.timeout_secs);
+    let output = run(&mut process, request.to_string(), timeout)
+      .map_err(|e| anyhow::anyhow!("Failed to run plugin '{}': {}", plugin.name, e))?;
+    parse_response(&output)
+      .map_err(|e| anyhow::anyhow!("Plugin '{}' {}: {}", plugin.name, method, e))
+  }
+}
+
+impl Plugin {
+  /// 插件进程：可执行文件，或由 WASI 运行时加载的模块
+  fn process(&self, wasm_runtime: &str) -> anyhow::Result<Process> {
+    let mut process = match (&self.manifest.exec, &self.manifest.wasm) {
+      (Some(exec), None) => Process::new(self.resolve(exec)),
+      (None, Some(wasm)) => {
+        let mut process = Process::new(wasm_runtime);
+        process.arg("run").arg(self.resolve(wasm));
+        process
+      }
+      _ => anyhow::bail!(
+        "Plugin '{}' must set exactly one of 'exec' or 'wasm' in {}",
+        self.name,
+        MANIFEST
+      ),
+    };
+    process
+      .args(&self.manifest.args)
+      .current_dir(&self.dir)
+      .env("RTFM_PLUGIN_DIR", &self.dir)
+      .env("RTFM_VERSION", env!("CARGO_PKG_VERSION"));
+    Ok(process)
+  }
+
+  /// 相对路径（含路径分隔符）相对插件目录解析，单独的程序名在 PATH 中查找
+  fn resolve(&self, program: &str) -> PathBuf {
+    let path = Path::new(program);
+    if path.is_relative() && path.components().count() > 1 {
+      self.dir.join(path.strip_prefix(".").unwrap_or(path))
+    } else {
+      path.to_path_buf()
+    }
+  }
+
+  /// 入口（可执行文件或模块）是否存在，用于 plugin list
+  pub fn check(&self) -> Result<(), String> {
+    let entry = match (&self.manifest.exec, &self.manifest.wasm) {
+      (Some(exec), None) => exec,
+      (None, Some(wasm)) => wasm,
+      _ => return Err("set exactly one of 'exec' or 'wasm'".to_string()),
+    };
+    let path = self.resolve(entry);
+    if path.components().count() > 1 && !path.exists() {
+      return Err(format!("{} not found", path.display()));
+    }
+    if self.manifest.provides.contains(&Capability::Parser) && self.manifest.commands.is_empty() {
+      return Err("parser plugins must list the commands they parse".to_string());
+    }
+    Ok(())
+  }
+}
+
+fn load_manifest(path: &Path) -> anyhow::Result<Manifest> {
+  let content = std::fs::read_to_string(path)?;
+  let manifest: Manifest = toml::from_str(&content)?;
+  if manifest.provides.is_empty() {
+    anyhow::bail!("'provides' must list at least one of source, parser, renderer");
+  }
+  Ok(manifest)
+}
+
+/// 命令名匹配，支持结尾的 * 通配
+fn matches(pattern: &str, command: &str) -> bool {
+  match pattern.strip_suffix('*') {
+    Some(prefix) => command.starts_with(prefix),
+    None => pattern == command,
+  }
+}
+
+/// 插件返回的命令，除名称外的字段都可省略
+#[derive(Debug, Deserialize)]
+struct PluginCommand {
+  name: String,
+  #[serde(default)]
+  description: String,
+  #[serde(default = "default_platform")]
+  platform: String,
+  #[serde(default = "default_lang")]
+  lang: String,
+  #[serde(default)]
+  examples: Vec<Example>,
+  #[serde(default)]
+  content: String,
+  #[serde(default)]
+  sections: Vec<Section>,
+  #[serde(default)]
+  options: Vec<OptionEntry>,
+}
+
+fn default_platform() -> String {
+  "common".to_string()
+}
+
+fn default_lang() -> String {
+  "en".to_string()
+}
+
+impl PluginCommand {
+  fn into_command(self) -> Command {
+    Command {
+      name: self.name,
+      description: self.description,
+      category: self.platform.clone(),
+      platform: self.platform,
+      lang: self.lang,
+      examples: self.examples,
+      content: self.content,
+      sections: self.sections,
+      options: self.options,
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct Response {
+  result: Option<serde_json::Value>,
+  error: Option<String>,
+}
+
+/// 进程输出
+struct Output {
+  stdout: String,
+  stderr: String,
+  success: bool,
+}
+
+fn parse_response<T: DeserializeOwned>(output: &Output) -> anyhow::Result<T> {
+  // 插件可能在响应前打印日志，取最后一个非空行
+  let line = output.stdout.lines().rev().find(|l| !l.trim().is_empty());
+  let response = line.and_then(|l| serde_json::from_str::<Response>(l).ok());
+  match response {
+    Some(Response {
+      error: Some(error), ..
+    }) => anyhow::bail!("{}", error),
+    Some(Response {
+      result: Some(result),
+      ..
+    }) => Ok(serde_json::from_value(result)?),
+    _ => {
+      let stderr = output.stderr.trim();
+      if !output.success && !stderr.is_empty() {
+        anyhow::bail!("{}", stderr.lines().last().unwrap_or(stderr));
+      }
+      anyhow::bail!(
+        "no valid response on stdout (expected {{\"result\": ...}} or {{\"error\": ...}})"
+      )
+    }
+  }
+}
+
+/// 运行插件：写入请求并关闭标准输入，超时则终止
+fn run(process: &mut Process, request: String, timeout: Duration) -> std::io::Result<Output> {
+  let mut child = process
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()?;
+
+  let mut stdin = child.stdin.take();
+  let writer = std::thread::spawn(move || {
+    if let Some(stdin) = stdin.as_mut() {
+      // 插件不读取请求时写入会失败，忽略即可
+      let _ = writeln!(stdin, "{}", request);
+    }
+  });
+  let stdout = read_all(child.stdout.take());
+  let stderr = read_all(child.stderr.take());
+
+  let deadline = Instant::now() + timeout;
+  let status = loop {
+    if let Some(status) = child.try_wait()? {
+      break status;
+    }
+    if Instant::now() >= deadline {
+      let _ = child.kill();
+      let _ = child.wait();
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!("no response within {}s", timeout.as_secs()),
+      ));
+    }
+    std::thread::sleep(Duration::from_millis(10));
+  };
+
+  let _ = writer.join();
+  Ok(Output {
+    stdout: stdout.join().unwrap_or_default(),
+    stderr: stderr.join().unwrap_or_default(),
+    success: status.success(),
+  })
+}
+
+fn read_all<R: Read + Send + 'static>(reader: Option<R>) -> std::thread::JoinHandle<String> {
+  std::thread::spawn(move || {
+    let mut out = Vec::new();
+    if let Some(mut reader) = reader {
+      let _ = reader.read_to_end(&mut out);
+    }
+    String::from_utf8_lossy(&out).into_owned()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_discover() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, manifest: &str| {
+      std::fs::create_dir_all(dir.path().join(name)).unwrap();
+      std::fs::write(dir.path().join(name).join(MANIFEST), manifest).unwrap();
+    };
+    write(
+      "kube",
+      "exec = \"./kube\"\nprovides = [\"parser\"]\ncommands = [\"kubectl*\"]\n",
+    );
+    write("html", "exec = \"html\"\nprovides = [\"renderer\"]\n");
+    write("broken", "exec = \"x\"\nprovides = [\"formatter\"]\n");
+
+    let config = PluginsConfig {
+      disabled: vec!["html".to_string()],
+      ..Default::default()
+    };
+    let registry = Registry::discover(dir.path(), &config);
+    let names: Vec<&str> = registry.plugins.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["html", "kube"]);
+    assert_eq!(registry.errors.len(), 1);
+
+    assert_eq!(registry.parser_for("kubectl-apply").unwrap().name, "kube");
+    assert!(registry.parser_for("kube").is_none());
+    assert!(registry.get("kube", Capability::Renderer).is_err());
+    assert!(registry.get("html", Capability::Renderer).is_err());
+    // 可执行文件不存在
+    assert!(registry
+      .get("kube", Capability::Parser)
+      .unwrap()
+      .check()
+      .is_err());
+    // 插件失败时保留内置解析结果，失败原因交给调用方输出
+    let (cmd, failure) =
+      registry.parse_help_content("kubectl-apply", "Usage: kubectl apply", "--help");
+    assert_eq!(cmd.name, "kubectl-apply");
+    let failure = failure.unwrap();
+    assert_eq!(
+      (failure.plugin.as_str(), failure.command.as_str()),
+      ("kube", "kubectl-apply")
+    );
+    assert!(!failure.to_string().contains('\x1b'));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_call() {
+    let dir = tempfile::tempdir().unwrap();
+    let plugin_dir = dir.path().join("echo");
+    std::fs::create_dir_all(&plugin_dir).unwrap();
+    std::fs::write(
+      plugin_dir.join(MANIFEST),
+      "exec = \"sh\"\nargs = [\"./render.sh\"]\nprovides = [\"renderer\", \"source\"]\n",
+    )
+    .unwrap();
+    // 读取请求，返回方法名；fetch 返回只有名称的命令
+    std::fs::write(
+      plugin_dir.join("render.sh"),
+      r#"read -r request
+case "$request" in
+  *'"method":"fetch"'*) echo '{"result": [{"name": "kubectl"}]}' ;;
+  *'"method":"render"'*) echo 'log line'; echo '{"result": "rendered"}' ;;
+  *) echo '{"error": "unsupported"}' ;;
+esac
+"#,
+    )
+    .unwrap();
+
+    let registry = Registry::discover(dir.path(), &PluginsConfig::default());
+    let plugin = registry.get("echo", Capability::Renderer).unwrap();
+    let cmd = crate::learn::parse_help_content("tool", "Usage: tool", "--help");
+    assert_eq!(registry.render(plugin, &cmd).unwrap(), "rendered");
+
+    let commands = registry.fetch(plugin).unwrap();
+    assert_eq!(commands[0].name, "kubectl");
+    assert_eq!(commands[0].lang, "en");
+
+    let err = registry.parse(plugin, "tool", "", "--help").unwrap_err();
+    assert!(err.to_string().contains("unsupported"));
+  }
+}
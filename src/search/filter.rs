@@ -0,0 +1,143 @@
+//! 查询中的筛选条件
+//!
+//! TUI 搜索框和 `rtfm <query>` 中可以直接写 `lang:zh`、`platform:linux`、`category:local`、`tag:git`，
+//! 在交给 SearchEngine 之前取出，其余文字照常搜索。lang 和 platform 在检索时筛选，
+//! category 和 tag（navi / cheat 速查表导入时写入描述的标签）在检索后筛选。
+
+use super::{Platform, SearchResult};
+use crate::storage::Command;
+
+/// 查询中的筛选条件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryFilters {
+  pub lang: Option<String>,
+  pub platform: Option<String>,
+  pub category: Option<String>,
+  pub tag: Option<String>,
+}
+
+impl QueryFilters {
+  /// 取出查询中的筛选条件，返回条件和其余文字；同一条件写多次时以最后一次为准
+  pub fn parse(query: &str) -> (Self, String) {
+    let mut filters = Self::default();
+    let mut words = Vec::new();
+    for word in query.split_whitespace() {
+      let slot = word.split_once(':').and_then(|(key, value)| {
+        let slot = match key.to_lowercase().as_str() {
+          "lang" => &mut filters.lang,
+          "platform" => &mut filters.platform,
+          "category" => &mut filters.category,
+          "tag" => &mut filters.tag,
+          _ => return None,
+        };
+        Some((slot, value)).filter(|(_, value)| !value.is_empty())
+      });
+      match slot {
+        Some((slot, value)) => *slot = Some(value.to_lowercase()),
+        None => words.push(word),
+      }
+    }
+    (filters, words.join(" "))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// 需要在检索后筛选（检索时要多取一些结果）
+  pub fn needs_post_filter(&self) -> bool {
+    self.category.is_some() || self.tag.is_some()
+  }
+
+  /// 检索时使用的平台：有 platform 条件时只保留该平台和 common
+  pub fn platform_or(&self, platform: &Platform) -> Platform {
+    match &self.platform {
+      Some(p) => Platform::Only(p.clone()),
+      None => platform.clone(),
+    }
+  }
+
+  fn matches(&self, lang: &str, platform: &str, category: &str, description: &str) -> bool {
+    let eq = |filter: &Option<String>, value: &str| {
+      filter
+        .as_deref()
+        .is_none_or(|f| f.eq_ignore_ascii_case(value))
+    };
+    eq(&self.lang, lang)
+      && eq(&self.category, category)
+      && self
+        .platform
+        .as_ref()
+        .is_none_or(|p| Platform::Only(p.clone()).matches(platform))
+      && self.tag.as_deref().is_none_or(|tag| {
+        crate::sheets::description_tags(description)
+          .iter()
+          .any(|t| t.eq_ignore_ascii_case(tag))
+      })
+  }
+
+  pub fn matches_result(&self, result: &SearchResult) -> bool {
+    self.matches(
+      &result.lang,
+      &result.platform,
+      &result.category,
+      &result.description,
+    )
+  }
+
+  pub fn matches_command(&self, cmd: &Command) -> bool {
+    self.matches(&cmd.lang, &cmd.platform, &cmd.category, &cmd.description)
+  }
+
+  /// 状态栏中显示的条件，如 `lang:zh platform:linux`
+  pub fn describe(&self) -> String {
+    [
+      ("lang", &self.lang),
+      ("platform", &self.platform),
+      ("category", &self.category),
+      ("tag", &self.tag),
+    ]
+    .iter()
+    .filter_map(|(key, value)| value.as_ref().map(|v| format!("{}:{}", key, v)))
+    .collect::<Vec<_>>()
+    .join(" ")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_filters() {
+    let (filters, text) = QueryFilters::parse("lang:zh  compress Platform:Linux tag:Git http://x");
+    assert_eq!(text, "compress http://x");
+    assert_eq!(filters.lang.as_deref(), Some("zh"));
+    assert_eq!(filters.platform.as_deref(), Some("linux"));
+    assert_eq!(filters.tag.as_deref(), Some("git"));
+    assert_eq!(filters.describe(), "lang:zh platform:linux tag:git");
+    // 空的条件按普通文字搜索
+    let (filters, text) = QueryFilters::parse("category: tar");
+    assert!(filters.is_empty());
+    assert_eq!(text, "category: tar");
+
+    let result = |platform: &str, category: &str, description: &str| SearchResult {
+      name: "git".to_string(),
+      description: description.to_string(),
+      category: category.to_string(),
+      lang: "local".to_string(),
+      platform: platform.to_string(),
+      score: 1.0,
+      example: None,
+    };
+    let (filters, _) = QueryFilters::parse("platform:linux category:local tag:vcs");
+    assert!(filters.matches_result(&result(
+      "common",
+      "local",
+      "Personal navi cheatsheet (tags: git, vcs)"
+    )));
+    assert!(!filters.matches_result(&result("osx", "local", "(tags: vcs)")));
+    assert!(!filters.matches_result(&result("linux", "common", "(tags: vcs)")));
+    assert!(!filters.matches_result(&result("linux", "local", "Version control")));
+  }
+}
@@ -1,20 +1,51 @@
-use std::path::Path;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
 use jieba_rs::Jieba;
+use lru::LruCache;
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
-use tantivy::schema::{Field, Schema, Value, STORED, TEXT};
-use tantivy::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer};
-use tantivy::{Index, IndexReader, IndexWriter, TantivyDocument};
+use tantivy::query::{Query, QueryParser};
+use tantivy::schema::{
+  Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, FAST, STORED, STRING,
+  TEXT,
+};
+use tantivy::tokenizer::{
+  Language, LowerCaser, SimpleTokenizer, Stemmer, StopWordFilter, TextAnalyzer,
+};
+use tantivy::{
+  DocAddress, DocId, Index, IndexReader, IndexWriter, Score, Searcher, SegmentReader,
+  TantivyDocument, Term,
+};
 use thiserror::Error;
 use utoipa::ToSchema;
 
-use crate::storage::Command;
+use crate::config::{SearchConfig, MIN_INDEX_BUFFER_SIZE};
+use crate::storage::{Command, Database};
+
+mod filter;
+mod suggest;
+
+pub use filter::QueryFilters;
+pub use suggest::MAX_SUGGESTIONS;
 
 static JIEBA: Lazy<Jieba> = Lazy::new(Jieba::new);
 
+/// 优先当前平台时，其他平台命令的分数乘以该系数
+const OTHER_PLATFORM_FACTOR: f32 = 0.5;
+
+/// 索引格式版本，字段或分词方式变化时加一；记录在每次提交的 payload 中，
+/// 与当前版本不同的索引在下次全量索引（或 open_index）时重建
+const INDEX_VERSION: u32 = 3;
+
+/// 英文词干分词器（去掉停用词，copying / copies -> copi）
+const STEM_TOKENIZER: &str = "en_stem";
+
+/// 词干字段的权重相对原字段的比例，原文完全相同的结果排在前面
+const STEM_BOOST_FACTOR: f32 = 0.5;
+
 #[derive(Error, Debug)]
 pub enum SearchError {
   #[error("Tantivy error: {0}")]
@@ -25,6 +56,8 @@ pub enum SearchError {
   OpenDirectory(#[from] tantivy::directory::error::OpenDirectoryError),
   #[error("IO error: {0}")]
   Io(#[from] std::io::Error),
+  #[error("User dictionary error: {0}")]
+  UserDict(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -37,107 +70,376 @@ pub struct SearchResult {
   pub category: String,
   /// Language code
   pub lang: String,
+  /// Target platform (empty for results from an index built before platforms were indexed)
+  #[serde(default)]
+  pub platform: String,
   /// Search relevance score
   pub score: f32,
+  /// The matching example, for results in the `examples` section; `description` is then the example's description
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub example: Option<ExampleMatch>,
+}
+
+/// An example that matched the query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExampleMatch {
+  /// Position of the example in the command, from 1 (as in `rtfm <name> --copy <N>`)
+  pub index: usize,
+  /// Example code
+  pub code: String,
+}
+
+/// 按平台筛选或排序结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Platform {
+  /// 不区分平台
+  #[default]
+  All,
+  /// 该平台和 common 的命令排在其他平台之前
+  Prefer(String),
+  /// 只保留该平台和 common 的命令；common 只保留通用命令
+  Only(String),
+}
+
+impl Platform {
+  /// 解析 --platform、platform 参数和 search.platform：auto 为当前系统，all 不区分，其他为平台名
+  pub fn parse(value: &str) -> Self {
+    match value.trim().to_lowercase().as_str() {
+      "" | "all" => Platform::All,
+      "auto" => Platform::Prefer(crate::learn::get_platform()),
+      platform => Platform::Only(platform.to_string()),
+    }
+  }
+
+  /// 首选或保留的平台
+  fn platforms(&self) -> Vec<String> {
+    match self {
+      Platform::All => Vec::new(),
+      Platform::Prefer(platform) | Platform::Only(platform) => {
+        let mut platforms = vec![platform.clone()];
+        if platform != "common" {
+          platforms.push("common".to_string());
+        }
+        platforms
+      }
+    }
+  }
+
+  /// 该平台的命令是否保留；旧索引和向量索引中没有平台的结果总是保留
+  pub fn matches(&self, platform: &str) -> bool {
+    match self {
+      Platform::Only(_) if !platform.is_empty() => self.platforms().iter().any(|p| p == platform),
+      _ => true,
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchResponse {
   /// Total results count
   pub total: usize,
+  /// Preferred language: the `lang` filter, otherwise detected from the query (`search.cjk_lang` for CJK text, else `search.default_lang`); results in it come before other languages of the same command
+  #[serde(default)]
+  pub lang: String,
   /// Search results
   pub results: Vec<SearchResult>,
+  /// Examples that match the query, best first (at most search.example_results)
+  #[serde(default)]
+  pub examples: Vec<SearchResult>,
+  /// Command names close to the query, closest first (at most 5, only when there are no results)
+  #[serde(default)]
+  pub suggestions: Vec<String>,
   /// Query execution time in milliseconds
   pub took_ms: u64,
 }
 
 pub struct SearchEngine {
+  path: PathBuf,
   index: Index,
   reader: IndexReader,
-  #[allow(dead_code)]
   schema: Schema,
+  /// 唯一键 `lang:name`，与数据库的键相同，用于替换和删除单条命令
+  key_field: Field,
+  /// 索引是否有 key 字段；旧版本建立的索引没有，下次全量索引时重建
+  has_key: bool,
   name_field: Field,
   description_field: Field,
   content_field: Field,
   category_field: Field,
   lang_field: Field,
+  platform_field: Field,
+  /// 索引是否有 platform 字段；没有时忽略平台，下次全量索引时重建
+  has_platform: bool,
+  /// description / content 的词干字段，原字段保留用于精确匹配
+  description_stem_field: Field,
+  content_stem_field: Field,
+  /// 索引是否有词干字段；没有时只搜索原字段
+  has_stem: bool,
+  /// 示例文档的字段：每个示例单独一个文档，key 等字段与所属命令相同，不含 name / description / content，
+  /// 因此不会出现在命令的结果中，按 key 替换和删除命令时一并处理
+  example_field: Field,
+  example_stem_field: Field,
+  /// 示例在命令中的位置（从 1 开始）
+  example_index_field: Field,
+  /// 示例的原文（只存储）
+  example_description_field: Field,
+  example_code_field: Field,
+  /// 索引是否有示例字段
+  has_examples: bool,
+  /// 每次搜索最多返回的示例数，0 表示不搜索示例
+  example_limit: usize,
+  /// 索引中记录的格式版本，没有记录时为 0
+  version: u32,
+  /// 加载了用户词典的分词器，未配置时使用共享的默认分词器
+  jieba: Option<Jieba>,
+  /// 索引写入缓冲区大小（字节）
+  buffer_size: usize,
+  /// name / description / content 字段的权重
+  boosts: [f32; 3],
+  /// 名称与查询完全相同的命令的分数倍数
+  exact_name_boost: f32,
+  /// 模糊匹配的编辑距离，0 表示关闭
+  fuzzy_distance: u8,
+  /// 模糊搜索的编辑距离，不小于 fuzzy_distance
+  fuzzy_search_distance: u8,
+  /// 查询的首选语言（search.default_lang、search.cjk_lang）
+  default_lang: String,
+  cjk_lang: String,
+  /// 最近查询的结果，键为 (查询, 语言, 平台, 数量, 编辑距离)；边输入边搜索的客户端会重复发出相同的查询。
+  /// 修改索引的方法都需要 &mut self，在其中清空缓存即可保证结果不过期
+  cache: Option<Mutex<LruCache<CacheKey, SearchResponse>>>,
 }
 
+type CacheKey = (String, Option<String>, Platform, usize, u8);
+
 impl SearchEngine {
-  pub fn open(path: &Path) -> Result<Self, SearchError> {
+  pub fn open(path: &Path, config: &SearchConfig) -> Result<Self, SearchError> {
     std::fs::create_dir_all(path)?;
 
-    // 构建 Schema
+    // 构建 Schema；新字段只能追加在末尾，已有字段的编号与旧索引一致
     let mut schema_builder = Schema::builder();
     let name_field = schema_builder.add_text_field("name", TEXT | STORED);
     let description_field = schema_builder.add_text_field("description", TEXT | STORED);
     let content_field = schema_builder.add_text_field("content", TEXT);
     let category_field = schema_builder.add_text_field("category", TEXT | STORED);
     let lang_field = schema_builder.add_text_field("lang", TEXT | STORED);
+    let key_field = schema_builder.add_text_field("key", STRING | STORED);
+    let platform_field = schema_builder.add_text_field("platform", STRING | STORED | FAST);
+    let stem = TextOptions::default().set_indexing_options(
+      TextFieldIndexing::default()
+        .set_tokenizer(STEM_TOKENIZER)
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    let description_stem_field = schema_builder.add_text_field("description_stem", stem.clone());
+    let content_stem_field = schema_builder.add_text_field("content_stem", stem.clone());
+    let example_field = schema_builder.add_text_field("example", TEXT);
+    let example_stem_field = schema_builder.add_text_field("example_stem", stem);
+    let example_index_field = schema_builder.add_u64_field("example_index", STORED);
+    let example_description_field = schema_builder.add_text_field("example_description", STORED);
+    let example_code_field = schema_builder.add_text_field("example_code", STORED);
     let schema = schema_builder.build();
 
     // 打开或创建索引
-    let index = if path.join("meta.json").exists() {
-      Index::open_in_dir(path)?
+    let (index, created) = if path.join("meta.json").exists() {
+      (Index::open_in_dir(path)?, false)
     } else {
-      Index::create_in_dir(path, schema.clone())?
+      (Index::create_in_dir(path, schema.clone())?, true)
     };
-
-    // 注册自定义分词器（简单分词 + 小写）
-    let tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
-      .filter(LowerCaser)
-      .build();
-    index.tokenizers().register("default", tokenizer);
-
+    let has_key = index.schema().get_field("key").is_ok();
+    let has_platform = index.schema().get_field("platform").is_ok();
+    let has_stem = index.schema().get_field("content_stem").is_ok();
+    let has_examples = index.schema().get_field("example_code").is_ok();
+    let version = if created {
+      INDEX_VERSION
+    } else {
+      index
+        .load_metas()?
+        .payload
+        .and_then(|payload| payload.parse().ok())
+        .unwrap_or(0)
+    };
+    register_tokenizer(&index);
     let reader = index.reader()?;
 
+    let jieba = match &config.user_dict {
+      Some(dict_path) => Some(load_user_dict(dict_path)?),
+      None => None,
+    };
+
     Ok(Self {
+      path: path.to_path_buf(),
       index,
       reader,
       schema,
+      key_field,
+      has_key,
       name_field,
       description_field,
       content_field,
       category_field,
       lang_field,
+      platform_field,
+      has_platform,
+      description_stem_field,
+      content_stem_field,
+      has_stem,
+      example_field,
+      example_stem_field,
+      example_index_field,
+      example_description_field,
+      example_code_field,
+      has_examples,
+      example_limit: config.example_results,
+      version,
+      jieba,
+      buffer_size: config.index_buffer_size.max(MIN_INDEX_BUFFER_SIZE),
+      boosts: [
+        config.name_boost,
+        config.description_boost,
+        config.content_boost,
+      ],
+      exact_name_boost: config.exact_name_boost,
+      fuzzy_distance: config.fuzzy_distance.min(2),
+      fuzzy_search_distance: config
+        .fuzzy_search_distance
+        .clamp(1, 2)
+        .max(config.fuzzy_distance.min(2)),
+      default_lang: config.default_lang.clone(),
+      cjk_lang: config.cjk_lang.clone(),
+      cache: NonZeroUsize::new(config.result_cache_size)
+        .map(|size| Mutex::new(LruCache::new(size))),
     })
   }
 
-  pub fn index_commands(&mut self, commands: &[Command]) -> Result<(), SearchError> {
-    let mut writer: IndexWriter = self.index.writer(50_000_000)?;
+  /// 索引变化后清空结果缓存
+  fn invalidate_cache(&mut self) {
+    if let Some(cache) = &mut self.cache {
+      cache.get_mut().clear();
+    }
+  }
+
+  fn jieba(&self) -> &Jieba {
+    self.jieba.as_ref().unwrap_or(&JIEBA)
+  }
+
+  /// 索引的格式与当前版本一致；不一致时应从数据库重建
+  pub fn is_current(&self) -> bool {
+    self.version == INDEX_VERSION
+  }
+
+  /// 索引读取器可见的文档数
+  pub fn num_docs(&self) -> u64 {
+    self.reader.searcher().num_docs()
+  }
+
+  /// 提交并在 payload 中记录索引的格式版本
+  fn commit(&self, writer: &mut IndexWriter) -> Result<(), SearchError> {
+    let mut prepared = writer.prepare_commit()?;
+    prepared.set_payload(&self.version.to_string());
+    prepared.commit()?;
+    Ok(())
+  }
+
+  /// 清空索引后重新索引全部命令（commands 应为数据库中的所有命令）
+  pub fn rebuild(&mut self, commands: &[Command]) -> Result<(), SearchError> {
+    if !self.is_current() {
+      self.recreate()?;
+    }
+    let mut writer: IndexWriter = self.index.writer(self.buffer_size)?;
 
     // 清空现有索引
     writer.delete_all_documents()?;
 
     for cmd in commands {
-      let mut doc = TantivyDocument::default();
-
-      // 对 name 和 description 也进行 jieba 分词，保持与查询时一致
-      let tokenized_name = self.tokenize_chinese(&cmd.name);
-      let tokenized_description = self.tokenize_chinese(&cmd.description);
-      doc.add_text(self.name_field, &tokenized_name);
-      doc.add_text(self.description_field, &tokenized_description);
+      for doc in self.documents(cmd) {
+        writer.add_document(doc)?;
+      }
+    }
 
-      // 对内容进行 jieba 分词后存入
-      let tokenized_content = self.tokenize_chinese(&cmd.content);
-      doc.add_text(self.content_field, &tokenized_content);
+    self.commit(&mut writer)?;
+    self.reader.reload()?;
+    self.invalidate_cache();
 
-      doc.add_text(self.category_field, &cmd.category);
-      doc.add_text(self.lang_field, &cmd.lang);
+    Ok(())
+  }
 
-      writer.add_document(doc)?;
+  /// 增量索引一批命令，替换同一语言的同名命令，其余命令不变
+  /// 旧版本建立的索引缺少字段或分词方式不同，返回 false，调用方应从数据库重建索引
+  pub fn index_commands(&mut self, commands: &[Command]) -> Result<bool, SearchError> {
+    if !self.is_current() {
+      return Ok(false);
     }
+    self.upsert(commands)?;
+    Ok(true)
+  }
+
+  /// 增量索引单个命令，替换同一语言的同名命令；旧版本的索引同样返回 false
+  pub fn index_single_command(&mut self, cmd: &Command) -> Result<bool, SearchError> {
+    self.index_commands(std::slice::from_ref(cmd))
+  }
 
-    writer.commit()?;
+  /// 只能写入当前格式的索引：旧索引的字段编号与当前 Schema 不同，写入的文档会使索引线程崩溃
+  fn upsert(&mut self, commands: &[Command]) -> Result<(), SearchError> {
+    debug_assert!(self.is_current());
+    let mut writer: IndexWriter = self.index.writer(self.buffer_size)?;
+
+    for cmd in commands {
+      if self.has_key {
+        writer.delete_term(self.key_term(&cmd.name, &cmd.lang));
+      }
+      for doc in self.documents(cmd) {
+        writer.add_document(doc)?;
+      }
+    }
+    self.commit(&mut writer)?;
     self.reader.reload()?;
+    self.invalidate_cache();
 
     Ok(())
   }
 
-  /// 增量索引单个命令
-  pub fn index_single_command(&mut self, cmd: &Command) -> Result<(), SearchError> {
-    let mut writer: IndexWriter = self.index.writer(50_000_000)?;
+  /// 从索引中删除一条命令
+  /// 旧版本建立的索引没有 key 字段，无法按命令删除，返回 false，调用方应重新建立索引
+  pub fn delete_command(&mut self, name: &str, lang: &str) -> Result<bool, SearchError> {
+    if !self.has_key {
+      return Ok(false);
+    }
+    let mut writer: IndexWriter = self.index.writer(self.buffer_size)?;
+    writer.delete_term(self.key_term(name, lang));
+    self.commit(&mut writer)?;
+    self.reader.reload()?;
+    self.invalidate_cache();
+    Ok(true)
+  }
+
+  fn key_term(&self, name: &str, lang: &str) -> Term {
+    Term::from_field_text(self.key_field, &format!("{}:{}", lang, name))
+  }
+
+  /// 命令的文档，以及每个示例的文档
+  fn documents(&self, cmd: &Command) -> Vec<TantivyDocument> {
+    let mut docs = vec![self.document(cmd)];
+    if !self.has_examples {
+      return docs;
+    }
+    for (i, example) in cmd.examples.iter().enumerate() {
+      let mut doc = TantivyDocument::default();
+      let description = self.tokenize_chinese(&example.description);
+      doc.add_text(
+        self.example_field,
+        format!("{}\n{}", description, self.tokenize_chinese(&example.code)),
+      );
+      doc.add_text(self.example_stem_field, &description);
+      doc.add_u64(self.example_index_field, i as u64 + 1);
+      doc.add_text(self.example_description_field, &example.description);
+      doc.add_text(self.example_code_field, &example.code);
+      self.add_command_fields(&mut doc, cmd);
+      docs.push(doc);
+    }
+    docs
+  }
 
+  fn document(&self, cmd: &Command) -> TantivyDocument {
     let mut doc = TantivyDocument::default();
 
     // 对 name 和 description 也进行 jieba 分词，保持与查询时一致
@@ -146,16 +448,51 @@ impl SearchEngine {
     doc.add_text(self.name_field, &tokenized_name);
     doc.add_text(self.description_field, &tokenized_description);
 
-    let tokenized_content = self.tokenize_chinese(&cmd.content);
+    // 对内容进行 jieba 分词后存入，个人笔记也可以搜索
+    let tokenized_content = match &cmd.notes {
+      Some(notes) => self.tokenize_chinese(&format!("{}\n{}", cmd.content, notes)),
+      None => self.tokenize_chinese(&cmd.content),
+    };
     doc.add_text(self.content_field, &tokenized_content);
+    if self.has_stem {
+      doc.add_text(self.description_stem_field, &tokenized_description);
+      doc.add_text(self.content_stem_field, &tokenized_content);
+    }
+    self.add_command_fields(&mut doc, cmd);
+    doc
+  }
 
+  /// 命令文档与示例文档共有的字段
+  fn add_command_fields(&self, doc: &mut TantivyDocument, cmd: &Command) {
     doc.add_text(self.category_field, &cmd.category);
     doc.add_text(self.lang_field, &cmd.lang);
+    if self.has_key {
+      doc.add_text(self.key_field, format!("{}:{}", cmd.lang, cmd.name));
+    }
+    if self.has_platform {
+      doc.add_text(self.platform_field, &cmd.platform);
+    }
+  }
 
-    writer.add_document(doc)?;
-    writer.commit()?;
-    self.reader.reload()?;
+  /// 按当前 Schema 重新创建空索引（旧索引缺少字段时）
+  fn recreate(&mut self) -> Result<(), SearchError> {
+    tracing::info!("Recreating search index with the current schema");
+    // 先换成内存索引释放旧索引的文件（Windows 上无法删除仍在映射的文件）
+    let placeholder = Index::create_in_ram(self.schema.clone());
+    self.reader = placeholder.reader()?;
+    self.index = placeholder;
 
+    std::fs::remove_dir_all(&self.path)?;
+    std::fs::create_dir_all(&self.path)?;
+    let index = Index::create_in_dir(&self.path, self.schema.clone())?;
+    register_tokenizer(&index);
+    self.reader = index.reader()?;
+    self.index = index;
+    self.has_key = true;
+    self.has_platform = true;
+    self.has_stem = true;
+    self.has_examples = true;
+    self.version = INDEX_VERSION;
     Ok(())
   }
 
@@ -163,7 +500,85 @@ impl SearchEngine {
     &self,
     query: &str,
     lang: Option<&str>,
+    platform: &Platform,
+    limit: usize,
+  ) -> Result<SearchResponse, SearchError> {
+    self.search_with_distance(query, lang, platform, limit, self.fuzzy_distance)
+  }
+
+  /// 容忍拼写错误的搜索：每个词允许 fuzzy_search_distance 次编辑（含相邻字母互换），
+  /// 如 "dokcer" 可以找到 docker
+  pub fn search_fuzzy(
+    &self,
+    query: &str,
+    lang: Option<&str>,
+    platform: &Platform,
     limit: usize,
+  ) -> Result<SearchResponse, SearchError> {
+    self.search_with_distance(query, lang, platform, limit, self.fuzzy_search_distance)
+  }
+
+  fn search_with_distance(
+    &self,
+    query: &str,
+    lang: Option<&str>,
+    platform: &Platform,
+    limit: usize,
+    distance: u8,
+  ) -> Result<SearchResponse, SearchError> {
+    // 同一命令有多种语言时首选语言的版本排在前面；缓存的是排序前的结果
+    let preferred = lang.unwrap_or_else(|| query_lang(query, &self.default_lang, &self.cjk_lang));
+    let mut response = self.search_cached(query, lang, platform, limit, distance)?;
+    prefer_lang(&mut response.results, preferred);
+    prefer_lang(&mut response.examples, preferred);
+    response.lang = preferred.to_string();
+    Ok(response)
+  }
+
+  fn search_cached(
+    &self,
+    query: &str,
+    lang: Option<&str>,
+    platform: &Platform,
+    limit: usize,
+    distance: u8,
+  ) -> Result<SearchResponse, SearchError> {
+    // 旧索引没有平台，不能筛选
+    let platform = if self.has_platform {
+      platform
+    } else {
+      &Platform::All
+    };
+    let Some(cache) = &self.cache else {
+      return self.search_index(query, lang, platform, limit, distance);
+    };
+
+    let start = std::time::Instant::now();
+    let key = (
+      query.to_string(),
+      lang.map(str::to_string),
+      platform.clone(),
+      limit,
+      distance,
+    );
+    if let Some(cached) = cache.lock().get(&key) {
+      return Ok(SearchResponse {
+        took_ms: start.elapsed().as_millis() as u64,
+        ..cached.clone()
+      });
+    }
+    let response = self.search_index(query, lang, platform, limit, distance)?;
+    cache.lock().put(key, response.clone());
+    Ok(response)
+  }
+
+  fn search_index(
+    &self,
+    query: &str,
+    lang: Option<&str>,
+    platform: &Platform,
+    limit: usize,
+    distance: u8,
   ) -> Result<SearchResponse, SearchError> {
     let start = std::time::Instant::now();
 
@@ -173,20 +588,32 @@ impl SearchEngine {
     let tokenized_query = self.tokenize_and_escape(query);
 
     // 构建查询
-    let query_parser = QueryParser::for_index(
-      &self.index,
-      vec![self.name_field, self.description_field, self.content_field],
-    );
-
-    // 如果指定了语言，添加语言过滤
-    let query_str = if let Some(l) = lang {
-      format!("({}) AND lang:{}", tokenized_query, l)
-    } else {
-      tokenized_query
-    };
+    let fields = [self.name_field, self.description_field, self.content_field];
+    let stem_fields = [
+      (self.description_stem_field, self.boosts[1]),
+      (self.content_stem_field, self.boosts[2]),
+    ];
+    let mut all_fields = fields.to_vec();
+    if self.has_stem {
+      all_fields.extend(stem_fields.iter().map(|(field, _)| *field));
+    }
+    let mut query_parser = QueryParser::for_index(&self.index, all_fields);
+    for (field, boost) in fields.into_iter().zip(self.boosts) {
+      query_parser.set_field_boost(field, boost);
+      if distance > 0 {
+        query_parser.set_field_fuzzy(field, false, distance, true);
+      }
+    }
+    // 词干字段不做模糊匹配，词干本身已经容忍词形变化
+    if self.has_stem {
+      for (field, boost) in stem_fields {
+        query_parser.set_field_boost(field, boost * STEM_BOOST_FACTOR);
+      }
+    }
 
+    let query_str = Self::filter_query(&tokenized_query, lang, platform);
     let parsed_query = query_parser.parse_query(&query_str)?;
-    let top_docs = searcher.search(&parsed_query, &TopDocs::with_limit(limit))?;
+    let top_docs = Self::top_docs(&searcher, &*parsed_query, platform, limit)?;
 
     let mut results = Vec::new();
     for (score, doc_address) in top_docs {
@@ -216,28 +643,157 @@ impl SearchEngine {
         .unwrap_or("")
         .to_string();
 
+      let platform = doc
+        .get_first(self.platform_field)
+        .filter(|_| self.has_platform)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
       results.push(SearchResult {
         name,
         description,
         category,
         lang,
+        platform,
         score,
+        example: None,
       });
     }
 
+    // 名称与查询完全相同的命令排在内容提到它的命令之前；多个词按 tldr 的命名规范连接（docker compose -> docker-compose）
+    if self.exact_name_boost != 1.0 {
+      let exact = query.split_whitespace().collect::<Vec<_>>().join("-");
+      for result in &mut results {
+        if result.name.eq_ignore_ascii_case(&exact) {
+          result.score *= self.exact_name_boost;
+        }
+      }
+      results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    let examples = if self.has_examples && self.example_limit > 0 {
+      self.search_examples(&searcher, &tokenized_query, lang, platform, distance)?
+    } else {
+      Vec::new()
+    };
+
+    // 没有结果时给出名称相近的命令
+    let suggestions = if results.is_empty() {
+      self.suggest(query, lang)?
+    } else {
+      Vec::new()
+    };
+
     let took_ms = start.elapsed().as_millis() as u64;
 
     Ok(SearchResponse {
       total: results.len(),
+      lang: String::new(),
       results,
+      examples,
+      suggestions,
       took_ms,
     })
   }
 
+  /// 搜索示例文档，结果的 description 为示例的说明
+  fn search_examples(
+    &self,
+    searcher: &Searcher,
+    tokenized_query: &str,
+    lang: Option<&str>,
+    platform: &Platform,
+    distance: u8,
+  ) -> Result<Vec<SearchResult>, SearchError> {
+    let mut query_parser = QueryParser::for_index(
+      &self.index,
+      vec![self.example_field, self.example_stem_field],
+    );
+    if distance > 0 {
+      query_parser.set_field_fuzzy(self.example_field, false, distance, true);
+    }
+    query_parser.set_field_boost(self.example_stem_field, STEM_BOOST_FACTOR);
+    let query_str = Self::filter_query(tokenized_query, lang, platform);
+    let parsed_query = query_parser.parse_query(&query_str)?;
+    let top_docs = Self::top_docs(searcher, &*parsed_query, platform, self.example_limit)?;
+
+    let mut examples = Vec::new();
+    for (score, doc_address) in top_docs {
+      let doc: TantivyDocument = searcher.doc(doc_address)?;
+      let text = |field: Field| {
+        doc
+          .get_first(field)
+          .and_then(|v| v.as_str())
+          .unwrap_or("")
+          .to_string()
+      };
+      // key 为 `lang:name`，命令名中可以有冒号
+      let key = text(self.key_field);
+      let Some((lang, name)) = key.split_once(':') else {
+        continue;
+      };
+      examples.push(SearchResult {
+        name: name.to_string(),
+        description: text(self.example_description_field),
+        category: text(self.category_field),
+        lang: lang.to_string(),
+        platform: text(self.platform_field),
+        score,
+        example: Some(ExampleMatch {
+          index: doc
+            .get_first(self.example_index_field)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1) as usize,
+          code: text(self.example_code_field),
+        }),
+      });
+    }
+    Ok(examples)
+  }
+
+  /// 在查询上加上语言和平台的筛选
+  fn filter_query(query: &str, lang: Option<&str>, platform: &Platform) -> String {
+    // 如果指定了语言，添加语言过滤
+    let mut query_str = if let Some(l) = lang {
+      format!("({}) AND lang:{}", query, l)
+    } else {
+      query.to_string()
+    };
+    // 指定平台时只保留该平台和通用命令
+    if let Platform::Only(_) = platform {
+      let platforms: Vec<String> = platform
+        .platforms()
+        .iter()
+        .map(|p| format!("platform:{}", Self::escape_special_chars(p)))
+        .collect();
+      query_str = format!("({}) AND ({})", query_str, platforms.join(" OR "));
+    }
+    query_str
+  }
+
+  /// 得分最高的文档；优先当前平台时降低其他平台的分数
+  fn top_docs(
+    searcher: &Searcher,
+    query: &dyn Query,
+    platform: &Platform,
+    limit: usize,
+  ) -> Result<Vec<(Score, DocAddress)>, SearchError> {
+    Ok(match platform {
+      Platform::Prefer(_) => {
+        let preferred = platform.platforms();
+        let collector = TopDocs::with_limit(limit)
+          .tweak_score(move |segment: &SegmentReader| prefer_platforms(segment, &preferred));
+        searcher.search(query, &collector)?
+      }
+      _ => searcher.search(query, &TopDocs::with_limit(limit))?,
+    })
+  }
+
   /// 分词并转义 Tantivy 特殊字符
   fn tokenize_and_escape(&self, text: &str) -> String {
     // 先用 jieba 分词
-    let tokens = JIEBA.cut(text, true);
+    let tokens = self.jieba().cut(text, true);
     // 转义每个 token 中的特殊字符
     tokens
       .into_iter()
@@ -279,28 +835,205 @@ impl SearchEngine {
   }
 
   fn tokenize_chinese(&self, text: &str) -> String {
-    let tokens = JIEBA.cut(text, true);
+    let tokens = self.jieba().cut(text, true);
     tokens.join(" ")
   }
 
   pub fn reload(&mut self) -> Result<(), SearchError> {
     self.reader.reload()?;
+    self.invalidate_cache();
     Ok(())
   }
 
+  /// 把所有段合并为一个并删除不再使用的文件，返回合并前的段数
+  /// 增量索引（learn、sync）每次提交都会新增一个段，段越多查询越慢
+  pub fn optimize(&mut self) -> Result<usize, SearchError> {
+    let segments = self.index.searchable_segment_ids()?;
+    if segments.len() > 1 {
+      let mut writer: IndexWriter = self.index.writer(self.buffer_size)?;
+      writer.merge(&segments).wait()?;
+      writer.garbage_collect_files().wait()?;
+      writer.wait_merging_threads()?;
+      self.reader.reload()?;
+    }
+    Ok(segments.len())
+  }
+
   /// 清空索引（用于重置）
   pub fn clear(&mut self) -> Result<(), SearchError> {
-    let mut writer: IndexWriter = self.index.writer(50_000_000)?;
+    let mut writer: IndexWriter = self.index.writer(self.buffer_size)?;
     writer.delete_all_documents()?;
-    writer.commit()?;
+    self.commit(&mut writer)?;
     self.reader.reload()?;
+    self.invalidate_cache();
     Ok(())
   }
 }
 
+/// 索引刚保存到数据库的命令（附上笔记）；旧索引不能按命令替换时从数据库整体重建
+pub fn index_commands(
+  db: &Database,
+  search: &mut SearchEngine,
+  commands: &[Command],
+) -> anyhow::Result<()> {
+  let mut commands = commands.to_vec();
+  db.attach_notes(&mut commands)?;
+  if !search.index_commands(&commands)? {
+    rebuild_index(db, search)?;
+  }
+  Ok(())
+}
+
+/// 打开索引；索引格式与当前版本不同（如升级后分词方式变化）时从数据库重建
+pub fn open_index(
+  db: &Database,
+  path: &Path,
+  config: &SearchConfig,
+) -> anyhow::Result<SearchEngine> {
+  let mut search = SearchEngine::open(path, config)?;
+  if !search.is_current() {
+    tracing::info!("Search index format changed, rebuilding from the database");
+    rebuild_index(db, &mut search)?;
+  }
+  Ok(search)
+}
+
+/// 从数据库重建整个索引（附上笔记），返回索引的命令数
+pub fn rebuild_index(db: &Database, search: &mut SearchEngine) -> anyhow::Result<usize> {
+  let mut commands = db.list_all_commands()?;
+  db.attach_notes(&mut commands)?;
+  search.rebuild(&commands)?;
+  Ok(commands.len())
+}
+
+/// 重新索引同名命令的所有语言（如修改笔记后）
+pub fn reindex_command(db: &Database, search: &mut SearchEngine, name: &str) -> anyhow::Result<()> {
+  let mut commands = Vec::new();
+  for lang in db.command_langs(name)? {
+    commands.extend(db.get_command(name, &lang)?);
+  }
+  index_commands(db, search, &commands)
+}
+
+/// 从数据库和索引中删除命令，lang 为 None 时删除所有语言的同名命令，返回删除的语言
+pub fn delete_command(
+  db: &Database,
+  search: &mut SearchEngine,
+  name: &str,
+  lang: Option<&str>,
+) -> anyhow::Result<Vec<String>> {
+  let langs = match lang {
+    Some(lang) => vec![lang.to_string()],
+    None => db.command_langs(name)?,
+  };
+  let mut deleted = Vec::new();
+  for lang in langs {
+    if db.delete_command(name, &lang)? {
+      deleted.push(lang);
+    }
+  }
+  for lang in &deleted {
+    if !search.delete_command(name, lang)? {
+      // 旧索引不能按命令删除，整体重建
+      rebuild_index(db, search)?;
+      break;
+    }
+  }
+  Ok(deleted)
+}
+
+/// 文本中是否有中日韩文字（汉字、假名、谚文）
+pub fn has_cjk(text: &str) -> bool {
+  text.chars().any(|c| {
+    matches!(c,
+      '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{20000}'..='\u{2fa1f}')
+  })
+}
+
+/// 查询应优先的语言：有中日韩文字且设置了 cjk_lang 时为 cjk_lang，否则为 default_lang
+pub fn query_lang<'a>(query: &str, default_lang: &'a str, cjk_lang: &'a str) -> &'a str {
+  if !cjk_lang.is_empty() && has_cjk(query) {
+    cjk_lang
+  } else {
+    default_lang
+  }
+}
+
+/// 同名命令有多种语言时，把 lang 版本移到其中排名最高的一条的位置，其余顺序不变
+pub fn prefer_lang(results: &mut [SearchResult], lang: &str) {
+  for i in 0..results.len() {
+    if results[i].lang == lang {
+      continue;
+    }
+    // 示例按在命令中的位置对应
+    let index = |r: &SearchResult| r.example.as_ref().map(|e| e.index);
+    let same = |r: &SearchResult| r.name == results[i].name && index(r) == index(&results[i]);
+    if let Some(j) = (i + 1..results.len()).find(|&j| results[j].lang == lang && same(&results[j]))
+    {
+      results[i..=j].rotate_right(1);
+    }
+  }
+}
+
+/// 段内的打分调整：首选平台之外的命令降低分数
+fn prefer_platforms(
+  segment: &SegmentReader,
+  preferred: &[String],
+) -> impl FnMut(DocId, Score) -> Score {
+  let column = segment.fast_fields().str("platform").ok().flatten();
+  let ords: Vec<u64> = column
+    .as_ref()
+    .map(|column| {
+      preferred
+        .iter()
+        .filter_map(|p| column.dictionary().term_ord(p).ok().flatten())
+        .collect()
+    })
+    .unwrap_or_default();
+  move |doc, score| match &column {
+    Some(column) if !column.term_ords(doc).any(|ord| ords.contains(&ord)) => {
+      score * OTHER_PLATFORM_FACTOR
+    }
+    _ => score,
+  }
+}
+
+/// 注册自定义分词器：原字段为简单分词 + 小写，词干字段再去掉英文停用词并提取词干
+fn register_tokenizer(index: &Index) {
+  let tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+    .filter(LowerCaser)
+    .build();
+  index.tokenizers().register("default", tokenizer);
+  let stem = TextAnalyzer::builder(SimpleTokenizer::default())
+    .filter(LowerCaser)
+    .filter(
+      StopWordFilter::new(Language::English).unwrap_or_else(|| StopWordFilter::remove(Vec::new())),
+    )
+    .filter(Stemmer::new(Language::English))
+    .build();
+  index.tokenizers().register(STEM_TOKENIZER, stem);
+}
+
+/// 在默认词典之上加载用户词典
+fn load_user_dict(path: &Path) -> Result<Jieba, SearchError> {
+  let file = std::fs::File::open(path)?;
+  let mut jieba = Jieba::new();
+  jieba
+    .load_dict(&mut std::io::BufReader::new(file))
+    .map_err(|e| SearchError::UserDict(format!("{}: {}", path.display(), e)))?;
+  Ok(jieba)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::storage::COMMAND_SCHEMA;
+  use tantivy::doc;
 
   #[test]
   fn test_escape_special_chars() {
@@ -325,16 +1058,545 @@ mod tests {
   #[test]
   fn test_search_engine_create() {
     let temp_dir = tempfile::tempdir().unwrap();
-    let engine = SearchEngine::open(temp_dir.path());
+    let engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default());
     assert!(engine.is_ok());
   }
 
   #[test]
   fn test_index_and_search() {
     let temp_dir = tempfile::tempdir().unwrap();
-    let mut engine = SearchEngine::open(temp_dir.path()).unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+
+    let commands = sample_commands();
+    engine.index_commands(&commands).unwrap();
+
+    // 测试搜索
+    let results = engine.search("docker", None, &Platform::All, 10).unwrap();
+    assert_eq!(results.results.len(), 1);
+    assert_eq!(results.results[0].name, "docker");
+
+    // 测试特殊字符
+    let results = engine.search("ps -a", None, &Platform::All, 10).unwrap();
+    assert!(!results.results.is_empty());
+  }
+
+  #[test]
+  fn test_result_cache_invalidation() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let commands = sample_commands();
+    engine.index_commands(&commands[..1]).unwrap();
+
+    let first = engine
+      .search(&commands[1].name, None, &Platform::All, 10)
+      .unwrap();
+    assert!(first.results.is_empty());
+    // 命中缓存
+    assert!(engine
+      .search(&commands[1].name, None, &Platform::All, 10)
+      .unwrap()
+      .results
+      .is_empty());
+
+    // 提交后缓存失效
+    engine.index_single_command(&commands[1]).unwrap();
+    let results = engine
+      .search(&commands[1].name, None, &Platform::All, 10)
+      .unwrap();
+    assert_eq!(results.results[0].name, commands[1].name);
+
+    assert_eq!(engine.optimize().unwrap(), 2);
+    assert_eq!(engine.optimize().unwrap(), 1);
+    let results = engine
+      .search(&commands[1].name, None, &Platform::All, 10)
+      .unwrap();
+    assert_eq!(results.results[0].name, commands[1].name);
+
+    engine.clear().unwrap();
+    assert!(engine
+      .search(&commands[1].name, None, &Platform::All, 10)
+      .unwrap()
+      .results
+      .is_empty());
+  }
+
+  #[test]
+  fn test_incremental_index() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let mut commands = sample_commands();
+    assert!(engine.index_commands(&commands[..1]).unwrap());
+    assert!(engine.index_commands(&commands[1..]).unwrap());
+    // 之前索引的命令仍在
+    let total = |engine: &SearchEngine, query: &str| {
+      engine
+        .search(query, None, &Platform::All, 10)
+        .unwrap()
+        .total
+    };
+    assert_eq!(total(&engine, "docker"), 1);
+    assert_eq!(total(&engine, "tar"), 1);
+
+    // 同一语言的同名命令被替换
+    commands[0].description = "Manage containerized whalesong".to_string();
+    engine.index_commands(&commands[..1]).unwrap();
+    assert_eq!(total(&engine, "docker"), 1);
+    assert_eq!(total(&engine, "whalesong"), 1);
+
+    // 从数据库重建时去掉数据库中没有的命令
+    let db_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&db_dir.path().join("test.redb")).unwrap();
+    db.save_commands(&commands[1..]).unwrap();
+    engine.rebuild(&db.list_all_commands().unwrap()).unwrap();
+    assert_eq!(total(&engine, "docker"), 0);
+    assert_eq!(total(&engine, "tar"), 1);
+  }
+
+  #[test]
+  fn test_search_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    engine.index_commands(&sample_commands()).unwrap();
+    assert!(engine
+      .search("dockr", None, &Platform::All, 10)
+      .unwrap()
+      .results
+      .is_empty());
+
+    // 模糊匹配
+    let config = SearchConfig {
+      fuzzy_distance: 1,
+      ..Default::default()
+    };
+    let engine = SearchEngine::open(temp_dir.path(), &config).unwrap();
+    let results = engine.search("dockr", None, &Platform::All, 10).unwrap();
+    assert_eq!(results.results[0].name, "docker");
+
+    // 按需模糊搜索：相邻字母互换算一次编辑
+    let engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert_eq!(
+      engine
+        .search("dokcer", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      0
+    );
+    let results = engine
+      .search_fuzzy("dokcer", None, &Platform::All, 10)
+      .unwrap();
+    assert_eq!(results.results[0].name, "docker");
+    assert!(engine
+      .search_fuzzy("dkocre", None, &Platform::All, 10)
+      .unwrap()
+      .results
+      .is_empty());
+    let config = SearchConfig {
+      fuzzy_search_distance: 2,
+      ..Default::default()
+    };
+    let engine = SearchEngine::open(temp_dir.path(), &config).unwrap();
+    let results = engine
+      .search_fuzzy("dkocer", None, &Platform::All, 10)
+      .unwrap();
+    assert_eq!(results.results[0].name, "docker");
+
+    // 用户词典
+    let dict = temp_dir.path().join("user.dict");
+    std::fs::write(&dict, "容器编排 1000 n\n").unwrap();
+    let config = SearchConfig {
+      user_dict: Some(dict),
+      ..Default::default()
+    };
+    let engine = SearchEngine::open(temp_dir.path(), &config).unwrap();
+    assert_eq!(engine.tokenize_chinese("容器编排"), "容器编排");
+  }
+
+  #[test]
+  fn test_ranking() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let mut commands = sample_commands();
+    // 内容多次提到 tar 的命令
+    commands.push(Command {
+      name: "gzip".to_string(),
+      description: "Compress tar archives and other files".to_string(),
+      content: "gzip archive.tar\ntar -czf archive.tar.gz dir\ngunzip archive.tar.gz".to_string(),
+      ..commands[1].clone()
+    });
+    engine.index_commands(&commands).unwrap();
+
+    let names = |engine: &SearchEngine| -> Vec<String> {
+      engine
+        .search("tar", None, &Platform::All, 10)
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|r| r.name)
+        .collect()
+    };
+    assert_eq!(names(&engine), ["tar", "gzip"]);
+
+    // 只按内容打分时 gzip 排在前面，名称完全相同的加分仍让 tar 排第一
+    let flat = SearchConfig {
+      name_boost: 0.01,
+      description_boost: 1.0,
+      content_boost: 1.0,
+      exact_name_boost: 1.0,
+      ..Default::default()
+    };
+    let engine = SearchEngine::open(temp_dir.path(), &flat).unwrap();
+    assert_eq!(names(&engine), ["gzip", "tar"]);
+    let engine = SearchEngine::open(
+      temp_dir.path(),
+      &SearchConfig {
+        exact_name_boost: 100.0,
+        ..flat
+      },
+    )
+    .unwrap();
+    assert_eq!(names(&engine), ["tar", "gzip"]);
+  }
+
+  #[test]
+  fn test_replace_and_delete() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let commands = sample_commands();
+    engine.index_commands(&commands).unwrap();
+
+    // 重新学习替换而不是重复
+    engine.index_single_command(&commands[0]).unwrap();
+    assert_eq!(
+      engine
+        .search("docker", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      1
+    );
+
+    assert!(engine.delete_command("docker", "en").unwrap());
+    assert_eq!(
+      engine
+        .search("docker", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      0
+    );
+    assert_eq!(
+      engine
+        .search("tar", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      1
+    );
+    // 其他语言的同名命令不受影响
+    assert!(engine.delete_command("tar", "zh").unwrap());
+    assert_eq!(
+      engine
+        .search("tar", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      1
+    );
+  }
+
+  #[test]
+  fn test_suggestions() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    engine.index_commands(&sample_commands()).unwrap();
+
+    let response = engine.search("dckr", None, &Platform::All, 10).unwrap();
+    assert!(response.results.is_empty());
+    assert_eq!(response.suggestions, ["docker"]);
+    assert!(engine.suggest("dckr", Some("zh")).unwrap().is_empty());
+    // 有结果时不给建议
+    let response = engine.search("docker", None, &Platform::All, 10).unwrap();
+    assert!(response.suggestions.is_empty());
+
+    // 已删除的命令不再建议
+    engine.delete_command("docker", "en").unwrap();
+    assert!(engine.suggest("dckr", None).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_stemming() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let mut commands = sample_commands();
+    commands[1].description = "Copy file to an archive".to_string();
+    commands.push(Command {
+      name: "cp".to_string(),
+      description: "Copying files".to_string(),
+      ..commands[1].clone()
+    });
+    engine.index_commands(&commands).unwrap();
+
+    let names = |query: &str| -> Vec<String> {
+      engine
+        .search(query, None, &Platform::All, 10)
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|r| r.name)
+        .collect()
+    };
+    // 词形不同也能找到，原文完全相同的排在前面
+    assert_eq!(names("copying files"), ["cp", "tar"]);
+    assert_eq!(names("copy file"), ["tar", "cp"]);
+    // 停用词不会匹配所有命令
+    assert_eq!(names("the"), Vec::<String>::new());
+  }
+
+  #[test]
+  fn test_example_search() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let mut commands = sample_commands();
+    commands[1].examples = [
+      ("Create an archive", "tar cf {{target.tar}} {{file}}"),
+      ("Extract a compressed archive", "tar xf {{source.tar.gz}}"),
+    ]
+    .map(|(description, code)| crate::storage::Example {
+      description: description.to_string(),
+      code: code.to_string(),
+      source: None,
+    })
+    .to_vec();
+    engine.index_commands(&commands).unwrap();
+
+    // 示例单独列出，不会作为命令重复出现
+    let response = engine
+      .search("extract tar.gz", None, &Platform::All, 10)
+      .unwrap();
+    let names: Vec<&str> = response.results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["tar"]);
+    let best = &response.examples[0];
+    assert_eq!(
+      (best.name.as_str(), best.description.as_str()),
+      ("tar", "Extract a compressed archive")
+    );
+    assert_eq!(
+      best.example,
+      Some(ExampleMatch {
+        index: 2,
+        code: "tar xf {{source.tar.gz}}".to_string(),
+      })
+    );
+
+    // 替换命令时一并替换其示例
+    commands[1].examples.clear();
+    engine.index_single_command(&commands[1]).unwrap();
+    assert!(engine
+      .search("extract", None, &Platform::All, 10)
+      .unwrap()
+      .examples
+      .is_empty());
+  }
+
+  #[test]
+  fn test_query_lang() {
+    assert!(has_cjk("解压 tar"));
+    assert!(has_cjk("ファイル"));
+    assert!(!has_cjk("tar -xzf café"));
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let mut commands = sample_commands();
+    commands[0].content = "docker ps -a 容器".to_string();
+    let mut zh = commands[0].clone();
+    zh.lang = "zh".to_string();
+    zh.description = "管理 Docker 容器".to_string();
+    commands.push(zh);
+    engine.index_commands(&commands).unwrap();
+
+    let langs = |response: &SearchResponse| -> Vec<String> {
+      response.results.iter().map(|r| r.lang.clone()).collect()
+    };
+    let response = engine.search("docker", None, &Platform::All, 10).unwrap();
+    assert_eq!(response.lang, "en");
+    assert_eq!(langs(&response), ["en", "zh"]);
+    let response = engine
+      .search("docker 容器", None, &Platform::All, 10)
+      .unwrap();
+    assert_eq!(response.lang, "zh");
+    assert_eq!(langs(&response), ["zh", "en"]);
+    // 指定了语言时不再检测
+    let response = engine
+      .search("容器", Some("en"), &Platform::All, 10)
+      .unwrap();
+    assert_eq!(response.lang, "en");
+
+    let config = SearchConfig {
+      cjk_lang: String::new(),
+      ..SearchConfig::default()
+    };
+    assert_eq!(config.query_lang("容器"), "en");
+    assert_eq!(SearchConfig::default().query_lang("容器"), "zh");
+  }
+
+  #[test]
+  fn test_index_version() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&db_dir.path().join("test.redb")).unwrap();
+    db.save_commands(&sample_commands()).unwrap();
 
-    let commands = vec![
+    // 上一版本的索引（有 key 和 platform，没有词干字段和版本记录）
+    let mut builder = Schema::builder();
+    for name in ["name", "description", "content", "category", "lang"] {
+      builder.add_text_field(name, TEXT | STORED);
+    }
+    builder.add_text_field("key", STRING | STORED);
+    builder.add_text_field("platform", STRING | STORED | FAST);
+    Index::create_in_dir(temp_dir.path(), builder.build()).unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert!(!engine.is_current());
+    assert!(!engine.index_commands(&sample_commands()).unwrap());
+    drop(engine);
+
+    // 打开时从数据库重建，版本在之后的提交中保留
+    let mut engine = open_index(&db, temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert!(engine.is_current());
+    assert!(engine.delete_command("docker", "en").unwrap());
+    drop(engine);
+    let engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert!(engine.is_current());
+    assert_eq!(
+      engine
+        .search("archive", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      1
+    );
+  }
+
+  #[test]
+  fn test_platform() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    let mut commands = sample_commands();
+    for (name, platform) in [("apt", "linux"), ("brew", "osx"), ("pkg", "common")] {
+      commands.push(Command {
+        name: name.to_string(),
+        description: "Package manager".to_string(),
+        platform: platform.to_string(),
+        ..commands[0].clone()
+      });
+    }
+    engine.index_commands(&commands).unwrap();
+
+    let names = |platform: &str| -> Vec<String> {
+      let mut names: Vec<String> = engine
+        .search("package", None, &Platform::parse(platform), 10)
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|r| r.name)
+        .collect();
+      names.sort();
+      names
+    };
+    assert_eq!(names("all"), ["apt", "brew", "pkg"]);
+    assert_eq!(names("linux"), ["apt", "pkg"]);
+    assert_eq!(names("OSX"), ["brew", "pkg"]);
+    assert_eq!(names("common"), ["pkg"]);
+    assert_eq!(names("windows"), ["pkg"]);
+
+    // 优先当前平台：其他平台的命令排在后面但不去掉
+    let results = engine
+      .search("package", None, &Platform::Prefer("osx".to_string()), 10)
+      .unwrap()
+      .results;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2].name, "apt");
+    assert_eq!(results[2].platform, "linux");
+    assert_eq!(
+      Platform::parse("auto"),
+      Platform::Prefer(crate::learn::get_platform())
+    );
+    assert!(Platform::Only("linux".to_string()).matches("common"));
+    assert!(!Platform::Only("linux".to_string()).matches("osx"));
+    assert!(Platform::Only("linux".to_string()).matches(""));
+  }
+
+  #[test]
+  fn test_old_index_without_platform() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut builder = Schema::builder();
+    for name in ["name", "description", "content", "category", "lang"] {
+      builder.add_text_field(name, TEXT | STORED);
+    }
+    builder.add_text_field("key", STRING | STORED);
+    let schema = builder.build();
+    // 上一版本写入的文档
+    let index = Index::create_in_dir(temp_dir.path(), schema.clone()).unwrap();
+    let mut writer: IndexWriter = index.writer(MIN_INDEX_BUFFER_SIZE).unwrap();
+    let field = |name: &str| schema.get_field(name).unwrap();
+    writer
+      .add_document(doc!(
+        field("name") => "docker",
+        field("description") => "Manage containers",
+        field("category") => "common",
+        field("lang") => "en",
+        field("key") => "en:docker",
+      ))
+      .unwrap();
+    writer.commit().unwrap();
+    drop(index);
+
+    // 旧索引忽略平台，也不接受写入
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert!(!engine.index_single_command(&sample_commands()[0]).unwrap());
+    let only = Platform::Only("osx".to_string());
+    assert_eq!(engine.search("docker", None, &only, 10).unwrap().total, 1);
+
+    // 不能增量索引，全量索引时重建
+    assert!(!engine.index_commands(&sample_commands()).unwrap());
+    engine.rebuild(&sample_commands()).unwrap();
+    assert_eq!(engine.search("docker", None, &only, 10).unwrap().total, 1);
+    let mut commands = sample_commands();
+    commands[0].platform = "linux".to_string();
+    engine.index_single_command(&commands[0]).unwrap();
+    assert_eq!(engine.search("docker", None, &only, 10).unwrap().total, 0);
+  }
+
+  #[test]
+  fn test_old_index_without_key() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut builder = Schema::builder();
+    for name in ["name", "description", "content", "category", "lang"] {
+      builder.add_text_field(name, TEXT | STORED);
+    }
+    Index::create_in_dir(temp_dir.path(), builder.build()).unwrap();
+
+    let mut engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert!(!engine.delete_command("docker", "en").unwrap());
+    // 没有 key 字段的索引不写入文档（字段编号越界会使索引线程崩溃）
+    assert!(!engine.index_single_command(&sample_commands()[0]).unwrap());
+    assert_eq!(engine.num_docs(), 0);
+    engine.rebuild(&sample_commands()).unwrap();
+    assert!(engine.delete_command("docker", "en").unwrap());
+    assert_eq!(
+      engine
+        .search("docker", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      0
+    );
+
+    let engine = SearchEngine::open(temp_dir.path(), &SearchConfig::default()).unwrap();
+    assert_eq!(
+      engine
+        .search("tar", None, &Platform::All, 10)
+        .unwrap()
+        .total,
+      1
+    );
+  }
+
+  fn sample_commands() -> Vec<Command> {
+    vec![
       Command {
         name: "docker".to_string(),
         description: "Manage Docker containers".to_string(),
@@ -343,6 +1605,11 @@ mod tests {
         lang: "en".to_string(),
         examples: vec![],
         content: "docker ps -a".to_string(),
+        sections: vec![],
+        options: vec![],
+        source_info: None,
+        notes: None,
+        schema: COMMAND_SCHEMA,
       },
       Command {
         name: "tar".to_string(),
@@ -352,18 +1619,12 @@ mod tests {
         lang: "en".to_string(),
         examples: vec![],
         content: "tar -xvf file.tar".to_string(),
+        sections: vec![],
+        options: vec![],
+        source_info: None,
+        notes: None,
+        schema: COMMAND_SCHEMA,
       },
-    ];
-
-    engine.index_commands(&commands).unwrap();
-
-    // 测试搜索
-    let results = engine.search("docker", None, 10).unwrap();
-    assert_eq!(results.results.len(), 1);
-    assert_eq!(results.results[0].name, "docker");
-
-    // 测试特殊字符
-    let results = engine.search("ps -a", None, 10).unwrap();
-    assert!(!results.results.is_empty());
+    ]
   }
 }
@@ -0,0 +1,158 @@
+//! 拼写建议（"did you mean"）
+//!
+//! 查询没有结果时，在索引的 key 字段（`lang:name`）中找名称与查询接近的命令：编辑距离（相邻字母互换算一次）
+//! 不超过按查询长度放宽的上限，或者字符二元组的相似度足够高（查询只是名称的一部分，如 compose -> docker-compose）。
+//! 词典中可能还有已删除文档的词，返回前逐个确认命令仍在索引中。
+
+use std::collections::HashSet;
+
+use tantivy::collector::Count;
+use tantivy::query::TermQuery;
+use tantivy::schema::IndexRecordOption;
+use tantivy::Term;
+
+use super::{SearchEngine, SearchError};
+
+/// 最多返回的建议数
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// 二元组相似度（Dice 系数）达到该值时，编辑距离超过上限也作为建议
+const MIN_SIMILARITY: f32 = 0.6;
+
+/// 候选命令：（编辑距离，相似度，名称，索引中的 key）
+type Candidate = (usize, f32, String, String);
+
+impl SearchEngine {
+  /// 名称与 query 接近的命令，最接近的在前；lang 限定命令的语言
+  pub fn suggest(&self, query: &str, lang: Option<&str>) -> Result<Vec<String>, SearchError> {
+    let target = query
+      .split_whitespace()
+      .collect::<Vec<_>>()
+      .join("-")
+      .to_lowercase();
+    // 旧索引没有 key 字段
+    if target.is_empty() || !self.has_key {
+      return Ok(Vec::new());
+    }
+
+    let searcher = self.reader.searcher();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for segment in searcher.segment_readers() {
+      let inverted = segment.inverted_index(self.key_field)?;
+      let mut terms = inverted.terms().stream()?;
+      while terms.advance() {
+        let Ok(key) = std::str::from_utf8(terms.key()) else {
+          continue;
+        };
+        let Some((key_lang, name)) = key.split_once(':') else {
+          continue;
+        };
+        if lang.is_some_and(|lang| lang != key_lang) {
+          continue;
+        }
+        if let Some((distance, similarity)) = closeness(&target, &name.to_lowercase()) {
+          candidates.push((distance, similarity, name.to_string(), key.to_string()));
+        }
+      }
+    }
+    candidates.sort_by(|a, b| {
+      a.0
+        .cmp(&b.0)
+        .then(b.1.total_cmp(&a.1))
+        .then_with(|| a.2.cmp(&b.2))
+    });
+
+    let mut seen = HashSet::new();
+    let mut suggestions = Vec::new();
+    for (_, _, name, key) in candidates {
+      if suggestions.len() == MAX_SUGGESTIONS {
+        break;
+      }
+      if seen.contains(&name) {
+        continue;
+      }
+      let term = Term::from_field_text(self.key_field, &key);
+      let query = TermQuery::new(term, IndexRecordOption::Basic);
+      if searcher.search(&query, &Count)? > 0 {
+        seen.insert(name.clone());
+        suggestions.push(name);
+      }
+    }
+    Ok(suggestions)
+  }
+}
+
+/// 名称足够接近查询时返回（编辑距离，相似度）；与查询相同的名称不算建议
+fn closeness(query: &str, name: &str) -> Option<(usize, f32)> {
+  if query == name {
+    return None;
+  }
+  let max_distance = match query.chars().count() {
+    0..=3 => 1,
+    4..=7 => 2,
+    _ => 3,
+  };
+  let distance = edit_distance(query, name);
+  let similarity = bigram_similarity(query, name);
+  (distance <= max_distance || similarity >= MIN_SIMILARITY).then_some((distance, similarity))
+}
+
+/// 编辑距离，相邻字符互换算一次编辑（与 tantivy 模糊匹配的 transposition 一致）
+fn edit_distance(a: &str, b: &str) -> usize {
+  let a: Vec<char> = a.chars().collect();
+  let b: Vec<char> = b.chars().collect();
+  // 保留最近两行
+  let mut before: Vec<usize> = Vec::new();
+  let mut prev: Vec<usize> = (0..=b.len()).collect();
+  for i in 1..=a.len() {
+    let mut cur = vec![i; b.len() + 1];
+    for j in 1..=b.len() {
+      let cost = usize::from(a[i - 1] != b[j - 1]);
+      cur[j] = (prev[j - 1] + cost).min(prev[j] + 1).min(cur[j - 1] + 1);
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        cur[j] = cur[j].min(before[j - 2] + 1);
+      }
+    }
+    before = std::mem::replace(&mut prev, cur);
+  }
+  prev[b.len()]
+}
+
+/// 字符二元组的 Dice 系数（0-1）
+fn bigram_similarity(a: &str, b: &str) -> f32 {
+  let bigrams = |s: &str| -> Vec<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+  };
+  let a = bigrams(a);
+  let mut b = bigrams(b);
+  let total = a.len() + b.len();
+  if total == 0 {
+    return 0.0;
+  }
+  let mut shared = 0;
+  for pair in &a {
+    if let Some(pos) = b.iter().position(|other| other == pair) {
+      b.swap_remove(pos);
+      shared += 1;
+    }
+  }
+  (2 * shared) as f32 / total as f32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_closeness() {
+    assert_eq!(edit_distance("dokcer", "docker"), 1);
+    assert_eq!(edit_distance("dckr", "docker"), 2);
+    assert_eq!(edit_distance("", "tar"), 3);
+    assert!(closeness("dokcer", "docker").is_some());
+    assert!(closeness("tar", "tar").is_none());
+    assert!(closeness("tar", "docker").is_none());
+    // 查询是名称的一部分
+    assert!(closeness("compose", "docker-compose").is_some());
+  }
+}
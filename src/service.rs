@@ -0,0 +1,324 @@
+//! Windows 服务（rtfm service install/start/stop/uninstall）
+//!
+//! install 在服务控制管理器（SCM）中注册 rtfm 服务，默认随系统自动启动。服务以 LocalSystem 运行，
+//! 看不到安装用户的目录，所以启动参数（隐藏的 `rtfm service run`）带上安装时的数据目录和配置文件，
+//! run 通过 RTFM_DATA_DIR / RTFM_CONFIG 使用它们。SCM 发出停止或关机请求时 HTTP 服务正常关闭。
+//! 其他平台上这些命令只给出提示（使用 rtfm serve --install-service 安装 systemd unit 或 launchd agent）。
+
+use std::path::PathBuf;
+
+/// 服务名
+pub const SERVICE_NAME: &str = "rtfm";
+
+/// install 的选项
+#[derive(Debug, Clone)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct InstallOptions {
+  pub exe: PathBuf,
+  pub data_dir: PathBuf,
+  /// 安装时使用的配置文件（绝对路径）
+  pub config: Option<PathBuf>,
+  pub bind: String,
+  pub port: u16,
+  pub read_only: bool,
+  /// 手动启动，否则随系统自动启动
+  pub manual: bool,
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+impl InstallOptions {
+  /// SCM 启动服务时传给 rtfm 的参数
+  pub fn launch_arguments(&self) -> Vec<String> {
+    let mut args = vec![
+      "service".to_string(),
+      "run".to_string(),
+      "--bind".to_string(),
+      self.bind.clone(),
+      "--port".to_string(),
+      self.port.to_string(),
+      "--data-dir".to_string(),
+      self.data_dir.display().to_string(),
+    ];
+    if let Some(config) = &self.config {
+      args.push("--config".to_string());
+      args.push(config.display().to_string());
+    }
+    if self.read_only {
+      args.push("--read-only".to_string());
+    }
+    args
+  }
+}
+
+/// `rtfm service run` 的参数
+#[derive(Debug, Clone)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct RunOptions {
+  pub bind: String,
+  pub port: u16,
+  pub read_only: bool,
+}
+
+#[cfg(windows)]
+pub use windows::{install, run, start, stop, stop_requested, uninstall};
+
+#[cfg(not(windows))]
+pub use unsupported::{install, run, start, stop, stop_requested, uninstall};
+
+#[cfg(windows)]
+mod windows {
+  use std::ffi::{OsStr, OsString};
+  use std::sync::OnceLock;
+  use std::time::Duration;
+
+  use tokio::sync::Notify;
+  use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+  };
+  use windows_service::service_control_handler::{
+    self, ServiceControlHandlerResult, ServiceStatusHandle,
+  };
+  use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+  use windows_service::{define_windows_service, service_dispatcher};
+
+  use super::{InstallOptions, RunOptions, SERVICE_NAME};
+  use crate::config::AppConfig;
+
+  /// ERROR_ACCESS_DENIED
+  const ACCESS_DENIED: i32 = 5;
+
+  /// SCM 请求停止
+  static STOP: Notify = Notify::const_new();
+
+  /// run 传给服务线程的参数
+  static RUN_OPTIONS: OnceLock<RunOptions> = OnceLock::new();
+
+  define_windows_service!(ffi_service_main, service_main);
+
+  /// 权限不足时提示使用管理员权限
+  fn context(e: windows_service::Error, action: &str) -> anyhow::Error {
+    match &e {
+      windows_service::Error::Winapi(io) if io.raw_os_error() == Some(ACCESS_DENIED) => {
+        anyhow::anyhow!(
+          "Failed to {} the service: access denied (run from an Administrator prompt)",
+          action
+        )
+      }
+      _ => anyhow::anyhow!("Failed to {} the service: {}", action, e),
+    }
+  }
+
+  fn manager(access: ServiceManagerAccess) -> anyhow::Result<ServiceManager> {
+    ServiceManager::local_computer(None::<&str>, access).map_err(|e| context(e, "connect to"))
+  }
+
+  pub fn install(options: &InstallOptions) -> anyhow::Result<()> {
+    let manager = manager(ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)?;
+    let info = ServiceInfo {
+      name: OsString::from(SERVICE_NAME),
+      display_name: OsString::from("RTFM cheatsheet server"),
+      service_type: ServiceType::OWN_PROCESS,
+      start_type: if options.manual {
+        ServiceStartType::OnDemand
+      } else {
+        ServiceStartType::AutoStart
+      },
+      error_control: ServiceErrorControl::Normal,
+      executable_path: options.exe.clone(),
+      launch_arguments: options
+        .launch_arguments()
+        .into_iter()
+        .map(OsString::from)
+        .collect(),
+      dependencies: Vec::new(),
+      account_name: None,
+      account_password: None,
+    };
+    let service = manager
+      .create_service(&info, ServiceAccess::CHANGE_CONFIG)
+      .map_err(|e| context(e, "install"))?;
+    service
+      .set_description("RTFM HTTP API for offline command cheatsheets")
+      .map_err(|e| context(e, "configure"))?;
+    Ok(())
+  }
+
+  pub fn start() -> anyhow::Result<()> {
+    let manager = manager(ServiceManagerAccess::CONNECT)?;
+    let service = manager
+      .open_service(SERVICE_NAME, ServiceAccess::START)
+      .map_err(|e| context(e, "open"))?;
+    service
+      .start(&[] as &[&OsStr])
+      .map_err(|e| context(e, "start"))
+  }
+
+  pub fn stop() -> anyhow::Result<()> {
+    let manager = manager(ServiceManagerAccess::CONNECT)?;
+    let service = manager
+      .open_service(SERVICE_NAME, ServiceAccess::STOP)
+      .map_err(|e| context(e, "open"))?;
+    service.stop().map_err(|e| context(e, "stop"))?;
+    Ok(())
+  }
+
+  /// 停止并删除服务；服务仍有打开的句柄时会在之后被删除
+  pub fn uninstall() -> anyhow::Result<()> {
+    let manager = manager(ServiceManagerAccess::CONNECT)?;
+    let service = manager
+      .open_service(
+        SERVICE_NAME,
+        ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+      )
+      .map_err(|e| context(e, "open"))?;
+    let status = service.query_status().map_err(|e| context(e, "query"))?;
+    if status.current_state != ServiceState::Stopped {
+      service.stop().map_err(|e| context(e, "stop"))?;
+    }
+    service.delete().map_err(|e| context(e, "uninstall"))
+  }
+
+  /// 由 SCM 启动时调用：交给 service dispatcher，服务停止后返回
+  pub fn run(options: RunOptions) -> anyhow::Result<()> {
+    let _ = RUN_OPTIONS.set(options);
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main).map_err(|e| {
+      anyhow::anyhow!(
+        "Failed to start the service dispatcher (use 'rtfm serve' outside the service manager): {}",
+        e
+      )
+    })
+  }
+
+  /// 等待 SCM 的停止请求
+  pub async fn stop_requested() {
+    STOP.notified().await;
+  }
+
+  fn set_state(
+    handle: &ServiceStatusHandle,
+    state: ServiceState,
+    exit_code: u32,
+  ) -> windows_service::Result<()> {
+    handle.set_service_status(ServiceStatus {
+      service_type: ServiceType::OWN_PROCESS,
+      current_state: state,
+      controls_accepted: if state == ServiceState::Running {
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+      } else {
+        ServiceControlAccept::empty()
+      },
+      exit_code: ServiceExitCode::Win32(exit_code),
+      checkpoint: 0,
+      wait_hint: Duration::from_secs(10),
+      process_id: None,
+    })
+  }
+
+  fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = run_service() {
+      tracing::error!("Service failed: {}", e);
+    }
+  }
+
+  fn run_service() -> anyhow::Result<()> {
+    let handle = service_control_handler::register(SERVICE_NAME, |event| match event {
+      ServiceControl::Stop | ServiceControl::Shutdown => {
+        STOP.notify_one();
+        ServiceControlHandlerResult::NoError
+      }
+      ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+      _ => ServiceControlHandlerResult::NotImplemented,
+    })?;
+    set_state(&handle, ServiceState::Running, 0)?;
+
+    let options = RUN_OPTIONS
+      .get()
+      .cloned()
+      .ok_or_else(|| anyhow::anyhow!("Service started without options"))?;
+    let result = tokio::runtime::Runtime::new()?.block_on(crate::run_server(
+      &options.bind,
+      options.port,
+      false,
+      options.read_only,
+      false,
+      AppConfig::load_default(),
+    ));
+
+    set_state(
+      &handle,
+      ServiceState::Stopped,
+      if result.is_ok() { 0 } else { 1 },
+    )?;
+    result
+  }
+}
+
+#[cfg(not(windows))]
+mod unsupported {
+  use super::{InstallOptions, RunOptions};
+
+  fn unsupported() -> anyhow::Result<()> {
+    anyhow::bail!(
+      "Windows services are only available on Windows; elsewhere use 'rtfm serve --install-service' (systemd or launchd)"
+    )
+  }
+
+  pub fn install(_options: &InstallOptions) -> anyhow::Result<()> {
+    unsupported()
+  }
+
+  pub fn start() -> anyhow::Result<()> {
+    unsupported()
+  }
+
+  pub fn stop() -> anyhow::Result<()> {
+    unsupported()
+  }
+
+  pub fn uninstall() -> anyhow::Result<()> {
+    unsupported()
+  }
+
+  pub fn run(_options: RunOptions) -> anyhow::Result<()> {
+    unsupported()
+  }
+
+  /// 没有服务管理器，永不返回
+  pub async fn stop_requested() {
+    std::future::pending::<()>().await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_launch_arguments() {
+    let mut options = InstallOptions {
+      exe: PathBuf::from(r"C:\Tools\rtfm.exe"),
+      data_dir: PathBuf::from(r"C:\Users\me\AppData\Local\rtfm"),
+      config: None,
+      bind: "127.0.0.1".to_string(),
+      port: 3030,
+      read_only: false,
+      manual: false,
+    };
+    assert_eq!(
+      options.launch_arguments().join(" "),
+      r"service run --bind 127.0.0.1 --port 3030 --data-dir C:\Users\me\AppData\Local\rtfm"
+    );
+
+    options.config = Some(PathBuf::from(
+      r"C:\Users\me\AppData\Roaming\rtfm\config.toml",
+    ));
+    options.read_only = true;
+    let args = options.launch_arguments();
+    assert_eq!(
+      args[8..10],
+      ["--config", r"C:\Users\me\AppData\Roaming\rtfm\config.toml"]
+    );
+    assert_eq!(args.last().unwrap(), "--read-only");
+  }
+}
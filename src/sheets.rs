@@ -0,0 +1,537 @@
+//! navi 与 cheat 的速查表格式（rtfm import --format navi|cheat、rtfm export --format navi）
+//!
+//! - navi：`.cheat` 文件，`% 标签` 开始一段，`# 描述` 后跟命令（可跨多行），`$ 变量: 命令` 为变量提供候选值，
+//!   `;` 开头为注释。一个文件可包含多个程序的命令，按命令的第一个词分组
+//! - cheat：每个命令一个无扩展名的文本文件，可选的 YAML 头（`---` 之间）声明 tags；`#` 注释说明其后的命令
+//!
+//! 两种格式的变量 `<name>` 都转换为 tldr 占位符 `{{name}}`。导入的命令作为本机命令（lang 为 local）保存，
+//! 与同名 tldr 页面合并显示；示例的来源标记为 navi 或 cheat，标签写入描述。
+//!
+//! 导出时每个命令名一个 `.cheat` 文件，每条命令一段（标签为命令名、平台和语言），
+//! tldr 占位符 `{{path/to/file}}` 转换为 navi 变量 `<path_to_file>`，选项占位符 `{{[-f|--force]}}` 写成长选项。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::storage::{Command, Database, Example, SourceInfo, COMMAND_SCHEMA};
+
+/// 一段描述与其后的命令
+struct Snippet {
+  description: String,
+  code: String,
+}
+
+/// 解析 navi `.cheat` 文件，每个程序一条命令
+pub fn parse_navi(content: &str, path: &Path) -> Vec<Command> {
+  let mut groups: BTreeMap<String, (Vec<Example>, Vec<String>)> = BTreeMap::new();
+  let mut tags: Vec<String> = Vec::new();
+  let mut description = String::new();
+  let mut code: Vec<String> = Vec::new();
+
+  let mut flush = |description: &mut String, code: &mut Vec<String>, tags: &[String]| {
+    if code.is_empty() {
+      return;
+    }
+    let snippet = Snippet {
+      description: std::mem::take(description),
+      code: code.join("\n"),
+    };
+    code.clear();
+    if let Some(name) = program_name(&snippet.code) {
+      let (examples, group_tags) = groups.entry(name).or_default();
+      examples.push(example(snippet, "navi"));
+      for tag in tags {
+        if !group_tags.contains(tag) {
+          group_tags.push(tag.clone());
+        }
+      }
+    }
+  };
+
+  for line in content.lines() {
+    let trimmed = line.trim();
+    if let Some(rest) = trimmed.strip_prefix('%') {
+      flush(&mut description, &mut code, &tags);
+      tags = split_tags(rest);
+    } else if let Some(rest) = trimmed.strip_prefix('#') {
+      flush(&mut description, &mut code, &tags);
+      append_line(&mut description, rest.trim());
+    } else if trimmed.is_empty() {
+      flush(&mut description, &mut code, &tags);
+    } else if trimmed.starts_with('$') || trimmed.starts_with(';') || trimmed.starts_with('@') {
+      // 变量候选值、注释和扩展其他段的声明不是命令
+      continue;
+    } else {
+      code.push(line.trim_end().to_string());
+    }
+  }
+  flush(&mut description, &mut code, &tags);
+
+  groups
+    .into_iter()
+    .map(|(name, (examples, tags))| command(name, examples, &tags, "navi", path, content))
+    .collect()
+}
+
+/// 解析 cheat 的速查表，name 为文件名
+pub fn parse_cheat(content: &str, name: &str, path: &Path) -> Option<Command> {
+  let (tags, examples) = parse_cheat_sheet(content);
+  if examples.is_empty() {
+    return None;
+  }
+  Some(command(
+    name.to_string(),
+    examples,
+    &tags,
+    "cheat",
+    path,
+    content,
+  ))
+}
+
+/// 解析 cheat 速查表的标签和示例（rtfm import 与 rtfm update --source cheat 共用）
+pub fn parse_cheat_sheet(content: &str) -> (Vec<String>, Vec<Example>) {
+  let mut tags = Vec::new();
+  let mut body = content;
+  if let Some(rest) = content.strip_prefix("---") {
+    if let Some((header, rest)) = rest.split_once("\n---") {
+      for line in header.lines() {
+        if let Some(value) = line.trim().strip_prefix("tags:") {
+          tags = split_tags(value.trim().trim_start_matches('[').trim_end_matches(']'));
+        }
+      }
+      body = rest.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+  }
+
+  let mut examples = Vec::new();
+  let mut description = String::new();
+  let mut code: Vec<String> = Vec::new();
+  let mut flush = |description: &mut String, code: &mut Vec<String>| {
+    if !code.is_empty() {
+      examples.push(example(
+        Snippet {
+          description: std::mem::take(description),
+          code: code.join("\n"),
+        },
+        "cheat",
+      ));
+      code.clear();
+    }
+  };
+
+  for line in body.lines() {
+    let trimmed = line.trim();
+    if let Some(rest) = trimmed.strip_prefix('#') {
+      if !code.is_empty() {
+        flush(&mut description, &mut code);
+      }
+      append_line(&mut description, rest.trim());
+    } else if trimmed.is_empty() {
+      flush(&mut description, &mut code);
+    } else {
+      code.push(line.trim_end().to_string());
+    }
+  }
+  flush(&mut description, &mut code);
+  (tags, examples)
+}
+
+fn example(snippet: Snippet, source: &str) -> Example {
+  let code = placeholders(&snippet.code);
+  Example {
+    // 没有描述时用命令本身
+    description: if snippet.description.is_empty() {
+      code.lines().next().unwrap_or_default().to_string()
+    } else {
+      snippet.description
+    },
+    code,
+    source: Some(source.to_string()),
+  }
+}
+
+fn command(
+  name: String,
+  examples: Vec<Example>,
+  tags: &[String],
+  format: &str,
+  path: &Path,
+  content: &str,
+) -> Command {
+  let source = format!("file: {}", path.display());
+  Command {
+    description: if tags.is_empty() {
+      format!("Personal {} cheatsheet", format)
+    } else {
+      format!("Personal {} cheatsheet (tags: {})", format, tags.join(", "))
+    },
+    category: "local".to_string(),
+    platform: "common".to_string(),
+    lang: "local".to_string(),
+    examples,
+    content: format!("Source: {}\n\n{}", source, content),
+    sections: vec![],
+    options: vec![],
+    source_info: Some(SourceInfo {
+      source,
+      learned_at: chrono::Utc::now().to_rfc3339(),
+      ..Default::default()
+    }),
+    notes: None,
+    schema: COMMAND_SCHEMA,
+    name,
+  }
+}
+
+fn append_line(description: &mut String, line: &str) {
+  if !description.is_empty() {
+    description.push(' ');
+  }
+  description.push_str(line);
+}
+
+/// 导入时写入描述的标签（描述末尾的 `(tags: a, b)`）
+pub fn description_tags(description: &str) -> Vec<&str> {
+  let Some(start) = description.rfind("(tags: ") else {
+    return Vec::new();
+  };
+  description[start + "(tags: ".len()..]
+    .trim_end_matches(')')
+    .split(',')
+    .map(str::trim)
+    .filter(|t| !t.is_empty())
+    .collect()
+}
+
+fn split_tags(s: &str) -> Vec<String> {
+  s.split(',')
+    .map(|t| t.trim().to_string())
+    .filter(|t| !t.is_empty())
+    .collect()
+}
+
+/// 命令所属的程序：跳过 sudo 和环境变量赋值后的第一个词
+fn program_name(code: &str) -> Option<String> {
+  code
+    .split_whitespace()
+    .find(|word| *word != "sudo" && !word.contains('='))
+    .map(|word| word.rsplit('/').next().unwrap_or(word).to_string())
+    .filter(|name| !name.starts_with('<') && !name.is_empty())
+}
+
+/// navi / cheat 变量 `<name>` 转换为 tldr 占位符 `{{name}}`；`< file` 之类的重定向保持不变
+pub fn placeholders(code: &str) -> String {
+  let mut out = String::with_capacity(code.len());
+  let mut rest = code;
+  while let Some(start) = rest.find('<') {
+    out.push_str(&rest[..start]);
+    let after = &rest[start + 1..];
+    match after.find('>') {
+      Some(end) if is_variable(&after[..end]) => {
+        out.push_str("{{");
+        out.push_str(&after[..end]);
+        out.push_str("}}");
+        rest = &after[end + 1..];
+      }
+      _ => {
+        out.push('<');
+        rest = after;
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+fn is_variable(name: &str) -> bool {
+  name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+    && name
+      .chars()
+      .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// 速查表中的同名命令（如多个文件中的 git）合并为一条，示例按出现顺序去重；tldr 页面保持不变
+pub fn combine(commands: Vec<Command>) -> Vec<Command> {
+  let mut combined: Vec<Command> = Vec::new();
+  for cmd in commands {
+    match combined
+      .iter_mut()
+      .find(|c| cmd.lang == "local" && c.lang == "local" && c.name == cmd.name)
+    {
+      Some(existing) => *existing = existing.clone().merge_learned(cmd),
+      None => combined.push(cmd),
+    }
+  }
+  combined
+}
+
+/// 速查表中的命令与数据库中已学习的同名命令合并（保留学习到的帮助），不覆盖
+pub fn merge_with_learned(db: &Database, commands: Vec<Command>) -> Vec<Command> {
+  commands
+    .into_iter()
+    .map(|cmd| match db.get_command(&cmd.name, "local") {
+      Ok(Some(existing)) if cmd.lang == "local" => existing.merge_learned(cmd),
+      _ => cmd,
+    })
+    .collect()
+}
+
+/// 导出为 navi `.cheat` 文件：按命令名分组，返回（文件名，内容）
+pub fn export_navi(commands: &[Command]) -> Vec<(String, String)> {
+  let mut files: BTreeMap<String, String> = BTreeMap::new();
+  for cmd in commands {
+    if cmd.examples.is_empty() {
+      continue;
+    }
+    let file_name = format!("{}.cheat", cmd.name.replace(['/', '\\'], "_"));
+    let content = files.entry(file_name).or_default();
+    if !content.is_empty() {
+      content.push('\n');
+    }
+    content.push_str(&navi_section(cmd));
+  }
+  files.into_iter().collect()
+}
+
+/// 一条命令的 navi 段
+fn navi_section(cmd: &Command) -> String {
+  let mut out = format!("% {}, {}, {}\n", cmd.name, cmd.platform, cmd.lang);
+  let description = one_line(&cmd.description);
+  if !description.is_empty() {
+    out.push_str(&format!("; {}\n", description));
+  }
+  for example in &cmd.examples {
+    // 空行会结束 navi 的代码段
+    let code: Vec<&str> = example
+      .code
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .collect();
+    if code.is_empty() {
+      continue;
+    }
+    out.push_str(&format!("\n# {}\n", one_line(&example.description)));
+    out.push_str(&navi_variables(&code.join("\n")));
+    out.push('\n');
+  }
+  out
+}
+
+fn one_line(s: &str) -> String {
+  s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// tldr 占位符 `{{name}}` 转换为 navi 变量 `<name>`
+pub fn navi_variables(code: &str) -> String {
+  let mut out = String::with_capacity(code.len());
+  let mut rest = code;
+  while let Some(start) = rest.find("{{") {
+    let Some(end) = rest[start + 2..].find("}}") else {
+      break;
+    };
+    out.push_str(&rest[..start]);
+    let inner = &rest[start + 2..start + 2 + end];
+    match inner
+      .strip_prefix('[')
+      .and_then(|s| s.strip_suffix(']'))
+      .filter(|s| s.contains('|'))
+    {
+      // tldr 的选项写法 [-f|--force]：不是变量，写成长选项
+      Some(options) => out.push_str(options.rsplit('|').next().unwrap_or(options)),
+      None => {
+        out.push('<');
+        out.push_str(&variable_name(inner));
+        out.push('>');
+      }
+    }
+    rest = &rest[start + 2 + end + 2..];
+  }
+  out.push_str(rest);
+  out
+}
+
+/// navi 变量名只能包含字母、数字、`_` 和 `-`
+fn variable_name(placeholder: &str) -> String {
+  let mut name = String::new();
+  for c in placeholder.chars() {
+    if c.is_alphanumeric() || c == '-' {
+      name.push(c);
+    } else if !name.is_empty() && !name.ends_with('_') {
+      name.push('_');
+    }
+  }
+  let name = name.trim_end_matches('_');
+  if name.is_empty() {
+    "value".to_string()
+  } else if name.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+    format!("arg_{}", name)
+  } else {
+    name.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_navi() {
+    let content = "\
+% git, vcs
+
+# Change branch
+git checkout <branch>
+
+$ branch: git branch | awk '{print $NF}'
+
+# Show status and log
+git status
+git log --oneline -n <count>
+
+; comment
+# Follow a log file
+sudo tail -f <file> \\
+  | grep <pattern>
+
+% docker
+# List containers
+docker ps -a
+";
+    let commands = parse_navi(content, Path::new("/sheets/dev.cheat"));
+    let names: Vec<&str> = commands.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["docker", "git", "tail"]);
+
+    let git = &commands[1];
+    assert_eq!(git.lang, "local");
+    assert_eq!(git.description, "Personal navi cheatsheet (tags: git, vcs)");
+    assert_eq!(git.examples.len(), 2);
+    assert_eq!(git.examples[0].description, "Change branch");
+    assert_eq!(git.examples[0].code, "git checkout {{branch}}");
+    assert_eq!(git.examples[1].description, "Show status and log");
+    assert_eq!(
+      git.examples[1].code,
+      "git status\ngit log --oneline -n {{count}}"
+    );
+    assert_eq!(git.examples[0].source.as_deref(), Some("navi"));
+    assert!(git
+      .source_info
+      .as_ref()
+      .unwrap()
+      .source
+      .starts_with("file: "));
+
+    assert_eq!(
+      commands[2].examples[0].code,
+      "sudo tail -f {{file}} \\\n  | grep {{pattern}}"
+    );
+    assert_eq!(
+      commands[0].description,
+      "Personal navi cheatsheet (tags: docker)"
+    );
+  }
+
+  #[test]
+  fn test_parse_cheat() {
+    let content = "\
+---
+syntax: bash
+tags: [ vcs, development ]
+---
+# To set your identity:
+git config --global user.name <name>
+git config --global user.email <email>
+
+git stash
+";
+    let cmd = parse_cheat(content, "git", Path::new("git")).unwrap();
+    assert_eq!(cmd.name, "git");
+    assert_eq!(
+      cmd.description,
+      "Personal cheat cheatsheet (tags: vcs, development)"
+    );
+    assert_eq!(cmd.examples.len(), 2);
+    assert_eq!(cmd.examples[0].description, "To set your identity:");
+    assert_eq!(
+      cmd.examples[0].code,
+      "git config --global user.name {{name}}\ngit config --global user.email {{email}}"
+    );
+    assert_eq!(cmd.examples[1].description, "git stash");
+    assert_eq!(cmd.examples[1].source.as_deref(), Some("cheat"));
+
+    assert!(parse_cheat("# only a comment\n", "x", Path::new("x")).is_none());
+  }
+
+  #[test]
+  fn test_placeholders() {
+    assert_eq!(
+      placeholders("cp <src> <dest-dir>"),
+      "cp {{src}} {{dest-dir}}"
+    );
+    assert_eq!(placeholders("sort < in.txt > out"), "sort < in.txt > out");
+    assert_eq!(placeholders("echo <<EOF"), "echo <<EOF");
+  }
+
+  #[test]
+  fn test_combine() {
+    let path = Path::new("a.cheat");
+    let mut commands = parse_navi("# Status\ngit status\n", path);
+    commands.extend(parse_navi("# Status\ngit status\n# Log\ngit log\n", path));
+    let combined = combine(commands);
+    assert_eq!(combined.len(), 1);
+    assert_eq!(combined[0].examples.len(), 2);
+  }
+
+  #[test]
+  fn test_merge_with_learned() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    let path = Path::new("a.cheat");
+    let mut learned = parse_navi("# Status\ngit status\n", path).remove(0);
+    learned.description = "the stupid content tracker".to_string();
+    db.save_command(&learned).unwrap();
+
+    let merged = merge_with_learned(&db, parse_navi("# Log\ngit log\n# Diff\ngit diff\n", path));
+    assert_eq!(merged[0].description, "the stupid content tracker");
+    assert_eq!(merged[0].examples.len(), 3);
+  }
+
+  #[test]
+  fn test_navi_variables() {
+    assert_eq!(
+      navi_variables("cp {{path/to/source}} {{path/to/dest.txt}}"),
+      "cp <path_to_source> <path_to_dest_txt>"
+    );
+    assert_eq!(
+      navi_variables("rm {{[-r|--recursive]}} {{...}} {{1}}"),
+      "rm --recursive <value> <arg_1>"
+    );
+    assert_eq!(navi_variables("echo '{{'"), "echo '{{'");
+  }
+
+  #[test]
+  fn test_export_navi() {
+    let mut tar = parse_navi("# Extract\ntar xf <archive>\n", Path::new("a.cheat")).remove(0);
+    tar.lang = "en".to_string();
+    tar.description = "Archiving\nutility".to_string();
+    tar.examples[0].code = "tar xf {{path/to/file.tar}}".to_string();
+    let local = parse_navi("tar tf <archive>\n", Path::new("a.cheat")).remove(0);
+    let mut empty = local.clone();
+    empty.name = "ls".to_string();
+    empty.examples.clear();
+
+    let files = export_navi(&[tar, local, empty]);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].0, "tar.cheat");
+    assert_eq!(
+      files[0].1,
+      "% tar, common, en\n; Archiving utility\n\n# Extract\ntar xf <path_to_file_tar>\n\n\
+       % tar, common, local\n; Personal navi cheatsheet\n\n# tar tf {{archive}}\ntar tf <archive>\n"
+    );
+
+    // 导出的文件可以再导入
+    let imported = parse_navi(&files[0].1, Path::new("tar.cheat"));
+    assert_eq!(imported[0].examples.len(), 2);
+    assert_eq!(imported[0].examples[0].code, "tar xf {{path_to_file_tar}}");
+  }
+}
@@ -1,12 +1,40 @@
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use redb::{Database as RedbDatabase, ReadableTable, ReadableTableMetadata, TableDefinition};
+use redb::{
+  Database as RedbDatabase, Durability, ReadableTable, ReadableTableMetadata, TableDefinition,
+  WriteTransaction,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use utoipa::ToSchema;
 
 const COMMANDS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("commands");
 const METADATA_TABLE: TableDefinition<&str, &str> = TableDefinition::new("metadata");
+const USAGE_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("usage");
+/// 审计日志，键为递增的序号
+const AUDIT_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("audit");
+/// 查看命令的历史，键为递增的序号
+const HISTORY_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("history");
+/// 保留最近的历史条数
+const MAX_HISTORY: u64 = 1000;
+/// 收藏的命令，值为收藏时间
+const FAVORITES_TABLE: TableDefinition<&str, &str> = TableDefinition::new("favorites");
+/// 命令别名，值为指向的命令（如 gco → git checkout）
+const ALIASES_TABLE: TableDefinition<&str, &str> = TableDefinition::new("aliases");
+/// 个人笔记，键为命令名（各语言共用），不随 tldr 更新清除
+const NOTES_TABLE: TableDefinition<&str, &str> = TableDefinition::new("notes");
+const LEARN_CHECKPOINT_KEY: &str = "learn_all_checkpoint";
+/// 数据集更新的记录（新的在前）
+const UPDATE_HISTORY_KEY: &str = "update_history";
+
+/// 命令序列化格式的版本；加入版本号之前保存的记录没有该字段，按 1 读取
+/// 2：增加 options
+pub const COMMAND_SCHEMA: u32 = 2;
+
+fn legacy_schema() -> u32 {
+  1
+}
 
 #[derive(Error, Debug)]
 pub enum StorageError {
@@ -22,6 +50,8 @@ pub enum StorageError {
   Storage(#[from] redb::StorageError),
   #[error("Serialization error: {0}")]
   Serialization(#[from] serde_json::Error),
+  #[error("Compaction error: {0}")]
+  Compaction(#[from] redb::CompactionError),
   #[error("Not found: {0}")]
   NotFound(String),
 }
@@ -48,6 +78,120 @@ pub struct Command {
   pub examples: Vec<Example>,
   /// Raw help content
   pub content: String,
+  /// Structured sections of the source page (e.g., man NAME/SYNOPSIS/OPTIONS)
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub sections: Vec<Section>,
+  /// Command-line options with their arguments and descriptions
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub options: Vec<OptionEntry>,
+  /// Where a learned command came from (absent for imported tldr pages)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub source_info: Option<SourceInfo>,
+  /// Personal notes added with rtfm note; stored apart from the page and shared by all its languages
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub notes: Option<String>,
+  /// Version of the stored format; records saved before versioning load as 1
+  #[serde(default = "legacy_schema")]
+  pub schema: u32,
+}
+
+impl Command {
+  /// Combine a tldr page with the locally learned help for the same command.
+  /// tldr examples come first, followed by local examples (labeled "local") that are
+  /// not already covered; local sections and source info are carried over.
+  pub fn merge_learned(mut self, local: Command) -> Command {
+    for example in local.examples {
+      if self.examples.iter().any(|e| e.code == example.code) {
+        continue;
+      }
+      self.examples.push(Example {
+        source: example.source.or_else(|| Some("local".to_string())),
+        ..example
+      });
+    }
+    if self.sections.is_empty() {
+      self.sections = local.sections;
+    }
+    // 本地帮助的选项更完整，tldr 中已有的选项保留其说明
+    for option in local.options {
+      if !self.options.iter().any(|o| o.overlaps(&option)) {
+        self.options.push(option);
+      }
+    }
+    if self.source_info.is_none() {
+      self.source_info = local.source_info;
+    }
+    self
+  }
+
+  /// Last time the command was learned; imported tldr pages have none
+  pub fn updated_at(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let info = self.source_info.as_ref()?;
+    chrono::DateTime::parse_from_rfc3339(&info.learned_at).ok()
+  }
+
+  /// Find a structured section by title (case-insensitive)
+  pub fn section(&self, title: &str) -> Option<&Section> {
+    self
+      .sections
+      .iter()
+      .find(|s| s.title.eq_ignore_ascii_case(title))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OptionEntry {
+  /// Spellings of the option (e.g., ["-v", "--verbose"])
+  pub flags: Vec<String>,
+  /// Argument the option takes (e.g., FILE)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub arg: Option<String>,
+  /// What the option does
+  pub description: String,
+}
+
+impl OptionEntry {
+  /// Flags joined for display, followed by the argument (e.g., "-f, --file FILE")
+  pub fn label(&self) -> String {
+    let flags = self.flags.join(", ");
+    match &self.arg {
+      Some(arg) => format!("{} {}", flags, arg),
+      None => flags,
+    }
+  }
+
+  /// Whether both entries describe the same option (they share a spelling)
+  pub fn overlaps(&self, other: &OptionEntry) -> bool {
+    self.flags.iter().any(|flag| other.flags.contains(flag))
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SourceInfo {
+  /// How the help content was obtained (e.g., --help, man, url: ...)
+  pub source: String,
+  /// First line of `<cmd> --version` at learn time
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+  /// Version number parsed from `version` (e.g., 2.43.0), compared by `rtfm learn --refresh-outdated`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version_number: Option<String>,
+  /// Resolved path of the binary at learn time
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub binary: Option<String>,
+  /// Binary modification time at learn time (Unix seconds)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub binary_mtime: Option<i64>,
+  /// Learn timestamp (RFC 3339)
+  pub learned_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Section {
+  /// Section title as it appears in the page (e.g., SYNOPSIS)
+  pub title: String,
+  /// Section body with the page indentation removed
+  pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -56,6 +200,9 @@ pub struct Example {
   pub description: String,
   /// Example code
   pub code: String,
+  /// Where the example came from when not parsed from the page itself (e.g., "llm:gpt-4o-mini")
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub source: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -68,72 +215,372 @@ pub struct Metadata {
   pub last_update: String,
   /// Available languages
   pub languages: Vec<String>,
+  /// Where the data came from (github, or local-file for `rtfm update --from-file`)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub source: Option<String>,
+  /// Languages the last tldr update was limited to (empty = all; not recorded by older versions)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub selected_languages: Option<Vec<String>>,
+}
+
+/// Progress of an interrupted or partially failed `learn-all` run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LearnCheckpoint {
+  /// Resolved source (man, path, powershell, builtin)
+  pub source: String,
+  /// Man section (only meaningful for the man source)
+  pub section: String,
+  /// Commands already attempted, successful or not
+  pub processed: Vec<String>,
+  /// Commands whose help could not be fetched or saved
+  pub failed: Vec<String>,
+  /// Last save timestamp (RFC 3339)
+  pub updated_at: String,
+}
+
+/// One tldr dataset update, whether it downloaded anything or not
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateRun {
+  /// Start time (RFC 3339)
+  pub started_at: String,
+  /// Who started it (api, scheduler or auto-update)
+  pub source: String,
+  /// Whether the update succeeded
+  pub success: bool,
+  /// Dataset version before the update
+  pub from_version: Option<String>,
+  /// Dataset version after the update
+  pub to_version: Option<String>,
+  /// Run time in milliseconds
+  pub duration_ms: u64,
+  /// Result summary or error message
+  pub message: String,
+}
+
+/// What a usage record counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+  /// A command shown by name
+  Lookup,
+  /// A full-text search
+  Query,
+}
+
+impl UsageKind {
+  fn prefix(self) -> &'static str {
+    match self {
+      UsageKind::Lookup => "lookup:",
+      UsageKind::Query => "query:",
+    }
+  }
+}
+
+/// Local usage statistics for one command or query
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UsageRecord {
+  /// Number of lookups or searches
+  pub count: u64,
+  /// Searches that returned no results (always 0 for lookups)
+  pub zero_results: u64,
+  /// Last use timestamp (RFC 3339)
+  pub last_used: String,
+}
+
+/// One data modification in the audit log
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+  /// Sequence number, increasing with every entry
+  #[serde(default)]
+  pub id: u64,
+  /// Time of the change (RFC 3339)
+  pub timestamp: String,
+  /// Operation: import, learn, update, delete, reset, restore or sync
+  pub action: String,
+  /// Where the change came from: cli, api, scheduler or watch
+  pub source: String,
+  /// Client address of the API request
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub client: Option<String>,
+  /// Authenticated user (multi-user mode)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub user: Option<String>,
+  /// Number of commands written or removed
+  pub count: usize,
+  /// What was changed (file, version, command name, remote, ...)
+  #[serde(default, skip_serializing_if = "String::is_empty")]
+  pub detail: String,
+}
+
+/// A command bookmarked with rtfm fav add, the TUI or /api/favorites
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Favorite {
+  /// Command name
+  pub name: String,
+  /// When it was added (RFC 3339)
+  pub added: String,
+}
+
+/// A command alias added with rtfm alias add or imported from shell and git config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alias {
+  /// Alias name (e.g., gco)
+  pub name: String,
+  /// Command it stands for (e.g., git checkout)
+  pub target: String,
+}
+
+/// Personal notes on a command, added with rtfm note or the TUI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Note {
+  /// Command name
+  pub name: String,
+  /// Note text (Markdown)
+  pub text: String,
+}
+
+/// One command view in the history
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct HistoryEntry {
+  /// Command name
+  pub name: String,
+  /// Language of the page that was shown
+  pub lang: String,
+  /// Where it was viewed: tui, cli or api
+  pub source: String,
+  /// Time of the view (RFC 3339)
+  pub timestamp: String,
+}
+
+/// 序列化命令记录；笔记保存在 NOTES_TABLE 中，不写入记录
+fn encode_command(cmd: &Command) -> Result<Vec<u8>, StorageError> {
+  if cmd.notes.is_none() {
+    return Ok(serde_json::to_vec(cmd)?);
+  }
+  Ok(serde_json::to_vec(&Command {
+    notes: None,
+    ..cmd.clone()
+  })?)
+}
+
+/// 读取命令记录并升级到当前格式：旧记录没有选项，tldr 页面的选项从示例中补出，
+/// 本地学习的命令重新学习后才有选项
+fn decode_command(data: &[u8]) -> Result<Command, StorageError> {
+  let mut cmd: Command = serde_json::from_slice(data)?;
+  if cmd.schema < COMMAND_SCHEMA {
+    if cmd.lang != "local" && cmd.options.is_empty() {
+      cmd.options = crate::update::extract_options(&cmd.examples);
+    }
+    cmd.schema = COMMAND_SCHEMA;
+  }
+  Ok(cmd)
+}
+
+/// 在事务中把一次查看或搜索计入 USAGE_TABLE
+fn bump_usage(
+  write_txn: &WriteTransaction,
+  kind: UsageKind,
+  key: &str,
+  results: usize,
+) -> Result<(), StorageError> {
+  let key = format!("{}{}", kind.prefix(), key);
+  let mut table = write_txn.open_table(USAGE_TABLE)?;
+  let mut record: UsageRecord = match table.get(key.as_str())? {
+    Some(data) => serde_json::from_slice(data.value())?,
+    None => UsageRecord::default(),
+  };
+  record.count += 1;
+  if kind == UsageKind::Query && results == 0 {
+    record.zero_results += 1;
+  }
+  record.last_used = chrono::Utc::now().to_rfc3339();
+  let data = serde_json::to_vec(&record)?;
+  table.insert(key.as_str(), data.as_slice())?;
+  Ok(())
+}
+
+/// 在事务中追加一条查看历史，只保留最近的 MAX_HISTORY 条
+fn push_history(write_txn: &WriteTransaction, entry: &HistoryEntry) -> Result<(), StorageError> {
+  let mut table = write_txn.open_table(HISTORY_TABLE)?;
+  let id = table.last()?.map_or(1, |(key, _)| key.value() + 1);
+  let data = serde_json::to_vec(entry)?;
+  table.insert(id, data.as_slice())?;
+  if id > MAX_HISTORY {
+    table.retain_in(..=id - MAX_HISTORY, |_, _| false)?;
+  }
+  Ok(())
 }
 
 pub struct Database {
-  db: RedbDatabase,
+  /// 压缩（compact）需要独占访问，其余操作只在开启事务时短暂持有读锁
+  db: parking_lot::RwLock<RedbDatabase>,
+  /// 本进程中命令被写入或清空的次数，供缓存判断是否失效
+  generation: AtomicU64,
 }
 
 impl Database {
   pub fn open(path: &Path) -> Result<Self, StorageError> {
     let db = RedbDatabase::create(path)?;
 
-    // 初始化表
-    let write_txn = db.begin_write()?;
+    // 初始化表；表都已存在时不开启写事务（提交写事务要同步到磁盘，会拖慢每次查询）
+    let read_txn = db.begin_read()?;
+    let initialized = read_txn.open_table(COMMANDS_TABLE).is_ok()
+      && read_txn.open_table(METADATA_TABLE).is_ok()
+      && read_txn.open_table(USAGE_TABLE).is_ok()
+      && read_txn.open_table(AUDIT_TABLE).is_ok()
+      && read_txn.open_table(FAVORITES_TABLE).is_ok()
+      && read_txn.open_table(ALIASES_TABLE).is_ok()
+      && read_txn.open_table(NOTES_TABLE).is_ok()
+      && read_txn.open_table(HISTORY_TABLE).is_ok();
+    drop(read_txn);
+    if !initialized {
+      let write_txn = db.begin_write()?;
+      {
+        let _ = write_txn.open_table(COMMANDS_TABLE)?;
+        let _ = write_txn.open_table(METADATA_TABLE)?;
+        let _ = write_txn.open_table(USAGE_TABLE)?;
+        let _ = write_txn.open_table(AUDIT_TABLE)?;
+        let _ = write_txn.open_table(FAVORITES_TABLE)?;
+        let _ = write_txn.open_table(ALIASES_TABLE)?;
+        let _ = write_txn.open_table(NOTES_TABLE)?;
+        let _ = write_txn.open_table(HISTORY_TABLE)?;
+      }
+      write_txn.commit()?;
+    }
+
+    Ok(Self {
+      db: parking_lot::RwLock::new(db),
+      generation: AtomicU64::new(0),
+    })
+  }
+
+  /// Reclaim free space in the database file.
+  /// Fails while another transaction is still open; returns whether anything was compacted.
+  pub fn compact(&self) -> Result<bool, StorageError> {
+    Ok(self.db.write().compact()?)
+  }
+
+  /// Write a consistent copy of the data to a new database file.
+  /// The audit log belongs to this installation and is not copied.
+  pub fn snapshot(&self, path: &Path) -> Result<(), StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let copy = RedbDatabase::create(path)?;
+    let write_txn = copy.begin_write()?;
     {
-      let _ = write_txn.open_table(COMMANDS_TABLE)?;
-      let _ = write_txn.open_table(METADATA_TABLE)?;
+      for definition in [COMMANDS_TABLE, USAGE_TABLE] {
+        let source = read_txn.open_table(definition)?;
+        let mut target = write_txn.open_table(definition)?;
+        for entry in source.iter()? {
+          let (key, value) = entry?;
+          target.insert(key.value(), value.value())?;
+        }
+      }
+      let source = read_txn.open_table(HISTORY_TABLE)?;
+      let mut target = write_txn.open_table(HISTORY_TABLE)?;
+      for entry in source.iter()? {
+        let (key, value) = entry?;
+        target.insert(key.value(), value.value())?;
+      }
+      for definition in [METADATA_TABLE, FAVORITES_TABLE, ALIASES_TABLE, NOTES_TABLE] {
+        let source = read_txn.open_table(definition)?;
+        let mut target = write_txn.open_table(definition)?;
+        for entry in source.iter()? {
+          let (key, value) = entry?;
+          target.insert(key.value(), value.value())?;
+        }
+      }
     }
     write_txn.commit()?;
+    Ok(())
+  }
+
+  /// Changes whenever commands are saved or cleared through this handle
+  pub fn generation(&self) -> u64 {
+    self.generation.load(Ordering::Acquire)
+  }
 
-    Ok(Self { db })
+  fn bump_generation(&self) {
+    self.generation.fetch_add(1, Ordering::AcqRel);
   }
 
   pub fn get_command(&self, name: &str, lang: &str) -> Result<Option<Command>, StorageError> {
     let key = format!("{}:{}", lang, name);
-    let read_txn = self.db.begin_read()?;
+    let read_txn = self.db.read().begin_read()?;
     let table = read_txn.open_table(COMMANDS_TABLE)?;
 
     match table.get(key.as_str())? {
-      Some(data) => {
-        let cmd: Command = serde_json::from_slice(data.value())?;
-        Ok(Some(cmd))
-      }
+      Some(data) => Ok(Some(decode_command(data.value())?)),
       None => Ok(None),
     }
   }
 
   pub fn save_command(&self, cmd: &Command) -> Result<(), StorageError> {
     let key = format!("{}:{}", cmd.lang, cmd.name);
-    let data = serde_json::to_vec(cmd)?;
+    let data = encode_command(cmd)?;
 
-    let write_txn = self.db.begin_write()?;
+    let write_txn = self.db.read().begin_write()?;
     {
       let mut table = write_txn.open_table(COMMANDS_TABLE)?;
       table.insert(key.as_str(), data.as_slice())?;
     }
     write_txn.commit()?;
+    self.bump_generation();
 
     Ok(())
   }
 
   pub fn save_commands(&self, commands: &[Command]) -> Result<(), StorageError> {
-    let write_txn = self.db.begin_write()?;
+    let write_txn = self.db.read().begin_write()?;
     {
       let mut table = write_txn.open_table(COMMANDS_TABLE)?;
       for cmd in commands {
         let key = format!("{}:{}", cmd.lang, cmd.name);
-        let data = serde_json::to_vec(cmd)?;
+        let data = encode_command(cmd)?;
         table.insert(key.as_str(), data.as_slice())?;
       }
     }
     write_txn.commit()?;
+    self.bump_generation();
 
     Ok(())
   }
 
+  /// Languages that have a command with this name
+  pub fn command_langs(&self, name: &str) -> Result<Vec<String>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(COMMANDS_TABLE)?;
+
+    let mut langs = Vec::new();
+    for entry in table.iter()? {
+      let (key, _) = entry?;
+      if let Some((lang, key_name)) = key.value().split_once(':') {
+        if key_name == name {
+          langs.push(lang.to_string());
+        }
+      }
+    }
+
+    Ok(langs)
+  }
+
+  /// Delete a command; returns false if it does not exist
+  pub fn delete_command(&self, name: &str, lang: &str) -> Result<bool, StorageError> {
+    let key = format!("{}:{}", lang, name);
+    let write_txn = self.db.read().begin_write()?;
+    let removed = {
+      let mut table = write_txn.open_table(COMMANDS_TABLE)?;
+      let removed = table.remove(key.as_str())?.is_some();
+      removed
+    };
+    write_txn.commit()?;
+    if removed {
+      self.bump_generation();
+    }
+
+    Ok(removed)
+  }
+
   pub fn get_all_commands(&self, lang: &str) -> Result<Vec<Command>, StorageError> {
-    let read_txn = self.db.begin_read()?;
+    let read_txn = self.db.read().begin_read()?;
     let table = read_txn.open_table(COMMANDS_TABLE)?;
 
     let prefix = format!("{}:", lang);
@@ -142,16 +589,57 @@ impl Database {
     for entry in table.iter()? {
       let (key, value) = entry?;
       if key.value().starts_with(&prefix) {
-        let cmd: Command = serde_json::from_slice(value.value())?;
-        commands.push(cmd);
+        commands.push(decode_command(value.value())?);
+      }
+    }
+
+    Ok(commands)
+  }
+
+  /// Look up a command for display: the preferred language first, then en/zh, then
+  /// the locally learned entry. When both a tldr page and a learned entry exist they
+  /// are merged into one view.
+  pub fn find_command(&self, name: &str, lang: &str) -> Result<Option<Command>, StorageError> {
+    let mut tldr = None;
+    for candidate in [lang, "en", "zh"] {
+      if candidate == "local" {
+        continue;
+      }
+      if let Some(cmd) = self.get_command(name, candidate)? {
+        tldr = Some(cmd);
+        break;
       }
     }
+    let local = self.get_command(name, "local")?;
+
+    let found = match (tldr, local) {
+      (Some(tldr), Some(local)) => Some(tldr.merge_learned(local)),
+      (tldr, local) => tldr.or(local),
+    };
+    found
+      .map(|mut cmd| {
+        cmd.notes = self.get_note(name)?;
+        Ok(cmd)
+      })
+      .transpose()
+  }
+
+  /// Get commands across all languages
+  pub fn list_all_commands(&self) -> Result<Vec<Command>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(COMMANDS_TABLE)?;
+
+    let mut commands = Vec::new();
+    for entry in table.iter()? {
+      let (_, value) = entry?;
+      commands.push(decode_command(value.value())?);
+    }
 
     Ok(commands)
   }
 
   pub fn get_metadata(&self) -> Result<Option<Metadata>, StorageError> {
-    let read_txn = self.db.begin_read()?;
+    let read_txn = self.db.read().begin_read()?;
     let table = read_txn.open_table(METADATA_TABLE)?;
 
     match table.get("metadata")? {
@@ -166,7 +654,7 @@ impl Database {
   pub fn save_metadata(&self, meta: &Metadata) -> Result<(), StorageError> {
     let data = serde_json::to_string(meta)?;
 
-    let write_txn = self.db.begin_write()?;
+    let write_txn = self.db.read().begin_write()?;
     {
       let mut table = write_txn.open_table(METADATA_TABLE)?;
       table.insert("metadata", data.as_str())?;
@@ -176,20 +664,366 @@ impl Database {
     Ok(())
   }
 
+  pub fn get_learn_checkpoint(&self) -> Result<Option<LearnCheckpoint>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(METADATA_TABLE)?;
+
+    match table.get(LEARN_CHECKPOINT_KEY)? {
+      Some(data) => Ok(Some(serde_json::from_str(data.value())?)),
+      None => Ok(None),
+    }
+  }
+
+  pub fn save_learn_checkpoint(&self, checkpoint: &LearnCheckpoint) -> Result<(), StorageError> {
+    let data = serde_json::to_string(checkpoint)?;
+
+    let write_txn = self.db.read().begin_write()?;
+    {
+      let mut table = write_txn.open_table(METADATA_TABLE)?;
+      table.insert(LEARN_CHECKPOINT_KEY, data.as_str())?;
+    }
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
+  pub fn clear_learn_checkpoint(&self) -> Result<(), StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    {
+      let mut table = write_txn.open_table(METADATA_TABLE)?;
+      table.remove(LEARN_CHECKPOINT_KEY)?;
+    }
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
+  /// 数据集更新的记录，新的在前
+  pub fn list_update_history(&self) -> Result<Vec<UpdateRun>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(METADATA_TABLE)?;
+
+    match table.get(UPDATE_HISTORY_KEY)? {
+      Some(data) => Ok(serde_json::from_str(data.value())?),
+      None => Ok(Vec::new()),
+    }
+  }
+
+  /// 记录一次更新，只保留最近 limit 条
+  pub fn push_update_run(&self, run: &UpdateRun, limit: usize) -> Result<(), StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    {
+      let mut table = write_txn.open_table(METADATA_TABLE)?;
+      let mut runs: Vec<UpdateRun> = match table.get(UPDATE_HISTORY_KEY)? {
+        Some(data) => serde_json::from_str(data.value())?,
+        None => Vec::new(),
+      };
+      runs.insert(0, run.clone());
+      runs.truncate(limit);
+      let data = serde_json::to_string(&runs)?;
+      table.insert(UPDATE_HISTORY_KEY, data.as_str())?;
+    }
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
   pub fn clear_commands(&self) -> Result<(), StorageError> {
-    let write_txn = self.db.begin_write()?;
+    let write_txn = self.db.read().begin_write()?;
     {
       // 删除并重新创建表
       write_txn.delete_table(COMMANDS_TABLE)?;
       let _ = write_txn.open_table(COMMANDS_TABLE)?;
     }
     write_txn.commit()?;
+    self.bump_generation();
+
+    Ok(())
+  }
+
+  /// 记录一次查看或搜索；results 为搜索结果数
+  pub fn record_usage(
+    &self,
+    kind: UsageKind,
+    key: &str,
+    results: usize,
+  ) -> Result<(), StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    bump_usage(&write_txn, kind, key, results)?;
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
+  /// 记录一次按名称查看：查看次数和查看历史在同一个事务中写入
+  /// 每次查询命令都会写入，不等待落盘（Eventual），进程退出后仍会由系统写回，只在系统崩溃时可能丢失最近几条
+  pub fn record_lookup(&self, entry: &HistoryEntry) -> Result<(), StorageError> {
+    let mut write_txn = self.db.read().begin_write()?;
+    write_txn.set_durability(Durability::Eventual);
+    bump_usage(&write_txn, UsageKind::Lookup, &entry.name, 1)?;
+    push_history(&write_txn, entry)?;
+    write_txn.commit()?;
 
     Ok(())
   }
 
+  pub fn list_usage(&self, kind: UsageKind) -> Result<Vec<(String, UsageRecord)>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(USAGE_TABLE)?;
+
+    let mut records = Vec::new();
+    for entry in table.iter()? {
+      let (key, value) = entry?;
+      if let Some(name) = key.value().strip_prefix(kind.prefix()) {
+        records.push((name.to_string(), serde_json::from_slice(value.value())?));
+      }
+    }
+
+    Ok(records)
+  }
+
+  pub fn clear_usage(&self) -> Result<(), StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    {
+      write_txn.delete_table(USAGE_TABLE)?;
+      let _ = write_txn.open_table(USAGE_TABLE)?;
+    }
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
+  /// 追加一条审计记录，返回分配的序号；记录写入后不再修改
+  pub fn append_audit(&self, entry: &AuditEntry) -> Result<u64, StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    let id = {
+      let mut table = write_txn.open_table(AUDIT_TABLE)?;
+      let id = table.last()?.map_or(1, |(key, _)| key.value() + 1);
+      let data = serde_json::to_vec(&AuditEntry {
+        id,
+        ..entry.clone()
+      })?;
+      table.insert(id, data.as_slice())?;
+      id
+    };
+    write_txn.commit()?;
+
+    Ok(id)
+  }
+
+  /// 最近的 limit 条审计记录（新的在前），可按操作过滤
+  pub fn list_audit(
+    &self,
+    limit: usize,
+    action: Option<&str>,
+  ) -> Result<Vec<AuditEntry>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(AUDIT_TABLE)?;
+
+    let mut entries = Vec::new();
+    for entry in table.iter()?.rev() {
+      if entries.len() >= limit {
+        break;
+      }
+      let (_, value) = entry?;
+      let entry: AuditEntry = serde_json::from_slice(value.value())?;
+      if action.is_none_or(|action| entry.action == action) {
+        entries.push(entry);
+      }
+    }
+
+    Ok(entries)
+  }
+
+  /// 追加一条查看历史，只保留最近的 MAX_HISTORY 条
+  pub fn append_history(&self, entry: &HistoryEntry) -> Result<(), StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    push_history(&write_txn, entry)?;
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
+  /// 最近的 limit 条查看历史，新的在前
+  pub fn list_history(&self, limit: usize) -> Result<Vec<HistoryEntry>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(HISTORY_TABLE)?;
+
+    let mut entries = Vec::new();
+    for entry in table.iter()?.rev().take(limit) {
+      let (_, value) = entry?;
+      entries.push(serde_json::from_slice(value.value())?);
+    }
+
+    Ok(entries)
+  }
+
+  pub fn clear_history(&self) -> Result<(), StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    {
+      write_txn.delete_table(HISTORY_TABLE)?;
+      let _ = write_txn.open_table(HISTORY_TABLE)?;
+    }
+    write_txn.commit()?;
+
+    Ok(())
+  }
+
+  /// 收藏命令；已收藏时返回 false
+  pub fn add_favorite(&self, name: &str) -> Result<bool, StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    let added = {
+      let mut table = write_txn.open_table(FAVORITES_TABLE)?;
+      let exists = table.get(name)?.is_some();
+      if !exists {
+        table.insert(name, chrono::Utc::now().to_rfc3339().as_str())?;
+      }
+      !exists
+    };
+    write_txn.commit()?;
+
+    Ok(added)
+  }
+
+  /// 取消收藏；未收藏时返回 false
+  pub fn remove_favorite(&self, name: &str) -> Result<bool, StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    let removed = write_txn
+      .open_table(FAVORITES_TABLE)?
+      .remove(name)?
+      .is_some();
+    write_txn.commit()?;
+
+    Ok(removed)
+  }
+
+  /// 所有收藏，按命令名排序
+  pub fn list_favorites(&self) -> Result<Vec<Favorite>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(FAVORITES_TABLE)?;
+
+    let mut favorites = Vec::new();
+    for entry in table.iter()? {
+      let (key, value) = entry?;
+      favorites.push(Favorite {
+        name: key.value().to_string(),
+        added: value.value().to_string(),
+      });
+    }
+
+    Ok(favorites)
+  }
+
+  /// 命令的笔记
+  pub fn get_note(&self, name: &str) -> Result<Option<String>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(NOTES_TABLE)?;
+    let note = table.get(name)?.map(|note| note.value().to_string());
+    Ok(note)
+  }
+
+  /// 保存笔记，内容为空时删除；返回笔记是否有变化
+  pub fn set_note(&self, name: &str, text: &str) -> Result<bool, StorageError> {
+    let text = text.trim();
+    let write_txn = self.db.read().begin_write()?;
+    let changed = {
+      let mut table = write_txn.open_table(NOTES_TABLE)?;
+      let previous = if text.is_empty() {
+        table.remove(name)?.map(|old| old.value().to_string())
+      } else {
+        table.insert(name, text)?.map(|old| old.value().to_string())
+      };
+      previous.as_deref().unwrap_or_default() != text
+    };
+    write_txn.commit()?;
+    // 笔记显示在命令详情中，缓存需要失效
+    if changed {
+      self.bump_generation();
+    }
+
+    Ok(changed)
+  }
+
+  /// 所有笔记，按命令名排序
+  pub fn list_notes(&self) -> Result<Vec<Note>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(NOTES_TABLE)?;
+
+    let mut notes = Vec::new();
+    for entry in table.iter()? {
+      let (key, value) = entry?;
+      notes.push(Note {
+        name: key.value().to_string(),
+        text: value.value().to_string(),
+      });
+    }
+
+    Ok(notes)
+  }
+
+  /// 为命令附上笔记（用于建立索引）
+  pub fn attach_notes(&self, commands: &mut [Command]) -> Result<(), StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(NOTES_TABLE)?;
+    if table.is_empty()? {
+      return Ok(());
+    }
+    for cmd in commands {
+      cmd.notes = table
+        .get(cmd.name.as_str())?
+        .map(|note| note.value().to_string());
+    }
+    Ok(())
+  }
+
+  /// 添加或替换别名，返回原来指向的命令
+  pub fn set_alias(&self, name: &str, target: &str) -> Result<Option<String>, StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    let previous = write_txn
+      .open_table(ALIASES_TABLE)?
+      .insert(name, target)?
+      .map(|old| old.value().to_string());
+    write_txn.commit()?;
+
+    Ok(previous)
+  }
+
+  /// 别名指向的命令
+  pub fn get_alias(&self, name: &str) -> Result<Option<String>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(ALIASES_TABLE)?;
+    let target = table.get(name)?.map(|target| target.value().to_string());
+    Ok(target)
+  }
+
+  /// 删除别名；不存在时返回 false
+  pub fn remove_alias(&self, name: &str) -> Result<bool, StorageError> {
+    let write_txn = self.db.read().begin_write()?;
+    let removed = write_txn.open_table(ALIASES_TABLE)?.remove(name)?.is_some();
+    write_txn.commit()?;
+
+    Ok(removed)
+  }
+
+  /// 所有别名，按名称排序
+  pub fn list_aliases(&self) -> Result<Vec<Alias>, StorageError> {
+    let read_txn = self.db.read().begin_read()?;
+    let table = read_txn.open_table(ALIASES_TABLE)?;
+
+    let mut aliases = Vec::new();
+    for entry in table.iter()? {
+      let (key, value) = entry?;
+      aliases.push(Alias {
+        name: key.value().to_string(),
+        target: value.value().to_string(),
+      });
+    }
+
+    Ok(aliases)
+  }
+
   pub fn count_commands(&self) -> Result<usize, StorageError> {
-    let read_txn = self.db.begin_read()?;
+    let read_txn = self.db.read().begin_read()?;
     let table = read_txn.open_table(COMMANDS_TABLE)?;
     Ok(table.len()? as usize)
   }
@@ -209,11 +1043,143 @@ mod tests {
       examples: vec![Example {
         description: "Example usage".to_string(),
         code: format!("{} --help", name),
+        source: None,
       }],
       content: format!("{} help content", name),
+      sections: vec![],
+      options: vec![],
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
     }
   }
 
+  #[test]
+  fn test_find_command_merges_learned() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    let mut local = create_test_command("tar", "local");
+    local.examples.push(Example {
+      description: "Extract".to_string(),
+      code: "tar -xf archive.tar".to_string(),
+      source: None,
+    });
+    db.save_command(&create_test_command("tar", "en")).unwrap();
+    db.save_command(&local).unwrap();
+    db.save_command(&create_test_command("ls", "local"))
+      .unwrap();
+
+    let merged = db.find_command("tar", "zh").unwrap().unwrap();
+    assert_eq!(merged.lang, "en");
+    // 重复的示例只保留 tldr 版本
+    assert_eq!(merged.examples.len(), 2);
+    assert_eq!(merged.examples[0].source, None);
+    assert_eq!(merged.examples[1].source.as_deref(), Some("local"));
+
+    // 只有本地学习的条目
+    let ls = db.find_command("ls", "en").unwrap().unwrap();
+    assert_eq!(ls.lang, "local");
+    assert!(db.find_command("missing", "en").unwrap().is_none());
+  }
+
+  #[test]
+  fn test_legacy_command_schema() {
+    // 加入 options 之前保存的 tldr 页面
+    let legacy = r#"{"name":"ls","description":"List directory contents","category":"common",
+      "platform":"common","lang":"en","content":"","examples":[
+      {"description":"List all files, including hidden files","code":"ls {{[-a|--all]}}"}]}"#;
+    let cmd = decode_command(legacy.as_bytes()).unwrap();
+    assert_eq!(cmd.schema, COMMAND_SCHEMA);
+    assert_eq!(cmd.options.len(), 1);
+    assert_eq!(cmd.options[0].flags, ["-a", "--all"]);
+    assert_eq!(
+      cmd.options[0].description,
+      "List all files, including hidden files"
+    );
+
+    // 当前格式的记录原样读取，本地学习的命令不补选项
+    let mut local = create_test_command("ls", "local");
+    local.examples[0].code = "ls -l".to_string();
+    let cmd = decode_command(&serde_json::to_vec(&local).unwrap()).unwrap();
+    assert!(cmd.options.is_empty());
+  }
+
+  #[test]
+  fn test_generation() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    let start = db.generation();
+    db.save_command(&create_test_command("tar", "en")).unwrap();
+    let saved = db.generation();
+    assert_ne!(saved, start);
+    // 使用统计等其他数据不影响
+    db.record_usage(UsageKind::Lookup, "tar", 1).unwrap();
+    assert_eq!(db.generation(), saved);
+    db.clear_commands().unwrap();
+    assert_ne!(db.generation(), saved);
+  }
+
+  #[test]
+  fn test_snapshot_and_compact() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    db.save_commands(&[
+      create_test_command("tar", "en"),
+      create_test_command("ls", "en"),
+    ])
+    .unwrap();
+    db.record_usage(UsageKind::Lookup, "tar", 1).unwrap();
+    db.add_favorite("git").unwrap();
+    db.append_history(&HistoryEntry::default()).unwrap();
+    db.clear_commands().unwrap();
+    db.save_command(&create_test_command("git", "en")).unwrap();
+    db.compact().unwrap();
+
+    let path = temp_dir.path().join("copy.redb");
+    db.snapshot(&path).unwrap();
+    let copy = Database::open(&path).unwrap();
+    assert_eq!(copy.count_commands().unwrap(), 1);
+    assert!(copy.get_command("git", "en").unwrap().is_some());
+    assert_eq!(copy.list_usage(UsageKind::Lookup).unwrap().len(), 1);
+    assert_eq!(copy.list_favorites().unwrap(), db.list_favorites().unwrap());
+    assert_eq!(copy.list_history(10).unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_audit_log() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    for (action, count) in [("import", 3), ("learn", 1), ("import", 2)] {
+      db.append_audit(&AuditEntry {
+        action: action.to_string(),
+        source: "cli".to_string(),
+        count,
+        ..Default::default()
+      })
+      .unwrap();
+    }
+
+    let all = db.list_audit(10, None).unwrap();
+    assert_eq!(all.iter().map(|e| e.id).collect::<Vec<_>>(), [3, 2, 1]);
+    let imports = db.list_audit(1, Some("import")).unwrap();
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].count, 2);
+
+    // 清空命令不影响审计记录，快照中不包含审计记录
+    db.clear_commands().unwrap();
+    assert_eq!(db.list_audit(10, None).unwrap().len(), 3);
+    let path = temp_dir.path().join("copy.redb");
+    db.snapshot(&path).unwrap();
+    assert!(Database::open(&path)
+      .unwrap()
+      .list_audit(10, None)
+      .unwrap()
+      .is_empty());
+  }
+
   #[test]
   fn test_database_create() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -264,6 +1230,147 @@ mod tests {
     assert!(result.is_none());
   }
 
+  #[test]
+  fn test_delete_command() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    db.save_command(&create_test_command("tar", "en")).unwrap();
+    db.save_command(&create_test_command("tar", "local"))
+      .unwrap();
+
+    let start = db.generation();
+    assert!(db.delete_command("tar", "local").unwrap());
+    assert!(db.generation() > start);
+    assert!(db.get_command("tar", "local").unwrap().is_none());
+    assert!(db.get_command("tar", "en").unwrap().is_some());
+
+    assert_eq!(db.command_langs("tar").unwrap(), ["en"]);
+    let start = db.generation();
+    assert!(!db.delete_command("tar", "local").unwrap());
+    assert_eq!(db.generation(), start);
+  }
+
+  #[test]
+  fn test_notes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    db.save_command(&create_test_command("tar", "en")).unwrap();
+
+    assert!(db.set_note("tar", "  use -z for .tgz\n").unwrap());
+    assert!(!db.set_note("tar", "use -z for .tgz").unwrap());
+    let cmd = db.find_command("tar", "en").unwrap().unwrap();
+    assert_eq!(cmd.notes.as_deref(), Some("use -z for .tgz"));
+
+    // 笔记不写入命令记录，重新导入页面后仍然保留
+    db.save_command(&cmd).unwrap();
+    assert!(db
+      .get_command("tar", "en")
+      .unwrap()
+      .unwrap()
+      .notes
+      .is_none());
+    db.clear_commands().unwrap();
+    assert_eq!(
+      db.get_note("tar").unwrap().as_deref(),
+      Some("use -z for .tgz")
+    );
+
+    let mut commands = vec![create_test_command("tar", "zh")];
+    db.attach_notes(&mut commands).unwrap();
+    assert!(commands[0].notes.is_some());
+    assert_eq!(db.list_notes().unwrap().len(), 1);
+
+    assert!(db.set_note("tar", "").unwrap());
+    assert!(db.get_note("tar").unwrap().is_none());
+  }
+
+  #[test]
+  fn test_history() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    for i in 0..MAX_HISTORY + 5 {
+      db.append_history(&HistoryEntry {
+        name: format!("cmd{}", i),
+        ..Default::default()
+      })
+      .unwrap();
+    }
+    let entries = db.list_history(usize::MAX).unwrap();
+    assert_eq!(entries.len() as u64, MAX_HISTORY);
+    assert_eq!(entries[0].name, format!("cmd{}", MAX_HISTORY + 4));
+    assert_eq!(entries.last().unwrap().name, "cmd5");
+    assert_eq!(db.list_history(2).unwrap().len(), 2);
+
+    db.clear_history().unwrap();
+    assert!(db.list_history(10).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_record_lookup() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    for _ in 0..2 {
+      db.record_lookup(&HistoryEntry {
+        name: "tar".to_string(),
+        ..Default::default()
+      })
+      .unwrap();
+    }
+    let usage = db.list_usage(UsageKind::Lookup).unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].1.count, 2);
+    assert_eq!(db.list_history(10).unwrap().len(), 2);
+  }
+
+  #[test]
+  fn test_favorites() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    assert!(db.add_favorite("tar").unwrap());
+    assert!(db.add_favorite("git").unwrap());
+    let added = db.list_favorites().unwrap()[1].added.clone();
+    assert!(!db.add_favorite("tar").unwrap());
+
+    let favorites = db.list_favorites().unwrap();
+    let names: Vec<&str> = favorites.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["git", "tar"]);
+    // 重复收藏不改变收藏时间
+    assert_eq!(favorites[1].added, added);
+
+    assert!(db.remove_favorite("git").unwrap());
+    assert!(!db.remove_favorite("git").unwrap());
+    assert_eq!(db.list_favorites().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_aliases() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+
+    assert_eq!(db.set_alias("gco", "git commit").unwrap(), None);
+    assert_eq!(
+      db.set_alias("gco", "git checkout").unwrap().as_deref(),
+      Some("git commit")
+    );
+    db.set_alias("k", "kubectl").unwrap();
+    assert_eq!(
+      db.get_alias("gco").unwrap().as_deref(),
+      Some("git checkout")
+    );
+    assert_eq!(db.get_alias("git").unwrap(), None);
+
+    let aliases = db.list_aliases().unwrap();
+    let names: Vec<&str> = aliases.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["gco", "k"]);
+
+    assert!(db.remove_alias("k").unwrap());
+    assert!(!db.remove_alias("k").unwrap());
+    assert_eq!(db.list_aliases().unwrap().len(), 1);
+  }
+
   #[test]
   fn test_clear_commands() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -292,6 +1399,8 @@ mod tests {
       command_count: 100,
       last_update: "2024-01-01".to_string(),
       languages: vec!["en".to_string(), "zh".to_string()],
+      source: Some("local-file".to_string()),
+      selected_languages: None,
     };
 
     db.save_metadata(&meta).unwrap();
@@ -303,6 +1412,57 @@ mod tests {
     assert_eq!(retrieved.command_count, 100);
   }
 
+  #[test]
+  fn test_learn_checkpoint() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("test.redb");
+    let db = Database::open(&db_path).unwrap();
+
+    assert!(db.get_learn_checkpoint().unwrap().is_none());
+
+    let checkpoint = LearnCheckpoint {
+      source: "man".to_string(),
+      section: "1".to_string(),
+      processed: vec!["ls".to_string(), "tar".to_string()],
+      failed: vec!["tar".to_string()],
+      updated_at: "2024-01-01T00:00:00Z".to_string(),
+    };
+    db.save_learn_checkpoint(&checkpoint).unwrap();
+
+    let retrieved = db.get_learn_checkpoint().unwrap().unwrap();
+    assert_eq!(retrieved.processed, ["ls", "tar"]);
+    assert_eq!(retrieved.failed, ["tar"]);
+    // 断点与元数据共用一张表，互不影响
+    assert!(db.get_metadata().unwrap().is_none());
+
+    db.clear_learn_checkpoint().unwrap();
+    assert!(db.get_learn_checkpoint().unwrap().is_none());
+  }
+
+  #[test]
+  fn test_update_history() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    assert!(db.list_update_history().unwrap().is_empty());
+
+    let run = |to: &str| UpdateRun {
+      started_at: "2024-01-01T00:00:00Z".to_string(),
+      source: "auto-update".to_string(),
+      success: true,
+      from_version: Some("2.2".to_string()),
+      to_version: Some(to.to_string()),
+      duration_ms: 10,
+      message: String::new(),
+    };
+    for to in ["2.3", "2.4", "2.5"] {
+      db.push_update_run(&run(to), 2).unwrap();
+    }
+    let history = db.list_update_history().unwrap();
+    let versions: Vec<_> = history.iter().map(|r| r.to_version.as_deref()).collect();
+    assert_eq!(versions, [Some("2.5"), Some("2.4")]);
+    assert!(db.get_metadata().unwrap().is_none());
+  }
+
   #[test]
   fn test_multilang_commands() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,539 @@
+//! 与远程 rtfm 服务器同步命令、收藏和笔记
+//!
+//! 双方先交换清单（每个命令的内容哈希），只传输哈希不同的命令。
+//! 接收方按同步策略处理冲突：
+//! - lww：保留较新的版本（按学习时间，没有时间的 tldr 页面视为最旧，时间相同时以传入的为准）
+//! - merge：以较新的版本为准，合并另一方独有的示例与章节
+//!
+//! 收藏和笔记数据量小，整体交换：收藏只增加不删除；笔记没有修改时间，lww 时以传入的为准，
+//! merge 时把双方不同的内容拼在一起。标签来自导入的速查表，是命令描述的一部分，随命令同步。
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::storage::{Command, Database, Favorite, Note, StorageError};
+
+/// 每次请求传输的命令数
+const BATCH_SIZE: usize = 200;
+
+/// 冲突处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncPolicy {
+  /// Keep the newer version
+  #[default]
+  Lww,
+  /// Keep the newer version and add examples/sections only the other side has
+  Merge,
+}
+
+impl SyncPolicy {
+  pub fn parse(s: &str) -> Option<Self> {
+    match s {
+      "lww" => Some(SyncPolicy::Lww),
+      "merge" => Some(SyncPolicy::Merge),
+      _ => None,
+    }
+  }
+}
+
+/// Command identity and content hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SyncEntry {
+  /// Command name
+  pub name: String,
+  /// Language code
+  pub lang: String,
+  /// SHA-256 of the stored command
+  pub hash: String,
+}
+
+/// Command identity
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncKey {
+  /// Command name
+  pub name: String,
+  /// Language code
+  pub lang: String,
+}
+
+/// Result of applying synced commands
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SyncReport {
+  /// Commands that did not exist before
+  pub added: usize,
+  /// Existing commands that were replaced or merged
+  pub updated: usize,
+  /// Incoming commands that lost to the existing version
+  pub kept: usize,
+  /// Favorites that did not exist before
+  #[serde(default)]
+  pub favorites: usize,
+  /// Notes that were added or changed
+  #[serde(default)]
+  pub notes: usize,
+}
+
+impl SyncReport {
+  pub fn changed(&self) -> usize {
+    self.added + self.updated + self.favorites + self.notes
+  }
+
+  pub fn add(&mut self, other: &SyncReport) {
+    self.added += other.added;
+    self.updated += other.updated;
+    self.kept += other.kept;
+    self.favorites += other.favorites;
+    self.notes += other.notes;
+  }
+}
+
+/// Favorites and notes, exchanged as a whole
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PersonalData {
+  /// Favorite commands
+  #[serde(default)]
+  pub favorites: Vec<Favorite>,
+  /// Notes on commands
+  #[serde(default)]
+  pub notes: Vec<Note>,
+}
+
+impl PersonalData {
+  pub fn collect(db: &Database) -> Result<Self, StorageError> {
+    Ok(Self {
+      favorites: db.list_favorites()?,
+      notes: db.list_notes()?,
+    })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.favorites.is_empty() && self.notes.is_empty()
+  }
+
+  /// 对方没有的收藏和内容不同的笔记
+  pub fn missing_from(&self, other: &PersonalData) -> PersonalData {
+    PersonalData {
+      favorites: self
+        .favorites
+        .iter()
+        .filter(|f| !other.favorites.iter().any(|o| o.name == f.name))
+        .cloned()
+        .collect(),
+      notes: self
+        .notes
+        .iter()
+        .filter(|n| !other.notes.contains(n))
+        .cloned()
+        .collect(),
+    }
+  }
+}
+
+/// 命令内容的哈希
+pub fn content_hash(cmd: &Command) -> String {
+  let json = serde_json::to_vec(cmd).unwrap_or_default();
+  format!("{:x}", Sha256::digest(json))
+}
+
+/// 本地命令清单，lang 为空表示全部语言
+pub fn manifest(db: &Database, lang: Option<&str>) -> Result<Vec<SyncEntry>, StorageError> {
+  let commands = match lang {
+    Some(lang) => db.get_all_commands(lang)?,
+    None => db.list_all_commands()?,
+  };
+  let mut entries: Vec<SyncEntry> = commands
+    .iter()
+    .map(|cmd| SyncEntry {
+      name: cmd.name.clone(),
+      lang: cmd.lang.clone(),
+      hash: content_hash(cmd),
+    })
+    .collect();
+  entries.sort_by(|a, b| (&a.lang, &a.name).cmp(&(&b.lang, &b.name)));
+  Ok(entries)
+}
+
+/// 读取指定的命令（不存在的跳过）
+pub fn fetch(db: &Database, keys: &[SyncKey]) -> Result<Vec<Command>, StorageError> {
+  let mut commands = Vec::new();
+  for key in keys {
+    if let Some(cmd) = db.get_command(&key.name, &key.lang)? {
+      commands.push(cmd);
+    }
+  }
+  Ok(commands)
+}
+
+/// 按策略保存传入的命令，同时返回保存的命令（供调用方增量索引）
+pub fn apply(
+  db: &Database,
+  incoming: Vec<Command>,
+  policy: SyncPolicy,
+) -> Result<(SyncReport, Vec<Command>), StorageError> {
+  let mut report = SyncReport::default();
+  let mut changed = Vec::new();
+  for cmd in incoming {
+    let existing = db.get_command(&cmd.name, &cmd.lang)?;
+    match resolve(existing.as_ref(), cmd, policy) {
+      Some(cmd) => {
+        if existing.is_some() {
+          report.updated += 1;
+        } else {
+          report.added += 1;
+        }
+        changed.push(cmd);
+      }
+      None => report.kept += 1,
+    }
+  }
+  db.save_commands(&changed)?;
+  Ok((report, changed))
+}
+
+/// 按策略保存传入的收藏和笔记，计入 report；返回笔记有变化的命令名（笔记参与搜索，需要重新索引）
+pub fn apply_personal(
+  db: &Database,
+  incoming: &PersonalData,
+  policy: SyncPolicy,
+  report: &mut SyncReport,
+) -> Result<Vec<String>, StorageError> {
+  let mut noted = Vec::new();
+  for favorite in &incoming.favorites {
+    if db.add_favorite(&favorite.name)? {
+      report.favorites += 1;
+    }
+  }
+  for note in &incoming.notes {
+    let existing = db.get_note(&note.name)?;
+    if let Some(text) = resolve_note(existing.as_deref(), &note.text, policy) {
+      if db.set_note(&note.name, &text)? {
+        report.notes += 1;
+        noted.push(note.name.clone());
+      }
+    }
+  }
+  Ok(noted)
+}
+
+/// 解决笔记冲突，返回需要保存的内容；None 表示保留现有笔记
+fn resolve_note(existing: Option<&str>, incoming: &str, policy: SyncPolicy) -> Option<String> {
+  let Some(existing) = existing else {
+    return Some(incoming.to_string());
+  };
+  if existing.contains(incoming) {
+    return None;
+  }
+  match policy {
+    SyncPolicy::Lww => Some(incoming.to_string()),
+    SyncPolicy::Merge if incoming.contains(existing) => Some(incoming.to_string()),
+    SyncPolicy::Merge => Some(format!("{}\n\n{}", existing, incoming)),
+  }
+}
+
+/// 解决冲突，返回需要保存的命令；None 表示保留现有版本
+fn resolve(existing: Option<&Command>, incoming: Command, policy: SyncPolicy) -> Option<Command> {
+  let Some(existing) = existing else {
+    return Some(incoming);
+  };
+  let incoming_newer = incoming.updated_at() >= existing.updated_at();
+  let resolved = match (policy, incoming_newer) {
+    (SyncPolicy::Lww, true) => incoming,
+    (SyncPolicy::Lww, false) => return None,
+    (SyncPolicy::Merge, true) => merge(incoming, existing),
+    (SyncPolicy::Merge, false) => merge(existing.clone(), &incoming),
+  };
+  (content_hash(&resolved) != content_hash(existing)).then_some(resolved)
+}
+
+/// 在 base 上补充 other 独有的示例与章节
+fn merge(mut base: Command, other: &Command) -> Command {
+  for example in &other.examples {
+    if !base.examples.iter().any(|e| e.code == example.code) {
+      base.examples.push(example.clone());
+    }
+  }
+  for section in &other.sections {
+    if base.section(&section.title).is_none() {
+      base.sections.push(section.clone());
+    }
+  }
+  base
+}
+
+/// 请求远程 rtfm 服务器的客户端
+pub struct SyncClient {
+  client: reqwest::Client,
+  base: String,
+}
+
+#[derive(Deserialize)]
+struct RemoteError {
+  error: String,
+}
+
+#[derive(Serialize)]
+struct FetchBody<'a> {
+  keys: &'a [SyncKey],
+}
+
+#[derive(Serialize)]
+struct PushBody<'a> {
+  commands: &'a [Command],
+  favorites: &'a [Favorite],
+  notes: &'a [Note],
+  policy: SyncPolicy,
+}
+
+impl SyncClient {
+  /// remote: 服务器地址，如 http://team-rtfm:3030（可带 /api 后缀）
+  /// token: 服务器的 API 密钥或多用户模式的用户令牌，每个请求都以 Bearer 发送
+  pub fn new(remote: &str, user_agent: &str, token: Option<&str>) -> anyhow::Result<Self> {
+    let base = remote.trim().trim_end_matches('/');
+    let base = base.strip_suffix("/api").unwrap_or(base);
+    if !base.starts_with("http://") && !base.starts_with("https://") {
+      anyhow::bail!("Remote '{}' must start with http:// or https://", remote);
+    }
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = token {
+      let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token.trim()))
+        .map_err(|_| anyhow::anyhow!("Sync token contains invalid characters"))?;
+      value.set_sensitive(true);
+      headers.insert(reqwest::header::AUTHORIZATION, value);
+    }
+    Ok(Self {
+      client: reqwest::Client::builder()
+        .user_agent(user_agent)
+        .default_headers(headers)
+        .build()?,
+      base: format!("{}/api/sync", base),
+    })
+  }
+
+  pub async fn manifest(&self, lang: Option<&str>) -> anyhow::Result<Vec<SyncEntry>> {
+    let mut request = self.client.get(format!("{}/manifest", self.base));
+    if let Some(lang) = lang {
+      request = request.query(&[("lang", lang)]);
+    }
+    send(request).await
+  }
+
+  pub async fn fetch(&self, keys: &[SyncKey]) -> anyhow::Result<Vec<Command>> {
+    let mut commands = Vec::new();
+    for chunk in keys.chunks(BATCH_SIZE) {
+      let request = self
+        .client
+        .post(format!("{}/fetch", self.base))
+        .json(&FetchBody { keys: chunk });
+      commands.extend(send::<Vec<Command>>(request).await?);
+    }
+    Ok(commands)
+  }
+
+  /// 远程的收藏和笔记；早于收藏和笔记同步的服务器没有这个接口，视为空
+  pub async fn personal(&self) -> anyhow::Result<PersonalData> {
+    let response = self
+      .client
+      .get(format!("{}/personal", self.base))
+      .send()
+      .await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+      return Ok(PersonalData::default());
+    }
+    read(response).await
+  }
+
+  /// 收藏和笔记随第一批命令发送
+  pub async fn push(
+    &self,
+    commands: &[Command],
+    personal: &PersonalData,
+    policy: SyncPolicy,
+  ) -> anyhow::Result<SyncReport> {
+    let mut report = SyncReport::default();
+    let mut chunks: Vec<&[Command]> = commands.chunks(BATCH_SIZE).collect();
+    if chunks.is_empty() {
+      chunks.push(&[]);
+    }
+    for (i, chunk) in chunks.into_iter().enumerate() {
+      let (favorites, notes) = if i == 0 {
+        (personal.favorites.as_slice(), personal.notes.as_slice())
+      } else {
+        (&[][..], &[][..])
+      };
+      let request = self
+        .client
+        .post(format!("{}/push", self.base))
+        .json(&PushBody {
+          commands: chunk,
+          favorites,
+          notes,
+          policy,
+        });
+      report.add(&send::<SyncReport>(request).await?);
+    }
+    Ok(report)
+  }
+}
+
+/// 发送请求
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> anyhow::Result<T> {
+  read(request.send().await?).await
+}
+
+/// 读取响应；服务器以 {"error": ...} 报告失败（先检查，字段都有默认值的类型也能解析错误响应）
+async fn read<T: DeserializeOwned>(response: reqwest::Response) -> anyhow::Result<T> {
+  let status = response.status();
+  let body = response.bytes().await?;
+  if let Ok(e) = serde_json::from_slice::<RemoteError>(&body) {
+    anyhow::bail!("Remote error: {}", e.error);
+  }
+  if let Ok(value) = serde_json::from_slice::<T>(&body) {
+    return Ok(value);
+  }
+  if !status.is_success() {
+    anyhow::bail!("Remote returned {}", status);
+  }
+  anyhow::bail!("Unexpected response from remote (is it an rtfm server with sync support?)")
+}
+
+/// 对比两份清单，返回 from 中哈希不同或 to 中没有的命令
+pub fn diff(from: &[SyncEntry], to: &[SyncEntry]) -> Vec<SyncKey> {
+  let known: HashMap<(&str, &str), &str> = to
+    .iter()
+    .map(|e| ((e.lang.as_str(), e.name.as_str()), e.hash.as_str()))
+    .collect();
+  from
+    .iter()
+    .filter(|e| known.get(&(e.lang.as_str(), e.name.as_str())) != Some(&e.hash.as_str()))
+    .map(|e| SyncKey {
+      name: e.name.clone(),
+      lang: e.lang.clone(),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{Example, SourceInfo, COMMAND_SCHEMA};
+
+  fn learned(code: &str, learned_at: &str) -> Command {
+    Command {
+      name: "tool".to_string(),
+      description: "a tool".to_string(),
+      category: "local".to_string(),
+      platform: "common".to_string(),
+      lang: "local".to_string(),
+      examples: vec![Example {
+        description: code.to_string(),
+        code: code.to_string(),
+        source: None,
+      }],
+      content: String::new(),
+      sections: Vec::new(),
+      options: Vec::new(),
+      source_info: Some(SourceInfo {
+        source: "--help".to_string(),
+        learned_at: learned_at.to_string(),
+        ..Default::default()
+      }),
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    }
+  }
+
+  #[test]
+  fn test_resolve() {
+    let old = learned("tool -a", "2024-01-01T00:00:00Z");
+    let new = learned("tool -b", "2024-06-01T00:00:00Z");
+
+    // 不存在时直接添加
+    assert!(resolve(None, old.clone(), SyncPolicy::Lww).is_some());
+    // 相同内容无需保存
+    assert!(resolve(Some(&old), old.clone(), SyncPolicy::Merge).is_none());
+
+    // lww：较新的一方获胜
+    let resolved = resolve(Some(&old), new.clone(), SyncPolicy::Lww).unwrap();
+    assert_eq!(resolved.examples[0].code, "tool -b");
+    assert!(resolve(Some(&new), old.clone(), SyncPolicy::Lww).is_none());
+
+    // merge：较新的版本在前，补充另一方的示例
+    let merged = resolve(Some(&new), old.clone(), SyncPolicy::Merge).unwrap();
+    let codes: Vec<&str> = merged.examples.iter().map(|e| e.code.as_str()).collect();
+    assert_eq!(codes, ["tool -b", "tool -a"]);
+    assert_eq!(
+      merged.source_info.unwrap().learned_at,
+      "2024-06-01T00:00:00Z"
+    );
+  }
+
+  #[test]
+  fn test_resolve_note() {
+    assert_eq!(
+      resolve_note(None, "use -z", SyncPolicy::Lww).as_deref(),
+      Some("use -z")
+    );
+    assert!(resolve_note(Some("use -z"), "use -z", SyncPolicy::Lww).is_none());
+    // 笔记没有时间，lww 以传入的为准
+    assert_eq!(
+      resolve_note(Some("use -z"), "use -j", SyncPolicy::Lww).as_deref(),
+      Some("use -j")
+    );
+    // merge 拼接不同的内容，已包含的不重复
+    assert_eq!(
+      resolve_note(Some("use -z"), "use -j", SyncPolicy::Merge).as_deref(),
+      Some("use -z\n\nuse -j")
+    );
+    assert!(resolve_note(Some("use -z\n\nuse -j"), "use -j", SyncPolicy::Merge).is_none());
+    assert_eq!(
+      resolve_note(Some("use -z"), "use -z\n\nuse -j", SyncPolicy::Merge).as_deref(),
+      Some("use -z\n\nuse -j")
+    );
+  }
+
+  #[test]
+  fn test_missing_from() {
+    let favorite = |name: &str| Favorite {
+      name: name.to_string(),
+      added: "2024-01-01T00:00:00Z".to_string(),
+    };
+    let note = |name: &str, text: &str| Note {
+      name: name.to_string(),
+      text: text.to_string(),
+    };
+    let local = PersonalData {
+      favorites: vec![favorite("tar"), favorite("git")],
+      notes: vec![note("tar", "use -z"), note("git", "rebase often")],
+    };
+    let remote = PersonalData {
+      // 收藏时间不同不算差异
+      favorites: vec![Favorite {
+        added: "2024-06-01T00:00:00Z".to_string(),
+        ..favorite("tar")
+      }],
+      notes: vec![note("tar", "use -z"), note("git", "merge often")],
+    };
+    let missing = local.missing_from(&remote);
+    assert_eq!(missing.favorites, [favorite("git")]);
+    assert_eq!(missing.notes, [note("git", "rebase often")]);
+    assert!(local.missing_from(&local).is_empty());
+  }
+
+  #[test]
+  fn test_diff() {
+    let entry = |name: &str, hash: &str| SyncEntry {
+      name: name.to_string(),
+      lang: "en".to_string(),
+      hash: hash.to_string(),
+    };
+    let local = [entry("tar", "1"), entry("git", "2"), entry("ls", "3")];
+    let remote = [entry("tar", "1"), entry("git", "x")];
+    let names: Vec<String> = diff(&local, &remote).into_iter().map(|k| k.name).collect();
+    assert_eq!(names, ["git", "ls"]);
+  }
+}
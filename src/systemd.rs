@@ -0,0 +1,267 @@
+//! systemd 集成（rtfm serve --systemd、rtfm install-service）
+//!
+//! --systemd 时：由 rtfm.socket 启动（socket activation）则使用 systemd 传入的监听套接字，
+//! 否则按 --port/--bind 监听；就绪和停止时通过 sd_notify 通知 systemd（Type=notify）；
+//! 日志写入 journald 而不是日志文件。install-service 和 serve --install-service 生成指向当前数据目录的 unit 文件，
+//! serve --uninstall-service 删除它们。
+
+use std::path::{Path, PathBuf};
+
+/// 生成的 unit 文件首行注释
+const GENERATED_MARKER: &str = "# Generated by rtfm install-service";
+
+/// unit 名称
+pub const SERVICE_NAME: &str = "rtfm.service";
+pub const SOCKET_NAME: &str = "rtfm.socket";
+
+/// systemd 传入的第一个 TCP 监听套接字；不是由 socket activation 启动时为 None
+#[cfg(unix)]
+pub fn activated_listener() -> anyhow::Result<Option<std::net::TcpListener>> {
+  let mut fds = listenfd::ListenFd::from_env();
+  let listener = fds.take_tcp_listener(0)?;
+  if let Some(listener) = &listener {
+    // tokio 要求非阻塞套接字
+    listener.set_nonblocking(true)?;
+  }
+  Ok(listener)
+}
+
+#[cfg(not(unix))]
+pub fn activated_listener() -> anyhow::Result<Option<std::net::TcpListener>> {
+  Ok(None)
+}
+
+/// 通知 systemd 服务已就绪；不在 systemd 下运行时不做任何事
+pub fn notify_ready() {
+  #[cfg(unix)]
+  if let Err(e) = sd_notify::notify(false, &[sd_notify::NotifyState::Ready]) {
+    tracing::warn!("sd_notify READY failed: {}", e);
+  }
+}
+
+/// 通知 systemd 服务正在停止
+pub fn notify_stopping() {
+  #[cfg(unix)]
+  if let Err(e) = sd_notify::notify(true, &[sd_notify::NotifyState::Stopping]) {
+    tracing::warn!("sd_notify STOPPING failed: {}", e);
+  }
+}
+
+/// journald 的套接字
+#[cfg(unix)]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// 本机是否运行 journald
+pub fn journald_available() -> bool {
+  #[cfg(unix)]
+  return Path::new(JOURNALD_SOCKET).exists();
+  #[cfg(not(unix))]
+  false
+}
+
+/// 写入 journald 的日志层；无法创建时返回 None
+#[cfg(unix)]
+pub fn journald_layer<S>() -> Option<impl tracing_subscriber::Layer<S>>
+where
+  S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+  match tracing_journald::layer() {
+    Ok(layer) => Some(layer.with_syslog_identifier("rtfm".to_string())),
+    Err(e) => {
+      eprintln!("Failed to connect to journald, logging to files: {}", e);
+      None
+    }
+  }
+}
+
+#[cfg(not(unix))]
+pub fn journald_layer<S: tracing::Subscriber>() -> Option<impl tracing_subscriber::Layer<S>> {
+  None::<tracing_subscriber::layer::Identity>
+}
+
+/// install-service 的选项
+#[derive(Debug, Clone)]
+pub struct ServiceOptions {
+  /// rtfm 可执行文件
+  pub exe: PathBuf,
+  pub data_dir: PathBuf,
+  /// 使用当前目录下的 rtfm.toml 时的工作目录
+  pub working_dir: Option<PathBuf>,
+  pub bind: String,
+  pub port: u16,
+  pub read_only: bool,
+  /// 同时生成 rtfm.socket，由 systemd 监听端口
+  pub socket: bool,
+  /// 系统服务（multi-user.target），否则为用户服务（default.target）
+  pub system: bool,
+}
+
+/// 生成 rtfm.service
+pub fn service_unit(options: &ServiceOptions) -> String {
+  let mut unit = format!(
+    "{}\n[Unit]\nDescription=RTFM cheatsheet HTTP server\n",
+    GENERATED_MARKER
+  );
+  if options.socket {
+    unit.push_str(&format!("Requires={0}\nAfter={0}\n", SOCKET_NAME));
+  } else {
+    unit.push_str("After=network.target\n");
+  }
+
+  let mut exec = format!(
+    "{} serve --systemd --bind {} --port {}",
+    quote(&options.exe.display().to_string()),
+    options.bind,
+    options.port
+  );
+  if options.read_only {
+    exec.push_str(" --read-only");
+  }
+  unit.push_str("\n[Service]\nType=notify\n");
+  unit.push_str(&format!("ExecStart={}\n", exec));
+  unit.push_str(&format!(
+    "Environment={}\n",
+    quote(&format!("RTFM_DATA_DIR={}", options.data_dir.display()))
+  ));
+  if let Some(dir) = &options.working_dir {
+    unit.push_str(&format!(
+      "WorkingDirectory={}\n",
+      quote(&dir.display().to_string())
+    ));
+  }
+  unit.push_str("Restart=on-failure\n");
+
+  if !options.socket {
+    unit.push_str(&format!(
+      "\n[Install]\nWantedBy={}\n",
+      install_target(options)
+    ));
+  }
+  unit
+}
+
+/// 生成 rtfm.socket
+pub fn socket_unit(options: &ServiceOptions) -> String {
+  format!(
+    "{}\n[Unit]\nDescription=RTFM cheatsheet HTTP server socket\n\n[Socket]\nListenStream={}:{}\n\n[Install]\nWantedBy=sockets.target\n",
+    GENERATED_MARKER,
+    // IPv6 地址需要方括号
+    if options.bind.contains(':') {
+      format!("[{}]", options.bind)
+    } else {
+      options.bind.clone()
+    },
+    options.port
+  )
+}
+
+fn install_target(options: &ServiceOptions) -> &'static str {
+  if options.system {
+    "multi-user.target"
+  } else {
+    "default.target"
+  }
+}
+
+/// 含空格的值加引号，systemd 按 shell 规则拆分 ExecStart 和 Environment
+fn quote(value: &str) -> String {
+  if value.contains([' ', '"', '\\']) {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+  } else {
+    value.to_string()
+  }
+}
+
+/// unit 文件目录：系统服务为 /etc/systemd/system，用户服务为 ~/.config/systemd/user
+pub fn unit_dir(system: bool) -> Option<PathBuf> {
+  if system {
+    Some(PathBuf::from("/etc/systemd/system"))
+  } else {
+    dirs::config_dir().map(|dir| dir.join("systemd").join("user"))
+  }
+}
+
+/// 写入 unit 文件，返回写入的路径
+/// 已存在且不是 rtfm 生成的文件不会被覆盖
+pub fn install(dir: &Path, options: &ServiceOptions) -> anyhow::Result<Vec<PathBuf>> {
+  let mut units = vec![(SERVICE_NAME, service_unit(options))];
+  if options.socket {
+    units.push((SOCKET_NAME, socket_unit(options)));
+  }
+  for (name, _) in &units {
+    let path = dir.join(name);
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+      if !existing.starts_with(GENERATED_MARKER) {
+        anyhow::bail!("{} exists and was not written by rtfm", path.display());
+      }
+    }
+  }
+
+  std::fs::create_dir_all(dir)?;
+  let mut written = Vec::new();
+  for (name, content) in units {
+    let path = dir.join(name);
+    std::fs::write(&path, content)?;
+    written.push(path);
+  }
+  Ok(written)
+}
+
+/// 删除 rtfm 生成的 unit 文件，返回删除的路径
+/// 存在但不是 rtfm 生成的文件时不删除任何文件
+pub fn uninstall(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+  let paths: Vec<PathBuf> = [SERVICE_NAME, SOCKET_NAME]
+    .iter()
+    .map(|name| dir.join(name))
+    .filter(|path| path.exists())
+    .collect();
+  for path in &paths {
+    if !std::fs::read_to_string(path)?.starts_with(GENERATED_MARKER) {
+      anyhow::bail!("{} was not written by rtfm", path.display());
+    }
+  }
+  for path in &paths {
+    std::fs::remove_file(path)?;
+  }
+  Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_units() {
+    let mut options = ServiceOptions {
+      exe: PathBuf::from("/usr/local/bin/rtfm"),
+      data_dir: PathBuf::from("/home/me/My Data/rtfm"),
+      working_dir: None,
+      bind: "127.0.0.1".to_string(),
+      port: 3030,
+      read_only: true,
+      socket: false,
+      system: false,
+    };
+    let service = service_unit(&options);
+    assert!(service.starts_with(GENERATED_MARKER));
+    assert!(service.contains(
+      "ExecStart=/usr/local/bin/rtfm serve --systemd --bind 127.0.0.1 --port 3030 --read-only\n"
+    ));
+    assert!(service.contains("Environment=\"RTFM_DATA_DIR=/home/me/My Data/rtfm\"\n"));
+    assert!(service.contains("WantedBy=default.target\n"));
+
+    options.socket = true;
+    assert!(service_unit(&options).contains("Requires=rtfm.socket\n"));
+    assert!(!service_unit(&options).contains("[Install]"));
+    assert!(socket_unit(&options).contains("ListenStream=127.0.0.1:3030\n"));
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    assert_eq!(install(temp_dir.path(), &options).unwrap().len(), 2);
+    assert!(install(temp_dir.path(), &options).is_ok());
+    assert_eq!(uninstall(temp_dir.path()).unwrap().len(), 2);
+    assert!(uninstall(temp_dir.path()).unwrap().is_empty());
+    std::fs::write(temp_dir.path().join(SERVICE_NAME), "[Unit]\n").unwrap();
+    assert!(install(temp_dir.path(), &options).is_err());
+    assert!(uninstall(temp_dir.path()).is_err());
+  }
+}
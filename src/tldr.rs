@@ -0,0 +1,137 @@
+//! tldr 客户端规范兼容模式（--tldr-compat、ui.tldr_compat）
+//!
+//! 按 tldr 客户端规范查找和显示页面，可以替代脚本中的 tldr 客户端：多个参数用 `-` 连接并转为小写，
+//! 只按名称精确查找（不做全文检索、不解析别名），找不到页面时退出码为 1。
+//! 平台按规范的顺序解析：指定的平台（默认为当前系统）、common、其他平台；
+//! 数据库中同名页面只保留一个平台的版本，显示其他平台的页面时在 stderr 说明。
+//! 显示时去掉占位符的花括号并用下划线标出，`\{\{` `\}\}` 显示为原样的花括号；
+//! stdout 不是终端或设置了 NO_COLOR 时不输出颜色。
+
+use std::io::IsTerminal;
+
+use crate::placeholder;
+use crate::storage::Command;
+
+/// 规范中的页面名：多个参数用 `-` 连接，转为小写
+pub fn page_name(query: &str) -> String {
+  query
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join("-")
+    .to_lowercase()
+}
+
+/// 页面的平台是否在解析顺序的前两位（指定的平台或 common）
+pub fn is_preferred_platform(page: &str, platform: &str) -> bool {
+  page.is_empty() || page == platform || page == "common"
+}
+
+/// 是否输出颜色
+pub fn use_color() -> bool {
+  std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// 按 tldr 客户端的格式显示页面
+pub fn render(cmd: &Command, color: bool) -> String {
+  let style = |code: &str, text: &str| {
+    if color {
+      format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+      text.to_string()
+    }
+  };
+
+  // tldr 页面的标题可以含空格（如 git checkout）
+  let title = match cmd
+    .content
+    .lines()
+    .next()
+    .and_then(|l| l.strip_prefix("# "))
+  {
+    Some(title) if !quoted_lines(cmd).is_empty() => title.trim(),
+    _ => cmd.name.as_str(),
+  };
+  let mut out = format!("\n  {}\n\n", style("1", title));
+  for line in description_lines(cmd) {
+    out.push_str(&format!("  {}\n", line));
+  }
+  for example in &cmd.examples {
+    out.push_str(&format!(
+      "\n  {}\n",
+      style("32", &format!("- {}:", example.description))
+    ));
+    // 占位符下划线，其余代码为红色
+    let code = if color {
+      format!(
+        "\x1b[31m{}\x1b[0m",
+        placeholder::highlight(&example.code, "\x1b[4;34m", "\x1b[0;31m")
+      )
+    } else {
+      placeholder::highlight(&example.code, "", "")
+    };
+    out.push_str(&format!("    {}\n", code));
+  }
+  out.push('\n');
+  out
+}
+
+/// 描述行：tldr 页面取原文中的引用行（含 More information），其他命令取描述
+fn description_lines(cmd: &Command) -> Vec<String> {
+  let quoted = quoted_lines(cmd);
+  if quoted.is_empty() {
+    vec![cmd.description.clone()]
+  } else {
+    quoted
+  }
+}
+
+/// tldr 页面原文中的引用行，去掉链接的尖括号；不是 tldr 页面时为空
+fn quoted_lines(cmd: &Command) -> Vec<String> {
+  if !cmd.content.starts_with("# ") {
+    return Vec::new();
+  }
+  cmd
+    .content
+    .lines()
+    .filter_map(|line| line.strip_prefix("> "))
+    .map(|line| line.replace(['<', '>'], ""))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{Example, COMMAND_SCHEMA};
+
+  #[test]
+  fn test_render() {
+    let cmd = Command {
+      name: "tar".to_string(),
+      description: "Archiving utility.".to_string(),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: vec![Example {
+        description: "Create an archive".to_string(),
+        code: r"tar cf {{target.tar}} {{file1}} \{\{literal\}\}".to_string(),
+        source: None,
+      }],
+      content:
+        "# tar\n\n> Archiving utility.\n> More information: <https://www.gnu.org/software/tar>.\n"
+          .to_string(),
+      sections: vec![],
+      options: vec![],
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    };
+
+    assert_eq!(
+      render(&cmd, false),
+      "\n  tar\n\n  Archiving utility.\n  More information: https://www.gnu.org/software/tar.\n\n  \
+       - Create an archive:\n    tar cf target.tar file1 {{literal}}\n\n"
+    );
+    assert!(render(&cmd, true).contains("\x1b[4;34mtarget.tar\x1b[0;31m"));
+    assert_eq!(page_name("Git  Checkout"), "git-checkout");
+  }
+}
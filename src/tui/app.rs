@@ -1,13 +1,24 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use lru::LruCache;
 use parking_lot::Mutex;
-use tokio::sync::RwLock;
+use ratatui::layout::Rect;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::task::JoinHandle;
 
+use crate::alias;
 use crate::config::AppConfig;
-use crate::search::{SearchEngine, SearchResult};
-use crate::storage::Database;
+use crate::i18n::t;
+use crate::search::{
+  Platform, QueryFilters, SearchEngine, SearchError, SearchResponse, SearchResult,
+};
+use crate::storage::{Command, Database, Example, HistoryEntry};
+
+use super::keymap::Keymap;
 
 /// 日志缓冲区（线程安全）
 pub type LogBuffer = Arc<Mutex<VecDeque<String>>>;
@@ -17,6 +28,44 @@ pub fn create_log_buffer(size: usize) -> LogBuffer {
   Arc::new(Mutex::new(VecDeque::with_capacity(size)))
 }
 
+/// 历史面板列出的条数
+const HISTORY_SIZE: usize = 100;
+
+/// 命令详情缓存的条目数
+const DETAIL_CACHE_SIZE: usize = 128;
+
+/// 命令详情缓存，键为 (命令名, 语言)
+/// 渲染时每帧都会取当前命令的详情，缓存避免反复读取数据库和反序列化；
+/// 数据库中的命令变化（generation 改变）时整体失效
+struct DetailCache {
+  generation: u64,
+  entries: LruCache<(String, String), Option<String>>,
+}
+
+/// 运行示例时标准输出和标准错误各自最多保留的字节数，超出后结束命令
+const RUN_OUTPUT_LIMIT: usize = 256 * 1024;
+
+/// 运行示例的时间上限，超时后结束命令
+const RUN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 加载动画的帧
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// 加载动画每帧的时长
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// 后台搜索的结果
+struct SearchOutcome {
+  /// 发起搜索时的序号，已被后来的输入取代的结果丢弃
+  generation: u64,
+  /// 别名指向的命令，排在最前
+  target: Option<String>,
+  /// 查询中的筛选条件
+  filters: QueryFilters,
+  /// 搜索结果，以及是否改用了模糊搜索
+  result: Result<(SearchResponse, bool), SearchError>,
+}
+
 /// 焦点位置
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Focus {
@@ -25,6 +74,119 @@ pub enum Focus {
   Detail,
 }
 
+/// 上一帧各区域在屏幕上的位置，鼠标事件据此判断点中的区域
+/// Modern 风格只有一个结果区，记在 detail 中
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaneAreas {
+  /// 搜索框
+  pub search: Rect,
+  /// 结果列表（Classic）
+  pub list: Rect,
+  /// 详情（Classic）或结果区（Modern）
+  pub detail: Rect,
+  /// 历史弹窗
+  pub history: Rect,
+  /// 填写示例弹窗
+  pub fill: Rect,
+  /// 运行示例弹窗
+  pub run: Rect,
+}
+
+/// 在详情中拖动选中的文字，坐标为屏幕位置（列, 行）
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+  /// 选区限制在其中的区域（详情内容区，不含边框）
+  pub area: Rect,
+  /// 按下鼠标的位置
+  pub anchor: (u16, u16),
+  /// 当前拖到的位置
+  pub head: (u16, u16),
+  /// 上一帧选中的文字
+  pub text: String,
+}
+
+impl Selection {
+  /// 按阅读顺序排列的起点和终点
+  pub fn range(&self) -> ((u16, u16), (u16, u16)) {
+    let (a, h) = (self.anchor, self.head);
+    if (a.1, a.0) <= (h.1, h.0) {
+      (a, h)
+    } else {
+      (h, a)
+    }
+  }
+
+  /// 屏幕位置是否在选区内
+  pub fn contains(&self, x: u16, y: u16) -> bool {
+    let (start, end) = self.range();
+    (y, x) >= (start.1, start.0) && (y, x) <= (end.1, end.0)
+  }
+}
+
+/// 填写示例占位符的弹窗：先选择示例，再依次填写占位符
+#[derive(Debug, Clone)]
+pub struct Fill {
+  /// 命令的示例
+  pub examples: Vec<Example>,
+  /// 选中的示例
+  pub selected: usize,
+  /// 选定示例后需要填写的占位符，选择示例时为空
+  pub names: Vec<String>,
+  /// 已填写的值，与 names 对应
+  pub values: Vec<String>,
+  /// 填好后运行（tui.allow_execute），否则复制
+  pub run: bool,
+}
+
+impl Fill {
+  /// 是否在填写占位符（否则在选择示例）
+  pub fn editing(&self) -> bool {
+    !self.names.is_empty()
+  }
+
+  /// 选中示例按当前填写的值展开
+  pub fn preview(&self) -> String {
+    self
+      .examples
+      .get(self.selected)
+      .map(|e| crate::placeholder::fill(&e.code, &self.values))
+      .unwrap_or_default()
+  }
+}
+
+/// 运行示例弹窗的阶段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunState {
+  /// 等待确认
+  Confirm,
+  /// 正在运行
+  Running,
+  /// 已结束：标准输出和标准错误，以及退出状态
+  Done { output: String, status: String },
+}
+
+/// 运行示例的弹窗：确认后在子 shell 中运行，输出可以滚动查看
+pub struct Run {
+  /// 要运行的命令
+  pub command: String,
+  pub state: RunState,
+  /// 输出的滚动位置
+  pub scroll: u16,
+  /// 输出最大可滚动行数
+  pub max_scroll: u16,
+  /// 运行中的命令，关闭弹窗时结束（子进程随之被杀掉）
+  task: Option<JoinHandle<()>>,
+  rx: Option<oneshot::Receiver<RunState>>,
+}
+
+impl Drop for Run {
+  fn drop(&mut self) {
+    if let Some(task) = self.task.take() {
+      task.abort();
+    }
+  }
+}
+
 /// 界面风格
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum UiStyle {
@@ -41,6 +203,13 @@ impl UiStyle {
     }
   }
 
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      UiStyle::Modern => "modern",
+      UiStyle::Classic => "classic",
+    }
+  }
+
   pub fn toggle(&self) -> Self {
     match self {
       UiStyle::Modern => UiStyle::Classic,
@@ -69,6 +238,10 @@ pub struct App {
   pub results: Vec<SearchResult>,
   /// 当前选中的索引
   pub selected: usize,
+  /// 查询为空时，结果中“最常用”部分开始的索引
+  pub frequent_start: Option<usize>,
+  /// 没有结果时名称相近的命令
+  pub suggestions: Vec<String>,
   /// 详情滚动位置
   pub detail_scroll: u16,
   /// 详情内容最大可滚动行数
@@ -80,6 +253,16 @@ pub struct App {
   pub status: String,
   /// 是否正在加载
   pub loading: bool,
+  /// 开始等待结果的时间，用于加载动画
+  loading_since: Option<Instant>,
+  /// 防抖结束、开始搜索的时间
+  search_due: Option<Instant>,
+  /// 最近一次搜索的序号
+  search_generation: u64,
+  /// 进行中的后台搜索
+  search_task: Option<JoinHandle<()>>,
+  search_tx: mpsc::UnboundedSender<SearchOutcome>,
+  search_rx: mpsc::UnboundedReceiver<SearchOutcome>,
   /// 命令总数
   #[allow(dead_code)]
   pub total_commands: usize,
@@ -101,6 +284,44 @@ pub struct App {
 
   /// 当前界面风格
   pub ui_style: UiStyle,
+  /// 快捷键
+  pub keymap: Keymap,
+  /// 结果的平台（search.platform）
+  pub platform: Platform,
+  /// 收藏的命令名
+  pub favorites: BTreeSet<String>,
+  /// 只显示收藏的命令
+  pub favorites_only: bool,
+  /// 是否显示查看历史
+  pub show_history: bool,
+  /// 查看历史，新的在前
+  pub history: Vec<HistoryEntry>,
+  /// 历史中选中的索引
+  pub history_selected: usize,
+  /// 本次运行中按命令名切换的语言（cycle_lang），优先于 [commands] 与 search.default_lang
+  pub lang_choices: HashMap<String, String>,
+  /// 当前查询的首选语言：有中日韩文字时为 search.cjk_lang，否则为 search.default_lang
+  pub query_lang: String,
+
+  /// 上一帧各区域的位置
+  pub areas: PaneAreas,
+  /// 上一帧结果列表第一行对应的结果
+  pub list_offset: usize,
+  /// 上一帧历史弹窗第一行对应的条目
+  pub history_offset: usize,
+  /// 拖动选中的文字
+  pub selection: Option<Selection>,
+  /// 填写示例占位符的弹窗
+  pub fill: Option<Fill>,
+  /// 详情中选中的示例：(命令名, 语言, 示例序号)，切换到其他命令后不再生效
+  example_cursor: Option<(String, String, usize)>,
+  /// 选中示例后下一帧把详情滚动到该示例
+  pub scroll_to_example: bool,
+  /// 运行示例的弹窗
+  pub run: Option<Run>,
+
+  /// 命令详情缓存
+  detail_cache: Mutex<DetailCache>,
 }
 
 impl App {
@@ -114,8 +335,17 @@ impl App {
     ui_style: UiStyle,
   ) -> Self {
     let total = db.count_commands().unwrap_or(0);
+    // 配置已在启动时校验
+    let keymap = Keymap::from_config(&config.tui.keys).unwrap_or_default();
+    let platform = Platform::parse(&config.search.platform);
+    let query_lang = config.search.default_lang.clone();
+    let favorites = db
+      .list_favorites()
+      .map(|favorites| favorites.into_iter().map(|f| f.name).collect())
+      .unwrap_or_default();
+    let (search_tx, search_rx) = mpsc::unbounded_channel();
 
-    Self {
+    let mut app = Self {
       db,
       search: Arc::new(RwLock::new(search)),
       data_dir,
@@ -124,11 +354,19 @@ impl App {
       cursor: 0,
       results: Vec::new(),
       selected: 0,
+      frequent_start: None,
+      suggestions: Vec::new(),
       detail_scroll: 0,
       detail_max_scroll: 0,
       focus: Focus::Search,
-      status: format!("{} commands total", total),
+      status: t!("tui.status.total", count = total),
       loading: false,
+      loading_since: None,
+      search_due: None,
+      search_generation: 0,
+      search_task: None,
+      search_tx,
+      search_rx,
       total_commands: total,
       show_help: false,
       should_quit: false,
@@ -137,7 +375,31 @@ impl App {
       log_scroll: 0,
       show_logs: debug_mode,
       ui_style,
-    }
+      keymap,
+      platform,
+      favorites,
+      favorites_only: false,
+      show_history: false,
+      history: Vec::new(),
+      history_selected: 0,
+      lang_choices: HashMap::new(),
+      query_lang,
+      areas: PaneAreas::default(),
+      list_offset: 0,
+      history_offset: 0,
+      selection: None,
+      fill: None,
+      example_cursor: None,
+      scroll_to_example: false,
+      run: None,
+      detail_cache: Mutex::new(DetailCache {
+        generation: 0,
+        entries: LruCache::new(NonZeroUsize::new(DETAIL_CACHE_SIZE).unwrap()),
+      }),
+    };
+    // 启动时列出最近查看和最常用的命令
+    app.show_recent();
+    app
   }
 
   /// 获取日志条目
@@ -156,30 +418,180 @@ impl App {
     }
   }
 
-  /// 执行搜索
-  pub async fn search(&mut self) {
+  /// 查询改变后安排搜索：等 search_debounce_ms 内没有新的输入再开始，
+  /// 之前未完成的搜索作废
+  pub fn request_search(&mut self) {
+    self.cancel_search();
+    self.frequent_start = None;
+    self.suggestions.clear();
+    self.query_lang = self.config.search.query_lang(&self.query).to_string();
+    let (filters, text) = QueryFilters::parse(&self.query);
+    if text.is_empty() && !filters.is_empty() {
+      // 只有筛选条件时列出符合条件的命令
+      self.results = self.filtered_results(&filters);
+      self.status = with_filters(t!("tui.status.total", count = self.results.len()), &filters);
+      self.selected = 0;
+      self.detail_scroll = 0;
+      return;
+    }
     if self.query.is_empty() {
-      self.results.clear();
+      // 只显示收藏时列出所有收藏，否则列出最近查看的命令
+      if self.favorites_only {
+        self.results = self.favorite_results();
+        self.status = t!("tui.status.favorites", count = self.results.len());
+      } else {
+        self.show_recent();
+      }
       self.selected = 0;
       self.detail_scroll = 0;
       return;
     }
 
+    let debounce = Duration::from_millis(self.config.tui.search_debounce_ms);
+    self.search_due = Some(Instant::now() + debounce);
     self.loading = true;
-    let search = self.search.read().await;
-    match search.search(&self.query, None, 100) {
-      Ok(response) => {
-        self.results = response.results;
+    self.loading_since.get_or_insert_with(Instant::now);
+  }
+
+  /// 距离防抖结束还要等待的时间
+  pub fn search_wait(&self) -> Option<Duration> {
+    self
+      .search_due
+      .map(|due| due.saturating_duration_since(Instant::now()))
+  }
+
+  /// 防抖结束时在后台开始搜索
+  pub fn start_due_search(&mut self) {
+    if self.search_wait() != Some(Duration::ZERO) {
+      return;
+    }
+    self.search_due = None;
+    self.search_generation += 1;
+
+    // lang:、platform: 等筛选条件不参与检索
+    let (filters, text) = QueryFilters::parse(&self.query);
+    // 别名（rtfm alias）按指向的命令搜索，该命令排在最前
+    let target = self.db.get_alias(&alias::normalize(&text)).ok().flatten();
+    let query = target.clone().unwrap_or(text);
+    // 只显示收藏或按分类、标签筛选时多取一些结果再过滤
+    let limit = if self.favorites_only || filters.needs_post_filter() {
+      self.config.search.max_limit
+    } else {
+      self.config.search.tui_limit
+    };
+    let generation = self.search_generation;
+    let search = self.search.clone();
+    let platform = filters.platform_or(&self.platform);
+    let tx = self.search_tx.clone();
+    self.search_task = Some(tokio::task::spawn_blocking(move || {
+      let search = search.blocking_read();
+      let lang = filters.lang.as_deref();
+      // 没有结果时改用模糊搜索，容忍拼写错误
+      let result = search
+        .search(&query, lang, &platform, limit)
+        .and_then(|response| {
+          if response.total > 0 {
+            return Ok((response, false));
+          }
+          search
+            .search_fuzzy(&query, lang, &platform, limit)
+            .map(|fuzzy| (fuzzy, true))
+        });
+      // 界面已退出时接收端不存在，忽略即可
+      let _ = tx.send(SearchOutcome {
+        generation,
+        target,
+        filters,
+        result,
+      });
+    }));
+  }
+
+  /// 作废等待中和进行中的搜索
+  fn cancel_search(&mut self) {
+    self.search_due = None;
+    // 已经开始的搜索无法中断，结果按序号丢弃
+    if let Some(task) = self.search_task.take() {
+      task.abort();
+    }
+    self.search_generation += 1;
+    self.loading = false;
+    self.loading_since = None;
+  }
+
+  /// 取回后台搜索的结果，丢弃已作废的
+  pub fn receive_search_results(&mut self) {
+    while let Ok(outcome) = self.search_rx.try_recv() {
+      if outcome.generation == self.search_generation {
+        self.search_task = None;
+        self.apply_search(outcome);
+      }
+    }
+  }
+
+  fn apply_search(&mut self, outcome: SearchOutcome) {
+    let filters = outcome.filters;
+    self.frequent_start = None;
+    match outcome.result {
+      Ok((mut response, fuzzy)) => {
+        if let Some(target) = &outcome.target {
+          self.pin_command(&mut response.results, target);
+        }
+        if self.favorites_only || filters.needs_post_filter() {
+          response.results.retain(|result| {
+            (!self.favorites_only || self.favorites.contains(&result.name))
+              && filters.matches_result(result)
+          });
+          response.results.truncate(self.config.search.tui_limit);
+        }
+        // 指定了语言时不再换成首选语言的版本
+        self.results = if filters.lang.is_some() {
+          response.results
+        } else {
+          self.prefer_languages(response.results)
+        };
+        response.total = self.results.len();
+        // 匹配的示例列在命令之后（tar → Extract a compressed archive）
+        self
+          .results
+          .extend(response.examples.into_iter().filter(|result| {
+            (!self.favorites_only || self.favorites.contains(&result.name))
+              && filters.matches_result(result)
+          }));
+        if self.results.is_empty() {
+          self.suggestions = response.suggestions;
+        }
         self.selected = 0;
         self.detail_scroll = 0;
-        self.status = format!("Found {} results ({}ms)", response.total, response.took_ms);
+        self.status = if fuzzy && response.total > 0 {
+          t!(
+            "tui.status.found_fuzzy",
+            count = response.total,
+            ms = response.took_ms
+          )
+        } else {
+          t!(
+            "tui.status.found",
+            count = response.total,
+            ms = response.took_ms
+          )
+        };
+        self.status = with_filters(std::mem::take(&mut self.status), &filters);
       }
       Err(e) => {
-        self.status = format!("Search failed: {}", e);
+        self.status = t!("tui.status.search_failed", error = e);
         self.results.clear();
       }
     }
     self.loading = false;
+    self.loading_since = None;
+  }
+
+  /// 当前的加载动画帧
+  pub fn spinner(&self) -> Option<&'static str> {
+    let since = self.loading_since?;
+    let frame = since.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+    Some(SPINNER[frame as usize % SPINNER.len()])
   }
 
   /// 输入字符
@@ -233,10 +645,27 @@ impl App {
     self.query.clear();
     self.cursor = 0;
     self.results.clear();
+    self.frequent_start = None;
     self.selected = 0;
     self.detail_scroll = 0;
   }
 
+  /// 列表中的第 row 行对应的结果；“最常用”的标题行没有对应的结果
+  pub fn result_at_row(&self, row: usize) -> Option<usize> {
+    match self.frequent_start {
+      Some(start) if row == start => None,
+      Some(start) if row > start => Some(row - 1),
+      _ => Some(row),
+    }
+  }
+
+  /// 选中的结果是否在“最常用”部分
+  pub fn in_frequent(&self) -> bool {
+    self
+      .frequent_start
+      .is_some_and(|start| self.selected >= start)
+  }
+
   /// 列表上移
   pub fn list_up(&mut self) {
     if self.selected > 0 {
@@ -253,6 +682,14 @@ impl App {
     }
   }
 
+  /// 选中指定的结果
+  pub fn select_result(&mut self, index: usize) {
+    if index < self.results.len() && index != self.selected {
+      self.selected = index;
+      self.detail_scroll = 0;
+    }
+  }
+
   /// 列表翻页上
   pub fn list_page_up(&mut self) {
     self.selected = self.selected.saturating_sub(10);
@@ -299,32 +736,562 @@ impl App {
       .map(|r| (r.name.as_str(), r.lang.as_str()))
   }
 
-  /// 获取命令详情
-  pub fn get_command_detail(&self, name: &str, lang: &str) -> Option<String> {
-    // 优先查询指定语言，如果没有则尝试中文，再尝试英文
-    let cmd = self
+  /// 收藏的命令（默认语言），数据库中已没有的命令不列出
+  fn favorite_results(&self) -> Vec<SearchResult> {
+    let lang = &self.config.search.default_lang;
+    let results = self
+      .favorites
+      .iter()
+      .filter_map(|name| self.db.find_command(name, lang).ok().flatten())
+      .map(|cmd| to_result(cmd, 0.0))
+      .collect();
+    self.prefer_languages(results)
+  }
+
+  /// 符合筛选条件的命令（按名称排序），只显示收藏时只列出收藏
+  fn filtered_results(&self, filters: &QueryFilters) -> Vec<SearchResult> {
+    let mut commands: Vec<_> = self
       .db
-      .get_command(name, lang)
+      .list_all_commands()
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|cmd| filters.matches_command(cmd))
+      .filter(|cmd| !self.favorites_only || self.favorites.contains(&cmd.name))
+      .collect();
+    commands.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.lang.cmp(&b.lang)));
+    let results = commands
+      .into_iter()
+      .map(|cmd| to_result(cmd, 0.0))
+      .collect();
+    let mut results = if filters.lang.is_some() {
+      results
+    } else {
+      self.prefer_languages(results)
+    };
+    results.truncate(self.config.search.tui_limit);
+    results
+  }
+
+  /// 把别名指向的命令放在结果最前
+  fn pin_command(&self, results: &mut Vec<SearchResult>, target: &str) {
+    for name in alias::command_names(target) {
+      if let Ok(Some(cmd)) = self.db.find_command(&name, self.preferred_lang(&name)) {
+        results.retain(|result| result.name != cmd.name);
+        let score = results.first().map_or(1.0, |result| result.score);
+        results.insert(0, to_result(cmd, score));
+        return;
+      }
+    }
+  }
+
+  /// 命令应显示的语言：本次切换的语言、[commands] 中的偏好，否则为查询的首选语言
+  fn preferred_lang(&self, name: &str) -> &str {
+    self
+      .lang_choices
+      .get(name)
+      .map(String::as_str)
+      .or_else(|| {
+        self
+          .config
+          .command_prefs(name)
+          .and_then(|p| p.lang.as_deref())
+      })
+      .unwrap_or(&self.query_lang)
+  }
+
+  /// 同一命令的多个语言只保留一条（位置取排名最高的一条），有首选语言的版本时换成该版本
+  fn prefer_languages(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    let mut preferred = Vec::with_capacity(results.len());
+    for result in &results {
+      if !seen.insert(result.name.clone()) {
+        continue;
+      }
+      let lang = self.preferred_lang(&result.name);
+      if result.lang == lang {
+        preferred.push(result.clone());
+        continue;
+      }
+      let other = results
+        .iter()
+        .find(|r| r.name == result.name && r.lang == lang)
+        .cloned()
+        .or_else(|| {
+          self
+            .db
+            .get_command(&result.name, lang)
+            .ok()
+            .flatten()
+            .map(|cmd| to_result(cmd, result.score))
+        });
+      preferred.push(other.unwrap_or_else(|| result.clone()));
+    }
+    preferred
+  }
+
+  /// 把选中的命令切换到下一个语言（local 学习的内容会合并显示，不单独列出）
+  pub fn cycle_lang(&mut self) {
+    let Some(result) = self.results.get(self.selected) else {
+      return;
+    };
+    let name = result.name.clone();
+    let langs: Vec<String> = self
+      .db
+      .command_langs(&name)
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|lang| lang != "local")
+      .collect();
+    if langs.len() < 2 {
+      self.status = t!("tui.status.one_lang", name = name);
+      return;
+    }
+    let next = langs
+      .iter()
+      .position(|lang| *lang == result.lang)
+      .map_or(0, |i| (i + 1) % langs.len());
+    let Some(cmd) = self.db.get_command(&name, &langs[next]).ok().flatten() else {
+      return;
+    };
+    self.status = t!(
+      "tui.status.lang",
+      name = name,
+      lang = cmd.lang,
+      langs = langs.join("/")
+    );
+    self.lang_choices.insert(name, cmd.lang.clone());
+    let score = result.score;
+    self.results[self.selected] = to_result(cmd, score);
+    self.detail_scroll = 0;
+  }
+
+  /// 列出最近查看的命令，之后是不在其中的最常用命令
+  fn show_recent(&mut self) {
+    self.results = self.recent_results();
+    let recent = self.results.len();
+    let frequent = self.frequent_results();
+    self.frequent_start = (!frequent.is_empty()).then_some(recent);
+    self.results.extend(frequent);
+    if self.results.is_empty() {
+      return;
+    }
+    self.status = if self.frequent_start.is_some() {
+      t!(
+        "tui.status.recent_frequent",
+        count = recent,
+        frequent = self.results.len() - recent
+      )
+    } else {
+      t!("tui.status.recent", count = recent)
+    };
+  }
+
+  /// 查看次数最多、不在最近查看中的命令（tui.frequent_commands 条）
+  fn frequent_results(&self) -> Vec<SearchResult> {
+    let limit = self.config.tui.frequent_commands;
+    if limit == 0 {
+      return Vec::new();
+    }
+    let top = crate::usage::most_used(&self.db, limit + self.results.len()).unwrap_or_default();
+    let lang = &self.config.search.default_lang;
+    let results = top
+      .into_iter()
+      .filter(|entry| !self.results.iter().any(|r| r.name == entry.name))
+      .filter_map(|entry| self.db.find_command(&entry.name, lang).ok().flatten())
+      .map(|cmd| to_result(cmd, 0.0))
+      .take(limit)
+      .collect();
+    self.prefer_languages(results)
+  }
+
+  /// 最近查看的命令，数据库中已没有的命令不列出
+  fn recent_results(&self) -> Vec<SearchResult> {
+    let recent =
+      crate::usage::recently_viewed(&self.db, self.config.search.tui_limit).unwrap_or_default();
+    let results = recent
+      .into_iter()
+      .filter_map(|entry| {
+        self
+          .db
+          .find_command(&entry.name, &entry.lang)
+          .ok()
+          .flatten()
+      })
+      .map(|cmd| to_result(cmd, 0.0))
+      .collect();
+    self.prefer_languages(results)
+  }
+
+  /// 显示或关闭查看历史
+  pub fn toggle_history(&mut self) {
+    self.show_history = !self.show_history;
+    if self.show_history {
+      self.history = self.db.list_history(HISTORY_SIZE).unwrap_or_default();
+      self.history_selected = 0;
+    }
+  }
+
+  /// 搜索历史中选中的命令并关闭历史，之后需要重新搜索
+  pub fn open_history_entry(&mut self) {
+    if let Some(entry) = self.history.get(self.history_selected) {
+      self.query = entry.name.clone();
+      self.cursor = self.query.len();
+      self.favorites_only = false;
+      self.focus = Focus::List;
+    }
+    self.show_history = false;
+  }
+
+  /// 收藏或取消收藏选中的命令
+  pub fn toggle_favorite(&mut self) {
+    let Some((name, _)) = self.selected_command() else {
+      return;
+    };
+    let name = name.to_string();
+    let favorite = !self.favorites.contains(&name);
+    let result = if favorite {
+      self.db.add_favorite(&name)
+    } else {
+      self.db.remove_favorite(&name)
+    };
+    if let Err(e) = result {
+      self.status = t!("tui.status.favorite_failed", error = e);
+      return;
+    }
+
+    if favorite {
+      self.status = t!("tui.status.favorite_added", name = name);
+      self.favorites.insert(name);
+    } else {
+      self.status = t!("tui.status.favorite_removed", name = name);
+      self.favorites.remove(&name);
+      // 只显示收藏时从列表中去掉
+      if self.favorites_only {
+        self.results.remove(self.selected);
+        self.selected = self.selected.min(self.results.len().saturating_sub(1));
+        self.detail_scroll = 0;
+      }
+    }
+  }
+
+  /// 切换只显示收藏，之后需要重新搜索
+  pub fn toggle_favorites_only(&mut self) {
+    self.favorites_only = !self.favorites_only;
+  }
+
+  /// 复制拖动选中的文字
+  pub fn copy_selection(&mut self) {
+    let Some(selection) = &self.selection else {
+      return;
+    };
+    let text = selection.text.trim_end().to_string();
+    if text.is_empty() {
+      return;
+    }
+    self.status = match crate::clipboard::copy(&text) {
+      Ok(method) => t!("tui.status.copied", method = method),
+      Err(e) => t!("tui.status.copy_failed", error = e),
+    };
+  }
+
+  /// 选中命令的示例，没有时在状态栏提示
+  fn selected_examples(&mut self) -> Option<(String, Vec<Example>)> {
+    let (name, lang) = self.selected_command()?;
+    let name = name.to_string();
+    let examples = self
+      .db
+      .find_command(&name, lang)
       .ok()
       .flatten()
-      .or_else(|| self.db.get_command(name, "zh").ok().flatten())
-      .or_else(|| self.db.get_command(name, "en").ok().flatten());
+      .map(|cmd| cmd.examples)
+      .unwrap_or_default();
+    if examples.is_empty() {
+      self.status = t!("tui.fill.no_examples", name = name);
+      return None;
+    }
+    Some((name, examples))
+  }
+
+  /// 详情中选中的示例（仍是当前命令时）
+  pub fn active_example(&self) -> Option<usize> {
+    let (name, lang) = self.selected_command()?;
+    self
+      .example_cursor
+      .as_ref()
+      .filter(|(n, l, _)| n == name && l == lang)
+      .map(|(_, _, index)| *index)
+  }
+
+  /// 没有选中示例时的目标：选中的是匹配的示例时为该示例，否则为 [commands] 中设置的默认示例
+  fn default_example(&self, name: &str, count: usize) -> usize {
+    let example = self
+      .results
+      .get(self.selected)
+      .and_then(|r| r.example.as_ref())
+      .map(|example| example.index);
+    example
+      .or_else(|| self.config.command_prefs(name).and_then(|p| p.example))
+      .and_then(|n| n.checked_sub(1))
+      .filter(|&i| i < count)
+      .unwrap_or(0)
+  }
+
+  /// 在详情中选中下一个（或上一个）示例，到头后回到另一端
+  pub fn select_example(&mut self, forward: bool) {
+    let Some((name, examples)) = self.selected_examples() else {
+      return;
+    };
+    let count = examples.len();
+    let index = match (self.active_example(), forward) {
+      (Some(i), true) => (i + 1) % count,
+      (Some(i), false) => (i + count - 1) % count,
+      (None, true) => 0,
+      (None, false) => count - 1,
+    };
+    self.status = t!(
+      "tui.status.example",
+      index = index + 1,
+      count = count,
+      description = examples[index].description
+    );
+    let lang = self.results[self.selected].lang.clone();
+    self.example_cursor = Some((name, lang, index));
+    self.scroll_to_example = true;
+  }
+
+  /// 复制选中示例的命令（占位符保持原样）
+  pub fn copy_example(&mut self) {
+    let Some((name, examples)) = self.selected_examples() else {
+      return;
+    };
+    let index = self
+      .active_example()
+      .unwrap_or_else(|| self.default_example(&name, examples.len()));
+    let Some(example) = examples.get(index) else {
+      return;
+    };
+    self.status = match crate::clipboard::copy(&example.code) {
+      Ok(method) => t!("tui.status.filled", method = method, command = example.code),
+      Err(e) => t!("tui.status.copy_failed", error = e),
+    };
+  }
+
+  /// 打开选中命令的填写示例弹窗；详情中已选中示例时直接填写该示例
+  /// run 为真时填好后确认运行（tui.allow_execute），否则复制
+  pub fn open_fill(&mut self, run: bool) {
+    if run && !self.config.tui.allow_execute {
+      self.status = t!("tui.run.disabled").to_string();
+      return;
+    }
+    let Some((name, examples)) = self.selected_examples() else {
+      return;
+    };
+    let active = self.active_example().filter(|&i| i < examples.len());
+    let selected = active.unwrap_or_else(|| self.default_example(&name, examples.len()));
+    self.fill = Some(Fill {
+      examples,
+      selected,
+      names: Vec::new(),
+      values: Vec::new(),
+      run,
+    });
+    if active.is_some() {
+      self.choose_fill_example();
+    }
+  }
+
+  /// 选定示例开始填写；没有占位符时直接完成
+  pub fn choose_fill_example(&mut self) {
+    let Some(fill) = self.fill.as_mut() else {
+      return;
+    };
+    let Some(example) = fill.examples.get(fill.selected) else {
+      return;
+    };
+    fill.names = crate::placeholder::names(&example.code);
+    fill.values = vec![String::new()];
+    if !fill.editing() {
+      self.finish_fill();
+    }
+  }
+
+  /// 当前占位符输入字符
+  pub fn fill_input_char(&mut self, c: char) {
+    if let Some(value) = self.fill.as_mut().and_then(|f| f.values.last_mut()) {
+      value.push(c);
+    }
+  }
+
+  /// 当前占位符删除字符
+  pub fn fill_delete_char(&mut self) {
+    if let Some(value) = self.fill.as_mut().and_then(|f| f.values.last_mut()) {
+      value.pop();
+    }
+  }
+
+  /// 填写下一个占位符；都填好后复制结果
+  pub fn fill_next(&mut self) {
+    let Some(fill) = self.fill.as_mut() else {
+      return;
+    };
+    if fill.values.len() < fill.names.len() {
+      fill.values.push(String::new());
+    } else {
+      self.finish_fill();
+    }
+  }
+
+  /// 复制填好的命令（或请求确认运行）并关闭弹窗
+  fn finish_fill(&mut self) {
+    let Some(fill) = self.fill.take() else {
+      return;
+    };
+    let command = fill.preview();
+    if fill.run {
+      self.run = Some(Run {
+        command,
+        state: RunState::Confirm,
+        scroll: 0,
+        max_scroll: 0,
+        task: None,
+        rx: None,
+      });
+      return;
+    }
+    self.status = match crate::clipboard::copy(&command) {
+      Ok(method) => t!("tui.status.filled", method = method, command = command),
+      Err(e) => t!("tui.status.copy_failed", error = e),
+    };
+  }
+
+  /// 确认后在后台的子 shell 中运行命令，不接收输入
+  pub fn start_run(&mut self) {
+    let Some(run) = self.run.as_mut().filter(|r| r.state == RunState::Confirm) else {
+      return;
+    };
+    let (tx, rx) = oneshot::channel();
+    let command = run.command.clone();
+    run.state = RunState::Running;
+    run.rx = Some(rx);
+    run.task = Some(tokio::spawn(async move {
+      // 弹窗已关闭时接收端不存在，忽略即可
+      let _ = tx.send(execute(&command).await);
+    }));
+  }
+
+  /// 是否有正在运行的示例
+  pub fn running(&self) -> bool {
+    self
+      .run
+      .as_ref()
+      .is_some_and(|r| r.state == RunState::Running)
+  }
+
+  /// 取回运行结果
+  pub fn receive_run_output(&mut self) {
+    let Some(run) = self.run.as_mut() else {
+      return;
+    };
+    if let Some(state) = run.rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+      run.state = state;
+      run.rx = None;
+      run.task = None;
+    }
+  }
+
+  /// 打开结果时记录使用统计（输入过程中的增量搜索不计）
+  pub fn record_open(&mut self) {
+    if let Some((name, lang)) = self.selected_command() {
+      let (name, lang) = (name.to_string(), lang.to_string());
+      crate::usage::record_query(&self.db, &self.config, &self.query, self.results.len());
+      crate::usage::record_lookup(&self.db, &self.config, &name, &lang, "tui");
+    }
+  }
+
+  /// 获取命令详情（带缓存）
+  pub fn get_command_detail(&self, name: &str, lang: &str) -> Option<String> {
+    let mut cache = self.detail_cache.lock();
+    let generation = self.db.generation();
+    if cache.generation != generation {
+      cache.entries.clear();
+      cache.generation = generation;
+    }
+
+    let key = (name.to_string(), lang.to_string());
+    if let Some(content) = cache.entries.get(&key) {
+      return content.clone();
+    }
+    let content = self.load_command_detail(name, lang);
+    cache.entries.put(key, content.clone());
+    content
+  }
+
+  fn load_command_detail(&self, name: &str, lang: &str) -> Option<String> {
+    // 优先查询指定语言，如果没有则尝试英文，再尝试中文
+    // 结果本身是本地学习的条目时，合并同名 tldr 页面
+    // [commands] 的语言偏好已在选择结果的语言时应用（prefer_languages）
+    let cmd = self.db.find_command(name, lang).ok().flatten();
 
     cmd.map(|cmd| {
       let mut content = format!("# {}\n\n{}\n\n", cmd.name, cmd.description);
+      if let Some(notes) = &cmd.notes {
+        content.push_str(&format!("## {}\n\n{}\n\n", t!("notes.title"), notes));
+      }
+      if let Some(version) = cmd.source_info.as_ref().and_then(|i| i.version.as_deref()) {
+        content.push_str(&format!(
+          "{}\n\n",
+          t!("tui.learned_from", version = version)
+        ));
+      }
+      // 学习之后本机的二进制有更新
+      if crate::learn::refresh::is_stale(&cmd) {
+        content.push_str(&format!("{}\n\n", t!("tui.learned_outdated")));
+      }
+      if let Some(synopsis) = cmd.section("SYNOPSIS") {
+        content.push_str(&format!("```\n{}\n```\n\n", synopsis.content));
+      }
+      let mut local_header = false;
       for example in &cmd.examples {
+        // 合并视图：本地学习的示例单独成节
+        if example.source.as_deref() == Some("local") && !local_header {
+          content.push_str(&format!("# {}\n\n", t!("tui.from_local_help")));
+          local_header = true;
+        }
+        // AI 生成的示例标注来源
+        let label = match example
+          .source
+          .as_deref()
+          .and_then(|s| s.strip_prefix("llm:"))
+        {
+          Some(model) => format!(" (AI: {})", model),
+          None => String::new(),
+        };
         content.push_str(&format!(
-          "## {}\n```\n{}\n```\n\n",
-          example.description, example.code
+          "## {}{}\n```\n{}\n```\n\n",
+          example.description, label, example.code
         ));
       }
+      // 选项：缩进的行按代码显示，说明另起一行
+      if !cmd.options.is_empty() {
+        content.push_str(&format!("## {}\n\n", t!("tui.options")));
+        for option in &cmd.options {
+          content.push_str(&format!("  {}\n{}\n\n", option.label(), option.description));
+        }
+      }
       content
     })
   }
 
-  /// 切换界面风格
+  /// 切换界面风格，开启 remember_style 时写回配置文件
   pub fn toggle_style(&mut self) {
     self.ui_style = self.ui_style.toggle();
+    if !self.config.tui.remember_style {
+      return;
+    }
+    match AppConfig::save_default_style(self.ui_style.as_str()) {
+      Ok(path) => tracing::debug!("Saved UI style to {:?}", path),
+      Err(e) => self.status = t!("tui.status.save_style_failed", error = e),
+    }
   }
 
   /// 获取前一个字符的字节边界
@@ -345,3 +1312,316 @@ impl App {
       .unwrap_or(s.len())
   }
 }
+
+/// 在子 shell 中运行命令，收集标准输出和标准错误
+async fn execute(command: &str) -> RunState {
+  execute_limited(command, RUN_OUTPUT_LIMIT, RUN_TIMEOUT).await
+}
+
+/// 输出超过 limit 或运行超过 timeout 时结束命令，保留已收集的输出
+/// （如 `yes`、`cat /dev/urandom` 不会占满内存或让弹窗一直等待）
+async fn execute_limited(command: &str, limit: usize, timeout: Duration) -> RunState {
+  #[cfg(windows)]
+  let mut shell = {
+    let mut shell = tokio::process::Command::new("cmd");
+    shell.arg("/C");
+    shell
+  };
+  #[cfg(not(windows))]
+  let mut shell = {
+    let mut shell = tokio::process::Command::new("sh");
+    shell.arg("-c");
+    shell
+  };
+  let spawned = shell
+    .arg(command)
+    .stdin(std::process::Stdio::null())
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .kill_on_drop(true)
+    .spawn();
+  let mut child = match spawned {
+    Ok(child) => child,
+    Err(e) => {
+      return RunState::Done {
+        output: String::new(),
+        status: t!("tui.run.failed", error = e),
+      }
+    }
+  };
+
+  let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+  let (stdout_pipe, stderr_pipe) = (child.stdout.take(), child.stderr.take());
+  let finished = tokio::time::timeout(timeout, async {
+    let (stdout_full, stderr_full) = tokio::join!(
+      read_limited(stdout_pipe, &mut stdout, limit),
+      read_limited(stderr_pipe, &mut stderr, limit)
+    );
+    if stdout_full || stderr_full {
+      return Ok(None);
+    }
+    child.wait().await.map(Some)
+  })
+  .await;
+
+  let status = match finished {
+    Ok(Ok(Some(status))) => match status.code() {
+      Some(code) => t!("tui.run.exit", code = code),
+      None => t!("tui.run.killed").to_string(),
+    },
+    Ok(Ok(None)) => {
+      let _ = child.kill().await;
+      t!("tui.run.output_limit", kb = limit / 1024)
+    }
+    Ok(Err(e)) => t!("tui.run.failed", error = e),
+    Err(_) => {
+      let _ = child.kill().await;
+      t!("tui.run.timed_out", secs = timeout.as_secs())
+    }
+  };
+
+  let mut text = String::from_utf8_lossy(&stdout).into_owned();
+  let stderr = String::from_utf8_lossy(&stderr);
+  if !stderr.is_empty() {
+    if !text.is_empty() && !text.ends_with('\n') {
+      text.push('\n');
+    }
+    text.push_str(&stderr);
+  }
+  RunState::Done {
+    output: text,
+    status,
+  }
+}
+
+/// 读取管道直到结束，最多保留 limit 字节；超出时返回 true
+async fn read_limited<R: tokio::io::AsyncRead + Unpin>(
+  pipe: Option<R>,
+  buf: &mut Vec<u8>,
+  limit: usize,
+) -> bool {
+  use tokio::io::AsyncReadExt;
+  let Some(mut pipe) = pipe else {
+    return false;
+  };
+  let mut chunk = [0u8; 8192];
+  loop {
+    match pipe.read(&mut chunk).await {
+      Ok(0) | Err(_) => return false,
+      Ok(n) => {
+        let take = n.min(limit - buf.len());
+        buf.extend_from_slice(&chunk[..take]);
+        if take < n {
+          return true;
+        }
+      }
+    }
+  }
+}
+
+/// 在状态消息后列出生效的筛选条件
+fn with_filters(status: String, filters: &QueryFilters) -> String {
+  if filters.is_empty() {
+    status
+  } else {
+    format!(
+      "{} · {}",
+      status,
+      t!("tui.status.filters", filters = filters.describe())
+    )
+  }
+}
+
+fn to_result(cmd: Command, score: f32) -> SearchResult {
+  SearchResult {
+    name: cmd.name,
+    description: cmd.description,
+    category: cmd.category,
+    lang: cmd.lang,
+    platform: cmd.platform,
+    score,
+    example: None,
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use crate::storage::COMMAND_SCHEMA;
+
+  fn command(name: &str, examples: &[(&str, &str)]) -> Command {
+    Command {
+      name: name.to_string(),
+      description: format!("{} command", name),
+      category: "common".to_string(),
+      platform: "common".to_string(),
+      lang: "en".to_string(),
+      examples: examples
+        .iter()
+        .map(|(description, code)| Example {
+          description: description.to_string(),
+          code: code.to_string(),
+          source: None,
+        })
+        .collect(),
+      content: String::new(),
+      sections: vec![],
+      options: vec![],
+      source_info: None,
+      notes: None,
+      schema: COMMAND_SCHEMA,
+    }
+  }
+
+  /// 结果为 tar（三个示例）和 ls（一个示例）的 App，选中 tar
+  pub(crate) fn app(dir: &std::path::Path, config: AppConfig, ui_style: UiStyle) -> App {
+    let db = Database::open(&dir.join("data.redb")).unwrap();
+    let commands = [
+      command(
+        "tar",
+        &[
+          ("Create an archive", "tar -cf {{archive}} {{files}}"),
+          ("Extract an archive", "tar -xf {{archive}}"),
+          ("List an archive", "tar -tf archive.tar"),
+        ],
+      ),
+      command("ls", &[("List files", "ls -l")]),
+    ];
+    db.save_commands(&commands).unwrap();
+    let mut search = SearchEngine::open(&dir.join("index"), &config.search).unwrap();
+    crate::search::rebuild_index(&db, &mut search).unwrap();
+    let mut app = App::with_debug(db, search, dir.to_path_buf(), false, None, config, ui_style);
+    app.results = commands
+      .into_iter()
+      .map(|cmd| to_result(cmd, 1.0))
+      .collect();
+    app.selected = 0;
+    app
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_execute_limits() {
+    let RunState::Done { output, status } =
+      execute_limited("echo hi; echo oops >&2", 1024, Duration::from_secs(10)).await
+    else {
+      panic!("not done");
+    };
+    assert_eq!(output, "hi\noops\n");
+    assert_eq!(status, t!("tui.run.exit", code = 0));
+
+    // 无穷输出在达到上限后结束
+    let RunState::Done { output, status } =
+      execute_limited("yes", 4096, Duration::from_secs(10)).await
+    else {
+      panic!("not done");
+    };
+    assert_eq!(output.len(), 4096);
+    assert_eq!(status, t!("tui.run.output_limit", kb = 4));
+
+    let RunState::Done { status, .. } =
+      execute_limited("sleep 10", 4096, Duration::from_millis(200)).await
+    else {
+      panic!("not done");
+    };
+    assert_eq!(status, t!("tui.run.timed_out", secs = 0));
+  }
+
+  #[test]
+  fn test_select_example() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut app = app(temp_dir.path(), AppConfig::default(), UiStyle::Modern);
+    assert_eq!(app.active_example(), None);
+
+    // 向后从第一个开始，到头后回到另一端
+    app.select_example(true);
+    assert_eq!(app.active_example(), Some(0));
+    assert!(app.scroll_to_example);
+    app.select_example(true);
+    app.select_example(true);
+    assert_eq!(app.active_example(), Some(2));
+    app.select_example(true);
+    assert_eq!(app.active_example(), Some(0));
+    app.select_example(false);
+    assert_eq!(app.active_example(), Some(2));
+
+    // 切换到其他命令后不再生效，切换回来时恢复
+    app.selected = 1;
+    assert_eq!(app.active_example(), None);
+    app.selected = 0;
+    assert_eq!(app.active_example(), Some(2));
+
+    // 从末尾向前
+    app.selected = 1;
+    app.select_example(false);
+    assert_eq!(app.active_example(), Some(0));
+  }
+
+  #[test]
+  fn test_fill_selected_example() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut app = app(temp_dir.path(), AppConfig::default(), UiStyle::Modern);
+
+    // 没有选中示例时先选择示例
+    app.open_fill(false);
+    let fill = app.fill.take().unwrap();
+    assert_eq!(fill.selected, 0);
+    assert!(!fill.editing());
+
+    // 选中的示例直接开始填写
+    app.select_example(true);
+    app.select_example(true);
+    app.open_fill(false);
+    let fill = app.fill.as_ref().unwrap();
+    assert_eq!(fill.selected, 1);
+    assert_eq!(fill.names, ["archive"]);
+  }
+
+  /// 等待后台搜索的结果
+  async fn wait_for_search(app: &mut App) {
+    for _ in 0..100 {
+      app.start_due_search();
+      app.receive_search_results();
+      if !app.loading {
+        return;
+      }
+      tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("search did not finish");
+  }
+
+  #[tokio::test]
+  async fn test_debounced_search() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = AppConfig::default();
+    config.tui.search_debounce_ms = 200;
+    let mut app = app(temp_dir.path(), config, UiStyle::Modern);
+
+    // 防抖期间不开始搜索
+    app.query = "ls".to_string();
+    app.request_search();
+    assert!(app.loading);
+    assert!(app.search_wait().is_some_and(|wait| wait > Duration::ZERO));
+    app.start_due_search();
+    assert!(app.search_task.is_none());
+
+    // 新的输入重新计时，取代等待中的查询
+    app.query = "tar".to_string();
+    app.request_search();
+    wait_for_search(&mut app).await;
+    assert_eq!(app.results[0].name, "tar");
+
+    // 已经开始的搜索被新的查询作废，结果不再显示
+    app.config.tui.search_debounce_ms = 0;
+    app.query = "tar".to_string();
+    app.request_search();
+    app.start_due_search();
+    app.query = "ls".to_string();
+    app.request_search();
+    wait_for_search(&mut app).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    app.receive_search_results();
+    assert_eq!(app.results[0].name, "ls");
+    assert!(app.search_wait().is_none());
+  }
+}
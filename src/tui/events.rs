@@ -1,7 +1,9 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
 use std::time::Duration;
 
-use super::app::{App, Focus, UiStyle};
+use super::app::{App, Focus, RunState, Selection, UiStyle};
+use super::keymap::{is_text_input, Action};
 
 /// 事件处理结果
 pub enum EventResult {
@@ -9,6 +11,8 @@ pub enum EventResult {
   Continue,
   /// 需要搜索
   Search,
+  /// 暂停界面，用编辑器编辑选中命令的笔记
+  EditNotes,
   /// 退出程序
   Quit,
 }
@@ -24,35 +28,42 @@ pub fn poll_event(timeout: Duration) -> std::io::Result<Option<Event>> {
 
 /// 处理按键事件
 pub fn handle_key_event(app: &mut App, key: KeyEvent) -> EventResult {
+  app.selection = None;
+
+  // 搜索框和占位符中的普通字符是输入，不作为快捷键
+  let editing = match &app.fill {
+    Some(fill) => fill.editing(),
+    None => app.focus == Focus::Search && !app.show_history && app.run.is_none(),
+  };
+  let typing = editing && !app.show_help && is_text_input(&key);
+  let action = if typing {
+    None
+  } else {
+    app.keymap.action(&key)
+  };
+
   // 全局快捷键（任何焦点状态下都生效）
-  match key.code {
-    // Ctrl+C 或 Ctrl+Q 强制退出
-    KeyCode::Char('c') | KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-      return EventResult::Quit;
-    }
-    // Ctrl+H 切换帮助（全局可用）
-    KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+  match action {
+    Some(Action::Quit) => return EventResult::Quit,
+    Some(Action::Help) => {
       app.show_help = !app.show_help;
       return EventResult::Continue;
     }
-    // ? 切换帮助（非搜索焦点时）
-    KeyCode::Char('?') if app.focus != Focus::Search => {
-      app.show_help = !app.show_help;
-      return EventResult::Continue;
-    }
-    // Ctrl+L 切换日志面板（调试模式）
-    KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+    // 切换日志面板（调试模式）
+    Some(Action::ToggleLogs) => {
       app.toggle_logs();
       return EventResult::Continue;
     }
-    // Ctrl+T 切换界面风格
-    KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+    Some(Action::ToggleStyle) => {
       app.toggle_style();
       return EventResult::Continue;
     }
-    // 帮助模式下 Esc 关闭帮助
-    KeyCode::Esc if app.show_help => {
-      app.show_help = false;
+    Some(Action::Favorites) if !app.show_help && app.fill.is_none() && app.run.is_none() => {
+      app.toggle_favorites_only();
+      return EventResult::Search;
+    }
+    Some(Action::History) if !app.show_help && app.fill.is_none() && app.run.is_none() => {
+      app.toggle_history();
       return EventResult::Continue;
     }
     _ => {}
@@ -60,41 +71,64 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent) -> EventResult {
 
   // 帮助模式下只响应关闭
   if app.show_help {
-    if matches!(
-      key.code,
-      KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('?')
-    ) {
+    if matches!(action, Some(Action::Back | Action::Open)) || key.code == KeyCode::Char(' ') {
       app.show_help = false;
     }
     return EventResult::Continue;
   }
 
+  if app.run.is_some() {
+    return handle_run_input(app, key, action);
+  }
+
+  if app.fill.is_some() {
+    return handle_fill_input(app, key, action);
+  }
+
+  if app.show_history {
+    return handle_history_input(app, action);
+  }
+
   // 根据焦点处理事件
   match app.focus {
-    Focus::Search => handle_search_input(app, key),
-    Focus::List => handle_list_input(app, key),
-    Focus::Detail => handle_detail_input(app, key),
+    Focus::Search => handle_search_input(app, key, action),
+    Focus::List => handle_list_input(app, key, action),
+    Focus::Detail => handle_detail_input(app, key, action),
   }
 }
 
-fn handle_search_input(app: &mut App, key: KeyEvent) -> EventResult {
-  match key.code {
-    // 清空 (Ctrl+U)
-    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+fn handle_search_input(app: &mut App, key: KeyEvent, action: Option<Action>) -> EventResult {
+  match action {
+    Some(Action::ClearSearch) => {
       app.clear_search();
-      EventResult::Search
+      return EventResult::Search;
     }
-    // Esc: 搜索框为空时退出，否则清空
-    KeyCode::Esc => {
+    // 搜索框为空时退出，否则清空
+    Some(Action::Back) => {
       if app.query.is_empty() {
-        EventResult::Quit
-      } else {
-        app.clear_search();
-        EventResult::Search
+        return EventResult::Quit;
+      }
+      app.clear_search();
+      return EventResult::Search;
+    }
+    // 切换焦点
+    Some(Action::NextFocus | Action::Down | Action::Open) => {
+      if !app.results.is_empty() {
+        // Modern 风格的结果区直接显示详情，进入即算查看
+        if action == Some(Action::Open) && app.ui_style == UiStyle::Modern {
+          app.record_open();
+        }
+        app.focus = Focus::List;
       }
+      return EventResult::Continue;
     }
+    _ => {}
+  }
+
+  // 文本编辑键
+  match key.code {
     // 输入字符
-    KeyCode::Char(c) => {
+    KeyCode::Char(c) if is_text_input(&key) => {
       app.input_char(c);
       EventResult::Search
     }
@@ -124,201 +158,474 @@ fn handle_search_input(app: &mut App, key: KeyEvent) -> EventResult {
       app.cursor_end();
       EventResult::Continue
     }
-    // 切换焦点
-    KeyCode::Tab | KeyCode::Down => {
-      if !app.results.is_empty() {
-        app.focus = Focus::List;
-      }
-      EventResult::Continue
+    _ => EventResult::Continue,
+  }
+}
+
+/// 历史面板：上下选择，打开时搜索选中的命令
+fn handle_history_input(app: &mut App, action: Option<Action>) -> EventResult {
+  match action {
+    Some(Action::Up) => app.history_selected = app.history_selected.saturating_sub(1),
+    Some(Action::Down) if app.history_selected + 1 < app.history.len() => app.history_selected += 1,
+    Some(Action::Top) => app.history_selected = 0,
+    Some(Action::Bottom) => app.history_selected = app.history.len().saturating_sub(1),
+    Some(Action::Open) => {
+      app.open_history_entry();
+      return EventResult::Search;
+    }
+    Some(Action::Back) => app.show_history = false,
+    _ => {}
+  }
+  EventResult::Continue
+}
+
+/// 填写示例弹窗：先上下选择示例，再依次输入占位符的值
+fn handle_fill_input(app: &mut App, key: KeyEvent, action: Option<Action>) -> EventResult {
+  let Some(fill) = app.fill.as_mut() else {
+    return EventResult::Continue;
+  };
+  if fill.editing() {
+    match action {
+      Some(Action::Open) => app.fill_next(),
+      Some(Action::Back) => app.fill = None,
+      _ => match key.code {
+        KeyCode::Char(c) if is_text_input(&key) => app.fill_input_char(c),
+        KeyCode::Backspace => app.fill_delete_char(),
+        _ => {}
+      },
     }
-    KeyCode::Enter => {
-      if !app.results.is_empty() {
-        app.focus = Focus::List;
-      }
-      EventResult::Continue
+    return EventResult::Continue;
+  }
+
+  let count = fill.examples.len();
+  match action {
+    Some(Action::Up) => fill.selected = fill.selected.saturating_sub(1),
+    Some(Action::Down) if fill.selected + 1 < count => fill.selected += 1,
+    Some(Action::Top) => fill.selected = 0,
+    Some(Action::Bottom) => fill.selected = count.saturating_sub(1),
+    Some(Action::Open) => app.choose_fill_example(),
+    Some(Action::Back) => app.fill = None,
+    _ => {}
+  }
+  EventResult::Continue
+}
+
+/// 运行示例弹窗：按 y 确认运行，之后上下滚动输出；关闭时结束仍在运行的命令
+fn handle_run_input(app: &mut App, key: KeyEvent, action: Option<Action>) -> EventResult {
+  let Some(run) = app.run.as_mut() else {
+    return EventResult::Continue;
+  };
+  if run.state == RunState::Confirm {
+    match key.code {
+      KeyCode::Char('y' | 'Y') => app.start_run(),
+      KeyCode::Char('n' | 'N') => app.run = None,
+      _ if action == Some(Action::Back) => app.run = None,
+      _ => {}
     }
-    _ => EventResult::Continue,
+    return EventResult::Continue;
+  }
+
+  match action {
+    Some(Action::Up) => run.scroll = run.scroll.saturating_sub(1),
+    Some(Action::Down) => run.scroll = run.scroll.saturating_add(1).min(run.max_scroll),
+    Some(Action::PageUp) => run.scroll = run.scroll.saturating_sub(10),
+    Some(Action::PageDown) => run.scroll = run.scroll.saturating_add(10).min(run.max_scroll),
+    Some(Action::Top) => run.scroll = 0,
+    Some(Action::Bottom) => run.scroll = run.max_scroll,
+    Some(Action::Back | Action::Open) => app.run = None,
+    _ => {}
   }
+  EventResult::Continue
 }
 
-fn handle_list_input(app: &mut App, key: KeyEvent) -> EventResult {
+fn handle_list_input(app: &mut App, key: KeyEvent, action: Option<Action>) -> EventResult {
   // Modern 风格：上下滚动内容，左右切换结果
   if app.ui_style == UiStyle::Modern {
-    return handle_modern_result_input(app, key);
+    return handle_modern_result_input(app, key, action);
   }
 
-  // Classic 风格：原有逻辑
-  match key.code {
+  // Classic 风格
+  match action {
     // 导航
-    KeyCode::Up | KeyCode::Char('k') => {
-      app.list_up();
-      EventResult::Continue
-    }
-    KeyCode::Down | KeyCode::Char('j') => {
-      app.list_down();
-      EventResult::Continue
-    }
-    KeyCode::PageUp => {
-      app.list_page_up();
-      EventResult::Continue
-    }
-    KeyCode::PageDown => {
-      app.list_page_down();
-      EventResult::Continue
-    }
-    // Jump to top (Home or 'g' for vim-style gg)
-    KeyCode::Home | KeyCode::Char('g') => {
+    Some(Action::Up) => app.list_up(),
+    Some(Action::Down) => app.list_down(),
+    Some(Action::PageUp) => app.list_page_up(),
+    Some(Action::PageDown) => app.list_page_down(),
+    Some(Action::Top) => {
       app.selected = 0;
       app.detail_scroll = 0;
-      EventResult::Continue
     }
-    // Jump to end (End or 'G' for vim-style)
-    KeyCode::End | KeyCode::Char('G') => {
+    Some(Action::Bottom) => {
       app.selected = app.results.len().saturating_sub(1);
       app.detail_scroll = 0;
-      EventResult::Continue
     }
     // 切换焦点
-    KeyCode::Tab => {
-      app.next_focus();
-      EventResult::Continue
-    }
-    KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+    Some(Action::NextFocus) => app.next_focus(),
+    Some(Action::Open) => {
+      app.record_open();
       app.focus = Focus::Detail;
-      EventResult::Continue
     }
-    KeyCode::Char('/') | KeyCode::Esc => {
-      app.focus = Focus::Search;
-      EventResult::Continue
-    }
-    // 在列表中也可以输入搜索
-    KeyCode::Char(c) if c.is_alphanumeric() || c == ' ' => {
-      app.focus = Focus::Search;
-      app.input_char(c);
-      EventResult::Search
-    }
-    _ => EventResult::Continue,
+    Some(Action::Next) => app.focus = Focus::Detail,
+    Some(Action::Search | Action::Back) => app.focus = Focus::Search,
+    Some(Action::ToggleFavorite) => app.toggle_favorite(),
+    Some(Action::CycleLang) => app.cycle_lang(),
+    Some(Action::EditNotes) if app.selected_command().is_some() => return EventResult::EditNotes,
+    _ => return type_to_search(app, key),
   }
+  EventResult::Continue
 }
 
-fn handle_detail_input(app: &mut App, key: KeyEvent) -> EventResult {
+fn handle_detail_input(app: &mut App, key: KeyEvent, action: Option<Action>) -> EventResult {
   // Modern 风格：上下滚动内容，左右切换结果
   if app.ui_style == UiStyle::Modern {
-    return handle_modern_result_input(app, key);
+    return handle_modern_result_input(app, key, action);
   }
 
-  // Classic 风格：原有逻辑
-  match key.code {
+  // Classic 风格
+  match action {
     // 滚动
-    KeyCode::Up | KeyCode::Char('k') => {
-      app.detail_scroll_up();
-      EventResult::Continue
-    }
-    KeyCode::Down | KeyCode::Char('j') => {
-      app.detail_scroll_down();
-      EventResult::Continue
-    }
-    KeyCode::PageUp | KeyCode::Char('g') => {
-      app.detail_scroll = app.detail_scroll.saturating_sub(10);
-      EventResult::Continue
-    }
-    KeyCode::PageDown | KeyCode::Char('G') => {
+    Some(Action::Up) => app.detail_scroll_up(),
+    Some(Action::Down) => app.detail_scroll_down(),
+    Some(Action::PageUp) => app.detail_scroll = app.detail_scroll.saturating_sub(10),
+    Some(Action::PageDown) => {
       app.detail_scroll = app
         .detail_scroll
         .saturating_add(10)
         .min(app.detail_max_scroll);
-      EventResult::Continue
-    }
-    // Jump to top
-    KeyCode::Home => {
-      app.detail_scroll = 0;
-      EventResult::Continue
-    }
-    // Jump to end
-    KeyCode::End => {
-      app.detail_scroll = app.detail_max_scroll;
-      EventResult::Continue
     }
+    Some(Action::Top) => app.detail_scroll = 0,
+    Some(Action::Bottom) => app.detail_scroll = app.detail_max_scroll,
     // 切换焦点
-    KeyCode::Tab => {
-      app.next_focus();
-      EventResult::Continue
-    }
-    KeyCode::Left | KeyCode::Char('h') | KeyCode::Esc => {
-      app.focus = Focus::List;
-      EventResult::Continue
-    }
-    KeyCode::Char('/') => {
-      app.focus = Focus::Search;
-      EventResult::Continue
-    }
-    _ => EventResult::Continue,
+    Some(Action::NextFocus) => app.next_focus(),
+    Some(Action::Prev | Action::Back) => app.focus = Focus::List,
+    Some(Action::Search) => app.focus = Focus::Search,
+    Some(Action::Open) => app.open_fill(false),
+    // 在示例之间跳转，选中的示例是填写、复制和运行的目标
+    Some(Action::NextExample) => app.select_example(true),
+    Some(Action::PrevExample) => app.select_example(false),
+    Some(Action::CopyExample) => app.copy_example(),
+    Some(Action::RunExample) => app.open_fill(true),
+    Some(Action::ToggleFavorite) => app.toggle_favorite(),
+    Some(Action::CycleLang) => app.cycle_lang(),
+    Some(Action::EditNotes) if app.selected_command().is_some() => return EventResult::EditNotes,
+    _ => {}
   }
+  EventResult::Continue
 }
 
 /// Modern 风格的结果区导航
-/// - 上下键/jk: 滚动内容
-/// - 左右键/hl: 切换结果
-fn handle_modern_result_input(app: &mut App, key: KeyEvent) -> EventResult {
-  match key.code {
-    // 上下键：滚动内容
-    KeyCode::Up | KeyCode::Char('k') => {
-      app.detail_scroll_up();
-      EventResult::Continue
-    }
-    KeyCode::Down | KeyCode::Char('j') => {
-      app.detail_scroll_down();
-      EventResult::Continue
-    }
-    // 左右键：切换结果
-    KeyCode::Left | KeyCode::Char('h') => {
+/// - up/down: 滚动内容
+/// - prev/next: 切换结果
+fn handle_modern_result_input(app: &mut App, key: KeyEvent, action: Option<Action>) -> EventResult {
+  match action {
+    // 滚动内容
+    Some(Action::Up) => app.detail_scroll_up(),
+    Some(Action::Down) => app.detail_scroll_down(),
+    // 切换结果
+    Some(Action::Prev) => {
       app.list_up();
       app.detail_scroll = 0; // 重置滚动位置
-      EventResult::Continue
     }
-    KeyCode::Right | KeyCode::Char('l') => {
+    Some(Action::Next) => {
       app.list_down();
       app.detail_scroll = 0; // 重置滚动位置
-      EventResult::Continue
     }
     // 翻页
-    KeyCode::PageUp => {
-      app.detail_scroll = app.detail_scroll.saturating_sub(10);
-      EventResult::Continue
-    }
-    KeyCode::PageDown => {
+    Some(Action::PageUp) => app.detail_scroll = app.detail_scroll.saturating_sub(10),
+    Some(Action::PageDown) => {
       app.detail_scroll = app
         .detail_scroll
         .saturating_add(10)
         .min(app.detail_max_scroll);
-      EventResult::Continue
     }
     // 跳转到首个/末个结果
-    KeyCode::Home | KeyCode::Char('g') => {
+    Some(Action::Top) => {
       app.selected = 0;
       app.detail_scroll = 0;
-      EventResult::Continue
     }
-    KeyCode::End | KeyCode::Char('G') => {
+    Some(Action::Bottom) => {
       app.selected = app.results.len().saturating_sub(1);
       app.detail_scroll = 0;
-      EventResult::Continue
     }
     // 回到搜索框
-    KeyCode::Char('/') | KeyCode::Esc => {
+    Some(Action::Search | Action::Back | Action::NextFocus) => app.focus = Focus::Search,
+    Some(Action::Open) => app.open_fill(false),
+    // 在示例之间跳转，选中的示例是填写、复制和运行的目标
+    Some(Action::NextExample) => app.select_example(true),
+    Some(Action::PrevExample) => app.select_example(false),
+    Some(Action::CopyExample) => app.copy_example(),
+    Some(Action::RunExample) => app.open_fill(true),
+    Some(Action::ToggleFavorite) => app.toggle_favorite(),
+    Some(Action::CycleLang) => app.cycle_lang(),
+    Some(Action::EditNotes) if app.selected_command().is_some() => return EventResult::EditNotes,
+    _ => return type_to_search(app, key),
+  }
+  EventResult::Continue
+}
+
+/// 在结果区输入未绑定的字符时切换到搜索
+fn type_to_search(app: &mut App, key: KeyEvent) -> EventResult {
+  match key.code {
+    KeyCode::Char(c) if is_text_input(&key) && (c.is_alphanumeric() || c == ' ') => {
       app.focus = Focus::Search;
-      EventResult::Continue
+      app.input_char(c);
+      EventResult::Search
     }
-    KeyCode::Tab => {
-      app.focus = Focus::Search;
+    _ => EventResult::Continue,
+  }
+}
+
+/// 处理鼠标事件
+pub fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> EventResult {
+  let position = Position::new(mouse.column, mouse.row);
+
+  // 帮助弹窗：点击任意位置关闭
+  if app.show_help {
+    if matches!(mouse.kind, MouseEventKind::Down(_)) {
+      app.show_help = false;
+    }
+    return EventResult::Continue;
+  }
+
+  if app.run.is_some() {
+    return handle_run_mouse(app, mouse.kind, position);
+  }
+
+  if app.fill.is_some() {
+    return handle_fill_mouse(app, mouse.kind, position);
+  }
+
+  if app.show_history {
+    return handle_history_mouse(app, mouse.kind, position);
+  }
+
+  match mouse.kind {
+    MouseEventKind::Down(MouseButton::Left) => handle_click(app, position),
+    MouseEventKind::Drag(MouseButton::Left) => {
+      if let Some(selection) = &mut app.selection {
+        selection.head = clamp_to(selection.area, position);
+      }
       EventResult::Continue
     }
-    // 输入字符时切换到搜索
-    KeyCode::Char(c) if c.is_alphanumeric() || c == ' ' => {
-      app.focus = Focus::Search;
-      app.input_char(c);
-      EventResult::Search
+    MouseEventKind::Up(MouseButton::Left) => {
+      // 只点击没有拖动时不算选中
+      if app.selection.as_ref().is_some_and(|s| s.anchor == s.head) {
+        app.selection = None;
+      } else {
+        app.copy_selection();
+      }
+      EventResult::Continue
     }
+    MouseEventKind::ScrollUp => handle_scroll(app, position, true),
+    MouseEventKind::ScrollDown => handle_scroll(app, position, false),
     _ => EventResult::Continue,
   }
 }
+
+/// 点击：切换焦点、选中结果，或在详情中开始拖动选择
+fn handle_click(app: &mut App, position: Position) -> EventResult {
+  app.selection = None;
+  let areas = app.areas;
+
+  if areas.search.contains(position) {
+    app.focus = Focus::Search;
+  } else if areas.list.contains(position) && !app.results.is_empty() {
+    app.focus = Focus::List;
+    let inner = areas.list.inner(Margin::new(1, 1));
+    if inner.contains(position) {
+      // 点击“最常用”的标题行不选择结果
+      let row = app.list_offset + usize::from(position.y - inner.y);
+      if let Some(index) = app.result_at_row(row) {
+        // 点击已选中的结果时打开，与回车相同
+        if index == app.selected {
+          app.record_open();
+          app.focus = Focus::Detail;
+        } else {
+          app.select_result(index);
+        }
+      }
+    }
+  } else if areas.detail.contains(position) && !app.results.is_empty() {
+    // Modern 风格的结果区即列表焦点
+    app.focus = match app.ui_style {
+      UiStyle::Modern => Focus::List,
+      UiStyle::Classic => Focus::Detail,
+    };
+    let inner = areas.detail.inner(Margin::new(1, 1));
+    if inner.contains(position) {
+      let point = (position.x, position.y);
+      app.selection = Some(Selection {
+        area: inner,
+        anchor: point,
+        head: point,
+        text: String::new(),
+      });
+    }
+  }
+  EventResult::Continue
+}
+
+/// 滚轮：结果列表上切换选中的结果，详情上滚动内容
+fn handle_scroll(app: &mut App, position: Position, up: bool) -> EventResult {
+  app.selection = None;
+  let step = app.config.tui.scroll_step.max(1);
+
+  if app.areas.list.contains(position) {
+    if up {
+      app.list_up();
+    } else {
+      app.list_down();
+    }
+  } else if app.areas.detail.contains(position) {
+    app.detail_scroll = if up {
+      app.detail_scroll.saturating_sub(step)
+    } else {
+      app
+        .detail_scroll
+        .saturating_add(step)
+        .min(app.detail_max_scroll)
+    };
+  }
+  EventResult::Continue
+}
+
+/// 历史弹窗：滚轮选择，点击条目打开，点击弹窗外关闭
+fn handle_history_mouse(app: &mut App, kind: MouseEventKind, position: Position) -> EventResult {
+  match kind {
+    MouseEventKind::ScrollUp => app.history_selected = app.history_selected.saturating_sub(1),
+    MouseEventKind::ScrollDown if app.history_selected + 1 < app.history.len() => {
+      app.history_selected += 1
+    }
+    MouseEventKind::Down(MouseButton::Left) => {
+      let area = app.areas.history;
+      let inner = area.inner(Margin::new(1, 1));
+      if !area.contains(position) {
+        app.show_history = false;
+      } else if inner.contains(position) {
+        let index = app.history_offset + usize::from(position.y - inner.y);
+        if index < app.history.len() {
+          app.history_selected = index;
+          app.open_history_entry();
+          return EventResult::Search;
+        }
+      }
+    }
+    _ => {}
+  }
+  EventResult::Continue
+}
+
+/// 填写示例弹窗：选择示例时滚轮切换，点击弹窗外关闭
+fn handle_fill_mouse(app: &mut App, kind: MouseEventKind, position: Position) -> EventResult {
+  let outside = !app.areas.fill.contains(position);
+  let Some(fill) = app.fill.as_mut() else {
+    return EventResult::Continue;
+  };
+  match kind {
+    MouseEventKind::ScrollUp if !fill.editing() => fill.selected = fill.selected.saturating_sub(1),
+    MouseEventKind::ScrollDown if !fill.editing() && fill.selected + 1 < fill.examples.len() => {
+      fill.selected += 1
+    }
+    MouseEventKind::Down(MouseButton::Left) if outside => app.fill = None,
+    _ => {}
+  }
+  EventResult::Continue
+}
+
+/// 运行示例弹窗：滚轮滚动输出，点击弹窗外关闭
+fn handle_run_mouse(app: &mut App, kind: MouseEventKind, position: Position) -> EventResult {
+  let outside = !app.areas.run.contains(position);
+  let step = app.config.tui.scroll_step.max(1);
+  let Some(run) = app.run.as_mut() else {
+    return EventResult::Continue;
+  };
+  match kind {
+    MouseEventKind::ScrollUp => run.scroll = run.scroll.saturating_sub(step),
+    MouseEventKind::ScrollDown => run.scroll = run.scroll.saturating_add(step).min(run.max_scroll),
+    MouseEventKind::Down(MouseButton::Left) if outside => app.run = None,
+    _ => {}
+  }
+  EventResult::Continue
+}
+
+/// 把位置限制在区域内（拖出详情时选区停在边缘）
+fn clamp_to(area: Rect, position: Position) -> (u16, u16) {
+  (
+    position
+      .x
+      .clamp(area.left(), area.right().saturating_sub(1)),
+    position
+      .y
+      .clamp(area.top(), area.bottom().saturating_sub(1)),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::AppConfig;
+  use crate::i18n::t;
+  use crate::tui::app::tests::app;
+  use crossterm::event::KeyModifiers;
+
+  fn press(app: &mut App, c: char) {
+    handle_key_event(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+  }
+
+  #[test]
+  fn test_run_disabled() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut app = app(temp_dir.path(), AppConfig::default(), UiStyle::Modern);
+    app.focus = Focus::List;
+    press(&mut app, 'x');
+    assert_eq!(app.status, t!("tui.run.disabled"));
+    assert!(app.fill.is_none());
+    assert!(app.run.is_none());
+  }
+
+  #[tokio::test]
+  async fn test_run_confirm() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = AppConfig::default();
+    config.tui.allow_execute = true;
+    let mut app = app(temp_dir.path(), config, UiStyle::Classic);
+    app.focus = Focus::Detail;
+
+    // 选中没有占位符的示例，填写后直接等待确认
+    press(&mut app, '[');
+    press(&mut app, 'x');
+    assert!(app.fill.is_none());
+    let run = app.run.as_ref().unwrap();
+    assert_eq!(run.command, "tar -tf archive.tar");
+    assert_eq!(run.state, RunState::Confirm);
+
+    // n 和 Esc 取消，不运行
+    press(&mut app, 'n');
+    assert!(app.run.is_none());
+    press(&mut app, 'x');
+    handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.run.is_none());
+
+    // 确认前其他按键不起作用
+    press(&mut app, 'x');
+    press(&mut app, 'j');
+    assert_eq!(app.run.as_ref().unwrap().state, RunState::Confirm);
+
+    // y 确认后运行，结束后显示退出状态
+    press(&mut app, 'y');
+    assert!(app.running());
+    for _ in 0..100 {
+      app.receive_run_output();
+      if !app.running() {
+        break;
+      }
+      tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(matches!(
+      app.run.as_ref().unwrap().state,
+      RunState::Done { .. }
+    ));
+    handle_key_event(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.run.is_none());
+  }
+}
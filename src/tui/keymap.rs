@@ -0,0 +1,442 @@
+//! 可配置的快捷键
+//!
+//! `[tui.keys]` 把动作映射到按键，启动时解析并检查冲突；
+//! 事件处理通过 Keymap 把按键翻译为动作，文本编辑键（字符输入、退格、光标移动）仍固定处理。
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeysConfig;
+use crate::i18n::t;
+
+/// 可绑定的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+  Quit,
+  Help,
+  ToggleLogs,
+  ToggleStyle,
+  ClearSearch,
+  Up,
+  Down,
+  Prev,
+  Next,
+  PageUp,
+  PageDown,
+  Top,
+  Bottom,
+  NextFocus,
+  Search,
+  Back,
+  Open,
+  ToggleFavorite,
+  Favorites,
+  History,
+  CycleLang,
+  EditNotes,
+  NextExample,
+  PrevExample,
+  CopyExample,
+  RunExample,
+}
+
+impl Action {
+  pub const ALL: [Action; 26] = [
+    Action::Search,
+    Action::Up,
+    Action::Down,
+    Action::Prev,
+    Action::Next,
+    Action::Open,
+    Action::NextExample,
+    Action::PrevExample,
+    Action::CopyExample,
+    Action::RunExample,
+    Action::ToggleFavorite,
+    Action::CycleLang,
+    Action::EditNotes,
+    Action::Favorites,
+    Action::History,
+    Action::NextFocus,
+    Action::Back,
+    Action::PageUp,
+    Action::PageDown,
+    Action::Top,
+    Action::Bottom,
+    Action::ClearSearch,
+    Action::Help,
+    Action::ToggleStyle,
+    Action::ToggleLogs,
+    Action::Quit,
+  ];
+
+  /// 配置中的键名
+  pub fn name(self) -> &'static str {
+    match self {
+      Action::Quit => "quit",
+      Action::Help => "help",
+      Action::ToggleLogs => "toggle_logs",
+      Action::ToggleStyle => "toggle_style",
+      Action::ClearSearch => "clear_search",
+      Action::Up => "up",
+      Action::Down => "down",
+      Action::Prev => "prev",
+      Action::Next => "next",
+      Action::PageUp => "page_up",
+      Action::PageDown => "page_down",
+      Action::Top => "top",
+      Action::Bottom => "bottom",
+      Action::NextFocus => "next_focus",
+      Action::Search => "search",
+      Action::Back => "back",
+      Action::Open => "open",
+      Action::ToggleFavorite => "toggle_favorite",
+      Action::Favorites => "favorites",
+      Action::History => "history",
+      Action::CycleLang => "cycle_lang",
+      Action::EditNotes => "notes",
+      Action::NextExample => "next_example",
+      Action::PrevExample => "prev_example",
+      Action::CopyExample => "copy_example",
+      Action::RunExample => "run_example",
+    }
+  }
+
+  /// 帮助弹窗中的说明
+  pub fn description(self) -> &'static str {
+    match self {
+      Action::Quit => t!("tui.action.quit"),
+      Action::Help => t!("tui.action.help"),
+      Action::ToggleLogs => t!("tui.action.toggle_logs"),
+      Action::ToggleStyle => t!("tui.action.toggle_style"),
+      Action::ClearSearch => t!("tui.action.clear_search"),
+      Action::Up => t!("tui.action.up"),
+      Action::Down => t!("tui.action.down"),
+      Action::Prev => t!("tui.action.prev"),
+      Action::Next => t!("tui.action.next"),
+      Action::PageUp => t!("tui.action.page_up"),
+      Action::PageDown => t!("tui.action.page_down"),
+      Action::Top => t!("tui.action.top"),
+      Action::Bottom => t!("tui.action.bottom"),
+      Action::NextFocus => t!("tui.action.next_focus"),
+      Action::Search => t!("tui.action.search"),
+      Action::Back => t!("tui.action.back"),
+      Action::Open => t!("tui.action.open"),
+      Action::ToggleFavorite => t!("tui.action.toggle_favorite"),
+      Action::Favorites => t!("tui.action.favorites"),
+      Action::History => t!("tui.action.history"),
+      Action::CycleLang => t!("tui.action.cycle_lang"),
+      Action::EditNotes => t!("tui.action.notes"),
+      Action::NextExample => t!("tui.action.next_example"),
+      Action::PrevExample => t!("tui.action.prev_example"),
+      Action::CopyExample => t!("tui.action.copy_example"),
+      Action::RunExample => t!("tui.action.run_example"),
+    }
+  }
+
+  fn configured(self, keys: &KeysConfig) -> &[String] {
+    match self {
+      Action::Quit => &keys.quit,
+      Action::Help => &keys.help,
+      Action::ToggleLogs => &keys.toggle_logs,
+      Action::ToggleStyle => &keys.toggle_style,
+      Action::ClearSearch => &keys.clear_search,
+      Action::Up => &keys.up,
+      Action::Down => &keys.down,
+      Action::Prev => &keys.prev,
+      Action::Next => &keys.next,
+      Action::PageUp => &keys.page_up,
+      Action::PageDown => &keys.page_down,
+      Action::Top => &keys.top,
+      Action::Bottom => &keys.bottom,
+      Action::NextFocus => &keys.next_focus,
+      Action::Search => &keys.search,
+      Action::Back => &keys.back,
+      Action::Open => &keys.open,
+      Action::ToggleFavorite => &keys.toggle_favorite,
+      Action::Favorites => &keys.favorites,
+      Action::History => &keys.history,
+      Action::CycleLang => &keys.cycle_lang,
+      Action::EditNotes => &keys.notes,
+      Action::NextExample => &keys.next_example,
+      Action::PrevExample => &keys.prev_example,
+      Action::CopyExample => &keys.copy_example,
+      Action::RunExample => &keys.run_example,
+    }
+  }
+}
+
+/// 一个按键（含 Ctrl/Alt/Shift 修饰）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+  code: KeyCode,
+  modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+  /// 解析 "ctrl+q"、"pagedown"、"G"、"shift+tab" 等写法
+  pub fn parse(spec: &str) -> Result<Self, String> {
+    let spec = spec.trim();
+    let (mods, key) = match spec.rsplit_once('+') {
+      // 单独的 "+" 或 "ctrl++"
+      Some((mods, "")) if !mods.is_empty() || spec == "+" => {
+        (mods.strip_suffix('+').unwrap_or(mods), "+")
+      }
+      Some((mods, key)) => (mods, key),
+      None => ("", spec),
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    for m in mods.split('+').filter(|m| !m.is_empty()) {
+      modifiers |= match m.to_lowercase().as_str() {
+        "ctrl" | "control" => KeyModifiers::CONTROL,
+        "alt" => KeyModifiers::ALT,
+        "shift" => KeyModifiers::SHIFT,
+        _ => return Err(format!("unknown modifier '{}' in '{}'", m, spec)),
+      };
+    }
+
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+      (Some(c), None) => KeyCode::Char(c),
+      _ => match key.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+          Some(n @ 1..=12) => KeyCode::F(n),
+          _ => return Err(format!("unknown key '{}'", spec)),
+        },
+      },
+    };
+
+    Ok(Self::normalize(code, modifiers))
+  }
+
+  /// 统一 Shift 的表示：字符按大小写区分，Shift+Tab 即 BackTab
+  fn normalize(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+    let code = match code {
+      KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+        KeyCode::Char(c.to_ascii_lowercase())
+      }
+      KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+        KeyCode::Char(c.to_ascii_uppercase())
+      }
+      KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+      code => code,
+    };
+    if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+      modifiers.remove(KeyModifiers::SHIFT);
+    }
+    Self {
+      code,
+      modifiers: modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+    }
+  }
+
+  fn from_event(key: &KeyEvent) -> Self {
+    Self::normalize(key.code, key.modifiers)
+  }
+}
+
+/// 不带 Ctrl/Alt 的字符键，在搜索框中作为输入处理
+pub fn is_text_input(key: &KeyEvent) -> bool {
+  matches!(key.code, KeyCode::Char(_))
+    && !key
+      .modifiers
+      .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
+
+impl std::fmt::Display for KeyBinding {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.modifiers.contains(KeyModifiers::CONTROL) {
+      write!(f, "Ctrl+")?;
+    }
+    if self.modifiers.contains(KeyModifiers::ALT) {
+      write!(f, "Alt+")?;
+    }
+    if self.modifiers.contains(KeyModifiers::SHIFT) {
+      write!(f, "Shift+")?;
+    }
+    match self.code {
+      KeyCode::Char(' ') => write!(f, "Space"),
+      KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+        write!(f, "{}", c.to_ascii_uppercase())
+      }
+      KeyCode::Char(c) => write!(f, "{}", c),
+      KeyCode::Up => write!(f, "↑"),
+      KeyCode::Down => write!(f, "↓"),
+      KeyCode::Left => write!(f, "←"),
+      KeyCode::Right => write!(f, "→"),
+      KeyCode::PageUp => write!(f, "PgUp"),
+      KeyCode::PageDown => write!(f, "PgDn"),
+      KeyCode::BackTab => write!(f, "Shift+Tab"),
+      KeyCode::F(n) => write!(f, "F{}", n),
+      code => write!(f, "{:?}", code),
+    }
+  }
+}
+
+/// 按键到动作的映射
+#[derive(Debug, Clone)]
+pub struct Keymap {
+  bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap {
+  fn default() -> Self {
+    Self::from_config(&KeysConfig::default()).expect("default key bindings are valid")
+  }
+}
+
+impl Keymap {
+  /// 解析配置；返回所有无法识别的按键和冲突的绑定
+  pub fn from_config(keys: &KeysConfig) -> Result<Self, Vec<String>> {
+    let mut bindings: Vec<(KeyBinding, Action)> = Vec::new();
+    let mut errors = Vec::new();
+
+    for action in Action::ALL {
+      for spec in action.configured(keys) {
+        let binding = match KeyBinding::parse(spec) {
+          Ok(binding) => binding,
+          Err(e) => {
+            errors.push(format!("{}: {}", action.name(), e));
+            continue;
+          }
+        };
+        match bindings.iter().find(|(b, _)| *b == binding) {
+          Some((_, other)) if *other == action => {}
+          Some((_, other)) => errors.push(format!(
+            "'{}' is bound to both '{}' and '{}'",
+            spec,
+            other.name(),
+            action.name()
+          )),
+          None => bindings.push((binding, action)),
+        }
+      }
+    }
+
+    if errors.is_empty() {
+      Ok(Self { bindings })
+    } else {
+      Err(errors)
+    }
+  }
+
+  /// 查找按键对应的动作
+  pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+    let pressed = KeyBinding::from_event(key);
+    self
+      .bindings
+      .iter()
+      .find(|(binding, _)| *binding == pressed)
+      .map(|(_, action)| *action)
+  }
+
+  /// 动作绑定的所有按键，用于帮助和提示，如 "Ctrl+H / ?"
+  pub fn label(&self, action: Action) -> String {
+    let keys: Vec<String> = self
+      .bindings
+      .iter()
+      .filter(|(_, a)| *a == action)
+      .map(|(binding, _)| binding.to_string())
+      .collect();
+    if keys.is_empty() {
+      "-".to_string()
+    } else {
+      keys.join(" / ")
+    }
+  }
+
+  /// 动作的第一个按键，用于简短提示
+  pub fn primary(&self, action: Action) -> String {
+    self
+      .bindings
+      .iter()
+      .find(|(_, a)| *a == action)
+      .map(|(binding, _)| binding.to_string())
+      .unwrap_or_else(|| "-".to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+  }
+
+  #[test]
+  fn test_keymap() {
+    let keymap = Keymap::default();
+    assert_eq!(
+      keymap.action(&press(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+      Some(Action::Quit)
+    );
+    // 终端报告的大写字母带 Shift
+    assert_eq!(
+      keymap.action(&press(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+      Some(Action::Bottom)
+    );
+    assert_eq!(
+      keymap.action(&press(KeyCode::Char('z'), KeyModifiers::NONE)),
+      None
+    );
+    assert_eq!(
+      keymap.action(&press(KeyCode::Char('n'), KeyModifiers::NONE)),
+      Some(Action::EditNotes)
+    );
+    assert_eq!(
+      keymap.action(&press(KeyCode::Char(']'), KeyModifiers::NONE)),
+      Some(Action::NextExample)
+    );
+    assert_eq!(
+      keymap.action(&press(KeyCode::Char('['), KeyModifiers::NONE)),
+      Some(Action::PrevExample)
+    );
+    assert_eq!(keymap.label(Action::Help), "Ctrl+H / ?");
+
+    assert_eq!(
+      KeyBinding::parse("shift+tab").unwrap(),
+      KeyBinding::parse("backtab").unwrap()
+    );
+    assert_eq!(KeyBinding::parse("ctrl++").unwrap().to_string(), "Ctrl++");
+    assert!(is_text_input(&press(
+      KeyCode::Char(' '),
+      KeyModifiers::NONE
+    )));
+    assert!(!is_text_input(&press(
+      KeyCode::Char('x'),
+      KeyModifiers::ALT
+    )));
+
+    let keys = KeysConfig {
+      search: vec!["j".to_string()],
+      quit: vec!["hyper+q".to_string(), "f13".to_string()],
+      ..Default::default()
+    };
+    let errors = Keymap::from_config(&keys).unwrap_err();
+    assert_eq!(
+      errors,
+      vec![
+        "'j' is bound to both 'search' and 'down'",
+        "quit: unknown modifier 'hyper' in 'hyper+q'",
+        "quit: unknown key 'f13'",
+      ]
+    );
+  }
+}
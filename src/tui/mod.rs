@@ -1,5 +1,6 @@
 pub mod app;
 pub mod events;
+pub mod keymap;
 pub mod ui;
 
 use std::io;
@@ -7,7 +8,8 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crossterm::{
-  event::{Event, KeyEventKind},
+  cursor::Show,
+  event::{DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
   execute,
   terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -18,11 +20,14 @@ use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::Layer;
 
 use crate::config::AppConfig;
+use crate::i18n::t;
+use crate::notes;
 use crate::search::SearchEngine;
 use crate::storage::Database;
+use crate::update::check::{self as update_check, Notice};
 
-use app::{create_log_buffer, App, LogBuffer};
-use events::{handle_key_event, poll_event, EventResult};
+use app::{create_log_buffer, App, LogBuffer, SPINNER_INTERVAL};
+use events::{handle_key_event, handle_mouse_event, poll_event, EventResult};
 
 // Re-export UiStyle for external use
 pub use app::UiStyle;
@@ -85,6 +90,11 @@ pub async fn run(
   config: AppConfig,
   ui_style: UiStyle,
 ) -> anyhow::Result<()> {
+  // 快捷键配置有误时在进入界面前报错
+  if let Err(errors) = keymap::Keymap::from_config(&config.tui.keys) {
+    anyhow::bail!("{}:\n  {}", t!("tui.invalid_keys"), errors.join("\n  "));
+  }
+
   // 创建日志缓冲区
   let log_buffer = if debug_mode {
     Some(create_log_buffer(config.tui.log_buffer_size))
@@ -95,10 +105,29 @@ pub async fn run(
   // 初始化日志系统
   init_tui_logging(&data_dir, log_buffer.clone(), debug_mode, &config);
 
+  // 定时检查数据集和 rtfm 新版本
+  let dataset_version = db.get_metadata().ok().flatten().map(|m| m.version);
+  let check_config = std::sync::Arc::new(config.clone());
+  let notices = update_check::spawn(
+    data_dir.clone(),
+    move || check_config.clone(),
+    move || dataset_version.clone(),
+  );
+
   // 初始化终端
+  let mouse = config.tui.mouse;
   enable_raw_mode()?;
   let mut stdout = io::stdout();
   execute!(stdout, EnterAlternateScreen)?;
+  if mouse {
+    execute!(stdout, EnableMouseCapture)?;
+  }
+  // panic 时也恢复终端，否则 shell 中会残留 raw 模式和鼠标捕获
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    let _ = restore_terminal(mouse);
+    default_hook(info);
+  }));
   let backend = CrosstermBackend::new(stdout);
   let mut terminal = Terminal::new(backend)?;
 
@@ -110,18 +139,62 @@ pub async fn run(
   debug!("TUI started");
 
   // 主循环
-  let result = run_app(&mut terminal, &mut app).await;
+  let result = run_app(&mut terminal, &mut app, notices).await;
 
   // 恢复终端
-  disable_raw_mode()?;
-  execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-  terminal.show_cursor()?;
+  restore_terminal(mouse)?;
 
   debug!("TUI exited");
 
   result
 }
 
+/// 暂停界面，在同一个终端中用编辑器编辑选中命令的笔记，保存后重新索引该命令
+async fn edit_notes(
+  terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+  app: &mut App,
+) -> anyhow::Result<()> {
+  let Some((name, _)) = app.selected_command() else {
+    return Ok(());
+  };
+  let name = name.to_string();
+  let initial = app.db.get_note(&name)?.unwrap_or_default();
+
+  let mouse = app.config.tui.mouse;
+  restore_terminal(mouse)?;
+  let edited = notes::edit(&name, &initial);
+  enable_raw_mode()?;
+  execute!(io::stdout(), EnterAlternateScreen)?;
+  if mouse {
+    execute!(io::stdout(), EnableMouseCapture)?;
+  }
+  terminal.clear()?;
+
+  let saved = match edited {
+    Ok(text) => {
+      let mut search = app.search.write().await;
+      notes::save(&app.db, &mut search, &name, &text)
+    }
+    Err(e) => Err(e),
+  };
+  app.status = match saved {
+    Ok(true) => t!("tui.status.note_saved", name = name),
+    Ok(false) => t!("note.unchanged", name = name),
+    Err(e) => t!("tui.status.note_failed", error = e),
+  };
+  Ok(())
+}
+
+/// 恢复终端：退出 raw 模式、鼠标捕获和备用屏幕，显示光标
+fn restore_terminal(mouse: bool) -> io::Result<()> {
+  disable_raw_mode()?;
+  let mut stdout = io::stdout();
+  if mouse {
+    execute!(stdout, DisableMouseCapture)?;
+  }
+  execute!(stdout, LeaveAlternateScreen, Show)
+}
+
 /// 初始化 TUI 日志系统
 fn init_tui_logging(
   data_dir: &Path,
@@ -172,16 +245,34 @@ fn init_tui_logging(
 async fn run_app(
   terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
   app: &mut App,
+  mut notices: tokio::sync::mpsc::UnboundedReceiver<Notice>,
 ) -> anyhow::Result<()> {
   let poll_timeout = Duration::from_millis(app.config.tui.poll_timeout_ms);
 
   loop {
+    // 更新提示显示在状态栏
+    while let Ok(notice) = notices.try_recv() {
+      app.status = format!("{}. {}", notice.title, notice.body);
+    }
+
+    app.receive_search_results();
+    app.receive_run_output();
+
     // 渲染
     terminal.draw(|f| ui::render(f, app))?;
 
+    // 等待搜索时缩短轮询，按时开始搜索并刷新加载动画
+    let mut timeout = poll_timeout;
+    if app.loading || app.running() {
+      timeout = timeout.min(SPINNER_INTERVAL);
+    }
+    if let Some(wait) = app.search_wait() {
+      timeout = timeout.min(wait);
+    }
+
     // 处理事件
-    if let Some(event) = poll_event(poll_timeout)? {
-      match event {
+    if let Some(event) = poll_event(timeout)? {
+      let result = match event {
         Event::Key(key) => {
           // 只处理按下事件，忽略释放事件（修复 Windows 上字符重复问题）
           if key.kind != KeyEventKind::Press {
@@ -189,24 +280,30 @@ async fn run_app(
           }
 
           debug!("Key: {:?}", key.code);
-
-          match handle_key_event(app, key) {
-            EventResult::Continue => {}
-            EventResult::Search => {
-              app.search().await;
-            }
-            EventResult::Quit => {
-              break;
-            }
-          }
+          handle_key_event(app, key)
         }
+        Event::Mouse(mouse) => handle_mouse_event(app, mouse),
         Event::Resize(w, h) => {
           debug!("Resize: {}x{}", w, h);
+          // 区域位置变了，选区不再对应原来的文字
+          app.selection = None;
+          EventResult::Continue
+        }
+        _ => EventResult::Continue,
+      };
+
+      match result {
+        EventResult::Continue => {}
+        EventResult::Search => app.request_search(),
+        EventResult::EditNotes => edit_notes(terminal, app).await?,
+        EventResult::Quit => {
+          break;
         }
-        _ => {}
       }
     }
 
+    app.start_due_search();
+
     if app.should_quit {
       break;
     }
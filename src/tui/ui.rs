@@ -1,13 +1,16 @@
 use ratatui::{
-  layout::{Alignment, Constraint, Direction, Layout, Rect},
+  layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
   style::{Color, Modifier, Style},
   text::{Line, Span},
   widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
   Frame,
 };
+use std::ops::Range;
 use unicode_width::UnicodeWidthStr;
 
-use super::app::{App, Focus, UiStyle};
+use super::app::{App, Focus, PaneAreas, RunState, UiStyle};
+use super::keymap::Action;
+use crate::i18n::t;
 
 /// ASCII Art Logo - 翻开的手册书本造型，致敬经典 RTFM 梗
 /// 固定 7 行高度，左对齐显示以保持排版
@@ -26,6 +29,7 @@ const LOGO_HEIGHT: u16 = 7;
 
 /// 主界面渲染
 pub fn render(frame: &mut Frame, app: &mut App) {
+  app.areas = PaneAreas::default();
   match app.ui_style {
     UiStyle::Modern => render_modern(frame, app),
     UiStyle::Classic => render_classic(frame, app),
@@ -85,6 +89,7 @@ fn render_classic(frame: &mut Frame, app: &mut App) {
     idx += 1;
   }
 
+  app.areas.search = chunks[idx];
   render_search_bar(frame, app, chunks[idx]);
   idx += 1;
 
@@ -97,10 +102,20 @@ fn render_classic(frame: &mut Frame, app: &mut App) {
   }
 
   render_status_bar(frame, app, chunks[idx]);
+  render_selection(frame, app);
 
-  // 帮助弹窗
+  // 历史、填写示例、运行示例和帮助弹窗
+  if app.show_history {
+    render_history_popup(frame, app);
+  }
+  if app.fill.is_some() {
+    render_fill_popup(frame, app);
+  }
+  if app.run.is_some() {
+    render_run_popup(frame, app);
+  }
   if app.show_help {
-    render_help_popup(frame);
+    render_help_popup(frame, app);
   }
 }
 
@@ -134,17 +149,24 @@ fn render_search_bar(frame: &mut Frame, app: &App, area: Rect) {
     Style::default().fg(Color::Gray)
   };
 
-  let search_block = Block::default()
+  let mut search_block = Block::default()
     .borders(Borders::ALL)
     .border_style(search_style)
-    .title(" Search (/ to focus) ");
+    .title(t!("tui.classic.search_title"));
+  // 等待搜索结果时在标题后显示加载动画
+  if let Some(spinner) = app.spinner() {
+    search_block = search_block.title(Span::styled(
+      format!("{} ", spinner),
+      Style::default().fg(Color::Yellow),
+    ));
+  }
 
   let inner = search_block.inner(chunks[0]);
 
   // 搜索内容
   let search_text = if app.query.is_empty() && app.focus != Focus::Search {
     Span::styled(
-      "Type to search commands...",
+      t!("tui.classic.search_placeholder"),
       Style::default().fg(Color::DarkGray),
     )
   } else {
@@ -161,9 +183,15 @@ fn render_search_bar(frame: &mut Frame, app: &App, area: Rect) {
   }
 
   // 快捷键提示
-  let hints = Paragraph::new(" [Tab] Switch  [Ctrl+H] Help  [Esc] Back/Quit")
-    .style(Style::default().fg(Color::DarkGray))
-    .alignment(Alignment::Right);
+  let keys = &app.keymap;
+  let hints = Paragraph::new(t!(
+    "tui.classic.search_hints",
+    switch = keys.primary(Action::NextFocus),
+    help = keys.primary(Action::Help),
+    back = keys.primary(Action::Back)
+  ))
+  .style(Style::default().fg(Color::DarkGray))
+  .alignment(Alignment::Right);
 
   // 垂直居中显示提示
   let hint_area = Rect {
@@ -182,23 +210,28 @@ fn render_main(frame: &mut Frame, app: &mut App, area: Rect) {
     .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
     .split(area);
 
+  app.areas.list = chunks[0];
+  app.areas.detail = chunks[1];
   render_list(frame, app, chunks[0]);
   render_detail(frame, app, chunks[1]);
 }
 
 /// 渲染搜索结果列表
-fn render_list(frame: &mut Frame, app: &App, area: Rect) {
+fn render_list(frame: &mut Frame, app: &mut App, area: Rect) {
   let list_style = if app.focus == Focus::List {
     Style::default().fg(Color::Yellow)
   } else {
     Style::default().fg(Color::Gray)
   };
 
-  let title = if app.results.is_empty() {
-    " Results ".to_string()
+  let mut title = if app.results.is_empty() {
+    t!("tui.classic.results_title").to_string()
   } else {
-    format!(" Results ({}) ", app.results.len())
+    t!("tui.classic.results_count", count = app.results.len())
   };
+  if app.favorites_only {
+    title = format!(" ★{}", title);
+  }
 
   let block = Block::default()
     .borders(Borders::ALL)
@@ -206,12 +239,20 @@ fn render_list(frame: &mut Frame, app: &App, area: Rect) {
     .title(title);
 
   if app.results.is_empty() {
-    let empty_text = if app.query.is_empty() {
-      "Type to search"
+    let empty_text = if app.query.is_empty() && app.favorites_only {
+      no_favorites(app)
+    } else if app.query.is_empty() {
+      t!("tui.classic.type_to_search").to_string()
     } else if app.loading {
-      "Searching..."
+      t!("tui.searching").to_string()
+    } else if !app.suggestions.is_empty() {
+      format!(
+        "{}\n\n{}",
+        t!("tui.no_results"),
+        t!("tui.did_you_mean", names = app.suggestions.join(", "))
+      )
     } else {
-      "No results found"
+      t!("tui.no_results").to_string()
     };
     let empty = Paragraph::new(empty_text)
       .style(Style::default().fg(Color::DarkGray))
@@ -220,7 +261,7 @@ fn render_list(frame: &mut Frame, app: &App, area: Rect) {
     return;
   }
 
-  let items: Vec<ListItem> = app
+  let mut items: Vec<ListItem> = app
     .results
     .iter()
     .enumerate()
@@ -235,10 +276,40 @@ fn render_list(frame: &mut Frame, app: &App, area: Rect) {
       };
 
       // Show full command name, let ratatui handle overflow
-      let content = Line::from(Span::styled(&result.name, style));
+      let content = if result.example.is_some() {
+        // 匹配的示例：命令名 → 示例说明
+        let arrow_style = if i == app.selected {
+          style
+        } else {
+          Style::default().fg(Color::DarkGray)
+        };
+        Line::from(vec![
+          Span::styled(result.name.as_str(), style),
+          Span::styled(" → ", arrow_style),
+          Span::styled(result.description.as_str(), arrow_style),
+        ])
+      } else if app.favorites.contains(&result.name) {
+        Line::from(Span::styled(format!("★ {}", result.name), style))
+      } else {
+        Line::from(Span::styled(result.name.as_str(), style))
+      };
       ListItem::new(content)
     })
     .collect();
+  // 查询为空时，最常用的命令列在最近查看之后，前面加一行标题
+  let mut selected_row = app.selected;
+  if let Some(start) = app.frequent_start {
+    items.insert(
+      start,
+      ListItem::new(Line::from(Span::styled(
+        format!("── {} ──", t!("tui.frequently_used")),
+        Style::default().fg(Color::DarkGray),
+      ))),
+    );
+    if app.selected >= start {
+      selected_row += 1;
+    }
+  }
 
   let list = List::new(items).block(block).highlight_style(
     Style::default()
@@ -248,8 +319,9 @@ fn render_list(frame: &mut Frame, app: &App, area: Rect) {
 
   // Use ListState for proper scrolling when selected item is out of view
   let mut list_state = ListState::default();
-  list_state.select(Some(app.selected));
+  list_state.select(Some(selected_row));
   frame.render_stateful_widget(list, area, &mut list_state);
+  app.list_offset = list_state.offset();
 }
 
 /// 渲染命令详情
@@ -263,49 +335,45 @@ fn render_detail(frame: &mut Frame, app: &mut App, area: Rect) {
   let block = Block::default()
     .borders(Borders::ALL)
     .border_style(detail_style)
-    .title(" Details ");
+    .title(t!("tui.classic.details_title"));
 
   let Some((name, lang)) = app.selected_command() else {
-    let empty = Paragraph::new("Select a command to view details")
+    let empty = Paragraph::new(t!("tui.classic.select_command"))
       .style(Style::default().fg(Color::DarkGray))
       .block(block);
     frame.render_widget(empty, area);
     return;
   };
 
-  let content = app
-    .get_command_detail(name, lang)
-    .unwrap_or_else(|| format!("Command not found: {} ({})", name, lang));
+  let content = app.get_command_detail(name, lang).unwrap_or_else(|| {
+    t!(
+      "tui.command_not_found",
+      name = format!("{} ({})", name, lang)
+    )
+  });
+
+  // 选中的示例加底色
+  let active = app
+    .active_example()
+    .and_then(|index| example_ranges(&content).into_iter().nth(index))
+    .unwrap_or_default();
 
   // 简单的 Markdown 渲染
   let lines: Vec<Line> = content
     .lines()
-    .map(|line| {
-      if let Some(header) = line.strip_prefix("# ") {
-        Line::from(Span::styled(
-          header,
-          Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD),
-        ))
-      } else if let Some(header) = line.strip_prefix("## ") {
-        Line::from(Span::styled(
-          header,
-          Style::default()
-            .fg(Color::Green)
-            .add_modifier(Modifier::BOLD),
-        ))
-      } else if line.starts_with("```") {
-        Line::from(Span::styled(
-          "────────────────",
-          Style::default().fg(Color::DarkGray),
-        ))
-      } else if line.starts_with("  ") || line.starts_with('\t') {
-        // 代码行
-        Line::from(Span::styled(line, Style::default().fg(Color::Yellow)))
-      } else {
-        Line::from(line)
+    .enumerate()
+    .map(|(i, line)| {
+      let mut line = render_classic_line(line);
+      if active.contains(&i) {
+        // 标记选中示例的说明行
+        if i == active.start {
+          line
+            .spans
+            .insert(0, Span::styled("▶ ", Style::default().fg(Color::Green)));
+        }
+        line = line.patch_style(Style::default().bg(Color::DarkGray));
       }
+      line
     })
     .collect();
 
@@ -313,6 +381,7 @@ fn render_detail(frame: &mut Frame, app: &mut App, area: Rect) {
   let content_lines = lines.len() as u16;
   let visible_lines = area.height.saturating_sub(2); // subtract border
   app.set_detail_max_scroll(content_lines, visible_lines);
+  scroll_to_example(app, active.start);
 
   let paragraph = Paragraph::new(lines)
     .block(block)
@@ -322,12 +391,75 @@ fn render_detail(frame: &mut Frame, app: &mut App, area: Rect) {
   frame.render_widget(paragraph, area);
 }
 
+/// Classic 详情的一行（简单的 Markdown 渲染）
+fn render_classic_line(line: &str) -> Line<'_> {
+  if let Some(header) = line.strip_prefix("# ") {
+    Line::from(Span::styled(
+      header,
+      Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD),
+    ))
+  } else if let Some(header) = line.strip_prefix("## ") {
+    Line::from(Span::styled(
+      header,
+      Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD),
+    ))
+  } else if line.starts_with("```") {
+    Line::from(Span::styled(
+      "────────────────",
+      Style::default().fg(Color::DarkGray),
+    ))
+  } else if line.starts_with("  ") || line.starts_with('\t') {
+    // 代码行
+    Line::from(Span::styled(line, Style::default().fg(Color::Yellow)))
+  } else {
+    Line::from(line)
+  }
+}
+
+/// 详情内容中各示例所在的行（"## 说明" 到代码块结束），按示例顺序
+fn example_ranges(content: &str) -> Vec<Range<usize>> {
+  let lines: Vec<&str> = content.lines().collect();
+  let mut ranges = Vec::new();
+  let mut i = 0;
+  while i < lines.len() {
+    let is_example =
+      lines[i].starts_with("## ") && lines.get(i + 1).is_some_and(|l| l.starts_with("```"));
+    if !is_example {
+      i += 1;
+      continue;
+    }
+    let end = lines[i + 2..]
+      .iter()
+      .position(|l| l.starts_with("```"))
+      .map_or(lines.len(), |p| i + 2 + p + 1);
+    ranges.push(i..end);
+    i = end;
+  }
+  ranges
+}
+
+/// 刚选中示例时把详情滚动到该示例开始的行
+fn scroll_to_example(app: &mut App, line: usize) {
+  if std::mem::take(&mut app.scroll_to_example) {
+    app.detail_scroll = u16::try_from(line)
+      .unwrap_or(u16::MAX)
+      .min(app.detail_max_scroll);
+  }
+}
+
 /// 渲染日志面板
 fn render_log_panel(frame: &mut Frame, app: &App, area: Rect) {
   let block = Block::default()
     .borders(Borders::ALL)
     .border_style(Style::default().fg(Color::Magenta))
-    .title(" Debug Logs [Ctrl+L close] ");
+    .title(t!(
+      "tui.classic.logs_title",
+      key = app.keymap.primary(Action::ToggleLogs)
+    ));
 
   let logs = app.get_logs();
   let inner_height = area.height.saturating_sub(2) as usize;
@@ -365,10 +497,14 @@ fn render_log_panel(frame: &mut Frame, app: &App, area: Rect) {
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
   // When an item is selected, show full name + description using entire width
   if let Some((name, lang)) = app.selected_command() {
+    // 匹配的示例显示其代码
     let desc = app
       .results
       .get(app.selected)
-      .map(|r| r.description.as_str())
+      .map(|r| match &r.example {
+        Some(example) => example.code.as_str(),
+        None => r.description.as_str(),
+      })
       .unwrap_or("");
 
     // Calculate available space: total width - name - " [xx] - " (about 8 chars)
@@ -394,94 +530,333 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let status = Paragraph::new(format!(" {}", app.status)).style(Style::default().fg(Color::Cyan));
     frame.render_widget(status, chunks[0]);
 
-    let hints = Paragraph::new("[↑↓/jk] Nav  [Enter] View ")
-      .style(Style::default().fg(Color::DarkGray))
-      .alignment(Alignment::Right);
+    let keys = &app.keymap;
+    let hints = Paragraph::new(t!(
+      "tui.classic.status_hints",
+      up = keys.primary(Action::Up),
+      down = keys.primary(Action::Down),
+      open = keys.primary(Action::Open)
+    ))
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Right);
     frame.render_widget(hints, chunks[1]);
   }
 }
 
+/// 只显示收藏但还没有收藏时的提示
+fn no_favorites(app: &App) -> String {
+  t!(
+    "tui.no_favorites",
+    key = app.keymap.primary(Action::ToggleFavorite)
+  )
+}
+
 /// 渲染帮助弹窗
-fn render_help_popup(frame: &mut Frame) {
-  let area = centered_rect(50, 60, frame.area());
+fn render_help_popup(frame: &mut Frame, app: &App) {
+  let area = centered_rect(60, 70, frame.area());
 
   frame.render_widget(Clear, area);
 
-  let help_text = vec![
+  // 按键列表来自 [tui.keys]
+  let labels: Vec<(String, &str)> = Action::ALL
+    .iter()
+    .map(|&action| (app.keymap.label(action), action.description()))
+    .collect();
+  let key_width = labels
+    .iter()
+    .map(|(keys, _)| keys.width())
+    .max()
+    .unwrap_or(0);
+
+  let mut help_text = vec![
     Line::from(Span::styled(
-      "Keyboard Shortcuts",
+      t!("tui.help.title"),
       Style::default()
         .fg(Color::Cyan)
         .add_modifier(Modifier::BOLD),
     )),
     Line::from(""),
-    Line::from(vec![
-      Span::styled("  /        ", Style::default().fg(Color::Yellow)),
-      Span::raw("Focus search"),
-    ]),
-    Line::from(vec![
-      Span::styled("  ↑↓ / jk  ", Style::default().fg(Color::Yellow)),
-      Span::raw("Navigate / Scroll"),
-    ]),
-    Line::from(vec![
-      Span::styled("  ←→ / hl  ", Style::default().fg(Color::Yellow)),
-      Span::raw("Switch results (Modern) / Focus (Classic)"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Enter    ", Style::default().fg(Color::Yellow)),
-      Span::raw("View details"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Tab      ", Style::default().fg(Color::Yellow)),
-      Span::raw("Switch focus"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Esc      ", Style::default().fg(Color::Yellow)),
-      Span::raw("Clear / Back / Quit"),
-    ]),
-    Line::from(vec![
-      Span::styled("  PgUp/Dn  ", Style::default().fg(Color::Yellow)),
-      Span::raw("Page up/down"),
-    ]),
-    Line::from(vec![
-      Span::styled("  g / G    ", Style::default().fg(Color::Yellow)),
-      Span::raw("Jump to first/last"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Ctrl+H   ", Style::default().fg(Color::Yellow)),
-      Span::raw("Toggle help (or ? outside search)"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Ctrl+T   ", Style::default().fg(Color::Yellow)),
-      Span::raw("Switch UI style (Modern/Classic)"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Ctrl+L   ", Style::default().fg(Color::Yellow)),
-      Span::raw("Toggle debug logs (requires --debug)"),
-    ]),
-    Line::from(vec![
-      Span::styled("  Ctrl+Q/C ", Style::default().fg(Color::Yellow)),
-      Span::raw("Force quit"),
-    ]),
-    Line::from(""),
-    Line::from(Span::styled(
-      "Press any key to close",
-      Style::default().fg(Color::DarkGray),
-    )),
   ];
+  for (keys, description) in labels {
+    let padding = " ".repeat(key_width - keys.width());
+    help_text.push(Line::from(vec![
+      Span::styled(
+        format!("  {}{}  ", keys, padding),
+        Style::default().fg(Color::Yellow),
+      ),
+      Span::raw(description),
+    ]));
+  }
+  help_text.push(Line::from(""));
+  help_text.push(Line::from(Span::styled(
+    t!("tui.help.footer"),
+    Style::default().fg(Color::DarkGray),
+  )));
 
   let help = Paragraph::new(help_text)
     .block(
       Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
-        .title(" Help "),
+        .title(t!("tui.help.border")),
     )
     .alignment(Alignment::Left);
 
   frame.render_widget(help, area);
 }
 
+/// 渲染查看历史弹窗
+fn render_history_popup(frame: &mut Frame, app: &mut App) {
+  let area = centered_rect(60, 70, frame.area());
+  app.areas.history = area;
+
+  frame.render_widget(Clear, area);
+
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .border_style(Style::default().fg(Color::Cyan))
+    .title(t!("tui.history.border"));
+
+  if app.history.is_empty() {
+    let empty = Paragraph::new(t!("tui.history.empty"))
+      .style(Style::default().fg(Color::DarkGray))
+      .block(block);
+    frame.render_widget(empty, area);
+    return;
+  }
+
+  let items: Vec<ListItem> = app
+    .history
+    .iter()
+    .map(|entry| {
+      let time = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+        .map(|t| {
+          t.with_timezone(&chrono::Local)
+            .format("%m-%d %H:%M")
+            .to_string()
+        })
+        .unwrap_or_default();
+      ListItem::new(Line::from(vec![
+        Span::styled(format!("{}  ", time), Style::default().fg(Color::DarkGray)),
+        Span::raw(entry.name.as_str()),
+        Span::styled(
+          format!("  [{}] {}", entry.lang, entry.source),
+          Style::default().fg(Color::DarkGray),
+        ),
+      ]))
+    })
+    .collect();
+
+  let list = List::new(items).block(block).highlight_style(
+    Style::default()
+      .bg(Color::Blue)
+      .fg(Color::White)
+      .add_modifier(Modifier::BOLD),
+  );
+
+  let mut list_state = ListState::default();
+  list_state.select(Some(app.history_selected));
+  frame.render_stateful_widget(list, area, &mut list_state);
+  app.history_offset = list_state.offset();
+}
+
+/// 渲染填写示例弹窗：选择示例，或填写选中示例的占位符
+fn render_fill_popup(frame: &mut Frame, app: &mut App) {
+  let area = centered_rect(70, 60, frame.area());
+  app.areas.fill = area;
+  let Some(fill) = &app.fill else {
+    return;
+  };
+  let keys = &app.keymap;
+
+  frame.render_widget(Clear, area);
+
+  let title = match fill.names.get(fill.values.len().saturating_sub(1)) {
+    Some(name) if fill.editing() => t!("tui.fill.input", name = name),
+    _ => t!("tui.fill.pick").to_string(),
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .border_style(Style::default().fg(Color::Cyan))
+    .title(title);
+  let inner = block.inner(area);
+  frame.render_widget(block, area);
+
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Min(1), Constraint::Length(1)])
+    .split(inner);
+  let open = keys.primary(Action::Open);
+  let back = keys.primary(Action::Back);
+  let hint = if fill.editing() {
+    t!("tui.fill.input_hint", open = open, back = back)
+  } else {
+    t!("tui.fill.pick_hint", open = open, back = back)
+  };
+  let hint = Paragraph::new(hint).style(Style::default().fg(Color::DarkGray));
+  frame.render_widget(hint, chunks[1]);
+
+  // 选择示例：说明和代码各占一行
+  if !fill.editing() {
+    let items: Vec<ListItem> = fill
+      .examples
+      .iter()
+      .map(|example| {
+        ListItem::new(vec![
+          Line::from(example.description.as_str()),
+          Line::from(Span::styled(
+            format!("  {}", example.code),
+            Style::default().fg(Color::Yellow),
+          )),
+        ])
+      })
+      .collect();
+    let list = List::new(items).highlight_style(
+      Style::default()
+        .bg(Color::Blue)
+        .fg(Color::White)
+        .add_modifier(Modifier::BOLD),
+    );
+    let mut list_state = ListState::default();
+    list_state.select(Some(fill.selected));
+    frame.render_stateful_widget(list, chunks[0], &mut list_state);
+    return;
+  }
+
+  // 填写占位符：逐个列出占位符，当前的带光标，下方是展开后的命令
+  let current = fill.values.len() - 1;
+  let mut lines = Vec::new();
+  let mut cursor = None;
+  for (i, name) in fill.names.iter().enumerate() {
+    let label = format!("  {}: ", name);
+    let value = fill.values.get(i).map(String::as_str).unwrap_or("");
+    let style = if i == current {
+      cursor = Some(Position::new(
+        chunks[0].x + (label.width() + value.width()) as u16,
+        chunks[0].y + i as u16,
+      ));
+      Style::default().fg(Color::Cyan)
+    } else {
+      Style::default().fg(Color::DarkGray)
+    };
+    lines.push(Line::from(vec![
+      Span::styled(label, style),
+      Span::raw(value),
+    ]));
+  }
+  lines.push(Line::from(""));
+  lines.push(Line::from(Span::styled(
+    fill.preview(),
+    Style::default()
+      .fg(Color::Yellow)
+      .add_modifier(Modifier::BOLD),
+  )));
+  frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), chunks[0]);
+  if let Some(position) = cursor.filter(|p| chunks[0].contains(*p)) {
+    frame.set_cursor_position(position);
+  }
+}
+
+/// 渲染运行示例弹窗：确认提示，或命令的输出
+fn render_run_popup(frame: &mut Frame, app: &mut App) {
+  let area = centered_rect(80, 70, frame.area());
+  app.areas.run = area;
+  let back = app.keymap.primary(Action::Back);
+  let Some(run) = app.run.as_mut() else {
+    return;
+  };
+
+  frame.render_widget(Clear, area);
+
+  let title = match &run.state {
+    RunState::Done { status, .. } => format!(" {} · {} ", t!("tui.run.title"), status),
+    _ => format!(" {} ", t!("tui.run.title")),
+  };
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .border_style(Style::default().fg(Color::Cyan))
+    .title(title);
+  let inner = block.inner(area);
+  frame.render_widget(block, area);
+
+  let chunks = Layout::default()
+    .direction(Direction::Vertical)
+    .constraints([Constraint::Min(1), Constraint::Length(1)])
+    .split(inner);
+
+  let command = Line::from(Span::styled(
+    format!("$ {}", run.command),
+    Style::default()
+      .fg(Color::Yellow)
+      .add_modifier(Modifier::BOLD),
+  ));
+  let (lines, hint) = match &run.state {
+    RunState::Confirm => (
+      vec![Line::from(t!("tui.run.confirm")), Line::from(""), command],
+      t!("tui.run.confirm_hint", back = back),
+    ),
+    RunState::Running => (
+      vec![
+        command,
+        Line::from(""),
+        Line::from(Span::styled(
+          t!("tui.run.running"),
+          Style::default().fg(Color::DarkGray),
+        )),
+      ],
+      t!("tui.run.stop_hint", back = back),
+    ),
+    RunState::Done { output, .. } => {
+      let mut lines = vec![command];
+      lines.extend(output.lines().map(Line::from));
+      (lines, t!("tui.run.output_hint", back = back))
+    }
+  };
+
+  let visible = chunks[0].height;
+  run.max_scroll = (lines.len() as u16).saturating_sub(visible);
+  run.scroll = run.scroll.min(run.max_scroll);
+  let body = Paragraph::new(lines)
+    .wrap(Wrap { trim: false })
+    .scroll((run.scroll, 0));
+  frame.render_widget(body, chunks[0]);
+  let hint = Paragraph::new(hint).style(Style::default().fg(Color::DarkGray));
+  frame.render_widget(hint, chunks[1]);
+}
+
+/// 高亮拖动选中的文字，并记下选中的内容供复制
+fn render_selection(frame: &mut Frame, app: &mut App) {
+  let Some(selection) = app.selection.as_mut() else {
+    return;
+  };
+  let ((_, top), (_, bottom)) = selection.range();
+  let area = selection.area;
+  let buffer = frame.buffer_mut();
+  let mut lines = Vec::new();
+  for y in top..=bottom {
+    let mut line = String::new();
+    // 宽字符后面被占用的格子不重复取字
+    let mut covered = 0;
+    for x in area.left()..area.right() {
+      if !selection.contains(x, y) {
+        continue;
+      }
+      let Some(cell) = buffer.cell_mut((x, y)) else {
+        continue;
+      };
+      if covered > 0 {
+        covered -= 1;
+      } else {
+        line.push_str(cell.symbol());
+        covered = cell.symbol().width().saturating_sub(1);
+      }
+      cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    lines.push(line.trim_end().to_string());
+  }
+  selection.text = lines.join("\n");
+}
+
 /// 居中矩形
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
   let popup_layout = Layout::default()
@@ -569,10 +944,12 @@ fn render_modern(frame: &mut Frame, app: &mut App) {
   }
 
   // 搜索框
+  app.areas.search = chunks[idx];
   render_modern_search(frame, app, chunks[idx]);
   idx += 1;
 
   // 结果详情（单条显示）
+  app.areas.detail = chunks[idx];
   render_modern_result(frame, app, chunks[idx]);
   idx += 1;
 
@@ -580,10 +957,20 @@ fn render_modern(frame: &mut Frame, app: &mut App) {
   if app.show_logs {
     render_modern_logs(frame, app, chunks[idx]);
   }
+  render_selection(frame, app);
 
-  // 帮助弹窗
+  // 历史、填写示例、运行示例和帮助弹窗
+  if app.show_history {
+    render_history_popup(frame, app);
+  }
+  if app.fill.is_some() {
+    render_fill_popup(frame, app);
+  }
+  if app.run.is_some() {
+    render_run_popup(frame, app);
+  }
   if app.show_help {
-    render_help_popup(frame);
+    render_help_popup(frame, app);
   }
 }
 
@@ -614,16 +1001,23 @@ fn render_modern_search(frame: &mut Frame, app: &App, area: Rect) {
     Color::DarkGray
   };
 
-  let block = Block::default()
+  let mut block = Block::default()
     .borders(Borders::ALL)
     .border_type(BorderType::Rounded)
     .border_style(Style::default().fg(border_color))
     .title(Span::styled(
-      " Search ",
+      t!("tui.modern.search_title"),
       Style::default()
         .fg(Color::White)
         .add_modifier(Modifier::BOLD),
     ));
+  // 等待搜索结果时在标题后显示加载动画
+  if let Some(spinner) = app.spinner() {
+    block = block.title(Span::styled(
+      format!("{} ", spinner),
+      Style::default().fg(Color::Rgb(100, 200, 255)),
+    ));
+  }
 
   let inner = block.inner(area);
 
@@ -631,7 +1025,7 @@ fn render_modern_search(frame: &mut Frame, app: &App, area: Rect) {
   let prefix = Span::styled("> ", Style::default().fg(Color::Rgb(100, 200, 255)));
   let content = if app.query.is_empty() && app.focus != Focus::Search {
     Span::styled(
-      "Type to search... (↑↓ to navigate results)",
+      t!("tui.modern.search_placeholder"),
       Style::default().fg(Color::DarkGray),
     )
   } else {
@@ -639,8 +1033,12 @@ fn render_modern_search(frame: &mut Frame, app: &App, area: Rect) {
   };
 
   // 右侧提示
-  let hint = " [Ctrl+H] Help  [Esc] Quit ";
-  let hint_width = hint.len() as u16;
+  let hint = t!(
+    "tui.modern.search_hints",
+    help = app.keymap.primary(Action::Help),
+    back = app.keymap.primary(Action::Back)
+  );
+  let hint_width = hint.width() as u16;
 
   let search = Paragraph::new(Line::from(vec![prefix, content])).block(block);
   frame.render_widget(search, area);
@@ -673,11 +1071,21 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
   };
 
   // 标题显示当前位置
-  let title = if app.results.is_empty() {
-    " Result ".to_string()
+  let mut title = if app.results.is_empty() {
+    t!("tui.modern.result_title").to_string()
   } else {
-    format!(" Result [{}/{}] ", app.selected + 1, app.results.len())
+    t!(
+      "tui.modern.result_position",
+      index = app.selected + 1,
+      count = app.results.len()
+    )
   };
+  if app.favorites_only {
+    title = format!(" ★{}", title);
+  }
+  if app.in_frequent() {
+    title = format!("{}· {} ", title, t!("tui.frequently_used"));
+  }
 
   let block = Block::default()
     .borders(Borders::ALL)
@@ -692,16 +1100,24 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
 
   // 无结果时的提示
   if app.results.is_empty() {
-    let empty_text = if app.query.is_empty() {
+    let empty_text = if app.query.is_empty() && app.favorites_only {
       vec![
         Line::from(""),
         Line::from(Span::styled(
-          "  Start typing to search commands...",
+          format!("  {}", no_favorites(app)),
           Style::default().fg(Color::DarkGray),
         )),
+      ]
+    } else if app.query.is_empty() {
+      vec![
         Line::from(""),
         Line::from(Span::styled(
-          "  Tips: Use ↑↓ or j/k to navigate results",
+          format!("  {}", t!("tui.modern.start_typing")),
+          Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+          format!("  {}", t!("tui.modern.tips")),
           Style::default().fg(Color::DarkGray),
         )),
       ]
@@ -709,23 +1125,34 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
       vec![
         Line::from(""),
         Line::from(Span::styled(
-          "  Searching...",
+          format!("  {}", t!("tui.searching")),
           Style::default().fg(Color::Yellow),
         )),
       ]
     } else {
-      vec![
-        Line::from(""),
-        Line::from(Span::styled(
-          "  No results found",
-          Style::default().fg(Color::DarkGray),
-        )),
+      let mut lines = vec![
         Line::from(""),
         Line::from(Span::styled(
-          "  Try a different search term or run 'rtfm update'",
+          format!("  {}", t!("tui.no_results")),
           Style::default().fg(Color::DarkGray),
         )),
-      ]
+      ];
+      if !app.suggestions.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+          format!(
+            "  {}",
+            t!("tui.did_you_mean", names = app.suggestions.join(", "))
+          ),
+          Style::default().fg(Color::Yellow),
+        )));
+      }
+      lines.push(Line::from(""));
+      lines.push(Line::from(Span::styled(
+        format!("  {}", t!("tui.modern.try_update")),
+        Style::default().fg(Color::DarkGray),
+      )));
+      lines
     };
     let empty = Paragraph::new(empty_text).block(block);
     frame.render_widget(empty, area);
@@ -734,14 +1161,45 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
 
   // 获取当前选中的命令
   let result = &app.results[app.selected];
+  let favorite = app.favorites.contains(&result.name);
   let content = app
     .get_command_detail(&result.name, &result.lang)
-    .unwrap_or_else(|| format!("Command not found: {}", result.name));
+    .unwrap_or_else(|| t!("tui.command_not_found", name = result.name));
 
   // 渲染命令详情（Markdown 风格）
   let mut lines: Vec<Line> = Vec::new();
 
-  for line in content.lines() {
+  // 匹配的示例显示在详情之前
+  if let Some(example) = &result.example {
+    lines.push(Line::from(vec![
+      Span::styled("  ", Style::default()),
+      Span::styled(
+        t!("tui.matching_example", index = example.index),
+        Style::default().fg(Color::DarkGray),
+      ),
+      Span::styled(
+        result.description.clone(),
+        Style::default()
+          .fg(Color::Green)
+          .add_modifier(Modifier::BOLD),
+      ),
+    ]));
+    lines.push(Line::from(vec![
+      Span::styled("    ", Style::default()),
+      Span::styled(example.code.clone(), Style::default().fg(Color::Yellow)),
+    ]));
+    lines.push(Line::from(""));
+  }
+
+  // 选中的示例加底色，说明前的箭头换成 ▶
+  let active = app
+    .active_example()
+    .and_then(|index| example_ranges(&content).into_iter().nth(index))
+    .unwrap_or_default();
+  let mut active_start = 0;
+
+  for (i, line) in content.lines().enumerate() {
+    let start = lines.len();
     if let Some(h) = line.strip_prefix("# ") {
       // 一级标题：命令名
       lines.push(Line::from(vec![
@@ -756,12 +1214,22 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
           format!("  [{}]", result.lang),
           Style::default().fg(Color::DarkGray),
         ),
+        Span::styled(
+          if favorite { "  ★" } else { "" },
+          Style::default().fg(Color::Yellow),
+        ),
       ]));
     } else if let Some(h) = line.strip_prefix("## ") {
       // 二级标题：示例描述
       lines.push(Line::from(""));
+      let arrow = if i == active.start && active.contains(&i) {
+        active_start = lines.len();
+        "  ▶ "
+      } else {
+        "  → "
+      };
       lines.push(Line::from(vec![
-        Span::styled("  → ", Style::default().fg(Color::Green)),
+        Span::styled(arrow, Style::default().fg(Color::Green)),
         Span::styled(
           h,
           Style::default()
@@ -784,12 +1252,26 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
         Span::styled(line, Style::default().fg(Color::White)),
       ]));
     }
+    if active.contains(&i) {
+      let highlight = Style::default().bg(Color::Rgb(45, 50, 65));
+      for line in lines.iter_mut().skip(start.max(active_start)) {
+        *line = std::mem::take(line).patch_style(highlight);
+      }
+    }
   }
 
   // 底部导航提示
   lines.push(Line::from(""));
   lines.push(Line::from(Span::styled(
-    "  ↑↓ Scroll  ←→ Switch result  / Search  ? Help",
+    t!(
+      "tui.modern.result_hints",
+      up = app.keymap.primary(Action::Up),
+      down = app.keymap.primary(Action::Down),
+      prev = app.keymap.primary(Action::Prev),
+      next = app.keymap.primary(Action::Next),
+      search = app.keymap.primary(Action::Search),
+      help = app.keymap.primary(Action::Help)
+    ),
     Style::default().fg(Color::DarkGray),
   )));
 
@@ -797,6 +1279,7 @@ fn render_modern_result(frame: &mut Frame, app: &mut App, area: Rect) {
   let content_lines = lines.len() as u16;
   let visible_lines = area.height.saturating_sub(2);
   app.set_detail_max_scroll(content_lines, visible_lines);
+  scroll_to_example(app, active_start);
 
   let paragraph = Paragraph::new(lines)
     .block(block)
@@ -813,7 +1296,10 @@ fn render_modern_logs(frame: &mut Frame, app: &App, area: Rect) {
     .border_type(BorderType::Rounded)
     .border_style(Style::default().fg(Color::Magenta))
     .title(Span::styled(
-      " Logs [Ctrl+L close] ",
+      t!(
+        "tui.modern.logs_title",
+        key = app.keymap.primary(Action::ToggleLogs)
+      ),
       Style::default().fg(Color::Magenta),
     ));
 
@@ -839,3 +1325,58 @@ fn render_modern_logs(frame: &mut Frame, app: &App, area: Rect) {
   let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
   frame.render_widget(paragraph, area);
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::AppConfig;
+  use crate::tui::app::tests::app;
+  use ratatui::backend::TestBackend;
+  use ratatui::Terminal;
+
+  #[test]
+  fn test_example_ranges() {
+    let content = "# tar\n\nArchiver\n\n## Create\n```\ntar -cf a b\n```\n\n## Options\n\n  -v\nVerbose\n\n## Extract\n```\ntar -xf a\n```\n";
+    assert_eq!(example_ranges(content), [4..8, 14..18]);
+    // 没有结束的代码块到内容末尾
+    assert_eq!(
+      example_ranges("## List\n```\ntar -tf a").first(),
+      Some(&(0..3))
+    );
+    assert!(example_ranges("## Options\n\n  -v").is_empty());
+  }
+
+  /// 渲染后以 ▶ 开头的行
+  fn marked_lines(app: &mut App) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    terminal.draw(|frame| render(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+      .map(|y| {
+        (0..buffer.area.width)
+          .map(|x| buffer[(x, y)].symbol())
+          .collect::<String>()
+      })
+      .filter_map(|line| {
+        let start = line.find('▶')?;
+        Some(line[start..].trim_end().to_string())
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_highlight_selected_example() {
+    for style in [UiStyle::Modern, UiStyle::Classic] {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let mut app = app(temp_dir.path(), AppConfig::default(), style);
+      app.focus = Focus::List;
+      assert!(marked_lines(&mut app).is_empty());
+
+      app.select_example(true);
+      app.select_example(true);
+      let marked = marked_lines(&mut app);
+      assert_eq!(marked.len(), 1, "{:?}", style);
+      assert!(marked[0].contains("Extract an archive"), "{:?}", marked);
+    }
+  }
+}
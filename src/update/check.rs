@@ -0,0 +1,225 @@
+//! serve/TUI 运行期间的定时更新检查
+//!
+//! 按 update.check_interval_hours 检查 tldr 数据集与 rtfm 的最新发布，发现新版本时
+//! 发送桌面通知（update.notify），并把提示交给调用方（TUI 状态栏、服务日志）。
+//! 上次检查时间与已提示过的版本保存在数据目录下，重启后不会重复检查或重复通知。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::config::{AppConfig, UpdateConfig};
+use crate::i18n::t;
+
+/// 检查状态文件名（位于数据目录）
+const STATE_FILENAME: &str = "update-check.json";
+
+/// 启动后首次检查前的等待，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(10);
+
+/// 距下次检查的时间较长时，隔一段时间重新读取配置（服务模式下可能热重载）
+const MAX_SLEEP: Duration = Duration::from_secs(3600);
+
+/// 一条更新提示
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+  pub title: String,
+  /// 包含下一步操作的提示
+  pub body: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+  checked_at: Option<DateTime<Utc>>,
+  /// 已提示过的数据集版本
+  dataset: Option<String>,
+  /// 已提示过的 rtfm 版本
+  release: Option<String>,
+}
+
+impl State {
+  fn load(path: &Path) -> Self {
+    std::fs::read_to_string(path)
+      .ok()
+      .and_then(|s| serde_json::from_str(&s).ok())
+      .unwrap_or_default()
+  }
+
+  fn save(&self, path: &Path) {
+    if let Err(e) = serde_json::to_string_pretty(self)
+      .map_err(std::io::Error::other)
+      .and_then(|s| std::fs::write(path, s))
+    {
+      tracing::warn!("Failed to save {}: {}", path.display(), e);
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+  tag_name: String,
+  #[serde(default)]
+  html_url: Option<String>,
+}
+
+async fn latest_release(url: &str, config: &UpdateConfig) -> anyhow::Result<GithubRelease> {
+  let response = reqwest::Client::builder()
+    .user_agent(&config.user_agent)
+    .timeout(Duration::from_secs(30))
+    .build()?
+    .get(url)
+    .header("Accept", "application/vnd.github.v3+json")
+    .send()
+    .await?;
+  if !response.status().is_success() {
+    anyhow::bail!("GitHub API error: {}", response.status());
+  }
+  Ok(response.json().await?)
+}
+
+/// 按数字逐段比较版本号（忽略前缀 v），无法解析时只要不同就视为更新
+fn is_newer(latest: &str, current: &str) -> bool {
+  let parse = |v: &str| -> Option<Vec<u64>> {
+    v.trim_start_matches('v')
+      .split('.')
+      .map(|part| part.parse().ok())
+      .collect()
+  };
+  match (parse(latest), parse(current)) {
+    (Some(latest), Some(current)) => latest > current,
+    _ => latest.trim_start_matches('v') != current.trim_start_matches('v'),
+  }
+}
+
+/// 检查一次，返回尚未提示过的新版本
+/// dataset_version: 当前已导入的 tldr 版本（未导入时不提示数据集更新）
+async fn check(
+  config: &UpdateConfig,
+  dataset_version: Option<String>,
+  state: &mut State,
+) -> Vec<Notice> {
+  let mut notices = Vec::new();
+
+  if let Some(current) = dataset_version {
+    match latest_release(&config.github_api_url, config).await {
+      Ok(release) => {
+        let latest = release.tag_name.trim_start_matches('v').to_string();
+        if is_newer(&latest, &current) && state.dataset.as_deref() != Some(latest.as_str()) {
+          notices.push(Notice {
+            title: t!("notice.dataset.title", version = latest),
+            body: t!("notice.dataset.body", current = current),
+          });
+          state.dataset = Some(latest);
+        }
+      }
+      Err(e) => tracing::debug!("Dataset update check failed: {}", e),
+    }
+  }
+
+  match latest_release(&config.release_api_url, config).await {
+    Ok(release) => {
+      let latest = release.tag_name.trim_start_matches('v').to_string();
+      let current = env!("CARGO_PKG_VERSION");
+      if is_newer(&latest, current) && state.release.as_deref() != Some(latest.as_str()) {
+        notices.push(Notice {
+          title: t!("notice.release.title", version = latest),
+          body: t!(
+            "notice.release.body",
+            current = current,
+            url = release
+              .html_url
+              .as_deref()
+              .unwrap_or("https://github.com/F2077/rtfm/releases")
+          ),
+        });
+        state.release = Some(latest);
+      }
+    }
+    Err(e) => tracing::debug!("Release check failed: {}", e),
+  }
+
+  state.checked_at = Some(Utc::now());
+  notices
+}
+
+/// 发送桌面通知；没有通知服务（如无图形界面的服务器）时只记录日志
+fn notify_desktop(notice: &Notice) {
+  if let Err(e) = notify_rust::Notification::new()
+    .appname("rtfm")
+    .summary(&notice.title)
+    .body(&notice.body)
+    .show()
+  {
+    tracing::debug!("Desktop notification failed: {}", e);
+  }
+}
+
+/// 启动后台检查任务，发现的新版本通过返回的通道传出
+/// config: 每次检查前读取，服务模式下可返回热重载后的配置
+/// dataset_version: 当前已导入的 tldr 版本
+pub fn spawn<C, D>(
+  data_dir: PathBuf,
+  config: C,
+  dataset_version: D,
+) -> mpsc::UnboundedReceiver<Notice>
+where
+  C: Fn() -> Arc<AppConfig> + Send + 'static,
+  D: Fn() -> Option<String> + Send + 'static,
+{
+  let (tx, rx) = mpsc::unbounded_channel();
+  tokio::spawn(async move {
+    let path = data_dir.join(STATE_FILENAME);
+    tokio::time::sleep(STARTUP_DELAY).await;
+
+    loop {
+      let update = config().update.clone();
+      if update.check_interval_hours == 0 {
+        tokio::time::sleep(MAX_SLEEP).await;
+        continue;
+      }
+      let interval = Duration::from_secs(update.check_interval_hours * 3600);
+
+      let mut state = State::load(&path);
+      let elapsed = state
+        .checked_at
+        .and_then(|t| (Utc::now() - t).to_std().ok())
+        .unwrap_or(interval);
+      if elapsed < interval {
+        tokio::time::sleep((interval - elapsed).min(MAX_SLEEP)).await;
+        continue;
+      }
+
+      let notices = check(&update, dataset_version(), &mut state).await;
+      state.save(&path);
+      for notice in notices {
+        tracing::info!("{}: {}", notice.title, notice.body);
+        if update.notify {
+          let desktop = notice.clone();
+          tokio::task::spawn_blocking(move || notify_desktop(&desktop));
+        }
+        // 接收方已退出时忽略
+        let _ = tx.send(notice);
+      }
+    }
+  });
+  rx
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_newer() {
+    assert!(is_newer("2.3", "2.2"));
+    assert!(is_newer("v0.10.0", "0.9.1"));
+    assert!(!is_newer("2.3", "2.3"));
+    assert!(!is_newer("0.1.0", "0.2.0"));
+    assert!(is_newer("2.3-beta", "2.2"));
+    assert!(!is_newer("v2.3-beta", "2.3-beta"));
+  }
+}
@@ -1,4 +1,8 @@
-use std::io::{Cursor, Read};
+pub mod check;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::Path;
 
 use flate2::read::GzDecoder;
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
@@ -7,8 +11,8 @@ use tar::Archive;
 use thiserror::Error;
 use zip::ZipArchive;
 
-use crate::config::UpdateConfig;
-use crate::storage::{Command, Example};
+use crate::config::{CommandPrefs, UpdateConfig};
+use crate::storage::{Command, Example, OptionEntry, COMMAND_SCHEMA};
 
 /// GitHub Release 信息
 #[derive(Debug)]
@@ -67,25 +71,119 @@ pub enum UpdateError {
 
 /// 解析 tldr-pages 压缩包
 /// languages: 允许的语言列表，空表示全部
-pub fn parse_tldr_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>, UpdateError> {
-  // 尝试作为 ZIP 解析
-  if let Ok(commands) = parse_zip_archive(data, languages) {
-    return Ok(commands);
+/// prefs: 按命令名的偏好，同名页面有多个平台版本时保留固定的平台
+pub fn parse_tldr_archive(
+  data: &[u8],
+  languages: &[String],
+  prefs: &HashMap<String, CommandPrefs>,
+) -> Result<Vec<Command>, UpdateError> {
+  // 尝试作为 ZIP 解析，再尝试作为 tar.gz 解析
+  let mut commands = parse_zip_archive(Cursor::new(data), languages)
+    .or_else(|_| parse_targz_archive(Cursor::new(data), languages))
+    .map_err(|_| UpdateError::Parse("Unrecognized archive format".to_string()))?;
+  pin_platforms(&mut commands, prefs);
+  Ok(commands)
+}
+
+/// 解析磁盘上的 tldr-pages 压缩包，不把整个文件读入内存
+pub fn parse_tldr_archive_file(
+  path: &Path,
+  languages: &[String],
+  prefs: &HashMap<String, CommandPrefs>,
+) -> Result<Vec<Command>, UpdateError> {
+  let open = || std::fs::File::open(path).map(BufReader::new);
+  let mut commands = parse_zip_archive(open()?, languages)
+    .or_else(|_| parse_targz_archive(open()?, languages))
+    .map_err(|_| UpdateError::Parse("Unrecognized archive format".to_string()))?;
+  pin_platforms(&mut commands, prefs);
+  Ok(commands)
+}
+
+/// 解压 .zip、.tar.gz 或 .tar 压缩包到 dest（用于不是 tldr-pages 结构的压缩包，解压后按目录导入）
+pub fn unpack_archive(path: &Path, dest: &Path) -> Result<(), UpdateError> {
+  let open = || std::fs::File::open(path).map(BufReader::new);
+  if let Ok(mut archive) = ZipArchive::new(open()?) {
+    return Ok(archive.extract(dest)?);
   }
+  Archive::new(GzDecoder::new(open()?))
+    .unpack(dest)
+    .or_else(|_| Archive::new(open()?).unpack(dest))
+    .map_err(|_| UpdateError::Parse("Unrecognized archive format".to_string()))
+}
 
-  // 尝试作为 tar.gz 解析
-  if let Ok(commands) = parse_targz_archive(data, languages) {
-    return Ok(commands);
+/// GitHub 仓库的网页地址（https://github.com/owner/repo，可带 .git 或 /tree/<分支>）换成源码压缩包的下载地址，
+/// 其他地址不变
+pub fn github_archive_url(url: &str) -> String {
+  let Some(rest) = url.strip_prefix("https://github.com/") else {
+    return url.to_string();
+  };
+  let parts: Vec<&str> = rest.trim_end_matches('/').split('/').collect();
+  match parts.as_slice() {
+    [owner, repo] => format!(
+      "https://github.com/{}/{}/archive/HEAD.tar.gz",
+      owner,
+      repo.trim_end_matches(".git")
+    ),
+    [owner, repo, "tree", branch @ ..] if !branch.is_empty() => format!(
+      "https://github.com/{}/{}/archive/{}.tar.gz",
+      owner,
+      repo,
+      branch.join("/")
+    ),
+    _ => url.to_string(),
   }
+}
 
-  Err(UpdateError::Parse(
-    "Unrecognized archive format".to_string(),
-  ))
+/// 把下载内容分块写入文件，返回字节数
+/// on_progress: 每写入一块后以（已下载字节数, 服务器报告的总大小）调用
+pub async fn download_to_file(
+  url: &str,
+  config: &UpdateConfig,
+  path: &Path,
+  mut on_progress: impl FnMut(u64, Option<u64>),
+) -> anyhow::Result<u64> {
+  use tokio::io::AsyncWriteExt;
+
+  let mut response = reqwest::Client::builder()
+    .user_agent(&config.user_agent)
+    .build()?
+    .get(url)
+    .send()
+    .await?;
+  if !response.status().is_success() {
+    anyhow::bail!("Download failed: {}", response.status());
+  }
+
+  let total = response.content_length();
+  let mut file = tokio::fs::File::create(path).await?;
+  let mut size = 0;
+  while let Some(chunk) = response.chunk().await? {
+    file.write_all(&chunk).await?;
+    size += chunk.len() as u64;
+    on_progress(size, total);
+  }
+  file.flush().await?;
+  Ok(size)
+}
+
+/// 去掉固定了平台的命令的其他平台版本（该平台的页面存在时）
+fn pin_platforms(commands: &mut Vec<Command>, prefs: &HashMap<String, CommandPrefs>) {
+  let pinned = |cmd: &Command| {
+    prefs.get(&cmd.name).and_then(|p| p.platform.as_deref()) == Some(cmd.platform.as_str())
+  };
+  let found: HashSet<(String, String)> = commands
+    .iter()
+    .filter(|cmd| pinned(cmd))
+    .map(|cmd| (cmd.lang.clone(), cmd.name.clone()))
+    .collect();
+  commands.retain(|cmd| pinned(cmd) || !found.contains(&(cmd.lang.clone(), cmd.name.clone())));
 }
 
-fn parse_zip_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>, UpdateError> {
-  let cursor = Cursor::new(data);
-  let mut archive = ZipArchive::new(cursor)?;
+fn parse_zip_archive<R: Read + Seek>(
+  reader: R,
+  languages: &[String],
+) -> Result<Vec<Command>, UpdateError> {
+  let mut archive = ZipArchive::new(reader)?;
 
   let mut commands = Vec::new();
 
@@ -105,7 +203,7 @@ fn parse_zip_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>,
     };
 
     // Filter by language if specified
-    if !languages.is_empty() && !languages.contains(&lang) {
+    if !wanted_language(&lang, languages) {
       continue;
     }
 
@@ -122,9 +220,11 @@ fn parse_zip_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>,
   Ok(commands)
 }
 
-fn parse_targz_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>, UpdateError> {
-  let cursor = Cursor::new(data);
-  let decoder = GzDecoder::new(cursor);
+fn parse_targz_archive<R: Read>(
+  reader: R,
+  languages: &[String],
+) -> Result<Vec<Command>, UpdateError> {
+  let decoder = GzDecoder::new(reader);
   let mut archive = Archive::new(decoder);
 
   let mut commands = Vec::new();
@@ -145,7 +245,7 @@ fn parse_targz_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>
     };
 
     // Filter by language if specified
-    if !languages.is_empty() && !languages.contains(&lang) {
+    if !wanted_language(&lang, languages) {
       continue;
     }
 
@@ -162,6 +262,155 @@ fn parse_targz_archive(data: &[u8], languages: &[String]) -> Result<Vec<Command>
   Ok(commands)
 }
 
+/// languages 为空时接受所有语言；本机学习的命令（rtfm export --format tldr 导出的 pages.local）总是接受
+fn wanted_language(lang: &str, languages: &[String]) -> bool {
+  languages.is_empty() || lang == "local" || languages.iter().any(|l| l == lang)
+}
+
+/// rtfm update --langs 选择的语言：没有指定时为 None（使用 update.languages），all 表示全部（空列表）
+pub fn parse_langs(langs: &[String]) -> Option<Vec<String>> {
+  let langs: Vec<String> = langs
+    .iter()
+    .map(|lang| lang.trim().to_string())
+    .filter(|lang| !lang.is_empty())
+    .collect();
+  if langs.is_empty() {
+    return None;
+  }
+  if langs.iter().any(|lang| lang == "all") {
+    return Some(Vec::new());
+  }
+  Some(langs)
+}
+
+/// 元数据中记录的语言：配置的语言列表，未限制语言时为导入的页面实际包含的语言
+pub fn imported_languages(languages: &[String], commands: &[Command]) -> Vec<String> {
+  if !languages.is_empty() {
+    return languages.to_vec();
+  }
+  let found: BTreeSet<&str> = commands.iter().map(|cmd| cmd.lang.as_str()).collect();
+  found.into_iter().map(str::to_string).collect()
+}
+
+/// 选择的语言与上次更新导入的语言不同
+/// languages 为空表示全部：上次更新限制了语言时需要重新导入，旧版本没有记录 selected 时也重新导入一次
+pub fn languages_changed(
+  languages: &[String],
+  imported: &[String],
+  selected: Option<&[String]>,
+) -> bool {
+  if languages.is_empty() {
+    return selected.is_none_or(|selected| !selected.is_empty());
+  }
+  let wanted: BTreeSet<&String> = languages.iter().collect();
+  wanted != imported.iter().collect()
+}
+
+/// cheat 社区速查表的分类，与 tldr 页面区分
+pub const CHEAT_CATEGORY: &str = "cheat";
+
+/// 解析 cheat/cheatsheets 仓库的压缩包（.zip 或 .tar.gz），每个速查表一条英文命令
+pub fn parse_cheat_archive(data: &[u8]) -> Result<Vec<Command>, UpdateError> {
+  let mut sheets = Vec::new();
+  match ZipArchive::new(Cursor::new(data)) {
+    Ok(mut archive) => {
+      for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if let Some(name) = cheat_sheet_name(file.name()) {
+          let mut content = String::new();
+          // 二进制或非 UTF-8 文件不是速查表
+          if file.read_to_string(&mut content).is_ok() {
+            sheets.push((name, content));
+          }
+        }
+      }
+    }
+    Err(_) => {
+      let mut archive = Archive::new(GzDecoder::new(Cursor::new(data)));
+      let entries = archive
+        .entries()
+        .map_err(|_| UpdateError::Parse("Unrecognized archive format".to_string()))?;
+      for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        if let Some(name) = cheat_sheet_name(&path) {
+          let mut content = String::new();
+          if entry.read_to_string(&mut content).is_ok() {
+            sheets.push((name, content));
+          }
+        }
+      }
+    }
+  }
+
+  Ok(
+    sheets
+      .into_iter()
+      .filter_map(|(name, content)| parse_cheat_page(&content, name))
+      .collect(),
+  )
+}
+
+/// 仓库根目录下的速查表文件名：没有扩展名，不是 README、LICENSE 之类的说明文件
+/// 例如: cheatsheets-master/tar -> tar
+fn cheat_sheet_name(path: &str) -> Option<String> {
+  let path = path.replace('\\', "/");
+  let parts: Vec<&str> = path.split('/').collect();
+  let [_, name] = parts.as_slice() else {
+    return None;
+  };
+  let sheet = !name.is_empty()
+    && !name.starts_with(['.', '_'])
+    && !name.contains('.')
+    && name.chars().any(|c| !c.is_ascii_uppercase());
+  sheet.then(|| name.to_string())
+}
+
+/// 解析一个社区速查表；没有示例时返回 None
+pub fn parse_cheat_page(content: &str, name: String) -> Option<Command> {
+  let (tags, examples) = crate::sheets::parse_cheat_sheet(content);
+  if examples.is_empty() {
+    return None;
+  }
+  Some(Command {
+    description: if tags.is_empty() {
+      "Community cheat sheet".to_string()
+    } else {
+      format!("Community cheat sheet (tags: {})", tags.join(", "))
+    },
+    category: CHEAT_CATEGORY.to_string(),
+    platform: "common".to_string(),
+    lang: "en".to_string(),
+    options: extract_options(&examples),
+    examples,
+    content: content.to_string(),
+    sections: vec![],
+    source_info: None,
+    notes: None,
+    schema: COMMAND_SCHEMA,
+    name,
+  })
+}
+
+/// 速查表并入同名的 tldr 页面：换掉上次导入的 cheat 示例，tldr 示例在前；
+/// 没有 tldr 页面时速查表单独作为一条 cheat 分类的命令
+pub fn merge_cheat_sheet(existing: Option<Command>, sheet: Command) -> Command {
+  match existing {
+    Some(page) if page.category != CHEAT_CATEGORY => {
+      strip_cheat_examples(page).merge_learned(sheet)
+    }
+    _ => sheet,
+  }
+}
+
+/// 去掉 tldr 页面中导入的 cheat 示例
+pub fn strip_cheat_examples(mut page: Command) -> Command {
+  page
+    .examples
+    .retain(|e| e.source.as_deref() != Some(CHEAT_CATEGORY));
+  page
+}
+
 /// 从 tldr-pages 路径解析语言、平台和命令名
 /// 例如: pages.zh/common/docker.md -> ("zh", "common", "docker")
 fn parse_tldr_path(path: &str) -> Option<(String, String, String)> {
@@ -200,7 +449,7 @@ fn parse_tldr_path(path: &str) -> Option<(String, String, String)> {
 /// > More information: <url>.
 /// - Example description:
 ///   `code`
-fn parse_tldr_markdown(
+pub fn parse_tldr_markdown(
   content: &str,
   name: String,
   lang: String,
@@ -259,6 +508,7 @@ fn parse_tldr_markdown(
           examples.push(Example {
             description: desc,
             code: current_code.trim().to_string(),
+            source: None,
           });
           current_example_desc.clear();
         }
@@ -308,6 +558,7 @@ fn parse_tldr_markdown(
             examples.push(Example {
               description: desc,
               code: code.to_string(),
+              source: None,
             });
             current_example_desc.clear();
           }
@@ -327,6 +578,7 @@ fn parse_tldr_markdown(
     description = name.clone();
   }
 
+  let options = extract_options(&examples);
   Some(Command {
     name,
     description,
@@ -335,11 +587,474 @@ fn parse_tldr_markdown(
     lang,
     examples,
     content: content.to_string(),
+    sections: vec![],
+    options,
+    source_info: None,
+    notes: None,
+    schema: COMMAND_SCHEMA,
   })
 }
 
+/// 从 tldr 示例中提取选项：只用到一个选项的示例，其说明即该选项的说明
+/// 选项写作 `-a`、`--all`、`--format=...` 或 tldr 的 `{{[-a|--all]}}`，紧随其后的 `{{占位符}}` 为参数
+pub fn extract_options(examples: &[Example]) -> Vec<OptionEntry> {
+  let mut options: Vec<OptionEntry> = Vec::new();
+  for example in examples {
+    let mut found = example_options(&example.code);
+    if found.len() != 1 {
+      continue;
+    }
+    let mut option = found.remove(0);
+    if options.iter().any(|o| o.overlaps(&option)) {
+      continue;
+    }
+    option.description = example.description.clone();
+    options.push(option);
+  }
+  options
+}
+
+/// 示例代码中的选项（说明留空）；管道、重定向之后是其他命令，不再提取
+fn example_options(code: &str) -> Vec<OptionEntry> {
+  let tokens = code_tokens(code);
+  let mut found = Vec::new();
+  for (i, token) in tokens.iter().enumerate() {
+    if matches!(*token, "|" | "||" | "&&" | ";" | ">" | ">>" | "<") {
+      break;
+    }
+    let (flags, value) = match token
+      .strip_prefix("{{[")
+      .and_then(|t| t.strip_suffix("]}}"))
+    {
+      Some(alternatives) => (
+        alternatives.split('|').filter(|f| is_flag(f)).collect(),
+        None,
+      ),
+      None => {
+        let (flag, value) = match token.split_once('=') {
+          Some((flag, value)) => (flag, Some(value)),
+          None => (*token, None),
+        };
+        if !is_flag(flag) {
+          continue;
+        }
+        (vec![flag], value)
+      }
+    };
+    if flags.is_empty() {
+      continue;
+    }
+    let arg = value.or_else(|| {
+      tokens
+        .get(i + 1)
+        .copied()
+        .filter(|next| next.starts_with("{{") && !next.starts_with("{{["))
+    });
+    found.push(OptionEntry {
+      flags: flags.into_iter().map(String::from).collect(),
+      arg: arg.map(|arg| {
+        arg
+          .trim_start_matches("{{")
+          .trim_end_matches("}}")
+          .to_string()
+      }),
+      description: String::new(),
+    });
+  }
+  found
+}
+
+/// 单个选项：-a 或 --all（-xvf 这样的组合不算）
+fn is_flag(token: &str) -> bool {
+  let name = match token.strip_prefix("--") {
+    Some(long) => long,
+    None => match token.strip_prefix('-') {
+      Some(short) if short.chars().count() == 1 => short,
+      _ => return false,
+    },
+  };
+  name
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphanumeric())
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// 按空白切分示例代码，`{{...}}` 占位符中的空白不切分
+fn code_tokens(code: &str) -> Vec<&str> {
+  let mut tokens = Vec::new();
+  let mut start = None;
+  let mut depth = 0;
+  let mut prev = '\0';
+  for (i, c) in code.char_indices() {
+    if c.is_whitespace() && depth == 0 {
+      if let Some(start) = start.take() {
+        tokens.push(&code[start..i]);
+      }
+      prev = c;
+      continue;
+    }
+    start.get_or_insert(i);
+    // 连续的两个括号算一次，"{{{" 中的第三个不再计入
+    prev = match (prev, c) {
+      ('{', '{') => {
+        depth += 1;
+        '\0'
+      }
+      ('}', '}') if depth > 0 => {
+        depth -= 1;
+        '\0'
+      }
+      _ => c,
+    };
+  }
+  if let Some(start) = start {
+    tokens.push(&code[start..]);
+  }
+  tokens
+}
+
 /// 解析本地 Markdown 文件
 pub fn parse_local_markdown(content: &str, filename: &str) -> Option<Command> {
   let name = filename.trim_end_matches(".md").to_string();
   parse_tldr_markdown(content, name, "zh".to_string(), "common".to_string())
 }
+
+/// 解析目录中的 Markdown 文件：位于 pages.<lang>/<platform>/ 下时使用路径中的语言和平台
+pub fn parse_page_file(content: &str, path: &Path) -> Option<Command> {
+  match parse_tldr_path(&path.to_string_lossy()) {
+    Some((lang, platform, name)) => parse_tldr_markdown(content, name, lang, platform),
+    None => {
+      let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+      parse_local_markdown(content, filename)
+    }
+  }
+}
+
+/// 命令在 tldr-pages 中的路径，如 pages.zh/common/docker.md（英文为 pages/common/docker.md）
+/// 名称、语言和平台可能来自同步、插件或导入，都只作为单独的一级路径
+pub fn tldr_page_path(cmd: &Command) -> String {
+  let pages = match cmd.lang.as_str() {
+    "en" => "pages".to_string(),
+    lang => format!("pages.{}", path_segment(lang)),
+  };
+  let platform = if cmd.platform.is_empty() {
+    "common".to_string()
+  } else {
+    path_segment(&cmd.platform)
+  };
+  format!("{}/{}/{}.md", pages, platform, path_segment(&cmd.name))
+}
+
+/// 替换路径分隔符，"." 和 ".." 不能成为目录名
+fn path_segment(s: &str) -> String {
+  let segment = s.replace(['/', '\\', ':'], "_");
+  if segment.is_empty() || segment.chars().all(|c| c == '.') {
+    segment.replace('.', "_")
+  } else {
+    segment
+  }
+}
+
+/// 按 tldr 格式写出命令，parse_tldr_markdown 可以读回
+pub fn render_tldr_markdown(cmd: &Command) -> String {
+  let mut out = format!("# {}\n\n", cmd.name);
+  for line in cmd.description.lines().filter(|l| !l.trim().is_empty()) {
+    out.push_str(&format!("> {}\n", line.trim()));
+  }
+  for example in &cmd.examples {
+    // 空行会结束行内代码所在的段落
+    let code: Vec<&str> = example
+      .code
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .collect();
+    if code.is_empty() {
+      continue;
+    }
+    let code = code.join("\n");
+    let code = if code.contains('`') {
+      format!("`` {} ``", code)
+    } else {
+      format!("`{}`", code)
+    };
+    out.push_str(&format!(
+      "\n- {}:\n\n{}\n",
+      example.description.trim().trim_end_matches([':', '.']),
+      code
+    ));
+  }
+  out
+}
+
+/// 导出为 tldr-pages 的目录结构，返回（相对路径，内容）；没有示例的命令不导出
+pub fn export_tldr(commands: &[Command]) -> Vec<(String, String)> {
+  commands
+    .iter()
+    .filter(|cmd| !cmd.examples.is_empty())
+    .map(|cmd| (tldr_page_path(cmd), render_tldr_markdown(cmd)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_archive_file() {
+    let page =
+      "# tar\n\n> Archiving utility.\n\n- Extract an archive:\n\n`tar xf {{source.tar}}`\n";
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+      Vec::new(),
+      flate2::Compression::default(),
+    ));
+    for path in ["tldr/pages/common/tar.md", "tldr/pages.zh/common/tar.md"] {
+      let mut header = tar::Header::new_gnu();
+      header.set_size(page.len() as u64);
+      header.set_mode(0o644);
+      header.set_cksum();
+      builder
+        .append_data(&mut header, path, page.as_bytes())
+        .unwrap();
+    }
+    let data = builder.into_inner().unwrap().finish().unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pages.tar.gz");
+    std::fs::write(&path, &data).unwrap();
+
+    let commands = parse_tldr_archive_file(&path, &["en".to_string()], &HashMap::new()).unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0].name, "tar");
+    assert_eq!(commands[0].examples[0].code, "tar xf {{source.tar}}");
+    let all = parse_tldr_archive(&data, &[], &HashMap::new()).unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(imported_languages(&[], &all), ["en", "zh"]);
+    let en = ["en".to_string()];
+    assert_eq!(imported_languages(&en, &commands), en);
+    assert!(languages_changed(
+      &en,
+      &imported_languages(&[], &all),
+      Some(&[])
+    ));
+    assert!(!languages_changed(&en, &en, Some(&en)));
+    // --langs all 在只导入了英文之后重新导入
+    assert!(languages_changed(&[], &en, Some(&en)));
+    assert!(!languages_changed(&[], &en, Some(&[])));
+    assert!(languages_changed(&[], &en, None));
+
+    let langs = |langs: &[&str]| langs.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+    assert_eq!(parse_langs(&[]), None);
+    assert_eq!(parse_langs(&langs(&[" ", ""])), None);
+    assert_eq!(
+      parse_langs(&langs(&["en", " zh"])),
+      Some(langs(&["en", "zh"]))
+    );
+    assert_eq!(parse_langs(&langs(&["en", "all"])), Some(Vec::new()));
+
+    std::fs::write(&path, b"not an archive").unwrap();
+    assert!(parse_tldr_archive_file(&path, &[], &HashMap::new()).is_err());
+  }
+
+  #[test]
+  fn test_unpack_archive() {
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+      Vec::new(),
+      flate2::Compression::default(),
+    ));
+    let sheet = "# git\n\n> Version control.\n\n- Show status:\n\n`git status`\n";
+    let mut header = tar::Header::new_gnu();
+    header.set_size(sheet.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+      .append_data(&mut header, "team-cheats-main/git.md", sheet.as_bytes())
+      .unwrap();
+    let data = builder.into_inner().unwrap().finish().unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("main.tar.gz");
+    std::fs::write(&path, &data).unwrap();
+    // 没有 pages 目录，不是 tldr-pages 结构
+    assert!(parse_tldr_archive_file(&path, &[], &HashMap::new())
+      .unwrap()
+      .is_empty());
+    let dest = temp_dir.path().join("unpacked");
+    unpack_archive(&path, &dest).unwrap();
+    assert_eq!(
+      std::fs::read_to_string(dest.join("team-cheats-main/git.md")).unwrap(),
+      sheet
+    );
+
+    std::fs::write(&path, b"not an archive").unwrap();
+    assert!(unpack_archive(&path, &dest).is_err());
+  }
+
+  #[test]
+  fn test_github_archive_url() {
+    assert_eq!(
+      github_archive_url("https://github.com/alice/cheats"),
+      "https://github.com/alice/cheats/archive/HEAD.tar.gz"
+    );
+    assert_eq!(
+      github_archive_url("https://github.com/alice/cheats.git/"),
+      "https://github.com/alice/cheats/archive/HEAD.tar.gz"
+    );
+    assert_eq!(
+      github_archive_url("https://github.com/alice/cheats/tree/feature/linux"),
+      "https://github.com/alice/cheats/archive/feature/linux.tar.gz"
+    );
+    let archive = "https://github.com/alice/cheats/archive/refs/heads/main.zip";
+    assert_eq!(github_archive_url(archive), archive);
+    let raw = "https://example.com/sheets/git.md";
+    assert_eq!(github_archive_url(raw), raw);
+  }
+
+  #[test]
+  fn test_parse_cheat_archive() {
+    let sheet = "---\ntags: [ compression ]\n---\n# To extract an archive:\ntar -xf <archive>\n";
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+      Vec::new(),
+      flate2::Compression::default(),
+    ));
+    for path in [
+      "cheatsheets-master/tar",
+      "cheatsheets-master/README.md",
+      "cheatsheets-master/LICENSE",
+      "cheatsheets-master/.github/CODEOWNERS",
+    ] {
+      let mut header = tar::Header::new_gnu();
+      header.set_size(sheet.len() as u64);
+      header.set_mode(0o644);
+      header.set_cksum();
+      builder
+        .append_data(&mut header, path, sheet.as_bytes())
+        .unwrap();
+    }
+    let data = builder.into_inner().unwrap().finish().unwrap();
+
+    let commands = parse_cheat_archive(&data).unwrap();
+    assert_eq!(commands.len(), 1);
+    let sheet = &commands[0];
+    assert_eq!((sheet.name.as_str(), sheet.lang.as_str()), ("tar", "en"));
+    assert_eq!(sheet.category, CHEAT_CATEGORY);
+    assert_eq!(
+      sheet.description,
+      "Community cheat sheet (tags: compression)"
+    );
+    assert_eq!(sheet.examples[0].code, "tar -xf {{archive}}");
+    assert!(parse_cheat_archive(b"not an archive").is_err());
+
+    // 并入 tldr 页面，再次导入时替换之前的 cheat 示例
+    let page = parse_tldr_markdown(
+      "# tar\n\n> Archiving utility.\n\n- Create an archive:\n\n`tar cf {{target.tar}} {{file}}`\n",
+      "tar".to_string(),
+      "en".to_string(),
+      "common".to_string(),
+    )
+    .unwrap();
+    let merged = merge_cheat_sheet(Some(page), sheet.clone());
+    assert_eq!(merged.category, "common");
+    assert_eq!(merged.examples.len(), 2);
+    let merged = merge_cheat_sheet(Some(merged), sheet.clone());
+    assert_eq!(merged.examples.len(), 2);
+    assert_eq!(merged.examples[1].source.as_deref(), Some("cheat"));
+    assert_eq!(
+      merge_cheat_sheet(None, sheet.clone()).category,
+      CHEAT_CATEGORY
+    );
+  }
+
+  #[test]
+  fn test_extract_options() {
+    let example = |description: &str, code: &str| Example {
+      description: description.to_string(),
+      code: code.to_string(),
+      source: None,
+    };
+    let examples = [
+      example(
+        "Show the first lines",
+        "head {{[-n|--lines]}} {{count}} {{path/to/file}}",
+      ),
+      example(
+        "Print in reverse",
+        "tac {{path/to/file1 path/to/file2 ...}} -r",
+      ),
+      example("Pick the format", "tool --format={{json|yaml}}"),
+      // 组合选项和多个选项的示例都不提取
+      example("Extract verbosely", "tar -xvf {{source.tar}}"),
+      example("Two options", "ls -l -a"),
+      // 管道之后是另一个命令
+      example("Count lines", "cat {{file}} | wc -l"),
+      // 已有的选项保留第一条说明
+      example("Last lines", "tail {{[-n|--lines]}} {{count}}"),
+    ];
+
+    let options = extract_options(&examples);
+    let labels: Vec<String> = options.iter().map(OptionEntry::label).collect();
+    assert_eq!(labels, ["-n, --lines count", "-r", "--format json|yaml"]);
+    assert_eq!(options[0].description, "Show the first lines");
+
+    assert_eq!(
+      code_tokens("cp {{path/to/a b}}  {{[-r|--recursive]}}"),
+      ["cp", "{{path/to/a b}}", "{{[-r|--recursive]}}"]
+    );
+  }
+
+  #[test]
+  fn test_export_tldr() {
+    let mut cmd = parse_tldr_markdown(
+      "# mytool\n\n> Does things.\n\n- Run it:\n\n`mytool {{path/to/file}}`\n",
+      "mytool".to_string(),
+      "local".to_string(),
+      "linux".to_string(),
+    )
+    .unwrap();
+    cmd.description = "Does things.\nSecond line.".to_string();
+    cmd.examples.push(Example {
+      description: "Quote".to_string(),
+      code: "mytool `date`\n\n  --verbose".to_string(),
+      source: None,
+    });
+    let mut empty = cmd.clone();
+    empty.name = "other".to_string();
+    empty.examples.clear();
+
+    let pages = export_tldr(&[cmd.clone(), empty]);
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].0, "pages.local/linux/mytool.md");
+    assert_eq!(
+      pages[0].1,
+      "# mytool\n\n> Does things.\n> Second line.\n\n- Run it:\n\n`mytool {{path/to/file}}`\n\n\
+       - Quote:\n\n`` mytool `date`\n  --verbose ``\n"
+    );
+
+    // 导出的页面按路径中的语言和平台读回
+    let parsed =
+      parse_page_file(&pages[0].1, Path::new("out/pages.local/linux/mytool.md")).unwrap();
+    assert_eq!(
+      (parsed.lang.as_str(), parsed.platform.as_str()),
+      ("local", "linux")
+    );
+    assert_eq!(parsed.description, "Does things. Second line.");
+    assert_eq!(parsed.examples[0].code, cmd.examples[0].code);
+    assert_eq!(parsed.examples[1].description, "Quote");
+    assert_eq!(parsed.examples[1].code, "mytool `date`   --verbose");
+
+    cmd.lang = "en".to_string();
+    assert_eq!(tldr_page_path(&cmd), "pages/linux/mytool.md");
+    let parsed = parse_page_file(&pages[0].1, Path::new("mytool.md")).unwrap();
+    assert_eq!(parsed.lang, "zh");
+
+    // 同步或插件给出的平台、语言不能跳出导出目录
+    cmd.platform = "../../x".to_string();
+    cmd.lang = "../..".to_string();
+    assert_eq!(tldr_page_path(&cmd), "pages..._../.._.._x/mytool.md");
+    cmd.platform = "..".to_string();
+    assert_eq!(tldr_page_path(&cmd), "pages..._../__/mytool.md");
+    for segment in Path::new(&tldr_page_path(&cmd)).components() {
+      assert!(matches!(segment, std::path::Component::Normal(_)));
+    }
+  }
+}
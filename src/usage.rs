@@ -0,0 +1,309 @@
+//! 本地使用统计（rtfm stats --dashboard、/api/stats/usage）
+//!
+//! 按名称查看命令和全文搜索时在数据库的 usage 表中计数（search.record_usage 可关闭），
+//! 汇总为最常查看的命令、没有结果的查询，以及本机已安装却还没有文档的命令（学习缺口）。
+//! 查看命令同时在 history 表中追加一条带时间的记录（rtfm history、TUI 的历史面板和最近查看），
+//! 近期趋势按其中最近 TRENDING_DAYS 天的记录计数。
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::config::AppConfig;
+use crate::storage::{Database, HistoryEntry, StorageError, UsageKind, UsageRecord};
+
+/// 近期趋势统计的天数
+pub const TRENDING_DAYS: i64 = 7;
+
+/// 一条统计
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UsageEntry {
+  /// Command name or normalized query
+  pub name: String,
+  /// Number of lookups or searches
+  pub count: u64,
+  /// Searches that returned no results
+  pub zero_results: u64,
+  /// Last use timestamp (RFC 3339)
+  pub last_used: String,
+}
+
+/// 已安装但没有文档的命令
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LearningGap {
+  /// Command name
+  pub name: String,
+  /// Searches for it that returned no results
+  pub searches: u64,
+  /// Resolved path of the installed binary
+  pub binary: String,
+}
+
+/// 使用统计汇总
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UsageReport {
+  /// Total lookups by command name
+  pub total_lookups: u64,
+  /// Total full-text searches
+  pub total_searches: u64,
+  /// Searches that returned no results
+  pub zero_result_searches: u64,
+  /// Most looked-up commands
+  pub top_lookups: Vec<UsageEntry>,
+  /// Most looked-up commands in the last 7 days
+  pub trending: Vec<UsageEntry>,
+  /// Most frequent searches that returned no results
+  pub zero_result_queries: Vec<UsageEntry>,
+  /// Installed commands that were searched for but have no documentation yet
+  pub learning_gaps: Vec<LearningGap>,
+}
+
+/// 统一大小写和空白，同一查询的不同写法计入一条
+pub fn normalize_query(query: &str) -> String {
+  query
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+    .to_lowercase()
+}
+
+/// 记录按名称查看命令并追加查看历史（同一个事务）；source 为 tui、cli 或 api。统计失败不影响查询本身
+pub fn record_lookup(db: &Database, config: &AppConfig, name: &str, lang: &str, source: &str) {
+  if !config.search.record_usage {
+    return;
+  }
+  let entry = HistoryEntry {
+    name: name.to_string(),
+    lang: lang.to_string(),
+    source: source.to_string(),
+    timestamp: chrono::Utc::now().to_rfc3339(),
+  };
+  if let Err(e) = db.record_lookup(&entry) {
+    tracing::debug!("Failed to record lookup: {}", e);
+  }
+}
+
+/// 最近查看的命令，每个命令只保留最近一次，新的在前
+pub fn recently_viewed(db: &Database, limit: usize) -> Result<Vec<HistoryEntry>, StorageError> {
+  let mut seen = std::collections::HashSet::new();
+  let mut entries = db.list_history(usize::MAX)?;
+  entries.retain(|e| seen.insert(e.name.clone()));
+  entries.truncate(limit);
+  Ok(entries)
+}
+
+/// 记录一次全文搜索及其结果数
+pub fn record_query(db: &Database, config: &AppConfig, query: &str, results: usize) {
+  let query = normalize_query(query);
+  if config.search.record_usage && !query.is_empty() {
+    if let Err(e) = db.record_usage(UsageKind::Query, &query, results) {
+      tracing::debug!("Failed to record search: {}", e);
+    }
+  }
+}
+
+fn entries(records: Vec<(String, UsageRecord)>) -> Vec<UsageEntry> {
+  records
+    .into_iter()
+    .map(|(name, record)| UsageEntry {
+      name,
+      count: record.count,
+      zero_results: record.zero_results,
+      last_used: record.last_used,
+    })
+    .collect()
+}
+
+/// 按次数从高到低，同次数时最近使用的在前
+fn sort_entries(entries: &mut [UsageEntry], key: impl Fn(&UsageEntry) -> u64) {
+  entries.sort_by(|a, b| {
+    key(b)
+      .cmp(&key(a))
+      .then_with(|| b.last_used.cmp(&a.last_used))
+  });
+}
+
+/// 查看次数最多的命令
+pub fn most_used(db: &Database, limit: usize) -> Result<Vec<UsageEntry>, StorageError> {
+  let mut lookups = entries(db.list_usage(UsageKind::Lookup)?);
+  sort_entries(&mut lookups, |e| e.count);
+  lookups.truncate(limit);
+  Ok(lookups)
+}
+
+/// 最近 days 天内查看次数最多的命令，按查看历史计数（历史只保留最近的记录）
+pub fn trending(db: &Database, days: i64, limit: usize) -> Result<Vec<UsageEntry>, StorageError> {
+  let since = chrono::Utc::now() - chrono::Duration::days(days);
+  let mut counts: std::collections::HashMap<String, UsageEntry> = std::collections::HashMap::new();
+  for entry in db.list_history(usize::MAX)? {
+    let recent = chrono::DateTime::parse_from_rfc3339(&entry.timestamp).is_ok_and(|t| t >= since);
+    if !recent {
+      continue;
+    }
+    let counted = counts
+      .entry(entry.name.clone())
+      .or_insert_with(|| UsageEntry {
+        name: entry.name,
+        count: 0,
+        zero_results: 0,
+        last_used: String::new(),
+      });
+    counted.count += 1;
+    if entry.timestamp > counted.last_used {
+      counted.last_used = entry.timestamp;
+    }
+  }
+  let mut trending: Vec<UsageEntry> = counts.into_values().collect();
+  sort_entries(&mut trending, |e| e.count);
+  trending.truncate(limit);
+  Ok(trending)
+}
+
+/// 汇总使用统计，每个列表最多 limit 条
+/// find_binary: 查找本机已安装的命令（学习缺口只列出可以学习的命令）
+pub fn report(
+  db: &Database,
+  limit: usize,
+  find_binary: impl Fn(&str) -> Option<std::path::PathBuf>,
+) -> Result<UsageReport, StorageError> {
+  let mut lookups = entries(db.list_usage(UsageKind::Lookup)?);
+  let mut queries = entries(db.list_usage(UsageKind::Query)?);
+
+  let total_lookups = lookups.iter().map(|e| e.count).sum();
+  let total_searches = queries.iter().map(|e| e.count).sum();
+  let zero_result_searches = queries.iter().map(|e| e.zero_results).sum();
+
+  queries.retain(|e| e.zero_results > 0);
+  sort_entries(&mut queries, |e| e.zero_results);
+
+  // 没有结果的单词查询如果是已安装且未收录的命令，就是可以补上的缺口
+  let mut learning_gaps = Vec::new();
+  for entry in &queries {
+    if entry.name.contains(' ') || db.find_command(&entry.name, "en")?.is_some() {
+      continue;
+    }
+    if let Some(binary) = find_binary(&entry.name) {
+      learning_gaps.push(LearningGap {
+        name: entry.name.clone(),
+        searches: entry.zero_results,
+        binary: binary.display().to_string(),
+      });
+    }
+  }
+  learning_gaps.truncate(limit);
+
+  sort_entries(&mut lookups, |e| e.count);
+  lookups.truncate(limit);
+  queries.truncate(limit);
+
+  Ok(UsageReport {
+    total_lookups,
+    total_searches,
+    zero_result_searches,
+    top_lookups: lookups,
+    trending: trending(db, TRENDING_DAYS, limit)?,
+    zero_result_queries: queries,
+    learning_gaps,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_report() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    let config = AppConfig::default();
+
+    record_lookup(&db, &config, "tar", "en", "cli");
+    record_lookup(&db, &config, "tar", "en", "tui");
+    record_lookup(&db, &config, "git", "zh", "api");
+    record_query(&db, &config, "  Extract  archive", 3);
+    record_query(&db, &config, "extract archive", 0);
+    record_query(&db, &config, "jq", 0);
+    record_query(&db, &config, "jq", 0);
+    record_query(&db, &config, "nonexistent", 0);
+
+    let report = report(&db, 10, |name| {
+      (name == "jq").then(|| std::path::PathBuf::from("/usr/bin/jq"))
+    })
+    .unwrap();
+    assert_eq!(report.total_lookups, 3);
+    assert_eq!(report.total_searches, 5);
+    assert_eq!(report.zero_result_searches, 4);
+    assert_eq!(report.top_lookups[0].name, "tar");
+    assert_eq!(report.top_lookups[0].count, 2);
+    assert_eq!(report.trending[0].name, "tar");
+    assert_eq!(report.trending[0].count, 2);
+    let zero: Vec<&str> = report
+      .zero_result_queries
+      .iter()
+      .map(|e| e.name.as_str())
+      .collect();
+    assert_eq!(zero[0], "jq");
+    assert!(zero.contains(&"extract archive"));
+    assert_eq!(report.learning_gaps.len(), 1);
+    assert_eq!(report.learning_gaps[0].name, "jq");
+    assert_eq!(report.learning_gaps[0].searches, 2);
+
+    let mut config = AppConfig::default();
+    config.search.record_usage = false;
+    record_lookup(&db, &config, "tar", "en", "cli");
+    assert_eq!(db.list_usage(UsageKind::Lookup).unwrap().len(), 2);
+    assert_eq!(db.list_history(10).unwrap().len(), 3);
+  }
+
+  #[test]
+  fn test_recently_viewed() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    let config = AppConfig::default();
+
+    record_lookup(&db, &config, "tar", "en", "cli");
+    record_lookup(&db, &config, "git", "zh", "api");
+    record_lookup(&db, &config, "tar", "en", "tui");
+    record_lookup(&db, &config, "ls", "en", "tui");
+
+    let names: Vec<String> = recently_viewed(&db, 10)
+      .unwrap()
+      .into_iter()
+      .map(|e| e.name)
+      .collect();
+    assert_eq!(names, ["ls", "tar", "git"]);
+    let recent = recently_viewed(&db, 2).unwrap();
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[1].source, "tui");
+    assert_eq!(db.list_history(10).unwrap().len(), 4);
+  }
+
+  #[test]
+  fn test_trending() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.redb")).unwrap();
+    let config = AppConfig::default();
+
+    // 很久以前大量查看的命令只计入总次数，不计入近期趋势
+    let old = (chrono::Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+    for _ in 0..3 {
+      db.record_usage(UsageKind::Lookup, "git", 1).unwrap();
+      db.append_history(&HistoryEntry {
+        name: "git".to_string(),
+        lang: "en".to_string(),
+        source: "cli".to_string(),
+        timestamp: old.clone(),
+      })
+      .unwrap();
+    }
+    record_lookup(&db, &config, "tar", "en", "cli");
+    record_lookup(&db, &config, "ls", "en", "tui");
+    record_lookup(&db, &config, "tar", "en", "api");
+
+    let names =
+      |entries: Vec<UsageEntry>| -> Vec<String> { entries.into_iter().map(|e| e.name).collect() };
+    assert_eq!(names(most_used(&db, 2).unwrap()), ["git", "tar"]);
+    let trending = trending(&db, TRENDING_DAYS, 10).unwrap();
+    assert_eq!(trending[0].count, 2);
+    assert_eq!(names(trending), ["tar", "ls"]);
+  }
+}
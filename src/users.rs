@@ -0,0 +1,165 @@
+//! 多用户服务模式（server.multi_user）
+//!
+//! 用户与令牌的 SHA-256 保存在配置文件的 [users.<name>] 中（rtfm user add 生成），修改后随配置热重载生效。
+//! 共享的 tldr 数据仍在主数据库与索引中；每个用户在 `<data_dir>/users/<name>/` 下有自己的数据库与索引，
+//! 保存其学习的 local 命令和使用统计。查询时先查共享数据，再叠加用户自己的命令。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+use crate::config::AppConfig;
+use crate::search::{self, SearchEngine, SearchResponse, SearchResult};
+use crate::storage::{Command, Database};
+
+/// 用户数据所在的子目录
+pub const USERS_DIRNAME: &str = "users";
+
+/// 生成新的访问令牌
+pub fn generate_token() -> anyhow::Result<String> {
+  let mut bytes = [0u8; 24];
+  getrandom::getrandom(&mut bytes).map_err(|e| anyhow::anyhow!("No random source: {}", e))?;
+  let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+  Ok(format!("rtfm_{}", hex))
+}
+
+/// 配置中保存的令牌摘要
+pub fn hash_token(token: &str) -> String {
+  format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// 按令牌查找用户，返回用户名与是否为管理员
+pub fn authenticate(config: &AppConfig, token: &str) -> Option<(String, bool)> {
+  let hash = hash_token(token);
+  config
+    .users
+    .iter()
+    .find(|(_, user)| user.token_sha256.eq_ignore_ascii_case(&hash))
+    .map(|(name, user)| (name.clone(), user.admin))
+}
+
+/// 一个用户的数据库与索引
+pub struct UserData {
+  pub db: Database,
+  pub search: RwLock<SearchEngine>,
+}
+
+/// 按需打开并缓存各用户的数据
+pub struct UserStore {
+  dir: PathBuf,
+  open: parking_lot::Mutex<HashMap<String, Arc<UserData>>>,
+}
+
+impl UserStore {
+  pub fn new(data_dir: &std::path::Path) -> Self {
+    Self {
+      dir: data_dir.join(USERS_DIRNAME),
+      open: Default::default(),
+    }
+  }
+
+  pub fn get(&self, name: &str, config: &AppConfig) -> anyhow::Result<Arc<UserData>> {
+    let mut open = self.open.lock();
+    if let Some(data) = open.get(name) {
+      return Ok(data.clone());
+    }
+    let dir = self.dir.join(name);
+    std::fs::create_dir_all(&dir)?;
+    let db = Database::open(&dir.join(&config.storage.db_filename))?;
+    let search = search::open_index(
+      &db,
+      &dir.join(&config.storage.index_dirname),
+      &config.search,
+    )?;
+    let data = Arc::new(UserData {
+      db,
+      search: RwLock::new(search),
+    });
+    open.insert(name.to_string(), data.clone());
+    Ok(data)
+  }
+}
+
+/// 共享数据叠加用户学习的命令：用户的 local 条目合并到共享页面中，附带用户自己的笔记
+pub fn find_command(
+  shared: &Database,
+  user: &Database,
+  name: &str,
+  lang: &str,
+) -> anyhow::Result<Option<Command>> {
+  let base = shared.find_command(name, lang)?;
+  let own = user.get_command(name, "local")?;
+  let mut cmd = match (base, own) {
+    (Some(base), Some(own)) => Some(base.merge_learned(own)),
+    (base, own) => base.or(own),
+  };
+  // 笔记属于用户，不使用共享数据库中的
+  if let Some(cmd) = cmd.as_mut() {
+    cmd.notes = user.get_note(&cmd.name)?;
+  }
+  Ok(cmd)
+}
+
+/// 合并共享索引与用户索引的结果，按分数排序；示例数不超过两者中较多的一方
+pub fn merge_results(shared: SearchResponse, user: SearchResponse, limit: usize) -> SearchResponse {
+  let merge = |mut results: Vec<SearchResult>, other: Vec<SearchResult>, limit: usize| {
+    results.extend(other);
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit);
+    results
+  };
+  let example_limit = shared.examples.len().max(user.examples.len());
+  let mut results = merge(shared.results, user.results, limit);
+  let mut examples = merge(shared.examples, user.examples, example_limit);
+  // 按分数合并后重新把首选语言的版本排在前面
+  crate::search::prefer_lang(&mut results, &shared.lang);
+  crate::search::prefer_lang(&mut examples, &shared.lang);
+  // 两边的建议都保留，共享数据集的在前
+  let mut suggestions = Vec::new();
+  if results.is_empty() {
+    for name in shared.suggestions.into_iter().chain(user.suggestions) {
+      if !suggestions.contains(&name) {
+        suggestions.push(name);
+      }
+    }
+    suggestions.truncate(crate::search::MAX_SUGGESTIONS);
+  }
+  SearchResponse {
+    total: results.len(),
+    lang: shared.lang,
+    results,
+    examples,
+    suggestions,
+    took_ms: shared.took_ms + user.took_ms,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::UserConfig;
+
+  #[test]
+  fn test_authenticate() {
+    let token = generate_token().unwrap();
+    assert!(token.starts_with("rtfm_"));
+    assert_ne!(token, generate_token().unwrap());
+
+    let mut config = AppConfig::default();
+    config.users.insert(
+      "alice".to_string(),
+      UserConfig {
+        token_sha256: hash_token(&token),
+        admin: false,
+      },
+    );
+    assert_eq!(
+      authenticate(&config, &token),
+      Some(("alice".to_string(), false))
+    );
+    assert_eq!(authenticate(&config, "rtfm_wrong"), None);
+  }
+}
@@ -0,0 +1,149 @@
+//! 监视目录并自动导入（rtfm import --watch、serve 模式的 import.watch_dirs）
+//!
+//! 通过文件系统通知监视目录及其子目录，新增或修改的 `.md` 文件按 tldr 格式解析后增量导入并索引，
+//! 适合用 Dropbox 或 git 同步的共享速查表目录。短时间内的多个事件合并为一批处理；
+//! 隐藏目录（如 .git）中的文件被忽略，删除文件不会删除已导入的命令。
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::search::{self, SearchEngine};
+use crate::storage::{Command, Database};
+use crate::{sheets, update};
+
+/// 最后一个事件之后等待的时间，同步工具一次写入多个文件时合并为一批
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 是否为需要导入的文件：root 下不在隐藏目录中的 .md 文件
+pub fn is_watched(root: &Path, path: &Path) -> bool {
+  let Ok(relative) = path.strip_prefix(root) else {
+    return false;
+  };
+  path.extension().is_some_and(|ext| ext == "md")
+    && !relative
+      .components()
+      .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// 监视目录，每批新增或修改的文件调用一次 on_change；阻塞直到监视失败
+pub fn watch(dirs: &[PathBuf], mut on_change: impl FnMut(Vec<PathBuf>)) -> notify::Result<()> {
+  // 部分平台的事件使用规范化的路径
+  let dirs = dirs
+    .iter()
+    .map(|dir| dir.canonicalize().map_err(notify::Error::io))
+    .collect::<notify::Result<Vec<_>>>()?;
+
+  let (tx, rx) = mpsc::channel();
+  let mut watcher = notify::recommended_watcher(tx)?;
+  for dir in &dirs {
+    watcher.watch(dir, RecursiveMode::Recursive)?;
+  }
+
+  while let Ok(event) = rx.recv() {
+    let mut changed = BTreeSet::new();
+    collect(&dirs, event, &mut changed);
+    while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+      collect(&dirs, event, &mut changed);
+    }
+    // 临时文件可能在合并期间已被重命名或删除
+    let files: Vec<PathBuf> = changed.into_iter().filter(|p| p.is_file()).collect();
+    if !files.is_empty() {
+      on_change(files);
+    }
+  }
+  Ok(())
+}
+
+/// 记录事件中需要导入的文件
+fn collect(dirs: &[PathBuf], event: notify::Result<Event>, changed: &mut BTreeSet<PathBuf>) {
+  let event = match event {
+    Ok(event) => event,
+    Err(e) => {
+      tracing::warn!("File watch error: {}", e);
+      return;
+    }
+  };
+  if matches!(event.kind, EventKind::Access(_) | EventKind::Remove(_)) {
+    return;
+  }
+  changed.extend(
+    event
+      .paths
+      .into_iter()
+      .filter(|path| dirs.iter().any(|dir| is_watched(dir, path))),
+  );
+}
+
+/// 解析并导入一批文件，返回导入的命令；读取失败或不是有效页面的文件被跳过
+pub fn import_files(
+  db: &Database,
+  search: &mut SearchEngine,
+  files: &[PathBuf],
+) -> anyhow::Result<Vec<Command>> {
+  let mut commands = Vec::new();
+  for path in files {
+    match std::fs::read_to_string(path) {
+      Ok(content) => commands.extend(update::parse_page_file(&content, path)),
+      Err(e) => tracing::warn!("Skipping {:?}: {}", path, e),
+    }
+  }
+  if commands.is_empty() {
+    return Ok(commands);
+  }
+
+  let commands = sheets::merge_with_learned(db, sheets::combine(commands));
+  db.save_commands(&commands)?;
+  search::index_commands(db, search, &commands)?;
+  Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_is_watched() {
+    let root = Path::new("/sheets");
+    assert!(is_watched(root, Path::new("/sheets/docker.md")));
+    assert!(is_watched(root, Path::new("/sheets/pages/common/git.md")));
+    assert!(!is_watched(root, Path::new("/sheets/.git/README.md")));
+    assert!(!is_watched(root, Path::new("/sheets/.docker.md.swp")));
+    assert!(!is_watched(root, Path::new("/sheets/notes.txt")));
+    assert!(!is_watched(root, Path::new("/other/docker.md")));
+  }
+
+  #[test]
+  fn test_import_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(&temp_dir.path().join("test.db")).unwrap();
+    let mut engine = SearchEngine::open(
+      &temp_dir.path().join("index"),
+      &crate::config::SearchConfig::default(),
+    )
+    .unwrap();
+
+    let page = temp_dir.path().join("deploy.md");
+    std::fs::write(
+      &page,
+      "# deploy\n\n> Ship the app.\n\n- Deploy to staging:\n\n`deploy --env staging`\n",
+    )
+    .unwrap();
+    let missing = temp_dir.path().join("missing.md");
+
+    let commands = import_files(&db, &mut engine, &[page, missing]).unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0].name, "deploy");
+    assert!(db
+      .get_command("deploy", &commands[0].lang)
+      .unwrap()
+      .is_some());
+    let results = engine
+      .search("deploy", None, &search::Platform::All, 10)
+      .unwrap();
+    assert_eq!(results.results[0].name, "deploy");
+  }
+}